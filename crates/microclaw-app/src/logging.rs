@@ -4,38 +4,133 @@ use std::collections::VecDeque;
 use std::fs::{self, File, OpenOptions};
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, OnceLock};
 use tracing_subscriber::fmt::writer::MakeWriter;
+use tracing_subscriber::layer::{Layered, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{reload, EnvFilter, Layer, Registry};
 
 pub const LOG_FILE_PREFIX: &str = "microclaw-";
 pub const LOG_FILE_SUFFIX: &str = ".log";
 pub const LOG_RETENTION_DAYS: i64 = 30;
 
+/// Additional subscriber layer installed alongside the log output, e.g. a span exporter.
+pub type ExtraLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+pub const LOG_LEVELS: &[&str] = &["trace", "debug", "info", "warn", "error", "off"];
+
+type FilterSubscriber = Layered<Option<ExtraLayer>, Registry>;
+
+static LOG_FILTER_HANDLE: OnceLock<LogFilterHandle> = OnceLock::new();
+
+fn env_filter() -> EnvFilter {
+    EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"))
+}
+
+/// Swaps the active `EnvFilter` of the installed subscriber without a restart.
+#[derive(Clone)]
+pub struct LogFilterHandle {
+    inner: reload::Handle<EnvFilter, FilterSubscriber>,
+}
+
+impl LogFilterHandle {
+    /// Wrap `filter` in a reloadable layer. The layer must be stacked directly
+    /// on top of the optional extra layer, as the `init_*` functions do.
+    pub fn new(filter: EnvFilter) -> (reload::Layer<EnvFilter, FilterSubscriber>, Self) {
+        let (layer, inner) = reload::Layer::new(filter);
+        (layer, Self { inner })
+    }
+
+    /// Current filter directives, e.g. `info` or `info,microclaw=debug`.
+    pub fn current(&self) -> String {
+        self.inner
+            .with_current(|filter| filter.to_string())
+            .unwrap_or_default()
+    }
+
+    /// Validate `raw` with [`normalize_log_filter`] and install it. Returns the
+    /// directives now in effect.
+    pub fn set(&self, raw: &str) -> std::result::Result<String, String> {
+        let directives = normalize_log_filter(raw)?;
+        let filter = EnvFilter::try_new(&directives).map_err(|e| e.to_string())?;
+        self.inner.reload(filter).map_err(|e| e.to_string())?;
+        Ok(directives)
+    }
+}
+
+/// Handle for the process-wide subscriber, set once logging is initialized.
+pub fn log_filter_handle() -> Option<&'static LogFilterHandle> {
+    LOG_FILTER_HANDLE.get()
+}
+
+/// Accept a bare level (`debug`) or comma-separated `target=level` directives
+/// (`info,microclaw=debug`). Levels must be one of [`LOG_LEVELS`]; anything
+/// else is rejected rather than silently treated as a target name.
+pub fn normalize_log_filter(raw: &str) -> std::result::Result<String, String> {
+    let mut directives = Vec::new();
+    for part in raw.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let (target, level) = match part.rsplit_once('=') {
+            Some((target, level)) => (Some(target.trim()), level.trim()),
+            None => (None, part),
+        };
+        let level = level.to_ascii_lowercase();
+        if !LOG_LEVELS.contains(&level.as_str()) {
+            return Err(format!(
+                "invalid log level '{level}' (expected one of: {})",
+                LOG_LEVELS.join(", ")
+            ));
+        }
+        match target {
+            Some("") => return Err(format!("missing target in '{part}'")),
+            Some(target) => directives.push(format!("{target}={level}")),
+            None => directives.push(level),
+        }
+    }
+    if directives.is_empty() {
+        return Err(format!("expected a log level: {}", LOG_LEVELS.join(", ")));
+    }
+    Ok(directives.join(","))
+}
+
+fn reloadable_env_filter() -> reload::Layer<EnvFilter, FilterSubscriber> {
+    let (layer, handle) = LogFilterHandle::new(env_filter());
+    let _ = LOG_FILTER_HANDLE.set(handle);
+    layer
+}
+
 pub fn init_logging(runtime_data_dir: &str) -> Result<()> {
+    init_logging_with_layer(runtime_data_dir, None)
+}
+
+pub fn init_logging_with_layer(runtime_data_dir: &str, extra: Option<ExtraLayer>) -> Result<()> {
     let log_dir = PathBuf::from(runtime_data_dir).join("logs");
     fs::create_dir_all(&log_dir)
         .with_context(|| format!("Failed to create log directory: {}", log_dir.display()))?;
     cleanup_old_logs(&log_dir, Utc::now(), LOG_RETENTION_DAYS)?;
 
     let writer = HourlyLogWriter::new(log_dir, LOG_RETENTION_DAYS)?;
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
+    tracing_subscriber::registry()
+        .with(extra)
+        .with(reloadable_env_filter())
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_ansi(false)
+                .with_writer(writer),
         )
-        .with_ansi(false)
-        .with_writer(writer)
         .init();
 
     Ok(())
 }
 
 pub fn init_console_logging() {
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
-        )
+    init_console_logging_with_layer(None);
+}
+
+pub fn init_console_logging_with_layer(extra: Option<ExtraLayer>) {
+    tracing_subscriber::registry()
+        .with(extra)
+        .with(reloadable_env_filter())
+        .with(tracing_subscriber::fmt::layer())
         .init();
 }
 
@@ -212,6 +307,37 @@ mod tests {
         std::env::temp_dir().join(format!("microclaw_logging_test_{}", Uuid::new_v4()))
     }
 
+    #[test]
+    fn test_filter_handle_reload_updates_active_level() {
+        let (layer, handle) = LogFilterHandle::new(EnvFilter::new("info"));
+        let subscriber = tracing_subscriber::registry()
+            .with(None::<ExtraLayer>)
+            .with(layer);
+        tracing::subscriber::with_default(subscriber, || {
+            assert_eq!(handle.current(), "info");
+            assert!(!tracing::enabled!(tracing::Level::DEBUG));
+
+            assert_eq!(handle.set(" DEBUG ").unwrap(), "debug");
+            assert_eq!(handle.current(), "debug");
+            assert!(tracing::enabled!(tracing::Level::DEBUG));
+
+            assert!(handle.set("verbose").is_err());
+            assert_eq!(handle.current(), "debug");
+        });
+    }
+
+    #[test]
+    fn test_normalize_log_filter() {
+        assert_eq!(normalize_log_filter("Warn").unwrap(), "warn");
+        assert_eq!(
+            normalize_log_filter("info, microclaw=TRACE").unwrap(),
+            "info,microclaw=trace"
+        );
+        assert!(normalize_log_filter("").is_err());
+        assert!(normalize_log_filter("microclaw").is_err());
+        assert!(normalize_log_filter("=debug").is_err());
+    }
+
     #[test]
     fn test_parse_log_filename_time() {
         assert!(parse_log_filename_time("microclaw-2026-02-08-10.log").is_some());
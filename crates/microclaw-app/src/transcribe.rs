@@ -1,15 +1,75 @@
 use reqwest::multipart;
 
+/// Optional hints for the Whisper API.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TranscriptionOptions {
+    /// ISO-639-1 language of the audio (e.g. "de"). Unset means auto-detect.
+    pub language: Option<String>,
+    /// Text that biases the transcription toward names and domain terms.
+    pub prompt: Option<String>,
+    /// MIME type the channel reported for the audio (e.g. "audio/mp4").
+    /// Unset means OGG/Opus, the format of most voice notes.
+    pub mime_type: Option<String>,
+}
+
+/// File extension and MIME type to upload audio as. Whisper picks the decoder
+/// from these, so they must match the real container; unknown types fall
+/// back to OGG.
+pub fn audio_format(mime_type: Option<&str>) -> (&'static str, &'static str) {
+    let essence = mime_type
+        .and_then(|m| m.split(';').next())
+        .map(|m| m.trim().to_ascii_lowercase())
+        .unwrap_or_default();
+    match essence.as_str() {
+        "audio/mpeg" | "audio/mp3" => ("mp3", "audio/mpeg"),
+        "audio/mp4" | "audio/m4a" | "audio/x-m4a" | "audio/aac" => ("m4a", "audio/mp4"),
+        "audio/webm" | "video/webm" => ("webm", "audio/webm"),
+        "audio/wav" | "audio/x-wav" | "audio/wave" => ("wav", "audio/wav"),
+        "audio/flac" | "audio/x-flac" => ("flac", "audio/flac"),
+        _ => ("ogg", "audio/ogg"),
+    }
+}
+
+/// Text fields of the multipart request, in the order they are sent.
+fn form_fields(options: &TranscriptionOptions) -> Vec<(&'static str, String)> {
+    let mut fields = vec![("model", "whisper-1".to_string())];
+    let non_empty = |v: &Option<String>| {
+        v.as_deref()
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    };
+    if let Some(language) = non_empty(&options.language) {
+        fields.push(("language", language));
+    }
+    if let Some(prompt) = non_empty(&options.prompt) {
+        fields.push(("prompt", prompt));
+    }
+    fields
+}
+
 pub async fn transcribe_audio(api_key: &str, audio_bytes: &[u8]) -> Result<String, String> {
+    transcribe_audio_with_options(api_key, audio_bytes, &TranscriptionOptions::default()).await
+}
+
+pub async fn transcribe_audio_with_options(
+    api_key: &str,
+    audio_bytes: &[u8],
+    options: &TranscriptionOptions,
+) -> Result<String, String> {
     let client = reqwest::Client::new();
 
+    let (extension, mime_type) = audio_format(options.mime_type.as_deref());
     let part = multipart::Part::bytes(audio_bytes.to_vec())
-        .file_name("audio.ogg")
-        .mime_str("audio/ogg")
+        .file_name(format!("audio.{extension}"))
+        .mime_str(mime_type)
         .map_err(|e| e.to_string())?;
 
-    let form = multipart::Form::new()
-        .text("model", "whisper-1")
+    let form = form_fields(options)
+        .into_iter()
+        .fold(multipart::Form::new(), |form, (name, value)| {
+            form.text(name, value)
+        })
         .part("file", part);
 
     let resp = client
@@ -39,8 +99,52 @@ pub async fn transcribe_audio(api_key: &str, audio_bytes: &[u8]) -> Result<Strin
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
     #[test]
     fn test_transcribe_module_exists() {
         // Basic smoke test that the module compiles
     }
+
+    #[test]
+    fn test_form_fields_include_language_and_prompt_when_set() {
+        assert_eq!(
+            form_fields(&TranscriptionOptions::default()),
+            vec![("model", "whisper-1".to_string())]
+        );
+        let options = TranscriptionOptions {
+            language: Some("de".into()),
+            prompt: Some("MicroClaw, Kubernetes".into()),
+            mime_type: Some("audio/mp4".into()),
+        };
+        assert_eq!(
+            form_fields(&options),
+            vec![
+                ("model", "whisper-1".to_string()),
+                ("language", "de".to_string()),
+                ("prompt", "MicroClaw, Kubernetes".to_string()),
+            ]
+        );
+        let blank = TranscriptionOptions {
+            language: Some("  ".into()),
+            ..TranscriptionOptions::default()
+        };
+        assert_eq!(form_fields(&blank).len(), 1);
+    }
+
+    #[test]
+    fn test_audio_format_follows_mime_type() {
+        assert_eq!(audio_format(None), ("ogg", "audio/ogg"));
+        assert_eq!(
+            audio_format(Some("audio/ogg; codecs=opus")),
+            ("ogg", "audio/ogg")
+        );
+        assert_eq!(audio_format(Some("audio/mpeg")), ("mp3", "audio/mpeg"));
+        assert_eq!(audio_format(Some("Audio/MP4")), ("m4a", "audio/mp4"));
+        assert_eq!(audio_format(Some("audio/webm")), ("webm", "audio/webm"));
+        assert_eq!(
+            audio_format(Some("application/x-unknown")),
+            ("ogg", "audio/ogg")
+        );
+    }
 }
@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use crate::channel_adapter::ChannelRegistry;
+use crate::channel_adapter::{ChannelAdapter, ChannelRegistry};
 use microclaw_storage::db::{call_blocking, Database, StoredMessage};
 
 #[derive(Clone, Debug)]
@@ -251,6 +251,116 @@ pub async fn deliver_and_store_bot_message(
         .map_err(|e| format!("Failed to store sent message: {e}"))
 }
 
+/// Like `deliver_and_store_bot_message`, but returns the platform message id
+/// when the channel can edit the message later. The stored copy uses that id
+/// so `edit_and_store_bot_message` can keep history in sync.
+pub async fn deliver_and_store_editable_bot_message(
+    registry: &ChannelRegistry,
+    db: Arc<Database>,
+    bot_username: &str,
+    chat_id: i64,
+    text: &str,
+) -> Result<Option<String>, String> {
+    let (adapter, external_chat_id) =
+        resolve_adapter_and_external_id(registry, db.clone(), chat_id).await?;
+    let message_id = if adapter.is_local_only() {
+        None
+    } else {
+        adapter.send_editable_text(&external_chat_id, text).await?
+    };
+
+    let msg = StoredMessage {
+        id: message_id
+            .clone()
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+        chat_id,
+        sender_name: bot_username.to_string(),
+        content: text.to_string(),
+        is_from_bot: true,
+        timestamp: chrono::Utc::now().to_rfc3339(),
+    };
+    call_blocking(db, move |d| d.store_message(&msg))
+        .await
+        .map_err(|e| format!("Failed to store sent message: {e}"))?;
+    Ok(message_id)
+}
+
+/// Replace the text of a bot message sent with
+/// `deliver_and_store_editable_bot_message`, updating the stored copy too.
+pub async fn edit_and_store_bot_message(
+    registry: &ChannelRegistry,
+    db: Arc<Database>,
+    chat_id: i64,
+    message_id: &str,
+    text: &str,
+) -> Result<(), String> {
+    let (adapter, external_chat_id) =
+        resolve_adapter_and_external_id(registry, db.clone(), chat_id).await?;
+    adapter
+        .edit_text(&external_chat_id, message_id, text)
+        .await?;
+    let message_id = message_id.to_string();
+    let text = text.to_string();
+    call_blocking(db, move |d| {
+        d.update_bot_message_content(chat_id, &message_id, &text)
+    })
+    .await
+    .map_err(|e| format!("Failed to store edited message: {e}"))?;
+    Ok(())
+}
+
+async fn resolve_adapter_and_external_id(
+    registry: &ChannelRegistry,
+    db: Arc<Database>,
+    chat_id: i64,
+) -> Result<(Arc<dyn ChannelAdapter>, String), String> {
+    let routing = get_required_chat_routing(registry, db.clone(), chat_id).await?;
+    let external_chat_id = call_blocking(db, move |d| d.get_chat_external_id(chat_id))
+        .await
+        .map_err(|e| format!("Failed to read external chat id for chat {chat_id}: {e}"))?
+        .unwrap_or_else(|| chat_id.to_string());
+    let adapter = registry
+        .get(&routing.channel_name)
+        .cloned()
+        .ok_or_else(|| {
+            format!(
+                "No adapter registered for channel '{}'",
+                routing.channel_name
+            )
+        })?;
+    Ok((adapter, external_chat_id))
+}
+
+/// Send a transient status message that may be edited later. Unlike
+/// `deliver_and_store_bot_message`, the text is not stored in chat history.
+/// Returns `None` when the channel cannot edit messages or is local-only.
+pub async fn send_editable_status_message(
+    registry: &ChannelRegistry,
+    db: Arc<Database>,
+    chat_id: i64,
+    text: &str,
+) -> Result<Option<String>, String> {
+    let (adapter, external_chat_id) =
+        resolve_adapter_and_external_id(registry, db, chat_id).await?;
+    if adapter.is_local_only() {
+        return Ok(None);
+    }
+    adapter.send_editable_text(&external_chat_id, text).await
+}
+
+/// Replace the text of a message sent with `send_editable_status_message`.
+pub async fn edit_status_message(
+    registry: &ChannelRegistry,
+    db: Arc<Database>,
+    chat_id: i64,
+    message_id: &str,
+    text: &str,
+) -> Result<(), String> {
+    let (adapter, external_chat_id) =
+        resolve_adapter_and_external_id(registry, db, chat_id).await?;
+    adapter.edit_text(&external_chat_id, message_id, text).await
+}
+
 #[cfg(test)]
 mod tests {
     use super::infer_channel_from_chat_type;
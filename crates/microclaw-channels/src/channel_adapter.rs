@@ -27,6 +27,31 @@ pub trait ChannelAdapter: Send + Sync {
     /// Send text to external chat. Called by deliver_and_store_bot_message.
     async fn send_text(&self, external_chat_id: &str, text: &str) -> Result<(), String>;
 
+    /// Send text and return a message id that `edit_text` can target later.
+    /// Default: plain `send_text`, returning no id (message is not editable).
+    async fn send_editable_text(
+        &self,
+        external_chat_id: &str,
+        text: &str,
+    ) -> Result<Option<String>, String> {
+        self.send_text(external_chat_id, text).await?;
+        Ok(None)
+    }
+
+    /// Replace the text of a message previously sent with `send_editable_text`.
+    /// Default: not supported.
+    async fn edit_text(
+        &self,
+        _external_chat_id: &str,
+        _message_id: &str,
+        _text: &str,
+    ) -> Result<(), String> {
+        Err(format!(
+            "editing messages not supported for {}",
+            self.name()
+        ))
+    }
+
     /// Send file attachment. Default: not supported.
     async fn send_attachment(
         &self,
@@ -27,6 +27,10 @@ pub enum ContentBlock {
         id: String,
         name: String,
         input: serde_json::Value,
+        /// Opaque Gemini `thoughtSignature` that must be echoed back with the
+        /// function call on later turns.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        thought_signature: Option<String>,
     },
     #[serde(rename = "tool_result")]
     ToolResult {
@@ -68,6 +72,17 @@ pub struct MessagesResponse {
     pub content: Vec<ResponseContentBlock>,
     pub stop_reason: Option<String>,
     pub usage: Option<Usage>,
+    /// Set by the fallback provider when a fallback, not the configured
+    /// provider, produced this response.
+    #[serde(skip)]
+    pub served_by: Option<ServedBy>,
+}
+
+/// The provider and model that actually answered a request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServedBy {
+    pub provider: String,
+    pub model: String,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -80,17 +95,27 @@ pub enum ResponseContentBlock {
         id: String,
         name: String,
         input: serde_json::Value,
+        /// Opaque Gemini `thoughtSignature` that must be echoed back with the
+        /// function call on later turns.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        thought_signature: Option<String>,
     },
     /// Catch-all for unknown block types (e.g. "thinking" from MiniMax M2.5)
     #[serde(other)]
     Other,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 #[allow(dead_code)]
 pub struct Usage {
     pub input_tokens: u32,
     pub output_tokens: u32,
+    /// Prompt tokens written to the provider's prompt cache (Anthropic).
+    #[serde(default)]
+    pub cache_creation_input_tokens: u32,
+    /// Prompt tokens served from the provider's prompt cache (Anthropic).
+    #[serde(default)]
+    pub cache_read_input_tokens: u32,
 }
 
 #[cfg(test)]
@@ -114,12 +139,14 @@ mod tests {
             id: "id_123".into(),
             name: "bash".into(),
             input: json!({"command": "ls"}),
+            thought_signature: None,
         };
         let json = serde_json::to_value(&block).unwrap();
         assert_eq!(json["type"], "tool_use");
         assert_eq!(json["id"], "id_123");
         assert_eq!(json["name"], "bash");
         assert_eq!(json["input"]["command"], "ls");
+        assert!(json.get("thought_signature").is_none());
     }
 
     #[test]
@@ -204,7 +231,9 @@ mod tests {
         });
         let block: ResponseContentBlock = serde_json::from_value(json).unwrap();
         match block {
-            ResponseContentBlock::ToolUse { id, name, input } => {
+            ResponseContentBlock::ToolUse {
+                id, name, input, ..
+            } => {
                 assert_eq!(id, "tu_abc");
                 assert_eq!(name, "bash");
                 assert_eq!(input["command"], "echo hi");
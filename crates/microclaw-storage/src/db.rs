@@ -31,6 +31,14 @@ where
         .map_err(|e| MicroClawError::ToolExecution(format!("DB task join error: {e}")))?
 }
 
+/// Page counts around one `run_maintenance` pass.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MaintenanceReport {
+    pub pages_before: i64,
+    pub pages_after: i64,
+    pub free_pages_before: i64,
+}
+
 #[derive(Debug, Clone)]
 pub struct StoredMessage {
     pub id: String,
@@ -69,9 +77,38 @@ pub struct LlmUsageSummary {
     pub input_tokens: i64,
     pub output_tokens: i64,
     pub total_tokens: i64,
+    /// Prompt tokens served from the provider's prompt cache.
+    pub cache_read_tokens: i64,
+    /// Prompt tokens written to the provider's prompt cache.
+    pub cache_write_tokens: i64,
     pub last_request_at: Option<String>,
 }
 
+/// Per-chat activity figures behind `/stats`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChatStats {
+    pub user_messages: i64,
+    pub bot_messages: i64,
+    pub first_message_at: Option<String>,
+    pub last_message_at: Option<String>,
+    /// `(tool_name, calls, errors)`, most used first.
+    pub top_tools: Vec<(String, i64, i64)>,
+    /// `(utc_hour, messages)` for hours with at least one message, busiest first.
+    pub active_hours: Vec<(u32, i64)>,
+    pub active_memories: i64,
+}
+
+/// One tool call from `tool_call_logs` as shown by `/trace`. `input_json` is
+/// stored already redacted; `result_preview` is clipped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolCallTrace {
+    pub tool_name: String,
+    pub input_json: Option<String>,
+    pub result_preview: Option<String>,
+    pub is_error: bool,
+    pub created_at: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct LlmModelUsageSummary {
     pub model: String,
@@ -183,7 +220,7 @@ pub struct AuditLogRecord {
 pub type SessionMetaRow = (String, String, Option<String>, Option<i64>);
 pub type SessionTreeRow = (i64, Option<String>, Option<i64>, String);
 
-const SCHEMA_VERSION_CURRENT: i64 = 11;
+const SCHEMA_VERSION_CURRENT: i64 = 23;
 
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
@@ -197,6 +234,7 @@ pub struct ScheduledTask {
     pub last_run: Option<String>,
     pub status: String, // "active", "paused", "completed", "cancelled"
     pub created_at: String,
+    pub task_type: String, // "agent" (run prompt through the agent) or "export"
 }
 
 #[derive(Debug, Clone)]
@@ -308,6 +346,28 @@ fn infer_channel_from_chat_type(chat_type: &str) -> &'static str {
     }
 }
 
+/// Make `(channel, external_chat_id)` unique. `channel` is the tenant key: it
+/// names the bot account (`telegram`, `telegram.sales`, ...), so the same
+/// platform chat id seen by two bots maps to two internal chats. Rows that
+/// already share an identity keep it on the oldest chat; the others get
+/// `#<chat_id>` appended so their history stays reachable by chat_id but no
+/// longer matches inbound lookups.
+fn namespace_duplicate_chat_identities(conn: &Connection) -> Result<(), MicroClawError> {
+    conn.execute_batch(
+        "UPDATE chats
+         SET external_chat_id = external_chat_id || '#' || chat_id
+         WHERE EXISTS (
+             SELECT 1 FROM chats AS older
+             WHERE older.channel = chats.channel
+               AND older.external_chat_id = chats.external_chat_id
+               AND older.chat_id < chats.chat_id
+         );
+         CREATE UNIQUE INDEX IF NOT EXISTS idx_chats_tenant_external
+             ON chats(channel, external_chat_id);",
+    )?;
+    Ok(())
+}
+
 fn ensure_chat_identity_schema(conn: &Connection) -> Result<(), MicroClawError> {
     if !table_has_column(conn, "chats", "channel")? {
         conn.execute("ALTER TABLE chats ADD COLUMN channel TEXT", [])?;
@@ -615,6 +675,139 @@ fn apply_schema_migrations(conn: &Connection) -> Result<(), MicroClawError> {
         set_schema_version(conn, 11)?;
         version = 11;
     }
+    if version < 12 {
+        if !table_has_column(conn, "scheduled_tasks", "task_type")? {
+            conn.execute(
+                "ALTER TABLE scheduled_tasks ADD COLUMN task_type TEXT NOT NULL DEFAULT 'agent'",
+                [],
+            )?;
+        }
+        set_schema_version(conn, 12)?;
+        version = 12;
+    }
+    if version < 13 {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS chat_settings (
+                chat_id INTEGER NOT NULL,
+                key TEXT NOT NULL,
+                value TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                PRIMARY KEY (chat_id, key)
+            );",
+        )?;
+        set_schema_version(conn, 13)?;
+        version = 13;
+    }
+    if version < 14 {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS message_claims (
+                channel TEXT NOT NULL,
+                message_id TEXT NOT NULL,
+                instance_id TEXT NOT NULL,
+                claimed_at_ms INTEGER NOT NULL,
+                PRIMARY KEY (channel, message_id)
+            );
+            CREATE INDEX IF NOT EXISTS idx_message_claims_claimed_at
+                ON message_claims(claimed_at_ms);",
+        )?;
+        set_schema_version(conn, 14)?;
+        version = 14;
+    }
+    if version < 15 {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS tool_call_logs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                chat_id INTEGER NOT NULL,
+                tool_name TEXT NOT NULL,
+                is_error INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_tool_call_logs_chat_created
+                ON tool_call_logs(chat_id, created_at);",
+        )?;
+        set_schema_version(conn, 15)?;
+        version = 15;
+    }
+    if version < 16 {
+        namespace_duplicate_chat_identities(conn)?;
+        set_schema_version(conn, 16)?;
+        version = 16;
+    }
+    if version < 17 {
+        for column in ["run_id", "input_json", "result_preview"] {
+            if !table_has_column(conn, "tool_call_logs", column)? {
+                conn.execute(
+                    &format!("ALTER TABLE tool_call_logs ADD COLUMN {column} TEXT"),
+                    [],
+                )?;
+            }
+        }
+        conn.execute_batch(
+            "CREATE INDEX IF NOT EXISTS idx_tool_call_logs_chat_run
+                ON tool_call_logs(chat_id, run_id);",
+        )?;
+        set_schema_version(conn, 17)?;
+        version = 17;
+    }
+    if version < 18 {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS chat_model_overrides (
+                chat_id INTEGER PRIMARY KEY,
+                model TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            );",
+        )?;
+        set_schema_version(conn, 18)?;
+        version = 18;
+    }
+    if version < 19 {
+        if !table_has_column(conn, "sessions", "created_at")? {
+            conn.execute("ALTER TABLE sessions ADD COLUMN created_at TEXT", [])?;
+        }
+        set_schema_version(conn, 19)?;
+        version = 19;
+    }
+    if version < 20 {
+        if !table_has_column(conn, "message_claims", "completed_at_ms")? {
+            conn.execute(
+                "ALTER TABLE message_claims ADD COLUMN completed_at_ms INTEGER",
+                [],
+            )?;
+        }
+        set_schema_version(conn, 20)?;
+        version = 20;
+    }
+    if version < 21 {
+        if !table_has_column(conn, "chat_model_overrides", "provider")? {
+            conn.execute(
+                "ALTER TABLE chat_model_overrides ADD COLUMN provider TEXT NOT NULL DEFAULT ''",
+                [],
+            )?;
+        }
+        set_schema_version(conn, 21)?;
+        version = 21;
+    }
+    if version < 22 {
+        for column in ["cache_read_tokens", "cache_write_tokens"] {
+            if !table_has_column(conn, "llm_usage_logs", column)? {
+                conn.execute(
+                    &format!(
+                        "ALTER TABLE llm_usage_logs ADD COLUMN {column} INTEGER NOT NULL DEFAULT 0"
+                    ),
+                    [],
+                )?;
+            }
+        }
+        set_schema_version(conn, 22)?;
+        version = 22;
+    }
+    if version < 23 {
+        if !table_has_column(conn, "sessions", "merged_through")? {
+            conn.execute("ALTER TABLE sessions ADD COLUMN merged_through TEXT", [])?;
+        }
+        set_schema_version(conn, 23)?;
+        version = 23;
+    }
     if version != SCHEMA_VERSION_CURRENT {
         set_schema_version(conn, SCHEMA_VERSION_CURRENT)?;
     }
@@ -629,6 +822,12 @@ impl Database {
         }
     }
 
+    /// Run a trivial query, to check the connection is usable and not held.
+    pub fn ping(&self) -> Result<(), MicroClawError> {
+        self.lock_conn().query_row("SELECT 1", [], |_| Ok(()))?;
+        Ok(())
+    }
+
     pub fn new(data_dir: &str) -> Result<Self, MicroClawError> {
         let db_path = Path::new(data_dir).join("microclaw.db");
         std::fs::create_dir_all(data_dir)?;
@@ -641,6 +840,7 @@ impl Database {
         });
 
         let conn = Connection::open(db_path)?;
+        conn.busy_timeout(std::time::Duration::from_secs(5))?;
         conn.execute_batch("PRAGMA journal_mode=WAL;")?;
 
         conn.execute_batch(
@@ -894,8 +1094,9 @@ impl Database {
                 chat_title = COALESCE(?2, chat_title),
                 chat_type = ?3,
                 last_message_time = ?4,
-                channel = COALESCE(?5, channel),
-                external_chat_id = COALESCE(external_chat_id, ?6)",
+                channel = COALESCE(NULLIF(trim(channel), ''), ?5),
+                external_chat_id = COALESCE(external_chat_id, ?6)
+             ON CONFLICT DO NOTHING",
             params![
                 chat_id,
                 chat_title,
@@ -908,6 +1109,9 @@ impl Database {
         Ok(())
     }
 
+    /// Map a platform chat to an internal chat_id. `channel` is the tenant key
+    /// (channel plus bot account), so identical external ids on different bots
+    /// never share sessions, memories or history.
     pub fn resolve_or_create_chat_id(
         &self,
         channel: &str,
@@ -1012,6 +1216,61 @@ impl Database {
         Ok(exists)
     }
 
+    /// Replace the content of a stored user message after it was edited on
+    /// the platform. Returns false when the message is unknown, was sent by
+    /// the bot, or already has this content.
+    pub fn update_user_message_content(
+        &self,
+        chat_id: i64,
+        message_id: &str,
+        content: &str,
+    ) -> Result<bool, MicroClawError> {
+        let conn = self.lock_conn();
+        let affected = conn.execute(
+            "UPDATE messages SET content = ?3
+             WHERE chat_id = ?1 AND id = ?2 AND is_from_bot = 0 AND content != ?3",
+            params![chat_id, message_id, content],
+        )?;
+        Ok(affected > 0)
+    }
+
+    /// Replace the content of a stored bot message after the bot edited it.
+    pub fn update_bot_message_content(
+        &self,
+        chat_id: i64,
+        message_id: &str,
+        content: &str,
+    ) -> Result<bool, MicroClawError> {
+        let conn = self.lock_conn();
+        let affected = conn.execute(
+            "UPDATE messages SET content = ?3
+             WHERE chat_id = ?1 AND id = ?2 AND is_from_bot = 1",
+            params![chat_id, message_id, content],
+        )?;
+        Ok(affected > 0)
+    }
+
+    /// Whether the bot has posted in `chat_id` at or after the time the given
+    /// message was stored.
+    pub fn has_bot_reply_since_message(
+        &self,
+        chat_id: i64,
+        message_id: &str,
+    ) -> Result<bool, MicroClawError> {
+        let conn = self.lock_conn();
+        let replied = conn.query_row(
+            "SELECT EXISTS(
+                 SELECT 1 FROM messages m
+                 JOIN messages b ON b.chat_id = m.chat_id
+                 WHERE m.chat_id = ?1 AND m.id = ?2
+                   AND b.is_from_bot = 1 AND b.timestamp >= m.timestamp
+             )",
+            params![chat_id, message_id],
+            |row| row.get::<_, bool>(0),
+        )?;
+        Ok(replied)
+    }
+
     pub fn get_recent_messages(
         &self,
         chat_id: i64,
@@ -1203,6 +1462,161 @@ impl Database {
         }
     }
 
+    /// Per-chat preference stored as a string, e.g. `streaming = off`.
+    pub fn get_chat_setting(
+        &self,
+        chat_id: i64,
+        key: &str,
+    ) -> Result<Option<String>, MicroClawError> {
+        let conn = self.lock_conn();
+        let result = conn.query_row(
+            "SELECT value FROM chat_settings WHERE chat_id = ?1 AND key = ?2",
+            params![chat_id, key],
+            |row| row.get::<_, String>(0),
+        );
+        match result {
+            Ok(v) => Ok(Some(v)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn set_chat_setting(
+        &self,
+        chat_id: i64,
+        key: &str,
+        value: &str,
+    ) -> Result<(), MicroClawError> {
+        let conn = self.lock_conn();
+        let now = chrono::Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO chat_settings (chat_id, key, value, updated_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(chat_id, key) DO UPDATE SET
+                value = excluded.value,
+                updated_at = excluded.updated_at",
+            params![chat_id, key, value, now],
+        )?;
+        Ok(())
+    }
+
+    /// Claim an inbound message for `instance_id` so that only one instance
+    /// sharing this database handles it. Returns true when the caller holds the
+    /// claim: the message was unclaimed, is already held by the same instance,
+    /// or the previous holder's lease (older than `lease_ms`) has expired.
+    /// Completed messages are never claimed again.
+    pub fn claim_message(
+        &self,
+        channel: &str,
+        message_id: &str,
+        instance_id: &str,
+        now_ms: i64,
+        lease_ms: i64,
+    ) -> Result<bool, MicroClawError> {
+        let conn = self.lock_conn();
+        let rows = conn.execute(
+            "INSERT INTO message_claims (channel, message_id, instance_id, claimed_at_ms)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(channel, message_id) DO UPDATE SET
+                instance_id = excluded.instance_id,
+                claimed_at_ms = excluded.claimed_at_ms
+             WHERE message_claims.completed_at_ms IS NULL
+                AND (message_claims.instance_id = excluded.instance_id
+                    OR message_claims.claimed_at_ms <= ?5)",
+            params![
+                channel,
+                message_id,
+                instance_id,
+                now_ms,
+                now_ms.saturating_sub(lease_ms)
+            ],
+        )?;
+        Ok(rows == 1)
+    }
+
+    /// Mark a claimed message as handled so later deliveries are dropped.
+    pub fn complete_message_claim(
+        &self,
+        channel: &str,
+        message_id: &str,
+        now_ms: i64,
+    ) -> Result<bool, MicroClawError> {
+        let conn = self.lock_conn();
+        let rows = conn.execute(
+            "UPDATE message_claims SET completed_at_ms = ?3
+             WHERE channel = ?1 AND message_id = ?2 AND completed_at_ms IS NULL",
+            params![channel, message_id, now_ms],
+        )?;
+        Ok(rows == 1)
+    }
+
+    pub fn prune_message_claims_before(&self, before_ms: i64) -> Result<usize, MicroClawError> {
+        let conn = self.lock_conn();
+        let n = conn.execute(
+            "DELETE FROM message_claims WHERE claimed_at_ms < ?1",
+            params![before_ms],
+        )?;
+        Ok(n)
+    }
+
+    /// Returns true if a setting was removed.
+    pub fn delete_chat_setting(&self, chat_id: i64, key: &str) -> Result<bool, MicroClawError> {
+        let conn = self.lock_conn();
+        let rows = conn.execute(
+            "DELETE FROM chat_settings WHERE chat_id = ?1 AND key = ?2",
+            params![chat_id, key],
+        )?;
+        Ok(rows > 0)
+    }
+
+    /// Model set for this chat with `/model`, taking precedence over the
+    /// channel's model.
+    /// The chat's `/model` choice as `(provider, model)`. `provider` is the
+    /// provider alias the model was chosen for (empty for older rows).
+    pub fn get_chat_model_override(
+        &self,
+        chat_id: i64,
+    ) -> Result<Option<(String, String)>, MicroClawError> {
+        let conn = self.lock_conn();
+        let row = conn
+            .query_row(
+                "SELECT provider, model FROM chat_model_overrides WHERE chat_id = ?1",
+                params![chat_id],
+                |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+            )
+            .optional()?;
+        Ok(row)
+    }
+
+    pub fn set_chat_model_override(
+        &self,
+        chat_id: i64,
+        provider: &str,
+        model: &str,
+    ) -> Result<(), MicroClawError> {
+        let conn = self.lock_conn();
+        let now = chrono::Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO chat_model_overrides (chat_id, provider, model, updated_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(chat_id) DO UPDATE SET
+                provider = excluded.provider,
+                model = excluded.model,
+                updated_at = excluded.updated_at",
+            params![chat_id, provider, model, now],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_chat_model_override(&self, chat_id: i64) -> Result<bool, MicroClawError> {
+        let conn = self.lock_conn();
+        let rows = conn.execute(
+            "DELETE FROM chat_model_overrides WHERE chat_id = ?1",
+            params![chat_id],
+        )?;
+        Ok(rows > 0)
+    }
+
     /// Get messages since the bot's last response in this chat.
     /// Falls back to `fallback_limit` most recent messages if bot never responded.
     pub fn get_messages_since_last_bot_response(
@@ -1281,13 +1695,32 @@ impl Database {
         schedule_type: &str,
         schedule_value: &str,
         next_run: &str,
+    ) -> Result<i64, MicroClawError> {
+        self.create_scheduled_task_with_type(
+            chat_id,
+            "agent",
+            prompt,
+            schedule_type,
+            schedule_value,
+            next_run,
+        )
+    }
+
+    pub fn create_scheduled_task_with_type(
+        &self,
+        chat_id: i64,
+        task_type: &str,
+        prompt: &str,
+        schedule_type: &str,
+        schedule_value: &str,
+        next_run: &str,
     ) -> Result<i64, MicroClawError> {
         let conn = self.lock_conn();
         let now = chrono::Utc::now().to_rfc3339();
         conn.execute(
-            "INSERT INTO scheduled_tasks (chat_id, prompt, schedule_type, schedule_value, next_run, status, created_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, 'active', ?6)",
-            params![chat_id, prompt, schedule_type, schedule_value, next_run, now],
+            "INSERT INTO scheduled_tasks (chat_id, prompt, schedule_type, schedule_value, next_run, status, created_at, task_type)
+             VALUES (?1, ?2, ?3, ?4, ?5, 'active', ?6, ?7)",
+            params![chat_id, prompt, schedule_type, schedule_value, next_run, now, task_type],
         )?;
         Ok(conn.last_insert_rowid())
     }
@@ -1295,7 +1728,7 @@ impl Database {
     pub fn get_due_tasks(&self, now: &str) -> Result<Vec<ScheduledTask>, MicroClawError> {
         let conn = self.lock_conn();
         let mut stmt = conn.prepare(
-            "SELECT id, chat_id, prompt, schedule_type, schedule_value, next_run, last_run, status, created_at
+            "SELECT id, chat_id, prompt, schedule_type, schedule_value, next_run, last_run, status, created_at, task_type
              FROM scheduled_tasks
              WHERE status = 'active' AND next_run <= ?1",
         )?;
@@ -1311,6 +1744,7 @@ impl Database {
                     last_run: row.get(6)?,
                     status: row.get(7)?,
                     created_at: row.get(8)?,
+                    task_type: row.get(9)?,
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
@@ -1326,7 +1760,7 @@ impl Database {
         let tx = conn.unchecked_transaction()?;
 
         let mut stmt = tx.prepare(
-            "SELECT id, chat_id, prompt, schedule_type, schedule_value, next_run, last_run, status, created_at
+            "SELECT id, chat_id, prompt, schedule_type, schedule_value, next_run, last_run, status, created_at, task_type
              FROM scheduled_tasks
              WHERE status = 'active' AND next_run <= ?1
              ORDER BY next_run ASC, id ASC
@@ -1344,6 +1778,7 @@ impl Database {
                     last_run: row.get(6)?,
                     status: row.get(7)?,
                     created_at: row.get(8)?,
+                    task_type: row.get(9)?,
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
@@ -1371,7 +1806,7 @@ impl Database {
     pub fn get_tasks_for_chat(&self, chat_id: i64) -> Result<Vec<ScheduledTask>, MicroClawError> {
         let conn = self.lock_conn();
         let mut stmt = conn.prepare(
-            "SELECT id, chat_id, prompt, schedule_type, schedule_value, next_run, last_run, status, created_at
+            "SELECT id, chat_id, prompt, schedule_type, schedule_value, next_run, last_run, status, created_at, task_type
              FROM scheduled_tasks
              WHERE chat_id = ?1 AND status IN ('active', 'paused')
              ORDER BY id",
@@ -1388,16 +1823,72 @@ impl Database {
                     last_run: row.get(6)?,
                     status: row.get(7)?,
                     created_at: row.get(8)?,
+                    task_type: row.get(9)?,
                 })
             })?
             .collect::<Result<Vec<_>, _>>()?;
         Ok(tasks)
     }
 
+    /// Active and paused tasks of a chat (or of every chat when `chat_id` is
+    /// `None`), plus one-shot tasks that completed at or after
+    /// `completed_since`, each with its most recent run log.
+    pub fn get_tasks_with_last_run(
+        &self,
+        chat_id: Option<i64>,
+        completed_since: &str,
+    ) -> Result<Vec<(ScheduledTask, Option<TaskRunLog>)>, MicroClawError> {
+        let conn = self.lock_conn();
+        let mut stmt = conn.prepare(
+            "SELECT t.id, t.chat_id, t.prompt, t.schedule_type, t.schedule_value, t.next_run,
+                    t.last_run, t.status, t.created_at, t.task_type,
+                    r.id, r.started_at, r.finished_at, r.duration_ms, r.success, r.result_summary
+             FROM scheduled_tasks t
+             LEFT JOIN task_run_logs r
+               ON r.id = (SELECT MAX(id) FROM task_run_logs WHERE task_id = t.id)
+             WHERE (?1 IS NULL OR t.chat_id = ?1)
+               AND (t.status IN ('active', 'paused')
+                    OR (t.status = 'completed' AND t.schedule_type = 'once'
+                        AND t.last_run >= ?2))
+             ORDER BY t.id",
+        )?;
+        let rows = stmt
+            .query_map(params![chat_id, completed_since], |row| {
+                let task = ScheduledTask {
+                    id: row.get(0)?,
+                    chat_id: row.get(1)?,
+                    prompt: row.get(2)?,
+                    schedule_type: row.get(3)?,
+                    schedule_value: row.get(4)?,
+                    next_run: row.get(5)?,
+                    last_run: row.get(6)?,
+                    status: row.get(7)?,
+                    created_at: row.get(8)?,
+                    task_type: row.get(9)?,
+                };
+                let last_run = match row.get::<_, Option<i64>>(10)? {
+                    Some(id) => Some(TaskRunLog {
+                        id,
+                        task_id: task.id,
+                        chat_id: task.chat_id,
+                        started_at: row.get(11)?,
+                        finished_at: row.get(12)?,
+                        duration_ms: row.get(13)?,
+                        success: row.get::<_, i32>(14)? != 0,
+                        result_summary: row.get(15)?,
+                    }),
+                    None => None,
+                };
+                Ok((task, last_run))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
     pub fn get_task_by_id(&self, task_id: i64) -> Result<Option<ScheduledTask>, MicroClawError> {
         let conn = self.lock_conn();
         let result = conn.query_row(
-            "SELECT id, chat_id, prompt, schedule_type, schedule_value, next_run, last_run, status, created_at
+            "SELECT id, chat_id, prompt, schedule_type, schedule_value, next_run, last_run, status, created_at, task_type
              FROM scheduled_tasks
              WHERE id = ?1",
             params![task_id],
@@ -1412,6 +1903,7 @@ impl Database {
                     last_run: row.get(6)?,
                     status: row.get(7)?,
                     created_at: row.get(8)?,
+                    task_type: row.get(9)?,
                 })
             },
         );
@@ -1518,6 +2010,16 @@ impl Database {
         &self,
         task_id: i64,
         limit: usize,
+    ) -> Result<Vec<TaskRunLog>, MicroClawError> {
+        self.get_task_run_logs_page(task_id, limit, 0)
+    }
+
+    /// Run logs of a task, most recent first, skipping the newest `offset`.
+    pub fn get_task_run_logs_page(
+        &self,
+        task_id: i64,
+        limit: usize,
+        offset: usize,
     ) -> Result<Vec<TaskRunLog>, MicroClawError> {
         let conn = self.lock_conn();
         let mut stmt = conn.prepare(
@@ -1525,10 +2027,10 @@ impl Database {
              FROM task_run_logs
              WHERE task_id = ?1
              ORDER BY id DESC
-             LIMIT ?2",
+             LIMIT ?2 OFFSET ?3",
         )?;
         let logs = stmt
-            .query_map(params![task_id, limit as i64], |row| {
+            .query_map(params![task_id, limit as i64, offset as i64], |row| {
                 Ok(TaskRunLog {
                     id: row.get(0)?,
                     task_id: row.get(1)?,
@@ -1720,8 +2222,8 @@ impl Database {
         let conn = self.lock_conn();
         let now = chrono::Utc::now().to_rfc3339();
         conn.execute(
-            "INSERT INTO sessions (chat_id, messages_json, updated_at, parent_session_key, fork_point, skill_envs_json)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            "INSERT INTO sessions (chat_id, messages_json, updated_at, parent_session_key, fork_point, skill_envs_json, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?3)
              ON CONFLICT(chat_id) DO UPDATE SET
                 messages_json = ?2,
                 updated_at = ?3,
@@ -1733,6 +2235,31 @@ impl Database {
         Ok(())
     }
 
+    /// Like [`Database::save_session_with_meta`], also recording
+    /// `merged_through`: the timestamp of the newest chat message folded into
+    /// the session. `None` keeps the recorded one.
+    pub fn save_session_with_merge_cursor(
+        &self,
+        chat_id: i64,
+        messages_json: &str,
+        skill_envs_json: Option<&str>,
+        merged_through: Option<&str>,
+    ) -> Result<(), MicroClawError> {
+        let conn = self.lock_conn();
+        let now = chrono::Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO sessions (chat_id, messages_json, updated_at, skill_envs_json, merged_through, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?3)
+             ON CONFLICT(chat_id) DO UPDATE SET
+                messages_json = ?2,
+                updated_at = ?3,
+                skill_envs_json = COALESCE(?4, skill_envs_json),
+                merged_through = COALESCE(?5, merged_through)",
+            params![chat_id, messages_json, now, skill_envs_json, merged_through],
+        )?;
+        Ok(())
+    }
+
     pub fn save_session_skill_envs(
         &self,
         chat_id: i64,
@@ -1760,24 +2287,72 @@ impl Database {
         }
     }
 
-    pub fn load_session_skill_envs(&self, chat_id: i64) -> Result<Option<String>, MicroClawError> {
+    /// The session's messages and the point after which chat messages are new
+    /// to it: `merged_through`, or `updated_at` for sessions saved before that
+    /// was recorded.
+    pub fn load_session_with_merge_cursor(
+        &self,
+        chat_id: i64,
+    ) -> Result<Option<(String, String)>, MicroClawError> {
         let conn = self.lock_conn();
-        let result = conn.query_row(
-            "SELECT skill_envs_json FROM sessions WHERE chat_id = ?1",
+        conn.query_row(
+            "SELECT messages_json, COALESCE(merged_through, updated_at)
+             FROM sessions WHERE chat_id = ?1",
             params![chat_id],
-            |row| row.get::<_, Option<String>>(0),
-        );
-        match result {
-            Ok(v) => Ok(v),
-            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-            Err(e) => Err(e.into()),
-        }
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+        )
+        .optional()
+        .map_err(Into::into)
     }
 
-    pub fn load_session_meta(
+    /// Replace a session's messages without bumping `updated_at`, which marks
+    /// where the next run picks up new chat messages for sessions without a
+    /// `merged_through` cursor.
+    pub fn update_session_messages(
         &self,
         chat_id: i64,
-    ) -> Result<Option<SessionMetaRow>, MicroClawError> {
+        messages_json: &str,
+    ) -> Result<bool, MicroClawError> {
+        let conn = self.lock_conn();
+        let rows = conn.execute(
+            "UPDATE sessions SET messages_json = ?2 WHERE chat_id = ?1",
+            params![chat_id, messages_json],
+        )?;
+        Ok(rows > 0)
+    }
+
+    /// When the current session was started. `None` for sessions saved before
+    /// this was recorded.
+    pub fn load_session_created_at(&self, chat_id: i64) -> Result<Option<String>, MicroClawError> {
+        let conn = self.lock_conn();
+        let created_at = conn
+            .query_row(
+                "SELECT created_at FROM sessions WHERE chat_id = ?1",
+                params![chat_id],
+                |row| row.get::<_, Option<String>>(0),
+            )
+            .optional()?;
+        Ok(created_at.flatten())
+    }
+
+    pub fn load_session_skill_envs(&self, chat_id: i64) -> Result<Option<String>, MicroClawError> {
+        let conn = self.lock_conn();
+        let result = conn.query_row(
+            "SELECT skill_envs_json FROM sessions WHERE chat_id = ?1",
+            params![chat_id],
+            |row| row.get::<_, Option<String>>(0),
+        );
+        match result {
+            Ok(v) => Ok(v),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn load_session_meta(
+        &self,
+        chat_id: i64,
+    ) -> Result<Option<SessionMetaRow>, MicroClawError> {
         let conn = self.lock_conn();
         let result = conn.query_row(
             "SELECT messages_json, updated_at, parent_session_key, fork_point
@@ -1862,6 +2437,165 @@ impl Database {
         Ok(affected > 0)
     }
 
+    pub fn log_tool_call(
+        &self,
+        chat_id: i64,
+        tool_name: &str,
+        is_error: bool,
+    ) -> Result<(), MicroClawError> {
+        let conn = self.lock_conn();
+        conn.execute(
+            "INSERT INTO tool_call_logs (chat_id, tool_name, is_error, created_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                chat_id,
+                tool_name,
+                is_error as i32,
+                chrono::Utc::now().to_rfc3339()
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Log a tool call together with the agent run it belongs to, its
+    /// (already redacted) input and a preview of its result, for `/trace`.
+    pub fn log_tool_call_trace(
+        &self,
+        chat_id: i64,
+        run_id: &str,
+        tool_name: &str,
+        is_error: bool,
+        input_json: &str,
+        result_preview: &str,
+    ) -> Result<(), MicroClawError> {
+        let conn = self.lock_conn();
+        conn.execute(
+            "INSERT INTO tool_call_logs
+                (chat_id, tool_name, is_error, created_at, run_id, input_json, result_preview)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                chat_id,
+                tool_name,
+                is_error as i32,
+                chrono::Utc::now().to_rfc3339(),
+                run_id,
+                input_json,
+                result_preview
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Delete tool call logs created before `before` (RFC 3339).
+    pub fn prune_tool_call_logs_before(&self, before: &str) -> Result<usize, MicroClawError> {
+        let conn = self.lock_conn();
+        let n = conn.execute(
+            "DELETE FROM tool_call_logs WHERE created_at < ?1",
+            params![before],
+        )?;
+        Ok(n)
+    }
+
+    /// Tool calls of the most recent traced run in a chat, in execution order.
+    pub fn get_last_run_tool_calls(
+        &self,
+        chat_id: i64,
+    ) -> Result<Vec<ToolCallTrace>, MicroClawError> {
+        let conn = self.lock_conn();
+        let run_id: Option<String> = conn
+            .query_row(
+                "SELECT run_id FROM tool_call_logs
+                 WHERE chat_id = ?1 AND run_id IS NOT NULL
+                 ORDER BY id DESC LIMIT 1",
+                params![chat_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let Some(run_id) = run_id else {
+            return Ok(Vec::new());
+        };
+        let mut stmt = conn.prepare(
+            "SELECT tool_name, input_json, result_preview, is_error, created_at
+             FROM tool_call_logs
+             WHERE chat_id = ?1 AND run_id = ?2
+             ORDER BY id ASC",
+        )?;
+        let rows = stmt
+            .query_map(params![chat_id, run_id], |row| {
+                Ok(ToolCallTrace {
+                    tool_name: row.get(0)?,
+                    input_json: row.get(1)?,
+                    result_preview: row.get(2)?,
+                    is_error: row.get::<_, i64>(3)? != 0,
+                    created_at: row.get(4)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    /// Aggregate activity for one chat. Only rows belonging to `chat_id` are read.
+    pub fn get_chat_stats(
+        &self,
+        chat_id: i64,
+        max_tools: usize,
+        max_hours: usize,
+    ) -> Result<ChatStats, MicroClawError> {
+        let conn = self.lock_conn();
+        let (user_messages, bot_messages, first_message_at, last_message_at) = conn.query_row(
+            "SELECT COALESCE(SUM(CASE WHEN is_from_bot = 0 THEN 1 ELSE 0 END), 0),
+                    COALESCE(SUM(CASE WHEN is_from_bot = 1 THEN 1 ELSE 0 END), 0),
+                    MIN(timestamp),
+                    MAX(timestamp)
+             FROM messages WHERE chat_id = ?1",
+            params![chat_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )?;
+
+        let mut stmt = conn.prepare(
+            "SELECT tool_name, COUNT(*) AS calls, COALESCE(SUM(is_error), 0)
+             FROM tool_call_logs WHERE chat_id = ?1
+             GROUP BY tool_name
+             ORDER BY calls DESC, tool_name ASC
+             LIMIT ?2",
+        )?;
+        let top_tools = stmt
+            .query_map(params![chat_id, max_tools as i64], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT CAST(strftime('%H', timestamp) AS INTEGER) AS hour, COUNT(*) AS n
+             FROM messages
+             WHERE chat_id = ?1 AND is_from_bot = 0 AND hour IS NOT NULL
+             GROUP BY hour
+             ORDER BY n DESC, hour ASC
+             LIMIT ?2",
+        )?;
+        let active_hours = stmt
+            .query_map(params![chat_id, max_hours as i64], |row| {
+                Ok((row.get::<_, i64>(0)? as u32, row.get(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let active_memories = conn.query_row(
+            "SELECT COUNT(*) FROM memories WHERE chat_id = ?1 AND is_archived = 0",
+            params![chat_id],
+            |row| row.get(0),
+        )?;
+
+        Ok(ChatStats {
+            user_messages,
+            bot_messages,
+            first_message_at,
+            last_message_at,
+            top_tools,
+            active_hours,
+            active_memories,
+        })
+    }
+
     pub fn delete_chat_data(&self, chat_id: i64) -> Result<bool, MicroClawError> {
         let conn = self.lock_conn();
         let tx = conn.unchecked_transaction()?;
@@ -1896,6 +2630,18 @@ impl Database {
             params![chat_id],
         )?;
         affected += tx.execute("DELETE FROM memories WHERE chat_id = ?1", params![chat_id])?;
+        affected += tx.execute(
+            "DELETE FROM chat_settings WHERE chat_id = ?1",
+            params![chat_id],
+        )?;
+        affected += tx.execute(
+            "DELETE FROM chat_model_overrides WHERE chat_id = ?1",
+            params![chat_id],
+        )?;
+        affected += tx.execute(
+            "DELETE FROM tool_call_logs WHERE chat_id = ?1",
+            params![chat_id],
+        )?;
         affected += tx.execute("DELETE FROM chats WHERE chat_id = ?1", params![chat_id])?;
 
         tx.commit()?;
@@ -2331,6 +3077,37 @@ impl Database {
         Ok(messages)
     }
 
+    /// Newest `limit` messages after `since`, returned oldest-first.
+    pub fn get_latest_messages_since(
+        &self,
+        chat_id: i64,
+        since: &str,
+        limit: usize,
+    ) -> Result<Vec<StoredMessage>, MicroClawError> {
+        let conn = self.lock_conn();
+        let mut stmt = conn.prepare(
+            "SELECT id, chat_id, sender_name, content, is_from_bot, timestamp
+             FROM messages
+             WHERE chat_id = ?1 AND timestamp > ?2
+             ORDER BY timestamp DESC
+             LIMIT ?3",
+        )?;
+        let mut messages = stmt
+            .query_map(params![chat_id, since, limit as i64], |row| {
+                Ok(StoredMessage {
+                    id: row.get(0)?,
+                    chat_id: row.get(1)?,
+                    sender_name: row.get(2)?,
+                    content: row.get(3)?,
+                    is_from_bot: row.get::<_, i32>(4)? != 0,
+                    timestamp: row.get(5)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        messages.reverse();
+        Ok(messages)
+    }
+
     pub fn get_reflector_cursor(&self, chat_id: i64) -> Result<Option<String>, MicroClawError> {
         let conn = self.lock_conn();
         let result = conn.query_row(
@@ -2373,14 +3150,44 @@ impl Database {
         input_tokens: i64,
         output_tokens: i64,
         request_kind: &str,
+    ) -> Result<i64, MicroClawError> {
+        self.log_llm_usage_with_cache(
+            chat_id,
+            caller_channel,
+            provider,
+            model,
+            input_tokens,
+            output_tokens,
+            0,
+            0,
+            request_kind,
+        )
+    }
+
+    /// Like [`Database::log_llm_usage`], also recording prompt cache reads
+    /// and writes. These are kept in their own columns because providers
+    /// differ on whether `input_tokens` already counts them.
+    #[allow(clippy::too_many_arguments)]
+    pub fn log_llm_usage_with_cache(
+        &self,
+        chat_id: i64,
+        caller_channel: &str,
+        provider: &str,
+        model: &str,
+        input_tokens: i64,
+        output_tokens: i64,
+        cache_read_tokens: i64,
+        cache_write_tokens: i64,
+        request_kind: &str,
     ) -> Result<i64, MicroClawError> {
         let conn = self.lock_conn();
         let now = chrono::Utc::now().to_rfc3339();
         let total_tokens = input_tokens.saturating_add(output_tokens);
         conn.execute(
             "INSERT INTO llm_usage_logs
-                (chat_id, caller_channel, provider, model, input_tokens, output_tokens, total_tokens, request_kind, created_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                (chat_id, caller_channel, provider, model, input_tokens, output_tokens, total_tokens,
+                 cache_read_tokens, cache_write_tokens, request_kind, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
             params![
                 chat_id,
                 caller_channel,
@@ -2389,6 +3196,8 @@ impl Database {
                 input_tokens,
                 output_tokens,
                 total_tokens,
+                cache_read_tokens,
+                cache_write_tokens,
                 request_kind,
                 now,
             ],
@@ -2409,94 +3218,119 @@ impl Database {
         since: Option<&str>,
     ) -> Result<LlmUsageSummary, MicroClawError> {
         let conn = self.lock_conn();
-        let (requests, input_tokens, output_tokens, total_tokens, last_request_at) =
-            match (chat_id, since) {
-                (Some(id), Some(since_ts)) => conn.query_row(
-                    "SELECT
+        let (
+            requests,
+            input_tokens,
+            output_tokens,
+            total_tokens,
+            cache_read_tokens,
+            cache_write_tokens,
+            last_request_at,
+        ) = match (chat_id, since) {
+            (Some(id), Some(since_ts)) => conn.query_row(
+                "SELECT
                     COUNT(*),
                     COALESCE(SUM(input_tokens), 0),
                     COALESCE(SUM(output_tokens), 0),
                     COALESCE(SUM(total_tokens), 0),
+                    COALESCE(SUM(cache_read_tokens), 0),
+                    COALESCE(SUM(cache_write_tokens), 0),
                     MAX(created_at)
                  FROM llm_usage_logs
                  WHERE chat_id = ?1 AND created_at >= ?2",
-                    params![id, since_ts],
-                    |row| {
-                        Ok((
-                            row.get::<_, i64>(0)?,
-                            row.get::<_, i64>(1)?,
-                            row.get::<_, i64>(2)?,
-                            row.get::<_, i64>(3)?,
-                            row.get::<_, Option<String>>(4)?,
-                        ))
-                    },
-                )?,
-                (Some(id), None) => conn.query_row(
-                    "SELECT
+                params![id, since_ts],
+                |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, i64>(1)?,
+                        row.get::<_, i64>(2)?,
+                        row.get::<_, i64>(3)?,
+                        row.get::<_, i64>(4)?,
+                        row.get::<_, i64>(5)?,
+                        row.get::<_, Option<String>>(6)?,
+                    ))
+                },
+            )?,
+            (Some(id), None) => conn.query_row(
+                "SELECT
                     COUNT(*),
                     COALESCE(SUM(input_tokens), 0),
                     COALESCE(SUM(output_tokens), 0),
                     COALESCE(SUM(total_tokens), 0),
+                    COALESCE(SUM(cache_read_tokens), 0),
+                    COALESCE(SUM(cache_write_tokens), 0),
                     MAX(created_at)
                  FROM llm_usage_logs
                  WHERE chat_id = ?1",
-                    params![id],
-                    |row| {
-                        Ok((
-                            row.get::<_, i64>(0)?,
-                            row.get::<_, i64>(1)?,
-                            row.get::<_, i64>(2)?,
-                            row.get::<_, i64>(3)?,
-                            row.get::<_, Option<String>>(4)?,
-                        ))
-                    },
-                )?,
-                (None, Some(since_ts)) => conn.query_row(
-                    "SELECT
+                params![id],
+                |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, i64>(1)?,
+                        row.get::<_, i64>(2)?,
+                        row.get::<_, i64>(3)?,
+                        row.get::<_, i64>(4)?,
+                        row.get::<_, i64>(5)?,
+                        row.get::<_, Option<String>>(6)?,
+                    ))
+                },
+            )?,
+            (None, Some(since_ts)) => conn.query_row(
+                "SELECT
                     COUNT(*),
                     COALESCE(SUM(input_tokens), 0),
                     COALESCE(SUM(output_tokens), 0),
                     COALESCE(SUM(total_tokens), 0),
+                    COALESCE(SUM(cache_read_tokens), 0),
+                    COALESCE(SUM(cache_write_tokens), 0),
                     MAX(created_at)
                  FROM llm_usage_logs
                  WHERE created_at >= ?1",
-                    params![since_ts],
-                    |row| {
-                        Ok((
-                            row.get::<_, i64>(0)?,
-                            row.get::<_, i64>(1)?,
-                            row.get::<_, i64>(2)?,
-                            row.get::<_, i64>(3)?,
-                            row.get::<_, Option<String>>(4)?,
-                        ))
-                    },
-                )?,
-                (None, None) => conn.query_row(
-                    "SELECT
+                params![since_ts],
+                |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, i64>(1)?,
+                        row.get::<_, i64>(2)?,
+                        row.get::<_, i64>(3)?,
+                        row.get::<_, i64>(4)?,
+                        row.get::<_, i64>(5)?,
+                        row.get::<_, Option<String>>(6)?,
+                    ))
+                },
+            )?,
+            (None, None) => conn.query_row(
+                "SELECT
                     COUNT(*),
                     COALESCE(SUM(input_tokens), 0),
                     COALESCE(SUM(output_tokens), 0),
                     COALESCE(SUM(total_tokens), 0),
+                    COALESCE(SUM(cache_read_tokens), 0),
+                    COALESCE(SUM(cache_write_tokens), 0),
                     MAX(created_at)
                  FROM llm_usage_logs",
-                    [],
-                    |row| {
-                        Ok((
-                            row.get::<_, i64>(0)?,
-                            row.get::<_, i64>(1)?,
-                            row.get::<_, i64>(2)?,
-                            row.get::<_, i64>(3)?,
-                            row.get::<_, Option<String>>(4)?,
-                        ))
-                    },
-                )?,
-            };
+                [],
+                |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, i64>(1)?,
+                        row.get::<_, i64>(2)?,
+                        row.get::<_, i64>(3)?,
+                        row.get::<_, i64>(4)?,
+                        row.get::<_, i64>(5)?,
+                        row.get::<_, Option<String>>(6)?,
+                    ))
+                },
+            )?,
+        };
 
         Ok(LlmUsageSummary {
             requests,
             input_tokens,
             output_tokens,
             total_tokens,
+            cache_read_tokens,
+            cache_write_tokens,
             last_request_at,
         })
     }
@@ -2699,6 +3533,28 @@ impl Database {
         Ok(memories)
     }
 
+    /// Number of memories that are not archived for a chat.
+    pub fn count_active_memories_for_chat(&self, chat_id: i64) -> Result<i64, MicroClawError> {
+        let conn = self.lock_conn();
+        let count = conn.query_row(
+            "SELECT COUNT(*) FROM memories WHERE chat_id = ?1 AND is_archived = 0",
+            params![chat_id],
+            |row| row.get(0),
+        )?;
+        Ok(count)
+    }
+
+    /// Chats with at least one active memory; `None` stands for global memories.
+    pub fn get_memory_chat_ids(&self) -> Result<Vec<Option<i64>>, MicroClawError> {
+        let conn = self.lock_conn();
+        let mut stmt =
+            conn.prepare("SELECT DISTINCT chat_id FROM memories WHERE is_archived = 0")?;
+        let ids = stmt
+            .query_map([], |row| row.get::<_, Option<i64>>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(ids)
+    }
+
     pub fn get_active_chat_ids_since(&self, since: &str) -> Result<Vec<i64>, MicroClawError> {
         let conn = self.lock_conn();
         let mut stmt = conn.prepare(
@@ -3048,7 +3904,46 @@ impl Database {
         Ok(rows > 0)
     }
 
+    /// Archive `duplicate_id` as a copy of `keeper_id` and record the edge
+    /// between them. The keeper is left untouched. Returns false when the
+    /// duplicate was already archived.
+    pub fn merge_duplicate_memory(
+        &self,
+        duplicate_id: i64,
+        keeper_id: i64,
+        reason: &str,
+    ) -> Result<bool, MicroClawError> {
+        let conn = self.lock_conn();
+        let tx = conn.unchecked_transaction()?;
+        let now = chrono::Utc::now().to_rfc3339();
+        let rows = tx.execute(
+            "UPDATE memories
+             SET is_archived = 1, archived_at = ?1, updated_at = ?1
+             WHERE id = ?2 AND is_archived = 0",
+            params![now, duplicate_id],
+        )?;
+        if rows > 0 {
+            tx.execute(
+                "INSERT INTO memory_supersede_edges(from_memory_id, to_memory_id, reason, created_at)
+                 VALUES(?1, ?2, ?3, ?4)",
+                params![duplicate_id, keeper_id, reason, now],
+            )?;
+        }
+        tx.commit()?;
+        Ok(rows > 0)
+    }
+
     pub fn archive_stale_memories(&self, stale_days: i64) -> Result<usize, MicroClawError> {
+        self.archive_decayed_memories(0.35, stale_days)
+    }
+
+    /// Archive active memories below `confidence_floor` that have not been
+    /// seen for `stale_days`.
+    pub fn archive_decayed_memories(
+        &self,
+        confidence_floor: f64,
+        stale_days: i64,
+    ) -> Result<usize, MicroClawError> {
         let conn = self.lock_conn();
         let cutoff = (chrono::Utc::now() - chrono::Duration::days(stale_days.max(1))).to_rfc3339();
         let now = chrono::Utc::now().to_rfc3339();
@@ -3056,13 +3951,36 @@ impl Database {
             "UPDATE memories
              SET is_archived = 1, archived_at = ?1, updated_at = ?1
              WHERE is_archived = 0
-               AND confidence < 0.35
+               AND confidence < ?3
                AND COALESCE(last_seen_at, updated_at, created_at) < ?2",
-            params![now, cutoff],
+            params![now, cutoff, confidence_floor],
         )?;
         Ok(rows)
     }
 
+    /// Checkpoint the WAL, rebuild the file with `VACUUM` so space freed by
+    /// pruning is returned to the filesystem, then refresh planner statistics.
+    /// Holds the connection lock for the whole pass, so no other write through
+    /// this handle can interleave with it.
+    pub fn run_maintenance(&self) -> Result<MaintenanceReport, MicroClawError> {
+        let conn = self.lock_conn();
+        let page_count =
+            |conn: &Connection| conn.query_row("PRAGMA page_count", [], |row| row.get::<_, i64>(0));
+        let pages_before = page_count(&conn)?;
+        let free_pages_before: i64 =
+            conn.query_row("PRAGMA freelist_count", [], |row| row.get(0))?;
+        conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+        conn.execute_batch("VACUUM;")?;
+        conn.execute_batch("PRAGMA optimize;")?;
+        conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+        let pages_after = page_count(&conn)?;
+        Ok(MaintenanceReport {
+            pages_before,
+            pages_after,
+            free_pages_before,
+        })
+    }
+
     pub fn supersede_memory(
         &self,
         from_memory_id: i64,
@@ -3835,6 +4753,52 @@ mod tests {
         cleanup(&dir);
     }
 
+    #[test]
+    fn test_update_user_message_content_after_edit() {
+        let (db, dir) = test_db();
+        let user = |id: &str, content: &str, ts: &str| StoredMessage {
+            id: id.into(),
+            chat_id: 100,
+            sender_name: "alice".into(),
+            content: content.into(),
+            is_from_bot: false,
+            timestamp: ts.into(),
+        };
+        db.store_message(&user("m1", "helo", "2024-01-01T00:00:01Z"))
+            .unwrap();
+        assert!(!db.has_bot_reply_since_message(100, "m1").unwrap());
+
+        assert!(db.update_user_message_content(100, "m1", "hello").unwrap());
+        let stored = db.get_recent_messages(100, 10).unwrap();
+        assert_eq!(stored[0].content, "hello");
+        assert_eq!(stored[0].timestamp, "2024-01-01T00:00:01Z");
+        // Unchanged content, unknown ids and other chats are not updated.
+        assert!(!db.update_user_message_content(100, "m1", "hello").unwrap());
+        assert!(!db.update_user_message_content(100, "nope", "x").unwrap());
+        assert!(!db.update_user_message_content(200, "m1", "x").unwrap());
+
+        db.store_message(&StoredMessage {
+            id: "b1".into(),
+            chat_id: 100,
+            sender_name: "bot".into(),
+            content: "hi alice".into(),
+            is_from_bot: true,
+            timestamp: "2024-01-01T00:00:02Z".into(),
+        })
+        .unwrap();
+        assert!(db.has_bot_reply_since_message(100, "m1").unwrap());
+        // Bot messages are never rewritten by user edits, only by the bot.
+        assert!(!db.update_user_message_content(100, "b1", "forged").unwrap());
+        assert!(!db.update_bot_message_content(100, "m1", "forged").unwrap());
+        assert!(db
+            .update_bot_message_content(100, "b1", "hi there")
+            .unwrap());
+        db.store_message(&user("m2", "later", "2024-01-01T00:00:03Z"))
+            .unwrap();
+        assert!(!db.has_bot_reply_since_message(100, "m2").unwrap());
+        cleanup(&dir);
+    }
+
     #[test]
     fn test_get_recent_messages_ordering_and_limit() {
         let (db, dir) = test_db();
@@ -3911,39 +4875,159 @@ mod tests {
         })
         .unwrap();
 
-        let messages = db
-            .get_messages_since_last_bot_response(100, 50, 10)
-            .unwrap();
-        // Should include the bot message and everything after it
-        assert!(messages.len() >= 2);
-        // First should be the bot msg or after it
-        assert_eq!(messages[0].id, "m2"); // the bot message (timestamp >= bot's timestamp)
-        assert_eq!(messages[1].id, "m3");
-        assert_eq!(messages[2].id, "m4");
+        let messages = db
+            .get_messages_since_last_bot_response(100, 50, 10)
+            .unwrap();
+        // Should include the bot message and everything after it
+        assert!(messages.len() >= 2);
+        // First should be the bot msg or after it
+        assert_eq!(messages[0].id, "m2"); // the bot message (timestamp >= bot's timestamp)
+        assert_eq!(messages[1].id, "m3");
+        assert_eq!(messages[2].id, "m4");
+        cleanup(&dir);
+    }
+
+    #[test]
+    fn test_get_messages_since_last_bot_response_no_bot_msg() {
+        let (db, dir) = test_db();
+
+        for i in 0..5 {
+            db.store_message(&StoredMessage {
+                id: format!("m{i}"),
+                chat_id: 100,
+                sender_name: "alice".into(),
+                content: format!("msg {i}"),
+                is_from_bot: false,
+                timestamp: format!("2024-01-01T00:00:0{i}Z"),
+            })
+            .unwrap();
+        }
+
+        // Fallback to last 3
+        let messages = db.get_messages_since_last_bot_response(100, 50, 3).unwrap();
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[0].content, "msg 2");
+        assert_eq!(messages[2].content, "msg 4");
+        cleanup(&dir);
+    }
+
+    #[test]
+    fn test_chat_settings_set_get_delete() {
+        let (db, dir) = test_db();
+        assert_eq!(db.get_chat_setting(1, "streaming").unwrap(), None);
+        db.set_chat_setting(1, "streaming", "off").unwrap();
+        db.set_chat_setting(2, "streaming", "on").unwrap();
+        assert_eq!(
+            db.get_chat_setting(1, "streaming").unwrap().as_deref(),
+            Some("off")
+        );
+        db.set_chat_setting(1, "streaming", "on").unwrap();
+        assert_eq!(
+            db.get_chat_setting(1, "streaming").unwrap().as_deref(),
+            Some("on")
+        );
+        assert!(db.delete_chat_setting(1, "streaming").unwrap());
+        assert!(!db.delete_chat_setting(1, "streaming").unwrap());
+        assert_eq!(db.get_chat_setting(1, "streaming").unwrap(), None);
+        assert_eq!(
+            db.get_chat_setting(2, "streaming").unwrap().as_deref(),
+            Some("on")
+        );
+        cleanup(&dir);
+    }
+
+    #[test]
+    fn test_chat_model_override_set_get_delete() {
+        let (db, dir) = test_db();
+        assert_eq!(db.get_chat_model_override(1).unwrap(), None);
+        db.set_chat_model_override(1, "openai", "gpt-4o").unwrap();
+        db.set_chat_model_override(1, "openai", "gpt-4o-mini")
+            .unwrap();
+        assert_eq!(
+            db.get_chat_model_override(1).unwrap(),
+            Some(("openai".to_string(), "gpt-4o-mini".to_string()))
+        );
+        assert_eq!(db.get_chat_model_override(2).unwrap(), None);
+        assert!(db.delete_chat_model_override(1).unwrap());
+        assert!(!db.delete_chat_model_override(1).unwrap());
+        assert_eq!(db.get_chat_model_override(1).unwrap(), None);
+        cleanup(&dir);
+    }
+
+    #[test]
+    fn test_claim_message_once_per_lease() {
+        let (db, dir) = test_db();
+        assert!(db
+            .claim_message("telegram", "1:42", "a", 1_000, 60_000)
+            .unwrap());
+        assert!(!db
+            .claim_message("telegram", "1:42", "b", 2_000, 60_000)
+            .unwrap());
+        // The holder can re-claim its own message.
+        assert!(db
+            .claim_message("telegram", "1:42", "a", 3_000, 60_000)
+            .unwrap());
+        // Same id on another channel is independent.
+        assert!(db
+            .claim_message("discord", "1:42", "b", 3_000, 60_000)
+            .unwrap());
+        // After the lease expires another instance may take over.
+        assert!(db
+            .claim_message("telegram", "1:42", "b", 63_000, 60_000)
+            .unwrap());
+        assert!(!db
+            .claim_message("telegram", "1:42", "a", 64_000, 60_000)
+            .unwrap());
+
+        assert_eq!(db.prune_message_claims_before(63_000).unwrap(), 1);
         cleanup(&dir);
     }
 
     #[test]
-    fn test_get_messages_since_last_bot_response_no_bot_msg() {
+    fn test_completed_message_claim_is_never_reclaimed() {
         let (db, dir) = test_db();
+        assert!(db
+            .claim_message("telegram", "1:42", "a", 1_000, 60_000)
+            .unwrap());
+        assert!(db
+            .complete_message_claim("telegram", "1:42", 2_000)
+            .unwrap());
+        assert!(!db
+            .complete_message_claim("telegram", "1:42", 3_000)
+            .unwrap());
+        // Neither the holder nor another instance after lease expiry may
+        // handle it again.
+        assert!(!db
+            .claim_message("telegram", "1:42", "a", 4_000, 60_000)
+            .unwrap());
+        assert!(!db
+            .claim_message("telegram", "1:42", "b", 120_000, 60_000)
+            .unwrap());
+        cleanup(&dir);
+    }
 
-        for i in 0..5 {
-            db.store_message(&StoredMessage {
-                id: format!("m{i}"),
-                chat_id: 100,
-                sender_name: "alice".into(),
-                content: format!("msg {i}"),
-                is_from_bot: false,
-                timestamp: format!("2024-01-01T00:00:0{i}Z"),
+    #[test]
+    fn test_claim_message_concurrent_instances_claim_once() {
+        let (db, dir) = test_db();
+        drop(db);
+        let dir_str = dir.to_str().unwrap().to_string();
+        let handles: Vec<_> = (0..4)
+            .map(|i| {
+                let dir_str = dir_str.clone();
+                std::thread::spawn(move || {
+                    let db = Database::new(&dir_str).unwrap();
+                    let instance = format!("instance-{i}");
+                    (0..25)
+                        .filter(|n| {
+                            db.claim_message("web", &format!("msg-{n}"), &instance, 1_000, 60_000)
+                                .unwrap()
+                        })
+                        .count()
+                })
             })
-            .unwrap();
-        }
-
-        // Fallback to last 3
-        let messages = db.get_messages_since_last_bot_response(100, 50, 3).unwrap();
-        assert_eq!(messages.len(), 3);
-        assert_eq!(messages[0].content, "msg 2");
-        assert_eq!(messages[2].content, "msg 4");
+            .collect();
+        let total: usize = handles.into_iter().map(|h| h.join().unwrap()).sum();
+        assert_eq!(total, 25);
         cleanup(&dir);
     }
 
@@ -3966,6 +5050,20 @@ mod tests {
         assert_eq!(tasks[0].prompt, "say hello");
         assert_eq!(tasks[0].schedule_type, "cron");
         assert_eq!(tasks[0].status, "active");
+        assert_eq!(tasks[0].task_type, "agent");
+
+        let export_id = db
+            .create_scheduled_task_with_type(
+                100,
+                "export",
+                "Export chat history",
+                "cron",
+                "0 0 9 * * *",
+                "2024-06-02T09:00:00Z",
+            )
+            .unwrap();
+        let export_task = db.get_task_by_id(export_id).unwrap().unwrap();
+        assert_eq!(export_task.task_type, "export");
         cleanup(&dir);
     }
 
@@ -4237,6 +5335,73 @@ mod tests {
         assert_eq!(logs.len(), 3);
         assert_eq!(logs[0].result_summary.as_deref(), Some("Run 4")); // most recent
         assert_eq!(logs[2].result_summary.as_deref(), Some("Run 2"));
+        let page = db.get_task_run_logs_page(task_id, 3, 3).unwrap();
+        assert_eq!(page.len(), 2);
+        assert_eq!(page[0].result_summary.as_deref(), Some("Run 1"));
+        cleanup(&dir);
+    }
+
+    #[test]
+    fn test_get_tasks_with_last_run_for_chat() {
+        let (db, dir) = test_db();
+        let cron = db
+            .create_scheduled_task(100, "cron", "cron", "0 * * * * *", "2024-01-01T00:00:00Z")
+            .unwrap();
+        let paused = db
+            .create_scheduled_task(100, "paused", "cron", "0 * * * * *", "2024-01-01T00:00:00Z")
+            .unwrap();
+        db.update_task_status(paused, "paused").unwrap();
+        let fired = db
+            .create_scheduled_task(
+                100,
+                "fired",
+                "once",
+                "2024-01-02T00:00:00Z",
+                "2024-01-02T00:00:00Z",
+            )
+            .unwrap();
+        db.update_task_after_run(fired, "2024-01-02T00:00:03Z", None)
+            .unwrap();
+        let old = db
+            .create_scheduled_task(
+                100,
+                "old",
+                "once",
+                "2023-01-01T00:00:00Z",
+                "2023-01-01T00:00:00Z",
+            )
+            .unwrap();
+        db.update_task_after_run(old, "2023-01-01T00:00:03Z", None)
+            .unwrap();
+        db.create_scheduled_task(200, "other", "cron", "0 * * * * *", "2024-01-01T00:00:00Z")
+            .unwrap();
+        for (i, success) in [true, false].into_iter().enumerate() {
+            db.log_task_run(
+                cron,
+                100,
+                &format!("2024-01-01T00:0{i}:00Z"),
+                &format!("2024-01-01T00:0{i}:02Z"),
+                2000 + i as i64,
+                success,
+                None,
+            )
+            .unwrap();
+        }
+
+        let rows = db
+            .get_tasks_with_last_run(Some(100), "2024-01-01T00:00:00Z")
+            .unwrap();
+        let ids: Vec<i64> = rows.iter().map(|(t, _)| t.id).collect();
+        assert_eq!(ids, vec![cron, paused, fired]);
+        let last = rows[0].1.as_ref().unwrap();
+        assert_eq!(last.duration_ms, 2001);
+        assert!(!last.success);
+        assert!(rows[1].1.is_none());
+        assert_eq!(rows[2].0.status, "completed");
+        let all = db
+            .get_tasks_with_last_run(None, "2024-01-01T00:00:00Z")
+            .unwrap();
+        assert_eq!(all.len(), 4);
         cleanup(&dir);
     }
 
@@ -4344,6 +5509,49 @@ mod tests {
         cleanup(&dir);
     }
 
+    #[test]
+    fn test_session_merge_cursor_survives_later_saves() {
+        let (db, dir) = test_db();
+        db.save_session(100, "[]").unwrap();
+        let (_, cursor) = db.load_session_with_merge_cursor(100).unwrap().unwrap();
+        let (_, updated_at) = db.load_session(100).unwrap().unwrap();
+        assert_eq!(cursor, updated_at);
+
+        db.save_session_with_merge_cursor(100, "[]", None, Some("2024-01-01T00:00:02Z"))
+            .unwrap();
+        db.save_session_with_merge_cursor(100, "[]", None, None)
+            .unwrap();
+        let (_, cursor) = db.load_session_with_merge_cursor(100).unwrap().unwrap();
+        assert_eq!(cursor, "2024-01-01T00:00:02Z");
+        assert!(db.load_session_with_merge_cursor(101).unwrap().is_none());
+        cleanup(&dir);
+    }
+
+    #[test]
+    fn test_update_session_messages_keeps_timestamps() {
+        let (db, dir) = test_db();
+        assert!(!db.update_session_messages(100, "[]").unwrap());
+        db.save_session(100, r#"[{"role":"user","content":"a"}]"#)
+            .unwrap();
+        let (_, updated_at) = db.load_session(100).unwrap().unwrap();
+        let created_at = db.load_session_created_at(100).unwrap().unwrap();
+
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        assert!(db.update_session_messages(100, "[]").unwrap());
+        assert_eq!(
+            db.load_session(100).unwrap().unwrap(),
+            ("[]".into(), updated_at)
+        );
+        db.save_session(100, r#"[{"role":"user","content":"b"}]"#)
+            .unwrap();
+        assert_eq!(
+            db.load_session_created_at(100).unwrap().as_deref(),
+            Some(created_at.as_str())
+        );
+        assert_eq!(db.load_session_created_at(999).unwrap(), None);
+        cleanup(&dir);
+    }
+
     #[test]
     fn test_load_session_nonexistent() {
         let (db, dir) = test_db();
@@ -4593,6 +5801,12 @@ mod tests {
         assert_eq!(msgs[0].id, "m2");
         assert_eq!(msgs[1].id, "m3");
 
+        let latest = db
+            .get_latest_messages_since(100, "2024-01-01T00:00:00Z", 2)
+            .unwrap();
+        let ids: Vec<&str> = latest.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, vec!["m2", "m3"]);
+
         cleanup(&dir);
     }
 
@@ -4652,6 +5866,79 @@ mod tests {
         cleanup(&dir);
     }
 
+    #[test]
+    fn test_identical_external_chat_ids_are_isolated_per_bot_account() {
+        let (db, dir) = test_db();
+
+        let resolve = |channel: &str| {
+            let id = db
+                .resolve_or_create_chat_id(channel, "777", Some("same-chat"), "telegram_private")
+                .unwrap();
+            // Adapters upsert the chat right after resolving it; that must not
+            // move the row out of its tenant.
+            db.upsert_chat(id, Some("same-chat"), "telegram_private")
+                .unwrap();
+            id
+        };
+        let default_bot = resolve("telegram");
+        let sales_bot = resolve("telegram.sales");
+        assert_ne!(default_bot, sales_bot);
+        assert_eq!(resolve("telegram"), default_bot);
+        assert_eq!(resolve("telegram.sales"), sales_bot);
+        assert_eq!(
+            db.get_chat_channel(sales_bot).unwrap().as_deref(),
+            Some("telegram.sales")
+        );
+
+        db.save_session(default_bot, "[\"default\"]").unwrap();
+        db.insert_memory(Some(sales_bot), "sales only", "KNOWLEDGE")
+            .unwrap();
+        assert!(db.load_session(sales_bot).unwrap().is_none());
+        assert!(db
+            .get_all_memories_for_chat(Some(default_bot))
+            .unwrap()
+            .is_empty());
+
+        cleanup(&dir);
+    }
+
+    #[test]
+    fn test_migration_namespaces_duplicate_chat_identities() {
+        let (db, dir) = test_db();
+        {
+            let conn = db.lock_conn();
+            conn.execute_batch(
+                "DROP INDEX idx_chats_tenant_external;
+                 INSERT INTO chats(chat_id, chat_title, chat_type, last_message_time, channel, external_chat_id)
+                 VALUES (10, 'a', 'telegram_private', '2024-01-01T00:00:00Z', 'telegram', '10'),
+                        (11, 'a', 'telegram_private', '2024-01-01T00:00:00Z', 'telegram', '10'),
+                        (12, 'b', 'discord', '2024-01-01T00:00:00Z', 'discord', '10');",
+            )
+            .unwrap();
+            namespace_duplicate_chat_identities(&conn).unwrap();
+        }
+
+        assert_eq!(db.get_chat_external_id(10).unwrap().as_deref(), Some("10"));
+        assert_eq!(
+            db.get_chat_external_id(11).unwrap().as_deref(),
+            Some("10#11")
+        );
+        assert_eq!(db.get_chat_external_id(12).unwrap().as_deref(), Some("10"));
+        assert_eq!(
+            db.resolve_or_create_chat_id("telegram", "10", None, "telegram_private")
+                .unwrap(),
+            10
+        );
+        let dup = db.lock_conn().execute(
+            "INSERT INTO chats(chat_title, chat_type, last_message_time, channel, external_chat_id)
+             VALUES ('c', 'telegram_private', '2024-01-01T00:00:00Z', 'telegram', '10')",
+            [],
+        );
+        assert!(dup.is_err());
+
+        cleanup(&dir);
+    }
+
     #[test]
     fn test_get_chat_id_by_channel_and_title_finds_non_recent_chat() {
         let (db, dir) = test_db();
@@ -4817,6 +6104,41 @@ mod tests {
         cleanup(&dir);
     }
 
+    #[test]
+    fn test_log_llm_usage_with_cache_tokens() {
+        let (db, dir) = test_db();
+        db.log_llm_usage_with_cache(
+            100,
+            "telegram",
+            "anthropic",
+            "claude-test",
+            12,
+            4,
+            900,
+            50,
+            "agent_loop",
+        )
+        .unwrap();
+        db.log_llm_usage(
+            100,
+            "telegram",
+            "anthropic",
+            "claude-test",
+            8,
+            2,
+            "agent_loop",
+        )
+        .unwrap();
+
+        let summary = db.get_llm_usage_summary(Some(100)).unwrap();
+        assert_eq!(summary.input_tokens, 20);
+        assert_eq!(summary.total_tokens, 26);
+        assert_eq!(summary.cache_read_tokens, 900);
+        assert_eq!(summary.cache_write_tokens, 50);
+
+        cleanup(&dir);
+    }
+
     #[test]
     fn test_delete_chat_data_cleans_llm_usage() {
         let (db, dir) = test_db();
@@ -5088,6 +6410,71 @@ mod tests {
         cleanup(&dir);
     }
 
+    #[test]
+    fn test_merge_duplicate_memory_archives_only_the_duplicate() {
+        let (db, dir) = test_db();
+        let keeper = db.insert_memory(Some(100), "likes tea", "PROFILE").unwrap();
+        let dup = db
+            .insert_memory(Some(100), "Likes tea.", "PROFILE")
+            .unwrap();
+        let keeper_before = db.get_memory_by_id(keeper).unwrap().unwrap();
+
+        assert!(db.merge_duplicate_memory(dup, keeper, "dedup").unwrap());
+        assert!(!db.merge_duplicate_memory(dup, keeper, "dedup").unwrap());
+        assert!(db.get_memory_by_id(dup).unwrap().unwrap().is_archived);
+        let keeper_after = db.get_memory_by_id(keeper).unwrap().unwrap();
+        assert!(!keeper_after.is_archived);
+        assert_eq!(keeper_after.updated_at, keeper_before.updated_at);
+        let edges: i64 = db
+            .lock_conn()
+            .query_row(
+                "SELECT COUNT(*) FROM memory_supersede_edges
+                 WHERE from_memory_id = ?1 AND to_memory_id = ?2",
+                params![dup, keeper],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(edges, 1);
+        cleanup(&dir);
+    }
+
+    #[test]
+    fn test_archive_decayed_memories_uses_floor_and_last_seen() {
+        let (db, dir) = test_db();
+        let stale_low = db
+            .insert_memory_with_metadata(Some(100), "old weak fact", "KNOWLEDGE", "tool", 0.2)
+            .unwrap();
+        let stale_strong = db
+            .insert_memory_with_metadata(Some(100), "old strong fact", "KNOWLEDGE", "tool", 0.9)
+            .unwrap();
+        let fresh_low = db
+            .insert_memory_with_metadata(None, "new weak fact", "KNOWLEDGE", "tool", 0.2)
+            .unwrap();
+        let old = (chrono::Utc::now() - chrono::Duration::days(40)).to_rfc3339();
+        db.lock_conn()
+            .execute(
+                "UPDATE memories SET last_seen_at = ?1 WHERE id IN (?2, ?3)",
+                params![old, stale_low, stale_strong],
+            )
+            .unwrap();
+
+        let mut chats = db.get_memory_chat_ids().unwrap();
+        chats.sort();
+        assert_eq!(chats, vec![None, Some(100)]);
+
+        assert_eq!(db.archive_decayed_memories(0.5, 30).unwrap(), 1);
+        assert!(db.get_memory_by_id(stale_low).unwrap().unwrap().is_archived);
+        assert!(
+            !db.get_memory_by_id(stale_strong)
+                .unwrap()
+                .unwrap()
+                .is_archived
+        );
+        assert!(!db.get_memory_by_id(fresh_low).unwrap().unwrap().is_archived);
+        assert_eq!(db.archive_decayed_memories(0.95, 30).unwrap(), 1);
+        cleanup(&dir);
+    }
+
     #[test]
     fn test_supersede_memory_creates_edge_and_archives_old() {
         let (db, dir) = test_db();
@@ -5266,6 +6653,154 @@ mod tests {
         cleanup(&dir);
     }
 
+    #[test]
+    fn test_get_chat_stats_aggregates_only_requested_chat() {
+        let (db, dir) = test_db();
+        let msg = |id: &str, chat_id: i64, from_bot: bool, ts: &str| StoredMessage {
+            id: id.into(),
+            chat_id,
+            sender_name: if from_bot { "bot" } else { "alice" }.into(),
+            content: "hi".into(),
+            is_from_bot: from_bot,
+            timestamp: ts.into(),
+        };
+        db.store_message(&msg("a", 1, false, "2024-01-01T09:15:00Z"))
+            .unwrap();
+        db.store_message(&msg("b", 1, true, "2024-01-01T09:15:05Z"))
+            .unwrap();
+        db.store_message(&msg("c", 1, false, "2024-01-02T09:40:00.123456+00:00"))
+            .unwrap();
+        db.store_message(&msg("d", 1, false, "2024-01-02T21:00:00+02:00"))
+            .unwrap();
+        db.store_message(&msg("e", 2, false, "2024-01-03T03:00:00Z"))
+            .unwrap();
+
+        db.log_tool_call(1, "bash", false).unwrap();
+        db.log_tool_call(1, "bash", true).unwrap();
+        db.log_tool_call(1, "web_search", false).unwrap();
+        db.log_tool_call(2, "read_file", false).unwrap();
+        db.insert_memory(Some(1), "likes tea", "PROFILE").unwrap();
+        let archived = db.insert_memory(Some(1), "old", "EVENT").unwrap();
+        db.archive_memory(archived).unwrap();
+        db.insert_memory(Some(2), "other chat", "PROFILE").unwrap();
+
+        let stats = db.get_chat_stats(1, 5, 3).unwrap();
+        assert_eq!(stats.user_messages, 3);
+        assert_eq!(stats.bot_messages, 1);
+        assert_eq!(
+            stats.first_message_at.as_deref(),
+            Some("2024-01-01T09:15:00Z")
+        );
+        assert_eq!(
+            stats.top_tools,
+            vec![("bash".to_string(), 2, 1), ("web_search".to_string(), 1, 0)]
+        );
+        // 21:00+02:00 is 19:00 UTC.
+        assert_eq!(stats.active_hours, vec![(9, 2), (19, 1)]);
+        assert_eq!(stats.active_memories, 1);
+
+        assert_eq!(db.get_chat_stats(99, 5, 3).unwrap(), ChatStats::default());
+
+        assert!(db.delete_chat_data(1).unwrap());
+        assert!(db.get_chat_stats(1, 5, 3).unwrap().top_tools.is_empty());
+        assert_eq!(db.get_chat_stats(2, 5, 3).unwrap().top_tools.len(), 1);
+
+        cleanup(&dir);
+    }
+
+    #[test]
+    fn test_prune_tool_call_logs_before_cutoff() {
+        let (db, dir) = test_db();
+        db.log_tool_call(1, "bash", false).unwrap();
+        db.log_tool_call(2, "read_file", true).unwrap();
+        assert_eq!(
+            db.prune_tool_call_logs_before("2000-01-01T00:00:00+00:00")
+                .unwrap(),
+            0
+        );
+        assert_eq!(
+            db.prune_tool_call_logs_before("9999-01-01T00:00:00+00:00")
+                .unwrap(),
+            2
+        );
+        assert!(db.get_chat_stats(1, 5, 3).unwrap().top_tools.is_empty());
+        cleanup(&dir);
+    }
+
+    #[test]
+    fn test_get_last_run_tool_calls_returns_latest_run_in_order() {
+        let (db, dir) = test_db();
+        assert!(db.get_last_run_tool_calls(1).unwrap().is_empty());
+
+        db.log_tool_call_trace(1, "run-a", "bash", false, r#"{"command":"ls"}"#, "a.txt")
+            .unwrap();
+        db.log_tool_call_trace(
+            1,
+            "run-b",
+            "web_search",
+            false,
+            r#"{"query":"rust"}"#,
+            "3 hits",
+        )
+        .unwrap();
+        db.log_tool_call(1, "read_file", false).unwrap();
+        db.log_tool_call_trace(1, "run-b", "web_fetch", true, r#"{"url":"x"}"#, "timeout")
+            .unwrap();
+        db.log_tool_call_trace(2, "run-c", "bash", false, "{}", "other chat")
+            .unwrap();
+
+        let trace = db.get_last_run_tool_calls(1).unwrap();
+        let summary: Vec<_> = trace
+            .iter()
+            .map(|t| {
+                (
+                    t.tool_name.as_str(),
+                    t.result_preview.as_deref(),
+                    t.is_error,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("web_search", Some("3 hits"), false),
+                ("web_fetch", Some("timeout"), true),
+            ]
+        );
+        assert_eq!(trace[0].input_json.as_deref(), Some(r#"{"query":"rust"}"#));
+        // Untraced rows still count towards /stats.
+        assert_eq!(db.get_chat_stats(1, 5, 3).unwrap().top_tools.len(), 4);
+
+        cleanup(&dir);
+    }
+
+    #[test]
+    fn test_run_maintenance_reclaims_pruned_pages() {
+        let (db, dir) = test_db();
+        let filler = "x".repeat(4000);
+        for i in 0..200 {
+            db.store_message(&StoredMessage {
+                id: format!("m{i}"),
+                chat_id: 1,
+                sender_name: "alice".into(),
+                content: filler.clone(),
+                is_from_bot: false,
+                timestamp: format!("2024-01-01T00:00:{:02}Z", i % 60),
+            })
+            .unwrap();
+        }
+        db.lock_conn().execute("DELETE FROM messages", []).unwrap();
+
+        let report = db.run_maintenance().unwrap();
+        assert!(report.free_pages_before > 0);
+        assert!(report.pages_after < report.pages_before);
+        // A second pass on a compact file is a harmless no-op.
+        let again = db.run_maintenance().unwrap();
+        assert_eq!(again.free_pages_before, 0);
+
+        cleanup(&dir);
+    }
+
     #[cfg(feature = "sqlite-vec")]
     #[test]
     fn test_sqlite_vec_prepare_and_knn() {
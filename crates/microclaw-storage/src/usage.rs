@@ -27,13 +27,21 @@ fn fmt_int(v: i64) -> String {
 }
 
 fn fmt_summary_line(name: &str, s: &LlmUsageSummary) -> String {
-    format!(
+    let mut line = format!(
         "{name:<8} req={:>4}  tok={} (in {} / out {})",
         fmt_int(s.requests),
         fmt_int(s.total_tokens),
         fmt_int(s.input_tokens),
         fmt_int(s.output_tokens)
-    )
+    );
+    if s.cache_read_tokens > 0 || s.cache_write_tokens > 0 {
+        line.push_str(&format!(
+            "  cache read {} / write {}",
+            fmt_int(s.cache_read_tokens),
+            fmt_int(s.cache_write_tokens)
+        ));
+    }
+    line
 }
 
 fn format_model_rows(rows: &[LlmModelUsageSummary], max_rows: usize) -> Vec<String> {
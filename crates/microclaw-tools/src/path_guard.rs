@@ -83,32 +83,70 @@ fn normalize_path(path: &Path) -> PathBuf {
     parts.iter().collect()
 }
 
-/// Check if a file path should be blocked.
-pub fn is_blocked(path: &Path) -> bool {
-    // Try to resolve symlinks; if the file doesn't exist, fall back to
-    // logical normalization so that `..` components are still resolved.
-    // For relative paths, prepend the working directory first so `..`
-    // at the start can be resolved against the absolute prefix.
-    let resolved = std::fs::canonicalize(path).unwrap_or_else(|_| {
-        let abs = if path.is_relative() {
-            if let Ok(cwd) = std::env::current_dir() {
-                cwd.join(path)
-            } else {
-                path.to_path_buf()
-            }
-        } else {
-            path.to_path_buf()
-        };
-        normalize_path(&abs)
-    });
+/// How many symlinks `resolve_real_path` follows before giving up.
+const MAX_SYMLINK_HOPS: usize = 16;
 
-    // Check against blocked absolute paths (both original and resolved)
+fn absolute_path(path: &Path) -> PathBuf {
+    if path.is_relative() {
+        if let Ok(cwd) = std::env::current_dir() {
+            return cwd.join(path);
+        }
+    }
+    path.to_path_buf()
+}
+
+/// Resolve `path` to the real location it refers to. Existing paths go
+/// through `canonicalize`. For a path that does not exist yet (a file about to
+/// be written), the deepest existing ancestor is canonicalized and the rest is
+/// appended, so a symlinked parent directory still resolves to its target.
+/// Dangling symlinks are followed to where they point.
+fn resolve_real_path(path: &Path) -> PathBuf {
+    resolve_real_path_with_hops(&absolute_path(path), MAX_SYMLINK_HOPS)
+}
+
+fn resolve_real_path_with_hops(path: &Path, hops: usize) -> PathBuf {
+    if let Ok(real) = std::fs::canonicalize(path) {
+        return real;
+    }
+    let components: Vec<Component> = path.components().collect();
+    for split in (1..=components.len()).rev() {
+        let prefix: PathBuf = components[..split].iter().collect();
+        let rest: PathBuf = components[split..].iter().collect();
+        if let Ok(real) = std::fs::canonicalize(&prefix) {
+            return normalize_path(&real.join(rest));
+        }
+        if hops == 0 {
+            continue;
+        }
+        if let Ok(target) = std::fs::read_link(&prefix) {
+            let target = match prefix.parent() {
+                Some(parent) if target.is_relative() => parent.join(target),
+                _ => target,
+            };
+            return resolve_real_path_with_hops(&target.join(rest), hops - 1);
+        }
+    }
+    normalize_path(path)
+}
+
+/// Check if a file path should be blocked. Both the path as written (with
+/// `.` and `..` resolved) and the real path it resolves to through symlinks
+/// are checked, so neither traversal nor a link into a sensitive directory
+/// gets through.
+pub fn is_blocked(path: &Path) -> bool {
     let original_str = path.to_string_lossy();
+    if BLOCKED_ABSOLUTE.contains(&original_str.as_ref()) {
+        return true;
+    }
+    let logical = normalize_path(&absolute_path(path));
+    let real = resolve_real_path(path);
+    is_blocked_resolved(&logical) || is_blocked_resolved(&real)
+}
+
+fn is_blocked_resolved(resolved: &Path) -> bool {
     let resolved_str = resolved.to_string_lossy();
-    for blocked in BLOCKED_ABSOLUTE {
-        if original_str == *blocked || resolved_str == *blocked {
-            return true;
-        }
+    if BLOCKED_ABSOLUTE.contains(&resolved_str.as_ref()) {
+        return true;
     }
 
     // Collect components as strings for checking
@@ -378,4 +416,50 @@ mod tests {
         assert!(err.contains("symlink"));
         let _ = std::fs::remove_dir_all(&dir);
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_blocks_traversal_into_real_blocked_dir() {
+        let dir = std::env::temp_dir().join(format!("mc_pg_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("project")).unwrap();
+        std::fs::create_dir_all(dir.join(".aws")).unwrap();
+        std::fs::write(dir.join(".aws/config"), "x").unwrap();
+
+        let existing = dir.join("project/../.aws/config");
+        let not_yet_written = dir.join("project/../.aws/new_file");
+        assert!(is_blocked(&existing));
+        assert!(is_blocked(&not_yet_written));
+        assert!(check_path(not_yet_written.to_string_lossy().as_ref()).is_err());
+        assert!(!is_blocked(&dir.join("project/../project/main.rs")));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_blocks_symlink_into_blocked_dir() {
+        let dir = std::env::temp_dir().join(format!("mc_pg_{}", uuid::Uuid::new_v4()));
+        let ssh = dir.join(".ssh");
+        std::fs::create_dir_all(&ssh).unwrap();
+        std::fs::write(ssh.join("config"), "Host *").unwrap();
+        let link = dir.join("innocent");
+        std::os::unix::fs::symlink(&ssh, &link).unwrap();
+        let dangling = dir.join("dangling");
+        std::os::unix::fs::symlink(ssh.join("missing_key"), &dangling).unwrap();
+
+        assert!(is_blocked(&link));
+        assert!(is_blocked(&link.join("config")));
+        assert!(is_blocked(&link.join("not_written_yet")));
+        assert!(is_blocked(&dangling));
+        let matches = filter_paths(vec![
+            link.join("config").to_string_lossy().to_string(),
+            dir.join("other.txt").to_string_lossy().to_string(),
+        ]);
+        assert_eq!(
+            matches,
+            vec![dir.join("other.txt").to_string_lossy().to_string()]
+        );
+        let err = check_path(link.join("config").to_string_lossy().as_ref()).unwrap_err();
+        assert!(err.contains("Access denied"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
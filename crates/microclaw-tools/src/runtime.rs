@@ -99,15 +99,21 @@ pub fn tool_risk(name: &str) -> ToolRisk {
         "bash" => ToolRisk::High,
         "write_file"
         | "edit_file"
+        | "save_attachment"
         | "write_memory"
         | "send_message"
+        | "edit_message"
+        | "generate_image"
+        | "http_request"
         | "sync_skills"
         | "schedule_task"
+        | "remind_me"
         | "pause_scheduled_task"
         | "resume_scheduled_task"
         | "cancel_scheduled_task"
         | "replay_scheduled_task_dlq"
         | "structured_memory_delete"
+        | "forget"
         | "structured_memory_update" => ToolRisk::Medium,
         _ => ToolRisk::Low,
     }
@@ -153,12 +159,33 @@ pub fn validate_execution_policy(
     }
 }
 
+/// One media file attached to the inbound message that started the current turn.
+#[derive(Clone)]
+pub struct InboundAttachment {
+    pub media_type: String,
+    /// Base64-encoded file content.
+    pub data_base64: String,
+}
+
+impl std::fmt::Debug for InboundAttachment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InboundAttachment")
+            .field("media_type", &self.media_type)
+            .field("data_base64_len", &self.data_base64.len())
+            .finish()
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct ToolAuthContext {
     pub caller_channel: String,
     pub caller_chat_id: i64,
     pub control_chat_ids: Vec<i64>,
     pub env_files: Vec<String>,
+    /// Every media file of the inbound message, in the order it arrived.
+    pub inbound_attachments: Vec<std::sync::Arc<InboundAttachment>>,
+    /// How many `sub_agent` calls deep this tool call runs; 0 for the main agent.
+    pub sub_agent_depth: usize,
 }
 
 impl ToolAuthContext {
@@ -195,11 +222,17 @@ pub fn auth_context_from_input(input: &serde_json::Value) -> Option<ToolAuthCont
                 .collect()
         })
         .unwrap_or_default();
+    let sub_agent_depth = ctx
+        .get("sub_agent_depth")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0) as usize;
     Some(ToolAuthContext {
         caller_channel,
         caller_chat_id,
         control_chat_ids,
         env_files,
+        inbound_attachments: Vec::new(),
+        sub_agent_depth,
     })
 }
 
@@ -228,6 +261,9 @@ pub fn inject_auth_context(input: serde_json::Value, auth: &ToolAuthContext) ->
     if !auth.env_files.is_empty() {
         auth_val["env_files"] = json!(auth.env_files);
     }
+    if auth.sub_agent_depth > 0 {
+        auth_val["sub_agent_depth"] = json!(auth.sub_agent_depth);
+    }
     obj.insert(AUTH_CONTEXT_KEY.to_string(), auth_val);
     serde_json::Value::Object(obj)
 }
@@ -361,4 +397,26 @@ mod tests {
         let ok = validate_execution_policy("bash", SandboxMode::All, false);
         assert!(ok.is_ok());
     }
+
+    #[test]
+    fn test_auth_context_round_trips_sub_agent_depth() {
+        let auth = ToolAuthContext {
+            caller_channel: "web".into(),
+            caller_chat_id: 7,
+            control_chat_ids: vec![],
+            env_files: vec![],
+            inbound_attachments: Vec::new(),
+            sub_agent_depth: 2,
+        };
+        let input = inject_auth_context(json!({"task": "x"}), &auth);
+        assert_eq!(auth_context_from_input(&input).unwrap().sub_agent_depth, 2);
+
+        let top_level = ToolAuthContext {
+            sub_agent_depth: 0,
+            ..auth
+        };
+        let input = inject_auth_context(json!({}), &top_level);
+        assert!(input["__microclaw_auth"].get("sub_agent_depth").is_none());
+        assert_eq!(auth_context_from_input(&input).unwrap().sub_agent_depth, 0);
+    }
 }
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 use std::path::{Component, Path, PathBuf};
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -138,13 +138,42 @@ pub struct SandboxExecOptions {
     #[allow(clippy::zero_sized_map_values)]
     pub envs: HashMap<String, String>,
     pub env_files: Vec<PathBuf>,
+    /// Start host commands from an empty environment, keeping only
+    /// `SAFE_ENV_VARS`, so credentials in the process environment don't leak.
+    pub clear_env: bool,
+    /// Cap on captured stdout plus stderr. The command is killed once it
+    /// prints more. `None` captures everything.
+    pub max_output_bytes: Option<usize>,
 }
 
+/// Inherited variables a cleared host environment keeps.
+pub const SAFE_ENV_VARS: &[&str] = &[
+    "PATH",
+    "HOME",
+    "USER",
+    "LOGNAME",
+    "SHELL",
+    "TMPDIR",
+    "TEMP",
+    "TMP",
+    "USERPROFILE",
+    "LANG",
+    "LC_ALL",
+    "LC_CTYPE",
+    "TERM",
+    "TZ",
+    "SYSTEMROOT",
+    "COMSPEC",
+    "PATHEXT",
+];
+
 #[derive(Debug, Clone)]
 pub struct SandboxExecResult {
     pub stdout: String,
     pub stderr: String,
     pub exit_code: i32,
+    /// Output went past `max_output_bytes` and the command was killed.
+    pub truncated: bool,
 }
 
 #[async_trait]
@@ -386,31 +415,104 @@ impl Sandbox for DockerSandbox {
         for (k, v) in &opts.envs {
             args.extend(["-e".to_string(), format!("{k}={v}")]);
         }
-        args.push(name);
+        let exec_id = next_exec_id();
+        args.extend(["-e".to_string(), format!("{EXEC_ID_ENV}={exec_id}")]);
+        args.push(name.clone());
         args.extend(["sh".to_string(), "-c".to_string(), command.to_string()]);
-        let child = tokio::process::Command::new(self.runtime.cli())
-            .args(&args)
+        let mut cmd = tokio::process::Command::new(self.runtime.cli());
+        cmd.args(&args)
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped())
-            .stdin(std::process::Stdio::null())
+            .stdin(std::process::Stdio::null());
+        #[cfg(unix)]
+        cmd.process_group(0);
+        cmd.kill_on_drop(true);
+        let child = cmd
             .spawn()
             .with_context(|| format!("failed to spawn {} exec", self.runtime.cli()))?;
-        match tokio::time::timeout(opts.timeout, child.wait_with_output()).await {
-            Ok(Ok(output)) => Ok(SandboxExecResult {
-                stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
-                stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
-                exit_code: output.status.code().unwrap_or(-1),
-            }),
-            Ok(Err(e)) => bail!("{} exec failed: {e}", self.runtime.cli()),
-            Err(_) => bail!(
+        // Killing the CLI leaves the command running in the container, so a
+        // timeout, the output cap or a dropped call also kills it in there.
+        let mut in_container = ContainerExecGuard {
+            cli: self.runtime.cli(),
+            container: Some(name),
+            exec_id,
+        };
+        let collected = collect_output(child, opts).await;
+        if matches!(&collected, Ok(result) if !result.truncated) {
+            in_container.disarm();
+        }
+        match collected {
+            Ok(result) => Ok(result),
+            Err(CollectError::TimedOut) => bail!(
                 "{} exec timed out after {} seconds",
                 self.runtime.cli(),
                 opts.timeout.as_secs()
             ),
+            Err(CollectError::Io(e)) => bail!("{} exec failed: {e}", self.runtime.cli()),
         }
     }
 }
 
+/// Environment variable that tags every process of one `docker exec`.
+const EXEC_ID_ENV: &str = "MICROCLAW_EXEC_ID";
+
+fn next_exec_id() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    format!(
+        "{}-{nanos}-{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+/// Shell script that kills every process whose environment carries the exec
+/// tag. Children inherit it, so this reaches the whole command tree without
+/// relying on `pkill` being installed in the image.
+fn kill_tagged_processes_script(exec_id: &str) -> String {
+    format!(
+        "for p in /proc/[0-9]*; do \
+         if tr '\\0' '\\n' < \"$p/environ\" 2>/dev/null | grep -qx '{EXEC_ID_ENV}={exec_id}'; then \
+         kill -9 \"${{p#/proc/}}\" 2>/dev/null; fi; done"
+    )
+}
+
+/// Kills the processes of an in-container command that did not finish on its
+/// own. The kill runs on a helper thread so `Drop` never blocks.
+struct ContainerExecGuard {
+    cli: &'static str,
+    container: Option<String>,
+    exec_id: String,
+}
+
+impl ContainerExecGuard {
+    fn disarm(&mut self) {
+        self.container = None;
+    }
+}
+
+impl Drop for ContainerExecGuard {
+    fn drop(&mut self) {
+        let Some(container) = self.container.take() else {
+            return;
+        };
+        let mut kill = std::process::Command::new(self.cli);
+        kill.args(["exec", &container, "sh", "-c"])
+            .arg(kill_tagged_processes_script(&self.exec_id))
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null());
+        std::thread::spawn(move || {
+            if let Err(e) = kill.status() {
+                tracing::warn!("failed to stop command in container {container}: {e}");
+            }
+        });
+    }
+}
+
 pub struct SandboxRouter {
     config: SandboxConfig,
     backend: Arc<dyn Sandbox>,
@@ -488,6 +590,14 @@ pub async fn exec_host_command(
 ) -> Result<SandboxExecResult> {
     let spec = shell_command(command);
     let mut cmd = build_command(&spec, opts.working_dir.as_deref());
+    if opts.clear_env {
+        cmd.env_clear();
+        for key in SAFE_ENV_VARS {
+            if let Some(value) = std::env::var_os(key) {
+                cmd.env(key, value);
+            }
+        }
+    }
     for env_file in &opts.env_files {
         if let Ok(content) = std::fs::read_to_string(env_file) {
             for (k, v) in crate::env_file::parse_dotenv(&content) {
@@ -501,16 +611,140 @@ pub async fn exec_host_command(
     cmd.stdout(std::process::Stdio::piped());
     cmd.stderr(std::process::Stdio::piped());
     cmd.stdin(std::process::Stdio::null());
+    #[cfg(unix)]
+    cmd.process_group(0);
+    cmd.kill_on_drop(true);
     let child = cmd.spawn().context("failed to start shell command")?;
-    match tokio::time::timeout(opts.timeout, child.wait_with_output()).await {
-        Ok(Ok(output)) => Ok(SandboxExecResult {
-            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
-            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
-            exit_code: output.status.code().unwrap_or(-1),
-        }),
-        Ok(Err(e)) => bail!("failed to run command: {e}"),
-        Err(_) => bail!("command timed out after {} seconds", opts.timeout.as_secs()),
+    match collect_output(child, opts).await {
+        Ok(result) => Ok(result),
+        Err(CollectError::TimedOut) => {
+            bail!("command timed out after {} seconds", opts.timeout.as_secs())
+        }
+        Err(CollectError::Io(e)) => bail!("failed to run command: {e}"),
+    }
+}
+
+enum CollectError {
+    TimedOut,
+    Io(std::io::Error),
+}
+
+/// Stream the child's stdout and stderr until it exits, keeping at most
+/// `opts.max_output_bytes` between them. The child's process group is killed
+/// when the cap is hit, `opts.timeout` runs out or the caller drops the
+/// future (a hard tool timeout or `/stop`), so nothing it started keeps
+/// running.
+async fn collect_output(
+    mut child: tokio::process::Child,
+    opts: &SandboxExecOptions,
+) -> std::result::Result<SandboxExecResult, CollectError> {
+    let mut group = ProcessGroupGuard(child.id());
+    let limit = opts.max_output_bytes.unwrap_or(usize::MAX);
+    let mut stdout_pipe = child.stdout.take();
+    let mut stderr_pipe = child.stderr.take();
+    let (mut stdout, mut stderr) = (Vec::new(), Vec::new());
+    let (mut stdout_buf, mut stderr_buf) = ([0u8; 8192], [0u8; 8192]);
+    let mut truncated = false;
+    let deadline = tokio::time::sleep(opts.timeout);
+    tokio::pin!(deadline);
+
+    while !truncated && (stdout_pipe.is_some() || stderr_pipe.is_some()) {
+        let remaining = limit.saturating_sub(stdout.len() + stderr.len());
+        tokio::select! {
+            read = read_pipe(&mut stdout_pipe, &mut stdout_buf), if stdout_pipe.is_some() => {
+                match read {
+                    Ok(0) | Err(_) => stdout_pipe = None,
+                    Ok(n) => {
+                        stdout.extend_from_slice(&stdout_buf[..n.min(remaining)]);
+                        truncated = n > remaining;
+                    }
+                }
+            }
+            read = read_pipe(&mut stderr_pipe, &mut stderr_buf), if stderr_pipe.is_some() => {
+                match read {
+                    Ok(0) | Err(_) => stderr_pipe = None,
+                    Ok(n) => {
+                        stderr.extend_from_slice(&stderr_buf[..n.min(remaining)]);
+                        truncated = n > remaining;
+                    }
+                }
+            }
+            _ = &mut deadline => {
+                kill_process_group(&mut child).await;
+                group.disarm();
+                return Err(CollectError::TimedOut);
+            }
+        }
+    }
+    if truncated {
+        kill_process_group(&mut child).await;
+    }
+    let status = tokio::select! {
+        status = child.wait() => status.map_err(CollectError::Io)?,
+        _ = &mut deadline => {
+            kill_process_group(&mut child).await;
+            group.disarm();
+            return Err(CollectError::TimedOut);
+        }
+    };
+    group.disarm();
+    Ok(SandboxExecResult {
+        stdout: String::from_utf8_lossy(&stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&stderr).into_owned(),
+        exit_code: status.code().unwrap_or(-1),
+        truncated,
+    })
+}
+
+/// Read from a pipe that may already be closed. `select!` builds every
+/// branch's future even when its guard is false, so a closed pipe must
+/// yield a future that never resolves rather than panic.
+async fn read_pipe<R: tokio::io::AsyncRead + Unpin>(
+    pipe: &mut Option<R>,
+    buf: &mut [u8],
+) -> std::io::Result<usize> {
+    use tokio::io::AsyncReadExt;
+
+    match pipe {
+        Some(pipe) => pipe.read(buf).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Kills the process group led by a child that is abandoned before it is
+/// reaped. `kill_on_drop` only reaches the child itself.
+struct ProcessGroupGuard(Option<u32>);
+
+impl ProcessGroupGuard {
+    fn disarm(&mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for ProcessGroupGuard {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Some(pid) = self.0 {
+            // SAFETY: see `kill_process_group`.
+            unsafe {
+                libc::kill(-(pid as i32), libc::SIGKILL);
+            }
+        }
+    }
+}
+
+/// Kill the child and, on unix, every process in its group (the child was
+/// started as a group leader).
+async fn kill_process_group(child: &mut tokio::process::Child) {
+    #[cfg(unix)]
+    if let Some(pid) = child.id() {
+        // SAFETY: kill(2) with a negative pid only sends a signal to that
+        // process group; it touches no memory.
+        unsafe {
+            libc::kill(-(pid as i32), libc::SIGKILL);
+        }
     }
+    let _ = child.kill().await;
 }
 
 fn runtime_available(cli: &str) -> bool {
@@ -697,6 +931,8 @@ mod tests {
             working_dir: None,
             envs: HashMap::new(),
             env_files: Vec::new(),
+            clear_env: false,
+            max_output_bytes: None,
         };
         let out = router.exec("chat-1", "printf microclaw-smoke", &opts).await;
         let out = out.expect("expected host fallback execution");
@@ -704,6 +940,103 @@ mod tests {
         assert_eq!(out.stdout, "microclaw-smoke");
     }
 
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_kill_script_stops_only_tagged_processes() {
+        let exec_id = next_exec_id();
+        let spawn_sleep = |tag: &str| {
+            std::process::Command::new("sh")
+                .args(["-c", "sleep 30"])
+                .env(EXEC_ID_ENV, tag)
+                .spawn()
+                .unwrap()
+        };
+        let mut tagged = spawn_sleep(&exec_id);
+        let mut other = spawn_sleep("someone-else");
+        std::thread::sleep(Duration::from_millis(100));
+
+        let status = std::process::Command::new("sh")
+            .args(["-c", &kill_tagged_processes_script(&exec_id)])
+            .status()
+            .unwrap();
+        assert!(status.success());
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while tagged.try_wait().unwrap().is_none() {
+            assert!(
+                std::time::Instant::now() < deadline,
+                "tagged process survived"
+            );
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        assert!(other.try_wait().unwrap().is_none());
+        let _ = other.kill();
+        let _ = other.wait();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_host_exec_caps_output_and_kills_command() {
+        let opts = SandboxExecOptions {
+            timeout: Duration::from_secs(10),
+            working_dir: None,
+            envs: HashMap::new(),
+            env_files: Vec::new(),
+            clear_env: false,
+            max_output_bytes: Some(1000),
+        };
+        let out = exec_host_command("yes", &opts).await.unwrap();
+        assert!(out.truncated);
+        assert_eq!(out.stdout.len() + out.stderr.len(), 1000);
+
+        let out = exec_host_command("printf small", &opts).await.unwrap();
+        assert!(!out.truncated);
+        assert_eq!((out.stdout.as_str(), out.exit_code), ("small", 0));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_host_exec_timeout_kills_process_group() {
+        let dir = std::env::temp_dir().join(format!("mc_sbx_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let opts = SandboxExecOptions {
+            timeout: Duration::from_secs(1),
+            working_dir: Some(dir.clone()),
+            envs: HashMap::new(),
+            env_files: Vec::new(),
+            clear_env: false,
+            max_output_bytes: None,
+        };
+        let err = exec_host_command("(sleep 2; touch orphan) & sleep 30", &opts)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        assert!(!dir.join("orphan").exists(), "background child survived");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_dropped_host_exec_kills_process_group() {
+        let dir = std::env::temp_dir().join(format!("mc_sbx_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let opts = SandboxExecOptions {
+            timeout: Duration::from_secs(30),
+            working_dir: Some(dir.clone()),
+            envs: HashMap::new(),
+            env_files: Vec::new(),
+            clear_env: false,
+            max_output_bytes: None,
+        };
+        let run = exec_host_command("(sleep 2; touch orphan) & sleep 30", &opts);
+        assert!(tokio::time::timeout(Duration::from_secs(1), run)
+            .await
+            .is_err());
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        assert!(!dir.join("orphan").exists(), "background child survived");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[tokio::test]
     async fn test_router_fails_closed_when_runtime_required_and_missing() {
         let cfg = SandboxConfig {
@@ -718,6 +1051,8 @@ mod tests {
             working_dir: None,
             envs: HashMap::new(),
             env_files: Vec::new(),
+            clear_env: false,
+            max_output_bytes: None,
         };
         let err = router.exec("chat-1", "echo hi", &opts).await.unwrap_err();
         assert!(err
@@ -98,6 +98,19 @@ pub struct WebFetchUrlValidationConfig {
     pub feed_sync: WebFetchFeedSyncConfig,
 }
 
+/// Retry policy for transient `web_fetch` failures (connection errors and
+/// 502/503/504). Client errors are never retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct WebFetchRetryConfig {
+    #[serde(default = "default_retry_max_retries")]
+    pub max_retries: u32,
+    #[serde(default = "default_retry_base_delay_ms")]
+    pub base_delay_ms: u64,
+}
+
+const MAX_RETRIES_CAP: u32 = 5;
+const MAX_RETRY_DELAY_MS: u64 = 10_000;
+
 struct FeedCacheEntry {
     fetched_at: Instant,
     entries: Vec<String>,
@@ -131,6 +144,14 @@ const fn default_feed_max_entries_per_source() -> usize {
     10_000
 }
 
+const fn default_retry_max_retries() -> u32 {
+    2
+}
+
+const fn default_retry_base_delay_ms() -> u64 {
+    500
+}
+
 fn default_allowed_schemes() -> Vec<String> {
     vec!["https".to_string(), "http".to_string()]
 }
@@ -166,6 +187,38 @@ impl WebFetchFeedSyncConfig {
     }
 }
 
+impl Default for WebFetchRetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: default_retry_max_retries(),
+            base_delay_ms: default_retry_base_delay_ms(),
+        }
+    }
+}
+
+impl WebFetchRetryConfig {
+    pub fn normalize(&mut self) {
+        self.max_retries = self.max_retries.min(MAX_RETRIES_CAP);
+        self.base_delay_ms = self.base_delay_ms.min(MAX_RETRY_DELAY_MS);
+    }
+
+    /// Backoff before retry number `attempt` (1-based): doubles each time,
+    /// capped at 10s.
+    pub fn delay_for_attempt(&self, attempt: u32) -> Duration {
+        let factor = 1u64 << attempt.saturating_sub(1).min(16);
+        Duration::from_millis(
+            self.base_delay_ms
+                .saturating_mul(factor)
+                .min(MAX_RETRY_DELAY_MS),
+        )
+    }
+}
+
+/// Gateway-style server errors that are usually transient.
+pub fn is_retryable_status(status: u16) -> bool {
+    matches!(status, 502..=504)
+}
+
 impl Default for WebFetchUrlValidationConfig {
     fn default() -> Self {
         Self {
@@ -455,29 +508,215 @@ pub async fn fetch_url_with_timeout(url: &str, timeout_secs: u64) -> Result<Stri
         timeout_secs,
         WebContentValidationConfig::default(),
         WebFetchUrlValidationConfig::default(),
+        WebFetchRetryConfig::default(),
     )
     .await
 }
 
+/// Send a GET, retrying connection errors and 502/503/504 with backoff.
+async fn get_with_retry(
+    client: &reqwest::Client,
+    url: &Url,
+    retry: WebFetchRetryConfig,
+) -> Result<reqwest::Response, String> {
+    let mut attempt = 0u32;
+    loop {
+        let result = client.get(url.clone()).send().await;
+        let retryable = match &result {
+            Ok(resp) => is_retryable_status(resp.status().as_u16()),
+            Err(e) => e.is_connect(),
+        };
+        if !retryable || attempt >= retry.max_retries {
+            return result.map_err(|e| e.to_string());
+        }
+        attempt += 1;
+        let delay = retry.delay_for_attempt(attempt);
+        match &result {
+            Ok(resp) => warn!(
+                "web_fetch got HTTP {} from {url}; retrying in {}ms ({attempt}/{})",
+                resp.status(),
+                delay.as_millis(),
+                retry.max_retries
+            ),
+            Err(e) => warn!(
+                "web_fetch failed to connect to {url}: {e}; retrying in {}ms ({attempt}/{})",
+                delay.as_millis(),
+                retry.max_retries
+            ),
+        }
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// Turn a response body into the text handed to the model, based on its
+/// `Content-Type`: HTML (or an unlabeled body) goes through the HTML-to-text
+/// pipeline, JSON is pretty-printed, and anything else (plain text, markdown,
+/// XML, CSV) is returned unchanged. `raw` skips extraction for every type.
+pub fn extract_body_text(body: &str, content_type: Option<&str>, raw: bool) -> String {
+    if raw {
+        return body.to_string();
+    }
+    let mime = content_type
+        .and_then(|ct| ct.split(';').next())
+        .map(|ct| ct.trim().to_ascii_lowercase())
+        .unwrap_or_default();
+    if mime.is_empty() || mime.contains("html") {
+        return html_to_text(extract_primary_html(body));
+    }
+    if mime == "application/json" || mime.ends_with("+json") {
+        return serde_json::from_str::<serde_json::Value>(body)
+            .ok()
+            .and_then(|v| serde_json::to_string_pretty(&v).ok())
+            .unwrap_or_else(|| body.to_string());
+    }
+    body.to_string()
+}
+
 pub async fn fetch_url_with_timeout_and_validation(
     url: &str,
     timeout_secs: u64,
     validation: WebContentValidationConfig,
     url_validation: WebFetchUrlValidationConfig,
+    retry: WebFetchRetryConfig,
+) -> Result<String, String> {
+    fetch_url_with_options(
+        url,
+        timeout_secs,
+        validation,
+        url_validation,
+        retry,
+        WebFetchOptions::default(),
+    )
+    .await
+}
+
+/// Per-call `web_fetch` options.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WebFetchOptions {
+    /// Return the body without any content extraction.
+    pub raw: bool,
+    /// Byte offset into the extracted text to start the returned page at.
+    pub offset: usize,
+}
+
+const MAX_PAGE_BYTES: usize = 20_000;
+const PAGE_CACHE_TTL: Duration = Duration::from_secs(300);
+const PAGE_CACHE_MAX_ENTRIES: usize = 32;
+
+struct PageCacheEntry {
+    fetched_at: Instant,
+    text: std::sync::Arc<String>,
+}
+
+/// Extracted text of recently fetched URLs, so paging through a long
+/// document with `offset` does not download and extract it again.
+fn page_cache() -> &'static Mutex<HashMap<(String, bool), PageCacheEntry>> {
+    static CACHE: OnceLock<Mutex<HashMap<(String, bool), PageCacheEntry>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn cached_page_text(url: &str, raw: bool) -> Option<std::sync::Arc<String>> {
+    let mut cache = page_cache().lock().unwrap_or_else(|e| e.into_inner());
+    cache.retain(|_, entry| entry.fetched_at.elapsed() < PAGE_CACHE_TTL);
+    cache
+        .get(&(url.to_string(), raw))
+        .map(|entry| entry.text.clone())
+}
+
+fn cache_page_text(url: &str, raw: bool, text: std::sync::Arc<String>) {
+    let mut cache = page_cache().lock().unwrap_or_else(|e| e.into_inner());
+    if cache.len() >= PAGE_CACHE_MAX_ENTRIES {
+        if let Some(oldest) = cache
+            .iter()
+            .min_by_key(|(_, entry)| entry.fetched_at)
+            .map(|(key, _)| key.clone())
+        {
+            cache.remove(&oldest);
+        }
+    }
+    cache.insert(
+        (url.to_string(), raw),
+        PageCacheEntry {
+            fetched_at: Instant::now(),
+            text,
+        },
+    );
+}
+
+/// The 20KB page of `text` starting at byte `offset`, with a hint for the
+/// next offset when more follows.
+pub fn page_text(text: &str, offset: usize) -> Result<String, String> {
+    let total = text.len();
+    if offset > 0 && offset >= total {
+        return Err(format!(
+            "offset {offset} is past the end of the document ({total} bytes)"
+        ));
+    }
+    let start = floor_char_boundary(text, offset);
+    let end = floor_char_boundary(text, start + MAX_PAGE_BYTES);
+    let page = &text[start..end];
+    if end < total {
+        Ok(format!(
+            "{page}\n\n[Truncated at 20KB: bytes {start}-{end} of {total}. Call web_fetch again with offset={end} to continue]"
+        ))
+    } else if start > 0 {
+        Ok(format!(
+            "{page}\n\n[End of document: bytes {start}-{end} of {total}]"
+        ))
+    } else {
+        Ok(page.to_string())
+    }
+}
+
+/// Like [`fetch_url_with_timeout_and_validation`], with [`WebFetchOptions`].
+/// A non-zero `offset` reuses the text extracted by a recent fetch of the
+/// same URL when there is one.
+pub async fn fetch_url_with_options(
+    url: &str,
+    timeout_secs: u64,
+    validation: WebContentValidationConfig,
+    url_validation: WebFetchUrlValidationConfig,
+    retry: WebFetchRetryConfig,
+    options: WebFetchOptions,
 ) -> Result<String, String> {
     let effective_url_validation = resolve_url_validation_config(url_validation).await?;
     validate_web_fetch_url(url, effective_url_validation.clone())?;
 
+    if options.offset > 0 {
+        if let Some(text) = cached_page_text(url, options.raw) {
+            return page_text(&text, options.offset);
+        }
+    }
+    let text = fetch_extracted_text(
+        url,
+        timeout_secs,
+        validation,
+        &effective_url_validation,
+        retry,
+        options.raw,
+    )
+    .await?;
+    let text = std::sync::Arc::new(text);
+    cache_page_text(url, options.raw, text.clone());
+    page_text(&text, options.offset)
+}
+
+/// Download `url`, following validated redirects, and return its extracted
+/// text after content validation.
+async fn fetch_extracted_text(
+    url: &str,
+    timeout_secs: u64,
+    validation: WebContentValidationConfig,
+    effective_url_validation: &WebFetchUrlValidationConfig,
+    retry: WebFetchRetryConfig,
+    raw: bool,
+) -> Result<String, String> {
     let client = http_client_no_redirect(timeout_secs.max(1));
     let mut current_url = Url::parse(url).map_err(|e| format!("invalid URL: {e}"))?;
     let mut redirects = 0usize;
 
     let resp = loop {
-        let resp = client
-            .get(current_url.clone())
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
+        let resp = get_with_retry(&client, &current_url, retry).await?;
 
         if !resp.status().is_redirection() {
             break resp;
@@ -494,20 +733,21 @@ pub async fn fetch_url_with_timeout_and_validation(
             .ok_or_else(|| "redirect response missing Location header".to_string())?
             .to_str()
             .map_err(|e| format!("invalid redirect Location header: {e}"))?;
-        current_url = resolve_and_validate_redirect_target(
-            &current_url,
-            location,
-            &effective_url_validation,
-        )?;
+        current_url =
+            resolve_and_validate_redirect_target(&current_url, location, effective_url_validation)?;
     };
 
     if !resp.status().is_success() {
         return Err(format!("HTTP {}", resp.status()));
     }
 
+    let content_type = resp
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
     let body = resp.text().await.map_err(|e| e.to_string())?;
-    let primary = extract_primary_html(&body);
-    let text = html_to_text(primary);
+    let text = extract_body_text(&body, content_type.as_deref(), raw);
 
     if let Err(failure) = validate_web_content_with_config(&text, validation) {
         warn!(
@@ -516,14 +756,7 @@ pub async fn fetch_url_with_timeout_and_validation(
         );
         return Err(failure.message());
     }
-
-    const MAX_BYTES: usize = 20_000;
-    if text.len() > MAX_BYTES {
-        let truncated = &text[..floor_char_boundary(&text, MAX_BYTES)];
-        Ok(format!("{truncated}\n\n[Truncated at 20KB]"))
-    } else {
-        Ok(text)
-    }
+    Ok(text)
 }
 
 pub async fn fetch_url(url: &str) -> Result<String, String> {
@@ -541,9 +774,11 @@ mod tests {
     use tokio::time::{timeout, Duration};
 
     use super::{
-        fetch_url_with_timeout_and_validation, resolve_and_validate_redirect_target,
+        extract_body_text, fetch_url_with_options, fetch_url_with_timeout_and_validation,
+        is_retryable_status, page_text, resolve_and_validate_redirect_target,
         resolve_url_validation_config, validate_web_fetch_url, WebFetchFeedFormat,
-        WebFetchFeedMode, WebFetchFeedSource, WebFetchFeedSyncConfig, WebFetchUrlValidationConfig,
+        WebFetchFeedMode, WebFetchFeedSource, WebFetchFeedSyncConfig, WebFetchOptions,
+        WebFetchRetryConfig, WebFetchUrlValidationConfig,
     };
     use crate::web_content_validation::WebContentValidationConfig;
 
@@ -838,6 +1073,7 @@ mod tests {
             5,
             WebContentValidationConfig::default(),
             url_cfg,
+            WebFetchRetryConfig::default(),
         )
         .await
         .unwrap_err();
@@ -851,4 +1087,209 @@ mod tests {
             "should not request redirect target after URL policy rejection"
         );
     }
+
+    #[test]
+    fn retry_classification_by_status() {
+        for status in [502u16, 503, 504] {
+            assert!(is_retryable_status(status), "{status} should retry");
+        }
+        for status in [200u16, 301, 400, 401, 403, 404, 429, 500, 501, 505] {
+            assert!(!is_retryable_status(status), "{status} should not retry");
+        }
+    }
+
+    #[test]
+    fn retry_config_backoff_is_bounded() {
+        let mut cfg = WebFetchRetryConfig {
+            max_retries: 100,
+            base_delay_ms: 400,
+        };
+        cfg.normalize();
+        assert_eq!(cfg.max_retries, 5);
+        assert_eq!(cfg.delay_for_attempt(1), Duration::from_millis(400));
+        assert_eq!(cfg.delay_for_attempt(2), Duration::from_millis(800));
+        assert_eq!(cfg.delay_for_attempt(30), Duration::from_millis(10_000));
+    }
+
+    async fn serve_statuses(statuses: Vec<u16>) -> (String, tokio::task::JoinHandle<usize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let mut served = 0usize;
+            for status in statuses {
+                let Ok(Ok((mut stream, _))) =
+                    timeout(Duration::from_secs(1), listener.accept()).await
+                else {
+                    break;
+                };
+                let mut buf = [0u8; 1024];
+                let _ = stream.read(&mut buf).await;
+                let body = "<html><body><p>hello retry</p></body></html>";
+                let response = format!(
+                    "HTTP/1.1 {status} X\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+                served += 1;
+            }
+            served
+        });
+        (format!("http://127.0.0.1:{}/page", addr.port()), server)
+    }
+
+    fn local_url_cfg() -> WebFetchUrlValidationConfig {
+        WebFetchUrlValidationConfig {
+            allowlist_hosts: vec!["127.0.0.1".to_string()],
+            ..WebFetchUrlValidationConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn fetch_retries_gateway_errors_then_succeeds() {
+        let (url, server) = serve_statuses(vec![503, 502, 200]).await;
+        let text = fetch_url_with_timeout_and_validation(
+            &url,
+            5,
+            WebContentValidationConfig::default(),
+            local_url_cfg(),
+            WebFetchRetryConfig {
+                max_retries: 2,
+                base_delay_ms: 1,
+            },
+        )
+        .await
+        .unwrap();
+        assert!(text.contains("hello retry"));
+        assert_eq!(server.await.unwrap(), 3);
+    }
+
+    async fn serve_body(content_type: &'static str, body: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok(Ok((mut stream, _))) =
+                timeout(Duration::from_secs(1), listener.accept()).await
+            {
+                let mut buf = [0u8; 1024];
+                let _ = stream.read(&mut buf).await;
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        format!("http://127.0.0.1:{}/doc", addr.port())
+    }
+
+    async fn fetch_local(url: &str, raw: bool) -> String {
+        fetch_url_with_options(
+            url,
+            5,
+            WebContentValidationConfig::default(),
+            local_url_cfg(),
+            WebFetchRetryConfig::default(),
+            WebFetchOptions { raw, offset: 0 },
+        )
+        .await
+        .unwrap()
+    }
+
+    #[test]
+    fn page_text_slices_with_next_offset_hint() {
+        let text = "é".repeat(15_000); // 30,000 bytes
+        let first = page_text(&text, 0).unwrap();
+        assert!(first.contains("bytes 0-20000 of 30000"));
+        assert!(first.contains("offset=20000"));
+        let second = page_text(&text, 20_000).unwrap();
+        assert!(second.starts_with("é"));
+        assert!(second.contains("[End of document: bytes 20000-30000 of 30000]"));
+        // Offsets inside a character snap back to its start.
+        assert!(page_text(&text, 20_001).unwrap().starts_with("é"));
+        assert!(page_text(&text, 30_000)
+            .unwrap_err()
+            .contains("past the end"));
+        assert_eq!(page_text("short", 0).unwrap(), "short");
+    }
+
+    #[tokio::test]
+    async fn fetch_with_offset_reuses_cached_text() {
+        let (url, server) = serve_statuses(vec![200]).await;
+        let options = |offset| WebFetchOptions { raw: false, offset };
+        let fetch = |offset| {
+            fetch_url_with_options(
+                &url,
+                5,
+                WebContentValidationConfig::default(),
+                local_url_cfg(),
+                WebFetchRetryConfig::default(),
+                options(offset),
+            )
+        };
+        let full = fetch(0).await.unwrap();
+        assert!(full.contains("hello retry"));
+        let rest = fetch(6).await.unwrap();
+        assert!(rest.starts_with("retry"), "{rest}");
+        assert!(rest.contains("End of document"));
+        assert_eq!(server.await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn fetch_passes_json_and_plain_text_through() {
+        let json_body = r#"{"items":[{"id":1,"tags":["<b>a</b>"]}],"next":null}"#;
+        let url = serve_body("application/json; charset=utf-8", json_body).await;
+        let text = fetch_local(&url, false).await;
+        assert!(text.contains('\n'), "JSON should be pretty-printed: {text}");
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&text).unwrap(),
+            serde_json::from_str::<serde_json::Value>(json_body).unwrap()
+        );
+
+        let plain_body = "# Notes\n\n<not a tag> keep   spacing\n- item";
+        let url = serve_body("text/markdown", plain_body).await;
+        assert_eq!(fetch_local(&url, false).await, plain_body);
+        let url = serve_body("text/plain", plain_body).await;
+        assert_eq!(fetch_local(&url, false).await, plain_body);
+    }
+
+    #[tokio::test]
+    async fn fetch_raw_skips_html_extraction() {
+        let html = "<html><head><script>x()</script></head><body><p>hi</p></body></html>";
+        let url = serve_body("text/html", html).await;
+        let text = fetch_local(&url, false).await;
+        assert!(text.contains("hi") && !text.contains("<p>"));
+        assert_eq!(fetch_local(&url, true).await, html);
+    }
+
+    #[test]
+    fn extract_body_text_falls_back_for_invalid_json_and_unlabeled_html() {
+        assert_eq!(
+            extract_body_text("{not json", Some("application/json"), false),
+            "{not json"
+        );
+        assert_eq!(
+            extract_body_text("<x/>", Some("application/xml"), false),
+            "<x/>"
+        );
+        assert!(!extract_body_text("<p>hello</p>", None, false).contains("<p>"));
+    }
+
+    #[tokio::test]
+    async fn fetch_does_not_retry_client_errors() {
+        let (url, server) = serve_statuses(vec![404, 200]).await;
+        let err = fetch_url_with_timeout_and_validation(
+            &url,
+            5,
+            WebContentValidationConfig::default(),
+            local_url_cfg(),
+            WebFetchRetryConfig {
+                max_retries: 2,
+                base_delay_ms: 1,
+            },
+        )
+        .await
+        .unwrap_err();
+        assert!(err.contains("404"));
+        assert_eq!(server.await.unwrap(), 1);
+    }
 }
@@ -37,7 +37,8 @@ function parseBuiltinTools() {
   const names = new Set();
   const re = /fn\s+name\s*\(\s*&self\s*\)\s*->\s*&str\s*\{\s*"([^"]+)"\s*\}/g;
   for (const file of files) {
-    const text = fs.readFileSync(file, 'utf8');
+    // Tools defined in a file's test module are not built in.
+    const text = fs.readFileSync(file, 'utf8').split(/#\[cfg\(test\)\]\s*mod\s+tests\b/)[0];
     let m;
     while ((m = re.exec(text)) !== null) {
       names.add(m[1]);
@@ -135,7 +136,12 @@ function parseProviderPresets() {
     rows.push({
       id,
       label,
-      protocol: protocolRaw === 'Anthropic' ? 'native_anthropic' : 'openai_compatible',
+      protocol:
+        protocolRaw === 'Anthropic'
+          ? 'native_anthropic'
+          : protocolRaw === 'Gemini'
+            ? 'native_gemini'
+            : 'openai_compatible',
       defaultBaseUrl: base || '(provider default)',
       defaultModel: firstModel || '(none)',
     });
@@ -0,0 +1,232 @@
+use crate::channels::{dingtalk, email, feishu, irc, matrix, nostr, qq, signal, slack, whatsapp};
+use crate::config::Config;
+
+/// Channels in startup order. When `max_active_channels` is set, channels
+/// earlier in this list win.
+pub const CHANNEL_ORDER: &[&str] = &[
+    "telegram", "discord", "slack", "feishu", "matrix", "whatsapp", "imessage", "email", "nostr",
+    "signal", "dingtalk", "qq", "irc", "web",
+];
+
+/// An enabled channel that is missing settings its adapter needs to start.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IncompleteChannel {
+    pub channel: String,
+    pub missing: Vec<&'static str>,
+    /// True when the channel was turned on with `enabled: true` rather than
+    /// inferred from the presence of its config section.
+    pub explicit: bool,
+}
+
+impl std::fmt::Display for IncompleteChannel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (missing {})", self.channel, self.missing.join(", "))
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ChannelSelection {
+    pub active: Vec<String>,
+    pub incomplete: Vec<IncompleteChannel>,
+    /// Ready channels left out because of `max_active_channels`.
+    pub over_limit: Vec<String>,
+}
+
+impl ChannelSelection {
+    pub fn is_active(&self, channel: &str) -> bool {
+        self.active.iter().any(|c| c == channel)
+    }
+}
+
+/// Keys a channel needs before its adapter can start.
+fn required_keys(channel: &str) -> &'static [&'static str] {
+    match channel {
+        "telegram" | "discord" => &["bot_token"],
+        "slack" => slack::SETUP_DEF.presence_keys,
+        "feishu" => feishu::SETUP_DEF.presence_keys,
+        "irc" => irc::SETUP_DEF.presence_keys,
+        "matrix" => matrix::SETUP_DEF.presence_keys,
+        "whatsapp" => whatsapp::SETUP_DEF.presence_keys,
+        // `service` falls back to a default, so iMessage needs nothing else.
+        "imessage" => &[],
+        "email" => email::SETUP_DEF.presence_keys,
+        "nostr" => nostr::SETUP_DEF.presence_keys,
+        "signal" => signal::SETUP_DEF.presence_keys,
+        "dingtalk" => dingtalk::SETUP_DEF.presence_keys,
+        "qq" => qq::SETUP_DEF.presence_keys,
+        _ => &[],
+    }
+}
+
+fn value_is_set(value: Option<&serde_yaml::Value>) -> bool {
+    match value {
+        None | Some(serde_yaml::Value::Null) => false,
+        Some(serde_yaml::Value::String(s)) => !s.trim().is_empty(),
+        Some(serde_yaml::Value::Sequence(items)) => !items.is_empty(),
+        Some(serde_yaml::Value::Mapping(map)) => !map.is_empty(),
+        Some(_) => true,
+    }
+}
+
+fn legacy_key_is_set(config: &Config, channel: &str, key: &str) -> bool {
+    match (channel, key) {
+        ("telegram", "bot_token") => !config.telegram_bot_token.trim().is_empty(),
+        ("discord", "bot_token") => config
+            .discord_bot_token
+            .as_deref()
+            .is_some_and(|v| !v.trim().is_empty()),
+        _ => false,
+    }
+}
+
+/// Required keys that are set neither on the channel itself nor on any one
+/// enabled account. Empty means the channel is ready to start.
+pub fn channel_missing_keys(config: &Config, channel: &str) -> Vec<&'static str> {
+    let keys = required_keys(channel);
+    let channel_cfg = config.channels.get(channel);
+    let top_level_has = |key: &str| {
+        value_is_set(channel_cfg.and_then(|v| v.get(key)))
+            || legacy_key_is_set(config, channel, key)
+    };
+    let top_missing: Vec<&'static str> = keys
+        .iter()
+        .copied()
+        .filter(|key| !top_level_has(key))
+        .collect();
+    if top_missing.is_empty() {
+        return top_missing;
+    }
+
+    let accounts = channel_cfg
+        .and_then(|v| v.get("accounts"))
+        .and_then(|v| v.as_mapping());
+    let mut best = top_missing;
+    for account in accounts.into_iter().flat_map(|m| m.values()) {
+        if account.get("enabled").and_then(|v| v.as_bool()) == Some(false) {
+            continue;
+        }
+        let missing: Vec<&'static str> = keys
+            .iter()
+            .copied()
+            .filter(|key| !top_level_has(key) && !value_is_set(account.get(*key)))
+            .collect();
+        if missing.len() < best.len() {
+            best = missing;
+        }
+    }
+    best
+}
+
+/// Decide which enabled channels start. Channels missing required settings
+/// are reported instead of started, and at most `max_active_channels` ready
+/// channels are kept (0 means no cap).
+pub fn select_channels(config: &Config) -> ChannelSelection {
+    let mut selection = ChannelSelection::default();
+    for channel in CHANNEL_ORDER {
+        if !config.channel_enabled(channel) {
+            continue;
+        }
+        let missing = channel_missing_keys(config, channel);
+        if !missing.is_empty() {
+            let explicit = config
+                .channels
+                .get(*channel)
+                .and_then(|v| v.get("enabled"))
+                .and_then(|v| v.as_bool())
+                == Some(true);
+            selection.incomplete.push(IncompleteChannel {
+                channel: channel.to_string(),
+                missing,
+                explicit,
+            });
+            continue;
+        }
+        if config.max_active_channels > 0 && selection.active.len() >= config.max_active_channels {
+            selection.over_limit.push(channel.to_string());
+            continue;
+        }
+        selection.active.push(channel.to_string());
+    }
+    selection
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_with_channels(yaml: &str) -> Config {
+        let mut config = Config::test_defaults();
+        config.telegram_bot_token = String::new();
+        config.channels = serde_yaml::from_str(yaml).unwrap();
+        config
+    }
+
+    #[test]
+    fn test_ready_channel_has_no_missing_keys() {
+        let config = config_with_channels(
+            "whatsapp:\n  enabled: true\n  access_token: tok\n  phone_number_id: '123'\n",
+        );
+        assert!(channel_missing_keys(&config, "whatsapp").is_empty());
+    }
+
+    #[test]
+    fn test_partial_channel_reports_missing_keys() {
+        let config = config_with_channels("whatsapp:\n  enabled: true\n  access_token: tok\n");
+        assert_eq!(
+            channel_missing_keys(&config, "whatsapp"),
+            vec!["phone_number_id"]
+        );
+        let selection = select_channels(&config);
+        assert!(!selection.is_active("whatsapp"));
+        assert_eq!(selection.incomplete.len(), 1);
+        assert!(selection.incomplete[0].explicit);
+        assert_eq!(
+            selection.incomplete[0].to_string(),
+            "whatsapp (missing phone_number_id)"
+        );
+    }
+
+    #[test]
+    fn test_account_level_keys_satisfy_readiness() {
+        let config = config_with_channels(
+            "slack:\n  accounts:\n    main:\n      bot_token: xoxb\n      app_token: xapp\n    off:\n      enabled: false\n",
+        );
+        assert!(channel_missing_keys(&config, "slack").is_empty());
+
+        let disabled_only = config_with_channels(
+            "slack:\n  accounts:\n    off:\n      enabled: false\n      bot_token: xoxb\n      app_token: xapp\n",
+        );
+        assert_eq!(
+            channel_missing_keys(&disabled_only, "slack"),
+            vec!["bot_token", "app_token"]
+        );
+    }
+
+    #[test]
+    fn test_legacy_telegram_token_counts_as_configured() {
+        let mut config = config_with_channels("telegram:\n  enabled: true\n");
+        assert_eq!(channel_missing_keys(&config, "telegram"), vec!["bot_token"]);
+        config.telegram_bot_token = "tok".into();
+        assert!(channel_missing_keys(&config, "telegram").is_empty());
+    }
+
+    #[test]
+    fn test_web_needs_no_keys_and_irc_needs_channels() {
+        let config = config_with_channels(
+            "web:\n  enabled: true\nirc:\n  enabled: true\n  server: irc.example\n  nick: bot\n  channels: ''\n",
+        );
+        assert!(channel_missing_keys(&config, "web").is_empty());
+        assert_eq!(channel_missing_keys(&config, "irc"), vec!["channels"]);
+    }
+
+    #[test]
+    fn test_max_active_channels_caps_in_startup_order() {
+        let mut config = config_with_channels(
+            "web:\n  enabled: true\ntelegram:\n  bot_token: tok\nsignal:\n  send_command: 'true'\n",
+        );
+        config.max_active_channels = 2;
+        let selection = select_channels(&config);
+        assert_eq!(selection.active, vec!["telegram", "signal"]);
+        assert_eq!(selection.over_limit, vec!["web"]);
+    }
+}
@@ -5,10 +5,16 @@ use std::sync::Arc;
 
 use serde::Deserialize;
 use serde_json::json;
+use serenity::all::{
+    Attachment, AutoArchiveDuration, Channel, ChannelType, Command, CommandInteraction,
+    CommandOptionType, CreateCommand, CreateCommandOption, CreateThread, EditInteractionResponse,
+    GuildChannel, Interaction,
+};
 use serenity::async_trait;
 use serenity::model::channel::Message as DiscordMessage;
+use serenity::model::event::MessageUpdateEvent;
 use serenity::model::gateway::Ready;
-use serenity::model::id::ChannelId;
+use serenity::model::id::{ChannelId, GuildId};
 use serenity::prelude::*;
 use tracing::{error, info, warn};
 
@@ -16,8 +22,10 @@ use crate::agent_engine::process_with_agent_with_events;
 use crate::agent_engine::should_suppress_user_error;
 use crate::agent_engine::AgentEvent;
 use crate::agent_engine::AgentRequestContext;
+use crate::channels::message_edits::{handle_message_edit, MessageEdit};
 use crate::channels::startup_guard::{
-    mark_channel_started, should_drop_pre_start_message, should_drop_recent_duplicate_message,
+    claim_inbound_message, mark_channel_connected, should_drop_pre_start_message,
+    should_drop_recent_duplicate_message,
 };
 use crate::chat_commands::maybe_handle_plugin_command;
 use crate::chat_commands::{handle_chat_command, is_slash_command, unknown_command_response};
@@ -36,6 +44,8 @@ pub struct DiscordAccountConfig {
     #[serde(default)]
     pub no_mention: bool,
     #[serde(default)]
+    pub use_threads: bool,
+    #[serde(default)]
     pub bot_username: String,
     #[serde(default)]
     pub model: Option<String>,
@@ -56,6 +66,8 @@ pub struct DiscordChannelConfig {
     #[serde(default)]
     pub no_mention: bool,
     #[serde(default)]
+    pub use_threads: bool,
+    #[serde(default)]
     pub model: Option<String>,
     #[serde(default)]
     pub accounts: HashMap<String, DiscordAccountConfig>,
@@ -129,6 +141,7 @@ pub fn build_discord_runtime_contexts(
                 channel_name,
                 allowed_channels: account_cfg.allowed_channels.clone(),
                 no_mention: account_cfg.no_mention,
+                use_threads: account_cfg.use_threads,
                 bot_username,
                 model,
             },
@@ -142,6 +155,7 @@ pub fn build_discord_runtime_contexts(
                 channel_name: "discord".to_string(),
                 allowed_channels: discord_cfg.allowed_channels,
                 no_mention: discord_cfg.no_mention,
+                use_threads: discord_cfg.use_threads,
                 bot_username: config.bot_username_for_channel("discord"),
                 model: discord_cfg
                     .model
@@ -338,10 +352,141 @@ pub struct DiscordRuntimeContext {
     pub channel_name: String,
     pub allowed_channels: Vec<u64>,
     pub no_mention: bool,
+    /// Post multi-message answers in guild channels into a thread started
+    /// from the user's message.
+    pub use_threads: bool,
     pub bot_username: String,
     pub model: Option<String>,
 }
 
+/// Slash commands registered with Discord. Each one runs the text command of
+/// the same name through `handle_chat_command`.
+fn slash_command_definitions() -> Vec<CreateCommand> {
+    vec![
+        CreateCommand::new("reset").description("Clear the session and chat history"),
+        CreateCommand::new("skills").description("List available skills"),
+        CreateCommand::new("archive").description("Archive the current session to markdown"),
+        CreateCommand::new("model")
+            .description("Show or switch the model for this chat")
+            .add_option(
+                CreateCommandOption::new(CommandOptionType::String, "name", "Model to switch to")
+                    .required(false),
+            ),
+    ]
+}
+
+/// Text form of a slash command, e.g. `/model gpt-5`.
+fn slash_command_text(name: &str, args: &[&str]) -> String {
+    std::iter::once(format!("/{name}"))
+        .chain(
+            args.iter()
+                .map(|a| a.trim())
+                .filter(|a| !a.is_empty())
+                .map(str::to_string),
+        )
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The channel a thread was started in; any other channel is its own parent.
+fn thread_parent_id(channel: &GuildChannel) -> ChannelId {
+    let is_thread = matches!(
+        channel.kind,
+        ChannelType::PublicThread | ChannelType::PrivateThread | ChannelType::NewsThread
+    );
+    match channel.parent_id {
+        Some(parent) if is_thread => parent,
+        _ => channel.id,
+    }
+}
+
+/// Map a thread to its parent channel, so the allowlist and the chat history
+/// follow the channel the thread lives in. Reads the cache and asks the API
+/// only for channels it has not seen.
+async fn conversation_channel_id(
+    ctx: &Context,
+    guild_id: Option<GuildId>,
+    channel_id: ChannelId,
+) -> ChannelId {
+    let Some(guild_id) = guild_id else {
+        return channel_id;
+    };
+    let cached = ctx.cache.guild(guild_id).and_then(|guild| {
+        if guild.channels.contains_key(&channel_id) {
+            return Some(channel_id);
+        }
+        guild
+            .threads
+            .iter()
+            .find(|thread| thread.id == channel_id)
+            .map(thread_parent_id)
+    });
+    if let Some(resolved) = cached {
+        return resolved;
+    }
+    match channel_id.to_channel(ctx).await {
+        Ok(Channel::Guild(channel)) => thread_parent_id(&channel),
+        _ => channel_id,
+    }
+}
+
+impl Handler {
+    async fn resolve_chat_id(&self, external_channel_id: u64) -> i64 {
+        let external_chat_id = external_channel_id.to_string();
+        let chat_type = "discord".to_string();
+        let title = format!("discord-{external_channel_id}");
+        let channel_name = self.runtime.channel_name.clone();
+        call_blocking(self.app_state.db.clone(), move |db| {
+            db.resolve_or_create_chat_id(&channel_name, &external_chat_id, Some(&title), &chat_type)
+        })
+        .await
+        .unwrap_or(external_channel_id as i64)
+    }
+
+    async fn handle_slash_command(&self, ctx: &Context, command: &CommandInteraction) {
+        let external_channel_id =
+            conversation_channel_id(ctx, command.guild_id, command.channel_id)
+                .await
+                .get();
+        let reply = if !self.runtime.allowed_channels.is_empty()
+            && !self.runtime.allowed_channels.contains(&external_channel_id)
+        {
+            "This channel is not enabled for the bot.".to_string()
+        } else {
+            let chat_id = self.resolve_chat_id(external_channel_id).await;
+            let args: Vec<&str> = command
+                .data
+                .options
+                .iter()
+                .filter_map(|o| o.value.as_str())
+                .collect();
+            let text = slash_command_text(&command.data.name, &args);
+            let sender_id_text = command.user.id.get().to_string();
+            handle_chat_command(
+                &self.app_state,
+                chat_id,
+                &self.runtime.channel_name,
+                &text,
+                Some(&sender_id_text),
+            )
+            .await
+            .unwrap_or_else(unknown_command_response)
+        };
+        // Interaction replies share the 2000 character message limit.
+        let reply = if reply.len() > 2000 {
+            format!("{}…", &reply[..floor_char_boundary(&reply, 1990)])
+        } else {
+            reply
+        };
+        if let Err(e) = command
+            .edit_response(&ctx.http, EditInteractionResponse::new().content(reply))
+            .await
+        {
+            warn!("Failed to answer Discord slash command: {e}");
+        }
+    }
+}
+
 #[async_trait]
 impl EventHandler for Handler {
     async fn message(&self, ctx: Context, msg: DiscordMessage) {
@@ -350,24 +495,11 @@ impl EventHandler for Handler {
             return;
         }
 
-        let text = msg.content.clone();
-        let external_channel_id = msg.channel_id.get();
-        let channel_id = {
-            let external_chat_id = external_channel_id.to_string();
-            let chat_type = "discord".to_string();
-            let title = format!("discord-{external_channel_id}");
-            let channel_name = self.runtime.channel_name.clone();
-            call_blocking(self.app_state.db.clone(), move |db| {
-                db.resolve_or_create_chat_id(
-                    &channel_name,
-                    &external_chat_id,
-                    Some(&title),
-                    &chat_type,
-                )
-            })
+        let mut text = msg.content.clone();
+        let external_channel_id = conversation_channel_id(&ctx, msg.guild_id, msg.channel_id)
             .await
-            .unwrap_or(external_channel_id as i64)
-        };
+            .get();
+        let channel_id = self.resolve_chat_id(external_channel_id).await;
         let sender_name = msg.author.name.clone();
 
         // Check allowed channels (empty = all)
@@ -381,9 +513,13 @@ impl EventHandler for Handler {
             if self.runtime.no_mention {
                 true
             } else {
-                let cache = &ctx.cache;
-                let bot_id = cache.current_user().id;
+                // A reply to one of the bot's messages counts as addressing it.
+                let bot_id = ctx.cache.current_user().id;
                 msg.mentions.iter().any(|u| u.id == bot_id)
+                    || msg
+                        .referenced_message
+                        .as_ref()
+                        .is_some_and(|replied| replied.author.id == bot_id)
             }
         } else {
             true
@@ -401,9 +537,24 @@ impl EventHandler for Handler {
         if should_drop_recent_duplicate_message(&self.runtime.channel_name, &inbound_message_id) {
             return;
         }
+        let Some(_claim) = claim_inbound_message(
+            &self.app_state,
+            &self.runtime.channel_name,
+            &inbound_message_id,
+        )
+        .await
+        else {
+            return;
+        };
 
         if is_slash_command(&text) {
-            if !should_respond && !self.app_state.config.allow_group_slash_without_mention {
+            if !should_respond
+                && !self
+                    .app_state
+                    .config
+                    .load()
+                    .allow_group_slash_without_mention
+            {
                 return;
             }
             let sender_id_text = msg.author.id.get().to_string();
@@ -420,7 +571,7 @@ impl EventHandler for Handler {
                 return;
             }
             if let Some(plugin_response) = maybe_plugin_slash_response(
-                &self.app_state.config,
+                &self.app_state.config.load(),
                 &text,
                 channel_id,
                 &self.runtime.channel_name,
@@ -437,6 +588,43 @@ impl EventHandler for Handler {
             return;
         }
 
+        if text.is_empty() {
+            if let Some(voice) = voice_attachment(&msg) {
+                // Transcription is a paid call; skip voice notes the bot won't answer.
+                if !should_respond {
+                    return;
+                }
+                let config = self.app_state.config.load();
+                if !crate::voice::transcription_available(&config) {
+                    let _ = msg
+                        .channel_id
+                        .say(
+                            &ctx.http,
+                            crate::voice::transcription_unavailable_message(&config),
+                        )
+                        .await;
+                    return;
+                }
+                match voice.download().await {
+                    Ok(bytes) => {
+                        let duration_secs = voice.duration_secs.map(|d| d.ceil() as u64);
+                        text = crate::voice::voice_message_text(
+                            &config,
+                            &sender_name,
+                            &bytes,
+                            duration_secs,
+                            &crate::voice::TranscriptionOptions {
+                                mime_type: voice.content_type.clone(),
+                                ..Default::default()
+                            },
+                        )
+                        .await;
+                    }
+                    Err(e) => error!("Failed to download Discord voice message: {e}"),
+                }
+            }
+        }
+
         if text.is_empty() {
             if msg.guild_id.is_some() {
                 info!(
@@ -461,11 +649,12 @@ impl EventHandler for Handler {
             is_from_bot: false,
             timestamp: chrono::Utc::now().to_rfc3339(),
         };
-        let inserted = call_blocking(self.app_state.db.clone(), move |db| {
-            db.store_message_if_new(&stored)
-        })
-        .await
-        .unwrap_or(false);
+        let inserted = crate::channels::store_inbound_message(
+            &self.app_state,
+            &self.runtime.channel_name,
+            stored,
+        )
+        .await;
         if !inserted {
             info!(
                 "Discord: skipping duplicate message chat_id={} message_id={}",
@@ -479,6 +668,16 @@ impl EventHandler for Handler {
             return;
         }
 
+        // One agent run per chat at a time. A burst of messages queues behind
+        // the current run and is answered by a single follow-up run.
+        let Some(_run_turn) = self.app_state.chat_run_queue.acquire(channel_id).await else {
+            info!(
+                "Discord: coalescing message into a newer queued run chat_id={} message_id={}",
+                channel_id, inbound_message_id
+            );
+            return;
+        };
+
         info!(
             "Discord message from {} in channel {}: {}",
             sender_name,
@@ -511,6 +710,11 @@ impl EventHandler for Handler {
             Ok(response) => {
                 drop(typing);
                 drop(event_tx);
+                // Messages already inside a thread are answered there.
+                let in_thread = external_channel_id != msg.channel_id.get();
+                let thread_from =
+                    (self.runtime.use_threads && msg.guild_id.is_some() && !in_thread)
+                        .then_some(&msg);
                 let mut used_send_message_tool = false;
                 while let Some(event) = event_rx.recv().await {
                     if let AgentEvent::ToolStart { name, .. } = event {
@@ -528,7 +732,7 @@ impl EventHandler for Handler {
                         );
                     }
                 } else if !response.is_empty() {
-                    send_discord_response(&ctx, msg.channel_id, &response).await;
+                    send_discord_response(&ctx, msg.channel_id, &response, thread_from).await;
 
                     // Store bot response
                     let bot_msg = StoredMessage {
@@ -545,7 +749,7 @@ impl EventHandler for Handler {
                     .await;
                 } else {
                     let fallback = "I couldn't produce a visible reply after an automatic retry. Please try again.".to_string();
-                    send_discord_response(&ctx, msg.channel_id, &fallback).await;
+                    send_discord_response(&ctx, msg.channel_id, &fallback, thread_from).await;
 
                     let bot_msg = StoredMessage {
                         id: uuid::Uuid::new_v4().to_string(),
@@ -571,37 +775,165 @@ impl EventHandler for Handler {
         }
     }
 
-    async fn ready(&self, _ctx: Context, ready: Ready) {
+    async fn message_update(
+        &self,
+        ctx: Context,
+        _old_if_available: Option<DiscordMessage>,
+        _new: Option<DiscordMessage>,
+        event: MessageUpdateEvent,
+    ) {
+        // Partial updates (embeds resolving, pins) carry no new content.
+        let (Some(author), Some(text)) = (event.author.as_ref(), event.content.as_ref()) else {
+            return;
+        };
+        if author.bot || text.is_empty() || is_slash_command(text) {
+            return;
+        }
+        let external_channel_id = conversation_channel_id(&ctx, event.guild_id, event.channel_id)
+            .await
+            .get();
+        if !self.runtime.allowed_channels.is_empty()
+            && !self.runtime.allowed_channels.contains(&external_channel_id)
+        {
+            return;
+        }
+        let addresses_bot = event.guild_id.is_none()
+            || self.runtime.no_mention
+            || event.mentions.as_ref().is_some_and(|mentions| {
+                let bot_id = ctx.cache.current_user().id;
+                mentions.iter().any(|u| u.id == bot_id)
+            });
+        let channel_id = {
+            let external_chat_id = external_channel_id.to_string();
+            let title = format!("discord-{external_channel_id}");
+            let channel_name = self.runtime.channel_name.clone();
+            call_blocking(self.app_state.db.clone(), move |db| {
+                db.resolve_or_create_chat_id(
+                    &channel_name,
+                    &external_chat_id,
+                    Some(&title),
+                    "discord",
+                )
+            })
+            .await
+            .unwrap_or(external_channel_id as i64)
+        };
+        let message_id = event.id.get().to_string();
+        handle_message_edit(
+            &self.app_state,
+            &MessageEdit {
+                channel_name: &self.runtime.channel_name,
+                chat_id: channel_id,
+                chat_type: if event.guild_id.is_some() {
+                    "group"
+                } else {
+                    "private"
+                },
+                message_id: &message_id,
+                content: text,
+                addresses_bot,
+            },
+        )
+        .await;
+    }
+
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        let Interaction::Command(command) = interaction else {
+            return;
+        };
+        // Acknowledge within Discord's 3 second window; commands like
+        // /archive may take longer to produce their reply.
+        if let Err(e) = command.defer(&ctx.http).await {
+            warn!("Failed to acknowledge Discord slash command: {e}");
+            return;
+        }
+        self.handle_slash_command(&ctx, &command).await;
+    }
+
+    async fn ready(&self, ctx: Context, ready: Ready) {
         info!("Discord bot connected as {}", ready.user.name);
+        // Overwrites the whole global command set, so repeated ready events
+        // and restarts do not create duplicates.
+        match Command::set_global_commands(&ctx.http, slash_command_definitions()).await {
+            Ok(commands) => info!("Registered {} Discord slash commands", commands.len()),
+            Err(e) => warn!("Failed to register Discord slash commands: {e}"),
+        }
     }
 }
 
-/// Split and send long messages (Discord limit is 2000 chars).
-async fn send_discord_response(ctx: &Context, channel_id: ChannelId, text: &str) {
-    const MAX_LEN: usize = 2000;
+/// The audio attachment of a voice message. Discord sends voice messages as
+/// a single ogg attachment with a duration and no text.
+fn voice_attachment(msg: &DiscordMessage) -> Option<&Attachment> {
+    msg.attachments.iter().find(|a| {
+        a.duration_secs.is_some()
+            || a.content_type
+                .as_deref()
+                .is_some_and(|t| t.starts_with("audio/"))
+    })
+}
 
-    if text.len() <= MAX_LEN {
-        let _ = channel_id.say(&ctx.http, text).await;
-        return;
-    }
+const DISCORD_MAX_LEN: usize = 2000;
+/// Discord rejects thread names longer than this.
+const THREAD_NAME_MAX_CHARS: usize = 100;
 
+/// Split text into chunks that fit one Discord message (2000 chars),
+/// preferring to break at newlines.
+fn discord_chunks(text: &str) -> Vec<&str> {
+    let mut chunks = Vec::new();
     let mut remaining = text;
     while !remaining.is_empty() {
-        let chunk_len = if remaining.len() <= MAX_LEN {
+        let chunk_len = if remaining.len() <= DISCORD_MAX_LEN {
             remaining.len()
         } else {
-            let boundary = floor_char_boundary(remaining, MAX_LEN.min(remaining.len()));
+            let boundary = floor_char_boundary(remaining, DISCORD_MAX_LEN);
             remaining[..boundary].rfind('\n').unwrap_or(boundary)
         };
 
-        let chunk = &remaining[..chunk_len];
-        let _ = channel_id.say(&ctx.http, chunk).await;
+        chunks.push(&remaining[..chunk_len]);
         remaining = &remaining[chunk_len..];
 
         if remaining.starts_with('\n') {
             remaining = &remaining[1..];
         }
     }
+    chunks
+}
+
+/// Name a reply thread after the first line of the message it starts from.
+fn thread_name(source: &str) -> String {
+    let first_line = source.lines().map(str::trim).find(|l| !l.is_empty());
+    match first_line {
+        Some(line) => line.chars().take(THREAD_NAME_MAX_CHARS).collect(),
+        None => "MicroClaw reply".to_string(),
+    }
+}
+
+/// Split and send long messages (Discord limit is 2000 chars). With
+/// `thread_from` set, an answer that needs more than one message goes into a
+/// thread started from that message; if the thread can't be created (e.g.
+/// the message is already inside a thread) it is posted in the channel.
+async fn send_discord_response(
+    ctx: &Context,
+    channel_id: ChannelId,
+    text: &str,
+    thread_from: Option<&DiscordMessage>,
+) {
+    let chunks = discord_chunks(text);
+    let mut target = channel_id;
+    if let (Some(source), true) = (thread_from, chunks.len() > 1) {
+        let thread = CreateThread::new(thread_name(&source.content))
+            .auto_archive_duration(AutoArchiveDuration::OneDay);
+        match channel_id
+            .create_thread_from_message(&ctx.http, source.id, thread)
+            .await
+        {
+            Ok(thread) => target = thread.id,
+            Err(e) => warn!("Discord: could not start reply thread, posting in channel: {e}"),
+        }
+    }
+    for chunk in chunks {
+        let _ = target.say(&ctx.http, chunk).await;
+    }
 }
 
 async fn run_discord_client(
@@ -630,8 +962,10 @@ pub async fn start_discord_bot(
     runtime: DiscordRuntimeContext,
     token: &str,
 ) {
-    mark_channel_started(&runtime.channel_name);
-    let base_intents = GatewayIntents::GUILD_MESSAGES | GatewayIntents::DIRECT_MESSAGES;
+    let _connection = mark_channel_connected(&runtime.channel_name);
+    // GUILDS fills the cache with channels and threads for thread lookups.
+    let base_intents =
+        GatewayIntents::GUILDS | GatewayIntents::GUILD_MESSAGES | GatewayIntents::DIRECT_MESSAGES;
     let full_intents = base_intents | GatewayIntents::MESSAGE_CONTENT;
 
     info!("Starting Discord bot (requesting MESSAGE_CONTENT intent)...");
@@ -655,6 +989,64 @@ pub async fn start_discord_bot(
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_slash_commands_map_to_text_commands() {
+        let names: Vec<String> = slash_command_definitions()
+            .iter()
+            .map(|c| {
+                serde_json::to_value(c).unwrap()["name"]
+                    .as_str()
+                    .unwrap()
+                    .to_string()
+            })
+            .collect();
+        assert_eq!(names, vec!["reset", "skills", "archive", "model"]);
+        assert_eq!(slash_command_text("reset", &[]), "/reset");
+        assert_eq!(slash_command_text("model", &[" gpt-5 "]), "/model gpt-5");
+        assert_eq!(slash_command_text("model", &[""]), "/model");
+    }
+
+    #[test]
+    fn test_thread_parent_id_only_follows_threads() {
+        let mut channel = GuildChannel::default();
+        channel.id = ChannelId::new(10);
+        channel.parent_id = Some(ChannelId::new(5));
+        channel.kind = ChannelType::Text;
+        // A text channel's parent is its category, not a conversation.
+        assert_eq!(thread_parent_id(&channel), ChannelId::new(10));
+        for kind in [
+            ChannelType::PublicThread,
+            ChannelType::PrivateThread,
+            ChannelType::NewsThread,
+        ] {
+            channel.kind = kind;
+            assert_eq!(thread_parent_id(&channel), ChannelId::new(5));
+        }
+    }
+
+    #[test]
+    fn test_discord_chunks_and_thread_name() {
+        assert_eq!(discord_chunks("short"), vec!["short"]);
+        let long = format!("{}\n{}", "a".repeat(1500), "b".repeat(1500));
+        let chunks = discord_chunks(&long);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0], "a".repeat(1500));
+        assert_eq!(chunks[1], "b".repeat(1500));
+        assert!(discord_chunks(&"x".repeat(4500))
+            .iter()
+            .all(|c| c.len() <= DISCORD_MAX_LEN));
+
+        assert_eq!(
+            thread_name("\n  <@1> how do I deploy?\nmore"),
+            "<@1> how do I deploy?"
+        );
+        assert_eq!(
+            thread_name(&"y".repeat(300)).chars().count(),
+            THREAD_NAME_MAX_CHARS
+        );
+        assert_eq!(thread_name(""), "MicroClaw reply");
+    }
+
     #[tokio::test]
     async fn test_discord_plugin_slash_dispatch_helper() {
         let root = std::env::temp_dir().join(format!("mc_dc_plugin_{}", uuid::Uuid::new_v4()));
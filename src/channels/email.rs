@@ -11,8 +11,8 @@ use tracing::{error, info};
 use crate::agent_engine::process_with_agent_with_events;
 use crate::agent_engine::{AgentEvent, AgentRequestContext};
 use crate::channels::startup_guard::{
-    mark_channel_started, parse_epoch_ms_from_seconds_str, parse_epoch_ms_from_str,
-    should_drop_pre_start_message, should_drop_recent_duplicate_message,
+    claim_inbound_message, mark_channel_started, parse_epoch_ms_from_seconds_str,
+    parse_epoch_ms_from_str, should_drop_pre_start_message, should_drop_recent_duplicate_message,
 };
 use crate::chat_commands::{handle_chat_command, is_slash_command, unknown_command_response};
 use crate::runtime::AppState;
@@ -361,11 +361,12 @@ pub async fn start_email_bot(_app_state: Arc<AppState>, runtime: EmailRuntimeCon
 pub fn register_email_webhook(router: Router, app_state: Arc<AppState>) -> Router {
     let Some(cfg) = app_state
         .config
+        .load()
         .channel_config::<EmailChannelConfig>("email")
     else {
         return router;
     };
-    if !app_state.config.channel_enabled("email") {
+    if !app_state.config.load().channel_enabled("email") {
         return router;
     }
     let path = cfg.webhook_path.trim();
@@ -389,7 +390,7 @@ async fn email_webhook_handler(
     headers: HeaderMap,
     payload: EmailWebhookPayload,
 ) -> impl IntoResponse {
-    let runtime_contexts = build_email_runtime_contexts(&app_state.config);
+    let runtime_contexts = build_email_runtime_contexts(&app_state.config.load());
     if runtime_contexts.is_empty() {
         return axum::http::StatusCode::NOT_FOUND;
     }
@@ -506,6 +507,11 @@ async fn process_email_webhook_message(
     if should_drop_recent_duplicate_message(&runtime_ctx.channel_name, &inbound_message_id) {
         return;
     }
+    let Some(_claim) =
+        claim_inbound_message(&app_state, &runtime_ctx.channel_name, &inbound_message_id).await
+    else {
+        return;
+    };
 
     let trimmed = trimmed_text.trim();
     if is_slash_command(trimmed) {
@@ -554,11 +560,8 @@ async fn process_email_webhook_message(
         is_from_bot: false,
         timestamp: chrono::Utc::now().to_rfc3339(),
     };
-    let inserted = call_blocking(app_state.db.clone(), move |db| {
-        db.store_message_if_new(&stored)
-    })
-    .await
-    .unwrap_or(false);
+    let inserted =
+        crate::channels::store_inbound_message(&app_state, &runtime_ctx.channel_name, stored).await;
     if !inserted {
         info!(
             "Email: skipping duplicate message chat_id={} message_id={}",
@@ -13,7 +13,7 @@ use crate::agent_engine::process_with_agent_with_events;
 use crate::agent_engine::should_suppress_user_error;
 use crate::agent_engine::AgentEvent;
 use crate::agent_engine::AgentRequestContext;
-use crate::channels::startup_guard::should_drop_recent_duplicate_message;
+use crate::channels::startup_guard::{claim_inbound_message, should_drop_recent_duplicate_message};
 use crate::chat_commands::maybe_handle_plugin_command;
 use crate::chat_commands::{handle_chat_command, is_slash_command, unknown_command_response};
 use crate::runtime::AppState;
@@ -1751,6 +1751,10 @@ async fn handle_feishu_event(
     if should_drop_recent_duplicate_message(&runtime.channel_name, message_id) {
         return;
     }
+    let Some(_claim) = claim_inbound_message(&app_state, &runtime.channel_name, message_id).await
+    else {
+        return;
+    };
 
     if let (Some(create_time_ms), Some(start_ms)) = (
         message_create_time_ms,
@@ -1928,6 +1932,7 @@ async fn handle_feishu_message(
                         Ok(bytes) => {
                             let max_bytes = app_state
                                 .config
+                                .load()
                                 .max_document_size_mb
                                 .saturating_mul(1024)
                                 .saturating_mul(1024);
@@ -1940,7 +1945,7 @@ async fn handle_feishu_message(
                                     &format!(
                                         "File is too large ({} bytes). Max allowed is {} MB.",
                                         bytes.len(),
-                                        app_state.config.max_document_size_mb
+                                        app_state.config.load().max_document_size_mb
                                     ),
                                     message_id,
                                     feishu_cfg.topic_mode,
@@ -1957,7 +1962,7 @@ async fn handle_feishu_message(
                                 })
                                 .collect();
 
-                            let dir = std::path::Path::new(&app_state.config.working_dir)
+                            let dir = std::path::Path::new(&app_state.config.load().working_dir)
                                 .join("uploads")
                                 .join(runtime.channel_name.replace('/', "_"))
                                 .join(external_chat_id);
@@ -2046,11 +2051,8 @@ async fn handle_feishu_message(
         is_from_bot: false,
         timestamp: chrono::Utc::now().to_rfc3339(),
     };
-    let inserted = call_blocking(app_state.db.clone(), move |db| {
-        db.store_message_if_new(&stored)
-    })
-    .await
-    .unwrap_or(false);
+    let inserted =
+        crate::channels::store_inbound_message(&app_state, &runtime.channel_name, stored).await;
     if !inserted {
         info!(
             "Feishu: skipping duplicate message chat_id={} message_id={}",
@@ -2060,7 +2062,7 @@ async fn handle_feishu_message(
     }
 
     if is_slash_command(trimmed) {
-        if !should_respond && !app_state.config.allow_group_slash_without_mention {
+        if !should_respond && !app_state.config.load().allow_group_slash_without_mention {
             return;
         }
         if let Some(reply) = handle_chat_command(
@@ -2084,9 +2086,13 @@ async fn handle_feishu_message(
             .await;
             return;
         }
-        if let Some(plugin_response) =
-            maybe_plugin_slash_response(&app_state.config, trimmed, chat_id, &runtime.channel_name)
-                .await
+        if let Some(plugin_response) = maybe_plugin_slash_response(
+            &app_state.config.load(),
+            trimmed,
+            chat_id,
+            &runtime.channel_name,
+        )
+        .await
         {
             let _ = send_feishu_response(
                 &http_client,
@@ -2525,7 +2531,7 @@ mod mention_tests {
 /// Register Feishu webhook routes on the given axum Router.
 /// Called when connection_mode is "webhook".
 pub fn register_feishu_webhook(router: axum::Router, app_state: Arc<AppState>) -> axum::Router {
-    let runtimes = build_feishu_runtime_contexts(&app_state.config);
+    let runtimes = build_feishu_runtime_contexts(&app_state.config.load());
     if runtimes.is_empty() {
         return router;
     }
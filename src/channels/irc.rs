@@ -272,7 +272,7 @@ impl ChannelAdapter for IrcAdapter {
 }
 
 pub async fn start_irc_bot(app_state: Arc<AppState>, adapter: Arc<IrcAdapter>) {
-    let cfg: IrcChannelConfig = match app_state.config.channel_config("irc") {
+    let cfg: IrcChannelConfig = match app_state.config.load().channel_config("irc") {
         Some(c) => c,
         None => {
             error!("IRC channel not configured");
@@ -476,7 +476,7 @@ async fn handle_irc_message(
     let should_respond =
         !is_group || !cfg.mention_required_bool() || is_irc_mention(&text, cfg.nick.trim());
     if is_slash_command(trimmed) {
-        if !should_respond && !app_state.config.allow_group_slash_without_mention {
+        if !should_respond && !app_state.config.load().allow_group_slash_without_mention {
             return;
         }
         if let Some(reply) =
@@ -486,7 +486,7 @@ async fn handle_irc_message(
             return;
         }
         if let Some(plugin_response) =
-            maybe_plugin_slash_response(&app_state.config, trimmed, chat_id, "irc").await
+            maybe_plugin_slash_response(&app_state.config.load(), trimmed, chat_id, "irc").await
         {
             let _ = adapter.send_text(&response_target, &plugin_response).await;
             return;
@@ -505,7 +505,7 @@ async fn handle_irc_message(
         is_from_bot: false,
         timestamp: chrono::Utc::now().to_rfc3339(),
     };
-    let _ = call_blocking(app_state.db.clone(), move |db| db.store_message(&stored)).await;
+    crate::channels::store_inbound_message(&app_state, "irc", stored).await;
 
     if !should_respond {
         return;
@@ -558,7 +558,7 @@ async fn handle_irc_message(
                 let bot_msg = StoredMessage {
                     id: uuid::Uuid::new_v4().to_string(),
                     chat_id,
-                    sender_name: app_state.config.bot_username_for_channel("irc"),
+                    sender_name: app_state.config.load().bot_username_for_channel("irc"),
                     content: response,
                     is_from_bot: true,
                     timestamp: chrono::Utc::now().to_rfc3339(),
@@ -572,7 +572,7 @@ async fn handle_irc_message(
                 let bot_msg = StoredMessage {
                     id: uuid::Uuid::new_v4().to_string(),
                     chat_id,
-                    sender_name: app_state.config.bot_username_for_channel("irc"),
+                    sender_name: app_state.config.load().bot_username_for_channel("irc"),
                     content: fallback.to_string(),
                     is_from_bot: true,
                     timestamp: chrono::Utc::now().to_rfc3339(),
@@ -26,9 +26,12 @@ use crate::agent_engine::should_suppress_user_error;
 use crate::agent_engine::AgentEvent;
 use crate::agent_engine::AgentRequestContext;
 use crate::channels::startup_guard::{
-    mark_channel_started, should_drop_pre_start_message, should_drop_recent_duplicate_message,
+    claim_inbound_message, mark_channel_connected, should_drop_pre_start_message,
+    should_drop_recent_duplicate_message,
+};
+use crate::chat_commands::{
+    handle_chat_command, is_slash_command, streaming_enabled_for_chat, unknown_command_response,
 };
-use crate::chat_commands::{handle_chat_command, is_slash_command, unknown_command_response};
 use crate::runtime::AppState;
 use crate::setup_def::{ChannelFieldDef, DynamicChannelDef};
 use microclaw_channels::channel::ConversationKind;
@@ -473,7 +476,7 @@ enum MatrixIncomingEvent {
 }
 
 pub async fn start_matrix_bot(app_state: Arc<AppState>, runtime: MatrixRuntimeContext) {
-    mark_channel_started(&runtime.channel_name);
+    let _connection = mark_channel_connected(&runtime.channel_name);
     if let Some(client) = build_matrix_sdk_client(app_state.clone(), &runtime).await {
         let client = Arc::new(client);
         matrix_sdk_clients()
@@ -491,6 +494,7 @@ pub async fn start_matrix_bot(app_state: Arc<AppState>, runtime: MatrixRuntimeCo
         runtime_with_sdk.sdk_client = Some(client_slot.clone());
         let e2ee_state = app_state.clone();
         tokio::spawn(async move {
+            let _connection = _connection;
             start_matrix_e2ee_sync(e2ee_state, runtime_with_sdk).await;
         });
 
@@ -750,7 +754,7 @@ fn matrix_channel_slug(channel_name: &str) -> String {
 }
 
 fn matrix_sdk_store_dir(app_state: &AppState, runtime: &MatrixRuntimeContext) -> PathBuf {
-    PathBuf::from(app_state.config.runtime_data_dir())
+    PathBuf::from(app_state.config.load().runtime_data_dir())
         .join("matrix_sdk")
         .join(matrix_channel_slug(&runtime.channel_name))
 }
@@ -1748,11 +1752,8 @@ async fn handle_matrix_reaction(
         is_from_bot: false,
         timestamp: chrono::Utc::now().to_rfc3339(),
     };
-    let inserted = call_blocking(app_state.db.clone(), move |db| {
-        db.store_message_if_new(&incoming)
-    })
-    .await
-    .unwrap_or(false);
+    let inserted =
+        crate::channels::store_inbound_message(&app_state, &runtime.channel_name, incoming).await;
     if !inserted {
         info!(
             "Matrix: skipping duplicate reaction chat_id={} event_id={}",
@@ -2111,10 +2112,15 @@ async fn handle_matrix_message(
     if should_drop_recent_duplicate_message(&runtime.channel_name, &inbound_event_id) {
         return;
     }
+    let Some(_claim) =
+        claim_inbound_message(&app_state, &runtime.channel_name, &inbound_event_id).await
+    else {
+        return;
+    };
     let should_respond = runtime.should_respond(&msg.body, msg.mentioned_bot, msg.is_direct);
     let trimmed = msg.body.trim();
     if is_slash_command(trimmed) {
-        if !should_respond && !app_state.config.allow_group_slash_without_mention {
+        if !should_respond && !app_state.config.load().allow_group_slash_without_mention {
             return;
         }
         if let Some(reply) = handle_chat_command(
@@ -2151,11 +2157,8 @@ async fn handle_matrix_message(
         is_from_bot: false,
         timestamp: chrono::Utc::now().to_rfc3339(),
     };
-    let inserted = call_blocking(app_state.db.clone(), move |db| {
-        db.store_message_if_new(&incoming)
-    })
-    .await
-    .unwrap_or(false);
+    let inserted =
+        crate::channels::store_inbound_message(&app_state, &runtime.channel_name, incoming).await;
     if !inserted {
         info!(
             "Matrix: skipping duplicate message chat_id={} event_id={}",
@@ -2178,7 +2181,8 @@ async fn handle_matrix_message(
 
     // Check if streaming is enabled for this room
     let streaming_config = runtime.streaming.clone();
-    let use_streaming = streaming_config.enabled;
+    let use_streaming =
+        streaming_enabled_for_chat(app_state.db.clone(), chat_id, streaming_config.enabled).await;
 
     match process_with_agent_with_events(
         &app_state,
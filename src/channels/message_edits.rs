@@ -0,0 +1,249 @@
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex};
+
+use tracing::{info, warn};
+
+use crate::agent_engine::{process_with_agent, AgentRequestContext};
+use crate::run_control;
+use crate::runtime::AppState;
+use microclaw_channels::channel::deliver_and_store_bot_message;
+use microclaw_storage::db::{call_blocking, Database};
+
+type RerunKey = (String, i64, String);
+
+/// Messages already re-run after an edit, keyed by (channel, chat, message
+/// id), so a message that keeps getting edited is answered again only once.
+static EDIT_RERUNS: LazyLock<Mutex<HashMap<RerunKey, i64>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+const EDIT_RERUN_TTL_MS: i64 = 24 * 60 * 60 * 1000;
+const EDIT_RERUN_MAX_ENTRIES: usize = 10_000;
+
+/// A user's edit of a message they sent earlier.
+pub struct MessageEdit<'a> {
+    pub channel_name: &'a str,
+    pub chat_id: i64,
+    /// Agent chat type (`private` or `group`).
+    pub chat_type: &'a str,
+    pub message_id: &'a str,
+    pub content: &'a str,
+    /// Whether the edited message would make the bot respond (a private
+    /// chat, or a mention / reply in a group).
+    pub addresses_bot: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditOutcome {
+    /// Unknown message, a bot message, or the content did not change.
+    Ignored,
+    /// The stored content was replaced.
+    Updated,
+    /// The stored content was replaced and the agent should answer again.
+    Rerun,
+}
+
+/// Update the stored copy of an edited message and decide whether the agent
+/// should run again: only when `rerun_on_message_edit` is on, the message
+/// addresses the bot, nothing has been answered since it arrived, and it has
+/// not been re-run before.
+pub async fn apply_message_edit(
+    db: Arc<Database>,
+    rerun_on_message_edit: bool,
+    edit: &MessageEdit<'_>,
+) -> EditOutcome {
+    let chat_id = edit.chat_id;
+    let message_id = edit.message_id.to_string();
+    let content = edit.content.to_string();
+    let updated = call_blocking(db.clone(), move |db| {
+        db.update_user_message_content(chat_id, &message_id, &content)
+    })
+    .await;
+    match updated {
+        Ok(true) => {}
+        Ok(false) => return EditOutcome::Ignored,
+        Err(e) => {
+            warn!("Failed to store edit of message {}: {e}", edit.message_id);
+            return EditOutcome::Ignored;
+        }
+    }
+    info!(
+        "Stored edit channel={} chat_id={} message_id={}",
+        edit.channel_name, edit.chat_id, edit.message_id
+    );
+
+    if !rerun_on_message_edit || !edit.addresses_bot {
+        return EditOutcome::Updated;
+    }
+    let message_id = edit.message_id.to_string();
+    let answered = call_blocking(db, move |db| {
+        db.has_bot_reply_since_message(chat_id, &message_id)
+    })
+    .await
+    .unwrap_or(true);
+    if answered || !claim_edit_rerun(edit.channel_name, edit.chat_id, edit.message_id) {
+        return EditOutcome::Updated;
+    }
+    EditOutcome::Rerun
+}
+
+/// Returns true the first time it is called for a message.
+fn claim_edit_rerun(channel_name: &str, chat_id: i64, message_id: &str) -> bool {
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    let mut reruns = EDIT_RERUNS.lock().unwrap_or_else(|e| e.into_inner());
+    let key = (channel_name.to_string(), chat_id, message_id.to_string());
+    if reruns.contains_key(&key) {
+        return false;
+    }
+    reruns.insert(key, now_ms);
+    // Keep memory bounded for long-running processes.
+    if reruns.len() > EDIT_RERUN_MAX_ENTRIES {
+        reruns.retain(|_, seen_ms| now_ms.saturating_sub(*seen_ms) <= EDIT_RERUN_TTL_MS);
+    }
+    true
+}
+
+/// Apply an edit and, if it calls for one, answer the edited message again
+/// through the chat's channel adapter. No re-run starts while another run is
+/// active for the chat: that run already covers the message and saves its
+/// session after it, which would hide the edit from a parallel re-run.
+/// The re-run loads the edited text like any pending message, so
+/// `InboundMessage` hooks apply to it.
+pub async fn handle_message_edit(state: &AppState, edit: &MessageEdit<'_>) -> EditOutcome {
+    let rerun = state.config.load().rerun_on_message_edit
+        && !run_control::has_active_run(edit.channel_name, edit.chat_id).await;
+    let outcome = apply_message_edit(state.db.clone(), rerun, edit).await;
+    if outcome != EditOutcome::Rerun {
+        return outcome;
+    }
+    // Queue behind runs that started after the check above; a newer message
+    // queued meanwhile answers the edit along with itself.
+    let Some(_run_turn) = state.chat_run_queue.acquire(edit.chat_id).await else {
+        return outcome;
+    };
+    info!(
+        "Re-running agent after edit channel={} chat_id={} message_id={}",
+        edit.channel_name, edit.chat_id, edit.message_id
+    );
+    let reply = match process_with_agent(
+        state,
+        AgentRequestContext {
+            caller_channel: edit.channel_name,
+            chat_id: edit.chat_id,
+            chat_type: edit.chat_type,
+        },
+        None,
+        None,
+    )
+    .await
+    {
+        Ok(response) => response,
+        Err(e) => {
+            warn!("Re-run after edit failed for chat {}: {e}", edit.chat_id);
+            return outcome;
+        }
+    };
+    if !reply.is_empty() {
+        let bot_username = state
+            .config
+            .load()
+            .bot_username_for_channel(edit.channel_name);
+        if let Err(e) = deliver_and_store_bot_message(
+            &state.channel_registry,
+            state.db.clone(),
+            &bot_username,
+            edit.chat_id,
+            &reply,
+        )
+        .await
+        {
+            warn!(
+                "Failed to deliver re-run reply for chat {}: {e}",
+                edit.chat_id
+            );
+        }
+    }
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use microclaw_storage::db::StoredMessage;
+
+    fn test_db() -> (Arc<Database>, std::path::PathBuf) {
+        let dir = std::env::temp_dir().join(format!("microclaw_edits_{}", uuid::Uuid::new_v4()));
+        let db = Arc::new(Database::new(dir.to_str().unwrap()).unwrap());
+        (db, dir)
+    }
+
+    fn store(db: &Database, id: &str, content: &str, is_from_bot: bool, ts: &str) {
+        db.store_message(&StoredMessage {
+            id: id.into(),
+            chat_id: 7,
+            sender_name: if is_from_bot { "bot" } else { "alice" }.into(),
+            content: content.into(),
+            is_from_bot,
+            timestamp: ts.into(),
+        })
+        .unwrap();
+    }
+
+    fn edit<'a>(message_id: &'a str, content: &'a str, addresses_bot: bool) -> MessageEdit<'a> {
+        MessageEdit {
+            channel_name: "telegram_edits_test",
+            chat_id: 7,
+            chat_type: "private",
+            message_id,
+            content,
+            addresses_bot,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_edit_updates_stored_message_without_rerun_when_disabled() {
+        let (db, dir) = test_db();
+        store(&db, "m1", "whats 2+3", false, "2024-01-01T00:00:01Z");
+
+        let outcome = apply_message_edit(db.clone(), false, &edit("m1", "whats 2+2", true)).await;
+        assert_eq!(outcome, EditOutcome::Updated);
+        assert_eq!(
+            db.get_recent_messages(7, 1).unwrap()[0].content,
+            "whats 2+2"
+        );
+        assert_eq!(
+            apply_message_edit(db.clone(), false, &edit("m1", "whats 2+2", true)).await,
+            EditOutcome::Ignored
+        );
+        assert_eq!(
+            apply_message_edit(db.clone(), false, &edit("unknown", "x", true)).await,
+            EditOutcome::Ignored
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_rerun_only_for_unanswered_addressed_messages_once() {
+        let (db, dir) = test_db();
+        store(&db, "answered", "hi", false, "2024-01-01T00:00:01Z");
+        store(&db, "reply", "hello", true, "2024-01-01T00:00:02Z");
+        store(&db, "pending", "what time", false, "2024-01-01T00:00:03Z");
+
+        assert_eq!(
+            apply_message_edit(db.clone(), true, &edit("answered", "hey", true)).await,
+            EditOutcome::Updated
+        );
+        assert_eq!(
+            apply_message_edit(db.clone(), true, &edit("pending", "what day", false)).await,
+            EditOutcome::Updated
+        );
+        assert_eq!(
+            apply_message_edit(db.clone(), true, &edit("pending", "what date", true)).await,
+            EditOutcome::Rerun
+        );
+        // Further edits of the same message never re-run again.
+        assert_eq!(
+            apply_message_edit(db.clone(), true, &edit("pending", "what year", true)).await,
+            EditOutcome::Updated
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
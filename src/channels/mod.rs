@@ -5,8 +5,10 @@ pub mod feishu;
 pub mod imessage;
 pub mod irc;
 pub mod matrix;
+pub mod message_edits;
 pub mod nostr;
 pub mod qq;
+pub mod read_receipts;
 pub mod signal;
 pub mod slack;
 pub mod startup_guard;
@@ -27,3 +29,40 @@ pub use signal::SignalAdapter;
 pub use slack::SlackAdapter;
 pub use telegram::TelegramAdapter;
 pub use whatsapp::WhatsAppAdapter;
+
+use crate::runtime::AppState;
+use microclaw_storage::db::{call_blocking, StoredMessage};
+
+/// Store an inbound user message unless its id is already stored, and emit the
+/// `message_received` webhook for a new one. Returns whether it was new;
+/// storage errors count as not new.
+pub async fn store_inbound_message(
+    state: &AppState,
+    channel: &str,
+    message: StoredMessage,
+) -> bool {
+    let stored = message.clone();
+    let inserted = call_blocking(state.db.clone(), move |db| db.store_message_if_new(&stored))
+        .await
+        .unwrap_or(false);
+    if inserted {
+        emit_message_received(state, channel, &message);
+    }
+    inserted
+}
+
+/// Send the `message_received` webhook for a just-stored user message.
+pub fn emit_message_received(state: &AppState, channel: &str, message: &StoredMessage) {
+    if let Some(webhook) = state.event_webhook.load().as_ref() {
+        webhook.emit(
+            "message_received",
+            message.chat_id,
+            Some(channel),
+            serde_json::json!({
+                "message_id": message.id,
+                "sender": message.sender_name,
+                "text": message.content,
+            }),
+        );
+    }
+}
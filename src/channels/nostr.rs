@@ -10,8 +10,8 @@ use tracing::{error, info};
 use crate::agent_engine::process_with_agent_with_events;
 use crate::agent_engine::{AgentEvent, AgentRequestContext};
 use crate::channels::startup_guard::{
-    mark_channel_started, parse_epoch_ms_from_seconds_str, should_drop_pre_start_message,
-    should_drop_recent_duplicate_message,
+    claim_inbound_message, mark_channel_started, parse_epoch_ms_from_seconds_str,
+    should_drop_pre_start_message, should_drop_recent_duplicate_message,
 };
 use crate::chat_commands::{handle_chat_command, is_slash_command, unknown_command_response};
 use crate::runtime::AppState;
@@ -295,11 +295,12 @@ pub async fn start_nostr_bot(_app_state: Arc<AppState>, runtime: NostrRuntimeCon
 pub fn register_nostr_webhook(router: Router, app_state: Arc<AppState>) -> Router {
     let Some(cfg) = app_state
         .config
+        .load()
         .channel_config::<NostrChannelConfig>("nostr")
     else {
         return router;
     };
-    if !app_state.config.channel_enabled("nostr") {
+    if !app_state.config.load().channel_enabled("nostr") {
         return router;
     }
     let path = cfg.webhook_path.trim();
@@ -324,7 +325,7 @@ async fn nostr_webhook_handler(
     headers: HeaderMap,
     payload: NostrWebhookPayload,
 ) -> impl axum::response::IntoResponse {
-    let runtime_contexts = build_nostr_runtime_contexts(&app_state.config);
+    let runtime_contexts = build_nostr_runtime_contexts(&app_state.config.load());
     if runtime_contexts.is_empty() {
         return axum::http::StatusCode::NOT_FOUND;
     }
@@ -400,6 +401,11 @@ async fn nostr_webhook_handler(
     if should_drop_recent_duplicate_message(&runtime_ctx.channel_name, &inbound_event_id) {
         return axum::http::StatusCode::OK;
     }
+    let Some(_claim) =
+        claim_inbound_message(&app_state, &runtime_ctx.channel_name, &inbound_event_id).await
+    else {
+        return axum::http::StatusCode::OK;
+    };
     if is_slash_command(content) {
         if let Some(reply) = handle_chat_command(
             &app_state,
@@ -432,11 +438,8 @@ async fn nostr_webhook_handler(
         is_from_bot: false,
         timestamp: chrono::Utc::now().to_rfc3339(),
     };
-    let inserted = call_blocking(app_state.db.clone(), move |db| {
-        db.store_message_if_new(&stored)
-    })
-    .await
-    .unwrap_or(false);
+    let inserted =
+        crate::channels::store_inbound_message(&app_state, &runtime_ctx.channel_name, stored).await;
     if !inserted {
         info!(
             "Nostr: skipping duplicate message chat_id={} event_id={}",
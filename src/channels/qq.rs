@@ -10,8 +10,8 @@ use tracing::{error, info};
 use crate::agent_engine::process_with_agent_with_events;
 use crate::agent_engine::{AgentEvent, AgentRequestContext};
 use crate::channels::startup_guard::{
-    mark_channel_started, parse_epoch_ms_from_seconds_str, parse_epoch_ms_from_str,
-    should_drop_pre_start_message, should_drop_recent_duplicate_message,
+    claim_inbound_message, mark_channel_started, parse_epoch_ms_from_seconds_str,
+    parse_epoch_ms_from_str, should_drop_pre_start_message, should_drop_recent_duplicate_message,
 };
 use crate::chat_commands::{handle_chat_command, is_slash_command, unknown_command_response};
 use crate::runtime::AppState;
@@ -281,10 +281,14 @@ pub async fn start_qq_bot(_app_state: Arc<AppState>, runtime: QQRuntimeContext)
 }
 
 pub fn register_qq_webhook(router: Router, app_state: Arc<AppState>) -> Router {
-    let Some(cfg) = app_state.config.channel_config::<QQChannelConfig>("qq") else {
+    let Some(cfg) = app_state
+        .config
+        .load()
+        .channel_config::<QQChannelConfig>("qq")
+    else {
         return router;
     };
-    if !app_state.config.channel_enabled("qq") {
+    if !app_state.config.load().channel_enabled("qq") {
         return router;
     }
     let path = cfg.webhook_path.trim();
@@ -308,7 +312,7 @@ async fn qq_webhook_handler(
     headers: HeaderMap,
     payload: QQWebhookPayload,
 ) -> impl axum::response::IntoResponse {
-    let runtime_contexts = build_qq_runtime_contexts(&app_state.config);
+    let runtime_contexts = build_qq_runtime_contexts(&app_state.config.load());
     let Some(runtime_ctx) = runtime_contexts.first().cloned() else {
         return axum::http::StatusCode::NOT_FOUND;
     };
@@ -373,6 +377,11 @@ async fn qq_webhook_handler(
     if should_drop_recent_duplicate_message(&runtime_ctx.channel_name, &inbound_message_id) {
         return axum::http::StatusCode::OK;
     }
+    let Some(_claim) =
+        claim_inbound_message(&app_state, &runtime_ctx.channel_name, &inbound_message_id).await
+    else {
+        return axum::http::StatusCode::OK;
+    };
     if is_slash_command(text) {
         if let Some(reply) = handle_chat_command(
             &app_state,
@@ -407,11 +416,8 @@ async fn qq_webhook_handler(
         is_from_bot: false,
         timestamp: chrono::Utc::now().to_rfc3339(),
     };
-    let inserted = call_blocking(app_state.db.clone(), move |db| {
-        db.store_message_if_new(&stored)
-    })
-    .await
-    .unwrap_or(false);
+    let inserted =
+        crate::channels::store_inbound_message(&app_state, &runtime_ctx.channel_name, stored).await;
     if !inserted {
         info!(
             "QQ: skipping duplicate message chat_id={} message_id={}",
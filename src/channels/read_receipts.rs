@@ -0,0 +1,85 @@
+/// When an adapter may mark an inbound message as read.
+///
+/// `OnResponse` keeps the sender from learning that the bot has seen a message
+/// it chose to ignore, which matters for allowlisted-only deployments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReadReceiptMode {
+    Always,
+    #[default]
+    OnResponse,
+    Never,
+}
+
+impl ReadReceiptMode {
+    /// Parse a `read_receipts` config value. Unknown or empty values fall back
+    /// to the default (`on_response`).
+    pub fn parse(raw: &str) -> Self {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "always" | "on" | "true" => Self::Always,
+            "never" | "off" | "false" => Self::Never,
+            _ => Self::OnResponse,
+        }
+    }
+}
+
+/// Pick the account-level override when set, otherwise the channel-level value.
+pub fn resolve_read_receipt_mode(account: Option<&str>, channel: Option<&str>) -> ReadReceiptMode {
+    account
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .or_else(|| channel.map(str::trim).filter(|v| !v.is_empty()))
+        .map(ReadReceiptMode::parse)
+        .unwrap_or_default()
+}
+
+/// Decide whether to send a read receipt once handling of a message finished.
+/// `responded` is true when anything was delivered back to the sender.
+pub fn should_send_read_receipt(mode: ReadReceiptMode, responded: bool) -> bool {
+    match mode {
+        ReadReceiptMode::Always => true,
+        ReadReceiptMode::OnResponse => responded,
+        ReadReceiptMode::Never => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_on_response_only_marks_read_after_reply() {
+        assert!(should_send_read_receipt(ReadReceiptMode::OnResponse, true));
+        assert!(!should_send_read_receipt(
+            ReadReceiptMode::OnResponse,
+            false
+        ));
+    }
+
+    #[test]
+    fn test_always_and_never_ignore_response() {
+        assert!(should_send_read_receipt(ReadReceiptMode::Always, false));
+        assert!(should_send_read_receipt(ReadReceiptMode::Always, true));
+        assert!(!should_send_read_receipt(ReadReceiptMode::Never, true));
+        assert!(!should_send_read_receipt(ReadReceiptMode::Never, false));
+    }
+
+    #[test]
+    fn test_resolve_mode_prefers_account_override() {
+        assert_eq!(
+            resolve_read_receipt_mode(Some("never"), Some("always")),
+            ReadReceiptMode::Never
+        );
+        assert_eq!(
+            resolve_read_receipt_mode(Some(" "), Some("Always")),
+            ReadReceiptMode::Always
+        );
+        assert_eq!(
+            resolve_read_receipt_mode(None, None),
+            ReadReceiptMode::OnResponse
+        );
+        assert_eq!(
+            resolve_read_receipt_mode(None, Some("bogus")),
+            ReadReceiptMode::OnResponse
+        );
+    }
+}
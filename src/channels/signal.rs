@@ -9,9 +9,12 @@ use tracing::{error, info};
 
 use crate::agent_engine::process_with_agent_with_events;
 use crate::agent_engine::{AgentEvent, AgentRequestContext};
+use crate::channels::read_receipts::{
+    resolve_read_receipt_mode, should_send_read_receipt, ReadReceiptMode,
+};
 use crate::channels::startup_guard::{
-    mark_channel_started, parse_epoch_ms_from_seconds_str, parse_epoch_ms_from_str,
-    should_drop_pre_start_message, should_drop_recent_duplicate_message,
+    claim_inbound_message, mark_channel_started, parse_epoch_ms_from_seconds_str,
+    parse_epoch_ms_from_str, should_drop_pre_start_message, should_drop_recent_duplicate_message,
 };
 use crate::chat_commands::{handle_chat_command, is_slash_command, unknown_command_response};
 use crate::runtime::AppState;
@@ -66,6 +69,20 @@ pub const SETUP_DEF: DynamicChannelDef = DynamicChannelDef {
             secret: false,
             required: false,
         },
+        ChannelFieldDef {
+            yaml_key: "receipt_command",
+            label: "Signal read receipt command (optional, env MICROCLAW_SIGNAL_TARGET/TIMESTAMP)",
+            default: "",
+            secret: false,
+            required: false,
+        },
+        ChannelFieldDef {
+            yaml_key: "read_receipts",
+            label: "Signal read receipts: always/on_response/never (default on_response)",
+            default: "on_response",
+            secret: false,
+            required: false,
+        },
     ],
 };
 
@@ -89,6 +106,10 @@ pub struct SignalAccountConfig {
     pub bot_username: String,
     #[serde(default)]
     pub model: Option<String>,
+    #[serde(default)]
+    pub receipt_command: String,
+    #[serde(default)]
+    pub read_receipts: Option<String>,
     #[serde(default = "default_enabled")]
     pub enabled: bool,
 }
@@ -106,6 +127,10 @@ pub struct SignalChannelConfig {
     #[serde(default)]
     pub model: Option<String>,
     #[serde(default)]
+    pub receipt_command: String,
+    #[serde(default)]
+    pub read_receipts: Option<String>,
+    #[serde(default)]
     pub accounts: HashMap<String, SignalAccountConfig>,
     #[serde(default)]
     pub default_account: Option<String>,
@@ -131,6 +156,8 @@ pub struct SignalRuntimeContext {
     pub webhook_token: String,
     pub bot_username: String,
     pub model: Option<String>,
+    pub receipt_command: String,
+    pub read_receipts: ReadReceiptMode,
 }
 
 fn pick_default_account_id(
@@ -206,6 +233,11 @@ pub fn build_signal_runtime_contexts(config: &crate::config::Config) -> Vec<Sign
             .map(str::trim)
             .filter(|v| !v.is_empty())
             .map(ToOwned::to_owned);
+        let receipt_command = if account_cfg.receipt_command.trim().is_empty() {
+            sig_cfg.receipt_command.trim().to_string()
+        } else {
+            account_cfg.receipt_command.trim().to_string()
+        };
         runtimes.push(SignalRuntimeContext {
             channel_name,
             send_command,
@@ -213,6 +245,11 @@ pub fn build_signal_runtime_contexts(config: &crate::config::Config) -> Vec<Sign
             webhook_token,
             bot_username,
             model,
+            receipt_command,
+            read_receipts: resolve_read_receipt_mode(
+                account_cfg.read_receipts.as_deref(),
+                sig_cfg.read_receipts.as_deref(),
+            ),
         });
     }
     if runtimes.is_empty() {
@@ -228,6 +265,8 @@ pub fn build_signal_runtime_contexts(config: &crate::config::Config) -> Vec<Sign
                 .map(str::trim)
                 .filter(|v| !v.is_empty())
                 .map(ToOwned::to_owned),
+            receipt_command: sig_cfg.receipt_command.trim().to_string(),
+            read_receipts: resolve_read_receipt_mode(None, sig_cfg.read_receipts.as_deref()),
         });
     }
     runtimes
@@ -275,6 +314,39 @@ impl ChannelAdapter for SignalAdapter {
     }
 }
 
+/// Run the configured receipt command for an inbound message when the
+/// read-receipt policy allows it. Signal identifies messages by sender timestamp.
+fn send_read_receipt_if_allowed(
+    runtime_ctx: &SignalRuntimeContext,
+    sender: &str,
+    message_id: &str,
+    timestamp_ms: Option<i64>,
+    responded: bool,
+) {
+    let command = runtime_ctx.receipt_command.trim();
+    if command.is_empty() || !should_send_read_receipt(runtime_ctx.read_receipts, responded) {
+        return;
+    }
+    let result = Command::new("sh")
+        .arg("-lc")
+        .arg(command)
+        .env("MICROCLAW_SIGNAL_TARGET", sender)
+        .env("MICROCLAW_SIGNAL_MESSAGE_ID", message_id)
+        .env(
+            "MICROCLAW_SIGNAL_TIMESTAMP",
+            timestamp_ms.map(|ts| ts.to_string()).unwrap_or_default(),
+        )
+        .output();
+    match result {
+        Ok(output) if !output.status.success() => error!(
+            "Signal: receipt command failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ),
+        Err(e) => error!("Signal: failed running receipt command: {e}"),
+        Ok(_) => {}
+    }
+}
+
 pub async fn start_signal_bot(_app_state: Arc<AppState>, runtime: SignalRuntimeContext) {
     mark_channel_started(&runtime.channel_name);
     info!("Signal adapter '{}' is ready", runtime.channel_name);
@@ -283,11 +355,12 @@ pub async fn start_signal_bot(_app_state: Arc<AppState>, runtime: SignalRuntimeC
 pub fn register_signal_webhook(router: Router, app_state: Arc<AppState>) -> Router {
     let Some(cfg) = app_state
         .config
+        .load()
         .channel_config::<SignalChannelConfig>("signal")
     else {
         return router;
     };
-    if !app_state.config.channel_enabled("signal") {
+    if !app_state.config.load().channel_enabled("signal") {
         return router;
     }
     let path = cfg.webhook_path.trim();
@@ -311,7 +384,7 @@ async fn signal_webhook_handler(
     headers: HeaderMap,
     payload: SignalWebhookPayload,
 ) -> impl axum::response::IntoResponse {
-    let runtime_contexts = build_signal_runtime_contexts(&app_state.config);
+    let runtime_contexts = build_signal_runtime_contexts(&app_state.config.load());
     let Some(runtime_ctx) = runtime_contexts.first().cloned() else {
         return axum::http::StatusCode::NOT_FOUND;
     };
@@ -400,6 +473,11 @@ async fn process_signal_webhook_message(
     if should_drop_recent_duplicate_message(&runtime_ctx.channel_name, &inbound_message_id) {
         return;
     }
+    let Some(_claim) =
+        claim_inbound_message(&app_state, &runtime_ctx.channel_name, &inbound_message_id).await
+    else {
+        return;
+    };
     if is_slash_command(&text) {
         if let Some(reply) = handle_chat_command(
             &app_state,
@@ -414,16 +492,30 @@ async fn process_signal_webhook_message(
                 runtime_ctx.channel_name.clone(),
                 runtime_ctx.send_command.clone(),
             );
-            let _ = adapter.send_text(&sender, &reply).await;
+            let sent = adapter.send_text(&sender, &reply).await;
+            send_read_receipt_if_allowed(
+                &runtime_ctx,
+                &sender,
+                &payload.message_id,
+                inbound_ts_ms,
+                sent.is_ok(),
+            );
             return;
         }
         let adapter = SignalAdapter::new(
             runtime_ctx.channel_name.clone(),
             runtime_ctx.send_command.clone(),
         );
-        let _ = adapter
+        let sent = adapter
             .send_text(&sender, &unknown_command_response())
             .await;
+        send_read_receipt_if_allowed(
+            &runtime_ctx,
+            &sender,
+            &payload.message_id,
+            inbound_ts_ms,
+            sent.is_ok(),
+        );
         return;
     }
     let stored = StoredMessage {
@@ -434,11 +526,8 @@ async fn process_signal_webhook_message(
         is_from_bot: false,
         timestamp: chrono::Utc::now().to_rfc3339(),
     };
-    let inserted = call_blocking(app_state.db.clone(), move |db| {
-        db.store_message_if_new(&stored)
-    })
-    .await
-    .unwrap_or(false);
+    let inserted =
+        crate::channels::store_inbound_message(&app_state, &runtime_ctx.channel_name, stored).await;
     if !inserted {
         info!(
             "Signal: skipping duplicate message chat_id={} message_id={}",
@@ -447,7 +536,7 @@ async fn process_signal_webhook_message(
         return;
     }
     let (event_tx, mut event_rx) = tokio::sync::mpsc::unbounded_channel::<AgentEvent>();
-    match process_with_agent_with_events(
+    let responded = match process_with_agent_with_events(
         &app_state,
         AgentRequestContext {
             caller_channel: &runtime_ctx.channel_name,
@@ -481,8 +570,10 @@ async fn process_signal_webhook_message(
                         chat_id
                     );
                 }
+                true
             } else if !response.is_empty() {
-                if let Err(e) = adapter.send_text(&sender, &response).await {
+                let sent = adapter.send_text(&sender, &response).await;
+                if let Err(e) = &sent {
                     error!("Signal: failed to send response: {e}");
                 }
                 let bot_msg = StoredMessage {
@@ -495,17 +586,27 @@ async fn process_signal_webhook_message(
                 };
                 let _ =
                     call_blocking(app_state.db.clone(), move |db| db.store_message(&bot_msg)).await;
+                sent.is_ok()
             } else {
-                let _ = adapter
+                adapter
                     .send_text(
                         &sender,
                         "I couldn't produce a visible reply after an automatic retry. Please try again.",
                     )
-                    .await;
+                    .await
+                    .is_ok()
             }
         }
         Err(e) => {
             error!("Signal: error processing message: {e}");
+            false
         }
-    }
+    };
+    send_read_receipt_if_allowed(
+        &runtime_ctx,
+        &sender,
+        &payload.message_id,
+        inbound_ts_ms,
+        responded,
+    );
 }
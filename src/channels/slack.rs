@@ -12,8 +12,8 @@ use crate::agent_engine::should_suppress_user_error;
 use crate::agent_engine::AgentEvent;
 use crate::agent_engine::AgentRequestContext;
 use crate::channels::startup_guard::{
-    mark_channel_started, parse_epoch_ms_from_seconds_fraction, should_drop_pre_start_message,
-    should_drop_recent_duplicate_message,
+    claim_inbound_message, mark_channel_connected, parse_epoch_ms_from_seconds_fraction,
+    should_drop_pre_start_message, should_drop_recent_duplicate_message,
 };
 use crate::chat_commands::maybe_handle_plugin_command;
 use crate::chat_commands::{handle_chat_command, is_slash_command, unknown_command_response};
@@ -760,7 +760,7 @@ pub struct SlackRuntimeContext {
 }
 
 pub async fn start_slack_bot(app_state: Arc<AppState>, runtime: SlackRuntimeContext) {
-    mark_channel_started(&runtime.channel_name);
+    let _connection = mark_channel_connected(&runtime.channel_name);
     let app_token = runtime.app_token.clone();
     let bot_token = runtime.bot_token.clone();
 
@@ -1027,6 +1027,11 @@ async fn handle_slack_message(
     if should_drop_recent_duplicate_message(&runtime.channel_name, &inbound_message_id) {
         return;
     }
+    let Some(_claim) =
+        claim_inbound_message(&app_state, &runtime.channel_name, &inbound_message_id).await
+    else {
+        return;
+    };
 
     let trimmed = text.trim();
     let mention_tag = format!("<@{bot_user_id}>");
@@ -1045,7 +1050,7 @@ async fn handle_slack_message(
     }
 
     if is_slash {
-        if !should_respond && !app_state.config.allow_group_slash_without_mention {
+        if !should_respond && !app_state.config.load().allow_group_slash_without_mention {
             return;
         }
         if let Some(reply) = handle_chat_command(
@@ -1060,9 +1065,13 @@ async fn handle_slack_message(
             let _ = send_slack_response(bot_token, channel, normalized_thread_ts, &reply).await;
             return;
         }
-        if let Some(plugin_response) =
-            maybe_plugin_slash_response(&app_state.config, trimmed, chat_id, &runtime.channel_name)
-                .await
+        if let Some(plugin_response) = maybe_plugin_slash_response(
+            &app_state.config.load(),
+            trimmed,
+            chat_id,
+            &runtime.channel_name,
+        )
+        .await
         {
             let _ = send_slack_response(bot_token, channel, normalized_thread_ts, &plugin_response)
                 .await;
@@ -1100,11 +1109,8 @@ async fn handle_slack_message(
         is_from_bot: false,
         timestamp: chrono::Utc::now().to_rfc3339(),
     };
-    let inserted = call_blocking(app_state.db.clone(), move |db| {
-        db.store_message_if_new(&stored)
-    })
-    .await
-    .unwrap_or(false);
+    let inserted =
+        crate::channels::store_inbound_message(&app_state, &runtime.channel_name, stored).await;
     if !inserted {
         info!(
             "Slack: skipping duplicate message chat_id={} message_id={}",
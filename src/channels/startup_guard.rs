@@ -1,9 +1,14 @@
-use std::collections::HashMap;
-use std::sync::{Mutex, OnceLock};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, OnceLock};
 
-use tracing::info;
+use tracing::{info, warn};
+
+use crate::runtime::AppState;
+use microclaw_core::error::MicroClawError;
+use microclaw_storage::db::{call_blocking, Database};
 
 static CHANNEL_START_MS: OnceLock<Mutex<HashMap<String, i64>>> = OnceLock::new();
+static CONNECTED_CHANNELS: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
 static CHANNEL_RECENT_MESSAGE_IDS: OnceLock<Mutex<HashMap<String, HashMap<String, i64>>>> =
     OnceLock::new();
 const RECENT_DUPLICATE_TTL_MS: i64 = 10 * 60 * 1000;
@@ -17,11 +22,52 @@ fn recent_message_registry() -> &'static Mutex<HashMap<String, HashMap<String, i
     CHANNEL_RECENT_MESSAGE_IDS.get_or_init(|| Mutex::new(HashMap::new()))
 }
 
+fn connected_registry() -> &'static Mutex<HashSet<String>> {
+    CONNECTED_CHANNELS.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
 pub fn mark_channel_started(channel_name: &str) {
     let now_ms = chrono::Utc::now().timestamp_millis();
     if let Ok(mut map) = registry().lock() {
         map.insert(channel_name.to_string(), now_ms);
     }
+    if let Ok(mut connected) = connected_registry().lock() {
+        connected.insert(channel_name.to_string());
+    }
+}
+
+/// Drops the channel from [`connected_channels`]. The start time is kept so
+/// the pre-start guard still applies until the channel starts again.
+pub fn mark_channel_stopped(channel_name: &str) {
+    if let Ok(mut connected) = connected_registry().lock() {
+        connected.remove(channel_name);
+    }
+}
+
+/// Marks a long-running channel connected until the guard is dropped, so a
+/// connection loop that returns or panics stops counting as connected.
+#[must_use = "the channel counts as connected only while the guard is held"]
+pub struct ChannelConnection(String);
+
+pub fn mark_channel_connected(channel_name: &str) -> ChannelConnection {
+    mark_channel_started(channel_name);
+    ChannelConnection(channel_name.to_string())
+}
+
+impl Drop for ChannelConnection {
+    fn drop(&mut self) {
+        mark_channel_stopped(&self.0);
+    }
+}
+
+/// Channels that are currently connected, sorted by name.
+pub fn connected_channels() -> Vec<String> {
+    let mut names: Vec<String> = connected_registry()
+        .lock()
+        .map(|set| set.iter().cloned().collect())
+        .unwrap_or_default();
+    names.sort();
+    names
 }
 
 pub fn should_drop_pre_start_message(
@@ -81,6 +127,158 @@ pub fn should_drop_recent_duplicate_message(channel_name: &str, message_id: &str
     false
 }
 
+const MESSAGE_CLAIM_RETENTION_MS: i64 = 24 * 60 * 60 * 1000;
+
+/// Identifies this process when claiming messages in a shared database.
+fn instance_id() -> &'static str {
+    static INSTANCE_ID: OnceLock<String> = OnceLock::new();
+    INSTANCE_ID.get_or_init(|| uuid::Uuid::new_v4().to_string())
+}
+
+/// A held claim on an inbound message. Keep it alive while the message is
+/// handled: the lease is renewed in the background so a long run never lets
+/// another instance take the message over, and dropping the claim marks the
+/// message completed in the database so it is not handled again.
+/// A claim dropped during a panic stops renewing and is left to expire.
+pub struct MessageClaim {
+    completion: Option<(Arc<Database>, String, String)>,
+    renewal: Option<tokio::task::JoinHandle<()>>,
+}
+
+impl MessageClaim {
+    fn untracked() -> Self {
+        Self {
+            completion: None,
+            renewal: None,
+        }
+    }
+}
+
+impl Drop for MessageClaim {
+    fn drop(&mut self) {
+        if let Some(renewal) = self.renewal.take() {
+            renewal.abort();
+        }
+        let Some((db, channel, message_id)) = self.completion.take() else {
+            return;
+        };
+        if std::thread::panicking() {
+            return;
+        }
+        let Ok(handle) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        handle.spawn(async move {
+            let now_ms = chrono::Utc::now().timestamp_millis();
+            if let Err(e) = call_blocking(db, move |db| {
+                db.complete_message_claim(&channel, &message_id, now_ms)
+            })
+            .await
+            {
+                warn!("Channel claim guard: failed to mark message completed: {e}");
+            }
+        });
+    }
+}
+
+/// Re-claim the message every third of the lease until aborted, so the claim
+/// never expires while this instance is still handling it.
+fn spawn_claim_renewal(
+    db: Arc<Database>,
+    channel: String,
+    message_id: String,
+    lease_ms: i64,
+) -> tokio::task::JoinHandle<()> {
+    let period = std::time::Duration::from_millis((lease_ms / 3).max(1) as u64);
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(period);
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            let now_ms = chrono::Utc::now().timestamp_millis();
+            let (channel, message_id) = (channel.clone(), message_id.clone());
+            match call_blocking(db.clone(), move |db| {
+                db.claim_message(&channel, &message_id, instance_id(), now_ms, lease_ms)
+            })
+            .await
+            {
+                Ok(true) => {}
+                Ok(false) => {
+                    warn!("Channel claim guard: lost the claim while handling the message");
+                    return;
+                }
+                Err(e) => warn!("Channel claim guard: failed to renew claim: {e}"),
+            }
+        }
+    })
+}
+
+/// Claim a message for this instance and keep the claim renewed while the
+/// returned [`MessageClaim`] is alive. Returns `Ok(None)` when another
+/// instance holds the message or it was already handled.
+async fn claim_message_with_lease(
+    db: Arc<Database>,
+    channel_name: &str,
+    message_id: &str,
+    lease_ms: i64,
+) -> Result<Option<MessageClaim>, MicroClawError> {
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    let channel = channel_name.to_string();
+    let id = message_id.to_string();
+    let claimed = call_blocking(db.clone(), move |db| {
+        let claimed = db.claim_message(&channel, &id, instance_id(), now_ms, lease_ms)?;
+        if claimed {
+            db.prune_message_claims_before(now_ms - MESSAGE_CLAIM_RETENTION_MS.max(lease_ms))?;
+        }
+        Ok(claimed)
+    })
+    .await?;
+    if !claimed {
+        return Ok(None);
+    }
+    let renewal = spawn_claim_renewal(
+        db.clone(),
+        channel_name.to_string(),
+        message_id.to_string(),
+        lease_ms,
+    );
+    Ok(Some(MessageClaim {
+        completion: Some((db, channel_name.to_string(), message_id.to_string())),
+        renewal: Some(renewal),
+    }))
+}
+
+/// Claim an inbound message in the database when `message_claim_enabled` is
+/// set. Returns `None` when another instance holds the message or it was
+/// already handled. Database errors fail open so a broken claim table never
+/// silences the bot.
+pub async fn claim_inbound_message(
+    state: &AppState,
+    channel_name: &str,
+    message_id: &str,
+) -> Option<MessageClaim> {
+    let message_id = message_id.trim();
+    if !state.config.load().message_claim_enabled || message_id.is_empty() {
+        return Some(MessageClaim::untracked());
+    }
+    let lease_ms =
+        (state.config.load().message_claim_lease_secs.max(1) as i64).saturating_mul(1000);
+    match claim_message_with_lease(state.db.clone(), channel_name, message_id, lease_ms).await {
+        Ok(Some(claim)) => Some(claim),
+        Ok(None) => {
+            info!(
+                "Channel claim guard: message already claimed or handled channel={} message_id={}",
+                channel_name, message_id
+            );
+            None
+        }
+        Err(e) => {
+            warn!("Channel claim guard: claim failed, handling message anyway: {e}");
+            Some(MessageClaim::untracked())
+        }
+    }
+}
+
 pub fn parse_epoch_ms_from_str(raw: &str) -> Option<i64> {
     raw.trim().parse::<i64>().ok()
 }
@@ -96,7 +294,17 @@ pub fn parse_epoch_ms_from_seconds_fraction(raw: &str) -> Option<i64> {
 
 #[cfg(test)]
 mod tests {
-    use super::should_drop_recent_duplicate_message;
+    use super::*;
+
+    #[test]
+    fn test_channel_connection_guard_tracks_disconnect() {
+        let channel = "test.startup_guard.connection";
+        let connection = mark_channel_connected(channel);
+        assert!(connected_channels().contains(&channel.to_string()));
+        drop(connection);
+        assert!(!connected_channels().contains(&channel.to_string()));
+        assert!(should_drop_pre_start_message(channel, "old", Some(0)));
+    }
 
     #[test]
     fn test_recent_duplicate_message_guard() {
@@ -105,4 +313,32 @@ mod tests {
         assert!(!should_drop_recent_duplicate_message(channel, message));
         assert!(should_drop_recent_duplicate_message(channel, message));
     }
+
+    #[tokio::test]
+    async fn test_message_claim_is_renewed_while_held() {
+        let dir =
+            std::env::temp_dir().join(format!("microclaw_claim_renewal_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let db = Arc::new(Database::new(dir.to_str().unwrap()).unwrap());
+        let lease_ms = 300;
+
+        let claim = claim_message_with_lease(db.clone(), "telegram", "1:42", lease_ms)
+            .await
+            .unwrap()
+            .expect("first claim succeeds");
+        // Outlive the lease several times over while the claim is held.
+        tokio::time::sleep(std::time::Duration::from_millis(lease_ms as u64 * 3)).await;
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        assert!(!db
+            .claim_message("telegram", "1:42", "other", now_ms, lease_ms)
+            .unwrap());
+
+        drop(claim);
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        let now_ms = chrono::Utc::now().timestamp_millis() + lease_ms * 2;
+        assert!(!db
+            .claim_message("telegram", "1:42", "other", now_ms, lease_ms)
+            .unwrap());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, OnceLock};
 use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
@@ -9,14 +9,21 @@ use teloxide::prelude::*;
 use teloxide::types::{ChatAction, InputFile, MessageId, ParseMode, ThreadId};
 use tracing::{debug, error, info, warn};
 
+#[cfg(test)]
+use crate::agent_engine::sanitize_xml;
 use crate::agent_engine::{
-    process_with_agent_with_events, should_suppress_user_error, AgentEvent, AgentRequestContext,
+    process_with_agent_with_images, should_suppress_user_error, AgentEvent, AgentRequestContext,
 };
+use crate::channels::message_edits::{handle_message_edit, MessageEdit};
 use crate::channels::startup_guard::{
-    mark_channel_started, should_drop_pre_start_message, should_drop_recent_duplicate_message,
+    claim_inbound_message, mark_channel_connected, should_drop_pre_start_message,
+    should_drop_recent_duplicate_message,
 };
 use crate::chat_commands::maybe_handle_plugin_command;
-use crate::chat_commands::{handle_chat_command, is_slash_command, unknown_command_response};
+use crate::chat_commands::{
+    handle_chat_command, is_catchup_command, is_slash_command, streaming_enabled_for_chat,
+    unknown_command_response,
+};
 use crate::runtime::AppState;
 use microclaw_channels::channel::ConversationKind;
 use microclaw_channels::channel_adapter::ChannelAdapter;
@@ -54,7 +61,7 @@ fn default_streaming_enabled() -> bool {
 }
 
 fn default_edit_interval_ms() -> u64 {
-    500 // Edit every 500ms to balance responsiveness and rate limits
+    1500 // At most one edit per 1.5s to stay clear of Telegram's flood limits
 }
 
 fn default_max_edits_per_message() -> usize {
@@ -76,22 +83,74 @@ pub enum ReasoningDisplayMode {
 
 /// State for streaming message editing
 struct StreamingState {
-    message_id: MessageId,
     buffer: String,
     reasoning_buffer: String,
     in_reasoning: bool,
+    /// Tool currently running, shown under the streamed text.
+    tool_status: Option<String>,
     edit_count: usize,
+    /// Whether the displayed text changed since the last edit.
+    dirty: bool,
 }
 
 impl StreamingState {
-    fn new(message_id: MessageId) -> Self {
+    fn new() -> Self {
         Self {
-            message_id,
             buffer: String::new(),
             reasoning_buffer: String::new(),
             in_reasoning: false,
+            tool_status: None,
             edit_count: 0,
+            dirty: false,
+        }
+    }
+
+    /// Add a streamed text delta. Returns true when the delta closes a
+    /// reasoning block.
+    fn push_delta(&mut self, delta: &str) -> bool {
+        if delta.contains("<thinking>") || delta.contains("<reasoning>") {
+            self.in_reasoning = true;
+            return false;
+        }
+        if delta.contains("</thinking>") || delta.contains("</reasoning>") {
+            self.in_reasoning = false;
+            return true;
+        }
+        if self.in_reasoning {
+            self.reasoning_buffer.push_str(delta);
+        } else {
+            self.buffer.push_str(delta);
+            self.tool_status = None;
+        }
+        self.dirty = true;
+        false
+    }
+
+    /// Text for the next edit, or `None` while there is nothing to show.
+    fn display_text(&self, reasoning_display: &ReasoningDisplayMode) -> Option<String> {
+        let mut text = if self.in_reasoning && *reasoning_display == ReasoningDisplayMode::Inline {
+            format!(
+                "🧠 *Thinking...*\n{}\n\n💭 {}",
+                self.reasoning_buffer.chars().take(200).collect::<String>(),
+                self.buffer
+            )
+        } else {
+            self.buffer.clone()
+        };
+        if let Some(tool) = &self.tool_status {
+            if !text.is_empty() {
+                text.push_str("\n\n");
+            }
+            text.push_str(&format!("🔧 Using tool: {tool}"));
         }
+        if text.trim().is_empty() {
+            return None;
+        }
+        if text.len() > STREAM_PREVIEW_MAX_BYTES {
+            text.truncate(floor_char_boundary(&text, STREAM_PREVIEW_MAX_BYTES));
+            text.push_str("...(truncated)");
+        }
+        Some(text)
     }
 }
 
@@ -209,6 +268,45 @@ impl ChannelAdapter for TelegramAdapter {
         Ok(())
     }
 
+    async fn send_editable_text(
+        &self,
+        external_chat_id: &str,
+        text: &str,
+    ) -> Result<Option<String>, String> {
+        let telegram_chat_id = external_chat_id
+            .parse::<i64>()
+            .map_err(|_| format!("Invalid Telegram external_chat_id '{}'", external_chat_id))?;
+        // A reply split over several messages cannot be edited as one.
+        let chunks = split_response_text(text);
+        if chunks.len() > 1 {
+            send_response(&self.bot, ChatId(telegram_chat_id), text, None).await;
+            return Ok(None);
+        }
+        let sent = send_telegram_markdown_or_plain(&self.bot, ChatId(telegram_chat_id), text, None)
+            .await
+            .ok_or_else(|| "Failed to send Telegram message".to_string())?;
+        Ok(Some(sent.0.to_string()))
+    }
+
+    async fn edit_text(
+        &self,
+        external_chat_id: &str,
+        message_id: &str,
+        text: &str,
+    ) -> Result<(), String> {
+        let telegram_chat_id = external_chat_id
+            .parse::<i64>()
+            .map_err(|_| format!("Invalid Telegram external_chat_id '{}'", external_chat_id))?;
+        let message_id = message_id
+            .parse::<i32>()
+            .map_err(|_| format!("Invalid Telegram message id '{}'", message_id))?;
+        let chat_id = ChatId(telegram_chat_id);
+        let message_id = MessageId(message_id);
+        edit_telegram_markdown_or_plain(&self.bot, chat_id, message_id, text)
+            .await
+            .map_err(|e| format!("Failed to edit Telegram message: {e}"))
+    }
+
     async fn send_attachment(
         &self,
         external_chat_id: &str,
@@ -252,13 +350,58 @@ impl ChannelAdapter for TelegramAdapter {
     }
 }
 
-/// Escape XML special characters in user-supplied content to prevent prompt injection.
-/// User messages are wrapped in XML tags; escaping ensures the content cannot break out.
-fn sanitize_xml(s: &str) -> String {
-    s.replace('&', "&amp;")
-        .replace('<', "&lt;")
-        .replace('>', "&gt;")
-        .replace('"', "&quot;")
+/// Whether a document should be read as text. Telegram often reports
+/// `application/octet-stream` for text files, so the extension is checked too.
+fn is_text_like_document(file_name: &str, mime: &str) -> bool {
+    let mime = mime.trim().to_ascii_lowercase();
+    let mime = mime.split(';').next().unwrap_or("").trim();
+    if matches!(
+        mime,
+        "text/plain" | "text/markdown" | "text/x-markdown" | "text/csv" | "application/json"
+    ) {
+        return true;
+    }
+    let ext = Path::new(file_name)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase);
+    matches!(
+        ext.as_deref(),
+        Some("txt" | "md" | "markdown" | "json" | "csv")
+    )
+}
+
+/// Describe a downloaded document for the agent. Text-like UTF-8 files are
+/// inlined as `[attached file <name>]: <contents>`, cut at `max_inline_bytes`;
+/// anything else becomes a `[document: <name>, <mime>, <size>]` placeholder.
+fn document_note(
+    file_name: &str,
+    mime: &str,
+    bytes: &[u8],
+    saved_path: Option<&str>,
+    max_inline_bytes: usize,
+) -> String {
+    let saved = saved_path
+        .map(|p| format!(" saved_path={p}"))
+        .unwrap_or_default();
+    let contents = (max_inline_bytes > 0 && is_text_like_document(file_name, mime))
+        .then(|| std::str::from_utf8(bytes).ok())
+        .flatten();
+    let Some(contents) = contents else {
+        return format!(
+            "[document: {file_name}, {mime}, {} bytes]{saved}",
+            bytes.len()
+        );
+    };
+    if contents.len() <= max_inline_bytes {
+        return format!("[attached file {file_name}]: {contents}");
+    }
+    let cut = floor_char_boundary(contents, max_inline_bytes);
+    format!(
+        "[attached file {file_name}]: {}\n[truncated: showing {cut} of {} bytes]{saved}",
+        &contents[..cut],
+        contents.len()
+    )
 }
 
 /// Format a user message with XML escaping and wrapping to clearly delimit user content.
@@ -282,6 +425,16 @@ pub struct TelegramRuntimeContext {
     pub streaming: TelegramStreamingConfig,
 }
 
+/// The group allowlist from the current config snapshot, so a reload
+/// applies to a running bot.
+fn current_allowed_groups(state: &AppState, tg_ctx: &TelegramRuntimeContext) -> Vec<i64> {
+    build_telegram_runtime_contexts(&state.config.load())
+        .into_iter()
+        .find(|(_, ctx)| ctx.channel_name == tg_ctx.channel_name)
+        .map(|(_, ctx)| ctx.allowed_groups)
+        .unwrap_or_else(|| tg_ctx.allowed_groups.clone())
+}
+
 pub fn build_telegram_runtime_contexts(
     config: &crate::config::Config,
 ) -> Vec<(String, TelegramRuntimeContext)> {
@@ -429,8 +582,10 @@ pub async fn start_telegram_bot(
         }
     }
 
-    mark_channel_started(&ctx.channel_name);
-    let handler = Update::filter_message().endpoint(handle_message);
+    let _connection = mark_channel_connected(&ctx.channel_name);
+    let handler = dptree::entry()
+        .branch(Update::filter_message().endpoint(handle_message))
+        .branch(Update::filter_edited_message().endpoint(handle_edited_message));
     let channel_name = ctx.channel_name.clone();
     let listener = teloxide::update_listeners::polling_default(bot.clone()).await;
     let listener_error_handler = teloxide::error_handlers::LoggingErrorHandler::with_custom_text(
@@ -489,14 +644,9 @@ fn check_private_chat_access(
     true
 }
 
-async fn handle_message(
-    bot: Bot,
-    msg: teloxide::types::Message,
-    state: Arc<AppState>,
-    tg_ctx: TelegramRuntimeContext,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let raw_chat_id = msg.chat.id.0;
-    let (runtime_chat_type, db_chat_type) = match msg.chat.kind {
+/// (agent chat type, stored chat type) for a Telegram chat.
+fn telegram_chat_types(kind: &teloxide::types::ChatKind) -> (&'static str, &'static str) {
+    match kind {
         teloxide::types::ChatKind::Private(_) => ("private", "telegram_private"),
         teloxide::types::ChatKind::Public(teloxide::types::ChatPublic {
             kind: teloxide::types::PublicChatKind::Group,
@@ -510,12 +660,120 @@ async fn handle_message(
             kind: teloxide::types::PublicChatKind::Channel(_),
             ..
         }) => ("group", "telegram_channel"),
+    }
+}
+
+/// How a group message addresses the bot: (mentioned by username, text
+/// mention, reply to one of the bot's messages).
+fn group_bot_addressing(
+    msg: &teloxide::types::Message,
+    text: &str,
+    bot_username: &str,
+    bot_user_id: Option<u64>,
+) -> (bool, bool, bool) {
+    let bot_mention = format!("@{}", bot_username);
+    let mentioned = text
+        .to_ascii_lowercase()
+        .contains(&bot_mention.to_ascii_lowercase());
+    let text_mentions_bot = bot_user_id
+        .map(|bot_id| {
+            msg.entities().is_some_and(|entities| {
+                entities.iter().any(|e| match &e.kind {
+                    teloxide::types::MessageEntityKind::TextMention { user } => user.id.0 == bot_id,
+                    _ => false,
+                })
+            })
+        })
+        .unwrap_or(false);
+    let replied_to_bot = msg
+        .reply_to_message()
+        .and_then(|m| m.from.as_ref())
+        .map(|u| {
+            u.is_bot && (Some(u.id.0) == bot_user_id || u.username.as_deref() == Some(bot_username))
+        })
+        .unwrap_or(false);
+    (mentioned, text_mentions_bot, replied_to_bot)
+}
+
+async fn handle_edited_message(
+    msg: teloxide::types::Message,
+    state: Arc<AppState>,
+    tg_ctx: TelegramRuntimeContext,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    // Only plain text edits are mirrored. Captions of photos and documents
+    // are stored together with attachment notes that an edit cannot rebuild.
+    let Some(text) = msg.text().map(str::to_string) else {
+        return Ok(());
     };
+    if is_slash_command(&text) {
+        return Ok(());
+    }
+    let raw_chat_id = msg.chat.id.0;
+    let (runtime_chat_type, db_chat_type) = telegram_chat_types(&msg.chat.kind);
+    let sender_user_id = msg.from.as_ref().and_then(|u| i64::try_from(u.id.0).ok());
+    if !check_private_chat_access(
+        db_chat_type,
+        &tg_ctx.allowed_user_ids,
+        sender_user_id,
+        raw_chat_id,
+    ) {
+        return Ok(());
+    }
+    let group_allowed =
+        !(db_chat_type == "telegram_group" || db_chat_type == "telegram_supergroup") || {
+            let allowed_groups = current_allowed_groups(&state, &tg_ctx);
+            allowed_groups.is_empty() || allowed_groups.contains(&raw_chat_id)
+        };
+    let addresses_bot = group_allowed
+        && (runtime_chat_type == "private" || {
+            let (mentioned, text_mentions_bot, replied_to_bot) =
+                group_bot_addressing(&msg, &text, &tg_ctx.bot_username, tg_ctx.bot_user_id);
+            mentioned || text_mentions_bot || replied_to_bot
+        });
+
+    let external_chat_id = raw_chat_id.to_string();
+    let chat_title = msg.chat.title().map(|t| t.to_string());
+    let channel_name = tg_ctx.channel_name.clone();
+    let chat_id = call_blocking(state.db.clone(), move |db| {
+        db.resolve_or_create_chat_id(
+            &channel_name,
+            &external_chat_id,
+            chat_title.as_deref(),
+            db_chat_type,
+        )
+    })
+    .await
+    .unwrap_or(raw_chat_id);
+
+    let message_id = msg.id.0.to_string();
+    handle_message_edit(
+        &state,
+        &MessageEdit {
+            channel_name: &tg_ctx.channel_name,
+            chat_id,
+            chat_type: runtime_chat_type,
+            message_id: &message_id,
+            content: &text,
+            addresses_bot,
+        },
+    )
+    .await;
+    Ok(())
+}
+
+async fn handle_message(
+    bot: Bot,
+    msg: teloxide::types::Message,
+    state: Arc<AppState>,
+    tg_ctx: TelegramRuntimeContext,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let raw_chat_id = msg.chat.id.0;
+    let (runtime_chat_type, db_chat_type) = telegram_chat_types(&msg.chat.kind);
     let chat_title = msg.chat.title().map(|t| t.to_string());
     let tg_channel_name = tg_ctx.channel_name.clone();
     let tg_bot_username = tg_ctx.bot_username.clone();
     let tg_bot_user_id = tg_ctx.bot_user_id;
-    let tg_allowed_groups = tg_ctx.allowed_groups.clone();
+    let tg_allowed_groups = current_allowed_groups(&state, &tg_ctx);
     let tg_allowed_user_ids = tg_ctx.allowed_user_ids.clone();
     let sender_user_id = msg.from.as_ref().and_then(|u| i64::try_from(u.id.0).ok());
 
@@ -531,37 +789,14 @@ async fn handle_message(
 
     // Extract content: text, photo, or voice
     let mut text = msg.text().unwrap_or("").to_string();
-    let mut image_data: Option<(String, String)> = None; // (base64, media_type)
+    let mut images: Vec<(String, String)> = Vec::new(); // (base64, media_type)
     let mut document_saved_path: Option<String> = None;
 
     let (mentioned, text_mentions_bot, replied_to_bot, should_respond) = match runtime_chat_type {
         "private" => (false, false, false, true),
         _ => {
-            let bot_mention = format!("@{}", tg_bot_username);
-            let mentioned = text
-                .to_ascii_lowercase()
-                .contains(&bot_mention.to_ascii_lowercase());
-            let text_mentions_bot = tg_bot_user_id
-                .map(|bot_id| {
-                    msg.entities().is_some_and(|entities| {
-                        entities.iter().any(|e| match &e.kind {
-                            teloxide::types::MessageEntityKind::TextMention { user } => {
-                                user.id.0 == bot_id
-                            }
-                            _ => false,
-                        })
-                    })
-                })
-                .unwrap_or(false);
-            let replied_to_bot = msg
-                .reply_to_message()
-                .and_then(|m| m.from.as_ref())
-                .map(|u| {
-                    u.is_bot
-                        && (Some(u.id.0) == tg_bot_user_id
-                            || u.username.as_deref() == Some(tg_bot_username.as_str()))
-                })
-                .unwrap_or(false);
+            let (mentioned, text_mentions_bot, replied_to_bot) =
+                group_bot_addressing(&msg, &text, &tg_bot_username, tg_bot_user_id);
             (
                 mentioned,
                 text_mentions_bot,
@@ -593,7 +828,20 @@ async fn handle_message(
         if should_drop_recent_duplicate_message(&tg_channel_name, &inbound_message_id) {
             return Ok(());
         }
-        if !should_respond && !state.config.allow_group_slash_without_mention {
+        let claim_id = format!("{raw_chat_id}:{inbound_message_id}");
+        let Some(_claim) = claim_inbound_message(&state, &tg_channel_name, &claim_id).await else {
+            return Ok(());
+        };
+        if !should_respond && !state.config.load().allow_group_slash_without_mention {
+            return Ok(());
+        }
+        // Commands run before the group allowlist check below; keep catch-up
+        // digests of group content to allowed groups.
+        if is_catchup_command(&text)
+            && (db_chat_type == "telegram_group" || db_chat_type == "telegram_supergroup")
+            && !tg_allowed_groups.is_empty()
+            && !tg_allowed_groups.contains(&raw_chat_id)
+        {
             return Ok(());
         }
         let sender_id_text = sender_user_id.map(|v| v.to_string());
@@ -624,7 +872,8 @@ async fn handle_message(
             return Ok(());
         }
         if let Some(plugin_response) =
-            maybe_plugin_slash_response(&state.config, &text, chat_id, &tg_channel_name).await
+            maybe_plugin_slash_response(&state.config.load(), &text, chat_id, &tg_channel_name)
+                .await
         {
             let _ = bot.send_message(msg.chat.id, plugin_response).await;
             return Ok(());
@@ -636,13 +885,14 @@ async fn handle_message(
     }
 
     if let Some(photos) = msg.photo() {
+        let mut image = None;
         // Pick the largest photo (last in the array)
         if let Some(photo) = photos.last() {
             match download_telegram_file(&bot, &photo.file.id.0).await {
                 Ok(bytes) => {
                     let base64 = base64_encode(&bytes);
                     let media_type = guess_image_media_type(&bytes);
-                    image_data = Some((base64, media_type));
+                    image = Some((base64, media_type));
                 }
                 Err(e) => {
                     error!("Failed to download photo: {e}");
@@ -653,12 +903,56 @@ async fn handle_message(
         if text.is_empty() {
             text = msg.caption().unwrap_or("").to_string();
         }
+        match msg.media_group_id() {
+            // Albums arrive as one update per photo. The first update waits
+            // for the rest and answers for the whole album.
+            Some(group_id) => {
+                let key = format!("{tg_channel_name}:{raw_chat_id}:{}", group_id.0);
+                let part = AlbumPart {
+                    message_id: msg.id.0,
+                    image,
+                    caption: text.clone(),
+                    should_respond,
+                };
+                if !media_groups().push(&key, part) {
+                    return Ok(());
+                }
+                // Teloxide hands this chat's next update over only after the
+                // handler returns, so the wait for the other parts is spawned.
+                let group_id = group_id.0.clone();
+                tokio::spawn(async move {
+                    tokio::time::sleep(MEDIA_GROUP_WINDOW).await;
+                    let album = merge_album(media_groups().take(&key), MAX_IMAGES_PER_RUN);
+                    if album.dropped_images > 0 {
+                        info!(
+                            "Telegram album {} has more than {} images; dropping {}",
+                            group_id, MAX_IMAGES_PER_RUN, album.dropped_images
+                        );
+                    }
+                    let content = InboundContent {
+                        text: album.caption,
+                        images: album.images,
+                        document_saved_path: None,
+                        should_respond: should_respond || album.should_respond,
+                        mentioned,
+                        text_mentions_bot,
+                        replied_to_bot,
+                    };
+                    if let Err(e) = store_and_answer(bot, msg, state, tg_ctx, content).await {
+                        error!("Failed to handle Telegram album {group_id}: {e}");
+                    }
+                });
+                return Ok(());
+            }
+            None => images.extend(image),
+        }
     }
 
     // Handle document messages (text/code/file attachments)
     if let Some(document) = msg.document() {
         let max_bytes = state
             .config
+            .load()
             .max_document_size_mb
             .saturating_mul(1024)
             .saturating_mul(1024);
@@ -669,7 +963,8 @@ async fn handle_message(
                     msg.chat.id,
                     format!(
                         "Document is too large ({} bytes). Max allowed is {} MB.",
-                        doc_bytes, state.config.max_document_size_mb
+                        doc_bytes,
+                        state.config.load().max_document_size_mb
                     ),
                 )
                 .await;
@@ -690,7 +985,7 @@ async fn handle_message(
                     })
                     .collect::<String>();
 
-                let dir = Path::new(&state.config.working_dir)
+                let dir = Path::new(&state.config.load().working_dir)
                     .join("uploads")
                     .join(tg_channel_name.replace('/', "_"))
                     .join(raw_chat_id.to_string());
@@ -709,19 +1004,17 @@ async fn handle_message(
                     }
                 }
 
-                let file_note = format!(
-                    "[document] filename={} bytes={} mime={}{}",
+                let mime = document
+                    .mime_type
+                    .as_ref()
+                    .map(|m| m.to_string())
+                    .unwrap_or_else(|| "application/octet-stream".to_string());
+                let file_note = document_note(
                     original_name,
-                    bytes.len(),
-                    document
-                        .mime_type
-                        .as_ref()
-                        .map(|m| m.to_string())
-                        .unwrap_or_else(|| "application/octet-stream".to_string()),
-                    document_saved_path
-                        .as_ref()
-                        .map(|p| format!(" saved_path={}", p))
-                        .unwrap_or_default(),
+                    &mime,
+                    &bytes,
+                    document_saved_path.as_deref(),
+                    state.config.load().max_inline_document_bytes,
                 );
 
                 if text.trim().is_empty() {
@@ -745,56 +1038,113 @@ async fn handle_message(
 
     // Handle voice messages
     if let Some(voice) = msg.voice() {
-        // Check if voice transcription is configured
-        let can_transcribe = if state.config.voice_provider == "local" {
-            state.config.voice_transcription_command.is_some()
-        } else {
-            state.config.openai_api_key.is_some()
-        };
-
-        if can_transcribe {
-            match download_telegram_file(&bot, &voice.file.id.0).await {
-                Ok(bytes) => {
-                    let sender_name = msg
-                        .from
-                        .as_ref()
-                        .map(|u| u.username.clone().unwrap_or_else(|| u.first_name.clone()))
-                        .unwrap_or_else(|| "Unknown".into());
-                    match transcribe_audio(&state.config, &bytes).await {
-                        Ok(transcription) => {
-                            text = format!(
-                                "[voice message from {}]: {}",
-                                sanitize_xml(&sender_name),
-                                sanitize_xml(&transcription)
-                            );
-                        }
-                        Err(e) => {
-                            error!("Voice transcription failed: {e}");
-                            text = format!(
-                                "[voice message from {}]: [transcription failed: {e}]",
-                                sanitize_xml(&sender_name)
-                            );
-                        }
-                    }
-                }
-                Err(e) => {
-                    error!("Failed to download voice message: {e}");
-                }
-            }
-        } else {
-            let provider = &state.config.voice_provider;
-            let msg_text = if provider == "local" {
-                "Voice messages not supported (local transcription configured but voice_transcription_command not set)"
-            } else {
-                "Voice messages not supported (no Whisper API key configured)"
-            };
-            let _ = bot.send_message(msg.chat.id, msg_text).await;
+        let config = state.config.load();
+        if !crate::voice::transcription_available(&config) {
+            let _ = bot
+                .send_message(
+                    msg.chat.id,
+                    crate::voice::transcription_unavailable_message(&config),
+                )
+                .await;
             return Ok(());
         }
+        match download_telegram_file(&bot, &voice.file.id.0).await {
+            Ok(bytes) => {
+                let sender_name = msg
+                    .from
+                    .as_ref()
+                    .map(|u| u.username.clone().unwrap_or_else(|| u.first_name.clone()))
+                    .unwrap_or_else(|| "Unknown".into());
+                let duration_secs = Some(u64::from(voice.duration.seconds()));
+                let external_chat_id = raw_chat_id.to_string();
+                let chat_title_for_lookup = chat_title.clone();
+                let chat_type_for_lookup = db_chat_type.to_string();
+                let channel_name = tg_channel_name.clone();
+                let recent = call_blocking(state.db.clone(), move |db| {
+                    let chat_id = db.resolve_or_create_chat_id(
+                        &channel_name,
+                        &external_chat_id,
+                        chat_title_for_lookup.as_deref(),
+                        &chat_type_for_lookup,
+                    )?;
+                    db.get_recent_messages(chat_id, VOICE_PROMPT_CONTEXT_MESSAGES)
+                })
+                .await
+                .unwrap_or_default();
+                let recent: Vec<String> = recent.into_iter().map(|m| m.content).collect();
+                let options = crate::voice::TranscriptionOptions {
+                    language: config.voice_languages.get(&raw_chat_id).cloned(),
+                    prompt: Some(crate::voice::transcription_prompt(
+                        &tg_bot_username,
+                        &recent,
+                    )),
+                    mime_type: voice.mime_type.as_ref().map(|m| m.to_string()),
+                };
+                text = crate::voice::voice_message_text(
+                    &config,
+                    &sender_name,
+                    &bytes,
+                    duration_secs,
+                    &options,
+                )
+                .await;
+            }
+            Err(e) => {
+                error!("Failed to download voice message: {e}");
+            }
+        }
     }
 
+    let content = InboundContent {
+        text,
+        images,
+        document_saved_path,
+        should_respond,
+        mentioned,
+        text_mentions_bot,
+        replied_to_bot,
+    };
+    store_and_answer(bot, msg, state, tg_ctx, content).await
+}
+
+/// What one update (or a whole album) carries once media is downloaded.
+struct InboundContent {
+    text: String,
+    images: Vec<(String, String)>,
+    document_saved_path: Option<String>,
+    should_respond: bool,
+    mentioned: bool,
+    text_mentions_bot: bool,
+    replied_to_bot: bool,
+}
+
+/// Store an inbound message and, when the bot is addressed, answer it.
+async fn store_and_answer(
+    bot: Bot,
+    msg: teloxide::types::Message,
+    state: Arc<AppState>,
+    tg_ctx: TelegramRuntimeContext,
+    content: InboundContent,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let InboundContent {
+        text,
+        images,
+        document_saved_path,
+        should_respond,
+        mentioned,
+        text_mentions_bot,
+        replied_to_bot,
+    } = content;
+    let raw_chat_id = msg.chat.id.0;
+    let (runtime_chat_type, db_chat_type) = telegram_chat_types(&msg.chat.kind);
+    let chat_title = msg.chat.title().map(|t| t.to_string());
+    let tg_channel_name = tg_ctx.channel_name.clone();
+    let tg_bot_username = tg_ctx.bot_username.clone();
+    let tg_bot_user_id = tg_ctx.bot_user_id;
+    let tg_allowed_groups = current_allowed_groups(&state, &tg_ctx);
+
     // If no text/image/document content, nothing to process
-    if text.trim().is_empty() && image_data.is_none() && document_saved_path.is_none() {
+    if text.trim().is_empty() && images.is_empty() && document_saved_path.is_none() {
         return Ok(());
     }
 
@@ -814,6 +1164,11 @@ async fn handle_message(
     if should_drop_recent_duplicate_message(&tg_channel_name, &inbound_message_id) {
         return Ok(());
     }
+    // Telegram message ids are only unique within a chat.
+    let claim_id = format!("{raw_chat_id}:{inbound_message_id}");
+    let Some(_claim) = claim_inbound_message(&state, &tg_channel_name, &claim_id).await else {
+        return Ok(());
+    };
 
     // Check group allowlist
     if (db_chat_type == "telegram_group" || db_chat_type == "telegram_supergroup")
@@ -841,9 +1196,10 @@ async fn handle_message(
             db.upsert_chat(chat_id, chat_title_owned.as_deref(), &chat_type_owned)
         })
         .await;
-        let stored_content = if image_data.is_some() {
+        let stored_content = if !images.is_empty() {
             format!(
-                "[image]{}",
+                "{}{}",
+                image_marker(images.len()),
                 if text.trim().is_empty() {
                     String::new()
                 } else {
@@ -867,7 +1223,7 @@ async fn handle_message(
             is_from_bot: false,
             timestamp: chrono::Utc::now().to_rfc3339(),
         };
-        let _ = call_blocking(state.db.clone(), move |db| db.store_message_if_new(&stored)).await;
+        crate::channels::store_inbound_message(&state, &tg_channel_name, stored).await;
         return Ok(());
     }
 
@@ -894,9 +1250,10 @@ async fn handle_message(
     })
     .await;
 
-    let stored_content = if image_data.is_some() {
+    let stored_content = if !images.is_empty() {
         format!(
-            "[image]{}",
+            "{}{}",
+            image_marker(images.len()),
             if text.trim().is_empty() {
                 String::new()
             } else {
@@ -920,9 +1277,7 @@ async fn handle_message(
         is_from_bot: false,
         timestamp: chrono::Utc::now().to_rfc3339(),
     };
-    let inserted = call_blocking(state.db.clone(), move |db| db.store_message_if_new(&stored))
-        .await
-        .unwrap_or(false);
+    let inserted = crate::channels::store_inbound_message(&state, &tg_channel_name, stored).await;
     if !inserted {
         info!(
             "Skipping duplicate Telegram message: chat_id={}, message_id={}",
@@ -946,94 +1301,112 @@ async fn handle_message(
         return Ok(());
     }
 
-    info!(
-        "Processing message from {} in chat {}: {}",
-        sender_name,
-        chat_id,
-        text.chars().take(100).collect::<String>()
-    );
+    // One agent run per chat at a time. Teloxide hands this chat's next
+    // update over only after this handler returns, so the run is spawned:
+    // messages arriving meanwhile queue behind it and a burst is answered
+    // by a single follow-up run.
+    tokio::spawn(async move {
+        // Hold the claim until the run ends, not just until the handler returns.
+        let _claim = _claim;
+        let Some(_run_turn) = state.chat_run_queue.acquire(chat_id).await else {
+            info!(
+                "Coalescing Telegram message into a newer queued run: chat_id={}, message_id={}",
+                chat_id, inbound_message_id
+            );
+            return;
+        };
 
-    // Start continuous typing indicator
-    let typing_chat_id = msg.chat.id;
-    let typing_bot = bot.clone();
-    let typing_handle = tokio::spawn(async move {
-        loop {
-            let _ = typing_bot
-                .send_chat_action(typing_chat_id, ChatAction::Typing)
-                .await;
-            tokio::time::sleep(std::time::Duration::from_secs(4)).await;
-        }
-    });
+        info!(
+            "Processing message from {} in chat {}: {}",
+            sender_name,
+            chat_id,
+            text.chars().take(100).collect::<String>()
+        );
 
-    // Check if streaming is enabled for this chat
-    let streaming_config = tg_ctx.streaming.clone();
-    let use_streaming = streaming_config.enabled;
+        // Start continuous typing indicator
+        let typing_chat_id = msg.chat.id;
+        let typing_bot = bot.clone();
+        let typing_handle = tokio::spawn(async move {
+            loop {
+                let _ = typing_bot
+                    .send_chat_action(typing_chat_id, ChatAction::Typing)
+                    .await;
+                tokio::time::sleep(std::time::Duration::from_secs(4)).await;
+            }
+        });
 
-    // Process through platform-agnostic agent engine.
-    let (event_tx, mut event_rx) = tokio::sync::mpsc::unbounded_channel::<AgentEvent>();
-    match process_with_agent_with_events(
-        &state,
-        AgentRequestContext {
-            caller_channel: &tg_channel_name,
+        // Check if streaming is enabled for this chat
+        let streaming_config = tg_ctx.streaming.clone();
+        let use_streaming = streaming_enabled_for_chat(
+            state.db.clone(),
             chat_id,
-            chat_type: runtime_chat_type,
-        },
-        None,
-        image_data,
-        Some(&event_tx),
-    )
-    .await
-    {
-        Ok(response) => {
-            typing_handle.abort();
-            // Important: close local sender before reading all events to avoid hanging recv loop.
-            drop(event_tx);
-            // Try streaming if enabled
-            let mut used_streaming = false;
-            if use_streaming && !response.is_empty() {
-                match send_streaming_response(
-                    &bot,
-                    msg.chat.id,
-                    &mut event_rx,
-                    &response,
-                    msg.thread_id,
-                    &streaming_config,
-                )
-                .await
-                {
-                    Ok(_) => {
-                        used_streaming = true;
-                        // Store bot response
-                        let bot_msg = StoredMessage {
-                            id: uuid::Uuid::new_v4().to_string(),
-                            chat_id,
-                            sender_name: tg_bot_username.clone(),
-                            content: response.clone(),
-                            is_from_bot: true,
-                            timestamp: chrono::Utc::now().to_rfc3339(),
-                        };
-                        let _ =
-                            call_blocking(state.db.clone(), move |db| db.store_message(&bot_msg))
-                                .await;
-                    }
-                    Err(e) => {
-                        warn!("Streaming failed, falling back to regular send: {}", e);
-                        // Fall through to regular send
+            streaming_config.enabled || state.config.load().telegram_streaming,
+        )
+        .await;
+
+        // With streaming on, the reply is edited into a placeholder while the
+        // agent is still running.
+        let (event_tx, event_rx) = tokio::sync::mpsc::unbounded_channel::<AgentEvent>();
+        let mut event_rx = Some(event_rx);
+        let mut live_stream = None;
+        if use_streaming {
+            match send_stream_placeholder(&bot, msg.chat.id, msg.thread_id, &streaming_config).await
+            {
+                Ok(placeholder_id) => {
+                    if let Some(rx) = event_rx.take() {
+                        let task = tokio::spawn(stream_agent_events(
+                            bot.clone(),
+                            msg.chat.id,
+                            placeholder_id,
+                            msg.thread_id,
+                            rx,
+                            streaming_config.clone(),
+                            typing_handle.abort_handle(),
+                        ));
+                        live_stream = Some((placeholder_id, task));
                     }
                 }
+                Err(e) => warn!("Streaming placeholder failed, falling back to regular send: {e}"),
             }
+        }
 
-            if !used_streaming {
-                let mut used_send_message_tool = false;
-                while let Some(event) = event_rx.recv().await {
-                    if let AgentEvent::ToolStart { name, .. } = event {
-                        if name == "send_message" {
-                            used_send_message_tool = true;
+        // Process through platform-agnostic agent engine.
+        let result = process_with_agent_with_images(
+            &state,
+            AgentRequestContext {
+                caller_channel: &tg_channel_name,
+                chat_id,
+                chat_type: runtime_chat_type,
+            },
+            None,
+            images,
+            Some(&event_tx),
+        )
+        .await;
+        typing_handle.abort();
+        // Important: close local sender before reading all events to avoid hanging recv loop.
+        drop(event_tx);
+        let (placeholder_id, stream_outcome) = match live_stream {
+            Some((placeholder_id, task)) => (Some(placeholder_id), task.await.unwrap_or_default()),
+            None => {
+                let mut outcome = LiveStreamOutcome::default();
+                if let Some(mut rx) = event_rx {
+                    while let Some(event) = rx.recv().await {
+                        if let AgentEvent::ToolStart { name, .. } = event {
+                            if name == "send_message" {
+                                outcome.used_send_message_tool = true;
+                            }
                         }
                     }
                 }
+                (None, outcome)
+            }
+        };
 
-                if used_send_message_tool {
+        match result {
+            Ok(response) => {
+                if stream_outcome.used_send_message_tool {
+                    delete_stream_placeholder(&bot, msg.chat.id, placeholder_id).await;
                     if !response.is_empty() {
                         info!(
                             "Suppressing final response for chat {} because send_message already delivered output",
@@ -1046,7 +1419,21 @@ async fn handle_message(
                         );
                     }
                 } else if !response.is_empty() {
-                    send_response(&bot, msg.chat.id, &response, msg.thread_id).await;
+                    match placeholder_id {
+                        Some(placeholder_id) => {
+                            finish_live_stream(
+                                &bot,
+                                msg.chat.id,
+                                placeholder_id,
+                                msg.thread_id,
+                                &response,
+                                &streaming_config,
+                                stream_outcome.reasoning_sent,
+                            )
+                            .await
+                        }
+                        None => send_response(&bot, msg.chat.id, &response, msg.thread_id).await,
+                    }
 
                     // Store bot response
                     let bot_msg = StoredMessage {
@@ -1060,6 +1447,7 @@ async fn handle_message(
                     let _ =
                         call_blocking(state.db.clone(), move |db| db.store_message(&bot_msg)).await;
                 } else {
+                    delete_stream_placeholder(&bot, msg.chat.id, placeholder_id).await;
                     let fallback = "I couldn't produce a visible reply after an automatic retry. Please try again.".to_string();
                     send_response(&bot, msg.chat.id, &fallback, msg.thread_id).await;
                     let bot_msg = StoredMessage {
@@ -1074,97 +1462,124 @@ async fn handle_message(
                         call_blocking(state.db.clone(), move |db| db.store_message(&bot_msg)).await;
                 }
             }
-        }
-        Err(e) => {
-            typing_handle.abort();
-            error!("Error processing message: {}", e);
-            if !should_suppress_user_error(&e) {
-                let mut req = bot.send_message(msg.chat.id, format!("Error: {e}"));
-                if let Some(tid) = msg.thread_id {
-                    req = req.message_thread_id(tid);
+            Err(e) => {
+                delete_stream_placeholder(&bot, msg.chat.id, placeholder_id).await;
+                error!("Error processing message: {}", e);
+                if !should_suppress_user_error(&e) {
+                    let mut req = bot.send_message(msg.chat.id, format!("Error: {e}"));
+                    if let Some(tid) = msg.thread_id {
+                        req = req.message_thread_id(tid);
+                    }
+                    let _ = req.await;
                 }
-                let _ = req.await;
             }
         }
-    }
+    });
 
     Ok(())
 }
 
-async fn download_telegram_file(
-    bot: &Bot,
-    file_id: &str,
-) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
-    let file = bot
-        .get_file(teloxide::types::FileId(file_id.to_string()))
-        .await?;
-    let mut buf = Vec::new();
-    teloxide::net::Download::download_file(bot, &file.path, &mut buf).await?;
-    Ok(buf)
+/// How long the first photo of an album waits for the rest to arrive.
+const MEDIA_GROUP_WINDOW: Duration = Duration::from_millis(1500);
+/// Most images sent to the model for one album.
+const MAX_IMAGES_PER_RUN: usize = 5;
+
+/// One photo update of a Telegram album (media group).
+#[derive(Debug)]
+struct AlbumPart {
+    message_id: i32,
+    image: Option<(String, String)>,
+    caption: String,
+    should_respond: bool,
 }
 
-/// Transcribe audio using configured provider (openai or local)
-pub async fn transcribe_audio(
-    config: &crate::config::Config,
-    audio_bytes: &[u8],
-) -> Result<String, String> {
-    let provider = &config.voice_provider;
-
-    if provider == "local" {
-        // Use local transcription command
-        let Some(ref command) = config.voice_transcription_command else {
-            return Err(
-                "Local voice transcription configured but voice_transcription_command not set"
-                    .into(),
-            );
-        };
+/// Album photos collected so far, keyed by channel, chat and media group id.
+#[derive(Default)]
+struct MediaGroupBuffer {
+    groups: Mutex<HashMap<String, Vec<AlbumPart>>>,
+}
 
-        // Write audio to a temp file
-        let temp_dir = std::env::temp_dir();
-        let temp_file = temp_dir.join(format!("voice_{}.ogg", uuid::Uuid::new_v4()));
-        tokio::fs::write(&temp_file, audio_bytes)
-            .await
-            .map_err(|e| e.to_string())?;
+impl MediaGroupBuffer {
+    /// Add a part. Returns true for the first part of a group, whose handler
+    /// then takes the whole group once the window has passed.
+    fn push(&self, key: &str, part: AlbumPart) -> bool {
+        let mut groups = self.groups.lock().unwrap_or_else(|e| e.into_inner());
+        let parts = groups.entry(key.to_string()).or_default();
+        parts.push(part);
+        parts.len() == 1
+    }
 
-        // Replace {file} placeholder with actual path
-        let cmd = command.replace("{file}", temp_file.to_str().unwrap_or(""));
+    fn take(&self, key: &str) -> Vec<AlbumPart> {
+        let mut groups = self.groups.lock().unwrap_or_else(|e| e.into_inner());
+        groups.remove(key).unwrap_or_default()
+    }
+}
 
-        // Execute the command
-        let output_result = tokio::process::Command::new("sh")
-            .arg("-c")
-            .arg(&cmd)
-            .output()
-            .await;
+fn media_groups() -> &'static MediaGroupBuffer {
+    static MEDIA_GROUPS: OnceLock<MediaGroupBuffer> = OnceLock::new();
+    MEDIA_GROUPS.get_or_init(MediaGroupBuffer::default)
+}
 
-        // Clean up temp file
-        let _ = tokio::fs::remove_file(&temp_file).await;
+#[derive(Debug, PartialEq)]
+struct Album {
+    images: Vec<(String, String)>,
+    caption: String,
+    should_respond: bool,
+    dropped_images: usize,
+}
 
-        let output =
-            output_result.map_err(|e| format!("Failed to run transcription command: {}", e))?;
+/// Combine album parts in message order. The caption comes from whichever
+/// photo carries one.
+fn merge_album(mut parts: Vec<AlbumPart>, max_images: usize) -> Album {
+    parts.sort_by_key(|p| p.message_id);
+    let caption = parts
+        .iter()
+        .map(|p| p.caption.trim())
+        .find(|c| !c.is_empty())
+        .unwrap_or("")
+        .to_string();
+    let should_respond = parts.iter().any(|p| p.should_respond);
+    let mut images: Vec<(String, String)> = parts.into_iter().filter_map(|p| p.image).collect();
+    let dropped_images = images.len().saturating_sub(max_images);
+    images.truncate(max_images);
+    Album {
+        images,
+        caption,
+        should_respond,
+        dropped_images,
+    }
+}
 
-        if output.status.success() {
-            Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
-        } else {
-            Err(format!(
-                "Transcription command failed: {}",
-                String::from_utf8_lossy(&output.stderr)
-            ))
-        }
+/// Stored-message prefix for a message with images.
+fn image_marker(count: usize) -> String {
+    if count > 1 {
+        format!("[{count} images]")
     } else {
-        // Default to OpenAI Whisper API
-        let Some(ref openai_key) = config.openai_api_key else {
-            return Err("Voice transcription requires openai_api_key".into());
-        };
-        microclaw_app::transcribe::transcribe_audio(openai_key, audio_bytes).await
+        "[image]".to_string()
     }
 }
 
+/// Recent chat messages fed to Whisper as a prompt for voice notes.
+const VOICE_PROMPT_CONTEXT_MESSAGES: usize = 5;
+
+async fn download_telegram_file(
+    bot: &Bot,
+    file_id: &str,
+) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    let file = bot
+        .get_file(teloxide::types::FileId(file_id.to_string()))
+        .await?;
+    let mut buf = Vec::new();
+    teloxide::net::Download::download_file(bot, &file.path, &mut buf).await?;
+    Ok(buf)
+}
+
 fn base64_encode(data: &[u8]) -> String {
     use base64::Engine;
     base64::engine::general_purpose::STANDARD.encode(data)
 }
 
-fn guess_image_media_type(data: &[u8]) -> String {
+pub(crate) fn guess_image_media_type(data: &[u8]) -> String {
     if data.starts_with(&[0x89, 0x50, 0x4E, 0x47]) {
         "image/png".into()
     } else if data.starts_with(&[0xFF, 0xD8]) {
@@ -1370,185 +1785,215 @@ fn parse_reasoning_blocks(text: &str) -> (Option<String>, String) {
     (None, text.to_string())
 }
 
-/// Send a streaming response with message editing
-async fn send_streaming_response(
+/// Longest text shown while a reply is still streaming; the final edit splits
+/// the full reply into 4096-character messages instead.
+const STREAM_PREVIEW_MAX_BYTES: usize = 4000;
+
+#[derive(Debug, Default)]
+struct LiveStreamOutcome {
+    used_send_message_tool: bool,
+    reasoning_sent: bool,
+}
+
+/// Send the message a streamed reply is edited into.
+async fn send_stream_placeholder(
     bot: &Bot,
     chat_id: ChatId,
-    event_rx: &mut tokio::sync::mpsc::UnboundedReceiver<AgentEvent>,
-    final_response: &str,
     message_thread_id: Option<ThreadId>,
     config: &TelegramStreamingConfig,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    // Send initial placeholder
+) -> Result<MessageId, teloxide::RequestError> {
     let initial_text = if config.reasoning_display == ReasoningDisplayMode::Hidden {
         "Thinking..."
     } else {
         "🔍 Reasoning..."
     };
-
-    let mut initial_req = bot.send_message(chat_id, initial_text);
+    let mut req = bot.send_message(chat_id, initial_text);
     if let Some(tid) = message_thread_id {
-        initial_req = initial_req.message_thread_id(tid);
+        req = req.message_thread_id(tid);
     }
+    Ok(req.await?.id)
+}
 
-    let initial_msg = initial_req.await?;
-    let mut streaming_state = StreamingState::new(initial_msg.id);
-    let mut reasoning_message_id: Option<MessageId> = None;
-
-    // Set up edit interval
-    let edit_interval = Duration::from_millis(config.edit_interval_ms);
-    let mut last_edit_time = Instant::now();
-
-    // Process events
-    while let Some(event) = event_rx.recv().await {
-        match event {
-            AgentEvent::TextDelta { delta } => {
-                // Check for reasoning block markers
-                if delta.contains("<thinking>") || delta.contains("<reasoning>") {
-                    streaming_state.in_reasoning = true;
-                    continue;
-                }
-                if delta.contains("</thinking>") || delta.contains("</reasoning>") {
-                    streaming_state.in_reasoning = false;
-
-                    // Handle separate reasoning message
-                    if config.reasoning_display == ReasoningDisplayMode::SeparateMessage
-                        && !streaming_state.reasoning_buffer.is_empty()
-                    {
-                        let reasoning_text = format!(
-                            "🧠 *Reasoning:*\n```\n{}\n```",
-                            streaming_state
-                                .reasoning_buffer
-                                .chars()
-                                .take(3800)
-                                .collect::<String>()
-                        );
-                        let mut reasoning_req = bot.send_message(chat_id, reasoning_text);
-                        if let Some(tid) = message_thread_id {
-                            reasoning_req = reasoning_req.message_thread_id(tid);
-                        }
-                        if let Ok(msg) = reasoning_req.parse_mode(ParseMode::MarkdownV2).await {
-                            reasoning_message_id = Some(msg.id);
-                        }
-                    }
-                    continue;
-                }
-
-                if streaming_state.in_reasoning {
-                    streaming_state.reasoning_buffer.push_str(&delta);
-                } else {
-                    streaming_state.buffer.push_str(&delta);
-                }
-
-                // Check if we should edit now
-                let should_edit = last_edit_time.elapsed() >= edit_interval
-                    || streaming_state.edit_count < 3 // Edit more frequently at start
-                    || streaming_state.buffer.len().is_multiple_of(100); // Edit every 100 chars
-
-                if should_edit && streaming_state.edit_count < config.max_edits_per_message {
-                    let display_text = if streaming_state.in_reasoning
-                        && config.reasoning_display == ReasoningDisplayMode::Inline
-                    {
-                        format!(
-                            "🧠 *Thinking...*\n{}\n\n💭 {}",
-                            streaming_state
-                                .reasoning_buffer
-                                .chars()
-                                .take(200)
-                                .collect::<String>(),
-                            &streaming_state.buffer
-                        )
-                    } else if !streaming_state.buffer.is_empty() {
-                        streaming_state.buffer.clone()
-                    } else {
-                        continue; // Skip empty edits
-                    };
-
-                    // Truncate if too long for Telegram (4096 limit)
-                    let edit_text = if display_text.len() > 4000 {
-                        format!("{}...(truncated)", &display_text[..4000])
-                    } else {
-                        display_text
-                    };
+async fn delete_stream_placeholder(bot: &Bot, chat_id: ChatId, message_id: Option<MessageId>) {
+    if let Some(message_id) = message_id {
+        if let Err(e) = bot.delete_message(chat_id, message_id).await {
+            debug!("Failed to delete streaming placeholder: {e}");
+        }
+    }
+}
 
-                    // Edit message - note: edit_message_text doesn't support message_thread_id
-                    // as the message already knows its context
-                    let edit_req =
-                        bot.edit_message_text(chat_id, streaming_state.message_id, edit_text);
+/// Returns whether the message was sent.
+async fn send_reasoning_message(
+    bot: &Bot,
+    chat_id: ChatId,
+    reasoning: &str,
+    message_thread_id: Option<ThreadId>,
+) -> bool {
+    let text = format!(
+        "🧠 *Reasoning:*\n```\n{}\n```",
+        reasoning.chars().take(3800).collect::<String>()
+    );
+    let mut req = bot.send_message(chat_id, text);
+    if let Some(tid) = message_thread_id {
+        req = req.message_thread_id(tid);
+    }
+    req.parse_mode(ParseMode::MarkdownV2).await.is_ok()
+}
 
-                    // Try to edit with text (ParseMode doesn't work the same way for edits)
-                    if edit_req.await.is_err() {
-                        let _ = bot
-                            .edit_message_text(
+/// Edit `message_id` with the agent's text as it streams in, until every
+/// event sender is dropped. Edits are debounced to one per `edit_interval_ms`
+/// and at most `max_edits_per_message`, and pause when Telegram asks us to
+/// retry later. The typing indicator stops at the first text delta.
+async fn stream_agent_events(
+    bot: Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    message_thread_id: Option<ThreadId>,
+    mut event_rx: tokio::sync::mpsc::UnboundedReceiver<AgentEvent>,
+    config: TelegramStreamingConfig,
+    typing: tokio::task::AbortHandle,
+) -> LiveStreamOutcome {
+    let mut streaming_state = StreamingState::new();
+    let mut outcome = LiveStreamOutcome::default();
+    let mut ticker = tokio::time::interval(Duration::from_millis(config.edit_interval_ms.max(1)));
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let mut resume_at = Instant::now();
+
+    loop {
+        tokio::select! {
+            event = event_rx.recv() => {
+                let Some(event) = event else {
+                    break;
+                };
+                match event {
+                    AgentEvent::TextDelta { delta } => {
+                        typing.abort();
+                        let closed_reasoning = streaming_state.push_delta(&delta);
+                        if closed_reasoning
+                            && config.reasoning_display == ReasoningDisplayMode::SeparateMessage
+                            && !outcome.reasoning_sent
+                            && !streaming_state.reasoning_buffer.is_empty()
+                        {
+                            outcome.reasoning_sent = send_reasoning_message(
+                                &bot,
                                 chat_id,
-                                streaming_state.message_id,
-                                &streaming_state.buffer,
+                                &streaming_state.reasoning_buffer,
+                                message_thread_id,
                             )
                             .await;
+                        }
                     }
-
-                    streaming_state.edit_count += 1;
-                    last_edit_time = Instant::now();
+                    AgentEvent::ToolStart { name, .. } => {
+                        if name == "send_message" {
+                            outcome.used_send_message_tool = true;
+                        }
+                        streaming_state.tool_status = Some(name);
+                        streaming_state.dirty = true;
+                    }
+                    _ => {}
                 }
             }
-            AgentEvent::ToolStart { name, .. } => {
-                // Show tool usage
-                if streaming_state.edit_count < config.max_edits_per_message {
-                    let tool_text =
-                        format!("{}\n\n🔧 Using tool: {}", streaming_state.buffer, name);
-                    let _ = bot
-                        .edit_message_text(chat_id, streaming_state.message_id, &tool_text)
-                        .await;
+            _ = ticker.tick() => {
+                if !streaming_state.dirty
+                    || streaming_state.edit_count >= config.max_edits_per_message
+                    || Instant::now() < resume_at
+                {
+                    continue;
+                }
+                let Some(text) = streaming_state.display_text(&config.reasoning_display) else {
+                    continue;
+                };
+                streaming_state.dirty = false;
+                streaming_state.edit_count += 1;
+                match bot.edit_message_text(chat_id, message_id, text).await {
+                    Err(teloxide::RequestError::RetryAfter(wait)) => {
+                        resume_at = Instant::now() + wait.duration();
+                        streaming_state.dirty = true;
+                    }
+                    Err(e) => debug!("Telegram streaming edit failed: {e}"),
+                    Ok(_) => {}
                 }
             }
-            _ => {} // Ignore other events
         }
     }
+    outcome
+}
 
-    // Final edit with complete response
+/// Replace the streamed placeholder with the final reply, sending any part
+/// beyond Telegram's 4096-character limit as follow-up messages.
+async fn finish_live_stream(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    message_thread_id: Option<ThreadId>,
+    final_response: &str,
+    config: &TelegramStreamingConfig,
+    reasoning_sent: bool,
+) {
     let (reasoning, answer) = parse_reasoning_blocks(final_response);
-
-    // Send reasoning if not already sent and mode is separate
-    if config.reasoning_display == ReasoningDisplayMode::SeparateMessage {
-        if let Some(reasoning_text) = reasoning {
-            if reasoning_message_id.is_none() && !reasoning_text.is_empty() {
-                let formatted_reasoning = format!(
-                    "🧠 *Reasoning:*\n```\n{}\n```",
-                    reasoning_text.chars().take(3800).collect::<String>()
-                );
-                let mut reasoning_req = bot.send_message(chat_id, formatted_reasoning);
-                if let Some(tid) = message_thread_id {
-                    reasoning_req = reasoning_req.message_thread_id(tid);
-                }
-                let _ = reasoning_req.parse_mode(ParseMode::MarkdownV2).await;
-            }
+    if config.reasoning_display == ReasoningDisplayMode::SeparateMessage && !reasoning_sent {
+        if let Some(reasoning) = reasoning.filter(|r| !r.is_empty()) {
+            send_reasoning_message(bot, chat_id, &reasoning, message_thread_id).await;
         }
     }
 
-    // Final message edit - note: edit_message_text doesn't need message_thread_id
     let final_text = if answer.is_empty() {
         final_response
     } else {
         &answer
     };
-    let final_edit = bot.edit_message_text(chat_id, streaming_state.message_id, final_text);
-
-    // Try to edit - ParseMode doesn't work the same way for edits
-    if final_edit.await.is_err() {
-        let _ = bot
-            .edit_message_text(chat_id, streaming_state.message_id, final_text)
-            .await;
+    let mut chunks = split_response_text(final_text).into_iter();
+    if let Some(first) = chunks.next() {
+        let mut edited = edit_telegram_markdown_or_plain(bot, chat_id, message_id, &first).await;
+        if let Err(teloxide::RequestError::RetryAfter(wait)) = edited {
+            tokio::time::sleep(wait.duration()).await;
+            edited = edit_telegram_markdown_or_plain(bot, chat_id, message_id, &first).await;
+        }
+        if let Err(e) = edited {
+            warn!("Final streaming edit failed, sending as a new message: {e}");
+            delete_stream_placeholder(bot, chat_id, Some(message_id)).await;
+            send_telegram_markdown_or_plain(bot, chat_id, &first, message_thread_id).await;
+        }
+    }
+    for chunk in chunks {
+        send_telegram_markdown_or_plain(bot, chat_id, &chunk, message_thread_id).await;
     }
+}
 
-    Ok(())
+/// Edit a message as MarkdownV2, retrying as plain text when Telegram cannot
+/// parse it. An unchanged message counts as success.
+async fn edit_telegram_markdown_or_plain(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    text: &str,
+) -> Result<(), teloxide::RequestError> {
+    let markdown = bot
+        .edit_message_text(chat_id, message_id, render_markdown_v2_safe(text))
+        .parse_mode(ParseMode::MarkdownV2)
+        .await;
+    let result = match markdown {
+        Err(teloxide::RequestError::Api(
+            teloxide::ApiError::CantParseEntities(_) | teloxide::ApiError::Unknown(_),
+        )) => bot
+            .edit_message_text(chat_id, message_id, text)
+            .await
+            .map(|_| ()),
+        other => other.map(|_| ()),
+    };
+    match result {
+        Err(teloxide::RequestError::Api(teloxide::ApiError::MessageNotModified)) => Ok(()),
+        other => other,
+    }
 }
 
+/// Returns the id of the sent message, if either attempt succeeded.
 async fn send_telegram_markdown_or_plain(
     bot: &Bot,
     chat_id: ChatId,
     text: &str,
     message_thread_id: Option<ThreadId>,
-) {
+) -> Option<MessageId> {
     let markdown_text = render_markdown_v2_safe(text);
     let mut req = bot
         .send_message(chat_id, markdown_text)
@@ -1558,13 +2003,16 @@ async fn send_telegram_markdown_or_plain(
         req = req.message_thread_id(tid);
     }
 
-    if let Err(err) = req.await {
-        warn!("Telegram MarkdownV2 send failed, falling back to plain text: {err}");
-        let mut plain_req = bot.send_message(chat_id, text);
-        if let Some(tid) = message_thread_id {
-            plain_req = plain_req.message_thread_id(tid);
+    match req.await {
+        Ok(sent) => Some(sent.id),
+        Err(err) => {
+            warn!("Telegram MarkdownV2 send failed, falling back to plain text: {err}");
+            let mut plain_req = bot.send_message(chat_id, text);
+            if let Some(tid) = message_thread_id {
+                plain_req = plain_req.message_thread_id(tid);
+            }
+            plain_req.await.ok().map(|sent| sent.id)
         }
-        let _ = plain_req.await;
     }
 }
 
@@ -1575,13 +2023,125 @@ pub async fn send_response(
     message_thread_id: Option<ThreadId>,
 ) {
     for chunk in split_response_text(text) {
-        send_telegram_markdown_or_plain(bot, chat_id, &chunk, message_thread_id).await;
+        let _ = send_telegram_markdown_or_plain(bot, chat_id, &chunk, message_thread_id).await;
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    fn album_part(message_id: i32, image: Option<&str>, caption: &str) -> AlbumPart {
+        AlbumPart {
+            message_id,
+            image: image.map(|data| (data.to_string(), "image/jpeg".to_string())),
+            caption: caption.to_string(),
+            should_respond: false,
+        }
+    }
+
+    #[test]
+    fn test_media_group_buffer_first_part_leads() {
+        let buffer = MediaGroupBuffer::default();
+        assert!(buffer.push("tg:1:g", album_part(10, Some("a"), "")));
+        assert!(!buffer.push("tg:1:g", album_part(11, Some("b"), "")));
+        assert!(buffer.push("tg:1:other", album_part(12, Some("c"), "")));
+        assert_eq!(buffer.take("tg:1:g").len(), 2);
+        assert!(buffer.take("tg:1:g").is_empty());
+    }
+
+    fn album_photo_update(
+        chat_id: i64,
+        message_id: i32,
+        group_id: &str,
+        caption: Option<&str>,
+    ) -> teloxide::types::Message {
+        let mut update = serde_json::json!({
+            "message_id": message_id,
+            "date": chrono::Utc::now().timestamp(),
+            "chat": {"id": chat_id, "type": "group", "title": "album test"},
+            "from": {"id": 7, "is_bot": false, "first_name": "Ann"},
+            "media_group_id": group_id,
+            "photo": [{
+                "file_id": format!("file-{message_id}"),
+                "file_unique_id": format!("unique-{message_id}"),
+                "width": 1,
+                "height": 1,
+                "file_size": 1
+            }]
+        });
+        if let Some(caption) = caption {
+            update["caption"] = serde_json::json!(caption);
+        }
+        serde_json::from_value(update).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_album_parts_handled_in_order_merge_into_one_message() {
+        let dir = std::env::temp_dir().join(format!("mc_tg_album_{}", uuid::Uuid::new_v4()));
+        let state = crate::test_support::app_state(
+            &dir,
+            crate::llm::create_provider(&crate::config::Config::test_defaults()),
+            |_| {},
+        );
+        // Downloads fail fast; the album still carries its caption.
+        let bot =
+            Bot::new("1:test").set_api_url(reqwest::Url::parse("http://127.0.0.1:9/").unwrap());
+        let tg_ctx = TelegramRuntimeContext {
+            channel_name: "telegram".into(),
+            bot_username: "album_test_bot".into(),
+            bot_user_id: None,
+            allowed_groups: vec![],
+            allowed_user_ids: vec![],
+            model: None,
+            streaming: TelegramStreamingConfig::default(),
+        };
+        let salt = uuid::Uuid::new_v4().as_u128() as u16;
+        let raw_chat_id = -(100_000 + i64::from(salt));
+        let first_id = 1_000 + i32::from(salt);
+        let group_id = uuid::Uuid::new_v4().to_string();
+
+        // Teloxide awaits the handler before passing on the chat's next
+        // update, so the second part only arrives once the first returns.
+        let started = std::time::Instant::now();
+        for (message_id, caption) in [(first_id, Some("what are these?")), (first_id + 1, None)] {
+            let update = album_photo_update(raw_chat_id, message_id, &group_id, caption);
+            handle_message(bot.clone(), update, state.clone(), tg_ctx.clone())
+                .await
+                .unwrap();
+        }
+        assert!(started.elapsed() < MEDIA_GROUP_WINDOW);
+
+        tokio::time::sleep(MEDIA_GROUP_WINDOW + Duration::from_millis(500)).await;
+        let chat_id = state
+            .db
+            .resolve_or_create_chat_id("telegram", &raw_chat_id.to_string(), None, "telegram_group")
+            .unwrap();
+        let stored = state.db.get_recent_messages(chat_id, 10).unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].content, "what are these?");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_merge_album_orders_caps_and_picks_caption() {
+        let mut late = album_part(12, Some("c"), "  what are these? ");
+        late.should_respond = true;
+        let parts = vec![
+            late,
+            album_part(10, Some("a"), ""),
+            album_part(11, None, ""),
+            album_part(13, Some("d"), ""),
+        ];
+        let album = merge_album(parts, 2);
+        let data: Vec<&str> = album.images.iter().map(|(d, _)| d.as_str()).collect();
+        assert_eq!(data, vec!["a", "c"]);
+        assert_eq!(album.caption, "what are these?");
+        assert!(album.should_respond);
+        assert_eq!(album.dropped_images, 1);
+        assert_eq!(image_marker(1), "[image]");
+        assert_eq!(image_marker(3), "[3 images]");
+    }
     use crate::agent_engine::{
         build_system_prompt, history_to_claude_messages, message_to_text, strip_images_for_session,
         strip_thinking,
@@ -1686,7 +2246,7 @@ mod tests {
 
     #[test]
     fn test_build_system_prompt_basic() {
-        let prompt = build_system_prompt("testbot", "telegram", "", 12345, "", "UTC", None);
+        let prompt = build_system_prompt("testbot", "telegram", "", 12345, "", "UTC", None, None);
         assert!(prompt.contains("testbot"));
         assert!(prompt.contains("12345"));
         assert!(prompt.contains("bash commands"));
@@ -1697,7 +2257,7 @@ mod tests {
     #[test]
     fn test_build_system_prompt_with_memory() {
         let memory = "<global_memory>\nUser likes Rust\n</global_memory>";
-        let prompt = build_system_prompt("testbot", "telegram", memory, 42, "", "UTC", None);
+        let prompt = build_system_prompt("testbot", "telegram", memory, 42, "", "UTC", None, None);
         assert!(prompt.contains("# Memories"));
         assert!(prompt.contains("User likes Rust"));
     }
@@ -1705,7 +2265,7 @@ mod tests {
     #[test]
     fn test_build_system_prompt_with_skills() {
         let catalog = "<available_skills>\n- pdf: Convert to PDF\n</available_skills>";
-        let prompt = build_system_prompt("testbot", "telegram", "", 42, catalog, "UTC", None);
+        let prompt = build_system_prompt("testbot", "telegram", "", 42, catalog, "UTC", None, None);
         assert!(prompt.contains("# Agent Skills"));
         assert!(prompt.contains("activate_skill"));
         assert!(prompt.contains("pdf: Convert to PDF"));
@@ -1713,7 +2273,7 @@ mod tests {
 
     #[test]
     fn test_build_system_prompt_without_skills() {
-        let prompt = build_system_prompt("testbot", "telegram", "", 42, "", "UTC", None);
+        let prompt = build_system_prompt("testbot", "telegram", "", 42, "", "UTC", None, None);
         assert!(!prompt.contains("# Agent Skills"));
     }
 
@@ -1842,6 +2402,53 @@ mod tests {
         assert_eq!(chunks[1].len(), 904);
     }
 
+    #[test]
+    fn test_streaming_state_tracks_text_and_reasoning() {
+        let mut state = StreamingState::new();
+        assert_eq!(state.display_text(&ReasoningDisplayMode::Hidden), None);
+        assert!(!state.push_delta("<thinking>"));
+        assert!(!state.push_delta("weighing options"));
+        assert_eq!(state.display_text(&ReasoningDisplayMode::Hidden), None);
+        assert!(state
+            .display_text(&ReasoningDisplayMode::Inline)
+            .unwrap()
+            .contains("weighing options"));
+        assert!(state.push_delta("</thinking>"));
+        state.dirty = false;
+        state.push_delta("Hello");
+        state.push_delta(" world");
+        assert!(state.dirty);
+        assert_eq!(
+            state.display_text(&ReasoningDisplayMode::Hidden).as_deref(),
+            Some("Hello world")
+        );
+        assert_eq!(state.reasoning_buffer, "weighing options");
+    }
+
+    #[test]
+    fn test_streaming_state_shows_tool_until_more_text() {
+        let mut state = StreamingState::new();
+        state.tool_status = Some("web_search".into());
+        assert_eq!(
+            state.display_text(&ReasoningDisplayMode::Hidden).as_deref(),
+            Some("🔧 Using tool: web_search")
+        );
+        state.push_delta("Found it");
+        assert_eq!(
+            state.display_text(&ReasoningDisplayMode::Hidden).as_deref(),
+            Some("Found it")
+        );
+    }
+
+    #[test]
+    fn test_streaming_preview_truncates_on_char_boundary() {
+        let mut state = StreamingState::new();
+        state.push_delta(&"é".repeat(3_000));
+        let text = state.display_text(&ReasoningDisplayMode::Hidden).unwrap();
+        assert!(text.ends_with("...(truncated)"));
+        assert!(text.len() <= STREAM_PREVIEW_MAX_BYTES + "...(truncated)".len());
+    }
+
     #[test]
     fn test_guess_image_media_type_jpeg() {
         let data = vec![0xFF, 0xD8, 0xFF, 0xE0];
@@ -1902,6 +2509,7 @@ mod tests {
                     id: "t1".into(),
                     name: "bash".into(),
                     input: serde_json::json!({"command": "ls"}),
+                    thought_signature: None,
                 },
             ]),
         };
@@ -1998,7 +2606,7 @@ mod tests {
 
     #[test]
     fn test_build_system_prompt_mentions_sub_agent() {
-        let prompt = build_system_prompt("testbot", "telegram", "", 12345, "", "UTC", None);
+        let prompt = build_system_prompt("testbot", "telegram", "", 12345, "", "UTC", None, None);
         assert!(prompt.contains("sub_agent"));
     }
 
@@ -2033,7 +2641,7 @@ mod tests {
 
     #[test]
     fn test_build_system_prompt_mentions_xml_security() {
-        let prompt = build_system_prompt("testbot", "telegram", "", 12345, "", "UTC", None);
+        let prompt = build_system_prompt("testbot", "telegram", "", 12345, "", "UTC", None, None);
         assert!(prompt.contains("user_message"));
         assert!(prompt.contains("untrusted"));
     }
@@ -2122,6 +2730,65 @@ mod tests {
         assert_eq!(sanitize_xml("a < b & c > d"), "a &lt; b &amp; c &gt; d");
     }
 
+    #[test]
+    fn test_document_note_inlines_text_document() {
+        let note = document_note(
+            "notes.md",
+            "text/markdown",
+            b"# Plan\n- ship <it>",
+            Some("/tmp/uploads/notes.md"),
+            64 * 1024,
+        );
+        assert_eq!(note, "[attached file notes.md]: # Plan\n- ship <it>");
+        // Inlined contents still go through the XML escaping of user messages.
+        assert!(format_user_message("alice", &note).contains("ship &lt;it&gt;"));
+
+        // Extension is enough when Telegram reports a generic MIME type.
+        let note = document_note(
+            "data.csv",
+            "application/octet-stream",
+            b"a,b\n1,2",
+            None,
+            100,
+        );
+        assert_eq!(note, "[attached file data.csv]: a,b\n1,2");
+    }
+
+    #[test]
+    fn test_document_note_truncates_oversized_text_document() {
+        let body = "é".repeat(40);
+        let note = document_note(
+            "big.txt",
+            "text/plain",
+            body.as_bytes(),
+            Some("/tmp/uploads/big.txt"),
+            11,
+        );
+        assert_eq!(
+            note,
+            "[attached file big.txt]: ééééé\n[truncated: showing 10 of 80 bytes] saved_path=/tmp/uploads/big.txt"
+        );
+    }
+
+    #[test]
+    fn test_document_note_placeholder_for_binary_document() {
+        let note = document_note(
+            "scan.pdf",
+            "application/pdf",
+            &[0x25, 0x50, 0x44, 0x46],
+            None,
+            1024,
+        );
+        assert_eq!(note, "[document: scan.pdf, application/pdf, 4 bytes]");
+        // Text-like name but invalid UTF-8, or inlining disabled.
+        assert!(
+            document_note("x.txt", "text/plain", &[0xff, 0xfe], None, 1024)
+                .starts_with("[document: x.txt")
+        );
+        assert!(document_note("x.txt", "text/plain", b"hi", Some("/p"), 0)
+            .ends_with("2 bytes] saved_path=/p"));
+    }
+
     #[test]
     fn test_format_user_message_with_empty_content() {
         assert_eq!(
@@ -2227,7 +2894,7 @@ mod tests {
     fn test_build_system_prompt_with_memory_and_skills() {
         let memory = "<global_memory>\nTest\n</global_memory>";
         let skills = "- translate: Translate text";
-        let prompt = build_system_prompt("bot", "telegram", memory, 42, skills, "UTC", None);
+        let prompt = build_system_prompt("bot", "telegram", memory, 42, skills, "UTC", None, None);
         assert!(prompt.contains("# Memories"));
         assert!(prompt.contains("Test"));
         assert!(prompt.contains("# Agent Skills"));
@@ -2236,20 +2903,20 @@ mod tests {
 
     #[test]
     fn test_build_system_prompt_mentions_todo() {
-        let prompt = build_system_prompt("testbot", "telegram", "", 12345, "", "UTC", None);
+        let prompt = build_system_prompt("testbot", "telegram", "", 12345, "", "UTC", None, None);
         assert!(prompt.contains("todo_read"));
         assert!(prompt.contains("todo_write"));
     }
 
     #[test]
     fn test_build_system_prompt_mentions_export() {
-        let prompt = build_system_prompt("testbot", "telegram", "", 12345, "", "UTC", None);
+        let prompt = build_system_prompt("testbot", "telegram", "", 12345, "", "UTC", None, None);
         assert!(prompt.contains("export_chat"));
     }
 
     #[test]
     fn test_build_system_prompt_mentions_schedule() {
-        let prompt = build_system_prompt("testbot", "telegram", "", 12345, "", "UTC", None);
+        let prompt = build_system_prompt("testbot", "telegram", "", 12345, "", "UTC", None, None);
         assert!(prompt.contains("schedule_task"));
         assert!(prompt.contains("6-field cron"));
     }
@@ -9,9 +9,12 @@ use tracing::{error, info};
 
 use crate::agent_engine::process_with_agent_with_events;
 use crate::agent_engine::{should_suppress_user_error, AgentEvent, AgentRequestContext};
+use crate::channels::read_receipts::{
+    resolve_read_receipt_mode, should_send_read_receipt, ReadReceiptMode,
+};
 use crate::channels::startup_guard::{
-    mark_channel_started, parse_epoch_ms_from_seconds_str, should_drop_pre_start_message,
-    should_drop_recent_duplicate_message,
+    claim_inbound_message, mark_channel_started, parse_epoch_ms_from_seconds_str,
+    should_drop_pre_start_message, should_drop_recent_duplicate_message,
 };
 use crate::chat_commands::{handle_chat_command, is_slash_command, unknown_command_response};
 use crate::runtime::AppState;
@@ -81,6 +84,13 @@ pub const SETUP_DEF: DynamicChannelDef = DynamicChannelDef {
             secret: false,
             required: false,
         },
+        ChannelFieldDef {
+            yaml_key: "read_receipts",
+            label: "WhatsApp read receipts: always/on_response/never (default on_response)",
+            default: "on_response",
+            secret: false,
+            required: false,
+        },
     ],
 };
 
@@ -108,6 +118,8 @@ pub struct WhatsAppAccountConfig {
     pub bot_username: String,
     #[serde(default)]
     pub model: Option<String>,
+    #[serde(default)]
+    pub read_receipts: Option<String>,
     #[serde(default = "default_enabled")]
     pub enabled: bool,
 }
@@ -129,6 +141,8 @@ pub struct WhatsAppChannelConfig {
     #[serde(default)]
     pub model: Option<String>,
     #[serde(default)]
+    pub read_receipts: Option<String>,
+    #[serde(default)]
     pub accounts: HashMap<String, WhatsAppAccountConfig>,
     #[serde(default)]
     pub default_account: Option<String>,
@@ -144,6 +158,7 @@ pub struct WhatsAppRuntimeContext {
     pub webhook_verify_token: String,
     pub bot_username: String,
     pub model: Option<String>,
+    pub read_receipts: ReadReceiptMode,
 }
 
 fn pick_default_account_id(
@@ -237,6 +252,10 @@ pub fn build_whatsapp_runtime_contexts(
             webhook_verify_token: verify_token,
             bot_username,
             model,
+            read_receipts: resolve_read_receipt_mode(
+                account_cfg.read_receipts.as_deref(),
+                wa_cfg.read_receipts.as_deref(),
+            ),
         });
     }
 
@@ -258,6 +277,7 @@ pub fn build_whatsapp_runtime_contexts(
                 .map(str::trim)
                 .filter(|v| !v.is_empty())
                 .map(ToOwned::to_owned),
+            read_receipts: resolve_read_receipt_mode(None, wa_cfg.read_receipts.as_deref()),
         });
     }
 
@@ -353,6 +373,60 @@ async fn send_whatsapp_text(
     Ok(())
 }
 
+/// Mark an inbound message as read (shows blue ticks to the sender).
+async fn mark_whatsapp_message_read(
+    http_client: &reqwest::Client,
+    access_token: &str,
+    phone_number_id: &str,
+    api_version: &str,
+    message_id: &str,
+) -> Result<(), String> {
+    let url = format!(
+        "https://graph.facebook.com/{}/{}/messages",
+        api_version.trim(),
+        phone_number_id.trim()
+    );
+    let body = serde_json::json!({
+        "messaging_product": "whatsapp",
+        "status": "read",
+        "message_id": message_id,
+    });
+    let response = http_client
+        .post(&url)
+        .bearer_auth(access_token.trim())
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("WhatsApp API request failed: {e}"))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("WhatsApp API error {status}: {body}"));
+    }
+    Ok(())
+}
+
+async fn send_read_receipt_if_allowed(
+    runtime: &WhatsAppRuntimeContext,
+    message_id: &str,
+    responded: bool,
+) {
+    if message_id.trim().is_empty() || !should_send_read_receipt(runtime.read_receipts, responded) {
+        return;
+    }
+    if let Err(e) = mark_whatsapp_message_read(
+        &reqwest::Client::new(),
+        &runtime.access_token,
+        &runtime.phone_number_id,
+        &runtime.api_version,
+        message_id,
+    )
+    .await
+    {
+        error!("WhatsApp: failed to mark message read: {e}");
+    }
+}
+
 pub async fn start_whatsapp_bot(_app_state: Arc<AppState>, runtime: WhatsAppRuntimeContext) {
     mark_channel_started(&runtime.channel_name);
     info!(
@@ -412,6 +486,8 @@ struct WhatsAppInboundMessage {
     message_type: String,
     #[serde(default)]
     text: Option<WhatsAppInboundText>,
+    #[serde(default)]
+    audio: Option<WhatsAppInboundMedia>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -419,6 +495,13 @@ struct WhatsAppInboundText {
     body: String,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+struct WhatsAppInboundMedia {
+    id: String,
+    #[serde(default)]
+    mime_type: Option<String>,
+}
+
 fn verify_token_allowed(runtime_contexts: &[WhatsAppRuntimeContext], token: &str) -> bool {
     let mut has_configured_token = false;
     for runtime in runtime_contexts {
@@ -446,7 +529,7 @@ async fn whatsapp_verify_handler(
         return axum::http::StatusCode::BAD_REQUEST.into_response();
     };
     let provided_token = query.hub_verify_token.unwrap_or_default();
-    let runtime_contexts = build_whatsapp_runtime_contexts(&app_state.config);
+    let runtime_contexts = build_whatsapp_runtime_contexts(&app_state.config.load());
     if runtime_contexts.is_empty() {
         return axum::http::StatusCode::NOT_FOUND.into_response();
     }
@@ -460,11 +543,12 @@ async fn whatsapp_verify_handler(
 pub fn register_whatsapp_webhook(router: Router, app_state: Arc<AppState>) -> Router {
     let Some(cfg) = app_state
         .config
+        .load()
         .channel_config::<WhatsAppChannelConfig>("whatsapp")
     else {
         return router;
     };
-    if !app_state.config.channel_enabled("whatsapp") {
+    if !app_state.config.load().channel_enabled("whatsapp") {
         return router;
     }
     let path = cfg.webhook_path.trim();
@@ -491,7 +575,7 @@ async fn whatsapp_webhook_handler(
     app_state: Arc<AppState>,
     payload: WhatsAppWebhookPayload,
 ) -> impl IntoResponse {
-    let runtime_contexts = build_whatsapp_runtime_contexts(&app_state.config);
+    let runtime_contexts = build_whatsapp_runtime_contexts(&app_state.config.load());
     if runtime_contexts.is_empty() {
         return axum::http::StatusCode::NOT_FOUND;
     }
@@ -516,15 +600,20 @@ async fn whatsapp_webhook_handler(
             };
 
             for message in change.value.messages {
-                if message.message_type != "text" {
-                    continue;
-                }
+                let audio = match message.message_type.as_str() {
+                    "text" => None,
+                    "audio" => match message.audio.as_ref() {
+                        Some(audio) => Some(audio.clone()),
+                        None => continue,
+                    },
+                    _ => continue,
+                };
                 let text = message
                     .text
                     .as_ref()
                     .map(|t| t.body.trim().to_string())
                     .unwrap_or_default();
-                if text.is_empty() {
+                if text.is_empty() && audio.is_none() {
                     continue;
                 }
                 let state = app_state.clone();
@@ -533,8 +622,16 @@ async fn whatsapp_webhook_handler(
                 let message_id = message.id.clone();
                 let timestamp = message.timestamp.clone();
                 tokio::spawn(async move {
-                    handle_whatsapp_message(state, runtime, &from, &text, &message_id, &timestamp)
-                        .await;
+                    handle_whatsapp_message(
+                        state,
+                        runtime,
+                        &from,
+                        text,
+                        audio,
+                        &message_id,
+                        &timestamp,
+                    )
+                    .await;
                 });
             }
         }
@@ -543,15 +640,115 @@ async fn whatsapp_webhook_handler(
     axum::http::StatusCode::OK
 }
 
+fn sender_allowed(runtime: &WhatsAppRuntimeContext, from: &str) -> bool {
+    runtime.allowed_user_ids.is_empty() || runtime.allowed_user_ids.iter().any(|u| u == from)
+}
+
+/// Download an inbound media object: the Graph API first returns a
+/// short-lived URL for the media id, which is then fetched with the same token.
+async fn download_whatsapp_media(
+    http_client: &reqwest::Client,
+    runtime: &WhatsAppRuntimeContext,
+    media_id: &str,
+) -> Result<Vec<u8>, String> {
+    let url = format!(
+        "https://graph.facebook.com/{}/{}",
+        runtime.api_version.trim(),
+        media_id.trim()
+    );
+    let response = http_client
+        .get(&url)
+        .bearer_auth(runtime.access_token.trim())
+        .send()
+        .await
+        .map_err(|e| format!("WhatsApp API request failed: {e}"))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("WhatsApp API error {status}: {body}"));
+    }
+    let media: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| format!("WhatsApp media lookup returned invalid JSON: {e}"))?;
+    let Some(media_url) = media.get("url").and_then(|v| v.as_str()) else {
+        return Err("WhatsApp media lookup returned no url".into());
+    };
+    let response = http_client
+        .get(media_url)
+        .bearer_auth(runtime.access_token.trim())
+        .send()
+        .await
+        .map_err(|e| format!("WhatsApp media download failed: {e}"))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "WhatsApp media download error {}",
+            response.status()
+        ));
+    }
+    response
+        .bytes()
+        .await
+        .map(|b| b.to_vec())
+        .map_err(|e| format!("WhatsApp media download failed: {e}"))
+}
+
+/// Turn an inbound audio message into message text. Returns `None` when
+/// there is nothing to pass to the agent.
+async fn whatsapp_voice_text(
+    app_state: &AppState,
+    runtime: &WhatsAppRuntimeContext,
+    from: &str,
+    audio: &WhatsAppInboundMedia,
+) -> Option<String> {
+    if !sender_allowed(runtime, from) {
+        return None;
+    }
+    let config = app_state.config.load();
+    let http_client = reqwest::Client::new();
+    if !crate::voice::transcription_available(&config) {
+        let _ = send_whatsapp_text(
+            &http_client,
+            &runtime.access_token,
+            &runtime.phone_number_id,
+            &runtime.api_version,
+            from,
+            crate::voice::transcription_unavailable_message(&config),
+        )
+        .await;
+        return None;
+    }
+    match download_whatsapp_media(&http_client, runtime, &audio.id).await {
+        Ok(bytes) => Some(
+            crate::voice::voice_message_text(
+                &config,
+                from,
+                &bytes,
+                None,
+                &crate::voice::TranscriptionOptions {
+                    mime_type: audio.mime_type.clone(),
+                    ..Default::default()
+                },
+            )
+            .await,
+        ),
+        Err(e) => {
+            error!("Failed to download WhatsApp voice message: {e}");
+            None
+        }
+    }
+}
+
 async fn handle_whatsapp_message(
     app_state: Arc<AppState>,
     runtime: WhatsAppRuntimeContext,
     from: &str,
-    text: &str,
+    text: String,
+    audio: Option<WhatsAppInboundMedia>,
     message_id: &str,
     timestamp: &str,
 ) {
-    if !runtime.allowed_user_ids.is_empty() && !runtime.allowed_user_ids.iter().any(|u| u == from) {
+    if !sender_allowed(&runtime, from) {
         return;
     }
 
@@ -596,6 +793,21 @@ async fn handle_whatsapp_message(
     if should_drop_recent_duplicate_message(&runtime.channel_name, &inbound_message_id) {
         return;
     }
+    let Some(_claim) =
+        claim_inbound_message(&app_state, &runtime.channel_name, &inbound_message_id).await
+    else {
+        return;
+    };
+
+    // Transcribe only once the message is ours, so a redelivered webhook
+    // does not pay for a second transcription.
+    let text = match audio {
+        Some(audio) => match whatsapp_voice_text(&app_state, &runtime, from, &audio).await {
+            Some(text) => text,
+            None => return,
+        },
+        None => text,
+    };
 
     let trimmed = text.trim();
     if is_slash_command(trimmed) {
@@ -608,7 +820,7 @@ async fn handle_whatsapp_message(
         )
         .await
         {
-            let _ = send_whatsapp_text(
+            let sent = send_whatsapp_text(
                 &reqwest::Client::new(),
                 &runtime.access_token,
                 &runtime.phone_number_id,
@@ -617,9 +829,10 @@ async fn handle_whatsapp_message(
                 &reply,
             )
             .await;
+            send_read_receipt_if_allowed(&runtime, message_id, sent.is_ok()).await;
             return;
         }
-        let _ = send_whatsapp_text(
+        let sent = send_whatsapp_text(
             &reqwest::Client::new(),
             &runtime.access_token,
             &runtime.phone_number_id,
@@ -628,6 +841,7 @@ async fn handle_whatsapp_message(
             &unknown_command_response(),
         )
         .await;
+        send_read_receipt_if_allowed(&runtime, message_id, sent.is_ok()).await;
         return;
     }
     let stored = StoredMessage {
@@ -638,11 +852,8 @@ async fn handle_whatsapp_message(
         is_from_bot: false,
         timestamp: chrono::Utc::now().to_rfc3339(),
     };
-    let inserted = call_blocking(app_state.db.clone(), move |db| {
-        db.store_message_if_new(&stored)
-    })
-    .await
-    .unwrap_or(false);
+    let inserted =
+        crate::channels::store_inbound_message(&app_state, &runtime.channel_name, stored).await;
     if !inserted {
         info!(
             "WhatsApp: skipping duplicate message chat_id={} message_id={}",
@@ -659,7 +870,7 @@ async fn handle_whatsapp_message(
     );
 
     let (event_tx, mut event_rx) = tokio::sync::mpsc::unbounded_channel::<AgentEvent>();
-    match process_with_agent_with_events(
+    let responded = match process_with_agent_with_events(
         &app_state,
         AgentRequestContext {
             caller_channel: &runtime.channel_name,
@@ -690,8 +901,9 @@ async fn handle_whatsapp_message(
                         chat_id
                     );
                 }
+                true
             } else if !response.is_empty() {
-                if let Err(e) = send_whatsapp_text(
+                let sent = send_whatsapp_text(
                     &reqwest::Client::new(),
                     &runtime.access_token,
                     &runtime.phone_number_id,
@@ -699,8 +911,8 @@ async fn handle_whatsapp_message(
                     external_chat_id,
                     &response,
                 )
-                .await
-                {
+                .await;
+                if let Err(e) = &sent {
                     error!("WhatsApp: failed to send response: {e}");
                 }
 
@@ -714,10 +926,11 @@ async fn handle_whatsapp_message(
                 };
                 let _ =
                     call_blocking(app_state.db.clone(), move |db| db.store_message(&bot_msg)).await;
+                sent.is_ok()
             } else {
                 let fallback =
                     "I couldn't produce a visible reply after an automatic retry. Please try again.";
-                let _ = send_whatsapp_text(
+                let sent = send_whatsapp_text(
                     &reqwest::Client::new(),
                     &runtime.access_token,
                     &runtime.phone_number_id,
@@ -737,12 +950,15 @@ async fn handle_whatsapp_message(
                 };
                 let _ =
                     call_blocking(app_state.db.clone(), move |db| db.store_message(&bot_msg)).await;
+                sent.is_ok()
             }
         }
         Err(e) => {
             error!("WhatsApp: error processing message: {e}");
-            if !should_suppress_user_error(&e) {
-                let _ = send_whatsapp_text(
+            if should_suppress_user_error(&e) {
+                false
+            } else {
+                send_whatsapp_text(
                     &reqwest::Client::new(),
                     &runtime.access_token,
                     &runtime.phone_number_id,
@@ -750,8 +966,10 @@ async fn handle_whatsapp_message(
                     external_chat_id,
                     &format!("Error: {e}"),
                 )
-                .await;
+                .await
+                .is_ok()
             }
         }
-    }
+    };
+    send_read_receipt_if_allowed(&runtime, message_id, responded).await;
 }
@@ -1,15 +1,33 @@
 use std::sync::Arc;
 
-use crate::agent_engine::archive_conversation;
+use crate::agent_engine::{
+    archive_conversation, build_summary_input, message_to_text, request_summary,
+    summarize_conversation,
+};
 use crate::config::{Config, ResolvedLlmProviderProfile};
+use crate::mcp::{McpReloadReport, McpServerHealth};
 use crate::run_control;
 use crate::runtime::AppState;
-use microclaw_core::llm_types::Message;
-use microclaw_storage::db::{call_blocking, Database};
+use microclaw_channels::channel::{get_chat_routing, ConversationKind};
+use microclaw_core::llm_types::{ContentBlock, Message, MessageContent, ToolDefinition};
+use microclaw_storage::db::{
+    call_blocking, ChatStats, Database, Memory, StoredMessage, ToolCallTrace,
+};
 use microclaw_storage::usage::build_usage_report;
 use microclaw_tools::todo_store::clear_todos;
 use serde::Deserialize;
-use tracing::warn;
+use tracing::{info, warn};
+
+const SUMMARY_MAX_MESSAGES: usize = 500;
+const CATCHUP_DEFAULT_MESSAGES: usize = 100;
+pub const STREAMING_SETTING_KEY: &str = "streaming";
+pub const DEBUG_SETTING_KEY: &str = "debug";
+/// Per-chat override of `tool_parallel_max`.
+pub const PARALLEL_TOOLS_SETTING_KEY: &str = "parallel_tools";
+/// Comma-separated tool names the agent may not use in a chat.
+pub const DISABLED_TOOLS_SETTING_KEY: &str = "disabled_tools";
+/// Every tool that can reach the network; `/web off` disables all of them.
+pub const WEB_TOOL_NAMES: &[&str] = &["web_search", "web_fetch", "http_request", "browser"];
 
 pub fn is_slash_command(text: &str) -> bool {
     normalized_slash_command(text).is_some()
@@ -60,7 +78,7 @@ pub async fn handle_chat_command(
             db.clear_chat_conversation(chat_id)
         })
         .await;
-        let groups_dir = std::path::PathBuf::from(&state.config.data_dir).join("groups");
+        let groups_dir = std::path::PathBuf::from(&state.config.load().data_dir).join("groups");
         if let Err(e) = clear_todos(&groups_dir, caller_channel, chat_id) {
             warn!("Failed to clear TODO.json for chat {}: {}", chat_id, e);
         }
@@ -69,7 +87,7 @@ pub async fn handle_chat_command(
 
     if trimmed == "/reset" {
         let _ = call_blocking(state.db.clone(), move |db| db.clear_chat_context(chat_id)).await;
-        let groups_dir = std::path::PathBuf::from(&state.config.data_dir).join("groups");
+        let groups_dir = std::path::PathBuf::from(&state.config.load().data_dir).join("groups");
         if let Err(e) = clear_todos(&groups_dir, caller_channel, chat_id) {
             warn!("Failed to clear TODO.json for chat {}: {}", chat_id, e);
         }
@@ -88,11 +106,24 @@ pub async fn handle_chat_command(
         return Some(state.skills.list_skills_formatted());
     }
 
+    if trimmed == "/tools" || trimmed.starts_with("/tools ") {
+        let disabled = disabled_tools_for_chat(state.db.clone(), chat_id).await;
+        let definitions = crate::agent_engine::without_disabled_tools(
+            state.tools.definitions().to_vec(),
+            &disabled,
+        );
+        return Some(build_tools_response(&definitions, trimmed));
+    }
+
     if trimmed == "/reload-skills" {
         let count = state.skills.reload().len();
         return Some(format!("Reloaded {count} skills from disk."));
     }
 
+    if trimmed == "/mcp" || trimmed.starts_with("/mcp ") {
+        return Some(build_mcp_response(state, chat_id, trimmed).await);
+    }
+
     if trimmed == "/archive" {
         if let Ok(Some((json, _))) =
             call_blocking(state.db.clone(), move |db| db.load_session(chat_id)).await
@@ -101,12 +132,66 @@ pub async fn handle_chat_command(
             if messages.is_empty() {
                 return Some("No session to archive.".to_string());
             }
-            archive_conversation(&state.config.data_dir, caller_channel, chat_id, &messages);
+            archive_conversation(&state.config.load(), caller_channel, chat_id, &messages);
             return Some(format!("Archived {} messages.", messages.len()));
         }
         return Some("No session to archive.".to_string());
     }
 
+    if trimmed == "/session" || trimmed.starts_with("/session ") {
+        return Some(
+            build_session_response(
+                state.db.clone(),
+                &state.config.load(),
+                caller_channel,
+                chat_id,
+                trimmed,
+            )
+            .await,
+        );
+    }
+
+    if trimmed == "/summary" || trimmed.starts_with("/summary ") {
+        return Some(build_summary_response(state, chat_id, caller_channel, trimmed).await);
+    }
+
+    if is_catchup_command(trimmed) {
+        return Some(build_catchup_response(state, chat_id, caller_channel, trimmed).await);
+    }
+
+    if trimmed == "/streaming" || trimmed.starts_with("/streaming ") {
+        return Some(build_streaming_response(state.db.clone(), chat_id, trimmed).await);
+    }
+
+    if trimmed == "/parallel" || trimmed.starts_with("/parallel ") {
+        return Some(
+            build_parallel_response(
+                state.db.clone(),
+                chat_id,
+                state.config.load().tool_parallel_max,
+                trimmed,
+            )
+            .await,
+        );
+    }
+
+    if trimmed == "/debug" || trimmed.starts_with("/debug ") {
+        return Some(build_debug_response(state.db.clone(), chat_id, trimmed).await);
+    }
+
+    if trimmed == "/web" || trimmed.starts_with("/web ") {
+        return Some(build_web_response(state.db.clone(), chat_id, trimmed).await);
+    }
+
+    if trimmed == "/loglevel" || trimmed.starts_with("/loglevel ") {
+        return Some(build_loglevel_response(
+            &state.config.load(),
+            crate::logging::log_filter_handle(),
+            chat_id,
+            trimmed,
+        ));
+    }
+
     if trimmed == "/usage" {
         let text = match build_usage_report(state.db.clone(), chat_id).await {
             Ok(v) => v,
@@ -115,11 +200,23 @@ pub async fn handle_chat_command(
         return Some(text);
     }
 
+    if trimmed == "/trace" {
+        return Some(build_trace_response(state, chat_id).await);
+    }
+
+    if trimmed == "/stats" {
+        return Some(build_stats_response(state, chat_id).await);
+    }
+
+    if trimmed == "/memories" || trimmed.starts_with("/memories ") {
+        return Some(build_memories_response(state, chat_id, caller_channel, trimmed).await);
+    }
+
     if trimmed == "/status" {
         return Some(
             build_status_response(
                 state.db.clone(),
-                &state.config,
+                &state.config.load(),
                 state.llm_provider_overrides.clone(),
                 state.llm_model_overrides.clone(),
                 chat_id,
@@ -139,7 +236,7 @@ pub async fn handle_chat_command(
     if trimmed == "/providers" {
         return Some(
             build_providers_response(
-                &state.config,
+                &state.config.load(),
                 state.llm_provider_overrides.clone(),
                 state.llm_model_overrides.clone(),
                 caller_channel,
@@ -151,10 +248,12 @@ pub async fn handle_chat_command(
     if trimmed == "/provider" || trimmed.starts_with("/provider ") {
         return Some(
             build_provider_response(
-                &state.config,
+                state.db.clone(),
+                &state.config.load(),
                 state.llm_provider_overrides.clone(),
                 state.llm_model_overrides.clone(),
                 caller_channel,
+                chat_id,
                 trimmed,
             )
             .await,
@@ -164,7 +263,7 @@ pub async fn handle_chat_command(
     if trimmed == "/models" || trimmed.starts_with("/models ") {
         return Some(
             build_models_response(
-                &state.config,
+                &state.config.load(),
                 state.llm_provider_overrides.clone(),
                 state.llm_model_overrides.clone(),
                 caller_channel,
@@ -177,7 +276,8 @@ pub async fn handle_chat_command(
     if trimmed == "/model" || trimmed.starts_with("/model ") {
         return Some(
             build_model_response(
-                &state.config,
+                state.db.clone(),
+                &state.config.load(),
                 state.llm_provider_overrides.clone(),
                 state.llm_model_overrides.clone(),
                 caller_channel,
@@ -189,7 +289,7 @@ pub async fn handle_chat_command(
     }
 
     if let Some(plugin_response) =
-        maybe_handle_plugin_command(&state.config, trimmed, chat_id, caller_channel).await
+        maybe_handle_plugin_command(&state.config.load(), trimmed, chat_id, caller_channel).await
     {
         return Some(plugin_response);
     }
@@ -197,6 +297,1031 @@ pub async fn handle_chat_command(
     None
 }
 
+/// `/tools` lists every tool the agent can call; `/tools <name>` describes one
+/// tool and its input parameters.
+pub fn build_tools_response(definitions: &[ToolDefinition], command_text: &str) -> String {
+    let requested = command_text
+        .trim()
+        .strip_prefix("/tools")
+        .unwrap_or("")
+        .trim();
+    if requested.is_empty() {
+        return format_tool_list(definitions);
+    }
+    match definitions
+        .iter()
+        .find(|d| d.name.eq_ignore_ascii_case(requested))
+    {
+        Some(def) => format_tool_description(def),
+        None => format!("Unknown tool: {requested}. Use /tools to list available tools."),
+    }
+}
+
+fn format_tool_list(definitions: &[ToolDefinition]) -> String {
+    if definitions.is_empty() {
+        return "No tools available.".to_string();
+    }
+    let mut defs = definitions.iter().collect::<Vec<_>>();
+    defs.sort_by(|a, b| a.name.cmp(&b.name));
+    let mut lines = vec![format!("Available tools ({}):", defs.len())];
+    for def in defs {
+        let summary = tool_summary_line(&def.description);
+        if summary.is_empty() {
+            lines.push(format!("- {}", def.name));
+        } else {
+            lines.push(format!("- {}: {summary}", def.name));
+        }
+    }
+    lines.push("Use /tools <name> for parameters.".to_string());
+    lines.join("\n")
+}
+
+fn tool_summary_line(description: &str) -> String {
+    const MAX_CHARS: usize = 100;
+    let first_line = description.lines().next().unwrap_or("").trim();
+    let sentence = match first_line.find(". ") {
+        Some(idx) => &first_line[..=idx],
+        None => first_line,
+    };
+    if sentence.chars().count() <= MAX_CHARS {
+        return sentence.to_string();
+    }
+    let truncated = sentence.chars().take(MAX_CHARS).collect::<String>();
+    format!("{}...", truncated.trim_end())
+}
+
+fn format_tool_description(def: &ToolDefinition) -> String {
+    let mut out = format!("{}\n{}\n", def.name, def.description.trim());
+    let properties = def
+        .input_schema
+        .get("properties")
+        .and_then(|v| v.as_object())
+        .filter(|p| !p.is_empty());
+    let Some(properties) = properties else {
+        out.push_str("\nParameters: none");
+        return out;
+    };
+    let required = def
+        .input_schema
+        .get("required")
+        .and_then(|v| v.as_array())
+        .map(|items| items.iter().filter_map(|v| v.as_str()).collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    let mut names = properties.keys().collect::<Vec<_>>();
+    names.sort_by_key(|name| (!required.contains(&name.as_str()), name.as_str()));
+    out.push_str("\nParameters:");
+    for name in names {
+        let schema = &properties[name.as_str()];
+        let mut kind = schema_type_label(schema);
+        if required.contains(&name.as_str()) {
+            kind.push_str(", required");
+        }
+        let mut line = format!("\n- {name} ({kind})");
+        if let Some(desc) = schema
+            .get("description")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+        {
+            line.push_str(&format!(": {desc}"));
+        }
+        if let Some(values) = schema.get("enum").and_then(|v| v.as_array()) {
+            let values = values
+                .iter()
+                .map(|v| {
+                    v.as_str()
+                        .map(str::to_string)
+                        .unwrap_or_else(|| v.to_string())
+                })
+                .collect::<Vec<_>>();
+            line.push_str(&format!(" [one of: {}]", values.join(", ")));
+        }
+        out.push_str(&line);
+    }
+    out
+}
+
+fn schema_type_label(schema: &serde_json::Value) -> String {
+    let kind = match schema.get("type") {
+        Some(serde_json::Value::String(t)) => t.clone(),
+        Some(serde_json::Value::Array(types)) => types
+            .iter()
+            .filter_map(|v| v.as_str())
+            .collect::<Vec<_>>()
+            .join(" | "),
+        _ => "any".to_string(),
+    };
+    if kind == "array" {
+        if let Some(item) = schema
+            .get("items")
+            .and_then(|v| v.get("type"))
+            .and_then(|v| v.as_str())
+        {
+            return format!("array of {item}");
+        }
+    }
+    kind
+}
+
+/// Window of chat history covered by `/summary`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SummaryRange {
+    /// The most recent `max_history_messages` messages.
+    Recent,
+    LastMessages(usize),
+    LastMinutes(i64),
+}
+
+impl SummaryRange {
+    fn describe(&self) -> String {
+        match self {
+            SummaryRange::Recent => "the recent conversation".to_string(),
+            SummaryRange::LastMessages(n) => format!("the last {n} messages"),
+            SummaryRange::LastMinutes(m) if *m % (24 * 60) == 0 => {
+                plural_window(*m / (24 * 60), "day")
+            }
+            SummaryRange::LastMinutes(m) if *m % 60 == 0 => plural_window(*m / 60, "hour"),
+            SummaryRange::LastMinutes(m) => plural_window(*m, "minute"),
+        }
+    }
+}
+
+fn plural_window(n: i64, unit: &str) -> String {
+    if n == 1 {
+        format!("the last {unit}")
+    } else {
+        format!("the last {n} {unit}s")
+    }
+}
+
+/// Parse `/summary` arguments such as `last 2 hours`, `30m`, `last day` or
+/// `last 50 messages`.
+fn parse_summary_range(args: &str) -> Result<SummaryRange, String> {
+    let args = args.trim().to_ascii_lowercase();
+    let args = args.strip_prefix("last").unwrap_or(&args).trim();
+    if args.is_empty() {
+        return Ok(SummaryRange::Recent);
+    }
+
+    let split = args
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(args.len());
+    let (num, unit) = args.split_at(split);
+    let amount: i64 = if num.is_empty() {
+        1
+    } else {
+        num.parse()
+            .map_err(|_| format!("Invalid number in range: {num}"))?
+    };
+    if amount <= 0 {
+        return Err("Range must be greater than zero.".to_string());
+    }
+
+    let minutes_per_unit = match unit.trim() {
+        "m" | "min" | "mins" | "minute" | "minutes" => 1,
+        "h" | "hr" | "hrs" | "hour" | "hours" => 60,
+        "d" | "day" | "days" => 24 * 60,
+        "msg" | "msgs" | "message" | "messages" => {
+            return Ok(SummaryRange::LastMessages(amount as usize))
+        }
+        other => return Err(format!("Unknown range unit: {other}")),
+    };
+    amount
+        .checked_mul(minutes_per_unit)
+        .filter(|minutes| chrono::Duration::try_minutes(*minutes).is_some())
+        .map(SummaryRange::LastMinutes)
+        .ok_or_else(|| "Range is too large.".to_string())
+}
+
+fn summary_range_since(range: SummaryRange, now: chrono::DateTime<chrono::Utc>) -> Option<String> {
+    match range {
+        SummaryRange::LastMinutes(minutes) => {
+            // A window reaching past the earliest representable time covers
+            // the whole history.
+            let since = chrono::Duration::try_minutes(minutes)
+                .and_then(|window| now.checked_sub_signed(window))
+                .unwrap_or(chrono::DateTime::<chrono::Utc>::MIN_UTC);
+            Some(since.to_rfc3339())
+        }
+        _ => None,
+    }
+}
+
+fn stored_messages_for_summary(
+    config: &Config,
+    caller_channel: &str,
+    history: &[StoredMessage],
+) -> Vec<Message> {
+    history
+        .iter()
+        .filter(|m| m.is_from_bot || !is_slash_command(&m.content))
+        .map(|m| {
+            let (role, text) = if m.is_from_bot {
+                ("assistant", m.content.clone())
+            } else {
+                let sender_name = config.normalize_sender_name(caller_channel, &m.sender_name);
+                ("user", format!("{sender_name}: {}", m.content))
+            };
+            Message {
+                role: role.into(),
+                content: MessageContent::Text(text),
+            }
+        })
+        .collect()
+}
+
+pub async fn build_summary_response(
+    state: &AppState,
+    chat_id: i64,
+    caller_channel: &str,
+    command_text: &str,
+) -> String {
+    let args = command_text.trim().strip_prefix("/summary").unwrap_or("");
+    let range = match parse_summary_range(args) {
+        Ok(range) => range,
+        Err(e) => return format!("{e}\nUsage: /summary [last <N> minutes|hours|days|messages]"),
+    };
+
+    let max_messages = state.config.load().max_history_messages.max(1);
+    let since = summary_range_since(range, chrono::Utc::now());
+    let history = call_blocking(state.db.clone(), move |db| match (range, since) {
+        (_, Some(since)) => db.get_latest_messages_since(chat_id, &since, SUMMARY_MAX_MESSAGES),
+        (SummaryRange::LastMessages(n), None) => {
+            db.get_recent_messages(chat_id, n.min(SUMMARY_MAX_MESSAGES))
+        }
+        _ => db.get_recent_messages(chat_id, max_messages),
+    })
+    .await;
+    let history = match history {
+        Ok(history) => history,
+        Err(e) => return format!("Failed to load conversation: {e}"),
+    };
+
+    let messages = stored_messages_for_summary(&state.config.load(), caller_channel, &history);
+    if messages.is_empty() {
+        return format!("Nothing to summarize in {}.", range.describe());
+    }
+
+    let summary_input = build_summary_input(&messages);
+    match summarize_conversation(
+        state,
+        &state.config.load(),
+        caller_channel,
+        chat_id,
+        &summary_input,
+        "summary",
+    )
+    .await
+    {
+        Ok(summary) if !summary.trim().is_empty() => format!(
+            "Summary of {} ({} messages):\n\n{}",
+            range.describe(),
+            messages.len(),
+            summary.trim()
+        ),
+        Ok(_) => "Summary unavailable: the model returned an empty response.".to_string(),
+        Err(reason) => {
+            warn!("Summary for chat {chat_id} {reason}");
+            format!("Summary unavailable: summarization {reason}.")
+        }
+    }
+}
+
+/// Whether `text` is `/catchup`. Channels that run commands before their
+/// group allowlist check use this to keep the digest out of other groups.
+pub fn is_catchup_command(text: &str) -> bool {
+    normalized_slash_command(text)
+        .map(str::trim)
+        .is_some_and(|t| t == "/catchup" || t.starts_with("/catchup "))
+}
+
+fn parse_catchup_limit(command_text: &str) -> Result<usize, String> {
+    let arg = command_text
+        .trim()
+        .strip_prefix("/catchup")
+        .unwrap_or("")
+        .trim();
+    if arg.is_empty() {
+        return Ok(CATCHUP_DEFAULT_MESSAGES);
+    }
+    match arg.parse::<usize>() {
+        Ok(n) if (1..=SUMMARY_MAX_MESSAGES).contains(&n) => Ok(n),
+        _ => Err(format!(
+            "Usage: /catchup [N] (messages to consider, 1-{SUMMARY_MAX_MESSAGES}, default {CATCHUP_DEFAULT_MESSAGES})"
+        )),
+    }
+}
+
+/// `/catchup [N]` posts a bullet-point digest of up to N messages sent since
+/// the bot last replied. The digest is only returned as the command reply, so
+/// it never enters the session or the next catch-up.
+pub async fn build_catchup_response(
+    state: &AppState,
+    chat_id: i64,
+    caller_channel: &str,
+    command_text: &str,
+) -> String {
+    let limit = match parse_catchup_limit(command_text) {
+        Ok(limit) => limit,
+        Err(usage) => return usage,
+    };
+    let history = match call_blocking(state.db.clone(), move |db| {
+        db.get_messages_since_last_bot_response(chat_id, limit, limit)
+    })
+    .await
+    {
+        Ok(history) => history,
+        Err(e) => return format!("Failed to load messages: {e}"),
+    };
+    let unread: Vec<StoredMessage> = history.into_iter().filter(|m| !m.is_from_bot).collect();
+    let messages = stored_messages_for_summary(&state.config.load(), caller_channel, &unread);
+    if messages.is_empty() {
+        return "Nothing new since my last reply.".to_string();
+    }
+
+    let request = format!(
+        "Write a short bullet-point digest of these chat messages for someone catching up. Cover the main topics, decisions, open questions and anything asked of the assistant; say who said what when it matters. Use \"- \" bullets only, with no introduction.\n\n---\n\n{}",
+        build_summary_input(&messages)
+    );
+    match request_summary(
+        state,
+        &state.config.load(),
+        caller_channel,
+        chat_id,
+        request,
+        None,
+        "catchup",
+    )
+    .await
+    {
+        Ok(digest) if !digest.trim().is_empty() => format!(
+            "Catch-up on {} messages since my last reply:\n\n{}",
+            messages.len(),
+            digest.trim()
+        ),
+        Ok(_) => "Catch-up unavailable: the model returned an empty response.".to_string(),
+        Err(reason) => {
+            warn!("Catch-up for chat {chat_id} {reason}");
+            format!("Catch-up unavailable: summarization {reason}.")
+        }
+    }
+}
+
+/// Whether replies in this chat should stream with live message edits. A
+/// per-chat `/streaming on|off` choice overrides the channel's configured default.
+pub async fn streaming_enabled_for_chat(
+    db: Arc<Database>,
+    chat_id: i64,
+    channel_default: bool,
+) -> bool {
+    match call_blocking(db, move |db| {
+        db.get_chat_setting(chat_id, STREAMING_SETTING_KEY)
+    })
+    .await
+    {
+        Ok(Some(v)) if v == "on" => true,
+        Ok(Some(v)) if v == "off" => false,
+        Ok(_) => channel_default,
+        Err(e) => {
+            warn!("Failed to read streaming setting for chat {chat_id}: {e}");
+            channel_default
+        }
+    }
+}
+
+/// Whether replies in this chat end with a `[debug]` footer of turn metrics.
+pub async fn debug_enabled_for_chat(db: Arc<Database>, chat_id: i64) -> bool {
+    match call_blocking(db, move |db| {
+        db.get_chat_setting(chat_id, DEBUG_SETTING_KEY)
+    })
+    .await
+    {
+        Ok(v) => v.as_deref() == Some("on"),
+        Err(e) => {
+            warn!("Failed to read debug setting for chat {chat_id}: {e}");
+            false
+        }
+    }
+}
+
+/// `/debug [on|off]` shows or changes whether replies in this chat carry a
+/// footer with tokens, tool calls, iterations, and elapsed time.
+pub async fn build_debug_response(db: Arc<Database>, chat_id: i64, command_text: &str) -> String {
+    let arg = command_text
+        .trim()
+        .strip_prefix("/debug")
+        .unwrap_or("")
+        .trim()
+        .to_ascii_lowercase();
+    let result = match arg.as_str() {
+        "" => {
+            return if debug_enabled_for_chat(db, chat_id).await {
+                "Debug footer for this chat: on.".to_string()
+            } else {
+                "Debug footer for this chat: off.".to_string()
+            };
+        }
+        "on" => {
+            call_blocking(db, move |db| {
+                db.set_chat_setting(chat_id, DEBUG_SETTING_KEY, "on")
+            })
+            .await
+        }
+        "off" => {
+            call_blocking(db, move |db| {
+                db.delete_chat_setting(chat_id, DEBUG_SETTING_KEY)
+                    .map(|_| ())
+            })
+            .await
+        }
+        _ => return "Usage: /debug [on|off]".to_string(),
+    };
+    match result {
+        Ok(()) if arg == "on" => {
+            "Debug footer enabled for this chat: replies show tokens, tool calls, iterations, and time.".to_string()
+        }
+        Ok(()) => "Debug footer disabled for this chat.".to_string(),
+        Err(e) => format!("Failed to update debug setting: {e}"),
+    }
+}
+
+fn parse_disabled_tools(raw: &str) -> Vec<String> {
+    let mut tools: Vec<String> = Vec::new();
+    for name in raw.split(',') {
+        let name = name.trim().to_ascii_lowercase();
+        if !name.is_empty() && !tools.contains(&name) {
+            tools.push(name);
+        }
+    }
+    tools
+}
+
+/// Tools switched off for this chat, e.g. by `/web off`.
+pub async fn disabled_tools_for_chat(db: Arc<Database>, chat_id: i64) -> Vec<String> {
+    match call_blocking(db, move |db| {
+        db.get_chat_setting(chat_id, DISABLED_TOOLS_SETTING_KEY)
+    })
+    .await
+    {
+        Ok(Some(raw)) => parse_disabled_tools(&raw),
+        Ok(None) => Vec::new(),
+        Err(e) => {
+            warn!("Failed to read disabled tools for chat {chat_id}: {e}");
+            Vec::new()
+        }
+    }
+}
+
+const STATS_MAX_TOOLS: usize = 5;
+const STATS_MAX_HOURS: usize = 3;
+
+/// Commands that expose chat-wide data run in group chats only from a control
+/// chat, so one member cannot pull the whole group's history. Returns the
+/// refusal text otherwise.
+async fn require_control_chat_in_group(
+    state: &AppState,
+    chat_id: i64,
+    command: &str,
+) -> Result<(), String> {
+    if state.config.load().control_chat_ids.contains(&chat_id) {
+        return Ok(());
+    }
+    let is_group = matches!(
+        get_chat_routing(&state.channel_registry, state.db.clone(), chat_id).await,
+        Ok(Some(routing)) if routing.conversation == ConversationKind::Group
+    );
+    if is_group {
+        return Err(format!(
+            "In group chats {command} requires control chat permission."
+        ));
+    }
+    Ok(())
+}
+
+/// `/stats` summarizes this chat's own activity. Group chats need control chat
+/// permission so one member cannot pull the whole group's usage.
+async fn build_stats_response(state: &AppState, chat_id: i64) -> String {
+    if let Err(denied) = require_control_chat_in_group(state, chat_id, "/stats").await {
+        return denied;
+    }
+    match call_blocking(state.db.clone(), move |db| {
+        db.get_chat_stats(chat_id, STATS_MAX_TOOLS, STATS_MAX_HOURS)
+    })
+    .await
+    {
+        Ok(stats) => format_chat_stats(&stats),
+        Err(e) => format!("Failed to query chat statistics: {e}"),
+    }
+}
+
+const TRACE_INPUT_MAX_CHARS: usize = 300;
+
+fn clip_chars(text: &str, max_chars: usize) -> String {
+    if text.chars().count() > max_chars {
+        format!("{}...", text.chars().take(max_chars).collect::<String>())
+    } else {
+        text.to_string()
+    }
+}
+
+/// Numbered list of a run's tool calls with their inputs and results.
+fn render_tool_trace(calls: &[ToolCallTrace]) -> String {
+    if calls.is_empty() {
+        return "No tool calls recorded for the last run in this chat.".to_string();
+    }
+    let mut out = format!(
+        "Last run: {} tool call{}",
+        calls.len(),
+        if calls.len() == 1 { "" } else { "s" }
+    );
+    for (i, call) in calls.iter().enumerate() {
+        let status = if call.is_error { "error" } else { "ok" };
+        out.push_str(&format!("\n{}. {} ({status})", i + 1, call.tool_name));
+        if let Some(input) = call.input_json.as_deref() {
+            out.push_str(&format!(
+                "\n   input: {}",
+                clip_chars(input, TRACE_INPUT_MAX_CHARS)
+            ));
+        }
+        if let Some(result) = call.result_preview.as_deref() {
+            let result = result.split_whitespace().collect::<Vec<_>>().join(" ");
+            out.push_str(&format!("\n   result: {result}"));
+        }
+    }
+    out
+}
+
+/// `/trace` lists the tool calls of the chat's last agent run from the tool
+/// audit log. Group chats need control chat permission, as with `/stats`.
+async fn build_trace_response(state: &AppState, chat_id: i64) -> String {
+    if let Err(denied) = require_control_chat_in_group(state, chat_id, "/trace").await {
+        return denied;
+    }
+    match call_blocking(state.db.clone(), move |db| {
+        db.get_last_run_tool_calls(chat_id)
+    })
+    .await
+    {
+        Ok(calls) => render_tool_trace(&calls),
+        Err(e) => format!("Failed to read tool trace: {e}"),
+    }
+}
+
+async fn build_memories_response(
+    state: &AppState,
+    chat_id: i64,
+    caller_channel: &str,
+    text: &str,
+) -> String {
+    let args: Vec<&str> = text.split_whitespace().skip(1).collect();
+    if args != ["export"] {
+        return "Usage: /memories export".to_string();
+    }
+    if let Err(denied) = require_control_chat_in_group(state, chat_id, "/memories export").await {
+        return denied;
+    }
+    let is_control = state.config.load().control_chat_ids.contains(&chat_id);
+    // Global memories are shared across chats, so only control chats export them.
+    let memories = match call_blocking(state.db.clone(), move |db| {
+        let mut memories = db.get_all_memories_for_chat(Some(chat_id))?;
+        if is_control {
+            memories.extend(db.get_all_memories_for_chat(None)?);
+        }
+        Ok(memories)
+    })
+    .await
+    {
+        Ok(memories) => memories,
+        Err(e) => return format!("Failed to load memories: {e}"),
+    };
+    if memories.is_empty() {
+        return "No memories to export for this chat.".to_string();
+    }
+
+    let now = chrono::Utc::now();
+    let markdown = render_memories_markdown(chat_id, &memories, &now.to_rfc3339());
+    let channel_dir = if caller_channel.trim().is_empty() {
+        "unknown"
+    } else {
+        caller_channel.trim()
+    };
+    let dir = std::path::PathBuf::from(&state.config.load().data_dir)
+        .join("groups")
+        .join(channel_dir)
+        .join(chat_id.to_string())
+        .join("exports");
+    let path = dir.join(format!("memories-{}.md", now.format("%Y%m%d-%H%M%S")));
+    let write = async {
+        tokio::fs::create_dir_all(&dir).await?;
+        tokio::fs::write(&path, markdown).await
+    };
+    if let Err(e) = write.await {
+        return format!("Failed to write memory export: {e}");
+    }
+    format!("Exported {} memories to {}", memories.len(), path.display())
+}
+
+/// Markdown for `/memories export`: one section per category (alphabetical),
+/// newest first within each, with confidence, source and timestamps.
+fn render_memories_markdown(chat_id: i64, memories: &[Memory], exported_at: &str) -> String {
+    let mut by_category: std::collections::BTreeMap<&str, Vec<&Memory>> =
+        std::collections::BTreeMap::new();
+    for memory in memories {
+        by_category
+            .entry(memory.category.as_str())
+            .or_default()
+            .push(memory);
+    }
+    let mut out = format!(
+        "# Memories for chat {chat_id}\n\nExported {exported_at} ({} memories)\n",
+        memories.len()
+    );
+    for (category, mut items) in by_category {
+        items.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+        out.push_str(&format!("\n## {category} ({})\n\n", items.len()));
+        for memory in items {
+            let mut tags = Vec::new();
+            if memory.chat_id.is_none() {
+                tags.push("global");
+            }
+            if memory.is_archived {
+                tags.push("archived");
+            }
+            let tags = if tags.is_empty() {
+                String::new()
+            } else {
+                format!(" [{}]", tags.join(", "))
+            };
+            out.push_str(&format!(
+                "- {}{tags}\n  - confidence {:.2}, source {}, created {}, updated {}\n",
+                memory.content.trim(),
+                memory.confidence,
+                memory.source,
+                memory.created_at,
+                memory.updated_at
+            ));
+        }
+    }
+    out
+}
+
+fn format_chat_stats(stats: &ChatStats) -> String {
+    if stats.user_messages == 0 && stats.bot_messages == 0 && stats.top_tools.is_empty() {
+        return "No activity recorded for this chat yet.".to_string();
+    }
+    let mut lines = vec!["Stats for this chat:".to_string()];
+    let since = stats
+        .first_message_at
+        .as_deref()
+        .and_then(|ts| ts.get(..10))
+        .map(|day| format!(" since {day}"))
+        .unwrap_or_default();
+    lines.push(format!(
+        "- Messages: {} from users, {} from the bot{since}",
+        stats.user_messages, stats.bot_messages
+    ));
+    if stats.top_tools.is_empty() {
+        lines.push("- Most-used tools: none".to_string());
+    } else {
+        let tools = stats
+            .top_tools
+            .iter()
+            .map(|(name, calls, errors)| {
+                if *errors > 0 {
+                    format!("{name} ×{calls} ({errors} failed)")
+                } else {
+                    format!("{name} ×{calls}")
+                }
+            })
+            .collect::<Vec<_>>();
+        lines.push(format!("- Most-used tools: {}", tools.join(", ")));
+    }
+    if !stats.active_hours.is_empty() {
+        let hours = stats
+            .active_hours
+            .iter()
+            .map(|(hour, n)| format!("{hour:02}:00 ({n})"))
+            .collect::<Vec<_>>();
+        lines.push(format!("- Most active hours (UTC): {}", hours.join(", ")));
+    }
+    lines.push(format!("- Active memories: {}", stats.active_memories));
+    lines.join("\n")
+}
+
+/// `/loglevel [<level>|<target>=<level>,...]` shows or changes the process-wide
+/// log filter. Restricted to control chats.
+pub fn build_loglevel_response(
+    config: &Config,
+    handle: Option<&crate::logging::LogFilterHandle>,
+    chat_id: i64,
+    command_text: &str,
+) -> String {
+    if !config.control_chat_ids.contains(&chat_id) {
+        return "Changing the log level requires control chat permission.".to_string();
+    }
+    let Some(handle) = handle else {
+        return "Log level cannot be changed: logging is not initialized.".to_string();
+    };
+    let arg = command_text
+        .trim()
+        .strip_prefix("/loglevel")
+        .unwrap_or("")
+        .trim();
+    if arg.is_empty() {
+        return format!(
+            "Log level: {}\nUsage: /loglevel <{}>",
+            handle.current(),
+            crate::logging::LOG_LEVELS.join("|")
+        );
+    }
+    match handle.set(arg) {
+        Ok(directives) => {
+            tracing::info!("Log level changed to '{directives}' from chat {chat_id}");
+            format!("Log level set to {directives}.")
+        }
+        Err(e) => format!("Log level unchanged: {e}"),
+    }
+}
+
+/// `/mcp [reload]` lists the connected MCP servers, or re-reads the MCP
+/// config and reconnects without a restart. Restricted to control chats.
+pub async fn build_mcp_response(state: &AppState, chat_id: i64, command_text: &str) -> String {
+    if !state.config.load().control_chat_ids.contains(&chat_id) {
+        return "Managing MCP servers requires control chat permission.".to_string();
+    }
+    let arg = command_text
+        .trim()
+        .strip_prefix("/mcp")
+        .unwrap_or("")
+        .trim();
+    match arg {
+        "" => format_mcp_servers(&mcp_health(state)),
+        "reload" => match state.mcp_manager.reload().await {
+            Ok(report) => {
+                state
+                    .tools
+                    .set_mcp_tools(crate::tools::mcp::mcp_tools(&state.mcp_manager));
+                info!("MCP reloaded from chat {chat_id}: {report:?}");
+                format!(
+                    "{}\n\n{}",
+                    format_mcp_reload(&report),
+                    format_mcp_servers(&mcp_health(state))
+                )
+            }
+            Err(e) => format!("MCP reload failed: {e}"),
+        },
+        _ => "Usage: /mcp [reload]".to_string(),
+    }
+}
+
+fn mcp_health(state: &AppState) -> Vec<McpServerHealth> {
+    state
+        .mcp_manager
+        .servers()
+        .iter()
+        .map(|server| server.health_snapshot())
+        .collect()
+}
+
+fn format_mcp_servers(servers: &[McpServerHealth]) -> String {
+    if servers.is_empty() {
+        return "No MCP servers connected.".to_string();
+    }
+    let mut lines = vec![format!("MCP servers ({}):", servers.len())];
+    for server in servers {
+        let status = match (&server.last_error, server.reachable) {
+            (_, true) => "reachable".to_string(),
+            (Some(e), false) => format!("unreachable: {}", clip_chars(e, 120)),
+            (None, false) => "unreachable".to_string(),
+        };
+        lines.push(format!(
+            "- {}: {} tools, protocol {}, {}",
+            server.name, server.tool_count, server.protocol_version, status
+        ));
+    }
+    lines.join("\n")
+}
+
+fn format_mcp_reload(report: &McpReloadReport) -> String {
+    let mut lines = vec!["MCP config reloaded.".to_string()];
+    for (label, names) in [
+        ("Added", &report.added),
+        ("Removed", &report.removed),
+        ("Reconnected", &report.reconnected),
+    ] {
+        if !names.is_empty() {
+            lines.push(format!("{label}: {}", names.join(", ")));
+        }
+    }
+    for (name, error) in &report.failed {
+        lines.push(format!("Failed: {name} ({})", clip_chars(error, 200)));
+    }
+    lines.join("\n")
+}
+
+/// `/web [on|off] [search|fetch|http|browser]` shows or changes whether the
+/// network-capable tools are available in this chat. Without a tool name all
+/// of them are changed.
+pub async fn build_web_response(db: Arc<Database>, chat_id: i64, command_text: &str) -> String {
+    let args: Vec<String> = command_text
+        .trim()
+        .strip_prefix("/web")
+        .unwrap_or("")
+        .split_whitespace()
+        .map(str::to_ascii_lowercase)
+        .collect();
+    let usage = "Usage: /web [on|off] [search|fetch|http|browser]";
+    let targets: Vec<&str> = match args.get(1).map(String::as_str) {
+        None => WEB_TOOL_NAMES.to_vec(),
+        Some("search") | Some("web_search") => vec!["web_search"],
+        Some("fetch") | Some("web_fetch") => vec!["web_fetch"],
+        Some("http") | Some("http_request") => vec!["http_request"],
+        Some("browser") => vec!["browser"],
+        Some(_) => return usage.to_string(),
+    };
+    if args.len() > 2 {
+        return usage.to_string();
+    }
+
+    let mut disabled = disabled_tools_for_chat(db.clone(), chat_id).await;
+    match args.first().map(String::as_str) {
+        None => {
+            let status = WEB_TOOL_NAMES
+                .iter()
+                .map(|name| {
+                    let state = if disabled.iter().any(|d| d == name) {
+                        "off"
+                    } else {
+                        "on"
+                    };
+                    format!("{name}: {state}")
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            return format!("Web tools for this chat: {status}.");
+        }
+        Some("on") => disabled.retain(|d| !targets.contains(&d.as_str())),
+        Some("off") => {
+            for name in &targets {
+                if !disabled.iter().any(|d| d == name) {
+                    disabled.push(name.to_string());
+                }
+            }
+        }
+        Some(_) => return usage.to_string(),
+    }
+
+    let value = disabled.join(",");
+    let result = call_blocking(db, move |db| {
+        if value.is_empty() {
+            db.delete_chat_setting(chat_id, DISABLED_TOOLS_SETTING_KEY)
+                .map(|_| ())
+        } else {
+            db.set_chat_setting(chat_id, DISABLED_TOOLS_SETTING_KEY, &value)
+        }
+    })
+    .await;
+    match result {
+        Ok(()) => {
+            let verb = if args[0] == "on" {
+                "enabled"
+            } else {
+                "disabled"
+            };
+            format!("{} {verb} for this chat.", targets.join(", "))
+        }
+        Err(e) => format!("Failed to update web tools setting: {e}"),
+    }
+}
+
+/// `/streaming [on|off|default]` shows or changes whether this chat receives
+/// streamed (live-edited) replies or only the final response.
+pub async fn build_streaming_response(
+    db: Arc<Database>,
+    chat_id: i64,
+    command_text: &str,
+) -> String {
+    let arg = command_text
+        .trim()
+        .strip_prefix("/streaming")
+        .unwrap_or("")
+        .trim()
+        .to_ascii_lowercase();
+    let result = match arg.as_str() {
+        "" => {
+            return match call_blocking(db, move |db| {
+                db.get_chat_setting(chat_id, STREAMING_SETTING_KEY)
+            })
+            .await
+            {
+                Ok(Some(v)) => format!("Streaming for this chat: {v}."),
+                Ok(None) => "Streaming for this chat: channel default.".to_string(),
+                Err(e) => format!("Failed to read streaming setting: {e}"),
+            };
+        }
+        "on" | "off" => {
+            let value = arg.clone();
+            call_blocking(db, move |db| {
+                db.set_chat_setting(chat_id, STREAMING_SETTING_KEY, &value)
+            })
+            .await
+        }
+        "default" => {
+            call_blocking(db, move |db| {
+                db.delete_chat_setting(chat_id, STREAMING_SETTING_KEY)
+                    .map(|_| ())
+            })
+            .await
+        }
+        _ => return "Usage: /streaming [on|off|default]".to_string(),
+    };
+    match result {
+        Ok(()) if arg == "default" => {
+            "Streaming for this chat reset to the channel default.".to_string()
+        }
+        Ok(()) if arg == "on" => "Streaming enabled for this chat.".to_string(),
+        Ok(()) => {
+            "Streaming disabled for this chat; only final responses will be sent.".to_string()
+        }
+        Err(e) => format!("Failed to update streaming setting: {e}"),
+    }
+}
+
+/// How many side-effect-free tool calls may run at once in this chat. A
+/// per-chat `/parallel <n>` choice can lower `tool_parallel_max` but never
+/// raise it.
+pub async fn parallel_tool_limit_for_chat(
+    db: Arc<Database>,
+    chat_id: i64,
+    global_default: usize,
+) -> usize {
+    match call_blocking(db, move |db| {
+        db.get_chat_setting(chat_id, PARALLEL_TOOLS_SETTING_KEY)
+    })
+    .await
+    {
+        Ok(Some(v)) => v
+            .parse::<usize>()
+            .ok()
+            .filter(|n| *n >= 1)
+            .map_or(global_default, |n| n.min(global_default)),
+        Ok(None) => global_default,
+        Err(e) => {
+            warn!("Failed to read parallel tools setting for chat {chat_id}: {e}");
+            global_default
+        }
+    }
+}
+
+/// `/parallel [<n>|default]` shows or changes how many side-effect-free tool
+/// calls run concurrently in this chat. `1` runs them one at a time.
+pub async fn build_parallel_response(
+    db: Arc<Database>,
+    chat_id: i64,
+    global_default: usize,
+    command_text: &str,
+) -> String {
+    let arg = command_text
+        .trim()
+        .strip_prefix("/parallel")
+        .unwrap_or("")
+        .trim()
+        .to_ascii_lowercase();
+    if arg.is_empty() {
+        let limit = parallel_tool_limit_for_chat(db, chat_id, global_default).await;
+        return format!("Parallel tool calls for this chat: up to {limit} at once.");
+    }
+    if arg == "default" {
+        return match call_blocking(db, move |db| {
+            db.delete_chat_setting(chat_id, PARALLEL_TOOLS_SETTING_KEY)
+                .map(|_| ())
+        })
+        .await
+        {
+            Ok(()) => format!(
+                "Parallel tool calls for this chat reset to the default ({global_default})."
+            ),
+            Err(e) => format!("Failed to update parallel tools setting: {e}"),
+        };
+    }
+    let max = global_default.max(1);
+    let Some(limit) = arg.parse::<usize>().ok().filter(|n| (1..=max).contains(n)) else {
+        return format!("Usage: /parallel [<1-{max}>|default]");
+    };
+    match call_blocking(db, move |db| {
+        db.set_chat_setting(chat_id, PARALLEL_TOOLS_SETTING_KEY, &limit.to_string())
+    })
+    .await
+    {
+        Ok(()) if limit == 1 => "Tool calls in this chat now run one at a time.".to_string(),
+        Ok(()) => {
+            format!("Up to {limit} side-effect-free tool calls now run at once in this chat.")
+        }
+        Err(e) => format!("Failed to update parallel tools setting: {e}"),
+    }
+}
+
 pub async fn build_status_response(
     db: Arc<Database>,
     config: &Config,
@@ -212,6 +1337,9 @@ pub async fn build_status_response(
         caller_channel,
     )
     .await;
+    let model = chat_model_override(db.clone(), chat_id, &profile.alias)
+        .await
+        .unwrap_or(model);
     let provider = profile.alias.clone();
 
     let session_line = match call_blocking(db.clone(), move |db| db.load_session(chat_id)).await {
@@ -246,58 +1374,211 @@ pub async fn build_status_response(
                 }
             }
 
-            let total = active + paused + completed + cancelled + other;
-            if other == 0 {
-                format!(
-                    "Scheduled tasks: total={total}, active={active}, paused={paused}, completed={completed}, cancelled={cancelled}"
-                )
-            } else {
-                format!(
-                    "Scheduled tasks: total={total}, active={active}, paused={paused}, completed={completed}, cancelled={cancelled}, other={other}"
-                )
-            }
-        }
-        Err(e) => format!("Scheduled tasks: unavailable ({e})"),
+            let total = active + paused + completed + cancelled + other;
+            if other == 0 {
+                format!(
+                    "Scheduled tasks: total={total}, active={active}, paused={paused}, completed={completed}, cancelled={cancelled}"
+                )
+            } else {
+                format!(
+                    "Scheduled tasks: total={total}, active={active}, paused={paused}, completed={completed}, cancelled={cancelled}, other={other}"
+                )
+            }
+        }
+        Err(e) => format!("Scheduled tasks: unavailable ({e})"),
+    };
+
+    format!(
+        "Status\nChannel: {caller_channel}\nProvider: {provider}\nModel: {model}\n{session_line}\n{task_line}"
+    )
+}
+
+/// The model chosen for this chat with `/model`, if any. It takes precedence
+/// over the channel's model override and the provider default. A choice made
+/// for another provider than `provider_alias` is ignored; `/provider` clears
+/// it when the chat switches providers.
+pub(crate) async fn chat_model_override(
+    db: Arc<Database>,
+    chat_id: i64,
+    provider_alias: &str,
+) -> Option<String> {
+    let (provider, model) = call_blocking(db, move |db| db.get_chat_model_override(chat_id))
+        .await
+        .ok()
+        .flatten()?;
+    provider
+        .eq_ignore_ascii_case(provider_alias)
+        .then_some(model)
+}
+
+/// Where the session should start after dropping at least `n` of its oldest
+/// messages: the next plain user turn, so the session never opens with an
+/// assistant reply or an orphaned tool result.
+fn session_trim_point(messages: &[Message], n: usize) -> usize {
+    let is_user_turn = |m: &Message| {
+        m.role == "user"
+            && !matches!(&m.content, MessageContent::Blocks(blocks)
+                if blocks.iter().any(|b| matches!(b, ContentBlock::ToolResult { .. })))
+    };
+    let mut start = n.min(messages.len());
+    while start < messages.len() && !is_user_turn(&messages[start]) {
+        start += 1;
+    }
+    start
+}
+
+fn format_age(secs: i64) -> String {
+    let secs = secs.max(0);
+    match secs {
+        s if s < 60 => format!("{s}s"),
+        s if s < 3600 => format!("{}m", s / 60),
+        s if s < 86_400 => format!("{}h {}m", s / 3600, s % 3600 / 60),
+        s => format!("{}d {}h", s / 86_400, s % 86_400 / 3600),
+    }
+}
+
+fn age_since(timestamp: &str, now: chrono::DateTime<chrono::Utc>) -> Option<String> {
+    let then = chrono::DateTime::parse_from_rfc3339(timestamp).ok()?;
+    Some(format_age(
+        now.signed_duration_since(then.with_timezone(&chrono::Utc))
+            .num_seconds(),
+    ))
+}
+
+/// `/session` reports the size and age of the chat's session; `/session trim
+/// <n>` archives and drops its oldest messages.
+pub async fn build_session_response(
+    db: Arc<Database>,
+    config: &Arc<Config>,
+    caller_channel: &str,
+    chat_id: i64,
+    command_text: &str,
+) -> String {
+    let args = command_text
+        .trim()
+        .strip_prefix("/session")
+        .map(str::trim)
+        .unwrap_or("");
+    let trim_count = match args.split_whitespace().collect::<Vec<_>>().as_slice() {
+        [] => None,
+        ["trim", n] => match n.parse::<usize>() {
+            Ok(n) if n > 0 => Some(n),
+            _ => return "Usage: /session trim <n> (n must be a positive number)".to_string(),
+        },
+        _ => return "Usage: /session | /session trim <n>".to_string(),
+    };
+
+    let (json, updated_at) =
+        match call_blocking(db.clone(), move |db| db.load_session(chat_id)).await {
+            Ok(Some(session)) => session,
+            Ok(None) => return "No active session.".to_string(),
+            Err(e) => return format!("Failed to load session: {e}"),
+        };
+    let messages: Vec<Message> = match serde_json::from_str(&json) {
+        Ok(messages) => messages,
+        Err(e) => return format!("Failed to read session: {e}"),
+    };
+    if messages.is_empty() {
+        return "No active session.".to_string();
+    }
+
+    let Some(n) = trim_count else {
+        let chars: usize = messages
+            .iter()
+            .map(|m| message_to_text(m).chars().count())
+            .sum();
+        let now = chrono::Utc::now();
+        let created_at = call_blocking(db, move |db| db.load_session_created_at(chat_id))
+            .await
+            .ok()
+            .flatten();
+        let age = created_at
+            .as_deref()
+            .and_then(|t| age_since(t, now))
+            .unwrap_or_else(|| "unknown".to_string());
+        let updated = age_since(&updated_at, now).unwrap_or(updated_at);
+        return format!(
+            "Session: {} messages, ~{} tokens\nAge: {age} (last updated {updated} ago)",
+            messages.len(),
+            chars / 4
+        );
     };
 
+    let start = session_trim_point(&messages, n);
+    let (removed, kept) = messages.split_at(start);
+    archive_conversation(config, caller_channel, chat_id, removed);
+    let saved = if kept.is_empty() {
+        call_blocking(db, move |db| db.delete_session(chat_id)).await
+    } else {
+        let kept_json = match serde_json::to_string(kept) {
+            Ok(json) => json,
+            Err(e) => return format!("Failed to trim session: {e}"),
+        };
+        call_blocking(db, move |db| {
+            db.update_session_messages(chat_id, &kept_json)
+        })
+        .await
+    };
+    if let Err(e) = saved {
+        return format!("Failed to trim session: {e}");
+    }
+    let extra = if removed.len() > n {
+        format!(
+            " ({} more than requested, so the session starts at a user message)",
+            removed.len() - n
+        )
+    } else {
+        String::new()
+    };
+    if kept.is_empty() {
+        return format!(
+            "Archived and removed all {} messages{extra}. The session is now empty.",
+            removed.len()
+        );
+    }
     format!(
-        "Status\nChannel: {caller_channel}\nProvider: {provider}\nModel: {model}\n{session_line}\n{task_line}"
+        "Archived and removed the oldest {} messages{extra}. {} messages remain.",
+        removed.len(),
+        kept.len()
     )
 }
 
 pub async fn build_model_response(
+    db: Arc<Database>,
     config: &Config,
     llm_provider_overrides: Arc<tokio::sync::RwLock<std::collections::HashMap<String, String>>>,
     llm_model_overrides: Arc<tokio::sync::RwLock<std::collections::HashMap<String, String>>>,
     caller_channel: &str,
-    _chat_id: i64,
+    chat_id: i64,
     command_text: &str,
 ) -> String {
-    let caller_channel = caller_channel.to_string();
     let requested = command_text
         .trim()
         .strip_prefix("/model")
         .map(str::trim)
         .unwrap_or("");
-    let (profile, current_model) = resolve_effective_provider_and_model(
+    let (profile, channel_model) = resolve_effective_provider_and_model(
         config,
         &llm_provider_overrides,
         &llm_model_overrides,
-        &caller_channel,
+        caller_channel,
     )
     .await;
     let provider = profile.alias.clone();
 
     if requested.is_empty() {
+        let current_model = chat_model_override(db, chat_id, &provider)
+            .await
+            .unwrap_or(channel_model);
         return format!("Current provider/model: {provider} / {current_model}");
     }
 
     if requested.eq_ignore_ascii_case("reset") || requested.eq_ignore_ascii_case("default") {
-        let mut overrides = llm_model_overrides.write().await;
-        overrides.remove(&caller_channel);
+        if let Err(e) = call_blocking(db, move |db| db.delete_chat_model_override(chat_id)).await {
+            return format!("Failed to clear model override: {e}");
+        }
         return format!(
-            "Model override cleared. Current provider/model: {provider} / {}",
-            profile.default_model
+            "Model override cleared. Current provider/model: {provider} / {channel_model}"
         );
     }
 
@@ -316,9 +1597,16 @@ pub async fn build_model_response(
         );
     }
 
-    let mut overrides = llm_model_overrides.write().await;
-    overrides.insert(caller_channel.clone(), requested.to_string());
-    format!("Model switched for this channel to: {provider} / {requested}")
+    let model = requested.to_string();
+    let alias = provider.clone();
+    match call_blocking(db, move |db| {
+        db.set_chat_model_override(chat_id, &alias, &model)
+    })
+    .await
+    {
+        Ok(()) => format!("Model switched for this chat to: {provider} / {requested}"),
+        Err(e) => format!("Failed to save model override: {e}"),
+    }
 }
 
 pub async fn build_providers_response(
@@ -354,10 +1642,12 @@ pub async fn build_providers_response(
 }
 
 pub async fn build_provider_response(
+    db: Arc<Database>,
     config: &Config,
     llm_provider_overrides: Arc<tokio::sync::RwLock<std::collections::HashMap<String, String>>>,
     llm_model_overrides: Arc<tokio::sync::RwLock<std::collections::HashMap<String, String>>>,
     caller_channel: &str,
+    chat_id: i64,
     command_text: &str,
 ) -> String {
     let requested = command_text
@@ -388,6 +1678,7 @@ pub async fn build_provider_response(
             let mut model_overrides = llm_model_overrides.write().await;
             model_overrides.remove(caller_channel);
         }
+        let _ = call_blocking(db, move |db| db.delete_chat_model_override(chat_id)).await;
         let profile = config
             .resolve_llm_provider_profile(&config.llm_provider)
             .expect("default provider should resolve");
@@ -415,6 +1706,8 @@ pub async fn build_provider_response(
         let mut model_overrides = llm_model_overrides.write().await;
         model_overrides.remove(caller_channel);
     }
+    // A /model choice belongs to the provider it was made for.
+    let _ = call_blocking(db, move |db| db.delete_chat_model_override(chat_id)).await;
     format!(
         "Provider switched for this channel to: {} (backend={}), model reset to {}",
         profile.alias, profile.provider, profile.default_model
@@ -710,11 +2003,20 @@ pub async fn maybe_handle_plugin_command(
 #[cfg(test)]
 mod tests {
     use super::{
-        build_model_response, build_models_response, build_provider_response,
-        is_placeholder_model_list, parse_anthropic_models_json_ids, parse_models_command_args,
-        parse_openai_models_json_ids, resolve_openai_models_url,
+        build_debug_response, build_loglevel_response, build_model_response, build_models_response,
+        build_parallel_response, build_provider_response, build_session_response,
+        build_streaming_response, build_tools_response, build_web_response, debug_enabled_for_chat,
+        disabled_tools_for_chat, format_chat_stats, format_mcp_reload, format_mcp_servers,
+        is_catchup_command, is_placeholder_model_list, parallel_tool_limit_for_chat,
+        parse_anthropic_models_json_ids, parse_catchup_limit, parse_models_command_args,
+        parse_openai_models_json_ids, parse_summary_range, render_memories_markdown,
+        render_tool_trace, resolve_openai_models_url, session_trim_point,
+        streaming_enabled_for_chat, summary_range_since, SummaryRange,
     };
     use crate::config::{Config, LlmProviderProfile, ResolvedLlmProviderProfile};
+    use crate::mcp::{McpReloadReport, McpServerHealth};
+    use microclaw_core::llm_types::{ContentBlock, Message, MessageContent};
+    use microclaw_storage::db::{ChatStats, Database, Memory};
     use std::collections::HashMap;
     use std::io::{Read, Write};
     use std::net::TcpListener;
@@ -753,23 +2055,206 @@ mod tests {
         cfg
     }
 
+    fn sample_tool_definitions() -> Vec<microclaw_core::llm_types::ToolDefinition> {
+        use microclaw_core::llm_types::ToolDefinition;
+        vec![
+            ToolDefinition {
+                name: "web_fetch".to_string(),
+                description: "Fetch a URL and return its text. Supports HTML and JSON.".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "url": {"type": "string", "description": "URL to fetch"},
+                        "format": {"type": "string", "enum": ["text", "markdown"]},
+                        "headers": {"type": "array", "items": {"type": "string"}}
+                    },
+                    "required": ["url"]
+                }),
+            },
+            ToolDefinition {
+                name: "mcp_github_list_issues".to_string(),
+                description: "List issues in a repository".to_string(),
+                input_schema: serde_json::json!({"type": "object", "properties": {}}),
+            },
+        ]
+    }
+
+    #[test]
+    fn tools_command_lists_sorted_tools_with_summaries() {
+        let text = build_tools_response(&sample_tool_definitions(), "/tools");
+        assert_eq!(
+            text,
+            "Available tools (2):\n\
+             - mcp_github_list_issues: List issues in a repository\n\
+             - web_fetch: Fetch a URL and return its text.\n\
+             Use /tools <name> for parameters."
+        );
+        assert_eq!(build_tools_response(&[], "/tools"), "No tools available.");
+    }
+
+    #[test]
+    fn tools_command_hides_disabled_tools() {
+        let definitions = crate::agent_engine::without_disabled_tools(
+            sample_tool_definitions(),
+            &["web_fetch".to_string()],
+        );
+        let text = build_tools_response(&definitions, "/tools");
+        assert!(text.starts_with("Available tools (1):"));
+        assert!(!text.contains("web_fetch"));
+        assert!(build_tools_response(&definitions, "/tools web_fetch").starts_with("Unknown tool"));
+    }
+
+    #[test]
+    fn tools_command_describes_single_tool() {
+        let defs = sample_tool_definitions();
+        let text = build_tools_response(&defs, "/tools WEB_FETCH");
+        assert_eq!(
+            text,
+            "web_fetch\nFetch a URL and return its text. Supports HTML and JSON.\n\n\
+             Parameters:\n\
+             - url (string, required): URL to fetch\n\
+             - format (string) [one of: text, markdown]\n\
+             - headers (array of string)"
+        );
+        let text = build_tools_response(&defs, "/tools mcp_github_list_issues");
+        assert!(text.ends_with("Parameters: none"));
+        let text = build_tools_response(&defs, "/tools nope");
+        assert_eq!(
+            text,
+            "Unknown tool: nope. Use /tools to list available tools."
+        );
+    }
+
+    #[test]
+    fn summary_range_parses_supported_forms() {
+        assert_eq!(parse_summary_range(""), Ok(SummaryRange::Recent));
+        assert_eq!(
+            parse_summary_range("last 2 hours"),
+            Ok(SummaryRange::LastMinutes(120))
+        );
+        assert_eq!(
+            parse_summary_range("30m"),
+            Ok(SummaryRange::LastMinutes(30))
+        );
+        assert_eq!(
+            parse_summary_range("last day"),
+            Ok(SummaryRange::LastMinutes(24 * 60))
+        );
+        assert_eq!(
+            parse_summary_range("last 50 messages"),
+            Ok(SummaryRange::LastMessages(50))
+        );
+        assert!(parse_summary_range("last 0 hours").is_err());
+        assert!(parse_summary_range("last 2 fortnights").is_err());
+        assert!(parse_summary_range("999999999999999d").is_err());
+        assert!(parse_summary_range("last 9223372036854775807 hours").is_err());
+    }
+
+    #[test]
+    fn catchup_command_parses_optional_limit() {
+        assert!(is_catchup_command("/catchup"));
+        assert!(is_catchup_command("@bot /catchup 200"));
+        assert!(!is_catchup_command("/catchupnow"));
+        assert_eq!(parse_catchup_limit("/catchup"), Ok(100));
+        assert_eq!(parse_catchup_limit("/catchup 200"), Ok(200));
+        assert!(parse_catchup_limit("/catchup 0").is_err());
+        assert!(parse_catchup_limit("/catchup 501").is_err());
+        assert!(parse_catchup_limit("/catchup lots").is_err());
+    }
+
+    #[test]
+    fn summary_range_since_only_applies_to_time_windows() {
+        let now = chrono::DateTime::parse_from_rfc3339("2026-01-02T12:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        assert_eq!(
+            summary_range_since(SummaryRange::LastMinutes(120), now).as_deref(),
+            Some("2026-01-02T10:00:00+00:00")
+        );
+        assert_eq!(
+            summary_range_since(SummaryRange::LastMessages(5), now),
+            None
+        );
+        assert_eq!(summary_range_since(SummaryRange::Recent, now), None);
+        let SummaryRange::LastMinutes(huge) = parse_summary_range("99999999999d").unwrap() else {
+            panic!("expected a time window");
+        };
+        assert!(summary_range_since(SummaryRange::LastMinutes(huge), now).is_some());
+        assert_eq!(
+            SummaryRange::LastMinutes(120).describe(),
+            "the last 2 hours"
+        );
+    }
+
+    fn model_test_db() -> (Arc<Database>, std::path::PathBuf) {
+        let dir = std::env::temp_dir().join(format!("mc_model_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let db = Arc::new(Database::new(dir.to_str().unwrap()).unwrap());
+        (db, dir)
+    }
+
     #[tokio::test]
     async fn model_command_reports_current_model() {
         let cfg = test_config();
+        let (db, dir) = model_test_db();
         let provider_overrides = Arc::new(RwLock::new(HashMap::new()));
-        let overrides = Arc::new(RwLock::new(HashMap::new()));
-        let text =
-            build_model_response(&cfg, provider_overrides, overrides, "telegram", 1, "/model")
-                .await;
+        let mut map = HashMap::new();
+        map.insert("telegram".to_string(), "gpt-5".to_string());
+        let overrides = Arc::new(RwLock::new(map));
+        let text = build_model_response(
+            db.clone(),
+            &cfg,
+            provider_overrides.clone(),
+            overrides.clone(),
+            "telegram",
+            1,
+            "/model",
+        )
+        .await;
+        assert_eq!(text, "Current provider/model: openai / gpt-5");
+
+        db.set_chat_model_override(1, "openai", "gpt-5.2").unwrap();
+        let text = build_model_response(
+            db.clone(),
+            &cfg,
+            provider_overrides.clone(),
+            overrides.clone(),
+            "telegram",
+            1,
+            "/model",
+        )
+        .await;
         assert_eq!(text, "Current provider/model: openai / gpt-5.2");
+
+        // A model chosen under another provider is ignored, not sent to this one.
+        db.set_chat_model_override(1, "anthropic", "claude-sonnet-4-5")
+            .unwrap();
+        let text = build_model_response(
+            db.clone(),
+            &cfg,
+            provider_overrides,
+            overrides,
+            "telegram",
+            1,
+            "/model",
+        )
+        .await;
+        assert_eq!(text, "Current provider/model: openai / gpt-5");
+        assert_eq!(
+            db.get_chat_model_override(1).unwrap(),
+            Some(("anthropic".to_string(), "claude-sonnet-4-5".to_string()))
+        );
+        let _ = std::fs::remove_dir_all(&dir);
     }
 
     #[tokio::test]
-    async fn model_command_sets_override() {
+    async fn model_command_sets_override_per_chat() {
         let cfg = test_config();
+        let (db, dir) = model_test_db();
         let provider_overrides = Arc::new(RwLock::new(HashMap::new()));
         let overrides = Arc::new(RwLock::new(HashMap::new()));
         let text = build_model_response(
+            db.clone(),
             &cfg,
             provider_overrides,
             overrides.clone(),
@@ -778,22 +2263,52 @@ mod tests {
             "/model gpt-5",
         )
         .await;
-        assert_eq!(text, "Model switched for this channel to: openai / gpt-5");
-        let guard = overrides.read().await;
-        assert_eq!(guard.get("telegram").map(String::as_str), Some("gpt-5"));
+        assert_eq!(text, "Model switched for this chat to: openai / gpt-5");
+        assert_eq!(
+            db.get_chat_model_override(1).unwrap(),
+            Some(("openai".to_string(), "gpt-5".to_string()))
+        );
+        assert_eq!(db.get_chat_model_override(2).unwrap(), None);
+        assert!(overrides.read().await.is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn model_command_rejects_unknown_model_without_persisting() {
+        let cfg = test_config();
+        let (db, dir) = model_test_db();
+        let provider_overrides = Arc::new(RwLock::new(HashMap::new()));
+        let overrides = Arc::new(RwLock::new(HashMap::new()));
+        let text = build_model_response(
+            db.clone(),
+            &cfg,
+            provider_overrides,
+            overrides,
+            "telegram",
+            1,
+            "/model not-a-model",
+        )
+        .await;
+        assert!(
+            text.contains("Model 'not-a-model' is not configured"),
+            "unexpected text: {text}"
+        );
+        assert_eq!(db.get_chat_model_override(1).unwrap(), None);
+        let _ = std::fs::remove_dir_all(&dir);
     }
 
     #[tokio::test]
     async fn model_command_resets_override() {
         let cfg = test_config();
+        let (db, dir) = model_test_db();
+        db.set_chat_model_override(1, "openai", "gpt-5").unwrap();
         let provider_overrides = Arc::new(RwLock::new(HashMap::new()));
-        let mut map = HashMap::new();
-        map.insert("telegram".to_string(), "qwen".to_string());
-        let overrides = Arc::new(RwLock::new(map));
+        let overrides = Arc::new(RwLock::new(HashMap::new()));
         let text = build_model_response(
+            db.clone(),
             &cfg,
             provider_overrides,
-            overrides.clone(),
+            overrides,
             "telegram",
             1,
             "/model reset",
@@ -803,8 +2318,8 @@ mod tests {
             text,
             "Model override cleared. Current provider/model: openai / gpt-5.2"
         );
-        let guard = overrides.read().await;
-        assert!(!guard.contains_key("telegram"));
+        assert_eq!(db.get_chat_model_override(1).unwrap(), None);
+        let _ = std::fs::remove_dir_all(&dir);
     }
 
     #[tokio::test]
@@ -814,11 +2329,15 @@ mod tests {
         let mut model_map = HashMap::new();
         model_map.insert("telegram".to_string(), "gpt-5".to_string());
         let model_overrides = Arc::new(RwLock::new(model_map));
+        let (db, dir) = model_test_db();
+        db.set_chat_model_override(1, "openai", "gpt-5.2").unwrap();
         let text = build_provider_response(
+            db.clone(),
             &cfg,
             provider_overrides.clone(),
             model_overrides.clone(),
             "telegram",
+            1,
             "/provider anthropic",
         )
         .await;
@@ -834,6 +2353,8 @@ mod tests {
         drop(provider_guard);
         let model_guard = model_overrides.read().await;
         assert!(!model_guard.contains_key("telegram"));
+        assert_eq!(db.get_chat_model_override(1).unwrap(), None);
+        let _ = std::fs::remove_dir_all(&dir);
     }
 
     #[tokio::test]
@@ -992,10 +2513,12 @@ mod tests {
                 models: vec!["custom-model".to_string()],
             },
         );
+        let (db, dir) = model_test_db();
         let provider_overrides = Arc::new(RwLock::new(HashMap::new()));
         let model_overrides = Arc::new(RwLock::new(HashMap::new()));
 
         let ok = build_model_response(
+            db.clone(),
             &cfg,
             provider_overrides.clone(),
             model_overrides.clone(),
@@ -1006,10 +2529,11 @@ mod tests {
         .await;
         assert_eq!(
             ok,
-            "Model switched for this channel to: lab-local / model-live-a"
+            "Model switched for this chat to: lab-local / model-live-a"
         );
 
         let bad = build_model_response(
+            db,
             &cfg,
             provider_overrides,
             model_overrides,
@@ -1021,6 +2545,272 @@ mod tests {
         server.join().unwrap();
         assert!(bad.contains("Model 'not-real' is not configured"));
         assert!(bad.contains("model-live-a"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    fn text_message(role: &str, text: &str) -> Message {
+        Message {
+            role: role.into(),
+            content: MessageContent::Text(text.into()),
+        }
+    }
+
+    /// user, assistant tool call, tool result, assistant reply, user, assistant.
+    fn session_with_tool_call() -> Vec<Message> {
+        vec![
+            text_message("user", "list files"),
+            Message {
+                role: "assistant".into(),
+                content: MessageContent::Blocks(vec![ContentBlock::ToolUse {
+                    id: "toolu_1".into(),
+                    name: "bash".into(),
+                    input: serde_json::json!({"command": "ls"}),
+                    thought_signature: None,
+                }]),
+            },
+            Message {
+                role: "user".into(),
+                content: MessageContent::Blocks(vec![ContentBlock::ToolResult {
+                    tool_use_id: "toolu_1".into(),
+                    content: "a.txt".into(),
+                    is_error: None,
+                }]),
+            },
+            text_message("assistant", "There is a.txt"),
+            text_message("user", "thanks"),
+            text_message("assistant", "You're welcome"),
+        ]
+    }
+
+    #[test]
+    fn session_trim_point_starts_at_user_turn() {
+        let messages = session_with_tool_call();
+        assert_eq!(session_trim_point(&messages, 0), 0);
+        // Trimming into the tool exchange skips the orphaned tool result.
+        assert_eq!(session_trim_point(&messages, 1), 4);
+        assert_eq!(session_trim_point(&messages, 2), 4);
+        assert_eq!(session_trim_point(&messages, 4), 4);
+        assert_eq!(session_trim_point(&messages, 5), 6);
+        assert_eq!(session_trim_point(&messages, 50), 6);
+    }
+
+    #[tokio::test]
+    async fn session_trim_archives_removed_messages() {
+        let dir = std::env::temp_dir().join(format!("mc_session_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let db = Arc::new(Database::new(dir.to_str().unwrap()).unwrap());
+        let mut cfg = Config::test_defaults();
+        cfg.data_dir = dir.to_string_lossy().to_string();
+        let cfg = Arc::new(cfg);
+        let json = serde_json::to_string(&session_with_tool_call()).unwrap();
+        db.save_session(5, &json).unwrap();
+        let (_, updated_at) = db.load_session(5).unwrap().unwrap();
+
+        let status = build_session_response(db.clone(), &cfg, "web", 5, "/session").await;
+        assert!(status.starts_with("Session: 6 messages, ~"), "{status}");
+        assert!(status.contains("Age: "), "{status}");
+
+        let reply = build_session_response(db.clone(), &cfg, "web", 5, "/session trim 2").await;
+        assert_eq!(
+            reply,
+            "Archived and removed the oldest 4 messages (2 more than requested, so the session starts at a user message). 2 messages remain."
+        );
+        let (json, new_updated_at) = db.load_session(5).unwrap().unwrap();
+        assert_eq!(new_updated_at, updated_at);
+        let kept: Vec<Message> = serde_json::from_str(&json).unwrap();
+        assert_eq!(kept.len(), 2);
+        assert_eq!(kept[0].role, "user");
+        assert_eq!(kept[1].role, "assistant");
+
+        let archive_dir = dir
+            .join("groups")
+            .join("web")
+            .join("5")
+            .join("conversations");
+        let archives: Vec<_> = std::fs::read_dir(&archive_dir).unwrap().collect();
+        assert_eq!(archives.len(), 1);
+        let archived = std::fs::read_to_string(archives[0].as_ref().unwrap().path()).unwrap();
+        assert!(archived.contains("list files"));
+        assert!(archived.contains("There is a.txt"));
+        assert!(!archived.contains("thanks"));
+
+        assert!(
+            build_session_response(db.clone(), &cfg, "web", 5, "/session trim 0")
+                .await
+                .starts_with("Usage:")
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_debug_command_toggles_footer_per_chat() {
+        let dir = std::env::temp_dir().join(format!("mc_debug_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let db = Arc::new(Database::new(dir.to_str().unwrap()).unwrap());
+        assert!(!debug_enabled_for_chat(db.clone(), 5).await);
+
+        build_debug_response(db.clone(), 5, "/debug on").await;
+        assert!(debug_enabled_for_chat(db.clone(), 5).await);
+        assert!(!debug_enabled_for_chat(db.clone(), 6).await);
+        assert_eq!(
+            build_debug_response(db.clone(), 5, "/debug").await,
+            "Debug footer for this chat: on."
+        );
+        build_debug_response(db.clone(), 5, "/debug OFF").await;
+        assert!(!debug_enabled_for_chat(db.clone(), 5).await);
+        assert_eq!(
+            build_debug_response(db, 5, "/debug loud").await,
+            "Usage: /debug [on|off]"
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_web_command_toggles_web_tools_per_chat() {
+        let dir = std::env::temp_dir().join(format!("mc_web_tools_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let db = Arc::new(Database::new(dir.to_str().unwrap()).unwrap());
+        assert!(disabled_tools_for_chat(db.clone(), 5).await.is_empty());
+
+        let off = build_web_response(db.clone(), 5, "/web off").await;
+        assert_eq!(
+            off,
+            "web_search, web_fetch, http_request, browser disabled for this chat."
+        );
+        assert_eq!(
+            disabled_tools_for_chat(db.clone(), 5).await,
+            vec!["web_search", "web_fetch", "http_request", "browser"]
+        );
+        assert!(disabled_tools_for_chat(db.clone(), 6).await.is_empty());
+
+        build_web_response(db.clone(), 5, "/web on fetch").await;
+        assert_eq!(
+            build_web_response(db.clone(), 5, "/web").await,
+            "Web tools for this chat: web_search: off, web_fetch: on, http_request: off, browser: off."
+        );
+        build_web_response(db.clone(), 5, "/web on").await;
+        assert!(disabled_tools_for_chat(db.clone(), 5).await.is_empty());
+        assert!(db.get_chat_setting(5, "disabled_tools").unwrap().is_none());
+
+        assert_eq!(
+            build_web_response(db.clone(), 5, "/web maybe").await,
+            "Usage: /web [on|off] [search|fetch|http|browser]"
+        );
+        assert_eq!(
+            build_web_response(db, 5, "/web off images").await,
+            "Usage: /web [on|off] [search|fetch|http|browser]"
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_streaming_toggle_overrides_channel_default() {
+        let dir = std::env::temp_dir().join(format!("mc_streaming_{}", uuid::Uuid::new_v4()));
+        let db = Arc::new(Database::new(dir.to_str().unwrap()).unwrap());
+
+        assert!(streaming_enabled_for_chat(db.clone(), 5, true).await);
+        assert!(!streaming_enabled_for_chat(db.clone(), 5, false).await);
+
+        let off = build_streaming_response(db.clone(), 5, "/streaming off").await;
+        assert!(off.contains("only final responses"));
+        assert!(!streaming_enabled_for_chat(db.clone(), 5, true).await);
+        // Other chats keep the channel default.
+        assert!(streaming_enabled_for_chat(db.clone(), 6, true).await);
+
+        build_streaming_response(db.clone(), 5, "/streaming on").await;
+        assert!(streaming_enabled_for_chat(db.clone(), 5, false).await);
+        assert_eq!(
+            build_streaming_response(db.clone(), 5, "/streaming").await,
+            "Streaming for this chat: on."
+        );
+
+        build_streaming_response(db.clone(), 5, "/streaming default").await;
+        assert!(!streaming_enabled_for_chat(db.clone(), 5, false).await);
+
+        let usage = build_streaming_response(db, 5, "/streaming maybe").await;
+        assert!(usage.starts_with("Usage:"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_render_tool_trace_from_stored_rows() {
+        let dir = std::env::temp_dir().join(format!("mc_trace_{}", uuid::Uuid::new_v4()));
+        let db = Database::new(dir.to_str().unwrap()).unwrap();
+        assert_eq!(
+            render_tool_trace(&db.get_last_run_tool_calls(7).unwrap()),
+            "No tool calls recorded for the last run in this chat."
+        );
+
+        db.log_tool_call_trace(7, "old", "bash", false, r#"{"command":"pwd"}"#, "/tmp")
+            .unwrap();
+        let long_input = format!(r#"{{"path":"{}"}}"#, "a".repeat(400));
+        db.log_tool_call_trace(
+            7,
+            "new",
+            "read_file",
+            false,
+            &long_input,
+            "line one\nline two",
+        )
+        .unwrap();
+        db.log_tool_call_trace(
+            7,
+            "new",
+            "web_fetch",
+            true,
+            r#"{"api_key":"***"}"#,
+            "timeout",
+        )
+        .unwrap();
+
+        let text = render_tool_trace(&db.get_last_run_tool_calls(7).unwrap());
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "Last run: 2 tool calls");
+        assert_eq!(lines[1], "1. read_file (ok)");
+        assert!(lines[2].starts_with("   input: {\"path\":\"aaa"));
+        assert!(lines[2].ends_with("..."));
+        assert_eq!(lines[3], "   result: line one line two");
+        assert_eq!(lines[4], "2. web_fetch (error)");
+        assert_eq!(lines[5], "   input: {\"api_key\":\"***\"}");
+        assert_eq!(lines[6], "   result: timeout");
+        assert!(!text.contains("pwd"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_parallel_setting_overrides_global_default() {
+        let dir = std::env::temp_dir().join(format!("mc_parallel_{}", uuid::Uuid::new_v4()));
+        let db = Arc::new(Database::new(dir.to_str().unwrap()).unwrap());
+
+        assert_eq!(parallel_tool_limit_for_chat(db.clone(), 5, 4).await, 4);
+        let reply = build_parallel_response(db.clone(), 5, 4, "/parallel 1").await;
+        assert!(reply.contains("one at a time"));
+        assert_eq!(parallel_tool_limit_for_chat(db.clone(), 5, 4).await, 1);
+        // Other chats keep the global default.
+        assert_eq!(parallel_tool_limit_for_chat(db.clone(), 6, 4).await, 4);
+        assert_eq!(
+            build_parallel_response(db.clone(), 5, 4, "/parallel").await,
+            "Parallel tool calls for this chat: up to 1 at once."
+        );
+
+        for bad in ["/parallel 0", "/parallel 5", "/parallel lots"] {
+            assert!(build_parallel_response(db.clone(), 5, 4, bad)
+                .await
+                .starts_with("Usage:"));
+        }
+        assert_eq!(parallel_tool_limit_for_chat(db.clone(), 5, 4).await, 1);
+
+        assert_eq!(
+            build_parallel_response(db.clone(), 5, 4, "/parallel 5").await,
+            "Usage: /parallel [<1-4>|default]"
+        );
+        // A stored choice above a since-lowered tool_parallel_max is capped.
+        build_parallel_response(db.clone(), 5, 4, "/parallel 4").await;
+        assert_eq!(parallel_tool_limit_for_chat(db.clone(), 5, 2).await, 2);
+
+        build_parallel_response(db.clone(), 5, 4, "/parallel default").await;
+        assert_eq!(parallel_tool_limit_for_chat(db, 5, 4).await, 4);
+        let _ = std::fs::remove_dir_all(&dir);
     }
 
     #[test]
@@ -1042,6 +2832,163 @@ mod tests {
             "https://llm.chutes.ai/v1/models"
         );
     }
+
+    #[test]
+    fn test_format_chat_stats() {
+        assert_eq!(
+            format_chat_stats(&ChatStats::default()),
+            "No activity recorded for this chat yet."
+        );
+        let stats = ChatStats {
+            user_messages: 12,
+            bot_messages: 10,
+            first_message_at: Some("2024-01-01T09:15:00Z".into()),
+            last_message_at: Some("2024-02-01T10:00:00Z".into()),
+            top_tools: vec![("bash".into(), 5, 1), ("web_search".into(), 2, 0)],
+            active_hours: vec![(9, 4), (19, 1)],
+            active_memories: 3,
+        };
+        assert_eq!(
+            format_chat_stats(&stats),
+            "Stats for this chat:\n\
+             - Messages: 12 from users, 10 from the bot since 2024-01-01\n\
+             - Most-used tools: bash ×5 (1 failed), web_search ×2\n\
+             - Most active hours (UTC): 09:00 (4), 19:00 (1)\n\
+             - Active memories: 3"
+        );
+    }
+
+    fn memory(
+        id: i64,
+        chat_id: Option<i64>,
+        category: &str,
+        content: &str,
+        updated: &str,
+    ) -> Memory {
+        Memory {
+            id,
+            chat_id,
+            content: content.to_string(),
+            category: category.to_string(),
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            updated_at: updated.to_string(),
+            embedding_model: None,
+            confidence: 0.9,
+            source: "explicit".to_string(),
+            last_seen_at: updated.to_string(),
+            is_archived: false,
+            archived_at: None,
+        }
+    }
+
+    #[test]
+    fn test_render_memories_markdown_groups_by_category() {
+        let mut archived = memory(
+            3,
+            Some(5),
+            "PROFILE",
+            "Old city: Paris",
+            "2024-01-02T00:00:00Z",
+        );
+        archived.is_archived = true;
+        archived.confidence = 0.456;
+        let memories = vec![
+            memory(
+                1,
+                Some(5),
+                "PROFILE",
+                "Lives in Berlin",
+                "2024-03-01T00:00:00Z",
+            ),
+            memory(
+                2,
+                None,
+                "KNOWLEDGE",
+                "Prefers metric units",
+                "2024-02-01T00:00:00Z",
+            ),
+            archived,
+        ];
+        assert_eq!(
+            render_memories_markdown(5, &memories, "2024-04-01T00:00:00Z"),
+            "# Memories for chat 5\n\n\
+             Exported 2024-04-01T00:00:00Z (3 memories)\n\
+             \n## KNOWLEDGE (1)\n\n\
+             - Prefers metric units [global]\n  \
+             - confidence 0.90, source explicit, created 2024-01-01T00:00:00Z, updated 2024-02-01T00:00:00Z\n\
+             \n## PROFILE (2)\n\n\
+             - Lives in Berlin\n  \
+             - confidence 0.90, source explicit, created 2024-01-01T00:00:00Z, updated 2024-03-01T00:00:00Z\n\
+             - Old city: Paris [archived]\n  \
+             - confidence 0.46, source explicit, created 2024-01-01T00:00:00Z, updated 2024-01-02T00:00:00Z\n"
+        );
+    }
+
+    #[test]
+    fn test_format_mcp_servers_and_reload_report() {
+        assert_eq!(format_mcp_servers(&[]), "No MCP servers connected.");
+        let servers = vec![
+            McpServerHealth {
+                name: "github".into(),
+                reachable: true,
+                last_success_at: None,
+                last_error: None,
+                tool_count: 12,
+                protocol_version: "2025-11-05".into(),
+            },
+            McpServerHealth {
+                name: "files".into(),
+                reachable: false,
+                last_success_at: None,
+                last_error: Some("connection refused".into()),
+                tool_count: 0,
+                protocol_version: "2025-06-18".into(),
+            },
+        ];
+        assert_eq!(
+            format_mcp_servers(&servers),
+            "MCP servers (2):\n\
+             - github: 12 tools, protocol 2025-11-05, reachable\n\
+             - files: 0 tools, protocol 2025-06-18, unreachable: connection refused"
+        );
+
+        let report = McpReloadReport {
+            added: vec!["search".into()],
+            removed: vec!["old".into(), "legacy".into()],
+            reconnected: Vec::new(),
+            failed: vec![("github".into(), "timed out".into())],
+        };
+        assert_eq!(
+            format_mcp_reload(&report),
+            "MCP config reloaded.\nAdded: search\nRemoved: old, legacy\nFailed: github (timed out)"
+        );
+    }
+
+    #[test]
+    fn test_loglevel_command_is_admin_only_and_validates() {
+        let mut config = Config::test_defaults();
+        config.control_chat_ids = vec![1];
+        let (_layer, handle) =
+            crate::logging::LogFilterHandle::new(tracing_subscriber::EnvFilter::new("info"));
+
+        let denied = build_loglevel_response(&config, Some(&handle), 2, "/loglevel debug");
+        assert!(denied.contains("control chat"));
+        assert_eq!(handle.current(), "info");
+
+        assert!(build_loglevel_response(&config, None, 1, "/loglevel debug")
+            .contains("not initialized"));
+
+        let shown = build_loglevel_response(&config, Some(&handle), 1, "/loglevel");
+        assert!(shown.starts_with("Log level: info"));
+
+        let set = build_loglevel_response(&config, Some(&handle), 1, "/loglevel debug");
+        assert_eq!(set, "Log level set to debug.");
+        assert_eq!(handle.current(), "debug");
+
+        let bad = build_loglevel_response(&config, Some(&handle), 1, "/loglevel loud");
+        assert!(bad.starts_with("Log level unchanged"));
+        assert_eq!(handle.current(), "debug");
+    }
 }
 
 #[cfg(test)]
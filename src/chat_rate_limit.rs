@@ -0,0 +1,261 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::config::Config;
+
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// Returned instead of running the agent for a chat that was already told to
+/// slow down, so channels drop the message without replying again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChatRateLimitedError {
+    pub retry_after_secs: u64,
+}
+
+impl std::fmt::Display for ChatRateLimitedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "chat is over its rate limit; retry in {}s",
+            self.retry_after_secs
+        )
+    }
+}
+
+impl std::error::Error for ChatRateLimitedError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitCheck {
+    Allowed,
+    /// `notify` is true only for the first rejected run after the chat was
+    /// last allowed one.
+    Limited {
+        retry_after_secs: u64,
+        notify: bool,
+    },
+}
+
+#[derive(Default)]
+struct ChatBucket {
+    runs: VecDeque<Instant>,
+    warned: bool,
+}
+
+/// Caps how many agent runs one chat may start per minute, so a single busy
+/// chat cannot monopolize the bot. Independent of any global concurrency cap.
+pub struct ChatRateLimiter {
+    default_per_minute: u32,
+    per_chat_type: HashMap<String, u32>,
+    burst: u32,
+    runs: Mutex<HashMap<i64, ChatBucket>>,
+}
+
+impl ChatRateLimiter {
+    pub fn new(default_per_minute: u32, per_chat_type: HashMap<String, u32>) -> Self {
+        Self {
+            default_per_minute,
+            per_chat_type,
+            burst: 0,
+            runs: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Also cap back-to-back runs: at most `burst` runs within the share of
+    /// the minute that `burst` runs are worth at the per-minute rate. 0, or a
+    /// value at or above the per-minute limit, adds no extra cap.
+    pub fn with_burst(mut self, burst: u32) -> Self {
+        self.burst = burst;
+        self
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(
+            config.chat_rate_limit_per_minute,
+            config.chat_rate_limit_per_chat_type.clone(),
+        )
+        .with_burst(config.chat_rate_limit_burst)
+    }
+
+    /// Runs per minute allowed for `chat_type`; 0 means unlimited.
+    pub fn limit_for(&self, chat_type: &str) -> u32 {
+        self.per_chat_type
+            .get(chat_type)
+            .copied()
+            .unwrap_or(self.default_per_minute)
+    }
+
+    /// Record a run for `chat_id` if the chat is under its limit. Otherwise
+    /// return how many seconds until the oldest run leaves the window.
+    pub fn try_acquire(&self, chat_id: i64, chat_type: &str) -> Result<(), u64> {
+        self.try_acquire_at(chat_id, chat_type, Instant::now())
+    }
+
+    /// Like `try_acquire`, but also reports whether the chat still needs to
+    /// be told to slow down.
+    pub fn check(&self, chat_id: i64, chat_type: &str) -> RateLimitCheck {
+        self.check_at(chat_id, chat_type, Instant::now())
+    }
+
+    fn check_at(&self, chat_id: i64, chat_type: &str, now: Instant) -> RateLimitCheck {
+        match self.try_acquire_at(chat_id, chat_type, now) {
+            Ok(()) => RateLimitCheck::Allowed,
+            Err(retry_after_secs) => {
+                let mut runs = self.runs.lock().unwrap_or_else(|e| e.into_inner());
+                let bucket = runs.entry(chat_id).or_default();
+                let notify = !bucket.warned;
+                bucket.warned = true;
+                RateLimitCheck::Limited {
+                    retry_after_secs,
+                    notify,
+                }
+            }
+        }
+    }
+
+    fn try_acquire_at(&self, chat_id: i64, chat_type: &str, now: Instant) -> Result<(), u64> {
+        let limit = self.limit_for(chat_type);
+        if limit == 0 {
+            return Ok(());
+        }
+        let mut runs = self.runs.lock().unwrap_or_else(|e| e.into_inner());
+        let bucket = runs.entry(chat_id).or_default();
+        while bucket
+            .runs
+            .front()
+            .is_some_and(|t| now.duration_since(*t) >= WINDOW)
+        {
+            bucket.runs.pop_front();
+        }
+        if bucket.runs.len() >= limit as usize {
+            let oldest = bucket.runs.front().copied().unwrap_or(now);
+            let wait = WINDOW.saturating_sub(now.duration_since(oldest));
+            return Err(wait.as_secs().max(1));
+        }
+        if self.burst > 0 && self.burst < limit {
+            let burst_window = WINDOW * self.burst / limit;
+            let mut recent = bucket
+                .runs
+                .iter()
+                .filter(|t| now.duration_since(**t) < burst_window);
+            if let Some(oldest) = recent.next() {
+                if recent.count() + 1 >= self.burst as usize {
+                    let wait = burst_window.saturating_sub(now.duration_since(*oldest));
+                    return Err(wait.as_secs().max(1));
+                }
+            }
+        }
+        bucket.runs.push_back(now);
+        bucket.warned = false;
+        Ok(())
+    }
+}
+
+pub fn slow_down_message(retry_after_secs: u64) -> String {
+    format!(
+        "You're sending messages faster than I can keep up with. Please slow down and try again in about {retry_after_secs}s."
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_is_per_chat_and_refills_after_window() {
+        let limiter = ChatRateLimiter::new(2, HashMap::new());
+        let start = Instant::now();
+        assert!(limiter.try_acquire_at(1, "private", start).is_ok());
+        assert!(limiter
+            .try_acquire_at(1, "private", start + Duration::from_secs(10))
+            .is_ok());
+        assert_eq!(
+            limiter.try_acquire_at(1, "private", start + Duration::from_secs(20)),
+            Err(40)
+        );
+        // Another chat has its own bucket.
+        assert!(limiter.try_acquire_at(2, "private", start).is_ok());
+        // The first run leaves the window after a minute.
+        assert!(limiter
+            .try_acquire_at(1, "private", start + Duration::from_secs(60))
+            .is_ok());
+        assert!(limiter
+            .try_acquire_at(1, "private", start + Duration::from_secs(61))
+            .is_err());
+    }
+
+    #[test]
+    fn test_chat_type_overrides_default_limit() {
+        let limiter = ChatRateLimiter::new(
+            1,
+            HashMap::from([("group".to_string(), 3), ("web".to_string(), 0)]),
+        );
+        let now = Instant::now();
+        assert_eq!(limiter.limit_for("private"), 1);
+        for _ in 0..3 {
+            assert!(limiter.try_acquire_at(5, "group", now).is_ok());
+        }
+        assert!(limiter.try_acquire_at(5, "group", now).is_err());
+        for _ in 0..100 {
+            assert!(limiter.try_acquire_at(6, "web", now).is_ok());
+        }
+        assert!(ChatRateLimiter::new(0, HashMap::new())
+            .try_acquire_at(7, "private", now)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_burst_caps_back_to_back_runs() {
+        // 6 per minute with a burst of 2: at most 2 runs per 20 seconds.
+        let limiter = ChatRateLimiter::new(6, HashMap::new()).with_burst(2);
+        let start = Instant::now();
+        assert!(limiter.try_acquire_at(1, "private", start).is_ok());
+        assert!(limiter
+            .try_acquire_at(1, "private", start + Duration::from_secs(1))
+            .is_ok());
+        assert_eq!(
+            limiter.try_acquire_at(1, "private", start + Duration::from_secs(5)),
+            Err(15)
+        );
+        assert!(limiter
+            .try_acquire_at(1, "private", start + Duration::from_secs(20))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_check_notifies_once_per_throttled_period() {
+        let limiter = ChatRateLimiter::new(1, HashMap::new());
+        let start = Instant::now();
+        assert_eq!(
+            limiter.check_at(1, "private", start),
+            RateLimitCheck::Allowed
+        );
+        assert_eq!(
+            limiter.check_at(1, "private", start + Duration::from_secs(1)),
+            RateLimitCheck::Limited {
+                retry_after_secs: 59,
+                notify: true
+            }
+        );
+        assert!(matches!(
+            limiter.check_at(1, "private", start + Duration::from_secs(2)),
+            RateLimitCheck::Limited { notify: false, .. }
+        ));
+        // Once a run is allowed again the next throttle is announced again.
+        assert_eq!(
+            limiter.check_at(1, "private", start + Duration::from_secs(60)),
+            RateLimitCheck::Allowed
+        );
+        assert!(matches!(
+            limiter.check_at(1, "private", start + Duration::from_secs(61)),
+            RateLimitCheck::Limited { notify: true, .. }
+        ));
+    }
+
+    #[test]
+    fn test_slow_down_message_mentions_wait() {
+        let text = slow_down_message(12);
+        assert!(text.contains("Please slow down"));
+        assert!(text.contains("12s"));
+    }
+}
@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tokio::sync::OwnedMutexGuard;
+
+use crate::keyed_lock::KeyedLocks;
+
+/// Chat locks idle for longer than this are dropped.
+const IDLE_LOCK_TTL: Duration = Duration::from_secs(300);
+
+/// Lets only one agent run per chat proceed at a time. Messages that arrive
+/// while a run is in flight queue behind it, and of the queued messages only
+/// the newest starts a run: the session merge picks up every user message
+/// stored after the last one an earlier run consumed, so the others are
+/// answered by that run.
+#[derive(Default)]
+pub struct ChatRunQueue {
+    locks: KeyedLocks<i64>,
+    next_ticket: AtomicU64,
+    newest: Mutex<HashMap<i64, u64>>,
+}
+
+/// Held for the duration of one agent run.
+pub struct ChatRunTurn<'a> {
+    queue: &'a ChatRunQueue,
+    chat_id: i64,
+    ticket: u64,
+    _guard: OwnedMutexGuard<()>,
+}
+
+impl ChatRunQueue {
+    /// Wait for this chat's turn. Returns `None` when a newer message for the
+    /// same chat arrived while waiting; its run will cover this one too.
+    pub async fn acquire(&self, chat_id: i64) -> Option<ChatRunTurn<'_>> {
+        let ticket = self.next_ticket.fetch_add(1, Ordering::Relaxed);
+        self.newest
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(chat_id, ticket);
+        let lock = self.locks.lock_for(&chat_id, IDLE_LOCK_TTL).await;
+        let guard = lock.lock_owned().await;
+        let superseded = self
+            .newest
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&chat_id)
+            .is_some_and(|newest| *newest != ticket);
+        if superseded {
+            return None;
+        }
+        Some(ChatRunTurn {
+            queue: self,
+            chat_id,
+            ticket,
+            _guard: guard,
+        })
+    }
+}
+
+impl Drop for ChatRunTurn<'_> {
+    fn drop(&mut self) {
+        let mut newest = self.queue.newest.lock().unwrap_or_else(|e| e.into_inner());
+        if newest.get(&self.chat_id) == Some(&self.ticket) {
+            newest.remove(&self.chat_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_queued_messages_coalesce_into_newest() {
+        let queue = Arc::new(ChatRunQueue::default());
+        let running = queue.acquire(7).await.expect("first message runs");
+
+        let second = tokio::spawn({
+            let queue = queue.clone();
+            async move { queue.acquire(7).await.is_some() }
+        });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let third = tokio::spawn({
+            let queue = queue.clone();
+            async move { queue.acquire(7).await.is_some() }
+        });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        // Other chats are not blocked.
+        assert!(queue.acquire(8).await.is_some());
+
+        drop(running);
+        assert!(!second.await.unwrap());
+        assert!(third.await.unwrap());
+        assert!(queue.newest.lock().unwrap().is_empty());
+    }
+}
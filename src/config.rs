@@ -13,7 +13,7 @@ use microclaw_core::error::MicroClawError;
 pub use microclaw_tools::sandbox::{SandboxBackend, SandboxConfig, SandboxMode, SecurityProfile};
 pub use microclaw_tools::types::WorkingDirIsolation;
 use microclaw_tools::web_content_validation::WebContentValidationConfig;
-use microclaw_tools::web_fetch::WebFetchUrlValidationConfig;
+use microclaw_tools::web_fetch::{WebFetchRetryConfig, WebFetchUrlValidationConfig};
 
 fn default_telegram_bot_token() -> String {
     String::new()
@@ -27,6 +27,63 @@ fn default_llm_provider() -> String {
 fn default_api_key() -> String {
     String::new()
 }
+fn default_skill_suggestion_min_score() -> usize {
+    3
+}
+fn default_skill_suggestion_max_hints() -> usize {
+    2
+}
+fn default_tool_transient_retry_tools() -> Vec<String> {
+    ["web_fetch", "web_search", "read_file", "glob", "grep"]
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+fn default_tool_transient_retry_backoff_ms() -> u64 {
+    1000
+}
+fn default_tool_parallel_max() -> usize {
+    4
+}
+fn default_tool_parallel_tools() -> Vec<String> {
+    default_tool_transient_retry_tools()
+}
+fn default_core_tools() -> Vec<String> {
+    [
+        "bash",
+        "read_file",
+        "write_file",
+        "edit_file",
+        "glob",
+        "grep",
+        "web_search",
+        "web_fetch",
+        "send_message",
+        "read_memory",
+        "write_memory",
+        "activate_skill",
+        "todo_read",
+        "todo_write",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+fn default_run_retry_budget() -> u32 {
+    10
+}
+fn default_event_webhook_events() -> Vec<String> {
+    crate::event_webhook::EVENT_TYPES
+        .iter()
+        .map(|e| e.to_string())
+        .collect()
+}
+fn default_event_webhook_max_retries() -> u32 {
+    3
+}
+fn default_tool_use_bias() -> String {
+    "balanced".into()
+}
 fn default_model() -> String {
     String::new()
 }
@@ -36,15 +93,60 @@ fn default_max_tokens() -> u32 {
 fn default_max_tool_iterations() -> usize {
     100
 }
+fn default_sub_agent_max_depth() -> usize {
+    1
+}
+fn default_sub_agent_max_iterations() -> usize {
+    10
+}
 fn default_compaction_timeout_secs() -> u64 {
     180
 }
+fn default_image_generation_model() -> String {
+    "gpt-image-1".into()
+}
+fn default_llm_max_retries() -> u32 {
+    3
+}
+fn default_llm_failure_threshold() -> u32 {
+    3
+}
+fn default_llm_health_probe_interval_secs() -> u64 {
+    60
+}
 fn default_max_history_messages() -> usize {
     50
 }
+fn default_group_backlog_keep_recent() -> usize {
+    10
+}
+fn default_max_inbound_message_chars() -> usize {
+    20_000
+}
 fn default_max_document_size_mb() -> u64 {
     100
 }
+fn default_max_inline_document_bytes() -> usize {
+    64 * 1024
+}
+fn default_save_attachment_max_size_mb() -> u64 {
+    20
+}
+fn default_save_attachment_allowed_types() -> Vec<String> {
+    [
+        "image/",
+        "audio/",
+        "text/",
+        "application/pdf",
+        "application/json",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+fn default_memory_default_category() -> String {
+    "KNOWLEDGE".into()
+}
 fn default_memory_token_budget() -> usize {
     1500
 }
@@ -73,6 +175,21 @@ fn default_working_dir() -> String {
 fn default_working_dir_isolation() -> WorkingDirIsolation {
     WorkingDirIsolation::Chat
 }
+fn default_bash_max_output_bytes() -> usize {
+    64 * 1024
+}
+
+/// Model used when `model` is left empty for `provider`.
+fn provider_default_model(provider: &str) -> &'static str {
+    match provider {
+        "anthropic" => "claude-sonnet-4-5-20250929",
+        "ollama" => "llama3.2",
+        "gemini" => "gemini-2.5-flash",
+        "openai-codex" => "gpt-5.3-codex",
+        _ => "gpt-5.2",
+    }
+}
+
 fn default_high_risk_tool_user_confirmation_required() -> bool {
     true
 }
@@ -85,6 +202,18 @@ fn default_sandbox_container_prefix() -> String {
 fn default_timezone() -> String {
     "UTC".into()
 }
+fn default_schedule_min_interval_secs() -> u64 {
+    60
+}
+fn default_schedule_max_tasks_per_chat() -> usize {
+    50
+}
+fn default_schedule_max_once_lead_days() -> u64 {
+    365
+}
+fn default_message_claim_lease_secs() -> u64 {
+    300
+}
 fn default_max_session_messages() -> usize {
     40
 }
@@ -94,6 +223,11 @@ fn default_compact_keep_recent() -> usize {
 fn default_tool_timeout_secs() -> u64 {
     30
 }
+fn default_tool_hard_timeout_secs() -> u64 {
+    600
+}
+/// How far the registry's hard limit stays above a tool's own timeout.
+const TOOL_HARD_TIMEOUT_MARGIN_SECS: u64 = 30;
 fn default_mcp_request_timeout_secs() -> u64 {
     120
 }
@@ -137,6 +271,24 @@ fn default_reflector_enabled() -> bool {
 fn default_reflector_interval_mins() -> u64 {
     15
 }
+fn default_db_maintenance_interval_hours() -> u64 {
+    0
+}
+fn default_tool_call_log_retention_days() -> u64 {
+    30
+}
+fn default_memory_prune_interval_hours() -> u64 {
+    0
+}
+fn default_memory_dedup_similarity() -> f64 {
+    0.92
+}
+fn default_memory_prune_confidence_floor() -> f64 {
+    0.35
+}
+fn default_memory_prune_stale_days() -> u64 {
+    30
+}
 fn default_soul_path() -> Option<String> {
     None
 }
@@ -146,9 +298,18 @@ fn default_souls_dir() -> Option<String> {
 fn default_clawhub_registry() -> String {
     "https://clawhub.ai".into()
 }
+fn default_image_ocr_provider() -> String {
+    "none".into()
+}
+fn default_image_ocr_max_chars() -> usize {
+    4000
+}
 fn default_voice_provider() -> String {
     "openai".into()
 }
+fn default_voice_segment_secs() -> u64 {
+    600
+}
 fn default_true() -> bool {
     true
 }
@@ -186,6 +347,69 @@ pub struct ModelPrice {
     pub output_per_million_usd: f64,
 }
 
+/// An allowed structured memory category, listed in the system prompt and the
+/// reflector instructions.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MemoryCategory {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+impl MemoryCategory {
+    pub fn defaults() -> Vec<MemoryCategory> {
+        [
+            ("PROFILE", "user attributes/preferences"),
+            ("KNOWLEDGE", "facts/expertise"),
+            ("EVENT", "significant things that happened"),
+        ]
+        .into_iter()
+        .map(|(name, description)| MemoryCategory {
+            name: name.into(),
+            description: description.into(),
+        })
+        .collect()
+    }
+}
+
+pub fn normalize_memory_category(
+    categories: &[MemoryCategory],
+    default_category: &str,
+    raw: Option<&str>,
+) -> String {
+    let candidate = raw.unwrap_or("").trim().to_ascii_uppercase();
+    if categories.iter().any(|c| c.name == candidate) {
+        candidate
+    } else {
+        default_category.to_string()
+    }
+}
+
+/// Regex rewrite applied to inbound sender names, configured per channel as
+/// `channels.<name>.sender_name_rules`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SenderNameRule {
+    pub pattern: String,
+    #[serde(default)]
+    pub replace: String,
+}
+
+/// An LLM tried, in order, when the primary provider fails with a retryable
+/// error after its own retries.
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
+pub struct LlmFallback {
+    pub provider: String,
+    /// Empty uses the provider's default model.
+    #[serde(default)]
+    pub model: String,
+    /// Defaults to the primary `api_key` / `llm_base_url` when `provider`
+    /// matches the primary provider, otherwise to none.
+    #[serde(default)]
+    pub api_key: Option<String>,
+    #[serde(default)]
+    pub llm_base_url: Option<String>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, Default)]
 pub struct LlmProviderProfile {
     #[serde(default)]
@@ -200,6 +424,23 @@ pub struct LlmProviderProfile {
     pub models: Vec<String>,
 }
 
+/// An LLM API key used for the runs of one chat or channel instead of the
+/// provider's own key. It only applies while the chat runs on `provider`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TenantApiKey {
+    pub provider: String,
+    pub api_key: String,
+}
+
+impl std::fmt::Debug for TenantApiKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TenantApiKey")
+            .field("provider", &self.provider)
+            .field("api_key", &"***")
+            .finish()
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct ResolvedLlmProviderProfile {
     pub alias: String,
@@ -222,25 +463,196 @@ pub struct Config {
     #[serde(default)]
     pub llm_providers: HashMap<String, LlmProviderProfile>,
     #[serde(default)]
+    pub llm_fallbacks: Vec<LlmFallback>,
+    /// Per-tenant API keys for cost attribution, keyed by
+    /// `<channel>:<external chat id>`, `<channel>` (e.g. `telegram.work`) or
+    /// base channel (`telegram`). The most specific match for the provider in
+    /// use wins; chats with no match use the provider's key.
+    #[serde(default)]
+    pub tenant_api_keys: HashMap<String, TenantApiKey>,
+    #[serde(default)]
     pub llm_base_url: Option<String>,
     #[serde(default = "default_max_tokens")]
     pub max_tokens: u32,
     #[serde(default = "default_max_tool_iterations")]
     pub max_tool_iterations: usize,
+    /// How deep `sub_agent` calls may nest. 1 lets the main agent delegate
+    /// but not its sub-agents; 0 rejects every call.
+    #[serde(default = "default_sub_agent_max_depth")]
+    pub sub_agent_max_depth: usize,
+    /// Tool-use iterations one sub-agent run may take.
+    #[serde(default = "default_sub_agent_max_iterations")]
+    pub sub_agent_max_iterations: usize,
+    /// Phrases that end the agent run as soon as the model outputs one,
+    /// even if it also requested more tool calls. Matched case-insensitively.
+    #[serde(default)]
+    pub agent_stop_phrases: Vec<String>,
+    /// How readily the agent reaches for tools: `conservative`, `balanced`,
+    /// or `aggressive`. Adds matching guidance to the system prompt.
+    #[serde(default = "default_tool_use_bias")]
+    pub tool_use_bias: String,
+    /// Tool results longer than this many characters are replaced by a short
+    /// summary once the model has seen them in full (0 disables).
+    #[serde(default)]
+    pub tool_output_summary_threshold_chars: usize,
+    /// Model used to summarize large tool results. Defaults to the chat's model.
+    #[serde(default)]
+    pub tool_output_summary_model: Option<String>,
+    /// Re-run a tool once, after a short backoff, when it fails with a
+    /// `network` or `timeout` error and is listed in `tool_transient_retry_tools`.
+    #[serde(default)]
+    pub tool_transient_retry_enabled: bool,
+    /// Tools that are safe to run twice (no side effects).
+    #[serde(default = "default_tool_transient_retry_tools")]
+    pub tool_transient_retry_tools: Vec<String>,
+    #[serde(default = "default_tool_transient_retry_backoff_ms")]
+    pub tool_transient_retry_backoff_ms: u64,
+    /// Most calls to `tool_parallel_tools` from one model response that run at
+    /// once. 1 runs every call in order. Chats can lower it with `/parallel`.
+    #[serde(default = "default_tool_parallel_max")]
+    pub tool_parallel_max: usize,
+    /// Side-effect-free tools whose calls may run concurrently.
+    #[serde(default = "default_tool_parallel_tools")]
+    pub tool_parallel_tools: Vec<String>,
+    /// Most tool definitions sent with one request. When unset, a limit known
+    /// for the provider is used (OpenAI: 128). Above the limit, the tools that
+    /// best match the current message are kept, plus `core_tools`.
+    #[serde(default)]
+    pub max_tools_per_request: Option<usize>,
+    /// Tools that are never trimmed by `max_tools_per_request`.
+    #[serde(default = "default_core_tools")]
+    pub core_tools: Vec<String>,
+    /// Retries one agent run may spend in total across LLM rate-limit retries,
+    /// stream fallbacks and transient tool retries. 0 means no shared cap.
+    #[serde(default = "default_run_retry_budget")]
+    pub run_retry_budget: u32,
+    /// Agent runs one chat may start per minute before it gets a "slow down"
+    /// reply. 0 means unlimited.
+    #[serde(default)]
+    pub chat_rate_limit_per_minute: u32,
+    /// Per chat-type (`private`, `group`, `web`, ...) overrides of
+    /// `chat_rate_limit_per_minute`.
+    #[serde(default)]
+    pub chat_rate_limit_per_chat_type: HashMap<String, u32>,
+    /// Most runs one chat may start back to back; spreads the per-minute
+    /// allowance over the minute. 0 means no extra cap.
+    #[serde(default)]
+    pub chat_rate_limit_burst: u32,
+    /// Optional endpoint that receives a JSON POST for each selected event.
+    #[serde(default)]
+    pub event_webhook_url: Option<String>,
+    /// When set, requests carry `X-MicroClaw-Signature: sha256=<hmac>` of the body.
+    #[serde(default)]
+    pub event_webhook_secret: Option<String>,
+    /// Which of `message_received`, `response_sent`, `tool_executed` and
+    /// `task_run` to send.
+    #[serde(default = "default_event_webhook_events")]
+    pub event_webhook_events: Vec<String>,
+    #[serde(default = "default_event_webhook_max_retries")]
+    pub event_webhook_max_retries: u32,
     #[serde(default = "default_compaction_timeout_secs")]
     pub compaction_timeout_secs: u64,
+    /// Consecutive LLM failures before the provider is marked unavailable (0 disables).
+    #[serde(default = "default_llm_failure_threshold")]
+    pub llm_failure_threshold: u32,
+    /// How often to probe a degraded provider for recovery.
+    #[serde(default = "default_llm_health_probe_interval_secs")]
+    pub llm_health_probe_interval_secs: u64,
+    /// Retry a streamed turn as a buffered request when the stream fails
+    /// before producing any output.
+    #[serde(default = "default_true")]
+    pub llm_stream_fallback: bool,
+    /// Retries with exponential backoff for rate limits, overload, 5xx
+    /// gateway errors and network timeouts. Other errors fail immediately.
+    #[serde(default = "default_llm_max_retries")]
+    pub llm_max_retries: u32,
+    /// Mark the system prompt, tool definitions and conversation prefix as
+    /// cacheable on Anthropic requests. Ignored by other providers.
+    #[serde(default = "default_true")]
+    pub prompt_caching: bool,
+    /// Send tool definitions to OpenAI-compatible providers with
+    /// `strict: true` and a strict-mode schema. Optional arguments become
+    /// nullable; nulls are dropped before the tool runs.
+    #[serde(default)]
+    pub openai_strict_tools: bool,
     #[serde(default = "default_max_history_messages")]
     pub max_history_messages: usize,
+    /// Estimated prompt size (system prompt, tools and history, at roughly
+    /// four characters per token) above which the oldest history is left out
+    /// of an LLM request. The latest user message is always sent. 0 disables.
+    #[serde(default)]
+    pub context_token_budget: usize,
+    /// When a group's catch-up (messages since the bot last replied) holds
+    /// more than this many messages, all but the newest
+    /// `group_backlog_keep_recent` are replaced by one summary (0 disables).
+    #[serde(default)]
+    pub group_backlog_summary_threshold: usize,
+    /// Newest catch-up messages kept verbatim when the backlog is summarized.
+    #[serde(default = "default_group_backlog_keep_recent")]
+    pub group_backlog_keep_recent: usize,
+    /// Inbound messages longer than this (in characters) are saved to a file
+    /// and shown to the model as a path plus a short preview. 0 disables.
+    #[serde(default = "default_max_inbound_message_chars")]
+    pub max_inbound_message_chars: usize,
     #[serde(default = "default_max_document_size_mb")]
     pub max_document_size_mb: u64,
+    /// Text-like documents (plain text, markdown, JSON, CSV) are inlined into
+    /// the user message up to this many bytes. 0 only records a placeholder.
+    #[serde(default = "default_max_inline_document_bytes")]
+    pub max_inline_document_bytes: usize,
+    /// When a user edits a message the bot has not answered yet,
+    /// run the agent again on the edited text (at most once per message).
+    #[serde(default)]
+    pub rerun_on_message_edit: bool,
+    /// Stream Telegram replies by live-editing one message while the agent
+    /// runs. Same as `channels.telegram.streaming.enabled`; `/streaming`
+    /// still overrides it per chat.
+    #[serde(default)]
+    pub telegram_streaming: bool,
+    /// Largest attachment the `save_attachment` tool will write to disk.
+    #[serde(default = "default_save_attachment_max_size_mb")]
+    pub save_attachment_max_size_mb: u64,
+    /// Media types `save_attachment` accepts. Entries ending in `/` match a
+    /// whole family (`image/`); `*` allows everything.
+    #[serde(default = "default_save_attachment_allowed_types")]
+    pub save_attachment_allowed_types: Vec<String>,
     #[serde(default = "default_memory_token_budget")]
     pub memory_token_budget: usize,
+    /// Allowed structured memory categories. Names are stored upper-case.
+    #[serde(default = "MemoryCategory::defaults")]
+    pub memory_categories: Vec<MemoryCategory>,
+    /// Category used when a memory arrives with a missing or unknown category.
+    #[serde(default = "default_memory_default_category")]
+    pub memory_default_category: String,
     #[serde(default = "default_max_session_messages")]
     pub max_session_messages: usize,
     #[serde(default = "default_compact_keep_recent")]
     pub compact_keep_recent: usize,
+    /// Also compact when the session's estimated size (about four characters
+    /// per token) exceeds this many tokens, keeping only as many recent
+    /// messages as fit in half of it. 0 leaves the message-count trigger only.
+    #[serde(default)]
+    pub compact_token_threshold: usize,
+    /// When a stored session fails to deserialize, keep the messages that still parse
+    /// instead of rebuilding from DB history. The raw blob is archived either way.
+    #[serde(default = "default_true")]
+    pub session_recover_partial: bool,
+    /// Write tool calls and results into conversation archives as full
+    /// fenced JSON blocks instead of the compact `[tool_use: ...]` lines.
+    #[serde(default)]
+    pub archive_structured_tool_calls: bool,
+    /// When the agent writes a todo list, send a "Here's my plan:" message and
+    /// edit it as steps complete (on channels that support message edits).
+    #[serde(default)]
+    pub agent_plan_messages_enabled: bool,
     #[serde(default = "default_tool_timeout_secs")]
     pub default_tool_timeout_secs: u64,
+    /// Hard limit on any single tool call, enforced by the tool registry so a
+    /// hung tool cannot block the chat. It is raised for a call whose own
+    /// timeout would otherwise reach it, so tools can still time out cleanly
+    /// first. 0 disables the limit.
+    #[serde(default = "default_tool_hard_timeout_secs")]
+    pub tool_hard_timeout_secs: u64,
     #[serde(default)]
     pub tool_timeout_overrides: HashMap<String, u64>,
     #[serde(default = "default_mcp_request_timeout_secs")]
@@ -265,16 +677,48 @@ pub struct Config {
     pub data_dir: String,
     #[serde(default)]
     pub skills_dir: Option<String>,
+    /// Score each incoming message against skill descriptions and hint the
+    /// best matches in the system prompt.
+    #[serde(default)]
+    pub skill_suggestions_enabled: bool,
+    #[serde(default = "default_skill_suggestion_min_score")]
+    pub skill_suggestion_min_score: usize,
+    #[serde(default = "default_skill_suggestion_max_hints")]
+    pub skill_suggestion_max_hints: usize,
     #[serde(default = "default_working_dir")]
     pub working_dir: String,
     #[serde(default = "default_working_dir_isolation")]
     pub working_dir_isolation: WorkingDirIsolation,
     #[serde(default = "default_high_risk_tool_user_confirmation_required")]
     pub high_risk_tool_user_confirmation_required: bool,
+    /// Outside control chats, hold back destructive `bash`, `write_file`,
+    /// `edit_file` and `forget` calls until the user replies with a
+    /// confirmation token.
+    #[serde(default)]
+    pub confirm_destructive_tools: bool,
+    /// Point `bash`'s `HOME` at the chat working directory and refuse commands
+    /// that leave it (`..`, `~`, `$HOME`, absolute paths outside it).
+    #[serde(default)]
+    pub bash_restrict_to_working_dir: bool,
+    /// Command prefixes the `bash` tool refuses (e.g. `rm -rf`, `sudo`).
+    #[serde(default)]
+    pub bash_denied_commands: Vec<String>,
+    /// Most stdout plus stderr `bash` captures before killing the command. 0 disables.
+    #[serde(default = "default_bash_max_output_bytes")]
+    pub bash_max_output_bytes: usize,
     #[serde(default)]
     pub sandbox: SandboxConfig,
     #[serde(default = "default_timezone")]
     pub timezone: String,
+    /// Shortest allowed gap between runs of a cron task created by `schedule_task`. 0 disables.
+    #[serde(default = "default_schedule_min_interval_secs")]
+    pub schedule_min_interval_secs: u64,
+    /// Maximum active or paused scheduled tasks per chat. 0 disables.
+    #[serde(default = "default_schedule_max_tasks_per_chat")]
+    pub schedule_max_tasks_per_chat: usize,
+    /// How far ahead a one-time task may be scheduled. 0 disables.
+    #[serde(default = "default_schedule_max_once_lead_days")]
+    pub schedule_max_once_lead_days: u64,
     #[serde(default = "default_control_chat_ids")]
     pub control_chat_ids: Vec<i64>,
     #[serde(default)]
@@ -283,6 +727,8 @@ pub struct Config {
     pub discord_allowed_channels: Vec<u64>,
     #[serde(default)]
     pub discord_no_mention: bool,
+    #[serde(default)]
+    pub discord_use_threads: bool,
     #[serde(default = "default_allow_group_slash_without_mention")]
     pub allow_group_slash_without_mention: bool,
 
@@ -307,6 +753,15 @@ pub struct Config {
     pub web_fetch_validation: WebContentValidationConfig,
     #[serde(default)]
     pub web_fetch_url_validation: WebFetchUrlValidationConfig,
+    #[serde(default)]
+    pub web_fetch_retry: WebFetchRetryConfig,
+    /// Hosts the `http_request` tool may call (subdomains included). Empty
+    /// denies every request.
+    #[serde(default)]
+    pub http_tool_allowed_hosts: Vec<String>,
+    /// Let `http_request` reach private, loopback and link-local addresses.
+    #[serde(default)]
+    pub http_tool_allow_private_networks: bool,
 
     // --- Embedding ---
     #[serde(default)]
@@ -322,6 +777,16 @@ pub struct Config {
     #[serde(default)]
     pub openai_api_key: Option<String>,
 
+    // --- Image generation ---
+    /// Model for the `generate_image` tool, which is only enabled when
+    /// `openai_api_key` is set.
+    #[serde(default = "default_image_generation_model")]
+    pub image_generation_model: String,
+    /// OpenAI-compatible API base for image generation. Defaults to
+    /// `https://api.openai.com/v1`.
+    #[serde(default)]
+    pub image_generation_base_url: Option<String>,
+
     // --- Pricing ---
     #[serde(default = "default_model_prices")]
     pub model_prices: Vec<ModelPrice>,
@@ -332,6 +797,33 @@ pub struct Config {
     #[serde(default = "default_reflector_interval_mins")]
     pub reflector_interval_mins: u64,
 
+    // --- Database maintenance ---
+    /// Hours between WAL checkpoint + `VACUUM` + `PRAGMA optimize` passes over
+    /// the SQLite database. Off (0) by default: `VACUUM` holds the database
+    /// lock for the whole pass, which stalls replies on large databases.
+    #[serde(default = "default_db_maintenance_interval_hours")]
+    pub db_maintenance_interval_hours: u64,
+    /// Days of `tool_call_logs` rows (used by `/stats` and `/trace`) to keep.
+    /// 0 keeps them forever.
+    #[serde(default = "default_tool_call_log_retention_days")]
+    pub tool_call_log_retention_days: u64,
+
+    // --- Memory pruning ---
+    /// Hours between passes that merge near-duplicate memories and archive
+    /// decayed ones. 0 (the default) disables the job.
+    #[serde(default = "default_memory_prune_interval_hours")]
+    pub memory_prune_interval_hours: u64,
+    /// Cosine similarity at or above which two memories of a chat count as
+    /// duplicates when an embedding provider is configured.
+    #[serde(default = "default_memory_dedup_similarity")]
+    pub memory_dedup_similarity: f64,
+    /// Memories below this confidence that have not been seen for
+    /// `memory_prune_stale_days` are archived.
+    #[serde(default = "default_memory_prune_confidence_floor")]
+    pub memory_prune_confidence_floor: f64,
+    #[serde(default = "default_memory_prune_stale_days")]
+    pub memory_prune_stale_days: u64,
+
     // --- Soul ---
     /// Path to a SOUL.md file that defines the bot's personality, voice, and values.
     /// If not set, looks for SOUL.md in data_dir root, then current directory.
@@ -341,6 +833,22 @@ pub struct Config {
     #[serde(default = "default_souls_dir")]
     pub souls_dir: Option<String>,
 
+    // --- System prompt ---
+    /// Replaces "a helpful AI assistant across chat channels" in the default
+    /// identity line (ignored when a SOUL file is loaded).
+    #[serde(default)]
+    pub persona_name: Option<String>,
+    /// Text placed before the built-in system prompt.
+    #[serde(default)]
+    pub system_prompt_prepend: Option<String>,
+    /// Text placed after the built-in system prompt.
+    #[serde(default)]
+    pub system_prompt_append: Option<String>,
+    /// File appended after `system_prompt_append`, re-read on every turn.
+    /// Relative paths resolve against `data_dir`.
+    #[serde(default)]
+    pub system_prompt_file: Option<String>,
+
     // --- ClawHub ---
     #[serde(flatten)]
     pub clawhub: ClawHubConfig,
@@ -357,6 +865,27 @@ pub struct Config {
     /// Example: "whisper-mlx --file {file}" or "/usr/local/bin/whisper {file}"
     #[serde(default, rename = "voice_transcription_command")]
     pub voice_transcription_command: Option<String>,
+    /// Voice notes longer than this are split with ffmpeg and transcribed segment by
+    /// segment. 0 disables splitting.
+    #[serde(default = "default_voice_segment_secs")]
+    pub voice_segment_secs: u64,
+    /// Whisper language hint (ISO-639-1, e.g. "de") per Telegram chat ID.
+    /// Chats without an entry use auto-detection.
+    #[serde(default)]
+    pub voice_languages: HashMap<i64, String>,
+
+    // --- Image OCR ---
+    /// OCR for inbound images: "none" (default), "tesseract" (tesseract CLI on PATH),
+    /// or "local" (uses image_ocr_command). Extracted text is appended to the user message.
+    #[serde(default = "default_image_ocr_provider")]
+    pub image_ocr_provider: String,
+    /// Command template for local OCR. Use {file} as placeholder for the image path;
+    /// the command must print the extracted text to stdout.
+    #[serde(default)]
+    pub image_ocr_command: Option<String>,
+    /// Maximum characters of OCR text appended to the message.
+    #[serde(default = "default_image_ocr_max_chars")]
+    pub image_ocr_max_chars: usize,
 
     // --- Channel registry (new dynamic config) ---
     /// Per-channel configuration. Keys are channel names (e.g. "telegram", "discord", "slack", "irc", "web").
@@ -364,6 +893,17 @@ pub struct Config {
     /// If empty, synthesized from legacy flat fields below in post_deserialize().
     #[serde(default)]
     pub channels: HashMap<String, serde_yaml::Value>,
+    /// Maximum number of channels started at once. 0 disables the cap.
+    #[serde(default)]
+    pub max_active_channels: usize,
+    /// Claim each inbound message in the database before handling it so that
+    /// several instances sharing one database reply only once.
+    #[serde(default)]
+    pub message_claim_enabled: bool,
+    /// How long a claim blocks other instances before it may be taken over.
+    /// The holder renews it while the message is being handled.
+    #[serde(default = "default_message_claim_lease_secs")]
+    pub message_claim_lease_secs: u64,
 
     // --- Legacy channel fields (deprecated, use `channels:` instead) ---
     #[serde(default = "default_telegram_bot_token")]
@@ -374,6 +914,28 @@ pub struct Config {
     pub allowed_groups: Vec<i64>,
 }
 
+/// Compiled `sender_name_rules` patterns, keyed by pattern text so each one is
+/// compiled (and an invalid one reported) only once, across config reloads.
+static SENDER_NAME_REGEXES: std::sync::LazyLock<
+    std::sync::Mutex<HashMap<String, Option<regex::Regex>>>,
+> = std::sync::LazyLock::new(|| std::sync::Mutex::new(HashMap::new()));
+
+fn cached_sender_name_regex(pattern: &str) -> Option<regex::Regex> {
+    let mut cache = SENDER_NAME_REGEXES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    cache
+        .entry(pattern.to_string())
+        .or_insert_with(|| match regex::Regex::new(pattern) {
+            Ok(re) => Some(re),
+            Err(e) => {
+                warn!("Invalid sender_name_rules pattern '{pattern}': {e}");
+                None
+            }
+        })
+        .clone()
+}
+
 impl Config {
     fn channel_default_account_id(&self, channel: &str) -> Option<String> {
         let channel_cfg = self.channels.get(channel)?;
@@ -472,6 +1034,26 @@ impl Config {
             .map(ToOwned::to_owned)
     }
 
+    /// The `tenant_api_keys` entry for a chat running on `provider` as
+    /// `(route, api_key)`. Checks `<channel>:<external chat id>`, then
+    /// `<channel>`, then the base channel of a multi-account channel name;
+    /// entries for other providers are skipped.
+    pub fn tenant_api_key(
+        &self,
+        channel: &str,
+        external_chat_id: &str,
+        provider: &str,
+    ) -> Option<(&str, &str)> {
+        let chat_route = format!("{channel}:{external_chat_id}");
+        let base_channel = channel.split_once('.').map(|(base, _)| base);
+        let (route, entry) = [Some(chat_route.as_str()), Some(channel), base_channel]
+            .into_iter()
+            .flatten()
+            .filter_map(|route| self.tenant_api_keys.get_key_value(route))
+            .find(|(_, entry)| entry.provider.trim().eq_ignore_ascii_case(provider.trim()))?;
+        Some((route.as_str(), entry.api_key.as_str()))
+    }
+
     pub fn bot_username_for_channel(&self, channel: &str) -> String {
         let channel_override = self
             .channels
@@ -502,6 +1084,45 @@ impl Config {
         }
     }
 
+    /// Sender-name rules for a channel. Account-scoped channels (`telegram.sales`)
+    /// use their own rules when set, otherwise the base channel's.
+    pub fn sender_name_rules_for_channel(&self, channel: &str) -> Vec<SenderNameRule> {
+        let read_rules = |name: &str| {
+            self.channels
+                .get(name)
+                .and_then(|v| v.get("sender_name_rules"))
+                .and_then(|v| serde_yaml::from_value::<Vec<SenderNameRule>>(v.clone()).ok())
+        };
+        read_rules(channel)
+            .or_else(|| {
+                channel
+                    .split_once('.')
+                    .and_then(|(base_channel, _)| read_rules(base_channel))
+            })
+            .unwrap_or_default()
+    }
+
+    /// Apply the channel's sender-name rules in order. Falls back to the raw name
+    /// if the rules would leave it empty.
+    pub fn normalize_sender_name(&self, channel: &str, sender_name: &str) -> String {
+        let rules = self.sender_name_rules_for_channel(channel);
+        if rules.is_empty() {
+            return sender_name.to_string();
+        }
+        let mut name = sender_name.to_string();
+        for rule in &rules {
+            if let Some(re) = cached_sender_name_regex(&rule.pattern) {
+                name = re.replace_all(&name, rule.replace.as_str()).into_owned();
+            }
+        }
+        let name = name.trim();
+        if name.is_empty() {
+            sender_name.to_string()
+        } else {
+            name.to_string()
+        }
+    }
+
     pub fn bot_username_overrides(&self) -> HashMap<String, String> {
         let mut overrides: HashMap<String, String> = self
             .channels
@@ -557,31 +1178,89 @@ impl Config {
             api_key: "key".into(),
             model: "claude-sonnet-4-5-20250929".into(),
             llm_providers: HashMap::new(),
+            llm_fallbacks: Vec::new(),
+            tenant_api_keys: HashMap::new(),
             llm_base_url: None,
             max_tokens: 8192,
             max_tool_iterations: 100,
+            sub_agent_max_depth: 1,
+            sub_agent_max_iterations: 10,
+            agent_stop_phrases: vec![],
+            tool_use_bias: "balanced".into(),
+            tool_output_summary_threshold_chars: 0,
+            tool_output_summary_model: None,
+            tool_transient_retry_enabled: false,
+            tool_transient_retry_tools: default_tool_transient_retry_tools(),
+            tool_transient_retry_backoff_ms: 1000,
+            tool_parallel_max: default_tool_parallel_max(),
+            tool_parallel_tools: default_tool_parallel_tools(),
+            max_tools_per_request: None,
+            core_tools: default_core_tools(),
+            run_retry_budget: default_run_retry_budget(),
+            chat_rate_limit_per_minute: 0,
+            chat_rate_limit_per_chat_type: HashMap::new(),
+            chat_rate_limit_burst: 0,
+            event_webhook_url: None,
+            event_webhook_secret: None,
+            event_webhook_events: default_event_webhook_events(),
+            event_webhook_max_retries: default_event_webhook_max_retries(),
             compaction_timeout_secs: 180,
+            llm_failure_threshold: default_llm_failure_threshold(),
+            llm_health_probe_interval_secs: default_llm_health_probe_interval_secs(),
+            llm_stream_fallback: true,
+            llm_max_retries: 3,
+            prompt_caching: true,
+            openai_strict_tools: false,
             max_history_messages: 50,
+            context_token_budget: 0,
+            group_backlog_summary_threshold: 0,
+            group_backlog_keep_recent: default_group_backlog_keep_recent(),
+            max_inbound_message_chars: default_max_inbound_message_chars(),
             max_document_size_mb: 100,
+            max_inline_document_bytes: default_max_inline_document_bytes(),
+            rerun_on_message_edit: false,
+            telegram_streaming: false,
+            save_attachment_max_size_mb: default_save_attachment_max_size_mb(),
+            save_attachment_allowed_types: default_save_attachment_allowed_types(),
             memory_token_budget: 1500,
+            memory_categories: MemoryCategory::defaults(),
+            memory_default_category: "KNOWLEDGE".into(),
             data_dir: default_data_dir(),
             skills_dir: None,
+            skill_suggestions_enabled: false,
+            skill_suggestion_min_score: default_skill_suggestion_min_score(),
+            skill_suggestion_max_hints: default_skill_suggestion_max_hints(),
             working_dir: default_working_dir(),
             working_dir_isolation: WorkingDirIsolation::Chat,
             high_risk_tool_user_confirmation_required: true,
+            confirm_destructive_tools: false,
+            bash_restrict_to_working_dir: false,
+            bash_denied_commands: Vec::new(),
+            bash_max_output_bytes: default_bash_max_output_bytes(),
             sandbox: SandboxConfig::default(),
             openai_api_key: None,
+            image_generation_model: "gpt-image-1".into(),
+            image_generation_base_url: None,
             timezone: "UTC".into(),
+            schedule_min_interval_secs: default_schedule_min_interval_secs(),
+            schedule_max_tasks_per_chat: default_schedule_max_tasks_per_chat(),
+            schedule_max_once_lead_days: default_schedule_max_once_lead_days(),
             allowed_groups: vec![],
             control_chat_ids: vec![],
             max_session_messages: 40,
             compact_keep_recent: 20,
+            compact_token_threshold: 0,
+            session_recover_partial: true,
+            archive_structured_tool_calls: false,
+            agent_plan_messages_enabled: false,
             default_tool_timeout_secs: default_tool_timeout_secs(),
+            tool_hard_timeout_secs: default_tool_hard_timeout_secs(),
             tool_timeout_overrides: HashMap::new(),
             default_mcp_request_timeout_secs: default_mcp_request_timeout_secs(),
             discord_bot_token: None,
             discord_allowed_channels: vec![],
             discord_no_mention: false,
+            discord_use_threads: false,
             allow_group_slash_without_mention: false,
             show_thinking: false,
             openai_compat_body_overrides: HashMap::new(),
@@ -597,6 +1276,9 @@ impl Config {
             web_session_idle_ttl_seconds: 300,
             web_fetch_validation: WebContentValidationConfig::default(),
             web_fetch_url_validation: WebFetchUrlValidationConfig::default(),
+            web_fetch_retry: WebFetchRetryConfig::default(),
+            http_tool_allowed_hosts: vec![],
+            http_tool_allow_private_networks: false,
             model_prices: vec![],
             embedding_provider: None,
             embedding_api_key: None,
@@ -605,13 +1287,31 @@ impl Config {
             embedding_dim: None,
             reflector_enabled: true,
             reflector_interval_mins: 15,
+            db_maintenance_interval_hours: 0,
+            tool_call_log_retention_days: default_tool_call_log_retention_days(),
+            memory_prune_interval_hours: default_memory_prune_interval_hours(),
+            memory_dedup_similarity: 0.92,
+            memory_prune_confidence_floor: 0.35,
+            memory_prune_stale_days: 30,
             soul_path: None,
             souls_dir: None,
+            persona_name: None,
+            system_prompt_prepend: None,
+            system_prompt_append: None,
+            system_prompt_file: None,
             clawhub: ClawHubConfig::default(),
             plugins: PluginsConfig::default(),
             voice_provider: "openai".into(),
             voice_transcription_command: None,
+            voice_segment_secs: default_voice_segment_secs(),
+            voice_languages: HashMap::new(),
+            image_ocr_provider: default_image_ocr_provider(),
+            image_ocr_command: None,
+            image_ocr_max_chars: default_image_ocr_max_chars(),
             channels: HashMap::new(),
+            max_active_channels: 0,
+            message_claim_enabled: false,
+            message_claim_lease_secs: default_message_claim_lease_secs(),
         }
     }
 
@@ -775,17 +1475,34 @@ impl Config {
 
         // Apply provider-specific default model if empty
         if self.model.is_empty() {
-            self.model = match self.llm_provider.as_str() {
-                "anthropic" => "claude-sonnet-4-5-20250929".into(),
-                "ollama" => "llama3.2".into(),
-                "openai-codex" => "gpt-5.3-codex".into(),
-                _ => "gpt-5.2".into(),
-            };
+            self.model = provider_default_model(&self.llm_provider).into();
         }
         self.model = self.model.trim().to_string();
         if self.model.is_empty() {
             self.model = "gpt-5.2".into();
         }
+        for (i, fallback) in self.llm_fallbacks.iter_mut().enumerate() {
+            fallback.provider = fallback.provider.trim().to_lowercase();
+            if fallback.provider.is_empty() {
+                return Err(MicroClawError::Config(format!(
+                    "llm_fallbacks[{i}].provider must not be empty"
+                )));
+            }
+            fallback.model = fallback.model.trim().to_string();
+            if fallback.model.is_empty() {
+                fallback.model = provider_default_model(&fallback.provider).into();
+            }
+            fallback.api_key = fallback
+                .api_key
+                .as_ref()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty());
+            fallback.llm_base_url = fallback
+                .llm_base_url
+                .as_ref()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty());
+        }
         self.llm_providers = self
             .llm_providers
             .drain()
@@ -826,11 +1543,66 @@ impl Config {
             })
             .collect();
 
+        for (route, entry) in &self.tenant_api_keys {
+            for (field, value) in [("provider", &entry.provider), ("api_key", &entry.api_key)] {
+                if value.trim().is_empty() {
+                    return Err(MicroClawError::Config(format!(
+                        "tenant_api_keys.{route}.{field} must not be empty"
+                    )));
+                }
+            }
+        }
+        if self.max_tools_per_request == Some(0) {
+            return Err(MicroClawError::Config(
+                "max_tools_per_request must be at least 1".into(),
+            ));
+        }
+        if self.tool_parallel_max == 0 {
+            return Err(MicroClawError::Config(
+                "tool_parallel_max must be at least 1".into(),
+            ));
+        }
+        if self.group_backlog_summary_threshold > 0 && self.group_backlog_keep_recent == 0 {
+            return Err(MicroClawError::Config(
+                "group_backlog_keep_recent must be at least 1 when group_backlog_summary_threshold is set".into(),
+            ));
+        }
+
+        for event in &self.event_webhook_events {
+            if !crate::event_webhook::EVENT_TYPES.contains(&event.as_str()) {
+                return Err(MicroClawError::Config(format!(
+                    "Unknown event_webhook_events entry '{event}' (expected one of: {})",
+                    crate::event_webhook::EVENT_TYPES.join(", ")
+                )));
+            }
+        }
+
         // Validate timezone
         self.timezone
             .parse::<chrono_tz::Tz>()
             .map_err(|_| MicroClawError::Config(format!("Invalid timezone: {}", self.timezone)))?;
 
+        // Validate sender-name rewrite rules
+        for (channel, channel_cfg) in &self.channels {
+            let Some(rules) = channel_cfg.get("sender_name_rules") else {
+                continue;
+            };
+            let rules: Vec<SenderNameRule> =
+                serde_yaml::from_value(rules.clone()).map_err(|e| {
+                    MicroClawError::Config(format!(
+                        "Invalid channels.{channel}.sender_name_rules: {e}"
+                    ))
+                })?;
+            for rule in rules {
+                regex::Regex::new(&rule.pattern).map_err(|e| {
+                    MicroClawError::Config(format!(
+                        "Invalid channels.{channel}.sender_name_rules pattern '{}': {e}",
+                        rule.pattern
+                    ))
+                })?;
+            }
+        }
+
         // Filter empty llm_base_url
         if let Some(ref url) = self.llm_base_url {
             if url.trim().is_empty() {
@@ -933,6 +1705,7 @@ Use operator password + API keys for Web auth."
         }
         self.web_fetch_validation.normalize();
         self.web_fetch_url_validation.normalize();
+        self.web_fetch_retry.normalize();
         if self.max_document_size_mb == 0 {
             self.max_document_size_mb = default_max_document_size_mb();
         }
@@ -991,6 +1764,52 @@ Use operator password + API keys for Web auth."
         if self.memory_token_budget == 0 {
             self.memory_token_budget = default_memory_token_budget();
         }
+        let mut categories: Vec<MemoryCategory> = Vec::new();
+        for mut category in std::mem::take(&mut self.memory_categories) {
+            category.name = category.name.trim().to_ascii_uppercase();
+            category.description = category.description.trim().to_string();
+            if category.name.is_empty() || categories.iter().any(|c| c.name == category.name) {
+                continue;
+            }
+            categories.push(category);
+        }
+        self.memory_categories = if categories.is_empty() {
+            MemoryCategory::defaults()
+        } else {
+            categories
+        };
+        self.memory_default_category = self.memory_default_category.trim().to_ascii_uppercase();
+        if self.memory_default_category.is_empty() {
+            self.memory_default_category = self.memory_categories[0].name.clone();
+        }
+        if !self
+            .memory_categories
+            .iter()
+            .any(|c| c.name == self.memory_default_category)
+        {
+            return Err(MicroClawError::Config(format!(
+                "memory_default_category '{}' is not listed in memory_categories",
+                self.memory_default_category
+            )));
+        }
+        self.tool_output_summary_model = self
+            .tool_output_summary_model
+            .take()
+            .map(|m| m.trim().to_string())
+            .filter(|m| !m.is_empty());
+        self.tool_use_bias = self.tool_use_bias.trim().to_ascii_lowercase();
+        if self.tool_use_bias.is_empty() {
+            self.tool_use_bias = default_tool_use_bias();
+        }
+        if !matches!(
+            self.tool_use_bias.as_str(),
+            "conservative" | "balanced" | "aggressive"
+        ) {
+            return Err(MicroClawError::Config(format!(
+                "tool_use_bias must be one of conservative, balanced, aggressive (got '{}')",
+                self.tool_use_bias
+            )));
+        }
         for price in &mut self.model_prices {
             price.model = price.model.trim().to_string();
             if price.model.is_empty() {
@@ -1035,6 +1854,7 @@ Use operator password + API keys for Web auth."
                             "bot_token": token,
                             "allowed_channels": self.discord_allowed_channels,
                             "no_mention": self.discord_no_mention,
+                            "use_threads": self.discord_use_threads,
                         }))
                         .unwrap(),
                     );
@@ -1248,6 +2068,20 @@ Use operator password + API keys for Web auth."
         )
     }
 
+    /// Map a raw category to an allowed one: case and surrounding whitespace
+    /// are ignored, and unknown or missing values become
+    /// `memory_default_category`.
+    pub fn normalize_memory_category(&self, raw: Option<&str>) -> String {
+        normalize_memory_category(&self.memory_categories, &self.memory_default_category, raw)
+    }
+
+    pub fn memory_category_names(&self) -> Vec<String> {
+        self.memory_categories
+            .iter()
+            .map(|c| c.name.clone())
+            .collect()
+    }
+
     pub fn tool_timeout_secs(&self, tool_name: &str, fallback: u64) -> u64 {
         let normalized = tool_name.trim().to_ascii_lowercase();
         if let Some(timeout_secs) = self.tool_timeout_overrides.get(&normalized) {
@@ -1260,6 +2094,31 @@ Use operator password + API keys for Web auth."
         }
     }
 
+    /// The registry-enforced limit for one call of `tool_name`, if any: at
+    /// least `TOOL_HARD_TIMEOUT_MARGIN_SECS` above the tool's own timeout
+    /// (its override or `default_tool_timeout_secs`, or `requested_secs` when
+    /// the call asked for longer).
+    pub fn tool_hard_timeout(
+        &self,
+        tool_name: &str,
+        requested_secs: Option<u64>,
+    ) -> Option<std::time::Duration> {
+        if self.tool_hard_timeout_secs == 0 {
+            return None;
+        }
+        let normalized = tool_name.trim().to_ascii_lowercase();
+        let soft_secs = self
+            .tool_timeout_overrides
+            .get(&normalized)
+            .copied()
+            .unwrap_or(self.default_tool_timeout_secs)
+            .max(requested_secs.unwrap_or(0));
+        let secs = self
+            .tool_hard_timeout_secs
+            .max(soft_secs.saturating_add(TOOL_HARD_TIMEOUT_MARGIN_SECS));
+        Some(std::time::Duration::from_secs(secs))
+    }
+
     pub fn mcp_request_timeout_secs(&self) -> u64 {
         if self.default_mcp_request_timeout_secs == 0 {
             default_mcp_request_timeout_secs()
@@ -1302,6 +2161,63 @@ mod tests {
         crate::test_support::env_lock()
     }
 
+    #[test]
+    fn test_normalize_sender_name_applies_channel_rules() {
+        let mut config = Config::test_defaults();
+        config.channels.insert(
+            "matrix".into(),
+            serde_yaml::from_str(
+                r#"
+sender_name_rules:
+  - pattern: "^\\[irc\\] "
+  - pattern: "_+$"
+  - pattern: "(?i)^alice.*$"
+    replace: "Alice"
+"#,
+            )
+            .unwrap(),
+        );
+        assert_eq!(config.normalize_sender_name("matrix", "[irc] bob__"), "bob");
+        assert_eq!(
+            config.normalize_sender_name("matrix", "[irc] ALICE_away"),
+            "Alice"
+        );
+        // Other channels are untouched.
+        assert_eq!(
+            config.normalize_sender_name("telegram", "[irc] bob__"),
+            "[irc] bob__"
+        );
+    }
+
+    #[test]
+    fn test_normalize_sender_name_account_fallback_and_empty_result() {
+        let mut config = Config::test_defaults();
+        config.channels.insert(
+            "irc".into(),
+            serde_yaml::from_str("sender_name_rules:\n  - pattern: \"\\\\|.*$\"\n").unwrap(),
+        );
+        assert_eq!(
+            config.normalize_sender_name("irc.libera", "carol|afk"),
+            "carol"
+        );
+        config.channels.insert(
+            "irc".into(),
+            serde_yaml::from_str("sender_name_rules:\n  - pattern: \".*\"\n").unwrap(),
+        );
+        assert_eq!(config.normalize_sender_name("irc", "dave"), "dave");
+    }
+
+    #[test]
+    fn test_invalid_sender_name_rule_rejected() {
+        let mut config = Config::test_defaults();
+        config.channels.insert(
+            "irc".into(),
+            serde_yaml::from_str("sender_name_rules:\n  - pattern: \"(unclosed\"\n").unwrap(),
+        );
+        let err = config.post_deserialize().unwrap_err().to_string();
+        assert!(err.contains("sender_name_rules"));
+    }
+
     #[test]
     fn test_clawhub_config_defaults() {
         let yaml = "telegram_bot_token: tok\nbot_username: bot\napi_key: key\n";
@@ -1468,6 +2384,31 @@ voice_transcription_command: "whisper-mlx --file {file}"
         assert_eq!(config.tool_timeout_secs("browser", 120), 45);
     }
 
+    #[test]
+    fn test_tool_hard_timeout_stays_above_the_tool_timeout() {
+        let secs = |d: Option<std::time::Duration>| d.map(|d| d.as_secs());
+        let mut config = test_config();
+        config
+            .tool_timeout_overrides
+            .insert("bash".to_string(), 900);
+        assert_eq!(secs(config.tool_hard_timeout("sub_agent", None)), Some(600));
+        assert_eq!(secs(config.tool_hard_timeout("bash", None)), Some(930));
+        assert_eq!(
+            secs(config.tool_hard_timeout("web_fetch", Some(1_000))),
+            Some(1_030)
+        );
+        assert_eq!(
+            secs(config.tool_hard_timeout("web_fetch", Some(5))),
+            Some(600)
+        );
+        config.default_tool_timeout_secs = 700;
+        assert_eq!(secs(config.tool_hard_timeout("sub_agent", None)), Some(730));
+
+        config.tool_hard_timeout_secs = 0;
+        assert_eq!(config.tool_hard_timeout("sub_agent", None), None);
+        assert_eq!(config.tool_hard_timeout("bash", Some(5)), None);
+    }
+
     #[test]
     fn test_default_data_dir_uses_microclaw_home() {
         let yaml = "telegram_bot_token: tok\nbot_username: bot\napi_key: key\n";
@@ -1609,6 +2550,60 @@ voice_transcription_command: "whisper-mlx --file {file}"
         assert!(msg.contains("Invalid timezone"));
     }
 
+    #[test]
+    fn test_normalize_memory_category_maps_unknown_to_default() {
+        let config = Config::test_defaults();
+        assert_eq!(
+            config.normalize_memory_category(Some(" profile ")),
+            "PROFILE"
+        );
+        assert_eq!(config.normalize_memory_category(Some("EVENT")), "EVENT");
+        assert_eq!(
+            config.normalize_memory_category(Some("gossip")),
+            "KNOWLEDGE"
+        );
+        assert_eq!(config.normalize_memory_category(None), "KNOWLEDGE");
+    }
+
+    #[test]
+    fn test_post_deserialize_memory_categories_normalized_and_validated() {
+        let yaml = "telegram_bot_token: tok\nbot_username: bot\napi_key: key\nmemory_categories:\n  - name: ' task '\n    description: open work items\n  - name: TASK\n  - name: profile\nmemory_default_category: task\n";
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        config.post_deserialize().unwrap();
+        assert_eq!(config.memory_category_names(), vec!["TASK", "PROFILE"]);
+        assert_eq!(config.memory_categories[0].description, "open work items");
+        assert_eq!(config.memory_default_category, "TASK");
+        assert_eq!(config.normalize_memory_category(Some("event")), "TASK");
+
+        let yaml =
+            "telegram_bot_token: tok\nbot_username: bot\napi_key: key\nmemory_categories: []\n";
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        config.post_deserialize().unwrap();
+        assert_eq!(
+            config.memory_category_names(),
+            vec!["PROFILE", "KNOWLEDGE", "EVENT"]
+        );
+
+        let yaml = "telegram_bot_token: tok\nbot_username: bot\napi_key: key\nmemory_default_category: misc\n";
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        let err = config.post_deserialize().unwrap_err();
+        assert!(err.to_string().contains("memory_default_category"));
+    }
+
+    #[test]
+    fn test_post_deserialize_tool_use_bias_normalized_and_validated() {
+        let yaml = "telegram_bot_token: tok\nbot_username: bot\napi_key: key\ntool_use_bias: ' Aggressive '\n";
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        config.post_deserialize().unwrap();
+        assert_eq!(config.tool_use_bias, "aggressive");
+
+        let yaml =
+            "telegram_bot_token: tok\nbot_username: bot\napi_key: key\ntool_use_bias: lazy\n";
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        let err = config.post_deserialize().unwrap_err();
+        assert!(err.to_string().contains("tool_use_bias"));
+    }
+
     #[test]
     fn test_post_deserialize_missing_api_key() {
         let yaml = "telegram_bot_token: tok\nbot_username: bot\n";
@@ -1888,6 +2883,32 @@ channels:
         assert_eq!(config.model, "llama3.2");
     }
 
+    #[test]
+    fn test_post_deserialize_normalizes_llm_fallbacks() {
+        let yaml = "telegram_bot_token: tok\nbot_username: bot\napi_key: key\nllm_fallbacks:\n  - provider: ' OpenAI '\n    api_key: ' sk-x '\n  - provider: ollama\n    model: qwen2.5\n";
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        config.post_deserialize().unwrap();
+        assert_eq!(config.llm_fallbacks[0].provider, "openai");
+        assert_eq!(config.llm_fallbacks[0].model, "gpt-5.2");
+        assert_eq!(config.llm_fallbacks[0].api_key.as_deref(), Some("sk-x"));
+        assert_eq!(config.llm_fallbacks[1].model, "qwen2.5");
+
+        let yaml = "telegram_bot_token: tok\nbot_username: bot\napi_key: key\nllm_fallbacks:\n  - provider: ''\n";
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        let err = config.post_deserialize().unwrap_err();
+        assert!(err.to_string().contains("llm_fallbacks[0].provider"));
+    }
+
+    #[test]
+    fn test_post_deserialize_gemini_default_model() {
+        let yaml =
+            "telegram_bot_token: tok\nbot_username: bot\napi_key: key\nllm_provider: Gemini\n";
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        config.post_deserialize().unwrap();
+        assert_eq!(config.llm_provider, "gemini");
+        assert_eq!(config.model, "gemini-2.5-flash");
+    }
+
     #[test]
     fn test_post_deserialize_empty_base_url_becomes_none() {
         let yaml = "telegram_bot_token: tok\nbot_username: bot\napi_key: key\nllm_base_url: '  '\n";
@@ -2015,6 +3036,70 @@ model_prices:
             .contains("model_prices entries must include non-empty model"));
     }
 
+    #[test]
+    fn test_tenant_api_key_precedence() {
+        let yaml = r#"
+telegram_bot_token: tok
+bot_username: bot
+api_key: default-key
+tenant_api_keys:
+  "telegram.work:42":
+    provider: anthropic
+    api_key: chat-key
+  "telegram.work":
+    provider: openai
+    api_key: account-key
+  telegram:
+    provider: anthropic
+    api_key: channel-key
+"#;
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        config.post_deserialize().unwrap();
+        assert_eq!(
+            config.tenant_api_key("telegram.work", "42", "anthropic"),
+            Some(("telegram.work:42", "chat-key"))
+        );
+        assert_eq!(
+            config.tenant_api_key("telegram.work", "7", "openai"),
+            Some(("telegram.work", "account-key"))
+        );
+        // An entry for another provider is skipped for a less specific one.
+        assert_eq!(
+            config.tenant_api_key("telegram.work", "7", "anthropic"),
+            Some(("telegram", "channel-key"))
+        );
+        assert_eq!(
+            config.tenant_api_key("telegram.home", "42", "anthropic"),
+            Some(("telegram", "channel-key"))
+        );
+        assert_eq!(
+            config.tenant_api_key("telegram", "42", "anthropic"),
+            Some(("telegram", "channel-key"))
+        );
+        assert_eq!(config.tenant_api_key("telegram", "42", "deepseek"), None);
+        assert_eq!(config.tenant_api_key("discord", "42", "anthropic"), None);
+        assert!(!format!("{config:?}").contains("chat-key"));
+
+        config.tenant_api_keys.insert(
+            "discord".into(),
+            TenantApiKey {
+                provider: "anthropic".into(),
+                api_key: "  ".into(),
+            },
+        );
+        let err = config.post_deserialize().unwrap_err();
+        assert!(err.to_string().contains("tenant_api_keys.discord.api_key"));
+        config.tenant_api_keys.insert(
+            "discord".into(),
+            TenantApiKey {
+                provider: String::new(),
+                api_key: "discord-key".into(),
+            },
+        );
+        let err = config.post_deserialize().unwrap_err();
+        assert!(err.to_string().contains("tenant_api_keys.discord.provider"));
+    }
+
     #[test]
     fn test_config_yaml_with_all_optional_fields() {
         let yaml = r#"
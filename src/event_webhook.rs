@@ -0,0 +1,291 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use serde_json::{json, Value};
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use tracing::warn;
+
+use crate::agent_engine::AgentEvent;
+use crate::config::Config;
+
+/// Events that can be sent to `event_webhook_url`.
+pub const EVENT_TYPES: &[&str] = &[
+    "message_received",
+    "response_sent",
+    "tool_executed",
+    "task_run",
+];
+
+pub const SIGNATURE_HEADER: &str = "X-MicroClaw-Signature";
+pub const EVENT_HEADER: &str = "X-MicroClaw-Event";
+
+const RETRY_BASE_DELAY_MS: u64 = 500;
+
+/// POSTs selected bot events as JSON to an integrator's endpoint.
+pub struct EventWebhook {
+    client: reqwest::Client,
+    url: String,
+    secret: Option<String>,
+    events: Vec<String>,
+    max_retries: u32,
+}
+
+impl EventWebhook {
+    pub fn from_config(config: &Config) -> Option<Arc<Self>> {
+        let url = config
+            .event_webhook_url
+            .as_deref()
+            .map(str::trim)
+            .filter(|u| !u.is_empty())?;
+        Some(Arc::new(Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+            url: url.to_string(),
+            secret: config
+                .event_webhook_secret
+                .clone()
+                .filter(|s| !s.trim().is_empty()),
+            events: config.event_webhook_events.clone(),
+            max_retries: config.event_webhook_max_retries,
+        }))
+    }
+
+    pub fn wants(&self, event: &str) -> bool {
+        self.events.iter().any(|e| e == event)
+    }
+
+    /// Whether any event derived from the agent loop's event channel is wanted.
+    pub fn wants_agent_events(&self) -> bool {
+        self.wants("response_sent") || self.wants("tool_executed")
+    }
+
+    /// Queue `event` for delivery in the background if it is selected.
+    pub fn emit(self: &Arc<Self>, event: &str, chat_id: i64, channel: Option<&str>, data: Value) {
+        if !self.wants(event) {
+            return;
+        }
+        let payload = build_payload(
+            event,
+            chat_id,
+            channel,
+            data,
+            &chrono::Utc::now().to_rfc3339(),
+        );
+        let webhook = self.clone();
+        let event = event.to_string();
+        tokio::spawn(async move {
+            webhook.deliver(&event, payload.to_string()).await;
+        });
+    }
+
+    async fn deliver(&self, event: &str, body: String) {
+        let signature = self.secret.as_deref().map(|s| sign_payload(s, &body));
+        let mut attempt = 0;
+        loop {
+            let mut req = self
+                .client
+                .post(&self.url)
+                .header("content-type", "application/json")
+                .header(EVENT_HEADER, event)
+                .body(body.clone());
+            if let Some(sig) = &signature {
+                req = req.header(SIGNATURE_HEADER, sig);
+            }
+            let retryable = match req.send().await {
+                Ok(resp) if resp.status().is_success() => return,
+                Ok(resp) => {
+                    let status = resp.status();
+                    warn!("Event webhook returned HTTP {status} for {event}");
+                    status.is_server_error() || status.as_u16() == 429
+                }
+                Err(e) => {
+                    warn!("Event webhook request failed for {event}: {e}");
+                    true
+                }
+            };
+            if !retryable || attempt >= self.max_retries {
+                return;
+            }
+            attempt += 1;
+            tokio::time::sleep(Duration::from_millis(
+                RETRY_BASE_DELAY_MS << (attempt - 1).min(6),
+            ))
+            .await;
+        }
+    }
+}
+
+/// The JSON body for one event. Values under secret-looking keys (tokens,
+/// passwords, API keys) are replaced with `***`.
+pub fn build_payload(
+    event: &str,
+    chat_id: i64,
+    channel: Option<&str>,
+    mut data: Value,
+    timestamp: &str,
+) -> Value {
+    crate::web::redact_json_secrets(&mut data, None);
+    json!({
+        "event": event,
+        "chat_id": chat_id,
+        "channel": channel,
+        "timestamp": timestamp,
+        "data": data,
+    })
+}
+
+/// HMAC-SHA256 of the request body, sent as `sha256=<hex>` in
+/// `X-MicroClaw-Signature`.
+pub fn sign_payload(secret: &str, body: &str) -> String {
+    let mut mac = Hmac::<sha2::Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(body.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Inputs of started tools that have not reported a result yet, queued per
+/// tool name so interleaved or repeated calls pair with their own input.
+#[derive(Default)]
+struct PendingToolInputs(HashMap<String, VecDeque<Value>>);
+
+impl PendingToolInputs {
+    fn start(&mut self, name: &str, input: Value) {
+        self.0.entry(name.to_string()).or_default().push_back(input);
+    }
+
+    fn finish(&mut self, name: &str) -> Option<Value> {
+        let queue = self.0.get_mut(name)?;
+        let input = queue.pop_front();
+        if queue.is_empty() {
+            self.0.remove(name);
+        }
+        input
+    }
+}
+
+/// Forward agent events to `downstream` unchanged while turning tool results
+/// and final replies into `tool_executed` / `response_sent` webhooks.
+pub(crate) async fn tap_agent_events(
+    webhook: Arc<EventWebhook>,
+    chat_id: i64,
+    channel: String,
+    mut rx: UnboundedReceiver<AgentEvent>,
+    downstream: Option<UnboundedSender<AgentEvent>>,
+) {
+    let mut tool_inputs = PendingToolInputs::default();
+    while let Some(event) = rx.recv().await {
+        match &event {
+            AgentEvent::ToolStart { name, input } => tool_inputs.start(name, input.clone()),
+            AgentEvent::ToolResult {
+                name,
+                is_error,
+                preview,
+                duration_ms,
+                error_type,
+                ..
+            } => webhook.emit(
+                "tool_executed",
+                chat_id,
+                Some(&channel),
+                json!({
+                    "tool": name,
+                    "input": tool_inputs.finish(name),
+                    "is_error": is_error,
+                    "error_type": error_type,
+                    "duration_ms": duration_ms,
+                    "preview": preview,
+                }),
+            ),
+            AgentEvent::FinalResponse { text } => webhook.emit(
+                "response_sent",
+                chat_id,
+                Some(&channel),
+                json!({ "text": text }),
+            ),
+            _ => {}
+        }
+        if let Some(tx) = &downstream {
+            let _ = tx.send(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_payload_redacts_secrets() {
+        let payload = build_payload(
+            "tool_executed",
+            42,
+            Some("telegram"),
+            json!({
+                "tool": "web_fetch",
+                "input": {"url": "https://example.com", "api_key": "sk-live", "headers": {"token": "t"}},
+            }),
+            "2024-01-01T00:00:00Z",
+        );
+        assert_eq!(
+            payload,
+            json!({
+                "event": "tool_executed",
+                "chat_id": 42,
+                "channel": "telegram",
+                "timestamp": "2024-01-01T00:00:00Z",
+                "data": {
+                    "tool": "web_fetch",
+                    "input": {"url": "https://example.com", "api_key": "***", "headers": {"token": "***"}},
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn test_sign_payload_matches_hmac_sha256() {
+        // RFC 4231 test case 2.
+        assert_eq!(
+            sign_payload("Jefe", "what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        assert_ne!(
+            sign_payload("Jefe", "what do ya want for nothing!"),
+            sign_payload("Jefe", "what do ya want for nothing?")
+        );
+    }
+
+    #[test]
+    fn test_pending_tool_inputs_pair_by_name_in_order() {
+        let mut pending = PendingToolInputs::default();
+        pending.start("bash", json!({"command": "ls"}));
+        pending.start("read_file", json!({"path": "a.txt"}));
+        pending.start("bash", json!({"command": "pwd"}));
+        assert_eq!(pending.finish("read_file"), Some(json!({"path": "a.txt"})));
+        assert_eq!(pending.finish("bash"), Some(json!({"command": "ls"})));
+        assert_eq!(pending.finish("bash"), Some(json!({"command": "pwd"})));
+        assert_eq!(pending.finish("bash"), None);
+        assert_eq!(pending.finish("web_fetch"), None);
+    }
+
+    #[tokio::test]
+    async fn test_tap_forwards_events_downstream() {
+        let mut config = Config::test_defaults();
+        config.event_webhook_url = Some("http://127.0.0.1:9/hook".into());
+        config.event_webhook_events = vec![];
+        let webhook = EventWebhook::from_config(&config).unwrap();
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let (down_tx, mut down_rx) = tokio::sync::mpsc::unbounded_channel();
+        tx.send(AgentEvent::FinalResponse { text: "hi".into() })
+            .unwrap();
+        drop(tx);
+        tap_agent_events(webhook, 1, "web".into(), rx, Some(down_tx)).await;
+        assert!(matches!(
+            down_rx.recv().await,
+            Some(AgentEvent::FinalResponse { text }) if text == "hi"
+        ));
+    }
+}
@@ -1,4 +1,5 @@
 use crate::config::Config;
+use crate::heartbeat::{self, HeartbeatHealth};
 use crate::logging;
 use anyhow::{anyhow, Context, Result};
 use clap::{Parser, Subcommand};
@@ -10,6 +11,9 @@ use std::process::Command;
 
 const LINUX_SERVICE_NAME: &str = "microclaw-gateway.service";
 const MAC_LABEL: &str = "ai.microclaw.gateway";
+const WINDOWS_TASK_NAME: &str = "MicroClawGateway";
+const WINDOWS_LAUNCHER_FILE: &str = "microclaw-gateway.cmd";
+const WINDOWS_TASK_XML_FILE: &str = "microclaw-gateway.xml";
 const LOG_STDOUT_FILE: &str = "microclaw-gateway.log";
 const LOG_STDERR_FILE: &str = "microclaw-gateway.error.log";
 const DEFAULT_LOG_LINES: usize = 200;
@@ -20,6 +24,7 @@ struct ServiceContext {
     working_dir: PathBuf,
     config_path: Option<PathBuf>,
     runtime_logs_dir: PathBuf,
+    heartbeat_path: PathBuf,
     service_env: BTreeMap<String, String>,
 }
 
@@ -53,6 +58,15 @@ struct LinuxRuntimeStatus {
     fragment_path: Option<String>,
 }
 
+#[derive(Debug, Default)]
+struct WindowsRuntimeStatus {
+    status: Option<String>,
+    last_run_time: Option<String>,
+    last_result: Option<String>,
+    next_run_time: Option<String>,
+    task_to_run: Option<String>,
+}
+
 pub fn handle_gateway_cli(args: &[String]) -> Result<()> {
     let cli = match GatewayCli::try_parse_from(
         std::iter::once("gateway").chain(args.iter().map(std::string::String::as_str)),
@@ -150,9 +164,11 @@ fn install(opts: InstallOptions) -> Result<()> {
         install_macos(&ctx, &opts)
     } else if cfg!(target_os = "linux") {
         install_linux(&ctx, &opts)
+    } else if cfg!(target_os = "windows") {
+        install_windows(&ctx, &opts)
     } else {
         Err(anyhow!(
-            "Gateway service is only supported on macOS and Linux"
+            "Gateway service is only supported on macOS, Linux and Windows"
         ))
     }
 }
@@ -162,9 +178,11 @@ fn uninstall() -> Result<()> {
         uninstall_macos()
     } else if cfg!(target_os = "linux") {
         uninstall_linux()
+    } else if cfg!(target_os = "windows") {
+        uninstall_windows()
     } else {
         Err(anyhow!(
-            "Gateway service is only supported on macOS and Linux"
+            "Gateway service is only supported on macOS, Linux and Windows"
         ))
     }
 }
@@ -174,9 +192,11 @@ fn start() -> Result<()> {
         start_macos()
     } else if cfg!(target_os = "linux") {
         start_linux()
+    } else if cfg!(target_os = "windows") {
+        start_windows()
     } else {
         Err(anyhow!(
-            "Gateway service is only supported on macOS and Linux"
+            "Gateway service is only supported on macOS, Linux and Windows"
         ))
     }
 }
@@ -186,9 +206,11 @@ fn stop() -> Result<()> {
         stop_macos()
     } else if cfg!(target_os = "linux") {
         stop_linux()
+    } else if cfg!(target_os = "windows") {
+        stop_windows()
     } else {
         Err(anyhow!(
-            "Gateway service is only supported on macOS and Linux"
+            "Gateway service is only supported on macOS, Linux and Windows"
         ))
     }
 }
@@ -198,9 +220,11 @@ fn restart() -> Result<()> {
         restart_macos()
     } else if cfg!(target_os = "linux") {
         restart_linux()
+    } else if cfg!(target_os = "windows") {
+        restart_windows()
     } else {
         Err(anyhow!(
-            "Gateway service is only supported on macOS and Linux"
+            "Gateway service is only supported on macOS, Linux and Windows"
         ))
     }
 }
@@ -212,9 +236,11 @@ fn status(opts: StatusOptions) -> Result<()> {
         status_macos(&ctx, &opts)
     } else if cfg!(target_os = "linux") {
         status_linux(&ctx, &opts)
+    } else if cfg!(target_os = "windows") {
+        status_windows(&ctx, &opts)
     } else {
         Err(anyhow!(
-            "Gateway service is only supported on macOS and Linux"
+            "Gateway service is only supported on macOS, Linux and Windows"
         ))
     }
 }
@@ -245,6 +271,7 @@ fn build_context() -> Result<ServiceContext> {
     let working_dir = std::env::current_dir().context("Failed to resolve current directory")?;
     let config_path = resolve_config_path(&working_dir);
     let runtime_logs_dir = resolve_runtime_logs_dir(&working_dir);
+    let heartbeat_path = resolve_runtime_data_dir(&working_dir).join(heartbeat::HEARTBEAT_FILE);
     let service_env = build_service_env(config_path.as_ref());
 
     Ok(ServiceContext {
@@ -252,6 +279,7 @@ fn build_context() -> Result<ServiceContext> {
         working_dir,
         config_path,
         runtime_logs_dir,
+        heartbeat_path,
         service_env,
     })
 }
@@ -275,10 +303,90 @@ fn resolve_config_path(cwd: &Path) -> Option<PathBuf> {
     None
 }
 
-fn resolve_runtime_logs_dir(cwd: &Path) -> PathBuf {
+fn resolve_runtime_data_dir(cwd: &Path) -> PathBuf {
     match Config::load() {
-        Ok(cfg) => PathBuf::from(cfg.runtime_data_dir()).join("logs"),
-        Err(_) => cwd.join("runtime").join("logs"),
+        Ok(cfg) => PathBuf::from(cfg.runtime_data_dir()),
+        Err(_) => cwd.join("runtime"),
+    }
+}
+
+fn resolve_runtime_logs_dir(cwd: &Path) -> PathBuf {
+    resolve_runtime_data_dir(cwd).join("logs")
+}
+
+/// Only checked for `--deep`; a missing file while the service runs means
+/// the bot has not written its first heartbeat or is an older build, so it
+/// is not reported as a failure.
+fn deep_heartbeat(ctx: &ServiceContext, opts: &StatusOptions) -> Option<HeartbeatHealth> {
+    opts.deep
+        .then(|| heartbeat::check_heartbeat(&ctx.heartbeat_path, chrono::Utc::now()))
+}
+
+fn heartbeat_json(health: Option<&HeartbeatHealth>) -> serde_json::Value {
+    let Some(health) = health else {
+        return serde_json::Value::Null;
+    };
+    match health {
+        HeartbeatHealth::Healthy {
+            age_secs,
+            heartbeat,
+        }
+        | HeartbeatHealth::Stale {
+            age_secs,
+            heartbeat,
+        } => json!({
+            "state": health.label(),
+            "age_secs": age_secs,
+            "timestamp": heartbeat.timestamp,
+            "pid": heartbeat.pid,
+            "connected_channels": heartbeat.connected_channels,
+        }),
+        HeartbeatHealth::Missing => json!({ "state": health.label() }),
+        HeartbeatHealth::Invalid(err) => json!({ "state": health.label(), "error": err }),
+    }
+}
+
+fn print_heartbeat_text(health: Option<&HeartbeatHealth>) {
+    let Some(health) = health else {
+        return;
+    };
+    match health {
+        HeartbeatHealth::Healthy {
+            age_secs,
+            heartbeat,
+        }
+        | HeartbeatHealth::Stale {
+            age_secs,
+            heartbeat,
+        } => {
+            let channels = if heartbeat.connected_channels.is_empty() {
+                "none".to_string()
+            } else {
+                heartbeat.connected_channels.join(", ")
+            };
+            println!(
+                "  heartbeat: {} ({}s ago, channels: {})",
+                health.label(),
+                age_secs,
+                channels
+            );
+        }
+        HeartbeatHealth::Missing => println!("  heartbeat: missing"),
+        HeartbeatHealth::Invalid(err) => println!("  heartbeat: invalid ({})", err),
+    }
+}
+
+fn status_result(running: bool, heartbeat: Option<&HeartbeatHealth>) -> Result<()> {
+    if !running {
+        return Err(anyhow!("Gateway service is not running"));
+    }
+    match heartbeat {
+        Some(HeartbeatHealth::Missing) => Ok(()),
+        Some(health) if !health.is_healthy() => Err(anyhow!(
+            "Gateway service is running but unhealthy (heartbeat {})",
+            health.label()
+        )),
+        _ => Ok(()),
     }
 }
 
@@ -654,6 +762,7 @@ fn audit_linux_unit(ctx: &ServiceContext, runtime: &LinuxRuntimeStatus) -> Vec<S
 fn print_linux_status_text(
     runtime: &LinuxRuntimeStatus,
     issues: &[String],
+    heartbeat: Option<&HeartbeatHealth>,
     raw_status: Option<&str>,
     deep: bool,
 ) {
@@ -699,6 +808,7 @@ fn print_linux_status_text(
             println!("    - {}", issue);
         }
     }
+    print_heartbeat_text(heartbeat);
 
     if deep {
         if let Some(raw) = raw_status {
@@ -745,6 +855,7 @@ fn status_linux(ctx: &ServiceContext, opts: &StatusOptions) -> Result<()> {
     };
 
     let running = runtime.active_state.as_deref() == Some("active");
+    let heartbeat = deep_heartbeat(ctx, opts);
 
     if opts.json {
         let value = json!({
@@ -759,18 +870,21 @@ fn status_linux(ctx: &ServiceContext, opts: &StatusOptions) -> Result<()> {
             "exec_main_status": runtime.exec_main_status,
             "fragment_path": runtime.fragment_path,
             "drift_issues": issues,
+            "heartbeat": heartbeat_json(heartbeat.as_ref()),
             "deep_status": deep_output,
         });
         println!("{}", serde_json::to_string_pretty(&value)?);
     } else {
-        print_linux_status_text(&runtime, &issues, deep_output.as_deref(), opts.deep);
+        print_linux_status_text(
+            &runtime,
+            &issues,
+            heartbeat.as_ref(),
+            deep_output.as_deref(),
+            opts.deep,
+        );
     }
 
-    if running {
-        Ok(())
-    } else {
-        Err(anyhow!("Gateway service is not running"))
-    }
+    status_result(running, heartbeat.as_ref())
 }
 
 fn mac_plist_path() -> Result<PathBuf> {
@@ -1042,6 +1156,7 @@ fn plist_key_has_true(content: &str, key: &str) -> bool {
 fn print_macos_status_text(
     runtime: &MacRuntimeStatus,
     issues: &[String],
+    heartbeat: Option<&HeartbeatHealth>,
     raw_status: Option<&str>,
     deep: bool,
 ) {
@@ -1073,6 +1188,7 @@ fn print_macos_status_text(
             println!("    - {}", issue);
         }
     }
+    print_heartbeat_text(heartbeat);
 
     if deep {
         if let Some(raw) = raw_status {
@@ -1103,6 +1219,7 @@ fn status_macos(ctx: &ServiceContext, opts: &StatusOptions) -> Result<()> {
         || runtime.pid.unwrap_or(0) > 0;
 
     let deep_raw = if opts.deep { Some(raw.clone()) } else { None };
+    let heartbeat = deep_heartbeat(ctx, opts);
 
     if opts.json {
         let value = json!({
@@ -1114,18 +1231,462 @@ fn status_macos(ctx: &ServiceContext, opts: &StatusOptions) -> Result<()> {
             "last_exit_status": runtime.last_exit_status,
             "last_exit_reason": runtime.last_exit_reason,
             "drift_issues": issues,
+            "heartbeat": heartbeat_json(heartbeat.as_ref()),
             "deep_status": deep_raw,
         });
         println!("{}", serde_json::to_string_pretty(&value)?);
     } else {
-        print_macos_status_text(&runtime, &issues, deep_raw.as_deref(), opts.deep);
+        print_macos_status_text(
+            &runtime,
+            &issues,
+            heartbeat.as_ref(),
+            deep_raw.as_deref(),
+            opts.deep,
+        );
+    }
+
+    status_result(running, heartbeat.as_ref())
+}
+
+fn windows_service_dir() -> Result<PathBuf> {
+    let local = std::env::var("LOCALAPPDATA").context("LOCALAPPDATA is not set")?;
+    Ok(PathBuf::from(local).join("MicroClaw"))
+}
+
+fn windows_launcher_path() -> Result<PathBuf> {
+    Ok(windows_service_dir()?.join(WINDOWS_LAUNCHER_FILE))
+}
+
+fn windows_task_user() -> Result<String> {
+    let user = std::env::var("USERNAME").context("USERNAME is not set")?;
+    match std::env::var("USERDOMAIN") {
+        Ok(domain) if !domain.trim().is_empty() => Ok(format!("{domain}\\{user}")),
+        _ => Ok(user),
+    }
+}
+
+fn cmd_escape_value(value: &str, label: &str) -> Result<String> {
+    assert_no_line_breaks(value, label)?;
+    if value.contains('"') {
+        return Err(anyhow!("{} cannot contain double quotes", label));
     }
+    Ok(value.replace('%', "%%"))
+}
+
+/// Batch launcher the scheduled task runs: the Windows counterpart of the
+/// systemd unit's WorkingDirectory/Environment/ExecStart lines.
+fn render_windows_launcher(ctx: &ServiceContext) -> Result<String> {
+    let mut script = String::new();
+    script.push_str("@echo off\r\n");
+    script.push_str("rem MicroClaw Gateway Service\r\n");
+    script.push_str(&format!(
+        "cd /d \"{}\"\r\n",
+        cmd_escape_value(&ctx.working_dir.to_string_lossy(), "Working directory")?
+    ));
+    for (key, value) in &ctx.service_env {
+        let key = cmd_escape_value(key, "Environment variable name")?;
+        let value = cmd_escape_value(value, "Environment variable value")?;
+        script.push_str(&format!("set \"{}={}\"\r\n", key, value));
+    }
+    script.push_str(&format!(
+        "\"{}\" start >> \"{}\" 2>> \"{}\"\r\n",
+        cmd_escape_value(&ctx.exe_path.to_string_lossy(), "Executable path")?,
+        cmd_escape_value(
+            &ctx.runtime_logs_dir.join(LOG_STDOUT_FILE).to_string_lossy(),
+            "Log path"
+        )?,
+        cmd_escape_value(
+            &ctx.runtime_logs_dir.join(LOG_STDERR_FILE).to_string_lossy(),
+            "Log path"
+        )?
+    ));
+    Ok(script)
+}
+
+/// Task Scheduler definition: start at logon, no time limit, restart on
+/// failure. A scheduled task is used instead of an SCM service because a
+/// plain console binary cannot answer Service Control Manager requests,
+/// and it installs without administrator rights.
+fn render_windows_task_xml(ctx: &ServiceContext, launcher: &Path, user: &str) -> String {
+    let user = xml_escape(user);
+    [
+        "<?xml version=\"1.0\" encoding=\"UTF-16\"?>".to_string(),
+        "<Task version=\"1.2\" xmlns=\"http://schemas.microsoft.com/windows/2004/02/mit/task\">"
+            .to_string(),
+        "  <RegistrationInfo>".to_string(),
+        "    <Description>MicroClaw Gateway Service</Description>".to_string(),
+        "  </RegistrationInfo>".to_string(),
+        "  <Triggers>".to_string(),
+        "    <LogonTrigger>".to_string(),
+        "      <Enabled>true</Enabled>".to_string(),
+        format!("      <UserId>{user}</UserId>"),
+        "    </LogonTrigger>".to_string(),
+        "  </Triggers>".to_string(),
+        "  <Principals>".to_string(),
+        "    <Principal id=\"Author\">".to_string(),
+        format!("      <UserId>{user}</UserId>"),
+        "      <LogonType>InteractiveToken</LogonType>".to_string(),
+        "      <RunLevel>LeastPrivilege</RunLevel>".to_string(),
+        "    </Principal>".to_string(),
+        "  </Principals>".to_string(),
+        "  <Settings>".to_string(),
+        "    <MultipleInstancesPolicy>IgnoreNew</MultipleInstancesPolicy>".to_string(),
+        "    <DisallowStartIfOnBatteries>false</DisallowStartIfOnBatteries>".to_string(),
+        "    <StopIfGoingOnBatteries>false</StopIfGoingOnBatteries>".to_string(),
+        "    <ExecutionTimeLimit>PT0S</ExecutionTimeLimit>".to_string(),
+        "    <RestartOnFailure>".to_string(),
+        "      <Interval>PT1M</Interval>".to_string(),
+        "      <Count>999</Count>".to_string(),
+        "    </RestartOnFailure>".to_string(),
+        "    <Enabled>true</Enabled>".to_string(),
+        "  </Settings>".to_string(),
+        "  <Actions Context=\"Author\">".to_string(),
+        "    <Exec>".to_string(),
+        format!(
+            "      <Command>{}</Command>",
+            xml_escape(&launcher.to_string_lossy())
+        ),
+        format!(
+            "      <WorkingDirectory>{}</WorkingDirectory>",
+            xml_escape(&ctx.working_dir.to_string_lossy())
+        ),
+        "    </Exec>".to_string(),
+        "  </Actions>".to_string(),
+        "</Task>".to_string(),
+    ]
+    .join("\r\n")
+}
+
+/// schtasks reads /XML files as UTF-16.
+fn utf16le_with_bom(text: &str) -> Vec<u8> {
+    let mut bytes = vec![0xFF, 0xFE];
+    for unit in text.encode_utf16() {
+        bytes.extend_from_slice(&unit.to_le_bytes());
+    }
+    bytes
+}
+
+/// Runs a PowerShell snippet and returns its stdout. Used instead of parsing
+/// `schtasks /Query`, whose labels and values follow the system language.
+fn run_powershell(script: &str) -> Result<std::process::Output> {
+    run_command(
+        "powershell",
+        &["-NoProfile", "-NonInteractive", "-Command", script],
+    )
+}
+
+/// Process ids of the running gateway launcher (`cmd.exe` running our
+/// script), one per line.
+fn windows_launcher_pids(launcher: &Path) -> Result<Vec<u32>> {
+    let script = format!(
+        "Get-CimInstance Win32_Process -Filter \"Name='cmd.exe'\" | Where-Object {{ $_.CommandLine -like '*{}*' }} | ForEach-Object {{ $_.ProcessId }}",
+        launcher.to_string_lossy().replace('\'', "''")
+    );
+    let output = run_powershell(&script)?;
+    Ok(parse_pid_lines(&String::from_utf8_lossy(&output.stdout)))
+}
+
+fn parse_pid_lines(output: &str) -> Vec<u32> {
+    output
+        .lines()
+        .filter_map(|line| line.trim().parse().ok())
+        .collect()
+}
+
+/// Stop the task and everything it started. `schtasks /End` terminates only
+/// the launcher's `cmd.exe` and would leave `microclaw.exe` running, so the
+/// launcher's process tree is killed first.
+fn end_windows_task() {
+    if let Ok(launcher) = windows_launcher_path() {
+        for pid in windows_launcher_pids(&launcher).unwrap_or_default() {
+            let pid = pid.to_string();
+            let _ = run_command("taskkill", &["/PID", &pid, "/T", "/F"]);
+        }
+    }
+    let _ = run_command("schtasks", &["/End", "/TN", WINDOWS_TASK_NAME]);
+}
+
+fn windows_task_exists() -> Result<bool> {
+    let output = run_command("schtasks", &["/Query", "/TN", WINDOWS_TASK_NAME])?;
+    Ok(output.status.success())
+}
+
+fn install_windows(ctx: &ServiceContext, opts: &InstallOptions) -> Result<()> {
+    assert_command_exists("schtasks")?;
+
+    let launcher_path = windows_launcher_path()?;
+    if windows_task_exists()? && !opts.force {
+        println!(
+            "Gateway service already installed as scheduled task {}. Use --force to reinstall.",
+            WINDOWS_TASK_NAME
+        );
+        return Ok(());
+    }
+
+    let service_dir = windows_service_dir()?;
+    std::fs::create_dir_all(&service_dir)
+        .with_context(|| format!("Failed to create {}", service_dir.display()))?;
+    std::fs::create_dir_all(&ctx.runtime_logs_dir)
+        .with_context(|| format!("Failed to create {}", ctx.runtime_logs_dir.display()))?;
+
+    std::fs::write(&launcher_path, render_windows_launcher(ctx)?)
+        .with_context(|| format!("Failed to write {}", launcher_path.display()))?;
+    let xml_path = service_dir.join(WINDOWS_TASK_XML_FILE);
+    let xml = render_windows_task_xml(ctx, &launcher_path, &windows_task_user()?);
+    std::fs::write(&xml_path, utf16le_with_bom(&xml))
+        .with_context(|| format!("Failed to write {}", xml_path.display()))?;
+
+    let xml_path_str = xml_path.to_string_lossy().to_string();
+    let create_args = [
+        "/Create",
+        "/TN",
+        WINDOWS_TASK_NAME,
+        "/XML",
+        xml_path_str.as_str(),
+        "/F",
+    ];
+    ensure_success(
+        run_command("schtasks", &create_args)?,
+        "schtasks",
+        &create_args,
+    )?;
+
+    end_windows_task();
+    start_windows()?;
+    println!(
+        "Installed and started gateway service: {}",
+        launcher_path.display()
+    );
+    Ok(())
+}
+
+fn uninstall_windows() -> Result<()> {
+    assert_command_exists("schtasks")?;
+
+    end_windows_task();
+    let _ = run_command("schtasks", &["/Delete", "/TN", WINDOWS_TASK_NAME, "/F"]);
+
+    let service_dir = windows_service_dir()?;
+    for file in [WINDOWS_LAUNCHER_FILE, WINDOWS_TASK_XML_FILE] {
+        let path = service_dir.join(file);
+        if path.exists() {
+            std::fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove {}", path.display()))?;
+        }
+    }
+    println!("Uninstalled gateway service");
+    Ok(())
+}
+
+fn start_windows() -> Result<()> {
+    assert_command_exists("schtasks")?;
+    if !windows_task_exists()? {
+        return Err(anyhow!(
+            "Service not installed. Run: microclaw gateway install"
+        ));
+    }
+    ensure_success(
+        run_command("schtasks", &["/Run", "/TN", WINDOWS_TASK_NAME])?,
+        "schtasks",
+        &["/Run", "/TN", WINDOWS_TASK_NAME],
+    )?;
+    println!("Gateway service started");
+    Ok(())
+}
+
+fn stop_windows() -> Result<()> {
+    assert_command_exists("schtasks")?;
+    if !windows_task_exists()? {
+        return Err(anyhow!(
+            "Service not installed. Run: microclaw gateway install"
+        ));
+    }
+    end_windows_task();
+    println!("Gateway service stopped");
+    Ok(())
+}
+
+fn restart_windows() -> Result<()> {
+    assert_command_exists("schtasks")?;
+    end_windows_task();
+    start_windows()?;
+    println!("Gateway service restarted");
+    Ok(())
+}
+
+/// Prints the task state as `key=value` lines. `State` is an enum, so it
+/// reads `Running`, `Ready`, ... whatever the system language.
+fn windows_status_script() -> String {
+    format!(
+        "$ErrorActionPreference = 'Stop'; \
+         $t = Get-ScheduledTask -TaskName '{WINDOWS_TASK_NAME}'; \
+         $i = $t | Get-ScheduledTaskInfo; \
+         \"status=$($t.State)\"; \
+         if ($i.LastRunTime) {{ \"last_run_time=$($i.LastRunTime.ToString('o'))\" }}; \
+         \"last_result=$($i.LastTaskResult)\"; \
+         if ($i.NextRunTime) {{ \"next_run_time=$($i.NextRunTime.ToString('o'))\" }}; \
+         \"task_to_run=$($t.Actions[0].Execute)\""
+    )
+}
 
-    if running {
-        Ok(())
+fn parse_windows_runtime_status(output: &str) -> WindowsRuntimeStatus {
+    let mut status = WindowsRuntimeStatus::default();
+    for line in output.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim().to_string();
+        let slot = match key.trim() {
+            "status" => &mut status.status,
+            "last_run_time" => &mut status.last_run_time,
+            "last_result" => &mut status.last_result,
+            "next_run_time" => &mut status.next_run_time,
+            "task_to_run" => &mut status.task_to_run,
+            _ => continue,
+        };
+        if slot.is_none() {
+            *slot = Some(value);
+        }
+    }
+    status
+}
+
+fn audit_windows_task(ctx: &ServiceContext, runtime: &WindowsRuntimeStatus) -> Vec<String> {
+    let launcher_path = match windows_launcher_path() {
+        Ok(path) => path,
+        Err(err) => {
+            return vec![format!("Unable to resolve gateway launcher path: {}", err)];
+        }
+    };
+
+    let content = match std::fs::read_to_string(&launcher_path) {
+        Ok(c) => c,
+        Err(err) => {
+            return vec![format!(
+                "Failed to read gateway launcher for drift audit ({}): {}",
+                launcher_path.display(),
+                err
+            )]
+        }
+    };
+
+    let mut issues = Vec::new();
+    if let Some(task_to_run) = &runtime.task_to_run {
+        if !task_to_run.contains(&*launcher_path.to_string_lossy()) {
+            issues.push("Scheduled task does not run the gateway launcher".to_string());
+        }
+    }
+
+    let expected_exec = format!("\"{}\" start", ctx.exe_path.display());
+    if !content.contains(&expected_exec) {
+        issues.push("Launcher does not match current microclaw binary".to_string());
+    }
+
+    if let Some(config_path) = &ctx.config_path {
+        let config_kv = format!("MICROCLAW_CONFIG={}", config_path.display());
+        if !content.contains(&config_kv) {
+            issues.push("Launcher MICROCLAW_CONFIG differs from current config path".to_string());
+        }
+    }
+
+    let stdout_path = ctx.runtime_logs_dir.join(LOG_STDOUT_FILE);
+    if !content.contains(&*stdout_path.to_string_lossy()) {
+        issues.push("Launcher log path does not match runtime logs directory".to_string());
+    }
+
+    issues
+}
+
+fn print_windows_status_text(
+    runtime: &WindowsRuntimeStatus,
+    issues: &[String],
+    heartbeat: Option<&HeartbeatHealth>,
+    raw_status: Option<&str>,
+    deep: bool,
+) {
+    println!("Gateway service: windows/task scheduler");
+    println!(
+        "  status: {}",
+        runtime
+            .status
+            .clone()
+            .unwrap_or_else(|| "unknown".to_string())
+    );
+    if let Some(last_run) = &runtime.last_run_time {
+        println!("  last_run_time: {}", last_run);
+    }
+    if let Some(result) = &runtime.last_result {
+        println!("  last_result: {}", result);
+    }
+    if let Some(next_run) = &runtime.next_run_time {
+        println!("  next_run_time: {}", next_run);
+    }
+
+    if issues.is_empty() {
+        println!("  drift_audit: clean");
+    } else {
+        println!("  drift_audit: {} issue(s)", issues.len());
+        for issue in issues {
+            println!("    - {}", issue);
+        }
+    }
+    print_heartbeat_text(heartbeat);
+
+    if deep {
+        if let Some(raw) = raw_status {
+            println!("\n-- Get-ScheduledTask --");
+            println!("{}", raw.trim_end());
+        }
+    }
+}
+
+fn status_windows(ctx: &ServiceContext, opts: &StatusOptions) -> Result<()> {
+    assert_command_exists("powershell")?;
+
+    let output = run_powershell(&windows_status_script())?;
+    let raw = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let runtime = parse_windows_runtime_status(&raw);
+    let issues = audit_windows_task(ctx, &runtime);
+    let running = runtime
+        .status
+        .as_ref()
+        .map(|s| s.eq_ignore_ascii_case("running"))
+        .unwrap_or(false);
+
+    let deep_raw = if opts.deep { Some(raw.clone()) } else { None };
+    let heartbeat = deep_heartbeat(ctx, opts);
+
+    if opts.json {
+        let value = json!({
+            "platform": "windows",
+            "task": WINDOWS_TASK_NAME,
+            "running": running,
+            "status": runtime.status,
+            "last_run_time": runtime.last_run_time,
+            "last_result": runtime.last_result,
+            "next_run_time": runtime.next_run_time,
+            "task_to_run": runtime.task_to_run,
+            "drift_issues": issues,
+            "heartbeat": heartbeat_json(heartbeat.as_ref()),
+            "deep_status": deep_raw,
+        });
+        println!("{}", serde_json::to_string_pretty(&value)?);
     } else {
-        Err(anyhow!("Gateway service is not running"))
+        print_windows_status_text(
+            &runtime,
+            &issues,
+            heartbeat.as_ref(),
+            deep_raw.as_deref(),
+            opts.deep,
+        );
     }
+
+    status_result(running, heartbeat.as_ref())
 }
 
 #[cfg(test)]
@@ -1145,6 +1706,7 @@ mod tests {
             working_dir: PathBuf::from("/tmp/microclaw"),
             config_path: Some(PathBuf::from("/tmp/microclaw/microclaw.config.yaml")),
             runtime_logs_dir: PathBuf::from("/tmp/microclaw/runtime/logs"),
+            heartbeat_path: PathBuf::from("/tmp/microclaw/runtime/heartbeat.json"),
             service_env,
         }
     }
@@ -1193,6 +1755,75 @@ mod tests {
         assert!(normalized.contains("/tmp/microclaw/runtime/logs/microclaw-gateway.error.log"));
     }
 
+    #[test]
+    fn test_render_windows_launcher_contains_expected_fields() {
+        let script = render_windows_launcher(&test_ctx()).unwrap();
+        assert!(script.starts_with("@echo off\r\n"));
+        assert!(script.contains("cd /d \"/tmp/microclaw\""));
+        assert!(script.contains("set \"MICROCLAW_GATEWAY=1\""));
+        assert!(script.contains("set \"MICROCLAW_CONFIG=/tmp/microclaw/microclaw.config.yaml\""));
+        let normalized = script.replace('\\', "/");
+        assert!(normalized.contains(
+            "\"/usr/local/bin/microclaw\" start >> \"/tmp/microclaw/runtime/logs/microclaw-gateway.log\""
+        ));
+        assert!(
+            normalized.contains("2>> \"/tmp/microclaw/runtime/logs/microclaw-gateway.error.log\"")
+        );
+
+        assert_eq!(cmd_escape_value("50%", "v").unwrap(), "50%%");
+        assert!(cmd_escape_value("a\"b", "v").is_err());
+        assert!(cmd_escape_value("a\r\nb", "v").is_err());
+    }
+
+    #[test]
+    fn test_render_windows_task_xml_contains_required_fields() {
+        let xml = render_windows_task_xml(
+            &test_ctx(),
+            Path::new(r"C:\Users\me\AppData\Local\MicroClaw\microclaw-gateway.cmd"),
+            r"HOME-PC\me",
+        );
+        assert!(xml.contains("<LogonTrigger>"));
+        assert!(xml.contains(r"<UserId>HOME-PC\me</UserId>"));
+        assert!(xml.contains("<ExecutionTimeLimit>PT0S</ExecutionTimeLimit>"));
+        assert!(xml.contains("<RestartOnFailure>"));
+        assert!(xml.contains(
+            r"<Command>C:\Users\me\AppData\Local\MicroClaw\microclaw-gateway.cmd</Command>"
+        ));
+        assert_eq!(&utf16le_with_bom("<")[..], &[0xFF, 0xFE, b'<', 0]);
+    }
+
+    #[test]
+    fn test_parse_windows_runtime_status() {
+        let output = "status=Running\r\nlast_run_time=2026-10-16T09:12:03.0000000+02:00\r\nlast_result=267009\r\ntask_to_run=C:\\Users\\me\\AppData\\Local\\MicroClaw\\microclaw-gateway.cmd\r\n";
+        let status = parse_windows_runtime_status(output);
+        assert_eq!(status.status.as_deref(), Some("Running"));
+        assert_eq!(status.next_run_time, None);
+        assert_eq!(
+            status.last_run_time.as_deref(),
+            Some("2026-10-16T09:12:03.0000000+02:00")
+        );
+        assert_eq!(status.last_result.as_deref(), Some("267009"));
+        assert_eq!(
+            status.task_to_run.as_deref(),
+            Some(r"C:\Users\me\AppData\Local\MicroClaw\microclaw-gateway.cmd")
+        );
+
+        let missing = parse_windows_runtime_status(
+            "Get-ScheduledTask : No MSFT_ScheduledTask objects found with property 'TaskName' equal to 'MicroClawGateway'.\r\n",
+        );
+        assert!(missing.status.is_none());
+        assert!(windows_status_script().contains("-TaskName 'MicroClawGateway'"));
+    }
+
+    #[test]
+    fn test_parse_pid_lines() {
+        assert_eq!(
+            parse_pid_lines("4242\r\n\r\n17\r\nnot a pid\r\n"),
+            vec![4242, 17]
+        );
+        assert!(parse_pid_lines("").is_empty());
+    }
+
     #[test]
     fn test_parse_log_lines_default_and_custom() {
         assert_eq!(parse_log_lines(None).unwrap(), DEFAULT_LOG_LINES);
@@ -1246,6 +1877,37 @@ mod tests {
         assert_eq!(status.last_exit_reason.as_deref(), Some("exited"));
     }
 
+    #[test]
+    fn test_status_result_reports_unhealthy_heartbeat() {
+        assert!(status_result(true, None).is_ok());
+        let err = status_result(false, None).unwrap_err().to_string();
+        assert!(err.contains("not running"));
+        let err = status_result(true, Some(&HeartbeatHealth::Invalid("bad json".into())))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("running but unhealthy"), "{err}");
+        let healthy = HeartbeatHealth::Healthy {
+            age_secs: 5,
+            heartbeat: heartbeat::Heartbeat {
+                timestamp: chrono::Utc::now().to_rfc3339(),
+                pid: 1,
+                connected_channels: vec!["web".to_string()],
+            },
+        };
+        assert!(status_result(true, Some(&healthy)).is_ok());
+        assert_eq!(
+            heartbeat_json(Some(&healthy))["connected_channels"][0],
+            "web"
+        );
+    }
+
+    #[test]
+    fn test_status_result_accepts_missing_heartbeat() {
+        // Just after startup no heartbeat has been written yet.
+        assert!(status_result(true, Some(&HeartbeatHealth::Missing)).is_ok());
+        assert!(status_result(false, Some(&HeartbeatHealth::Missing)).is_err());
+    }
+
     #[test]
     fn test_resolve_runtime_logs_dir_fallback() {
         let dir = resolve_runtime_logs_dir(Path::new("/tmp/microclaw"));
@@ -0,0 +1,157 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::runtime::AppState;
+use microclaw_storage::db::call_blocking;
+
+pub const HEARTBEAT_FILE: &str = "heartbeat.json";
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+/// A heartbeat older than this means the process is stuck or gone.
+pub const HEARTBEAT_STALE_AFTER: Duration = Duration::from_secs(90);
+
+/// Written by the running bot to `<runtime_data_dir>/heartbeat.json` so
+/// `gateway status --deep` can tell a live process from a healthy one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Heartbeat {
+    /// RFC 3339 time of the last write.
+    pub timestamp: String,
+    pub pid: u32,
+    pub connected_channels: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HeartbeatHealth {
+    Healthy { age_secs: i64, heartbeat: Heartbeat },
+    Stale { age_secs: i64, heartbeat: Heartbeat },
+    Missing,
+    Invalid(String),
+}
+
+impl HeartbeatHealth {
+    pub fn is_healthy(&self) -> bool {
+        matches!(self, Self::Healthy { .. })
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Healthy { .. } => "healthy",
+            Self::Stale { .. } => "stale",
+            Self::Missing => "missing",
+            Self::Invalid(_) => "invalid",
+        }
+    }
+}
+
+pub fn heartbeat_path(runtime_data_dir: &str) -> PathBuf {
+    PathBuf::from(runtime_data_dir).join(HEARTBEAT_FILE)
+}
+
+fn write_heartbeat(path: &Path, heartbeat: &Heartbeat) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_string_pretty(heartbeat)?;
+    // Write then rename so readers never see a half-written file.
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, json)?;
+    std::fs::rename(&tmp, path)
+}
+
+/// Read the heartbeat at `path` and judge it against `now`.
+pub fn check_heartbeat(path: &Path, now: chrono::DateTime<chrono::Utc>) -> HeartbeatHealth {
+    let raw = match std::fs::read_to_string(path) {
+        Ok(raw) => raw,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return HeartbeatHealth::Missing,
+        Err(e) => return HeartbeatHealth::Invalid(e.to_string()),
+    };
+    let heartbeat: Heartbeat = match serde_json::from_str(&raw) {
+        Ok(heartbeat) => heartbeat,
+        Err(e) => return HeartbeatHealth::Invalid(e.to_string()),
+    };
+    let written_at = match chrono::DateTime::parse_from_rfc3339(&heartbeat.timestamp) {
+        Ok(ts) => ts.with_timezone(&chrono::Utc),
+        Err(e) => return HeartbeatHealth::Invalid(format!("bad timestamp: {e}")),
+    };
+    let age_secs = (now - written_at).num_seconds().max(0);
+    if age_secs as u64 > HEARTBEAT_STALE_AFTER.as_secs() {
+        HeartbeatHealth::Stale {
+            age_secs,
+            heartbeat,
+        }
+    } else {
+        HeartbeatHealth::Healthy {
+            age_secs,
+            heartbeat,
+        }
+    }
+}
+
+pub fn spawn_heartbeat(state: Arc<AppState>) {
+    let path = heartbeat_path(&state.config.load().runtime_data_dir());
+    tokio::spawn(async move {
+        info!(
+            "Heartbeat started ({}, every {}s)",
+            path.display(),
+            HEARTBEAT_INTERVAL.as_secs()
+        );
+        loop {
+            // A database that errors or stays locked holds the heartbeat back,
+            // so the file goes stale instead of reporting a wedged process
+            // as healthy.
+            match call_blocking(state.db.clone(), |db| db.ping()).await {
+                Ok(()) => {
+                    let heartbeat = Heartbeat {
+                        timestamp: chrono::Utc::now().to_rfc3339(),
+                        pid: std::process::id(),
+                        connected_channels: crate::channels::startup_guard::connected_channels(),
+                    };
+                    if let Err(e) = write_heartbeat(&path, &heartbeat) {
+                        warn!("Failed to write heartbeat {}: {e}", path.display());
+                    }
+                }
+                Err(e) => warn!("Skipping heartbeat: database check failed: {e}"),
+            }
+            tokio::time::sleep(HEARTBEAT_INTERVAL).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_heartbeat_ages() {
+        let dir = std::env::temp_dir().join(format!("mc_heartbeat_{}", uuid::Uuid::new_v4()));
+        let path = dir.join(HEARTBEAT_FILE);
+        let now = chrono::Utc::now();
+        assert_eq!(check_heartbeat(&path, now), HeartbeatHealth::Missing);
+
+        let heartbeat = Heartbeat {
+            timestamp: (now - chrono::Duration::seconds(10)).to_rfc3339(),
+            pid: 42,
+            connected_channels: vec!["telegram".into()],
+        };
+        write_heartbeat(&path, &heartbeat).unwrap();
+        assert_eq!(
+            check_heartbeat(&path, now),
+            HeartbeatHealth::Healthy {
+                age_secs: 10,
+                heartbeat: heartbeat.clone()
+            }
+        );
+        let later = now + chrono::Duration::seconds(300);
+        assert!(matches!(
+            check_heartbeat(&path, later),
+            HeartbeatHealth::Stale { age_secs: 310, .. }
+        ));
+
+        std::fs::write(&path, "not json").unwrap();
+        assert_eq!(check_heartbeat(&path, now).label(), "invalid");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -17,6 +17,7 @@ use crate::tools::ToolResult;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum HookEvent {
+    InboundMessage,
     BeforeLLMCall,
     BeforeToolCall,
     AfterToolCall,
@@ -25,6 +26,7 @@ pub enum HookEvent {
 impl HookEvent {
     fn as_str(self) -> &'static str {
         match self {
+            HookEvent::InboundMessage => "InboundMessage",
             HookEvent::BeforeLLMCall => "BeforeLLMCall",
             HookEvent::BeforeToolCall => "BeforeToolCall",
             HookEvent::AfterToolCall => "AfterToolCall",
@@ -33,6 +35,7 @@ impl HookEvent {
 
     fn from_str(v: &str) -> Option<Self> {
         match v.trim() {
+            "InboundMessage" => Some(HookEvent::InboundMessage),
             "BeforeLLMCall" => Some(HookEvent::BeforeLLMCall),
             "BeforeToolCall" => Some(HookEvent::BeforeToolCall),
             "AfterToolCall" => Some(HookEvent::AfterToolCall),
@@ -322,6 +325,45 @@ impl HookManager {
         .await;
     }
 
+    /// Let `InboundMessage` hooks rewrite an incoming user message before the run.
+    /// Hook errors, timeouts and blocks leave the text untouched.
+    pub async fn transform_inbound(
+        &self,
+        chat_id: i64,
+        caller_channel: &str,
+        sender_name: &str,
+        text: &str,
+    ) -> String {
+        let outcome = self
+            .run(
+                HookEvent::InboundMessage,
+                json!({
+                    "event": HookEvent::InboundMessage.as_str(),
+                    "chat_id": chat_id,
+                    "caller_channel": caller_channel,
+                    "sender_name": sender_name,
+                    "text": text
+                }),
+            )
+            .await;
+        match outcome {
+            Ok(HookOutcome::Allow { patches }) => patches
+                .iter()
+                .rev()
+                .find_map(|p| p.get("text").and_then(|v| v.as_str()))
+                .map(str::to_string)
+                .unwrap_or_else(|| text.to_string()),
+            Ok(HookOutcome::Block { reason }) => {
+                warn!("InboundMessage hooks cannot block messages; ignoring: {reason}");
+                text.to_string()
+            }
+            Err(e) => {
+                warn!("InboundMessage hooks failed: {e}");
+                text.to_string()
+            }
+        }
+    }
+
     pub async fn run_before_llm(
         &self,
         chat_id: i64,
@@ -763,4 +805,85 @@ fi
             _ => panic!("expected allow after disable"),
         }
     }
+
+    #[cfg(not(windows))]
+    fn write_inbound_hook(hooks_dir: &Path, name: &str, script: &str, timeout_ms: u64) {
+        let hook_dir = hooks_dir.join(name);
+        std::fs::create_dir_all(&hook_dir).unwrap();
+        std::fs::write(
+            hook_dir.join("HOOK.md"),
+            format!(
+                r#"---
+name: {name}
+description: inbound test
+events: [InboundMessage]
+command: "sh hook.sh"
+enabled: true
+timeout_ms: {timeout_ms}
+---
+"#
+            ),
+        )
+        .unwrap();
+        std::fs::write(hook_dir.join("hook.sh"), script).unwrap();
+    }
+
+    #[cfg(not(windows))]
+    #[tokio::test]
+    async fn test_transform_inbound_applies_text_patch() {
+        let root = std::env::temp_dir().join(format!("hook_inbound_{}", uuid::Uuid::new_v4()));
+        let hooks_dir = root.join("hooks");
+        write_inbound_hook(
+            &hooks_dir,
+            "expand-shortcut",
+            r#"#!/bin/sh
+payload="$(cat)"
+if echo "$payload" | grep -q '"text":"tl;dr"'; then
+  echo '{"action":"modify","patch":{"text":"Please summarize briefly."}}'
+else
+  echo '{"action":"allow"}'
+fi
+"#,
+            2000,
+        );
+        let manager =
+            HookManager::from_test_paths(hooks_dir, root.join("runtime/hooks_state.json"));
+
+        let transformed = manager
+            .transform_inbound(1, "telegram", "alice", "tl;dr")
+            .await;
+        assert_eq!(transformed, "Please summarize briefly.");
+        let untouched = manager
+            .transform_inbound(1, "telegram", "alice", "hello")
+            .await;
+        assert_eq!(untouched, "hello");
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[cfg(not(windows))]
+    #[tokio::test]
+    async fn test_transform_inbound_passes_through_on_failure_and_timeout() {
+        let root = std::env::temp_dir().join(format!("hook_inbound_{}", uuid::Uuid::new_v4()));
+        let hooks_dir = root.join("hooks");
+        write_inbound_hook(
+            &hooks_dir,
+            "broken",
+            "#!/bin/sh\necho boom >&2\nexit 1\n",
+            2000,
+        );
+        write_inbound_hook(
+            &hooks_dir,
+            "slow",
+            "#!/bin/sh\nsleep 2\necho '{\"action\":\"modify\",\"patch\":{\"text\":\"late\"}}'\n",
+            50,
+        );
+        let manager =
+            HookManager::from_test_paths(hooks_dir, root.join("runtime/hooks_state.json"));
+
+        let text = manager
+            .transform_inbound(1, "discord", "bob", "keep me as-is")
+            .await;
+        assert_eq!(text, "keep me as-is");
+        let _ = std::fs::remove_dir_all(&root);
+    }
 }
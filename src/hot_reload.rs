@@ -0,0 +1,340 @@
+use std::sync::{Arc, RwLock};
+
+use serde_json::Value;
+use tracing::{info, warn};
+
+use crate::config::Config;
+use crate::runtime::AppState;
+
+/// Top-level config fields a SIGHUP applies to the running bot. Telegram's
+/// per-channel and per-account `allowed_groups` reload with `allowed_groups`.
+pub const HOT_RELOAD_FIELDS: &[&str] = &[
+    "model",
+    "max_tokens",
+    "max_tool_iterations",
+    "max_history_messages",
+    "show_thinking",
+    "allowed_groups",
+    "system_prompt_prepend",
+    "system_prompt_append",
+    "system_prompt_file",
+    "event_webhook_url",
+    "event_webhook_secret",
+    "event_webhook_events",
+    "event_webhook_max_retries",
+];
+
+/// Fields the LLM provider captures when it is built.
+const PROVIDER_FIELDS: &[&str] = &["model", "max_tokens", "show_thinking"];
+
+/// Fields the event webhook captures when it is built.
+const EVENT_WEBHOOK_FIELDS: &[&str] = &[
+    "event_webhook_url",
+    "event_webhook_secret",
+    "event_webhook_events",
+    "event_webhook_max_retries",
+];
+
+/// A value that handlers read as a snapshot and a reload can replace.
+pub struct Live<T: ?Sized> {
+    current: RwLock<Arc<T>>,
+}
+
+impl<T: ?Sized> Live<T> {
+    pub fn new(value: Arc<T>) -> Self {
+        Self {
+            current: RwLock::new(value),
+        }
+    }
+
+    /// The current snapshot. Hold on to it for a whole run so every read
+    /// within the run sees the same values.
+    pub fn load(&self) -> Arc<T> {
+        self.current
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    pub fn store(&self, value: Arc<T>) {
+        *self.current.write().unwrap_or_else(|e| e.into_inner()) = value;
+    }
+}
+
+impl<T> From<T> for Live<T> {
+    fn from(value: T) -> Self {
+        Self::new(Arc::new(value))
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct ReloadReport {
+    /// Hot-reloadable fields whose value changed.
+    pub applied: Vec<String>,
+    /// Changed fields that keep their old value until a restart.
+    pub requires_restart: Vec<String>,
+}
+
+/// Build the config to run with after a reload: `current` with the
+/// hot-reloadable fields taken from `loaded`.
+pub fn merge_hot_fields(current: &Config, loaded: &Config) -> (Config, ReloadReport) {
+    let mut next = current.clone();
+    let mut applied = Vec::new();
+    macro_rules! take {
+        ($($field:ident),*) => {$(
+            if next.$field != loaded.$field {
+                next.$field = loaded.$field.clone();
+                applied.push(stringify!($field).to_string());
+            }
+        )*};
+    }
+    take!(
+        model,
+        max_tokens,
+        max_tool_iterations,
+        max_history_messages,
+        show_thinking,
+        allowed_groups,
+        system_prompt_prepend,
+        system_prompt_append,
+        system_prompt_file,
+        event_webhook_url,
+        event_webhook_secret,
+        event_webhook_events,
+        event_webhook_max_retries
+    );
+    if take_telegram_allowed_groups(&mut next, loaded)
+        && !applied.iter().any(|f| f == "allowed_groups")
+    {
+        applied.push("allowed_groups".to_string());
+    }
+
+    let requires_restart = changed_keys(&next, loaded);
+    (
+        next,
+        ReloadReport {
+            applied,
+            requires_restart,
+        },
+    )
+}
+
+/// Copy `allowed_groups` of the telegram channel and its accounts from
+/// `loaded`, leaving every other telegram setting as it is.
+fn take_telegram_allowed_groups(next: &mut Config, loaded: &Config) -> bool {
+    let (Some(target), Some(source)) = (
+        next.channels.get_mut("telegram"),
+        loaded.channels.get("telegram"),
+    ) else {
+        return false;
+    };
+    let key = serde_yaml::Value::from("allowed_groups");
+    let mut changed = copy_yaml_key(target, source, &key);
+    let accounts = serde_yaml::Value::from("accounts");
+    if let (Some(target_accounts), Some(source_accounts)) = (
+        target
+            .as_mapping_mut()
+            .and_then(|m| m.get_mut(&accounts))
+            .and_then(|v| v.as_mapping_mut()),
+        source
+            .as_mapping()
+            .and_then(|m| m.get(&accounts))
+            .and_then(|v| v.as_mapping()),
+    ) {
+        for (account_id, target_account) in target_accounts.iter_mut() {
+            if let Some(source_account) = source_accounts.get(account_id) {
+                changed |= copy_yaml_key(target_account, source_account, &key);
+            }
+        }
+    }
+    changed
+}
+
+fn copy_yaml_key(
+    target: &mut serde_yaml::Value,
+    source: &serde_yaml::Value,
+    key: &serde_yaml::Value,
+) -> bool {
+    let Some(target) = target.as_mapping_mut() else {
+        return false;
+    };
+    let new_value = source.as_mapping().and_then(|m| m.get(key)).cloned();
+    if target.get(key) == new_value.as_ref() {
+        return false;
+    }
+    match new_value {
+        Some(value) => target.insert(key.clone(), value),
+        None => target.remove(key),
+    };
+    true
+}
+
+/// Top-level keys (and `channels.<name>`) that differ between two configs.
+fn changed_keys(a: &Config, b: &Config) -> Vec<String> {
+    let (Ok(Value::Object(a)), Ok(Value::Object(b))) =
+        (serde_json::to_value(a), serde_json::to_value(b))
+    else {
+        return Vec::new();
+    };
+    let mut keys: Vec<&String> = a.keys().chain(b.keys()).collect();
+    keys.sort();
+    keys.dedup();
+
+    let mut changed = Vec::new();
+    for key in keys {
+        let (left, right) = (a.get(key), b.get(key));
+        if left == right {
+            continue;
+        }
+        match (left, right) {
+            (Some(Value::Object(left)), Some(Value::Object(right))) if key == "channels" => {
+                let mut names: Vec<&String> = left.keys().chain(right.keys()).collect();
+                names.sort();
+                names.dedup();
+                changed.extend(
+                    names
+                        .into_iter()
+                        .filter(|name| left.get(*name) != right.get(*name))
+                        .map(|name| format!("channels.{name}")),
+                );
+            }
+            _ => changed.push(key.clone()),
+        }
+    }
+    changed
+}
+
+/// Re-read the config file and apply the hot-reloadable fields.
+pub fn reload_config(state: &AppState) {
+    let loaded = match Config::load() {
+        Ok(config) => config,
+        Err(e) => {
+            warn!("Config reload failed, keeping the current config: {e}");
+            return;
+        }
+    };
+    let current = state.config.load();
+    let (next, report) = merge_hot_fields(&current, &loaded);
+    if report
+        .applied
+        .iter()
+        .any(|field| PROVIDER_FIELDS.contains(&field.as_str()))
+    {
+        state
+            .llm
+            .store(Arc::from(crate::llm::create_provider(&next)));
+    }
+    if report
+        .applied
+        .iter()
+        .any(|field| EVENT_WEBHOOK_FIELDS.contains(&field.as_str()))
+    {
+        state
+            .event_webhook
+            .store(Arc::new(crate::event_webhook::EventWebhook::from_config(
+                &next,
+            )));
+    }
+    state.config.store(Arc::new(next));
+
+    info!(
+        "Config reloaded. Hot-reloadable fields: {}. Changed: {}",
+        HOT_RELOAD_FIELDS.join(", "),
+        if report.applied.is_empty() {
+            "none".to_string()
+        } else {
+            report.applied.join(", ")
+        }
+    );
+    for field in &report.requires_restart {
+        warn!("Config field `{field}` changed but requires restart; keeping the old value");
+    }
+}
+
+/// Reload the config whenever the process receives SIGHUP.
+#[cfg(unix)]
+pub fn spawn_sighup_reload(state: Arc<AppState>) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut hangup = match signal(SignalKind::hangup()) {
+        Ok(hangup) => hangup,
+        Err(e) => {
+            warn!("Failed to install SIGHUP handler, config hot-reload is off: {e}");
+            return;
+        }
+    };
+    tokio::spawn(async move {
+        while hangup.recv().await.is_some() {
+            info!("SIGHUP received, reloading config");
+            reload_config(&state);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_telegram(config: &mut Config, yaml: &str) {
+        config
+            .channels
+            .insert("telegram".into(), serde_yaml::from_str(yaml).unwrap());
+    }
+
+    #[test]
+    fn test_merge_applies_hot_fields_and_reports_the_rest() {
+        let mut current = Config::test_defaults();
+        with_telegram(
+            &mut current,
+            "{ enabled: true, bot_token: old, allowed_groups: [1] }",
+        );
+        let mut loaded = current.clone();
+        loaded.model = "new-model".into();
+        loaded.max_tool_iterations = current.max_tool_iterations + 5;
+        loaded.show_thinking = !current.show_thinking;
+        loaded.web_port = current.web_port + 1;
+        loaded.event_webhook_url = Some("https://hooks.example.com/mc".into());
+        with_telegram(
+            &mut loaded,
+            "{ enabled: true, bot_token: new, allowed_groups: [1, 2] }",
+        );
+
+        let (next, report) = merge_hot_fields(&current, &loaded);
+        assert_eq!(next.model, "new-model");
+        assert_eq!(next.max_tool_iterations, loaded.max_tool_iterations);
+        assert_eq!(next.show_thinking, loaded.show_thinking);
+        assert_eq!(next.web_port, current.web_port);
+        assert_eq!(
+            next.event_webhook_url.as_deref(),
+            Some("https://hooks.example.com/mc")
+        );
+        let telegram = &next.channels["telegram"];
+        assert_eq!(telegram["bot_token"].as_str(), Some("old"));
+        assert_eq!(telegram["allowed_groups"].as_sequence().unwrap().len(), 2);
+        assert_eq!(
+            report.applied,
+            vec![
+                "model",
+                "max_tool_iterations",
+                "show_thinking",
+                "event_webhook_url",
+                "allowed_groups"
+            ]
+        );
+        assert_eq!(
+            report.requires_restart,
+            vec!["channels.telegram", "web_port"]
+        );
+
+        let (_, unchanged) = merge_hot_fields(&next, &next);
+        assert_eq!(unchanged, ReloadReport::default());
+    }
+
+    #[test]
+    fn test_live_store_replaces_snapshot() {
+        let live = Live::from(1);
+        let before = live.load();
+        live.store(Arc::new(2));
+        assert_eq!((*before, *live.load()), (1, 2));
+    }
+}
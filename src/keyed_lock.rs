@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::sync::Mutex;
+
+struct LockEntry {
+    lock: Arc<Mutex<()>>,
+    last_touch: Instant,
+}
+
+/// One async mutex per key (a web session, a chat), created on first use and
+/// dropped again once it has been idle for a while.
+pub(crate) struct KeyedLocks<K> {
+    locks: Mutex<HashMap<K, LockEntry>>,
+}
+
+impl<K> Default for KeyedLocks<K> {
+    fn default() -> Self {
+        Self {
+            locks: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<K: Eq + Hash + Clone> KeyedLocks<K> {
+    /// The lock for `key`. Other keys idle for longer than `idle_ttl` are
+    /// pruned on the way.
+    pub(crate) async fn lock_for(&self, key: &K, idle_ttl: Duration) -> Arc<Mutex<()>> {
+        let now = Instant::now();
+        let mut guard = self.locks.lock().await;
+        guard.retain(|k, entry| {
+            if k == key {
+                return true;
+            }
+            let stale = now.duration_since(entry.last_touch) > idle_ttl;
+            // Remove only stale + uncontended locks.
+            !(stale && Arc::strong_count(&entry.lock) == 1 && entry.lock.try_lock().is_ok())
+        });
+        guard
+            .entry(key.clone())
+            .and_modify(|entry| entry.last_touch = now)
+            .or_insert_with(|| LockEntry {
+                lock: Arc::new(Mutex::new(())),
+                last_touch: now,
+            })
+            .lock
+            .clone()
+    }
+
+    #[cfg(test)]
+    pub(crate) async fn len(&self) -> usize {
+        self.locks.lock().await.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_same_key_shares_lock_and_idle_keys_are_pruned() {
+        let locks = KeyedLocks::default();
+        let a = locks
+            .lock_for(&"a".to_string(), Duration::from_secs(60))
+            .await;
+        let again = locks
+            .lock_for(&"a".to_string(), Duration::from_secs(60))
+            .await;
+        assert!(Arc::ptr_eq(&a, &again));
+        drop((a, again));
+
+        let held = locks.lock_for(&"b".to_string(), Duration::ZERO).await;
+        let _guard = held.lock().await;
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        let _c = locks.lock_for(&"c".to_string(), Duration::ZERO).await;
+        // "a" was idle and unused; "b" is still held.
+        assert_eq!(locks.len().await, 2);
+    }
+}
@@ -1,18 +1,32 @@
 pub mod agent_engine;
+pub mod channel_readiness;
 pub mod channels;
 pub mod chat_commands;
+pub mod chat_rate_limit;
+pub mod chat_run_queue;
 pub mod clawhub;
 pub mod codex_auth;
 pub mod config;
 pub mod doctor;
 pub mod embedding;
+pub mod event_webhook;
 pub mod gateway;
+pub mod heartbeat;
 pub mod hooks;
+pub mod hot_reload;
+pub(crate) mod keyed_lock;
 pub mod llm;
 pub mod mcp;
 pub mod memory_backend;
+pub mod ocr;
 pub mod otlp;
+#[cfg(feature = "otel-tracing")]
+pub mod otlp_tracing;
+pub(crate) mod pending_actions;
 pub mod plugins;
+pub mod provider_health;
+pub mod redact;
+pub(crate) mod retry_budget;
 pub(crate) mod run_control;
 pub mod runtime;
 pub mod scheduler;
@@ -20,6 +34,7 @@ pub mod setup;
 pub mod setup_def;
 pub mod skills;
 pub mod tools;
+pub mod voice;
 pub mod web;
 
 pub use channels::discord;
@@ -39,7 +54,14 @@ pub use microclaw_tools::sandbox;
 
 #[cfg(test)]
 pub mod test_support {
-    use std::sync::{Mutex, MutexGuard, OnceLock};
+    use std::path::Path;
+    use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+
+    use crate::config::{Config, WorkingDirIsolation};
+    use crate::llm::LlmProvider;
+    use crate::runtime::AppState;
+    use microclaw_channels::channel_adapter::ChannelRegistry;
+    use microclaw_storage::db::Database;
 
     pub fn env_lock() -> MutexGuard<'static, ()> {
         static ENV_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
@@ -48,4 +70,54 @@ pub mod test_support {
             .lock()
             .expect("env lock poisoned")
     }
+
+    /// An `AppState` rooted in `base_dir` with only the web channel registered.
+    pub fn app_state(
+        base_dir: &Path,
+        llm: Box<dyn LlmProvider>,
+        configure: impl FnOnce(&mut Config),
+    ) -> Arc<AppState> {
+        let runtime_dir = base_dir.join("runtime");
+        std::fs::create_dir_all(&runtime_dir).unwrap();
+        let mut cfg = Config::test_defaults();
+        cfg.data_dir = base_dir.to_string_lossy().to_string();
+        cfg.working_dir = base_dir.join("tmp").to_string_lossy().to_string();
+        cfg.working_dir_isolation = WorkingDirIsolation::Shared;
+        cfg.web_port = 3900;
+        configure(&mut cfg);
+        let db = Arc::new(Database::new(runtime_dir.to_str().unwrap()).unwrap());
+        let memory_backend = Arc::new(crate::memory_backend::MemoryBackend::local_only(db.clone()));
+        let mut registry = ChannelRegistry::new();
+        registry.register(Arc::new(crate::web::WebAdapter));
+        let channel_registry = Arc::new(registry);
+        let live_config = Arc::new(crate::hot_reload::Live::from(cfg.clone()));
+        Arc::new(AppState {
+            config: live_config.clone(),
+            channel_registry: channel_registry.clone(),
+            db: db.clone(),
+            memory: crate::memory::MemoryManager::new(runtime_dir.to_str().unwrap()),
+            skills: crate::skills::SkillManager::from_skills_dir(&cfg.skills_data_dir()),
+            hooks: Arc::new(crate::hooks::HookManager::from_config(&cfg)),
+            llm: crate::hot_reload::Live::new(Arc::from(llm)),
+            provider_health: Arc::new(crate::provider_health::ProviderHealth::from_config(&cfg)),
+            llm_provider_overrides: Arc::new(tokio::sync::RwLock::new(
+                std::collections::HashMap::new(),
+            )),
+            llm_model_overrides: Arc::new(tokio::sync::RwLock::new(
+                std::collections::HashMap::new(),
+            )),
+            embedding: None,
+            memory_backend: memory_backend.clone(),
+            tools: crate::tools::ToolRegistry::new(
+                live_config,
+                channel_registry,
+                db,
+                memory_backend,
+            ),
+            mcp_manager: Arc::new(crate::mcp::McpManager::default()),
+            event_webhook: None.into(),
+            chat_rate_limiter: Arc::new(crate::chat_rate_limit::ChatRateLimiter::from_config(&cfg)),
+            chat_run_queue: Arc::new(crate::chat_run_queue::ChatRunQueue::default()),
+        })
+    }
 }
@@ -18,7 +18,7 @@ use crate::config::WorkingDirIsolation;
 use microclaw_core::error::MicroClawError;
 use microclaw_core::llm_types::{
     ContentBlock, ImageSource, Message, MessageContent, MessagesRequest, MessagesResponse,
-    ResponseContentBlock, ToolDefinition, Usage,
+    ResponseContentBlock, ServedBy, ToolDefinition, Usage,
 };
 
 /// Remove invalid `ToolResult` blocks that cannot be matched to the most recent
@@ -104,14 +104,16 @@ fn sanitize_messages(messages: Vec<Message>) -> Vec<Message> {
     sanitized
 }
 
+/// Incremental parser for `text/event-stream` bodies; yields each event's
+/// joined `data:` lines.
 #[derive(Default)]
-struct SseEventParser {
+pub(crate) struct SseEventParser {
     pending: String,
     data_lines: Vec<String>,
 }
 
 impl SseEventParser {
-    fn push_chunk(&mut self, chunk: &str) -> Vec<String> {
+    pub(crate) fn push_chunk(&mut self, chunk: &str) -> Vec<String> {
         self.pending.push_str(chunk);
         let mut events = Vec::new();
 
@@ -129,7 +131,7 @@ impl SseEventParser {
         events
     }
 
-    fn finish(&mut self) -> Vec<String> {
+    pub(crate) fn finish(&mut self) -> Vec<String> {
         let mut events = Vec::new();
         if !self.pending.is_empty() {
             let mut line = std::mem::take(&mut self.pending);
@@ -233,12 +235,377 @@ pub trait LlmProvider: Send + Sync {
 }
 
 pub fn create_provider(config: &Config) -> Box<dyn LlmProvider> {
+    let primary = create_single_provider(config);
+    if config.llm_fallbacks.is_empty() {
+        return primary;
+    }
+    let fallbacks = config
+        .llm_fallbacks
+        .iter()
+        .map(|fallback| {
+            let sub_config = fallback_config(config, fallback);
+            FallbackEntry {
+                label: format!("{}/{}", sub_config.llm_provider, sub_config.model),
+                served_by: ServedBy {
+                    provider: sub_config.llm_provider.clone(),
+                    model: sub_config.model.clone(),
+                },
+                provider: create_single_provider(&sub_config),
+            }
+        })
+        .collect();
+    Box::new(FallbackProvider {
+        primary_label: format!("{}/{}", config.llm_provider, config.model),
+        primary,
+        fallbacks,
+    })
+}
+
+fn create_single_provider(config: &Config) -> Box<dyn LlmProvider> {
     match config.llm_provider.trim().to_lowercase().as_str() {
         "anthropic" => Box::new(AnthropicProvider::new(config)),
+        "gemini" => Box::new(GeminiProvider::new(config)),
         _ => Box::new(OpenAiProvider::new(config)),
     }
 }
 
+// ---------------------------------------------------------------------------
+// Fallback chain
+// ---------------------------------------------------------------------------
+
+/// The primary config with the provider settings of `fallback` applied.
+/// Credentials and base URL carry over only when the provider is the same.
+fn fallback_config(config: &Config, fallback: &crate::config::LlmFallback) -> Config {
+    let same_provider = fallback.provider == config.llm_provider.trim().to_lowercase();
+    let mut sub_config = config.clone();
+    sub_config.llm_fallbacks.clear();
+    sub_config.llm_provider = fallback.provider.clone();
+    sub_config.model = fallback.model.clone();
+    sub_config.api_key = match (&fallback.api_key, same_provider) {
+        (Some(key), _) => key.clone(),
+        (None, true) => config.api_key.clone(),
+        (None, false) => String::new(),
+    };
+    sub_config.llm_base_url = match (&fallback.llm_base_url, same_provider) {
+        (Some(url), _) => Some(url.clone()),
+        (None, true) => config.llm_base_url.clone(),
+        (None, false) => None,
+    };
+    sub_config
+}
+
+/// Whether a provider error is worth retrying on another provider: rate
+/// limits, overload, transient gateway errors and network failures. Errors
+/// about the request itself (bad input, auth) would fail there too.
+fn is_retryable_llm_error(err: &MicroClawError) -> bool {
+    match err {
+        MicroClawError::RateLimited => true,
+        MicroClawError::Http(e) => is_retryable_request_error(e),
+        MicroClawError::LlmApi(message) => {
+            if let Some(status) = message
+                .strip_prefix("HTTP ")
+                .and_then(|rest| rest.get(..3))
+                .and_then(|code| code.parse::<u16>().ok())
+                .and_then(|code| reqwest::StatusCode::from_u16(code).ok())
+            {
+                return is_retryable_status(status);
+            }
+            let lower = message.to_lowercase();
+            [
+                "overloaded",
+                "rate_limit",
+                "rate limit",
+                "resource_exhausted",
+                "unavailable",
+            ]
+            .iter()
+            .any(|marker| lower.contains(marker))
+        }
+        _ => false,
+    }
+}
+
+/// Whether a provider error says the provider itself is unhealthy (retryable
+/// failures, any 5xx, or a rejected key), as opposed to a problem with one
+/// particular request.
+pub(crate) fn is_provider_health_failure(err: &MicroClawError) -> bool {
+    if is_retryable_llm_error(err) {
+        return true;
+    }
+    let MicroClawError::LlmApi(message) = err else {
+        return false;
+    };
+    if ["HTTP 5", "HTTP 401", "HTTP 403"]
+        .iter()
+        .any(|prefix| message.starts_with(prefix))
+    {
+        return true;
+    }
+    // Providers that return a JSON error body are reported as
+    // "{error_type}: {message}" (Anthropic) or "{status}: {message}" (Gemini).
+    let error_type = message.split(':').next().unwrap_or_default().trim();
+    matches!(
+        error_type,
+        "authentication_error"
+            | "permission_error"
+            | "api_error"
+            | "overloaded_error"
+            | "UNAUTHENTICATED"
+            | "PERMISSION_DENIED"
+            | "INTERNAL"
+    )
+}
+
+struct FallbackEntry {
+    label: String,
+    served_by: ServedBy,
+    provider: Box<dyn LlmProvider>,
+}
+
+/// Sends to the primary provider and, when it fails with a retryable error
+/// after its own retries, to each of `llm_fallbacks` in turn. Fallbacks use
+/// their own model and ignore per-call model overrides, and tag their
+/// responses with `served_by` so usage is logged against them.
+struct FallbackProvider {
+    primary_label: String,
+    primary: Box<dyn LlmProvider>,
+    fallbacks: Vec<FallbackEntry>,
+}
+
+impl FallbackProvider {
+    async fn send(
+        &self,
+        system: &str,
+        messages: Vec<Message>,
+        tools: Option<Vec<ToolDefinition>>,
+        model_override: Option<&str>,
+    ) -> Result<MessagesResponse, MicroClawError> {
+        let mut result = self
+            .primary
+            .send_message_with_model(system, messages.clone(), tools.clone(), model_override)
+            .await;
+        let mut failed_label = &self.primary_label;
+        for fallback in &self.fallbacks {
+            match &result {
+                Err(e) if is_retryable_llm_error(e) => {
+                    warn!(
+                        "LLM {failed_label} failed ({e}); falling back to {}",
+                        fallback.label
+                    );
+                }
+                _ => break,
+            }
+            result = fallback
+                .provider
+                .send_message_with_model(
+                    system,
+                    messages.clone(),
+                    tools.clone(),
+                    Some(&fallback.served_by.model),
+                )
+                .await
+                .map(|mut response| {
+                    response.served_by = Some(fallback.served_by.clone());
+                    response
+                });
+            failed_label = &fallback.label;
+        }
+        result
+    }
+
+    /// Like `send`, but only fails over while nothing has been streamed to
+    /// `text_tx`; a partial answer cannot be taken back.
+    async fn send_stream(
+        &self,
+        system: &str,
+        messages: Vec<Message>,
+        tools: Option<Vec<ToolDefinition>>,
+        text_tx: Option<&UnboundedSender<String>>,
+        model_override: Option<&str>,
+    ) -> Result<MessagesResponse, MicroClawError> {
+        let Some(text_tx) = text_tx else {
+            return self.send(system, messages, tools, model_override).await;
+        };
+        let attempts = std::iter::once((&self.primary_label, &self.primary, None)).chain(
+            self.fallbacks
+                .iter()
+                .map(|f| (&f.label, &f.provider, Some(&f.served_by))),
+        );
+        let mut last_err = None;
+        for (label, provider, served_by) in attempts {
+            let model = served_by.map_or(model_override, |s| Some(s.model.as_str()));
+            if let Some((failed_label, e)) = last_err.take() {
+                warn!("LLM {failed_label} failed ({e}); falling back to {label}");
+            }
+            let (llm_tx, mut llm_rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+            let forward_tx = text_tx.clone();
+            let forward_handle = tokio::spawn(async move {
+                let mut forwarded = false;
+                while let Some(delta) = llm_rx.recv().await {
+                    forwarded = true;
+                    let _ = forward_tx.send(delta);
+                }
+                forwarded
+            });
+            let result = provider
+                .send_message_stream_with_model(
+                    system,
+                    messages.clone(),
+                    tools.clone(),
+                    Some(&llm_tx),
+                    model,
+                )
+                .await;
+            drop(llm_tx);
+            let forwarded_any = forward_handle.await.unwrap_or(true);
+            match result {
+                Err(e) if !forwarded_any && is_retryable_llm_error(&e) => {
+                    last_err = Some((label, e));
+                }
+                Ok(mut response) => {
+                    response.served_by = served_by.cloned();
+                    return Ok(response);
+                }
+                other => return other,
+            }
+        }
+        match last_err {
+            Some((_, e)) => Err(e),
+            None => unreachable!("the primary provider is always attempted"),
+        }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for FallbackProvider {
+    async fn send_message(
+        &self,
+        system: &str,
+        messages: Vec<Message>,
+        tools: Option<Vec<ToolDefinition>>,
+    ) -> Result<MessagesResponse, MicroClawError> {
+        self.send(system, messages, tools, None).await
+    }
+
+    async fn send_message_with_model(
+        &self,
+        system: &str,
+        messages: Vec<Message>,
+        tools: Option<Vec<ToolDefinition>>,
+        model_override: Option<&str>,
+    ) -> Result<MessagesResponse, MicroClawError> {
+        self.send(system, messages, tools, model_override).await
+    }
+
+    async fn send_message_stream(
+        &self,
+        system: &str,
+        messages: Vec<Message>,
+        tools: Option<Vec<ToolDefinition>>,
+        text_tx: Option<&UnboundedSender<String>>,
+    ) -> Result<MessagesResponse, MicroClawError> {
+        self.send_stream(system, messages, tools, text_tx, None)
+            .await
+    }
+
+    async fn send_message_stream_with_model(
+        &self,
+        system: &str,
+        messages: Vec<Message>,
+        tools: Option<Vec<ToolDefinition>>,
+        text_tx: Option<&UnboundedSender<String>>,
+        model_override: Option<&str>,
+    ) -> Result<MessagesResponse, MicroClawError> {
+        self.send_stream(system, messages, tools, text_tx, model_override)
+            .await
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Retries
+// ---------------------------------------------------------------------------
+
+const LLM_RETRY_BASE_DELAY: std::time::Duration = std::time::Duration::from_secs(1);
+const LLM_RETRY_MAX_DELAY: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Rate limits, overload (Anthropic's 529) and transient gateway errors.
+fn is_retryable_status(status: reqwest::StatusCode) -> bool {
+    matches!(status.as_u16(), 429 | 500 | 502 | 503 | 529)
+}
+
+fn is_retryable_request_error(err: &reqwest::Error) -> bool {
+    err.is_timeout() || err.is_connect()
+}
+
+/// Exponential backoff for retry `attempt` (1-based) with up to 50% random
+/// jitter, so concurrent runs that failed together do not retry in lockstep.
+fn retry_backoff(base: std::time::Duration, attempt: u32) -> std::time::Duration {
+    let exp = base.saturating_mul(1u32 << (attempt.saturating_sub(1)).min(16));
+    let jitter_permille = (uuid::Uuid::new_v4().as_u128() % 501) as u32;
+    exp.saturating_add(exp * jitter_permille / 1000)
+        .min(LLM_RETRY_MAX_DELAY)
+}
+
+fn retry_after_header(response: &reqwest::Response) -> Option<std::time::Duration> {
+    let secs = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    Some(std::time::Duration::from_secs(secs).min(LLM_RETRY_MAX_DELAY))
+}
+
+/// Send the request built by `build`, retrying up to `max_retries` times on
+/// retryable statuses and network timeouts. Any other response, successful
+/// or not, is returned for the caller to handle; so is the last response
+/// once retries (or the run's retry budget) are used up.
+async fn send_with_retries(
+    max_retries: u32,
+    build: impl Fn() -> reqwest::RequestBuilder,
+) -> Result<reqwest::Response, MicroClawError> {
+    send_with_retries_from(max_retries, LLM_RETRY_BASE_DELAY, build).await
+}
+
+async fn send_with_retries_from(
+    max_retries: u32,
+    base_delay: std::time::Duration,
+    build: impl Fn() -> reqwest::RequestBuilder,
+) -> Result<reqwest::Response, MicroClawError> {
+    let mut attempt = 0u32;
+    loop {
+        let (delay, reason) = match build().send().await {
+            Ok(response) if !is_retryable_status(response.status()) => return Ok(response),
+            Ok(response) => {
+                if attempt >= max_retries || !crate::retry_budget::try_consume_retry("llm") {
+                    return Ok(response);
+                }
+                attempt += 1;
+                let backoff = retry_backoff(base_delay, attempt);
+                let delay = retry_after_header(&response).map_or(backoff, |d| d.max(backoff));
+                (delay, format!("HTTP {}", response.status()))
+            }
+            Err(e) => {
+                if !is_retryable_request_error(&e)
+                    || attempt >= max_retries
+                    || !crate::retry_budget::try_consume_retry("llm")
+                {
+                    return Err(e.into());
+                }
+                attempt += 1;
+                (retry_backoff(base_delay, attempt), e.to_string())
+            }
+        };
+        warn!(
+            "LLM request failed ({reason}), retrying in {:?} (attempt {attempt}/{max_retries})",
+            delay
+        );
+        tokio::time::sleep(delay).await;
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Anthropic provider
 // ---------------------------------------------------------------------------
@@ -248,7 +615,9 @@ pub struct AnthropicProvider {
     api_key: String,
     model: String,
     max_tokens: u32,
+    max_retries: u32,
     base_url: String,
+    prompt_caching: bool,
 }
 
 impl AnthropicProvider {
@@ -258,7 +627,9 @@ impl AnthropicProvider {
             api_key: config.api_key.clone(),
             model: config.model.clone(),
             max_tokens: config.max_tokens,
+            max_retries: config.llm_max_retries,
             base_url: resolve_anthropic_messages_url(config.llm_base_url.as_deref().unwrap_or("")),
+            prompt_caching: config.prompt_caching,
         }
     }
 
@@ -269,6 +640,7 @@ impl AnthropicProvider {
     ) -> Result<MessagesResponse, MicroClawError> {
         let mut streamed_request = request.clone();
         streamed_request.stream = Some(true);
+        let body = anthropic_request_body(&streamed_request, self.prompt_caching);
 
         debug!(
             provider = "anthropic",
@@ -278,15 +650,15 @@ impl AnthropicProvider {
             "Sending LLM stream request"
         );
 
-        let response = self
-            .http
-            .post(&self.base_url)
-            .header("x-api-key", &self.api_key)
-            .header("anthropic-version", "2023-06-01")
-            .header("content-type", "application/json")
-            .json(&streamed_request)
-            .send()
-            .await?;
+        let response = send_with_retries(self.max_retries, || {
+            self.http
+                .post(&self.base_url)
+                .header("x-api-key", &self.api_key)
+                .header("anthropic-version", "2023-06-01")
+                .header("content-type", "application/json")
+                .json(&body)
+        })
+        .await?;
 
         let status = response.status();
         if !status.is_success() {
@@ -327,7 +699,7 @@ impl AnthropicProvider {
                     &mut text_blocks,
                     &mut tool_blocks,
                     &mut ordered_indexes,
-                );
+                )?;
             }
         }
         for data in sse.finish() {
@@ -342,7 +714,7 @@ impl AnthropicProvider {
                 &mut text_blocks,
                 &mut tool_blocks,
                 &mut ordered_indexes,
-            );
+            )?;
         }
 
         Ok(build_stream_response(
@@ -366,6 +738,72 @@ fn resolve_anthropic_messages_url(configured_base: &str) -> String {
     format!("{trimmed}/v1/messages")
 }
 
+/// Serialize an Anthropic request. With prompt caching on, `cache_control`
+/// breakpoints go on the system prompt, the last tool definition and the last
+/// message; Anthropic caches the whole prompt prefix up to each breakpoint, so
+/// the next turn re-reads the conversation so far from the cache.
+fn anthropic_request_body(request: &MessagesRequest, prompt_caching: bool) -> serde_json::Value {
+    let mut body = serde_json::to_value(request).unwrap_or_else(|_| json!({}));
+    // Gemini thought signatures are not part of the Anthropic block schema.
+    for message in body
+        .get_mut("messages")
+        .and_then(|m| m.as_array_mut())
+        .into_iter()
+        .flatten()
+    {
+        for block in message
+            .get_mut("content")
+            .and_then(|c| c.as_array_mut())
+            .into_iter()
+            .flatten()
+        {
+            if let Some(block) = block.as_object_mut() {
+                block.remove("thought_signature");
+            }
+        }
+    }
+    if !prompt_caching {
+        return body;
+    }
+    let cache_control = json!({"type": "ephemeral"});
+    if !request.system.is_empty() {
+        body["system"] = json!([{
+            "type": "text",
+            "text": request.system,
+            "cache_control": cache_control,
+        }]);
+    }
+    if let Some(last_tool) = body
+        .get_mut("tools")
+        .and_then(|t| t.as_array_mut())
+        .and_then(|t| t.last_mut())
+    {
+        last_tool["cache_control"] = cache_control.clone();
+    }
+    if let Some(last_message) = body
+        .get_mut("messages")
+        .and_then(|m| m.as_array_mut())
+        .and_then(|m| m.last_mut())
+    {
+        if let Some(text) = last_message
+            .get("content")
+            .and_then(|c| c.as_str())
+            .filter(|t| !t.is_empty())
+            .map(str::to_string)
+        {
+            last_message["content"] = json!([{"type": "text", "text": text}]);
+        }
+        if let Some(block) = last_message
+            .get_mut("content")
+            .and_then(|c| c.as_array_mut())
+            .and_then(|b| b.last_mut())
+        {
+            block["cache_control"] = cache_control;
+        }
+    }
+    body
+}
+
 #[derive(Default)]
 struct StreamToolUseBlock {
     id: String,
@@ -374,15 +812,26 @@ struct StreamToolUseBlock {
 }
 
 fn usage_from_json(v: &serde_json::Value) -> Option<Usage> {
-    let input = v.get("input_tokens").and_then(|n| n.as_u64())?;
+    let input = v
+        .get("input_tokens")
+        .and_then(|n| n.as_u64())
+        .or_else(|| v.get("prompt_tokens").and_then(|n| n.as_u64()))?;
     let output = v
         .get("output_tokens")
         .and_then(|n| n.as_u64())
         .or_else(|| v.get("completion_tokens").and_then(|n| n.as_u64()))
         .unwrap_or(0);
+    let count = |key: &str| {
+        v.get(key)
+            .and_then(|n| n.as_u64())
+            .map(|n| u32::try_from(n).unwrap_or(u32::MAX))
+            .unwrap_or(0)
+    };
     Some(Usage {
         input_tokens: u32::try_from(input).unwrap_or(u32::MAX),
         output_tokens: u32::try_from(output).unwrap_or(u32::MAX),
+        cache_creation_input_tokens: count("cache_creation_input_tokens"),
+        cache_read_input_tokens: count("cache_read_input_tokens"),
     })
 }
 
@@ -394,9 +843,10 @@ fn process_anthropic_stream_event(
     text_blocks: &mut std::collections::HashMap<usize, String>,
     tool_blocks: &mut std::collections::HashMap<usize, StreamToolUseBlock>,
     ordered_indexes: &mut Vec<usize>,
-) {
+) -> Result<(), MicroClawError> {
     let Ok(v) = serde_json::from_str::<serde_json::Value>(data) else {
-        return;
+        debug!("Skipping non-JSON Anthropic stream event: {}", data);
+        return Ok(());
     };
 
     let event_type = v.get("type").and_then(|t| t.as_str()).unwrap_or_default();
@@ -459,10 +909,10 @@ fn process_anthropic_stream_event(
                 .and_then(|i| i.as_u64())
                 .and_then(|i| usize::try_from(i).ok())
             else {
-                return;
+                return Ok(());
             };
             let Some(delta) = v.get("delta") else {
-                return;
+                return Ok(());
             };
             match delta.get("type").and_then(|t| t.as_str()) {
                 Some("text_delta") => {
@@ -502,7 +952,17 @@ fn process_anthropic_stream_event(
                 *stop_reason = Some(reason.to_string());
             }
             if let Some(u) = v.get("usage") {
-                *usage = usage_from_json(u);
+                // `message_delta` usually carries only the final output count;
+                // keep the input and cache counts from `message_start`.
+                match (usage.as_mut(), usage_from_json(u)) {
+                    (_, Some(full)) => *usage = Some(full),
+                    (Some(existing), None) => {
+                        if let Some(out) = u.get("output_tokens").and_then(|n| n.as_u64()) {
+                            existing.output_tokens = u32::try_from(out).unwrap_or(u32::MAX);
+                        }
+                    }
+                    (None, None) => {}
+                }
             }
         }
         "message_start" => {
@@ -510,7 +970,25 @@ fn process_anthropic_stream_event(
                 *usage = usage_from_json(u);
             }
         }
-        _ => {}
+        "error" => return Err(stream_error_event(&v)),
+        "ping" | "content_block_stop" | "message_stop" => {}
+        other => debug!("Skipping unknown Anthropic stream event type '{}'", other),
+    }
+    Ok(())
+}
+
+/// Build an error from a provider's in-stream error event, e.g.
+/// `{"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}`.
+fn stream_error_event(v: &serde_json::Value) -> MicroClawError {
+    let error = v.get("error").unwrap_or(v);
+    let message = error
+        .get("message")
+        .and_then(|m| m.as_str())
+        .map(ToString::to_string)
+        .unwrap_or_else(|| error.to_string());
+    match error.get("type").and_then(|t| t.as_str()) {
+        Some(error_type) => MicroClawError::LlmApi(format!("{error_type}: {message}")),
+        None => MicroClawError::LlmApi(message),
     }
 }
 
@@ -522,10 +1000,14 @@ fn process_openai_stream_event(
     stop_reason: &mut Option<String>,
     usage: &mut Option<Usage>,
     tool_calls: &mut std::collections::BTreeMap<usize, StreamToolUseBlock>,
-) {
+) -> Result<(), MicroClawError> {
     let Ok(v) = serde_json::from_str::<serde_json::Value>(data) else {
-        return;
+        debug!("Skipping non-JSON OpenAI stream event: {}", data);
+        return Ok(());
     };
+    if v.get("error").is_some_and(|e| !e.is_null()) {
+        return Err(stream_error_event(&v));
+    }
 
     if usage.is_none() {
         *usage = v.get("usage").and_then(usage_from_json);
@@ -536,7 +1018,10 @@ fn process_openai_stream_event(
         .and_then(|c| c.as_array())
         .and_then(|arr| arr.first())
     else {
-        return;
+        if v.get("usage").is_none() {
+            debug!("Skipping OpenAI stream event without choices: {}", data);
+        }
+        return Ok(());
     };
 
     if let Some(reason) = choice.get("finish_reason").and_then(|r| r.as_str()) {
@@ -544,7 +1029,7 @@ fn process_openai_stream_event(
     }
 
     let Some(delta) = choice.get("delta") else {
-        return;
+        return Ok(());
     };
 
     if let Some(piece) = delta.get("content").and_then(|t| t.as_str()) {
@@ -588,6 +1073,7 @@ fn process_openai_stream_event(
             }
         }
     }
+    Ok(())
 }
 
 fn normalize_stop_reason(reason: Option<String>) -> Option<String> {
@@ -626,6 +1112,7 @@ fn build_stream_response(
                 id: tool.id.clone(),
                 name: tool.name.clone(),
                 input: parse_tool_input(&tool.input_json),
+                thought_signature: None,
             });
         }
     }
@@ -640,6 +1127,7 @@ fn build_stream_response(
         content,
         stop_reason: normalize_stop_reason(stop_reason),
         usage,
+        served_by: None,
     }
 }
 
@@ -688,51 +1176,36 @@ impl LlmProvider for AnthropicProvider {
             tools,
             stream: None,
         };
+        let body = anthropic_request_body(&request, self.prompt_caching);
 
-        let mut retries = 0u32;
-        let max_retries = 3;
-
-        loop {
-            let response = self
-                .http
+        let response = send_with_retries(self.max_retries, || {
+            self.http
                 .post(&self.base_url)
                 .header("x-api-key", &self.api_key)
                 .header("anthropic-version", "2023-06-01")
                 .header("content-type", "application/json")
-                .json(&request)
-                .send()
-                .await?;
-
-            let status = response.status();
+                .json(&body)
+        })
+        .await?;
 
-            if status.is_success() {
-                let body = response.text().await?;
-                let parsed: MessagesResponse = serde_json::from_str(&body).map_err(|e| {
-                    MicroClawError::LlmApi(format!("Failed to parse response: {e}\nBody: {body}"))
-                })?;
-                return Ok(parsed);
-            }
+        let status = response.status();
 
-            if status.as_u16() == 429 && retries < max_retries {
-                retries += 1;
-                let delay = std::time::Duration::from_secs(2u64.pow(retries));
-                warn!(
-                    "Rate limited, retrying in {:?} (attempt {retries}/{max_retries})",
-                    delay
-                );
-                tokio::time::sleep(delay).await;
-                continue;
-            }
+        if status.is_success() {
+            let body = response.text().await?;
+            let parsed: MessagesResponse = serde_json::from_str(&body).map_err(|e| {
+                MicroClawError::LlmApi(format!("Failed to parse response: {e}\nBody: {body}"))
+            })?;
+            return Ok(parsed);
+        }
 
-            let body = response.text().await.unwrap_or_default();
-            if let Ok(api_err) = serde_json::from_str::<AnthropicApiError>(&body) {
-                return Err(MicroClawError::LlmApi(format!(
-                    "{}: {}",
-                    api_err.error.error_type, api_err.error.message
-                )));
-            }
-            return Err(MicroClawError::LlmApi(format!("HTTP {status}: {body}")));
+        let body = response.text().await.unwrap_or_default();
+        if let Ok(api_err) = serde_json::from_str::<AnthropicApiError>(&body) {
+            return Err(MicroClawError::LlmApi(format!(
+                "{}: {}",
+                api_err.error.error_type, api_err.error.message
+            )));
         }
+        Err(MicroClawError::LlmApi(format!("HTTP {status}: {body}")))
     }
 
     async fn send_message_stream(
@@ -784,10 +1257,12 @@ pub struct OpenAiProvider {
     provider: String,
     model: String,
     max_tokens: u32,
+    max_retries: u32,
     is_openai_codex: bool,
     enable_reasoning_content_bridge: bool,
     enable_thinking_param: bool,
     prefer_max_completion_tokens: bool,
+    strict_tools: bool,
     openai_compat_body_overrides: HashMap<String, serde_json::Value>,
     openai_compat_body_overrides_by_provider: HashMap<String, HashMap<String, serde_json::Value>>,
     openai_compat_body_overrides_by_model: HashMap<String, HashMap<String, serde_json::Value>>,
@@ -840,10 +1315,12 @@ impl OpenAiProvider {
             provider: config.llm_provider.clone(),
             model: config.model.clone(),
             max_tokens: config.max_tokens,
+            max_retries: config.llm_max_retries,
             is_openai_codex,
             enable_reasoning_content_bridge,
             enable_thinking_param,
             prefer_max_completion_tokens: config.llm_provider.eq_ignore_ascii_case("openai"),
+            strict_tools: config.openai_strict_tools,
             openai_compat_body_overrides: config.openai_compat_body_overrides.clone(),
             openai_compat_body_overrides_by_provider: config
                 .openai_compat_body_overrides_by_provider
@@ -929,6 +1406,8 @@ struct OaiMessage {
 
 #[derive(Debug, Deserialize)]
 struct OaiToolCall {
+    /// Some local servers (ollama, llama.cpp) leave this out.
+    #[serde(default)]
     id: String,
     function: OaiFunction,
 }
@@ -936,7 +1415,9 @@ struct OaiToolCall {
 #[derive(Debug, Deserialize)]
 struct OaiFunction {
     name: String,
-    arguments: String,
+    /// A JSON-encoded string per the spec; some servers send an object.
+    #[serde(default)]
+    arguments: serde_json::Value,
 }
 
 #[derive(Debug, Deserialize)]
@@ -1102,23 +1583,15 @@ impl LlmProvider for OpenAiProvider {
 
         if let Some(ref tool_defs) = tools {
             if !tool_defs.is_empty() {
-                body["tools"] = json!(translate_tools_to_oai(tool_defs));
+                body["tools"] = json!(translate_tools_to_oai(tool_defs, self.strict_tools));
             }
         }
 
-        let mut retries = 0u32;
-        let max_retries = 3;
-
         loop {
-            let mut req = self
-                .http
-                .post(&self.chat_url)
-                .header("Content-Type", "application/json")
-                .json(&body);
-            if !self.api_key.trim().is_empty() {
-                req = req.header("Authorization", format!("Bearer {}", self.api_key));
-            }
-            let response = req.send().await?;
+            let response = send_with_retries(self.max_retries, || {
+                self.chat_request(&self.chat_url, &body)
+            })
+            .await?;
 
             let status = response.status();
 
@@ -1132,17 +1605,6 @@ impl LlmProvider for OpenAiProvider {
                 return Ok(translate_oai_response(oai));
             }
 
-            if status.as_u16() == 429 && retries < max_retries {
-                retries += 1;
-                let delay = std::time::Duration::from_secs(2u64.pow(retries));
-                warn!(
-                    "Rate limited, retrying in {:?} (attempt {retries}/{max_retries})",
-                    delay
-                );
-                tokio::time::sleep(delay).await;
-                continue;
-            }
-
             let text = response.text().await.unwrap_or_default();
             if should_retry_with_max_completion_tokens(&text)
                 && switch_to_max_completion_tokens(&mut body)
@@ -1232,7 +1694,7 @@ impl LlmProvider for OpenAiProvider {
 
         if let Some(ref tool_defs) = tools {
             if !tool_defs.is_empty() {
-                body["tools"] = json!(translate_tools_to_oai(tool_defs));
+                body["tools"] = json!(translate_tools_to_oai(tool_defs, self.strict_tools));
             }
         }
 
@@ -1245,15 +1707,10 @@ impl LlmProvider for OpenAiProvider {
         );
 
         let response = loop {
-            let mut req = self
-                .http
-                .post(&self.chat_url)
-                .header("Content-Type", "application/json")
-                .json(&body);
-            if !self.api_key.trim().is_empty() {
-                req = req.header("Authorization", format!("Bearer {}", self.api_key));
-            }
-            let response = req.send().await?;
+            let response = send_with_retries(self.max_retries, || {
+                self.chat_request(&self.chat_url, &body)
+            })
+            .await?;
             let status = response.status();
             if status.is_success() {
                 break response;
@@ -1300,7 +1757,7 @@ impl LlmProvider for OpenAiProvider {
                     &mut stop_reason,
                     &mut usage,
                     &mut tool_calls,
-                );
+                )?;
             }
         }
         for data in sse.finish() {
@@ -1315,35 +1772,54 @@ impl LlmProvider for OpenAiProvider {
                 &mut stop_reason,
                 &mut usage,
                 &mut tool_calls,
-            );
-        }
-
-        let mut content = Vec::new();
-        if !text.is_empty() {
-            content.push(ResponseContentBlock::Text { text });
-        } else if !reasoning_text.is_empty() && !tool_calls.is_empty() {
-            content.push(ResponseContentBlock::Text {
-                text: reasoning_text,
-            });
-        }
-        for (_index, tool) in tool_calls {
-            content.push(ResponseContentBlock::ToolUse {
-                id: tool.id,
-                name: tool.name,
-                input: parse_tool_input(&tool.input_json),
-            });
-        }
-        if content.is_empty() {
-            content.push(ResponseContentBlock::Text {
-                text: String::new(),
-            });
+            )?;
         }
 
-        Ok(MessagesResponse {
-            content,
-            stop_reason: normalize_stop_reason(stop_reason),
+        Ok(build_openai_stream_response(
+            text,
+            reasoning_text,
+            tool_calls,
+            stop_reason,
             usage,
-        })
+        ))
+    }
+}
+
+fn build_openai_stream_response(
+    text: String,
+    reasoning_text: String,
+    tool_calls: std::collections::BTreeMap<usize, StreamToolUseBlock>,
+    stop_reason: Option<String>,
+    usage: Option<Usage>,
+) -> MessagesResponse {
+    let mut content = Vec::new();
+    if !text.is_empty() {
+        content.push(ResponseContentBlock::Text { text });
+    } else if !reasoning_text.is_empty() && !tool_calls.is_empty() {
+        content.push(ResponseContentBlock::Text {
+            text: reasoning_text,
+        });
+    }
+    let has_tool_calls = !tool_calls.is_empty();
+    for (index, tool) in tool_calls {
+        content.push(ResponseContentBlock::ToolUse {
+            id: oai_tool_call_id(tool.id, index),
+            name: tool.name,
+            input: drop_null_arguments(parse_tool_input(&tool.input_json)),
+            thought_signature: None,
+        });
+    }
+    if content.is_empty() {
+        content.push(ResponseContentBlock::Text {
+            text: String::new(),
+        });
+    }
+
+    MessagesResponse {
+        content,
+        stop_reason: oai_stop_reason(stop_reason.as_deref(), has_tool_calls),
+        usage,
+        served_by: None,
     }
 }
 
@@ -1391,48 +1867,42 @@ impl OpenAiProvider {
             }
         }
 
-        let mut retries = 0u32;
-        let max_retries = 3;
-
-        loop {
-            let mut req = self
-                .http
-                .post(&self.responses_url)
-                .header("Content-Type", "application/json")
-                .json(&body);
-            if !self.api_key.trim().is_empty() {
-                req = req.header("Authorization", format!("Bearer {}", self.api_key));
-            }
-            if let Some(account_id) = self.codex_account_id.as_deref() {
-                if !account_id.trim().is_empty() {
-                    req = req.header("ChatGPT-Account-ID", account_id);
+        let response = send_with_retries(self.max_retries, || {
+            let req = self.chat_request(&self.responses_url, &body);
+            match self.codex_account_id.as_deref() {
+                Some(account_id) if !account_id.trim().is_empty() => {
+                    req.header("ChatGPT-Account-ID", account_id)
                 }
+                _ => req,
             }
-            let response = req.send().await?;
-            let status = response.status();
+        })
+        .await?;
+        let status = response.status();
 
-            if status.is_success() {
-                let text = response.text().await?;
-                let parsed = parse_openai_codex_response_payload(&text)?;
-                return Ok(translate_oai_responses_response(parsed));
-            }
+        if status.is_success() {
+            let text = response.text().await?;
+            let parsed = parse_openai_codex_response_payload(&text)?;
+            return Ok(translate_oai_responses_response(parsed));
+        }
 
-            if status.as_u16() == 429 && retries < max_retries {
-                retries += 1;
-                let delay = std::time::Duration::from_secs(2u64.pow(retries));
-                warn!(
-                    "Rate limited, retrying in {:?} (attempt {retries}/{max_retries})",
-                    delay
-                );
-                tokio::time::sleep(delay).await;
-                continue;
-            }
+        let text = response.text().await.unwrap_or_default();
+        if let Ok(err) = serde_json::from_str::<OaiErrorResponse>(&text) {
+            return Err(MicroClawError::LlmApi(err.error.message));
+        }
+        Err(MicroClawError::LlmApi(format!("HTTP {status}: {text}")))
+    }
 
-            let text = response.text().await.unwrap_or_default();
-            if let Ok(err) = serde_json::from_str::<OaiErrorResponse>(&text) {
-                return Err(MicroClawError::LlmApi(err.error.message));
-            }
-            return Err(MicroClawError::LlmApi(format!("HTTP {status}: {text}")));
+    /// JSON POST with the bearer token, if one is configured.
+    fn chat_request(&self, url: &str, body: &serde_json::Value) -> reqwest::RequestBuilder {
+        let req = self
+            .http
+            .post(url)
+            .header("Content-Type", "application/json")
+            .json(body);
+        if self.api_key.trim().is_empty() {
+            req
+        } else {
+            req.header("Authorization", format!("Bearer {}", self.api_key))
         }
     }
 }
@@ -1526,7 +1996,9 @@ fn translate_messages_to_oai_with_reasoning(
                     let tool_calls: Vec<serde_json::Value> = blocks
                         .iter()
                         .filter_map(|b| match b {
-                            ContentBlock::ToolUse { id, name, input } => Some(json!({
+                            ContentBlock::ToolUse {
+                                id, name, input, ..
+                            } => Some(json!({
                                 "id": id,
                                 "type": "function",
                                 "function": {
@@ -1635,22 +2107,128 @@ fn translate_messages_to_oai_with_reasoning(
     out
 }
 
-fn translate_tools_to_oai(tools: &[ToolDefinition]) -> Vec<serde_json::Value> {
+fn translate_tools_to_oai(tools: &[ToolDefinition], strict: bool) -> Vec<serde_json::Value> {
     tools
         .iter()
         .map(|t| {
-            json!({
-                "type": "function",
-                "function": {
-                    "name": t.name,
-                    "description": t.description,
-                    "parameters": t.input_schema,
-                }
-            })
+            if strict {
+                json!({
+                    "type": "function",
+                    "function": {
+                        "name": t.name,
+                        "description": t.description,
+                        "parameters": strict_tool_schema(&t.input_schema),
+                        "strict": true,
+                    }
+                })
+            } else {
+                json!({
+                    "type": "function",
+                    "function": {
+                        "name": t.name,
+                        "description": t.description,
+                        "parameters": t.input_schema,
+                    }
+                })
+            }
         })
         .collect()
 }
 
+/// Rewrite a tool's input schema for OpenAI strict mode: every object with
+/// `properties` lists all of them as required and forbids extra keys, and
+/// properties that were optional become nullable. Free-form objects (no
+/// `properties`) are left alone.
+fn strict_tool_schema(schema: &serde_json::Value) -> serde_json::Value {
+    let mut out = schema.clone();
+    let Some(obj) = out.as_object_mut() else {
+        return out;
+    };
+    if let Some(items) = obj.get("items") {
+        let items = strict_tool_schema(items);
+        obj.insert("items".into(), items);
+    }
+    let Some(properties) = obj.get("properties").and_then(|p| p.as_object()) else {
+        return out;
+    };
+    let required: Vec<&str> = obj
+        .get("required")
+        .and_then(|r| r.as_array())
+        .map(|r| r.iter().filter_map(|v| v.as_str()).collect())
+        .unwrap_or_default();
+    let mut strict_properties = serde_json::Map::new();
+    for (name, property) in properties {
+        let mut property = strict_tool_schema(property);
+        if !required.contains(&name.as_str()) {
+            make_schema_nullable(&mut property);
+        }
+        strict_properties.insert(name.clone(), property);
+    }
+    let all_names: Vec<serde_json::Value> = strict_properties
+        .keys()
+        .map(|k| serde_json::Value::String(k.clone()))
+        .collect();
+    obj.insert(
+        "properties".into(),
+        serde_json::Value::Object(strict_properties),
+    );
+    obj.insert("required".into(), serde_json::Value::Array(all_names));
+    obj.insert("additionalProperties".into(), json!(false));
+    out
+}
+
+fn make_schema_nullable(schema: &mut serde_json::Value) {
+    match schema.get("type").cloned() {
+        Some(serde_json::Value::String(ty)) => {
+            schema["type"] = json!([ty, "null"]);
+        }
+        Some(serde_json::Value::Array(mut types)) => {
+            if !types.contains(&json!("null")) {
+                types.push(json!("null"));
+            }
+            schema["type"] = serde_json::Value::Array(types);
+        }
+        _ => {
+            *schema = json!({ "anyOf": [schema.clone(), { "type": "null" }] });
+            return;
+        }
+    }
+    if let Some(values) = schema.get_mut("enum").and_then(|e| e.as_array_mut()) {
+        if !values.contains(&serde_json::Value::Null) {
+            values.push(serde_json::Value::Null);
+        }
+    }
+}
+
+/// Strict mode sends `null` for optional arguments the model did not fill;
+/// tools expect those keys to be absent.
+fn drop_null_arguments(mut input: serde_json::Value) -> serde_json::Value {
+    if let Some(obj) = input.as_object_mut() {
+        obj.retain(|_, v| !v.is_null());
+    }
+    input
+}
+
+fn oai_tool_call_id(id: String, index: usize) -> String {
+    if id.trim().is_empty() {
+        format!("call_{index}")
+    } else {
+        id
+    }
+}
+
+/// Our stop reason for an OpenAI `finish_reason`. Local servers often report
+/// `stop` alongside tool calls, so any tool call means `tool_use`.
+fn oai_stop_reason(finish_reason: Option<&str>, has_tool_calls: bool) -> Option<String> {
+    match finish_reason {
+        // A cut-off response may end in a half-written tool call; report the
+        // truncation rather than running it.
+        Some("length") => Some("max_tokens".into()),
+        _ if has_tool_calls => Some("tool_use".into()),
+        _ => Some("end_turn".into()),
+    }
+}
+
 fn translate_tools_to_oai_responses(tools: &[ToolDefinition]) -> Vec<serde_json::Value> {
     tools
         .iter()
@@ -1704,7 +2282,10 @@ fn translate_messages_to_oai_responses_input(messages: &[Message]) -> Vec<serde_
                     }
 
                     for block in blocks {
-                        if let ContentBlock::ToolUse { id, name, input } = block {
+                        if let ContentBlock::ToolUse {
+                            id, name, input, ..
+                        } = block
+                        {
                             out.push(json!({
                                 "type": "function_call",
                                 "call_id": id,
@@ -1836,6 +2417,7 @@ fn translate_oai_responses_response(resp: OaiResponsesResponse) -> MessagesRespo
                     id: call_id,
                     name,
                     input: parsed_args,
+                    thought_signature: None,
                 });
                 saw_tool_use = true;
             }
@@ -1859,7 +2441,9 @@ fn translate_oai_responses_response(resp: OaiResponsesResponse) -> MessagesRespo
         usage: resp.usage.map(|usage| Usage {
             input_tokens: usage.input_tokens,
             output_tokens: usage.output_tokens,
+            ..Default::default()
         }),
+        served_by: None,
     }
 }
 
@@ -1873,6 +2457,7 @@ fn translate_oai_response(oai: OaiResponse) -> MessagesResponse {
                 }],
                 stop_reason: Some("end_turn".into()),
                 usage: None,
+                served_by: None,
             };
         }
     };
@@ -1892,17 +2477,20 @@ fn translate_oai_response(oai: OaiResponse) -> MessagesResponse {
         }
     }
 
-    let has_tool_calls = tool_calls.is_some();
-    if let Some(tool_calls) = tool_calls {
-        for tc in tool_calls {
-            let input: serde_json::Value =
-                serde_json::from_str(&tc.function.arguments).unwrap_or_default();
-            content.push(ResponseContentBlock::ToolUse {
-                id: tc.id,
-                name: tc.function.name,
-                input,
-            });
-        }
+    let tool_calls = tool_calls.unwrap_or_default();
+    let has_tool_calls = !tool_calls.is_empty();
+    for (index, tc) in tool_calls.into_iter().enumerate() {
+        let input = match tc.function.arguments {
+            serde_json::Value::String(raw) => parse_tool_input(&raw),
+            serde_json::Value::Null => json!({}),
+            other => other,
+        };
+        content.push(ResponseContentBlock::ToolUse {
+            id: oai_tool_call_id(tc.id, index),
+            name: tc.function.name,
+            input: drop_null_arguments(input),
+            thought_signature: None,
+        });
     }
 
     if has_tool_calls && !has_visible_text {
@@ -1919,21 +2507,522 @@ fn translate_oai_response(oai: OaiResponse) -> MessagesResponse {
         });
     }
 
-    let stop_reason = match choice.finish_reason.as_deref() {
-        Some("tool_calls") => Some("tool_use".into()),
-        Some("length") => Some("max_tokens".into()),
-        _ => Some("end_turn".into()),
-    };
+    let stop_reason = oai_stop_reason(choice.finish_reason.as_deref(), has_tool_calls);
 
     let usage = oai.usage.map(|u| Usage {
         input_tokens: u.prompt_tokens,
         output_tokens: u.completion_tokens,
+        ..Default::default()
     });
 
     MessagesResponse {
         content,
         stop_reason,
         usage,
+        served_by: None,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Google Gemini provider  (native generateContent API)
+// ---------------------------------------------------------------------------
+
+pub struct GeminiProvider {
+    http: reqwest::Client,
+    api_key: String,
+    model: String,
+    max_tokens: u32,
+    max_retries: u32,
+    base_url: String,
+}
+
+fn resolve_gemini_base(configured_base: &str) -> String {
+    let trimmed = configured_base.trim().trim_end_matches('/');
+    if trimmed.is_empty() {
+        "https://generativelanguage.googleapis.com/v1beta".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+impl GeminiProvider {
+    pub fn new(config: &Config) -> Self {
+        GeminiProvider {
+            http: reqwest::Client::new(),
+            api_key: config.api_key.clone(),
+            model: config.model.clone(),
+            max_tokens: config.max_tokens,
+            max_retries: config.llm_max_retries,
+            base_url: resolve_gemini_base(config.llm_base_url.as_deref().unwrap_or("")),
+        }
+    }
+
+    fn model_url(&self, model: &str, method: &str) -> String {
+        let model = model.strip_prefix("models/").unwrap_or(model);
+        format!("{}/models/{model}:{method}", self.base_url)
+    }
+
+    async fn post(
+        &self,
+        url: &str,
+        body: &serde_json::Value,
+    ) -> Result<reqwest::Response, MicroClawError> {
+        let response = send_with_retries(self.max_retries, || {
+            self.http
+                .post(url)
+                .header("x-goog-api-key", &self.api_key)
+                .header("content-type", "application/json")
+                .json(body)
+        })
+        .await?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        let text = response.text().await.unwrap_or_default();
+        Err(gemini_api_error(status, &text))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GeminiErrorResponse {
+    error: GeminiErrorDetail,
+}
+
+#[derive(Debug, Deserialize)]
+struct GeminiErrorDetail {
+    message: String,
+    #[serde(default)]
+    status: String,
+}
+
+fn gemini_api_error(status: reqwest::StatusCode, body: &str) -> MicroClawError {
+    match serde_json::from_str::<GeminiErrorResponse>(body) {
+        Ok(err) if !err.error.status.is_empty() => {
+            MicroClawError::LlmApi(format!("{}: {}", err.error.status, err.error.message))
+        }
+        Ok(err) => MicroClawError::LlmApi(err.error.message),
+        Err(_) => MicroClawError::LlmApi(format!("HTTP {status}: {body}")),
+    }
+}
+
+#[async_trait]
+impl LlmProvider for GeminiProvider {
+    async fn send_message(
+        &self,
+        system: &str,
+        messages: Vec<Message>,
+        tools: Option<Vec<ToolDefinition>>,
+    ) -> Result<MessagesResponse, MicroClawError> {
+        self.send_message_with_model(system, messages, tools, None)
+            .await
+    }
+
+    async fn send_message_with_model(
+        &self,
+        system: &str,
+        messages: Vec<Message>,
+        tools: Option<Vec<ToolDefinition>>,
+        model_override: Option<&str>,
+    ) -> Result<MessagesResponse, MicroClawError> {
+        let messages = sanitize_messages(messages);
+        let model = model_override
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .unwrap_or(&self.model);
+        let body = gemini_request_body(system, &messages, tools.as_deref(), self.max_tokens);
+        let response = self
+            .post(&self.model_url(model, "generateContent"), &body)
+            .await?;
+        let text = response.text().await?;
+        let v: serde_json::Value = serde_json::from_str(&text).map_err(|e| {
+            MicroClawError::LlmApi(format!(
+                "Failed to parse Gemini response: {e}\nBody: {text}"
+            ))
+        })?;
+        let mut state = GeminiStreamState::default();
+        state.push(&v, None)?;
+        Ok(state.finish())
+    }
+
+    async fn send_message_stream(
+        &self,
+        system: &str,
+        messages: Vec<Message>,
+        tools: Option<Vec<ToolDefinition>>,
+        text_tx: Option<&UnboundedSender<String>>,
+    ) -> Result<MessagesResponse, MicroClawError> {
+        self.send_message_stream_with_model(system, messages, tools, text_tx, None)
+            .await
+    }
+
+    async fn send_message_stream_with_model(
+        &self,
+        system: &str,
+        messages: Vec<Message>,
+        tools: Option<Vec<ToolDefinition>>,
+        text_tx: Option<&UnboundedSender<String>>,
+        model_override: Option<&str>,
+    ) -> Result<MessagesResponse, MicroClawError> {
+        let messages = sanitize_messages(messages);
+        let model = model_override
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .unwrap_or(&self.model);
+        let body = gemini_request_body(system, &messages, tools.as_deref(), self.max_tokens);
+        let url = format!("{}?alt=sse", self.model_url(model, "streamGenerateContent"));
+
+        debug!(
+            provider = "gemini",
+            model = %model,
+            url = %url,
+            messages_count = messages.len(),
+            "Sending LLM stream request"
+        );
+
+        let response = self.post(&url, &body).await?;
+        let mut byte_stream = response.bytes_stream();
+        let mut sse = SseEventParser::default();
+        let mut state = GeminiStreamState::default();
+        while let Some(chunk_res) = byte_stream.next().await {
+            let chunk = match chunk_res {
+                Ok(c) => c,
+                Err(_) => break,
+            };
+            for data in sse.push_chunk(&String::from_utf8_lossy(&chunk)) {
+                state.push_event(&data, text_tx)?;
+            }
+        }
+        for data in sse.finish() {
+            state.push_event(&data, text_tx)?;
+        }
+        Ok(state.finish())
+    }
+}
+
+/// Build a `generateContent` body: the system prompt goes to
+/// `systemInstruction`, assistant turns use the `model` role, and tool
+/// results become `functionResponse` parts named after their tool call.
+fn gemini_request_body(
+    system: &str,
+    messages: &[Message],
+    tools: Option<&[ToolDefinition]>,
+    max_tokens: u32,
+) -> serde_json::Value {
+    let mut body = json!({
+        "contents": translate_messages_to_gemini(messages),
+        "generationConfig": { "maxOutputTokens": max_tokens },
+    });
+    if !system.trim().is_empty() {
+        body["systemInstruction"] = json!({ "parts": [{ "text": system }] });
+    }
+    if let Some(tools) = tools.filter(|t| !t.is_empty()) {
+        body["tools"] = json!([{ "functionDeclarations": translate_tools_to_gemini(tools) }]);
+    }
+    body
+}
+
+fn translate_messages_to_gemini(messages: &[Message]) -> Vec<serde_json::Value> {
+    let mut tool_names: HashMap<String, String> = HashMap::new();
+    let mut contents: Vec<serde_json::Value> = Vec::new();
+    for msg in messages {
+        let role = if msg.role == "assistant" {
+            "model"
+        } else {
+            "user"
+        };
+        let parts: Vec<serde_json::Value> = match &msg.content {
+            MessageContent::Text(text) => vec![json!({ "text": text })],
+            MessageContent::Blocks(blocks) => blocks
+                .iter()
+                .map(|block| match block {
+                    ContentBlock::Text { text } => json!({ "text": text }),
+                    ContentBlock::Image { source } => json!({
+                        "inlineData": { "mimeType": source.media_type, "data": source.data }
+                    }),
+                    ContentBlock::ToolUse {
+                        id,
+                        name,
+                        input,
+                        thought_signature,
+                    } => {
+                        tool_names.insert(id.clone(), name.clone());
+                        let mut part = json!({ "functionCall": { "name": name, "args": input } });
+                        if let Some(signature) = thought_signature {
+                            part["thoughtSignature"] = json!(signature);
+                        }
+                        part
+                    }
+                    ContentBlock::ToolResult {
+                        tool_use_id,
+                        content,
+                        is_error,
+                    } => {
+                        let name = tool_names
+                            .get(tool_use_id)
+                            .cloned()
+                            .unwrap_or_else(|| tool_use_id.clone());
+                        let key = if is_error.unwrap_or(false) {
+                            "error"
+                        } else {
+                            "content"
+                        };
+                        json!({
+                            "functionResponse": { "name": name, "response": { (key): content } }
+                        })
+                    }
+                })
+                .collect(),
+        };
+        if parts.is_empty() {
+            continue;
+        }
+        // Gemini wants strictly alternating roles; merge consecutive turns.
+        match contents.last_mut() {
+            Some(last) if last["role"] == role => {
+                if let Some(existing) = last["parts"].as_array_mut() {
+                    existing.extend(parts);
+                }
+            }
+            _ => contents.push(json!({ "role": role, "parts": parts })),
+        }
+    }
+    contents
+}
+
+fn translate_tools_to_gemini(tools: &[ToolDefinition]) -> Vec<serde_json::Value> {
+    tools
+        .iter()
+        .map(|t| {
+            let mut decl = json!({ "name": t.name, "description": t.description });
+            let parameters = gemini_schema(&t.input_schema);
+            if parameters
+                .get("properties")
+                .and_then(|p| p.as_object())
+                .is_some_and(|p| !p.is_empty())
+            {
+                decl["parameters"] = parameters;
+            }
+            decl
+        })
+        .collect()
+}
+
+/// Schema keywords Gemini's OpenAPI-subset function schema accepts.
+const GEMINI_SCHEMA_KEYS: &[&str] = &[
+    "type",
+    "format",
+    "description",
+    "nullable",
+    "enum",
+    "items",
+    "properties",
+    "required",
+    "minimum",
+    "maximum",
+    "minItems",
+    "maxItems",
+    "minLength",
+    "maxLength",
+    "pattern",
+    "anyOf",
+];
+
+/// Reduce a JSON schema to what Gemini accepts: unsupported keywords
+/// (`additionalProperties`, `$schema`, `default`, ...) are dropped, a
+/// `["T", "null"]` type becomes `T` with `nullable`, and empty `properties`
+/// are removed.
+fn gemini_schema(schema: &serde_json::Value) -> serde_json::Value {
+    let Some(obj) = schema.as_object() else {
+        return schema.clone();
+    };
+    let mut out = serde_json::Map::new();
+    for (key, value) in obj {
+        if !GEMINI_SCHEMA_KEYS.contains(&key.as_str()) {
+            continue;
+        }
+        let value = match key.as_str() {
+            "type" => match value.as_array() {
+                Some(types) => {
+                    if types.iter().any(|t| t == "null") {
+                        out.insert("nullable".into(), json!(true));
+                    }
+                    types
+                        .iter()
+                        .find(|t| *t != "null")
+                        .cloned()
+                        .unwrap_or_else(|| json!("string"))
+                }
+                None => value.clone(),
+            },
+            "items" => gemini_schema(value),
+            "anyOf" => json!(value
+                .as_array()
+                .map(|a| a.iter().map(gemini_schema).collect::<Vec<_>>())
+                .unwrap_or_default()),
+            "properties" => {
+                let Some(props) = value.as_object().filter(|p| !p.is_empty()) else {
+                    continue;
+                };
+                json!(props
+                    .iter()
+                    .map(|(name, prop)| (name.clone(), gemini_schema(prop)))
+                    .collect::<serde_json::Map<_, _>>())
+            }
+            "enum" => json!(value
+                .as_array()
+                .map(|a| a
+                    .iter()
+                    .filter(|v| !v.is_null())
+                    .cloned()
+                    .collect::<Vec<_>>())
+                .unwrap_or_default()),
+            _ => value.clone(),
+        };
+        out.insert(key.clone(), value);
+    }
+    serde_json::Value::Object(out)
+}
+
+/// Accumulates `generateContent` response chunks. The non-streaming API
+/// returns a single chunk; `streamGenerateContent` sends one per SSE event,
+/// each carrying new text or whole function calls.
+#[derive(Default)]
+struct GeminiStreamState {
+    content: Vec<ResponseContentBlock>,
+    finish_reason: Option<String>,
+    usage: Option<Usage>,
+}
+
+impl GeminiStreamState {
+    fn push_event(
+        &mut self,
+        data: &str,
+        text_tx: Option<&UnboundedSender<String>>,
+    ) -> Result<(), MicroClawError> {
+        let Ok(v) = serde_json::from_str::<serde_json::Value>(data) else {
+            debug!("Skipping non-JSON Gemini stream event: {}", data);
+            return Ok(());
+        };
+        self.push(&v, text_tx)
+    }
+
+    fn push(
+        &mut self,
+        v: &serde_json::Value,
+        text_tx: Option<&UnboundedSender<String>>,
+    ) -> Result<(), MicroClawError> {
+        if let Some(err) = v.get("error").filter(|e| !e.is_null()) {
+            let message = err
+                .get("message")
+                .and_then(|m| m.as_str())
+                .unwrap_or("Gemini stream error");
+            return Err(MicroClawError::LlmApi(message.to_string()));
+        }
+        if let Some(meta) = v.get("usageMetadata") {
+            let count = |key: &str| {
+                meta.get(key)
+                    .and_then(|n| n.as_u64())
+                    .map(|n| u32::try_from(n).unwrap_or(u32::MAX))
+                    .unwrap_or(0)
+            };
+            self.usage = Some(Usage {
+                input_tokens: count("promptTokenCount"),
+                output_tokens: count("candidatesTokenCount"),
+                cache_read_input_tokens: count("cachedContentTokenCount"),
+                ..Default::default()
+            });
+        }
+        let Some(candidate) = v
+            .get("candidates")
+            .and_then(|c| c.as_array())
+            .and_then(|c| c.first())
+        else {
+            if let Some(reason) = v
+                .get("promptFeedback")
+                .and_then(|f| f.get("blockReason"))
+                .and_then(|r| r.as_str())
+            {
+                return Err(MicroClawError::LlmApi(format!(
+                    "Gemini blocked the prompt: {reason}"
+                )));
+            }
+            return Ok(());
+        };
+        if let Some(reason) = candidate.get("finishReason").and_then(|r| r.as_str()) {
+            self.finish_reason = Some(reason.to_string());
+        }
+        let parts = candidate
+            .get("content")
+            .and_then(|c| c.get("parts"))
+            .and_then(|p| p.as_array());
+        for part in parts.into_iter().flatten() {
+            if part.get("thought").and_then(|t| t.as_bool()) == Some(true) {
+                continue;
+            }
+            if let Some(call) = part.get("functionCall") {
+                let id = call
+                    .get("id")
+                    .and_then(|id| id.as_str())
+                    .filter(|id| !id.is_empty())
+                    .map(str::to_string)
+                    .unwrap_or_else(|| format!("call_{}", uuid::Uuid::new_v4().simple()));
+                self.content.push(ResponseContentBlock::ToolUse {
+                    id,
+                    name: call
+                        .get("name")
+                        .and_then(|n| n.as_str())
+                        .unwrap_or_default()
+                        .to_string(),
+                    input: call.get("args").cloned().unwrap_or_else(|| json!({})),
+                    thought_signature: part
+                        .get("thoughtSignature")
+                        .and_then(|s| s.as_str())
+                        .map(str::to_string),
+                });
+            } else if let Some(piece) = part.get("text").and_then(|t| t.as_str()) {
+                if piece.is_empty() {
+                    continue;
+                }
+                if let Some(tx) = text_tx {
+                    let _ = tx.send(piece.to_string());
+                }
+                match self.content.last_mut() {
+                    Some(ResponseContentBlock::Text { text }) => text.push_str(piece),
+                    _ => self.content.push(ResponseContentBlock::Text {
+                        text: piece.to_string(),
+                    }),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(mut self) -> MessagesResponse {
+        let has_tool_calls = self
+            .content
+            .iter()
+            .any(|b| matches!(b, ResponseContentBlock::ToolUse { .. }));
+        // A call cut off by the token limit may carry partial args, so it
+        // must not be executed.
+        let stop_reason = if self.finish_reason.as_deref() == Some("MAX_TOKENS") {
+            "max_tokens"
+        } else if has_tool_calls {
+            "tool_use"
+        } else {
+            "end_turn"
+        };
+        if self.content.is_empty() {
+            self.content.push(ResponseContentBlock::Text {
+                text: String::new(),
+            });
+        }
+        MessagesResponse {
+            content: self.content,
+            stop_reason: Some(stop_reason.into()),
+            usage: self.usage,
+            served_by: None,
+        }
     }
 }
 
@@ -2002,6 +3091,7 @@ mod tests {
                     id: "t1".into(),
                     name: "bash".into(),
                     input: json!({"command": "ls"}),
+                    thought_signature: None,
                 },
             ]),
         }];
@@ -2027,6 +3117,7 @@ mod tests {
                     id: "t1".into(),
                     name: "bash".into(),
                     input: json!({"command": "ls"}),
+                    thought_signature: None,
                 },
             ]),
         }];
@@ -2049,6 +3140,7 @@ mod tests {
                     id: "t1".into(),
                     name: "glob".into(),
                     input: json!({}),
+                    thought_signature: None,
                 }]),
             },
             Message {
@@ -2077,6 +3169,7 @@ mod tests {
                     id: "t1".into(),
                     name: "glob".into(),
                     input: json!({}),
+                    thought_signature: None,
                 }]),
             },
             Message {
@@ -2118,6 +3211,7 @@ mod tests {
                     id: "t1".into(),
                     name: "glob".into(),
                     input: json!({}),
+                    thought_signature: None,
                 }]),
             },
             Message {
@@ -2180,6 +3274,7 @@ mod tests {
                     id: "t1".into(),
                     name: "glob".into(),
                     input: json!({}),
+                    thought_signature: None,
                 }]),
             },
             Message {
@@ -2214,13 +3309,62 @@ mod tests {
             description: "Run bash".into(),
             input_schema: json!({"type": "object", "properties": {"cmd": {"type": "string"}}}),
         }];
-        let out = translate_tools_to_oai(&tools);
+        let out = translate_tools_to_oai(&tools, false);
         assert_eq!(out.len(), 1);
         assert_eq!(out[0]["type"], "function");
         assert_eq!(out[0]["function"]["name"], "bash");
         assert_eq!(out[0]["function"]["description"], "Run bash");
     }
 
+    #[test]
+    fn test_translate_tools_to_oai_strict_schema() {
+        let tools = vec![ToolDefinition {
+            name: "forget".into(),
+            description: "Forget memories".into(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "query": {"type": "string"},
+                    "scope": {"type": "string", "enum": ["chat", "global"]},
+                    "tags": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "properties": {"name": {"type": "string"}},
+                        }
+                    },
+                    "extra": {"type": "object"}
+                },
+                "required": ["query"]
+            }),
+        }];
+        let out = translate_tools_to_oai(&tools, true);
+        let function = &out[0]["function"];
+        assert_eq!(function["strict"], true);
+        let params = &function["parameters"];
+        assert_eq!(params["additionalProperties"], false);
+        assert_eq!(params["required"].as_array().unwrap().len(), 4);
+        assert_eq!(params["properties"]["query"]["type"], "string");
+        assert_eq!(
+            params["properties"]["scope"],
+            json!({"type": ["string", "null"], "enum": ["chat", "global", null]})
+        );
+        let item = &params["properties"]["tags"]["items"];
+        assert_eq!(item["additionalProperties"], false);
+        assert_eq!(
+            item["properties"]["name"]["type"],
+            json!(["string", "null"])
+        );
+        assert_eq!(
+            params["properties"]["extra"],
+            json!({"type": ["object", "null"]})
+        );
+
+        let loose = translate_tools_to_oai(&tools, false);
+        assert!(loose[0]["function"].get("strict").is_none());
+        assert_eq!(loose[0]["function"]["parameters"], tools[0].input_schema);
+    }
+
     #[test]
     fn test_translate_tools_to_oai_responses() {
         let tools = vec![ToolDefinition {
@@ -2290,7 +3434,9 @@ mod tests {
         let resp = translate_oai_response(oai);
         assert_eq!(resp.stop_reason.as_deref(), Some("tool_use"));
         match &resp.content[0] {
-            ResponseContentBlock::ToolUse { id, name, input } => {
+            ResponseContentBlock::ToolUse {
+                id, name, input, ..
+            } => {
                 assert_eq!(id, "call_1");
                 assert_eq!(name, "bash");
                 assert_eq!(input["command"], "ls");
@@ -2299,6 +3445,102 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_translate_oai_response_from_openai_json() {
+        let body = r#"{
+            "id": "chatcmpl-1",
+            "object": "chat.completion",
+            "choices": [{
+                "index": 0,
+                "message": {
+                    "role": "assistant",
+                    "content": null,
+                    "tool_calls": [
+                        {"id": "call_abc", "type": "function",
+                         "function": {"name": "read_file", "arguments": "{\"path\":\"a.txt\",\"limit\":null}"}},
+                        {"type": "function",
+                         "function": {"name": "bash", "arguments": {"command": "ls"}}}
+                    ]
+                },
+                "finish_reason": "stop"
+            }],
+            "usage": {"prompt_tokens": 12, "completion_tokens": 7, "total_tokens": 19}
+        }"#;
+        let oai: OaiResponse = serde_json::from_str(body).unwrap();
+        let resp = translate_oai_response(oai);
+        assert_eq!(resp.stop_reason.as_deref(), Some("tool_use"));
+        assert_eq!(resp.content.len(), 2);
+        match &resp.content[0] {
+            ResponseContentBlock::ToolUse {
+                id, name, input, ..
+            } => {
+                assert_eq!(id, "call_abc");
+                assert_eq!(name, "read_file");
+                assert_eq!(input, &json!({"path": "a.txt"}));
+            }
+            _ => panic!("Expected ToolUse"),
+        }
+        match &resp.content[1] {
+            ResponseContentBlock::ToolUse {
+                id, name, input, ..
+            } => {
+                assert_eq!(id, "call_1");
+                assert_eq!(name, "bash");
+                assert_eq!(input["command"], "ls");
+            }
+            _ => panic!("Expected ToolUse"),
+        }
+        assert_eq!(resp.usage.unwrap().input_tokens, 12);
+    }
+
+    #[test]
+    fn test_openai_stream_deltas_build_tool_use_response() {
+        let events = [
+            r#"{"choices":[{"delta":{"role":"assistant","content":"Checking."},"finish_reason":null}]}"#,
+            r#"{"choices":[{"delta":{"tool_calls":[{"index":0,"id":"call_9","type":"function","function":{"name":"bash","arguments":""}}]},"finish_reason":null}]}"#,
+            r#"{"choices":[{"delta":{"tool_calls":[{"index":0,"function":{"arguments":"{\"command\":"}}]},"finish_reason":null}]}"#,
+            r#"{"choices":[{"delta":{"tool_calls":[{"index":0,"function":{"arguments":"\"pwd\"}"}}]},"finish_reason":null}]}"#,
+            r#"{"choices":[{"delta":{},"finish_reason":"tool_calls"}]}"#,
+            r#"{"choices":[],"usage":{"prompt_tokens":5,"completion_tokens":3}}"#,
+        ];
+        let mut text = String::new();
+        let mut reasoning_text = String::new();
+        let mut stop_reason = None;
+        let mut usage = None;
+        let mut tool_calls = std::collections::BTreeMap::new();
+        for data in events {
+            process_openai_stream_event(
+                data,
+                None,
+                &mut text,
+                &mut reasoning_text,
+                &mut stop_reason,
+                &mut usage,
+                &mut tool_calls,
+            )
+            .unwrap();
+        }
+
+        let resp =
+            build_openai_stream_response(text, reasoning_text, tool_calls, stop_reason, usage);
+        assert_eq!(resp.stop_reason.as_deref(), Some("tool_use"));
+        match &resp.content[0] {
+            ResponseContentBlock::Text { text } => assert_eq!(text, "Checking."),
+            _ => panic!("Expected Text"),
+        }
+        match &resp.content[1] {
+            ResponseContentBlock::ToolUse {
+                id, name, input, ..
+            } => {
+                assert_eq!(id, "call_9");
+                assert_eq!(name, "bash");
+                assert_eq!(input, &json!({"command": "pwd"}));
+            }
+            _ => panic!("Expected ToolUse"),
+        }
+        assert_eq!(resp.usage.unwrap().output_tokens, 3);
+    }
+
     #[test]
     fn test_translate_oai_response_empty_choices() {
         let oai = OaiResponse {
@@ -2335,6 +3577,16 @@ mod tests {
         };
         let resp = translate_oai_response(oai);
         assert_eq!(resp.stop_reason.as_deref(), Some("max_tokens"));
+
+        assert_eq!(
+            oai_stop_reason(Some("length"), true).as_deref(),
+            Some("max_tokens")
+        );
+        assert_eq!(
+            oai_stop_reason(Some("stop"), true).as_deref(),
+            Some("tool_use")
+        );
+        assert_eq!(oai_stop_reason(None, false).as_deref(), Some("end_turn"));
     }
 
     #[test]
@@ -2410,37 +3662,225 @@ mod tests {
             Some("max_tokens")
         );
         assert_eq!(
-            normalize_stop_reason(Some("stop".into())).as_deref(),
-            Some("end_turn")
+            normalize_stop_reason(Some("stop".into())).as_deref(),
+            Some("end_turn")
+        );
+    }
+
+    #[test]
+    fn test_process_openai_stream_event_collects_reasoning_content() {
+        let data = r#"{"choices":[{"delta":{"reasoning_content":"think","tool_calls":[{"index":0,"id":"c1","function":{"name":"bash","arguments":"{\"command\":\"ls\"}"}}]},"finish_reason":null}],"usage":null}"#;
+        let mut text = String::new();
+        let mut reasoning_text = String::new();
+        let mut stop_reason = None;
+        let mut usage = None;
+        let mut tool_calls = std::collections::BTreeMap::new();
+
+        process_openai_stream_event(
+            data,
+            None,
+            &mut text,
+            &mut reasoning_text,
+            &mut stop_reason,
+            &mut usage,
+            &mut tool_calls,
+        )
+        .unwrap();
+
+        assert!(text.is_empty());
+        assert_eq!(reasoning_text, "think");
+        assert_eq!(stop_reason, None);
+        let call = tool_calls.get(&0).unwrap();
+        assert_eq!(call.id, "c1");
+        assert_eq!(call.name, "bash");
+        assert_eq!(call.input_json, r#"{"command":"ls"}"#);
+    }
+
+    #[test]
+    fn test_anthropic_stream_skips_unknown_events_and_keep_alives() {
+        let stream = concat!(
+            ": keep-alive\n\n",
+            "event: message_start\n",
+            "data: {\"type\":\"message_start\",\"message\":{\"usage\":{\"input_tokens\":3,\"output_tokens\":0}}}\n\n",
+            "event: ping\ndata: {\"type\": \"ping\"}\n\n",
+            "event: vendor_progress\ndata: {\"type\":\"vendor_progress\",\"pct\":50}\n\n",
+            "data: not json at all\n\n",
+            "event: content_block_start\n",
+            "data: {\"type\":\"content_block_start\",\"index\":0,\"content_block\":{\"type\":\"text\",\"text\":\"\"}}\n\n",
+            ": another comment\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"Hello\"}}\n\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"citations_delta\"}}\n\n",
+            "data: {\"type\":\"content_block_stop\",\"index\":0}\n\n",
+            "data: {\"type\":\"message_delta\",\"delta\":{\"stop_reason\":\"end_turn\"}}\n\n",
+            "data: {\"type\":\"message_stop\"}\n\n",
+        );
+        let mut sse = SseEventParser::default();
+        let mut stop_reason = None;
+        let mut usage = None;
+        let mut text_blocks = std::collections::HashMap::new();
+        let mut tool_blocks = std::collections::HashMap::new();
+        let mut ordered_indexes = Vec::new();
+        // Feed in small chunks to exercise events split across reads.
+        let bytes = stream.as_bytes();
+        let mut events = Vec::new();
+        for chunk in bytes.chunks(7) {
+            events.extend(sse.push_chunk(std::str::from_utf8(chunk).unwrap()));
+        }
+        events.extend(sse.finish());
+        for data in events {
+            process_anthropic_stream_event(
+                &data,
+                None,
+                &mut stop_reason,
+                &mut usage,
+                &mut text_blocks,
+                &mut tool_blocks,
+                &mut ordered_indexes,
+            )
+            .unwrap();
+        }
+
+        let response = build_stream_response(
+            ordered_indexes,
+            text_blocks,
+            tool_blocks,
+            stop_reason,
+            usage,
+        );
+        assert_eq!(response.stop_reason.as_deref(), Some("end_turn"));
+        match &response.content[0] {
+            ResponseContentBlock::Text { text } => assert_eq!(text, "Hello"),
+            _ => panic!("Expected Text"),
+        }
+    }
+
+    #[test]
+    fn test_anthropic_request_body_marks_cache_breakpoints() {
+        let request = MessagesRequest {
+            model: "claude-test".into(),
+            max_tokens: 100,
+            system: "You are helpful.".into(),
+            messages: vec![
+                Message {
+                    role: "user".into(),
+                    content: MessageContent::Text("first".into()),
+                },
+                Message {
+                    role: "assistant".into(),
+                    content: MessageContent::Text("reply".into()),
+                },
+                Message {
+                    role: "user".into(),
+                    content: MessageContent::Text("second".into()),
+                },
+            ],
+            tools: Some(vec![
+                ToolDefinition {
+                    name: "bash".into(),
+                    description: "Run".into(),
+                    input_schema: json!({"type": "object"}),
+                },
+                ToolDefinition {
+                    name: "read_file".into(),
+                    description: "Read".into(),
+                    input_schema: json!({"type": "object"}),
+                },
+            ]),
+            stream: None,
+        };
+
+        let body = anthropic_request_body(&request, true);
+        let ephemeral = json!({"type": "ephemeral"});
+        assert_eq!(body["system"][0]["text"], "You are helpful.");
+        assert_eq!(body["system"][0]["cache_control"], ephemeral);
+        assert!(body["tools"][0].get("cache_control").is_none());
+        assert_eq!(body["tools"][1]["cache_control"], ephemeral);
+        assert_eq!(body["messages"][0]["content"], "first");
+        assert_eq!(body["messages"][2]["content"][0]["text"], "second");
+        assert_eq!(
+            body["messages"][2]["content"][0]["cache_control"],
+            ephemeral
         );
+
+        let plain = anthropic_request_body(&request, false);
+        assert_eq!(plain["system"], "You are helpful.");
+        assert!(!plain.to_string().contains("cache_control"));
     }
 
     #[test]
-    fn test_process_openai_stream_event_collects_reasoning_content() {
-        let data = r#"{"choices":[{"delta":{"reasoning_content":"think","tool_calls":[{"index":0,"id":"c1","function":{"name":"bash","arguments":"{\"command\":\"ls\"}"}}]},"finish_reason":null}],"usage":null}"#;
-        let mut text = String::new();
-        let mut reasoning_text = String::new();
+    fn test_anthropic_stream_keeps_cache_usage_from_message_start() {
+        let mut stop_reason = None;
+        let mut usage = None;
+        for data in [
+            r#"{"type":"message_start","message":{"usage":{"input_tokens":12,"output_tokens":1,"cache_creation_input_tokens":0,"cache_read_input_tokens":900}}}"#,
+            r#"{"type":"message_delta","delta":{"stop_reason":"end_turn"},"usage":{"output_tokens":42}}"#,
+        ] {
+            process_anthropic_stream_event(
+                data,
+                None,
+                &mut stop_reason,
+                &mut usage,
+                &mut std::collections::HashMap::new(),
+                &mut std::collections::HashMap::new(),
+                &mut Vec::new(),
+            )
+            .unwrap();
+        }
+        let usage = usage.unwrap();
+        assert_eq!(usage.input_tokens, 12);
+        assert_eq!(usage.output_tokens, 42);
+        assert_eq!(usage.cache_read_input_tokens, 900);
+        assert_eq!(usage.cache_creation_input_tokens, 0);
+    }
+
+    #[test]
+    fn test_stream_error_events_fail_the_stream() {
         let mut stop_reason = None;
         let mut usage = None;
+        let err = process_anthropic_stream_event(
+            r#"{"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#,
+            None,
+            &mut stop_reason,
+            &mut usage,
+            &mut std::collections::HashMap::new(),
+            &mut std::collections::HashMap::new(),
+            &mut Vec::new(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("overloaded_error: Overloaded"));
+
+        let mut text = String::new();
+        let mut reasoning_text = String::new();
         let mut tool_calls = std::collections::BTreeMap::new();
+        for data in [
+            r#"{"id":"x","object":"chat.completion.chunk","choices":[]}"#,
+            r#"{"object":"keepalive"}"#,
+            r#"{"choices":[{"delta":{"content":"hi"},"finish_reason":null}],"error":null}"#,
+        ] {
+            process_openai_stream_event(
+                data,
+                None,
+                &mut text,
+                &mut reasoning_text,
+                &mut stop_reason,
+                &mut usage,
+                &mut tool_calls,
+            )
+            .unwrap();
+        }
+        assert_eq!(text, "hi");
 
-        process_openai_stream_event(
-            data,
+        let err = process_openai_stream_event(
+            r#"{"error":{"message":"rate limited","code":429}}"#,
             None,
             &mut text,
             &mut reasoning_text,
             &mut stop_reason,
             &mut usage,
             &mut tool_calls,
-        );
-
-        assert!(text.is_empty());
-        assert_eq!(reasoning_text, "think");
-        assert_eq!(stop_reason, None);
-        let call = tool_calls.get(&0).unwrap();
-        assert_eq!(call.id, "c1");
-        assert_eq!(call.name, "bash");
-        assert_eq!(call.input_json, r#"{"command":"ls"}"#);
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("rate limited"));
     }
 
     #[test]
@@ -2587,7 +4027,9 @@ mod tests {
         );
         assert_eq!(resp.stop_reason.as_deref(), Some("tool_use"));
         match &resp.content[0] {
-            ResponseContentBlock::ToolUse { id, name, input } => {
+            ResponseContentBlock::ToolUse {
+                id, name, input, ..
+            } => {
                 assert_eq!(id, "call_1");
                 assert_eq!(name, "bash");
                 assert_eq!(input["command"], "ls");
@@ -2894,6 +4336,7 @@ mod tests {
                     id: "t1".into(),
                     name: "bash".into(),
                     input: json!({}),
+                    thought_signature: None,
                 }]),
             },
             Message {
@@ -2951,6 +4394,7 @@ mod tests {
                     id: "t1".into(),
                     name: "bash".into(),
                     input: json!({}),
+                    thought_signature: None,
                 }]),
             },
             Message {
@@ -3120,4 +4564,595 @@ data: [DONE]
             _ => panic!("Expected text block"),
         }
     }
+
+    /// Serve one canned response per status in `statuses`, in order, and
+    /// report how many requests arrived.
+    fn spawn_status_server(statuses: Vec<u16>) -> (String, std::thread::JoinHandle<usize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/v1/messages", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let mut served = 0;
+            for status in statuses {
+                let Ok((mut stream, _)) = listener.accept() else {
+                    break;
+                };
+                stream
+                    .set_read_timeout(Some(Duration::from_secs(2)))
+                    .unwrap();
+                let mut buf = [0u8; 8192];
+                let _ = stream.read(&mut buf);
+                let body = format!(r#"{{"status":{status}}}"#);
+                let response = format!(
+                    "HTTP/1.1 {status} X\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes());
+                let _ = stream.flush();
+                served += 1;
+            }
+            served
+        });
+        (url, server)
+    }
+
+    #[test]
+    fn test_is_retryable_status() {
+        for code in [429, 500, 502, 503, 529] {
+            assert!(is_retryable_status(
+                reqwest::StatusCode::from_u16(code).unwrap()
+            ));
+        }
+        for code in [200, 400, 401, 403, 404, 504] {
+            assert!(!is_retryable_status(
+                reqwest::StatusCode::from_u16(code).unwrap()
+            ));
+        }
+    }
+
+    #[test]
+    fn test_retry_backoff_grows_with_jitter_and_caps() {
+        let base = Duration::from_secs(1);
+        for attempt in 1..=4 {
+            let exp = base * (1 << (attempt - 1));
+            let delay = retry_backoff(base, attempt);
+            assert!(delay >= exp && delay <= exp + exp / 2, "{delay:?}");
+        }
+        assert_eq!(retry_backoff(base, 30), LLM_RETRY_MAX_DELAY);
+    }
+
+    #[tokio::test]
+    async fn test_send_with_retries_retries_transient_errors() {
+        let (url, server) = spawn_status_server(vec![529, 503, 200]);
+        let http = reqwest::Client::new();
+        let response = send_with_retries_from(3, Duration::from_millis(1), || http.post(&url))
+            .await
+            .unwrap();
+        assert_eq!(response.status().as_u16(), 200);
+        assert_eq!(server.join().unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_send_with_retries_gives_up_after_max_retries() {
+        let (url, server) = spawn_status_server(vec![429, 429, 429]);
+        let http = reqwest::Client::new();
+        let response = send_with_retries_from(2, Duration::from_millis(1), || http.post(&url))
+            .await
+            .unwrap();
+        assert_eq!(response.status().as_u16(), 429);
+        assert_eq!(server.join().unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_anthropic_fails_fast_on_client_error() {
+        let (url, server) = spawn_status_server(vec![400]);
+        let mut config = Config::test_defaults();
+        config.llm_base_url = Some(url);
+        let provider = AnthropicProvider::new(&config);
+        let err = provider
+            .send_message("sys", vec![], None)
+            .await
+            .unwrap_err();
+        // A retry would hit the closed listener and fail with a connect error.
+        assert!(err.to_string().contains("HTTP 400"), "{err}");
+        assert_eq!(server.join().unwrap(), 1);
+    }
+
+    // -----------------------------------------------------------------------
+    // Gemini
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_gemini_request_body_maps_messages_and_tools() {
+        let messages = vec![
+            Message {
+                role: "user".into(),
+                content: MessageContent::Text("list files".into()),
+            },
+            Message {
+                role: "assistant".into(),
+                content: MessageContent::Blocks(vec![
+                    ContentBlock::Text {
+                        text: "Sure.".into(),
+                    },
+                    ContentBlock::ToolUse {
+                        id: "call_1".into(),
+                        name: "bash".into(),
+                        input: json!({"command": "ls"}),
+                        thought_signature: None,
+                    },
+                ]),
+            },
+            Message {
+                role: "user".into(),
+                content: MessageContent::Blocks(vec![ContentBlock::ToolResult {
+                    tool_use_id: "call_1".into(),
+                    content: "a.txt".into(),
+                    is_error: None,
+                }]),
+            },
+            Message {
+                role: "user".into(),
+                content: MessageContent::Blocks(vec![ContentBlock::Image {
+                    source: ImageSource {
+                        source_type: "base64".into(),
+                        media_type: "image/png".into(),
+                        data: "iVBO".into(),
+                    },
+                }]),
+            },
+        ];
+        let tools = vec![
+            ToolDefinition {
+                name: "bash".into(),
+                description: "Run bash".into(),
+                input_schema: json!({
+                    "type": "object",
+                    "properties": {
+                        "command": {"type": "string"},
+                        "timeout_secs": {"type": ["integer", "null"], "default": 120}
+                    },
+                    "required": ["command"],
+                    "additionalProperties": false
+                }),
+            },
+            ToolDefinition {
+                name: "todo_read".into(),
+                description: "Read todos".into(),
+                input_schema: json!({"type": "object", "properties": {}}),
+            },
+        ];
+
+        let body = gemini_request_body("Be brief.", &messages, Some(&tools), 512);
+        assert_eq!(body["systemInstruction"]["parts"][0]["text"], "Be brief.");
+        assert_eq!(body["generationConfig"]["maxOutputTokens"], 512);
+
+        let contents = body["contents"].as_array().unwrap();
+        assert_eq!(contents.len(), 3, "consecutive user turns are merged");
+        assert_eq!(contents[0]["role"], "user");
+        assert_eq!(contents[1]["role"], "model");
+        assert_eq!(contents[1]["parts"][0]["text"], "Sure.");
+        assert_eq!(
+            contents[1]["parts"][1]["functionCall"],
+            json!({"name": "bash", "args": {"command": "ls"}})
+        );
+        assert_eq!(contents[2]["role"], "user");
+        assert_eq!(
+            contents[2]["parts"][0]["functionResponse"],
+            json!({"name": "bash", "response": {"content": "a.txt"}})
+        );
+        assert_eq!(
+            contents[2]["parts"][1]["inlineData"],
+            json!({"mimeType": "image/png", "data": "iVBO"})
+        );
+
+        let decls = body["tools"][0]["functionDeclarations"].as_array().unwrap();
+        assert_eq!(decls[0]["name"], "bash");
+        let params = &decls[0]["parameters"];
+        assert!(params.get("additionalProperties").is_none());
+        assert_eq!(
+            params["properties"]["timeout_secs"],
+            json!({"type": "integer", "nullable": true})
+        );
+        assert_eq!(params["required"], json!(["command"]));
+        assert!(decls[1].get("parameters").is_none());
+    }
+
+    #[test]
+    fn test_gemini_parses_function_call_response() {
+        let body = json!({
+            "candidates": [{
+                "content": {
+                    "role": "model",
+                    "parts": [
+                        {"text": "thinking it over", "thought": true},
+                        {"text": "Let me check."},
+                        {"functionCall": {"name": "read_file", "args": {"path": "a.txt"}}}
+                    ]
+                },
+                "finishReason": "STOP"
+            }],
+            "usageMetadata": {"promptTokenCount": 20, "candidatesTokenCount": 9}
+        });
+        let mut state = GeminiStreamState::default();
+        state.push(&body, None).unwrap();
+        let resp = state.finish();
+
+        assert_eq!(resp.stop_reason.as_deref(), Some("tool_use"));
+        assert_eq!(resp.content.len(), 2);
+        match &resp.content[0] {
+            ResponseContentBlock::Text { text } => assert_eq!(text, "Let me check."),
+            _ => panic!("Expected Text"),
+        }
+        match &resp.content[1] {
+            ResponseContentBlock::ToolUse {
+                id, name, input, ..
+            } => {
+                assert!(id.starts_with("call_"));
+                assert_eq!(name, "read_file");
+                assert_eq!(input, &json!({"path": "a.txt"}));
+            }
+            _ => panic!("Expected ToolUse"),
+        }
+        let usage = resp.usage.unwrap();
+        assert_eq!((usage.input_tokens, usage.output_tokens), (20, 9));
+    }
+
+    #[test]
+    fn test_gemini_truncated_function_call_stops_on_max_tokens() {
+        let body = json!({
+            "candidates": [{
+                "content": {
+                    "role": "model",
+                    "parts": [
+                        {"functionCall": {"name": "write_file", "args": {"path": "a.txt"}}}
+                    ]
+                },
+                "finishReason": "MAX_TOKENS"
+            }]
+        });
+        let mut state = GeminiStreamState::default();
+        state.push(&body, None).unwrap();
+        let resp = state.finish();
+        assert_eq!(resp.stop_reason.as_deref(), Some("max_tokens"));
+    }
+
+    #[test]
+    fn test_gemini_thought_signature_round_trips_through_history() {
+        let body = json!({
+            "candidates": [{
+                "content": {
+                    "role": "model",
+                    "parts": [{
+                        "functionCall": {"name": "read_file", "args": {"path": "a.txt"}},
+                        "thoughtSignature": "sig-abc"
+                    }]
+                },
+                "finishReason": "STOP"
+            }]
+        });
+        let mut state = GeminiStreamState::default();
+        state.push(&body, None).unwrap();
+        let resp = state.finish();
+        let Some(ResponseContentBlock::ToolUse {
+            id,
+            name,
+            input,
+            thought_signature,
+        }) = resp.content.into_iter().next()
+        else {
+            panic!("Expected ToolUse");
+        };
+        assert_eq!(thought_signature.as_deref(), Some("sig-abc"));
+
+        let messages = vec![Message {
+            role: "assistant".into(),
+            content: MessageContent::Blocks(vec![ContentBlock::ToolUse {
+                id,
+                name,
+                input,
+                thought_signature,
+            }]),
+        }];
+        let contents = translate_messages_to_gemini(&messages);
+        assert_eq!(contents[0]["parts"][0]["thoughtSignature"], "sig-abc");
+
+        let request = MessagesRequest {
+            model: "claude-test".into(),
+            max_tokens: 100,
+            system: String::new(),
+            messages,
+            tools: None,
+            stream: None,
+        };
+        let body = anthropic_request_body(&request, false);
+        assert!(body["messages"][0]["content"][0]
+            .get("thought_signature")
+            .is_none());
+    }
+
+    #[test]
+    fn test_gemini_stream_chunks_accumulate_text() {
+        let stream = concat!(
+            "data: {\"candidates\":[{\"content\":{\"role\":\"model\",\"parts\":[{\"text\":\"Hel\"}]}}]}\r\n\r\n",
+            "data: {\"candidates\":[{\"content\":{\"role\":\"model\",\"parts\":[{\"text\":\"lo\"}]},\"finishReason\":\"MAX_TOKENS\"}],",
+            "\"usageMetadata\":{\"promptTokenCount\":4,\"candidatesTokenCount\":2}}\r\n\r\n",
+        );
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut sse = SseEventParser::default();
+        let mut state = GeminiStreamState::default();
+        for data in sse.push_chunk(stream).into_iter().chain(sse.finish()) {
+            state.push_event(&data, Some(&tx)).unwrap();
+        }
+        let resp = state.finish();
+
+        assert_eq!(resp.stop_reason.as_deref(), Some("max_tokens"));
+        match &resp.content[..] {
+            [ResponseContentBlock::Text { text }] => assert_eq!(text, "Hello"),
+            other => panic!("unexpected content: {other:?}"),
+        }
+        assert_eq!(rx.try_recv().unwrap(), "Hel");
+        assert_eq!(rx.try_recv().unwrap(), "lo");
+    }
+
+    #[test]
+    fn test_gemini_error_and_model_url() {
+        let err = gemini_api_error(
+            reqwest::StatusCode::BAD_REQUEST,
+            r#"{"error":{"code":400,"message":"API key not valid","status":"INVALID_ARGUMENT"}}"#,
+        );
+        assert_eq!(
+            err.to_string(),
+            "LLM API error: INVALID_ARGUMENT: API key not valid"
+        );
+
+        let mut config = Config::test_defaults();
+        config.llm_provider = "gemini".into();
+        config.model = "models/gemini-2.5-flash".into();
+        let provider = GeminiProvider::new(&config);
+        assert_eq!(
+            provider.model_url(&config.model, "generateContent"),
+            "https://generativelanguage.googleapis.com/v1beta/models/gemini-2.5-flash:generateContent"
+        );
+    }
+
+    // -----------------------------------------------------------------------
+    // Fallback chain
+    // -----------------------------------------------------------------------
+
+    struct ScriptedLlm {
+        name: &'static str,
+        error: Option<fn() -> MicroClawError>,
+        stream_before_error: bool,
+        calls: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    impl ScriptedLlm {
+        fn new(name: &'static str, error: Option<fn() -> MicroClawError>) -> Self {
+            Self {
+                name,
+                error,
+                stream_before_error: false,
+                calls: Default::default(),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl LlmProvider for ScriptedLlm {
+        async fn send_message(
+            &self,
+            _system: &str,
+            _messages: Vec<Message>,
+            _tools: Option<Vec<ToolDefinition>>,
+        ) -> Result<MessagesResponse, MicroClawError> {
+            self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            if let Some(error) = self.error {
+                return Err(error());
+            }
+            Ok(MessagesResponse {
+                content: vec![ResponseContentBlock::Text {
+                    text: format!("from {}", self.name),
+                }],
+                stop_reason: Some("end_turn".into()),
+                usage: None,
+                served_by: None,
+            })
+        }
+
+        async fn send_message_stream(
+            &self,
+            system: &str,
+            messages: Vec<Message>,
+            tools: Option<Vec<ToolDefinition>>,
+            text_tx: Option<&UnboundedSender<String>>,
+        ) -> Result<MessagesResponse, MicroClawError> {
+            if self.stream_before_error {
+                if let Some(tx) = text_tx {
+                    let _ = tx.send("partial".into());
+                }
+            }
+            let response = self.send_message(system, messages, tools).await?;
+            if let Some(tx) = text_tx {
+                let _ = tx.send(format!("from {}", self.name));
+            }
+            Ok(response)
+        }
+    }
+
+    fn fallback_chain(primary: ScriptedLlm, fallback: ScriptedLlm) -> FallbackProvider {
+        FallbackProvider {
+            primary_label: "primary".into(),
+            primary: Box::new(primary),
+            fallbacks: vec![FallbackEntry {
+                label: "fallback".into(),
+                served_by: ServedBy {
+                    provider: "fallback-provider".into(),
+                    model: "fallback-model".into(),
+                },
+                provider: Box::new(fallback),
+            }],
+        }
+    }
+
+    fn overloaded() -> MicroClawError {
+        MicroClawError::LlmApi("HTTP 503 Service Unavailable: upstream overloaded".into())
+    }
+
+    fn response_text(resp: &MessagesResponse) -> &str {
+        match &resp.content[..] {
+            [ResponseContentBlock::Text { text }] => text,
+            other => panic!("unexpected content: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_is_provider_health_failure_ignores_request_errors() {
+        assert!(is_provider_health_failure(&MicroClawError::RateLimited));
+        assert!(is_provider_health_failure(&MicroClawError::LlmApi(
+            "HTTP 504 Gateway Timeout: upstream".into()
+        )));
+        assert!(is_provider_health_failure(&MicroClawError::LlmApi(
+            "HTTP 401 Unauthorized: invalid api key".into()
+        )));
+        assert!(is_provider_health_failure(&MicroClawError::LlmApi(
+            "authentication_error: invalid x-api-key".into()
+        )));
+        assert!(is_provider_health_failure(&MicroClawError::LlmApi(
+            "permission_error: key lacks access to this model".into()
+        )));
+        assert!(is_provider_health_failure(&MicroClawError::LlmApi(
+            "api_error: Internal server error".into()
+        )));
+        assert!(is_provider_health_failure(&MicroClawError::LlmApi(
+            "UNAUTHENTICATED: API key not valid".into()
+        )));
+        assert!(!is_provider_health_failure(&MicroClawError::LlmApi(
+            "HTTP 400 Bad Request: invalid tool schema".into()
+        )));
+        assert!(!is_provider_health_failure(&MicroClawError::LlmApi(
+            "invalid_request_error: max_tokens: must be positive".into()
+        )));
+        assert!(!is_provider_health_failure(&MicroClawError::LlmApi(
+            "context_length_exceeded".into()
+        )));
+    }
+
+    #[test]
+    fn test_is_retryable_llm_error() {
+        assert!(is_retryable_llm_error(&MicroClawError::RateLimited));
+        assert!(is_retryable_llm_error(&overloaded()));
+        assert!(is_retryable_llm_error(&MicroClawError::LlmApi(
+            "overloaded_error: Overloaded".into()
+        )));
+        assert!(is_retryable_llm_error(&MicroClawError::LlmApi(
+            "RESOURCE_EXHAUSTED: Quota exceeded".into()
+        )));
+        assert!(!is_retryable_llm_error(&MicroClawError::LlmApi(
+            "HTTP 400 Bad Request: invalid tool schema".into()
+        )));
+        assert!(!is_retryable_llm_error(&MicroClawError::LlmApi(
+            "authentication_error: invalid x-api-key".into()
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_fallback_provider_switches_on_retryable_error() {
+        let primary = ScriptedLlm::new("primary", Some(overloaded));
+        let fallback = ScriptedLlm::new("fallback", None);
+        let (primary_calls, fallback_calls) = (primary.calls.clone(), fallback.calls.clone());
+        let chain = fallback_chain(primary, fallback);
+
+        let resp = chain
+            .send_message_with_model("sys", vec![], None, Some("primary-model"))
+            .await
+            .unwrap();
+        assert_eq!(response_text(&resp), "from fallback");
+        let served_by = resp.served_by.expect("fallback response is tagged");
+        assert_eq!(served_by.provider, "fallback-provider");
+        assert_eq!(served_by.model, "fallback-model");
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let resp = chain
+            .send_message_stream("sys", vec![], None, Some(&tx))
+            .await
+            .unwrap();
+        assert_eq!(response_text(&resp), "from fallback");
+        assert_eq!(
+            resp.served_by.map(|s| s.model).as_deref(),
+            Some("fallback-model")
+        );
+        assert_eq!(rx.try_recv().unwrap(), "from fallback");
+        assert!(rx.try_recv().is_err());
+
+        let chain = fallback_chain(
+            ScriptedLlm::new("primary", None),
+            ScriptedLlm::new("fallback", None),
+        );
+        let resp = chain.send_message("sys", vec![], None).await.unwrap();
+        assert!(resp.served_by.is_none());
+
+        use std::sync::atomic::Ordering;
+        assert_eq!(primary_calls.load(Ordering::SeqCst), 2);
+        assert_eq!(fallback_calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_fallback_provider_keeps_non_retryable_and_streamed_errors() {
+        let primary = ScriptedLlm::new(
+            "primary",
+            Some(|| MicroClawError::LlmApi("invalid_request_error: bad input".into())),
+        );
+        let fallback = ScriptedLlm::new("fallback", None);
+        let fallback_calls = fallback.calls.clone();
+        let chain = fallback_chain(primary, fallback);
+        let err = chain.send_message("sys", vec![], None).await.unwrap_err();
+        assert!(err.to_string().contains("bad input"));
+        assert_eq!(fallback_calls.load(std::sync::atomic::Ordering::SeqCst), 0);
+
+        let mut primary = ScriptedLlm::new("primary", Some(overloaded));
+        primary.stream_before_error = true;
+        let fallback = ScriptedLlm::new("fallback", None);
+        let fallback_calls = fallback.calls.clone();
+        let chain = fallback_chain(primary, fallback);
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let err = chain
+            .send_message_stream("sys", vec![], None, Some(&tx))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("HTTP 503"));
+        assert_eq!(rx.try_recv().unwrap(), "partial");
+        assert!(rx.try_recv().is_err());
+        assert_eq!(fallback_calls.load(std::sync::atomic::Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_fallback_config_inherits_credentials_only_for_same_provider() {
+        let mut config = Config::test_defaults();
+        config.llm_provider = "anthropic".into();
+        config.api_key = "primary-key".into();
+        config.llm_base_url = Some("https://proxy.example".into());
+        config.llm_fallbacks = vec![crate::config::LlmFallback {
+            provider: "anthropic".into(),
+            model: "claude-haiku-4-5".into(),
+            ..Default::default()
+        }];
+
+        let same = fallback_config(&config, &config.llm_fallbacks[0]);
+        assert_eq!(same.api_key, "primary-key");
+        assert_eq!(same.llm_base_url.as_deref(), Some("https://proxy.example"));
+        assert_eq!(same.model, "claude-haiku-4-5");
+        assert!(same.llm_fallbacks.is_empty());
+
+        let other = crate::config::LlmFallback {
+            provider: "openai".into(),
+            model: "gpt-5.2".into(),
+            api_key: Some("sk-other".into()),
+            llm_base_url: None,
+        };
+        let other = fallback_config(&config, &other);
+        assert_eq!(other.llm_provider, "openai");
+        assert_eq!(other.api_key, "sk-other");
+        assert!(other.llm_base_url.is_none());
+    }
 }
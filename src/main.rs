@@ -537,10 +537,15 @@ async fn main() -> anyhow::Result<()> {
     migrate_legacy_runtime_layout(&data_root_dir, Path::new(&runtime_data_dir));
     migrate_legacy_skills_dir(&legacy_skills_dir, Path::new(&skills_data_dir));
 
+    #[cfg(feature = "otel-tracing")]
+    let trace_layer = microclaw::otlp_tracing::build_layer(&config)
+        .map(|layer| Box::new(layer) as logging::ExtraLayer);
+    #[cfg(not(feature = "otel-tracing"))]
+    let trace_layer: Option<logging::ExtraLayer> = None;
     if std::env::var("MICROCLAW_GATEWAY").is_ok() {
-        logging::init_logging(&runtime_data_dir)?;
+        logging::init_logging_with_layer(&runtime_data_dir, trace_layer)?;
     } else {
-        logging::init_console_logging();
+        logging::init_console_logging_with_layer(trace_layer);
     }
 
     builtin_skills::ensure_builtin_skills(Path::new(&skills_data_dir))?;
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, Mutex as StdMutex};
+use std::sync::{Arc, Mutex as StdMutex, RwLock as StdRwLock, Weak};
 use std::time::{Duration, Instant};
 
 use serde::{Deserialize, Serialize};
@@ -9,6 +9,8 @@ use tokio::process::{Child, Command};
 use tokio::sync::{Mutex, Semaphore};
 use tracing::{error, info, warn};
 
+use crate::llm::SseEventParser;
+
 const DEFAULT_PROTOCOL_VERSION: &str = "2025-11-05";
 const DEFAULT_MAX_RETRIES: u32 = 2;
 const DEFAULT_HEALTH_INTERVAL_SECS: u64 = 60;
@@ -137,6 +139,9 @@ struct McpHttpInner {
     endpoint: String,
     headers: HashMap<String, String>,
     next_id: u64,
+    /// `Mcp-Session-Id` issued by the server on `initialize`, echoed on every
+    /// later request.
+    session_id: Option<String>,
 }
 
 enum McpTransport {
@@ -246,6 +251,98 @@ pub struct McpServer {
     inflight_limiter: Arc<Semaphore>,
     queue_wait: Duration,
     rate_limiter: StdMutex<FixedWindowRateLimiter>,
+    health: StdMutex<McpProbeState>,
+}
+
+/// Outcome of the most recent health probe (or the initial connect).
+#[derive(Debug, Clone, Default)]
+struct McpProbeState {
+    reachable: bool,
+    last_success_at: Option<chrono::DateTime<chrono::Utc>>,
+    last_error: Option<String>,
+}
+
+/// Point-in-time health of one MCP server, as reported by `/api/health`.
+#[derive(Debug, Clone, Serialize)]
+pub struct McpServerHealth {
+    pub name: String,
+    pub reachable: bool,
+    pub last_success_at: Option<String>,
+    pub last_error: Option<String>,
+    pub tool_count: usize,
+    pub protocol_version: String,
+}
+
+/// Summary object for the health endpoint: per-server entries plus counts.
+pub fn mcp_health_json(servers: &[McpServerHealth]) -> serde_json::Value {
+    serde_json::json!({
+        "total": servers.len(),
+        "reachable": servers.iter().filter(|s| s.reachable).count(),
+        "servers": servers,
+    })
+}
+
+const MCP_SESSION_ID_HEADER: &str = "mcp-session-id";
+const HTTP_SESSION_EXPIRED_ERROR: &str = "HTTP MCP session expired";
+
+fn session_id_from_headers(headers: &reqwest::header::HeaderMap) -> Option<String> {
+    headers
+        .get(MCP_SESSION_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+}
+
+fn is_session_expired_error(err: &str) -> bool {
+    err.starts_with(HTTP_SESSION_EXPIRED_ERROR)
+}
+
+/// Read an SSE response stream until the JSON-RPC response for `request_id`
+/// arrives, skipping notifications and server requests sent before it.
+async fn read_sse_response(
+    response: reqwest::Response,
+    request_id: u64,
+) -> Result<serde_json::Value, String> {
+    use futures_util::StreamExt;
+
+    let mut byte_stream = response.bytes_stream();
+    let mut sse = SseEventParser::default();
+    while let Some(chunk) = byte_stream.next().await {
+        let chunk = chunk.map_err(|e| format!("Failed to read HTTP MCP event stream: {e}"))?;
+        for data in sse.push_chunk(&String::from_utf8_lossy(&chunk)) {
+            if let Some(message) = jsonrpc_response_for_id(&data, request_id) {
+                return Ok(message);
+            }
+        }
+    }
+    for data in sse.finish() {
+        if let Some(message) = jsonrpc_response_for_id(&data, request_id) {
+            return Ok(message);
+        }
+    }
+    Err(format!(
+        "HTTP MCP event stream ended without a response to request {request_id}"
+    ))
+}
+
+/// The JSON-RPC response to `request_id` in one SSE event's data, which may
+/// hold a single message or a batch.
+fn jsonrpc_response_for_id(data: &str, request_id: u64) -> Option<serde_json::Value> {
+    let value: serde_json::Value = serde_json::from_str(data).ok()?;
+    let matches = |message: &serde_json::Value| {
+        message.get("method").is_none()
+            && match message.get("id") {
+                Some(serde_json::Value::Number(n)) => n.as_u64() == Some(request_id),
+                Some(serde_json::Value::String(s)) => s == &request_id.to_string(),
+                _ => false,
+            }
+    };
+    match value {
+        serde_json::Value::Array(batch) => batch.into_iter().find(|m| matches(m)),
+        message if matches(&message) => Some(message),
+        _ => None,
+    }
 }
 
 /// Resolve a command name to its full path. On Windows, also checks for
@@ -301,6 +398,7 @@ fn spawn_stdio_inner(spec: &McpStdioSpawnSpec, server_name: &str) -> Result<McpS
     cmd.stdin(std::process::Stdio::piped());
     cmd.stdout(std::process::Stdio::piped());
     cmd.stderr(std::process::Stdio::null());
+    cmd.kill_on_drop(true);
 
     let mut child = cmd
         .spawn()
@@ -318,6 +416,33 @@ fn spawn_stdio_inner(spec: &McpStdioSpawnSpec, server_name: &str) -> Result<McpS
     })
 }
 
+/// Extra attempts at `notifications/initialized` after the first one fails.
+const INITIALIZED_NOTIFICATION_RETRIES: u32 = 1;
+
+/// The step of the MCP handshake that failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum McpInitFailure {
+    /// The `initialize` request failed; the server was never set up.
+    Initialize(String),
+    /// `initialize` succeeded but `notifications/initialized` could not be
+    /// delivered, leaving the server half-initialized.
+    InitializedNotification(String),
+}
+
+impl McpInitFailure {
+    fn describe(&self, server: &str) -> String {
+        match self {
+            McpInitFailure::Initialize(e) => {
+                format!("MCP server '{server}' failed to initialize: {e}")
+            }
+            McpInitFailure::InitializedNotification(e) => format!(
+                "MCP server '{server}' accepted initialize but notifications/initialized failed \
+                 ({e}); treating the half-initialized connection as failed"
+            ),
+        }
+    }
+}
+
 impl McpServer {
     pub async fn connect(
         name: &str,
@@ -386,6 +511,7 @@ impl McpServer {
                         endpoint: config.endpoint.clone(),
                         headers: config.headers.clone(),
                         next_id: 1,
+                        session_id: None,
                     }))),
                     None,
                 )
@@ -414,10 +540,12 @@ impl McpServer {
             inflight_limiter: Arc::new(Semaphore::new(max_concurrent_requests as usize)),
             queue_wait,
             rate_limiter: StdMutex::new(FixedWindowRateLimiter::new(rate_limit_per_minute)),
+            health: StdMutex::new(McpProbeState::default()),
         };
 
         server.initialize_connection().await?;
         let _ = server.refresh_tools_cache(true).await?;
+        server.record_probe(&Ok(()));
 
         Ok(server)
     }
@@ -650,9 +778,34 @@ impl McpServer {
             *guard = negotiated;
         }
 
-        self.send_notification_stdio_once("notifications/initialized", None)
-            .await?;
-        Ok(())
+        self.confirm_initialized().await
+    }
+
+    /// Send `notifications/initialized`, retrying once. A server that answered
+    /// `initialize` but never got this notification is half-initialized and may
+    /// reject later calls, so a failure here fails the whole connection.
+    async fn confirm_initialized(&self) -> Result<(), String> {
+        let mut last_err = String::new();
+        for attempt in 0..=INITIALIZED_NOTIFICATION_RETRIES {
+            if attempt > 0 {
+                tokio::time::sleep(Duration::from_millis(200)).await;
+            }
+            match self
+                .send_notification("notifications/initialized", None)
+                .await
+            {
+                Ok(()) => return Ok(()),
+                Err(e) => {
+                    warn!(
+                        "MCP server '{}' notifications/initialized failed (attempt {}): {e}",
+                        self.name,
+                        attempt + 1
+                    );
+                    last_err = e;
+                }
+            }
+        }
+        Err(McpInitFailure::InitializedNotification(last_err).describe(&self.name))
     }
 
     async fn send_request_stdio_with_retries(
@@ -697,6 +850,55 @@ impl McpServer {
         &self,
         method: &str,
         params: Option<serde_json::Value>,
+    ) -> Result<serde_json::Value, String> {
+        match self.send_request_http_once(method, params.clone()).await {
+            Err(e) if method != "initialize" && is_session_expired_error(&e) => {
+                warn!(
+                    "MCP server '{}' session expired; re-initializing and retrying {method}",
+                    self.name
+                );
+                self.initialize_http_session().await?;
+                self.send_request_http_once(method, params).await
+            }
+            result => result,
+        }
+    }
+
+    /// Start a new session after the server forgot the previous one.
+    async fn initialize_http_session(&self) -> Result<(), String> {
+        let params = serde_json::json!({
+            "protocolVersion": self.requested_protocol,
+            "capabilities": {},
+            "clientInfo": {
+                "name": "microclaw",
+                "version": env!("CARGO_PKG_VERSION")
+            }
+        });
+
+        let result = self
+            .send_request_http_once("initialize", Some(params))
+            .await?;
+        let negotiated = result
+            .get("protocolVersion")
+            .and_then(|v| v.as_str())
+            .unwrap_or(&self.requested_protocol)
+            .to_string();
+
+        {
+            let mut guard = self
+                .negotiated_protocol
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            *guard = negotiated;
+        }
+
+        self.confirm_initialized().await
+    }
+
+    async fn send_request_http_once(
+        &self,
+        method: &str,
+        params: Option<serde_json::Value>,
     ) -> Result<serde_json::Value, String> {
         let inner = match &self.transport {
             McpTransport::StreamableHttp(inner) => inner,
@@ -708,6 +910,9 @@ impl McpServer {
         let mut inner = inner.lock().await;
         let id = inner.next_id;
         inner.next_id += 1;
+        if method == "initialize" {
+            inner.session_id = None;
+        }
 
         let request = JsonRpcRequest {
             jsonrpc: "2.0".to_string(),
@@ -725,16 +930,40 @@ impl McpServer {
         for (k, v) in &inner.headers {
             req = req.header(k, v);
         }
+        if let Some(session_id) = &inner.session_id {
+            req = req.header(MCP_SESSION_ID_HEADER, session_id);
+        }
 
         let response = req
             .send()
             .await
             .map_err(|e| format!("HTTP request failed: {e}"))?;
         let status = response.status();
-        let body: serde_json::Value = response
-            .json()
-            .await
-            .map_err(|e| format!("Failed to parse HTTP MCP response: {e}"))?;
+        if status == reqwest::StatusCode::NOT_FOUND && inner.session_id.is_some() {
+            inner.session_id = None;
+            return Err(format!("{HTTP_SESSION_EXPIRED_ERROR} (HTTP {status})"));
+        }
+        if method == "initialize" && status.is_success() {
+            inner.session_id = session_id_from_headers(response.headers());
+        }
+        drop(inner);
+        let is_event_stream = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|ct| {
+                ct.trim_start()
+                    .to_ascii_lowercase()
+                    .starts_with("text/event-stream")
+            });
+        let body: serde_json::Value = if is_event_stream {
+            read_sse_response(response, id).await?
+        } else {
+            response
+                .json()
+                .await
+                .map_err(|e| format!("Failed to parse HTTP MCP response: {e}"))?
+        };
 
         if !status.is_success() {
             return Err(format!("HTTP MCP request failed with {status}: {body}"));
@@ -846,6 +1075,9 @@ impl McpServer {
                 for (k, v) in &inner.headers {
                     req = req.header(k, v);
                 }
+                if let Some(session_id) = &inner.session_id {
+                    req = req.header(MCP_SESSION_ID_HEADER, session_id);
+                }
 
                 let response = req
                     .send()
@@ -873,7 +1105,10 @@ impl McpServer {
             }
         });
 
-        let result = self.send_request("initialize", Some(params)).await?;
+        let result = self
+            .send_request("initialize", Some(params))
+            .await
+            .map_err(|e| McpInitFailure::Initialize(e).describe(&self.name))?;
         let negotiated = result
             .get("protocolVersion")
             .and_then(|v| v.as_str())
@@ -894,10 +1129,7 @@ impl McpServer {
             *guard = negotiated;
         }
 
-        self.send_notification("notifications/initialized", None)
-            .await?;
-
-        Ok(())
+        self.confirm_initialized().await
     }
 
     async fn list_tools_uncached(&self) -> Result<Vec<McpToolInfo>, String> {
@@ -947,8 +1179,40 @@ impl McpServer {
     }
 
     pub async fn health_probe(&self) -> Result<(), String> {
-        let _ = self.refresh_tools_cache(true).await?;
-        Ok(())
+        let result = self.refresh_tools_cache(true).await.map(|_| ());
+        self.record_probe(&result);
+        result
+    }
+
+    fn record_probe(&self, result: &Result<(), String>) {
+        let mut health = self.health.lock().unwrap_or_else(|e| e.into_inner());
+        match result {
+            Ok(()) => {
+                health.reachable = true;
+                health.last_success_at = Some(chrono::Utc::now());
+                health.last_error = None;
+            }
+            Err(e) => {
+                health.reachable = false;
+                health.last_error = Some(e.clone());
+            }
+        }
+    }
+
+    pub fn health_snapshot(&self) -> McpServerHealth {
+        let health = self
+            .health
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        McpServerHealth {
+            name: self.name.clone(),
+            reachable: health.reachable,
+            last_success_at: health.last_success_at.map(|t| t.to_rfc3339()),
+            last_error: health.last_error,
+            tool_count: self.tools_snapshot().len(),
+            protocol_version: self.protocol_version(),
+        }
     }
 
     pub fn start_health_probe(self: Arc<Self>, interval_secs: u64) {
@@ -956,11 +1220,18 @@ impl McpServer {
             return;
         }
 
+        // Hold only a weak reference so a server dropped by a reload stops
+        // being probed.
+        let server: Weak<Self> = Arc::downgrade(&self);
+        drop(self);
         tokio::spawn(async move {
             loop {
                 tokio::time::sleep(Duration::from_secs(interval_secs)).await;
-                if let Err(e) = self.health_probe().await {
-                    warn!("MCP health probe failed for '{}': {}", self.name, e);
+                let Some(server) = server.upgrade() else {
+                    break;
+                };
+                if let Err(e) = server.health_probe().await {
+                    warn!("MCP health probe failed for '{}': {}", server.name, e);
                 }
             }
         });
@@ -1036,8 +1307,22 @@ impl McpServer {
 
 // --- MCP manager ---
 
+#[derive(Default)]
 pub struct McpManager {
-    servers: Vec<Arc<McpServer>>,
+    servers: StdRwLock<Vec<Arc<McpServer>>>,
+    config_paths: Vec<PathBuf>,
+    default_request_timeout_secs: u64,
+}
+
+/// What changed when the MCP config was re-read.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct McpReloadReport {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub reconnected: Vec<String>,
+    /// Servers that could not be (re)connected, with the error. A server that
+    /// was already connected keeps its previous connection.
+    pub failed: Vec<(String, String)>,
 }
 
 impl McpManager {
@@ -1046,29 +1331,52 @@ impl McpManager {
     }
 
     pub async fn from_config_paths(paths: &[PathBuf], default_request_timeout_secs: u64) -> Self {
-        let default_request_timeout_secs =
-            resolve_request_timeout_secs(None, default_request_timeout_secs);
+        let manager = McpManager {
+            servers: StdRwLock::new(Vec::new()),
+            config_paths: paths.to_vec(),
+            default_request_timeout_secs: resolve_request_timeout_secs(
+                None,
+                default_request_timeout_secs,
+            ),
+        };
         let (loaded_any_config, merged_default_protocol_version, merged_servers) =
             merge_config_sources(paths);
         if !loaded_any_config {
             // Config file not found is normal — MCP is optional
-            return McpManager {
-                servers: Vec::new(),
-            };
+            return manager;
         }
 
         let mut servers = Vec::new();
-        let mut entries: Vec<(String, McpServerConfig)> = merged_servers.into_iter().collect();
+        for (_, result) in manager
+            .connect_all(merged_servers, merged_default_protocol_version.as_deref())
+            .await
+        {
+            match result {
+                Ok(server) => servers.push(server),
+                Err(e) => warn!("{e}"),
+            }
+        }
+        *manager.servers.write().unwrap_or_else(|e| e.into_inner()) = servers;
+        manager
+    }
+
+    async fn connect_all(
+        &self,
+        servers: HashMap<String, McpServerConfig>,
+        default_protocol_version: Option<&str>,
+    ) -> Vec<(String, Result<Arc<McpServer>, String>)> {
+        let mut entries: Vec<(String, McpServerConfig)> = servers.into_iter().collect();
         entries.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut results = Vec::new();
         for (name, server_config) in entries {
             info!("Connecting to MCP server '{name}'...");
-            match tokio::time::timeout(
+            let result = match tokio::time::timeout(
                 Duration::from_secs(30),
                 McpServer::connect(
                     &name,
                     &server_config,
-                    merged_default_protocol_version.as_deref(),
-                    default_request_timeout_secs,
+                    default_protocol_version,
+                    self.default_request_timeout_secs,
                 ),
             )
             .await
@@ -1085,28 +1393,71 @@ impl McpManager {
                         server.tools_snapshot().len(),
                         server.protocol_version()
                     );
-                    servers.push(server);
+                    Ok(server)
                 }
-                Ok(Err(e)) => {
-                    warn!("Failed to connect MCP server '{name}': {e}");
+                Ok(Err(e)) => Err(format!("Failed to connect MCP server '{name}': {e}")),
+                Err(_) => Err(format!("MCP server '{name}' connection timed out (30s)")),
+            };
+            results.push((name, result));
+        }
+        results
+    }
+
+    /// Re-read the MCP config files and reconnect every configured server.
+    /// Servers no longer in the config are dropped. Fails without touching
+    /// the current servers when no config file can be read.
+    pub async fn reload(&self) -> Result<McpReloadReport, String> {
+        let (loaded_any_config, merged_default_protocol_version, merged_servers) =
+            merge_config_sources(&self.config_paths);
+        if !loaded_any_config {
+            return Err("no readable MCP config file found".to_string());
+        }
+
+        let previous = self.servers();
+        let mut report = McpReloadReport::default();
+        let mut servers = Vec::new();
+        for (name, result) in self
+            .connect_all(merged_servers, merged_default_protocol_version.as_deref())
+            .await
+        {
+            let existing = previous.iter().find(|s| s.name == name);
+            match result {
+                Ok(server) => {
+                    if existing.is_some() {
+                        report.reconnected.push(name);
+                    } else {
+                        report.added.push(name);
+                    }
+                    servers.push(server);
                 }
-                Err(_) => {
-                    warn!("MCP server '{name}' connection timed out (30s)");
+                Err(e) => {
+                    warn!("{e}");
+                    if let Some(existing) = existing {
+                        servers.push(existing.clone());
+                    }
+                    report.failed.push((name, e));
                 }
             }
         }
-
-        McpManager { servers }
+        report.removed = previous
+            .iter()
+            .filter(|old| !servers.iter().any(|s| s.name == old.name))
+            .map(|old| old.name.clone())
+            .collect();
+        *self.servers.write().unwrap_or_else(|e| e.into_inner()) = servers;
+        Ok(report)
     }
 
-    #[allow(dead_code)]
-    pub fn servers(&self) -> &[Arc<McpServer>] {
-        &self.servers
+    pub fn servers(&self) -> Vec<Arc<McpServer>> {
+        self.servers
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
     }
 
     pub fn all_tools(&self) -> Vec<(Arc<McpServer>, McpToolInfo)> {
         let mut tools = Vec::new();
-        for server in &self.servers {
+        for server in self.servers() {
             for tool in server.tools_snapshot() {
                 tools.push((server.clone(), tool));
             }
@@ -1164,6 +1515,46 @@ fn load_config_from_path(path: &Path) -> Option<McpConfig> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_mcp_health_json_shape() {
+        let servers = vec![
+            McpServerHealth {
+                name: "github".into(),
+                reachable: true,
+                last_success_at: Some("2026-01-02T03:04:05+00:00".into()),
+                last_error: None,
+                tool_count: 12,
+                protocol_version: "2025-11-05".into(),
+            },
+            McpServerHealth {
+                name: "files".into(),
+                reachable: false,
+                last_success_at: None,
+                last_error: Some("read error: closed connection".into()),
+                tool_count: 3,
+                protocol_version: "2025-06-18".into(),
+            },
+        ];
+        let value = mcp_health_json(&servers);
+        assert_eq!(value["total"], 2);
+        assert_eq!(value["reachable"], 1);
+        let first = &value["servers"][0];
+        assert_eq!(first["name"], "github");
+        assert_eq!(first["reachable"], true);
+        assert_eq!(first["last_success_at"], "2026-01-02T03:04:05+00:00");
+        assert!(first["last_error"].is_null());
+        assert_eq!(first["tool_count"], 12);
+        assert_eq!(first["protocol_version"], "2025-11-05");
+        let second = &value["servers"][1];
+        assert_eq!(second["reachable"], false);
+        assert!(second["last_success_at"].is_null());
+        assert_eq!(second["last_error"], "read error: closed connection");
+
+        let empty = mcp_health_json(&[]);
+        assert_eq!(empty["total"], 0);
+        assert_eq!(empty["servers"].as_array().unwrap().len(), 0);
+    }
+
     #[test]
     fn test_mcp_config_defaults() {
         let json = r#"{
@@ -1218,6 +1609,288 @@ mod tests {
         assert_eq!(remote.health_interval_secs, Some(15));
     }
 
+    /// Serve JSON-RPC over HTTP where the first `failing_notifications`
+    /// notifications get HTTP 500. Returns the endpoint and a notification counter.
+    async fn spawn_http_mcp_stub(
+        failing_notifications: usize,
+    ) -> (String, Arc<std::sync::atomic::AtomicUsize>) {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        let notifications = Arc::new(AtomicUsize::new(0));
+        let counter = notifications.clone();
+        let app = axum::Router::new().route(
+            "/mcp",
+            axum::routing::post(move |axum::Json(body): axum::Json<serde_json::Value>| {
+                let counter = counter.clone();
+                async move {
+                    let Some(id) = body.get("id").cloned() else {
+                        let seen = counter.fetch_add(1, Ordering::SeqCst);
+                        let status = if seen < failing_notifications {
+                            axum::http::StatusCode::INTERNAL_SERVER_ERROR
+                        } else {
+                            axum::http::StatusCode::ACCEPTED
+                        };
+                        return (status, axum::Json(serde_json::json!({})));
+                    };
+                    let result = match body.get("method").and_then(|m| m.as_str()) {
+                        Some("initialize") => serde_json::json!({"protocolVersion": "2025-11-05"}),
+                        _ => serde_json::json!({"tools": []}),
+                    };
+                    (
+                        axum::http::StatusCode::OK,
+                        axum::Json(
+                            serde_json::json!({"jsonrpc": "2.0", "id": id, "result": result}),
+                        ),
+                    )
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+        (format!("http://{addr}/mcp"), notifications)
+    }
+
+    fn http_server_config(endpoint: &str) -> McpServerConfig {
+        serde_json::from_value(serde_json::json!({
+            "transport": "streamable_http",
+            "endpoint": endpoint,
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_failed_initialized_notification_fails_connection() {
+        use std::sync::atomic::Ordering;
+        let (endpoint, notifications) = spawn_http_mcp_stub(usize::MAX).await;
+        let err = McpServer::connect("stub", &http_server_config(&endpoint), None, 5)
+            .await
+            .err()
+            .expect("half-initialized server must not connect");
+        assert!(err.contains("accepted initialize but notifications/initialized failed"));
+        assert!(err.contains("500"));
+        // One retry after the first failure.
+        assert_eq!(notifications.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_initialized_notification_is_retried_once() {
+        use std::sync::atomic::Ordering;
+        let (endpoint, notifications) = spawn_http_mcp_stub(1).await;
+        let server = McpServer::connect("stub", &http_server_config(&endpoint), None, 5)
+            .await
+            .unwrap();
+        assert_eq!(notifications.load(Ordering::SeqCst), 2);
+        assert_eq!(server.protocol_version(), "2025-11-05");
+    }
+
+    #[test]
+    fn test_jsonrpc_response_for_id_skips_notifications() {
+        let progress =
+            r#"{"jsonrpc":"2.0","method":"notifications/progress","params":{"progress":1}}"#;
+        assert!(jsonrpc_response_for_id(progress, 3).is_none());
+        let server_request = r#"{"jsonrpc":"2.0","id":3,"method":"sampling/createMessage"}"#;
+        assert!(jsonrpc_response_for_id(server_request, 3).is_none());
+        assert!(jsonrpc_response_for_id(r#"{"jsonrpc":"2.0","id":2,"result":{}}"#, 3).is_none());
+        let batch = r#"[{"jsonrpc":"2.0","method":"notifications/message"},{"jsonrpc":"2.0","id":"3","result":{"ok":true}}]"#;
+        assert_eq!(
+            jsonrpc_response_for_id(batch, 3).unwrap()["result"]["ok"],
+            true
+        );
+        assert!(jsonrpc_response_for_id("not json", 3).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_http_event_stream_response_is_parsed() {
+        let app = axum::Router::new().route(
+            "/mcp",
+            axum::routing::post(|axum::Json(body): axum::Json<serde_json::Value>| async move {
+                use axum::response::IntoResponse;
+                let Some(id) = body.get("id").cloned() else {
+                    return axum::http::StatusCode::ACCEPTED.into_response();
+                };
+                if body.get("method").and_then(|m| m.as_str()) == Some("initialize") {
+                    return axum::Json(serde_json::json!({
+                        "jsonrpc": "2.0",
+                        "id": id,
+                        "result": {"protocolVersion": "2025-11-05"}
+                    }))
+                    .into_response();
+                }
+                let response = serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "result": {"tools": [{"name": "search", "inputSchema": {"type": "object"}}]}
+                });
+                let sse = format!(
+                    ": keep-alive\n\n\
+                     event: message\n\
+                     data: {{\"jsonrpc\":\"2.0\",\"method\":\"notifications/progress\",\"params\":{{\"progress\":50}}}}\n\n\
+                     event: message\r\n\
+                     data: {response}\r\n\r\n"
+                );
+                (
+                    [(axum::http::header::CONTENT_TYPE, "text/event-stream")],
+                    sse,
+                )
+                    .into_response()
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+
+        let endpoint = format!("http://{addr}/mcp");
+        let server = McpServer::connect("sse", &http_server_config(&endpoint), None, 5)
+            .await
+            .unwrap();
+        let tools = server.tools_snapshot();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].name, "search");
+    }
+
+    #[test]
+    fn test_session_id_from_headers() {
+        let mut headers = reqwest::header::HeaderMap::new();
+        assert_eq!(session_id_from_headers(&headers), None);
+        headers.insert("Mcp-Session-Id", " abc-123 ".parse().unwrap());
+        assert_eq!(
+            session_id_from_headers(&headers).as_deref(),
+            Some("abc-123")
+        );
+        headers.insert("mcp-session-id", "".parse().unwrap());
+        assert_eq!(session_id_from_headers(&headers), None);
+        assert!(is_session_expired_error(
+            "HTTP MCP session expired (HTTP 404 Not Found)"
+        ));
+        assert!(!is_session_expired_error("HTTP request failed: 404"));
+    }
+
+    #[tokio::test]
+    async fn test_http_session_id_is_echoed_and_renewed_after_expiry() {
+        use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+        let sessions = Arc::new(AtomicUsize::new(0));
+        let expire_first = Arc::new(AtomicBool::new(false));
+        let (sessions_srv, expire_srv) = (sessions.clone(), expire_first.clone());
+        let app = axum::Router::new().route(
+            "/mcp",
+            axum::routing::post(
+                move |headers: axum::http::HeaderMap,
+                      axum::Json(body): axum::Json<serde_json::Value>| {
+                    let (sessions, expire_first) = (sessions_srv.clone(), expire_srv.clone());
+                    async move {
+                        use axum::response::IntoResponse;
+                        let session = headers
+                            .get("mcp-session-id")
+                            .and_then(|v| v.to_str().ok())
+                            .map(str::to_string);
+                        let method = body.get("method").and_then(|m| m.as_str()).unwrap_or("");
+                        if method == "initialize" {
+                            let n = sessions.fetch_add(1, Ordering::SeqCst) + 1;
+                            return (
+                                [("mcp-session-id", format!("sess-{n}"))],
+                                axum::Json(serde_json::json!({
+                                    "jsonrpc": "2.0",
+                                    "id": body["id"],
+                                    "result": {"protocolVersion": "2025-11-05"}
+                                })),
+                            )
+                                .into_response();
+                        }
+                        let current = format!("sess-{}", sessions.load(Ordering::SeqCst));
+                        if session.as_deref() != Some(current.as_str())
+                            || (expire_first.load(Ordering::SeqCst)
+                                && session.as_deref() == Some("sess-1"))
+                        {
+                            return axum::http::StatusCode::NOT_FOUND.into_response();
+                        }
+                        if body.get("id").is_none() {
+                            return axum::http::StatusCode::ACCEPTED.into_response();
+                        }
+                        axum::Json(serde_json::json!({
+                            "jsonrpc": "2.0",
+                            "id": body["id"],
+                            "result": {"tools": []}
+                        }))
+                        .into_response()
+                    }
+                },
+            ),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+
+        let endpoint = format!("http://{addr}/mcp");
+        let server = McpServer::connect("stateful", &http_server_config(&endpoint), None, 5)
+            .await
+            .unwrap();
+        assert_eq!(sessions.load(Ordering::SeqCst), 1);
+
+        expire_first.store(true, Ordering::SeqCst);
+        server.refresh_tools_cache(true).await.unwrap();
+        assert_eq!(sessions.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_reload_adds_and_removes_servers() {
+        let (endpoint, _) = spawn_http_mcp_stub(0).await;
+        let dir =
+            std::env::temp_dir().join(format!("microclaw_mcp_reload_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("mcp.json");
+        let write_config = |names: &[(&str, &str)]| {
+            let servers: serde_json::Map<String, serde_json::Value> = names
+                .iter()
+                .map(|(name, endpoint)| {
+                    (
+                        name.to_string(),
+                        serde_json::json!({"transport": "streamable_http", "endpoint": endpoint}),
+                    )
+                })
+                .collect();
+            std::fs::write(
+                &path,
+                serde_json::json!({"mcpServers": servers}).to_string(),
+            )
+            .unwrap();
+        };
+
+        write_config(&[("alpha", &endpoint), ("beta", &endpoint)]);
+        let manager = McpManager::from_config_paths(std::slice::from_ref(&path), 5).await;
+        assert_eq!(manager.servers().len(), 2);
+
+        write_config(&[("beta", &endpoint), ("gamma", &endpoint)]);
+        let report = manager.reload().await.unwrap();
+        assert_eq!(report.added, vec!["gamma".to_string()]);
+        assert_eq!(report.removed, vec!["alpha".to_string()]);
+        assert_eq!(report.reconnected, vec!["beta".to_string()]);
+        assert!(report.failed.is_empty());
+        let names: Vec<String> = manager.servers().iter().map(|s| s.name.clone()).collect();
+        assert_eq!(names, vec!["beta".to_string(), "gamma".to_string()]);
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(manager.reload().await.is_err());
+        assert_eq!(manager.servers().len(), 2);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_init_failure_diagnostics_name_the_failed_step() {
+        let init = McpInitFailure::Initialize("timeout".into()).describe("files");
+        assert_eq!(init, "MCP server 'files' failed to initialize: timeout");
+        let half = McpInitFailure::InitializedNotification("Write error: broken pipe".into())
+            .describe("files");
+        assert!(half.contains("'files' accepted initialize"));
+        assert!(half.contains("(Write error: broken pipe)"));
+        assert!(half.contains("half-initialized"));
+    }
+
     #[test]
     fn test_resolve_request_timeout_secs_prefers_server_override() {
         assert_eq!(resolve_request_timeout_secs(Some(25), 90), 25);
@@ -3,6 +3,7 @@ use std::sync::Arc;
 
 use tracing::{info, warn};
 
+use crate::config::{normalize_memory_category, Config, MemoryCategory};
 use crate::mcp::{McpManager, McpServer, McpToolInfo};
 use microclaw_core::error::MicroClawError;
 use microclaw_storage::db::{call_blocking, Database, Memory};
@@ -60,15 +61,39 @@ impl MemoryMcpClient {
 pub struct MemoryBackend {
     db: Arc<Database>,
     mcp: Option<MemoryMcpClient>,
+    categories: Vec<MemoryCategory>,
+    default_category: String,
 }
 
 impl MemoryBackend {
     pub fn new(db: Arc<Database>, mcp: Option<MemoryMcpClient>) -> Self {
-        Self { db, mcp }
+        Self {
+            db,
+            mcp,
+            categories: Vec::new(),
+            default_category: String::new(),
+        }
     }
 
     pub fn local_only(db: Arc<Database>) -> Self {
-        Self { db, mcp: None }
+        Self::new(db, None)
+    }
+
+    /// Restrict written categories to the configured set; anything else is
+    /// stored as `memory_default_category`.
+    pub fn with_categories(mut self, config: &Config) -> Self {
+        self.categories = config.memory_categories.clone();
+        self.default_category = config.memory_default_category.clone();
+        self
+    }
+
+    /// The category to store for `raw`. Without configured categories the
+    /// caller's value is kept.
+    fn allowed_category(&self, raw: &str) -> String {
+        if self.categories.is_empty() {
+            return raw.to_string();
+        }
+        normalize_memory_category(&self.categories, &self.default_category, Some(raw))
     }
 
     pub fn prefers_mcp(&self) -> bool {
@@ -188,6 +213,7 @@ impl MemoryBackend {
         source: &str,
         confidence: f64,
     ) -> Result<i64, MicroClawError> {
+        let category = &self.allowed_category(category);
         if let Some(mcp) = &self.mcp {
             let payload = serde_json::json!({
                 "op": "insert",
@@ -224,6 +250,7 @@ impl MemoryBackend {
         confidence: f64,
         source: &str,
     ) -> Result<bool, MicroClawError> {
+        let category = &self.allowed_category(category);
         if let Some(mcp) = &self.mcp {
             let payload = serde_json::json!({
                 "op": "update",
@@ -290,6 +317,7 @@ impl MemoryBackend {
         confidence: f64,
         reason: Option<&str>,
     ) -> Result<i64, MicroClawError> {
+        let category = &self.allowed_category(category);
         if let Some(mcp) = &self.mcp {
             let payload = serde_json::json!({
                 "op": "supersede",
@@ -0,0 +1,129 @@
+use std::time::Duration;
+
+use base64::Engine;
+
+use crate::config::Config;
+
+/// How long an OCR command may run before it is killed.
+const OCR_COMMAND_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Returns the shell command used for OCR, or `None` when OCR is disabled.
+fn ocr_command(config: &Config) -> Result<Option<String>, String> {
+    match config
+        .image_ocr_provider
+        .trim()
+        .to_ascii_lowercase()
+        .as_str()
+    {
+        "" | "none" | "off" => Ok(None),
+        "tesseract" => Ok(Some("tesseract {file} stdout".to_string())),
+        "local" => config
+            .image_ocr_command
+            .as_deref()
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .map(|c| Some(c.to_string()))
+            .ok_or_else(|| "Local image OCR configured but image_ocr_command not set".into()),
+        other => Err(format!("Unknown image_ocr_provider: {other}")),
+    }
+}
+
+pub fn ocr_enabled(config: &Config) -> bool {
+    matches!(ocr_command(config), Ok(Some(_)))
+}
+
+fn image_extension(media_type: &str) -> &'static str {
+    match media_type {
+        "image/png" => "png",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        _ => "jpg",
+    }
+}
+
+/// Run the configured OCR backend on a base64-encoded image. Returns `Ok(None)` when
+/// OCR is disabled or the image contains no text.
+pub async fn extract_image_text(
+    config: &Config,
+    base64_data: &str,
+    media_type: &str,
+) -> Result<Option<String>, String> {
+    let Some(command) = ocr_command(config)? else {
+        return Ok(None);
+    };
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(base64_data)
+        .map_err(|e| format!("Invalid image data: {e}"))?;
+
+    let temp_file = std::env::temp_dir().join(format!(
+        "ocr_{}.{}",
+        uuid::Uuid::new_v4(),
+        image_extension(media_type)
+    ));
+    tokio::fs::write(&temp_file, &bytes)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let cmd = command.replace("{file}", temp_file.to_str().unwrap_or(""));
+    let output_result = run_ocr_command(&cmd, OCR_COMMAND_TIMEOUT).await;
+
+    let _ = tokio::fs::remove_file(&temp_file).await;
+
+    let output = output_result?;
+    if !output.status.success() {
+        return Err(format!(
+            "OCR command failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if text.is_empty() {
+        return Ok(None);
+    }
+    let max_chars = config.image_ocr_max_chars.max(1);
+    if text.chars().count() > max_chars {
+        let truncated = text.chars().take(max_chars).collect::<String>();
+        return Ok(Some(format!("{truncated}\n... (truncated)")));
+    }
+    Ok(Some(text))
+}
+
+/// Run `cmd` through `sh -c`, killing it if it outlives `timeout`.
+async fn run_ocr_command(cmd: &str, timeout: Duration) -> Result<std::process::Output, String> {
+    let child = tokio::process::Command::new("sh")
+        .arg("-c")
+        .arg(cmd)
+        .kill_on_drop(true)
+        .output();
+    match tokio::time::timeout(timeout, child).await {
+        Ok(result) => result.map_err(|e| format!("Failed to run OCR command: {e}")),
+        Err(_) => Err(format!(
+            "OCR command timed out after {}s",
+            timeout.as_secs()
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_run_ocr_command_times_out() {
+        let started = std::time::Instant::now();
+        let err = run_ocr_command("sleep 5", Duration::from_millis(100))
+            .await
+            .unwrap_err();
+        assert!(err.contains("timed out"));
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn test_run_ocr_command_returns_output() {
+        let output = run_ocr_command("echo hello", Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "hello");
+    }
+}
@@ -0,0 +1,367 @@
+//! OTLP/HTTP span export for the `agent_run`, `llm_call` and `tool_call` tracing
+//! spans. Enabled with the `otel-tracing` cargo feature plus
+//! `channels.observability.otlp_tracing_enabled: true`.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use opentelemetry_proto::tonic::collector::trace::v1::ExportTraceServiceRequest;
+use opentelemetry_proto::tonic::common::v1::{any_value, AnyValue, InstrumentationScope, KeyValue};
+use opentelemetry_proto::tonic::resource::v1::Resource;
+use opentelemetry_proto::tonic::trace::v1::{span::SpanKind, ResourceSpans, ScopeSpans, Span};
+use prost::Message;
+use tokio::sync::mpsc;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::Subscriber;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+use crate::config::Config;
+
+/// Only spans from this crate are exported; dependency spans stay local.
+const EXPORTED_TARGET_PREFIX: &str = "microclaw";
+
+#[derive(Debug, Clone)]
+pub struct OtlpTracingConfig {
+    pub endpoint: String,
+    pub headers: Vec<(String, String)>,
+    pub service_name: String,
+    pub queue_capacity: usize,
+    pub batch_size: usize,
+    pub batch_max_delay: Duration,
+}
+
+impl OtlpTracingConfig {
+    /// Reads `channels.observability`. Uses `otlp_traces_endpoint` when set, otherwise
+    /// derives `/v1/traces` from the metrics `otlp_endpoint`.
+    pub fn from_config(config: &Config) -> Option<Self> {
+        let map = config.channels.get("observability")?.as_mapping()?;
+        let get = |key: &str| map.get(serde_yaml::Value::String(key.to_string()));
+        let enabled = get("otlp_tracing_enabled")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        if !enabled {
+            return None;
+        }
+        let explicit = get("otlp_traces_endpoint")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string);
+        let endpoint = match explicit {
+            Some(endpoint) => endpoint,
+            None => traces_endpoint_from_metrics(
+                get("otlp_endpoint")
+                    .and_then(|v| v.as_str())
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())?,
+            ),
+        };
+        let service_name = get("service_name")
+            .and_then(|v| v.as_str())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| "microclaw".to_string());
+        let queue_capacity = get("otlp_queue_capacity")
+            .and_then(|v| v.as_u64())
+            .map(|n| n.clamp(8, 100_000) as usize)
+            .unwrap_or(1024);
+        let batch_size = get("otlp_batch_size")
+            .and_then(|v| v.as_u64())
+            .map(|n| n.clamp(1, 1024) as usize)
+            .unwrap_or(64);
+        let batch_max_delay = get("otlp_batch_max_delay_ms")
+            .and_then(|v| v.as_u64())
+            .map(|n| Duration::from_millis(n.clamp(20, 30_000)))
+            .unwrap_or_else(|| Duration::from_millis(1000));
+
+        let mut headers = Vec::new();
+        if let Some(hmap) = get("otlp_headers").and_then(|v| v.as_mapping()) {
+            for (k, v) in hmap {
+                if let (Some(key), Some(val)) = (k.as_str(), v.as_str()) {
+                    headers.push((key.to_string(), val.to_string()));
+                }
+            }
+        }
+
+        Some(Self {
+            endpoint,
+            headers,
+            service_name,
+            queue_capacity,
+            batch_size,
+            batch_max_delay,
+        })
+    }
+}
+
+fn traces_endpoint_from_metrics(metrics_endpoint: &str) -> String {
+    let base = metrics_endpoint.trim_end_matches('/');
+    match base.strip_suffix("/v1/metrics") {
+        Some(root) => format!("{root}/v1/traces"),
+        None => format!("{base}/v1/traces"),
+    }
+}
+
+/// Build the export layer and spawn its worker. Must be called inside a Tokio runtime.
+pub fn build_layer(config: &Config) -> Option<OtlpTraceLayer> {
+    let cfg = OtlpTracingConfig::from_config(config)?;
+    let (tx, rx) = mpsc::channel::<Span>(cfg.queue_capacity);
+    let client = reqwest::Client::new();
+    tokio::spawn(async move { run_worker(rx, client, cfg).await });
+    Some(OtlpTraceLayer { tx })
+}
+
+pub struct OtlpTraceLayer {
+    tx: mpsc::Sender<Span>,
+}
+
+/// Per-span state kept in the registry extensions until the span closes.
+struct SpanRecord {
+    trace_id: [u8; 16],
+    span_id: [u8; 8],
+    parent_span_id: Option<[u8; 8]>,
+    start_time_unix_nano: u64,
+    attributes: Vec<KeyValue>,
+}
+
+impl<S> Layer<S> for OtlpTraceLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if !attrs
+            .metadata()
+            .target()
+            .starts_with(EXPORTED_TARGET_PREFIX)
+        {
+            return;
+        }
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let parent = span.parent().and_then(|parent| {
+            parent
+                .extensions()
+                .get::<SpanRecord>()
+                .map(|p| (p.trace_id, p.span_id))
+        });
+        let (trace_id, parent_span_id) = match parent {
+            Some((trace_id, parent_id)) => (trace_id, Some(parent_id)),
+            None => (*uuid::Uuid::new_v4().as_bytes(), None),
+        };
+        let mut span_id = [0u8; 8];
+        span_id.copy_from_slice(&uuid::Uuid::new_v4().as_bytes()[..8]);
+
+        let mut visitor = AttributeVisitor::default();
+        attrs.record(&mut visitor);
+        span.extensions_mut().insert(SpanRecord {
+            trace_id,
+            span_id,
+            parent_span_id,
+            start_time_unix_nano: unix_nanos_now(),
+            attributes: visitor.attributes,
+        });
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut extensions = span.extensions_mut();
+        if let Some(record) = extensions.get_mut::<SpanRecord>() {
+            let mut visitor = AttributeVisitor::default();
+            values.record(&mut visitor);
+            for kv in visitor.attributes {
+                record.attributes.retain(|existing| existing.key != kv.key);
+                record.attributes.push(kv);
+            }
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let Some(record) = span.extensions_mut().remove::<SpanRecord>() else {
+            return;
+        };
+        let exported = Span {
+            trace_id: record.trace_id.to_vec(),
+            span_id: record.span_id.to_vec(),
+            parent_span_id: record
+                .parent_span_id
+                .map(|p| p.to_vec())
+                .unwrap_or_default(),
+            name: span.name().to_string(),
+            kind: SpanKind::Internal as i32,
+            start_time_unix_nano: record.start_time_unix_nano,
+            end_time_unix_nano: unix_nanos_now(),
+            attributes: record.attributes,
+            ..Default::default()
+        };
+        // Dropping spans under backpressure is preferable to blocking the caller.
+        let _ = self.tx.try_send(exported);
+    }
+}
+
+#[derive(Default)]
+struct AttributeVisitor {
+    attributes: Vec<KeyValue>,
+}
+
+impl AttributeVisitor {
+    fn push(&mut self, field: &Field, value: any_value::Value) {
+        self.attributes.push(KeyValue {
+            key: field.name().to_string(),
+            value: Some(AnyValue { value: Some(value) }),
+        });
+    }
+}
+
+impl Visit for AttributeVisitor {
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.push(field, any_value::Value::IntValue(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.push(
+            field,
+            any_value::Value::IntValue(i64::try_from(value).unwrap_or(i64::MAX)),
+        );
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.push(field, any_value::Value::BoolValue(value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.push(field, any_value::Value::StringValue(value.to_string()));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.push(field, any_value::Value::StringValue(format!("{value:?}")));
+    }
+}
+
+fn unix_nanos_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or_default()
+}
+
+async fn run_worker(mut rx: mpsc::Receiver<Span>, client: reqwest::Client, cfg: OtlpTracingConfig) {
+    while let Some(span) = rx.recv().await {
+        let mut batch = Vec::with_capacity(cfg.batch_size);
+        batch.push(span);
+        let window_start = tokio::time::Instant::now();
+        while batch.len() < cfg.batch_size {
+            let Some(remaining) = cfg.batch_max_delay.checked_sub(window_start.elapsed()) else {
+                break;
+            };
+            match tokio::time::timeout(remaining, rx.recv()).await {
+                Ok(Some(item)) => batch.push(item),
+                Ok(None) | Err(_) => break,
+            }
+        }
+        let payload = build_traces_payload(&cfg.service_name, batch).encode_to_vec();
+        let mut req = client
+            .post(&cfg.endpoint)
+            .header("content-type", "application/x-protobuf")
+            .body(payload);
+        for (k, v) in &cfg.headers {
+            req = req.header(k, v);
+        }
+        match req.send().await {
+            Ok(resp) if resp.status().is_success() => {}
+            // Use eprintln: logging through tracing here would feed back into the exporter.
+            Ok(resp) => eprintln!("otlp trace export failed: {}", resp.status()),
+            Err(e) => eprintln!("otlp trace export failed: {e}"),
+        }
+    }
+}
+
+fn build_traces_payload(service_name: &str, spans: Vec<Span>) -> ExportTraceServiceRequest {
+    ExportTraceServiceRequest {
+        resource_spans: vec![ResourceSpans {
+            resource: Some(Resource {
+                attributes: vec![KeyValue {
+                    key: "service.name".to_string(),
+                    value: Some(AnyValue {
+                        value: Some(any_value::Value::StringValue(service_name.to_string())),
+                    }),
+                }],
+                dropped_attributes_count: 0,
+            }),
+            scope_spans: vec![ScopeSpans {
+                scope: Some(InstrumentationScope {
+                    name: "microclaw".to_string(),
+                    version: env!("CARGO_PKG_VERSION").to_string(),
+                    attributes: vec![],
+                    dropped_attributes_count: 0,
+                }),
+                spans,
+                schema_url: String::new(),
+            }],
+            schema_url: String::new(),
+        }],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    fn config_with_observability(yaml: &str) -> Config {
+        let mut config = Config::test_defaults();
+        config.channels.insert(
+            "observability".to_string(),
+            serde_yaml::from_str(yaml).unwrap(),
+        );
+        config
+    }
+
+    #[test]
+    fn test_tracing_disabled_without_flag() {
+        let config = config_with_observability("otlp_endpoint: http://127.0.0.1:4318/v1/metrics");
+        assert!(OtlpTracingConfig::from_config(&config).is_none());
+        assert!(OtlpTracingConfig::from_config(&Config::test_defaults()).is_none());
+    }
+
+    #[test]
+    fn test_traces_endpoint_derived_from_metrics_endpoint() {
+        let config = config_with_observability(
+            "otlp_tracing_enabled: true\notlp_endpoint: http://127.0.0.1:4318/v1/metrics",
+        );
+        let cfg = OtlpTracingConfig::from_config(&config).unwrap();
+        assert_eq!(cfg.endpoint, "http://127.0.0.1:4318/v1/traces");
+
+        let config = config_with_observability(
+            "otlp_tracing_enabled: true\notlp_endpoint: http://collector:4318\notlp_traces_endpoint: http://tempo:4318/v1/traces",
+        );
+        let cfg = OtlpTracingConfig::from_config(&config).unwrap();
+        assert_eq!(cfg.endpoint, "http://tempo:4318/v1/traces");
+    }
+
+    #[tokio::test]
+    async fn test_layer_initializes_and_records_spans() {
+        let config = config_with_observability(
+            "otlp_tracing_enabled: true\notlp_endpoint: http://127.0.0.1:9/v1/metrics",
+        );
+        let layer = build_layer(&config).expect("layer should initialize when configured");
+        let subscriber = tracing_subscriber::registry().with(layer);
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!(
+                "tool_call",
+                chat_id = 42_i64,
+                tool = "bash",
+                is_error = tracing::field::Empty
+            );
+            let _guard = span.enter();
+            span.record("is_error", false);
+        });
+    }
+}
@@ -0,0 +1,136 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::LazyLock;
+
+use serde_json::Value;
+use tokio::sync::Mutex;
+
+type ChatKey = (String, i64);
+
+/// A destructive tool call held back until the user replies with its token.
+#[derive(Clone)]
+struct PendingAction {
+    tool: String,
+    fingerprint: String,
+    token: String,
+    confirmed: bool,
+}
+
+static PENDING_ACTIONS: LazyLock<Mutex<HashMap<ChatKey, PendingAction>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Input key the agent sets when re-invoking a tool the user confirmed.
+pub const CONFIRMED_KEY: &str = "confirmed";
+
+/// Tool input with `confirmed` and internal `__microclaw_*` keys dropped, in
+/// key order, so a re-invocation with reordered arguments still matches.
+fn fingerprint(input: &Value) -> String {
+    match input.as_object() {
+        Some(obj) => {
+            let kept: BTreeMap<&String, &Value> = obj
+                .iter()
+                .filter(|(k, _)| k.as_str() != CONFIRMED_KEY && !k.starts_with("__microclaw"))
+                .collect();
+            serde_json::to_string(&kept).unwrap_or_default()
+        }
+        None => input.to_string(),
+    }
+}
+
+fn new_token() -> String {
+    let id = uuid::Uuid::new_v4().simple().to_string();
+    format!("CONFIRM-{}", id[..6].to_ascii_uppercase())
+}
+
+/// Hold back `tool` with `input` for this chat and return the token the user
+/// must reply with. Asking again for the same action keeps its token; a
+/// different action replaces the chat's pending one.
+pub async fn request(channel: &str, chat_id: i64, tool: &str, input: &Value) -> String {
+    let fingerprint = fingerprint(input);
+    let mut map = PENDING_ACTIONS.lock().await;
+    let key = (channel.to_string(), chat_id);
+    if let Some(existing) = map.get(&key) {
+        if existing.tool == tool && existing.fingerprint == fingerprint {
+            return existing.token.clone();
+        }
+    }
+    let token = new_token();
+    map.insert(
+        key,
+        PendingAction {
+            tool: tool.to_string(),
+            fingerprint,
+            token: token.clone(),
+            confirmed: false,
+        },
+    );
+    token
+}
+
+/// Mark the chat's pending action confirmed when `text` (a user message)
+/// contains its token. Returns whether it did.
+pub async fn confirm_from_user_text(channel: &str, chat_id: i64, text: &str) -> bool {
+    let mut map = PENDING_ACTIONS.lock().await;
+    let Some(pending) = map.get_mut(&(channel.to_string(), chat_id)) else {
+        return false;
+    };
+    if text
+        .to_ascii_uppercase()
+        .contains(&pending.token.to_ascii_uppercase())
+    {
+        pending.confirmed = true;
+        return true;
+    }
+    false
+}
+
+/// Consume the chat's pending action if the user confirmed it and it matches
+/// `tool` with `input`. Each confirmation allows exactly one execution.
+pub async fn take_confirmed(channel: &str, chat_id: i64, tool: &str, input: &Value) -> bool {
+    let mut map = PENDING_ACTIONS.lock().await;
+    let key = (channel.to_string(), chat_id);
+    let matches = map
+        .get(&key)
+        .is_some_and(|p| p.confirmed && p.tool == tool && p.fingerprint == fingerprint(input));
+    if matches {
+        map.remove(&key);
+    }
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_confirm_round_trip() {
+        let input = json!({"path": "a.txt", "content": "x"});
+        let token = request("test_pa", 1, "write_file", &input).await;
+        assert!(token.starts_with("CONFIRM-"));
+        assert!(!take_confirmed("test_pa", 1, "write_file", &input).await);
+
+        assert!(!confirm_from_user_text("test_pa", 1, "yes please").await);
+        assert!(
+            confirm_from_user_text("test_pa", 1, &format!("ok {}", token.to_lowercase())).await
+        );
+
+        let reordered = json!({"content": "x", "confirmed": true, "path": "a.txt"});
+        assert!(take_confirmed("test_pa", 1, "write_file", &reordered).await);
+        // One confirmation, one execution.
+        assert!(!take_confirmed("test_pa", 1, "write_file", &input).await);
+    }
+
+    #[tokio::test]
+    async fn test_confirmation_is_bound_to_action_and_chat() {
+        let input = json!({"command": "rm -rf build"});
+        let token = request("test_pa", 2, "bash", &input).await;
+        assert_eq!(request("test_pa", 2, "bash", &input).await, token);
+        assert!(!confirm_from_user_text("test_pa", 3, &token).await);
+        assert!(confirm_from_user_text("test_pa", 2, &token).await);
+
+        let other = json!({"command": "rm -rf /"});
+        assert!(!take_confirmed("test_pa", 2, "bash", &other).await);
+        assert!(!take_confirmed("test_pa", 3, "bash", &input).await);
+        assert!(take_confirmed("test_pa", 2, "bash", &input).await);
+    }
+}
@@ -703,6 +703,7 @@ pub async fn collect_plugin_context_injections(
                     stdout: text,
                     stderr: String::new(),
                     exit_code: 0,
+                    truncated: false,
                 })
             } else if let Some(run) = &provider.run {
                 execute_with_template(
@@ -927,6 +928,8 @@ async fn execute_command_with_policy(
         working_dir: Some(working_dir),
         envs: std::collections::HashMap::new(),
         env_files: Vec::new(),
+        clear_env: false,
+        max_output_bytes: None,
     };
 
     if !execution_policy.is_allowed(router.mode(), router.runtime_available()) {
@@ -0,0 +1,224 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tracing::{debug, info, warn};
+
+use crate::config::Config;
+use crate::runtime::AppState;
+use microclaw_core::llm_types::{Message, MessageContent};
+
+pub const PROVIDER_UNAVAILABLE_TEXT: &str =
+    "LLM provider unavailable. Replies are paused until it recovers; please try again later.";
+
+/// Returned by the agent loop instead of calling the provider while it is degraded.
+/// Only the first error per chat per outage is meant to reach the user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProviderUnavailableError {
+    pub notify: bool,
+}
+
+impl std::fmt::Display for ProviderUnavailableError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(PROVIDER_UNAVAILABLE_TEXT)
+    }
+}
+
+impl std::error::Error for ProviderUnavailableError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProviderHealthCheck {
+    Available,
+    Unavailable { notify: bool },
+}
+
+#[derive(Default)]
+struct HealthState {
+    consecutive_failures: u32,
+    degraded: bool,
+    notified_chats: HashSet<i64>,
+}
+
+/// Tracks consecutive failures of the default LLM provider. After
+/// `failure_threshold` failures in a row the provider is marked degraded and
+/// stays that way until a health probe succeeds.
+pub struct ProviderHealth {
+    failure_threshold: u32,
+    probe_interval: Duration,
+    state: Mutex<HealthState>,
+}
+
+impl ProviderHealth {
+    pub fn new(failure_threshold: u32, probe_interval: Duration) -> Self {
+        Self {
+            failure_threshold,
+            probe_interval,
+            state: Mutex::new(HealthState::default()),
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(
+            config.llm_failure_threshold,
+            Duration::from_secs(config.llm_health_probe_interval_secs.max(1)),
+        )
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.failure_threshold > 0
+    }
+
+    pub fn is_degraded(&self) -> bool {
+        self.lock().degraded
+    }
+
+    /// Decide whether a run may call the provider. While degraded, the first
+    /// check for each chat asks the caller to notify the user; later checks
+    /// stay silent.
+    pub fn check(&self, chat_id: i64) -> ProviderHealthCheck {
+        let mut state = self.lock();
+        if !state.degraded {
+            return ProviderHealthCheck::Available;
+        }
+        let notify = state.notified_chats.insert(chat_id);
+        ProviderHealthCheck::Unavailable { notify }
+    }
+
+    /// Returns true when this success ends a degraded period.
+    pub fn record_success(&self) -> bool {
+        let mut state = self.lock();
+        let recovered = state.degraded;
+        state.consecutive_failures = 0;
+        state.degraded = false;
+        state.notified_chats.clear();
+        recovered
+    }
+
+    /// Returns true when this failure moves the provider into the degraded state.
+    pub fn record_failure(&self) -> bool {
+        if !self.enabled() {
+            return false;
+        }
+        let mut state = self.lock();
+        state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+        if state.degraded || state.consecutive_failures < self.failure_threshold {
+            return false;
+        }
+        state.degraded = true;
+        true
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HealthState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Periodically probe the default provider while it is degraded.
+pub fn spawn_provider_health_probe(state: Arc<AppState>) {
+    if !state.provider_health.enabled() {
+        return;
+    }
+    let interval = state.provider_health.probe_interval;
+    tokio::spawn(async move {
+        info!(
+            "Provider health probe started (every {}s while degraded)",
+            interval.as_secs()
+        );
+        loop {
+            tokio::time::sleep(interval).await;
+            if !state.provider_health.is_degraded() {
+                continue;
+            }
+            let probe = vec![Message {
+                role: "user".into(),
+                content: MessageContent::Text("ping".into()),
+            }];
+            let result = tokio::time::timeout(
+                Duration::from_secs(30),
+                state
+                    .llm
+                    .load()
+                    .send_message("Reply with a single word.", probe, None),
+            )
+            .await;
+            match result {
+                Ok(Ok(_)) => {
+                    if state.provider_health.record_success() {
+                        info!("LLM provider recovered; resuming replies");
+                    }
+                }
+                Ok(Err(e)) => debug!("Provider health probe failed: {e}"),
+                Err(_) => warn!("Provider health probe timed out"),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_degrades_after_threshold_consecutive_failures() {
+        let health = ProviderHealth::new(3, Duration::from_secs(60));
+        assert!(!health.record_failure());
+        assert!(!health.record_failure());
+        assert_eq!(health.check(1), ProviderHealthCheck::Available);
+        assert!(health.record_failure());
+        assert!(health.is_degraded());
+        // Further failures do not re-trigger the transition.
+        assert!(!health.record_failure());
+    }
+
+    #[test]
+    fn test_success_resets_failure_count() {
+        let health = ProviderHealth::new(2, Duration::from_secs(60));
+        assert!(!health.record_failure());
+        assert!(!health.record_success());
+        assert!(!health.record_failure());
+        assert!(!health.is_degraded());
+    }
+
+    #[test]
+    fn test_degraded_notifies_each_chat_once() {
+        let health = ProviderHealth::new(1, Duration::from_secs(60));
+        assert!(health.record_failure());
+        assert_eq!(
+            health.check(1),
+            ProviderHealthCheck::Unavailable { notify: true }
+        );
+        assert_eq!(
+            health.check(1),
+            ProviderHealthCheck::Unavailable { notify: false }
+        );
+        assert_eq!(
+            health.check(2),
+            ProviderHealthCheck::Unavailable { notify: true }
+        );
+    }
+
+    #[test]
+    fn test_probe_success_recovers_and_resets_notifications() {
+        let health = ProviderHealth::new(1, Duration::from_secs(60));
+        assert!(health.record_failure());
+        let _ = health.check(7);
+        assert!(health.record_success());
+        assert!(!health.is_degraded());
+        assert_eq!(health.check(7), ProviderHealthCheck::Available);
+
+        assert!(health.record_failure());
+        assert_eq!(
+            health.check(7),
+            ProviderHealthCheck::Unavailable { notify: true }
+        );
+    }
+
+    #[test]
+    fn test_zero_threshold_disables_tracking() {
+        let health = ProviderHealth::new(0, Duration::from_secs(60));
+        for _ in 0..10 {
+            assert!(!health.record_failure());
+        }
+        assert_eq!(health.check(1), ProviderHealthCheck::Available);
+    }
+}
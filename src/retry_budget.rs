@@ -0,0 +1,98 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+use tracing::warn;
+
+/// Retries left for one agent run, shared by every retry source (LLM rate
+/// limits, stream fallbacks, transient tool errors) so a flaky run cannot
+/// keep retrying on each call independently.
+pub struct RetryBudget {
+    limit: u32,
+    used: AtomicU32,
+}
+
+impl RetryBudget {
+    pub fn new(limit: u32) -> Self {
+        Self {
+            limit,
+            used: AtomicU32::new(0),
+        }
+    }
+
+    /// Take one retry from the budget. Returns false once it is exhausted,
+    /// after which every source must give up instead of retrying.
+    pub fn try_consume(&self, source: &str) -> bool {
+        let taken = self
+            .used
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |used| {
+                (used < self.limit).then_some(used + 1)
+            });
+        match taken {
+            Ok(used) => {
+                if used + 1 == self.limit {
+                    warn!(
+                        "Retry budget for this run is now used up ({} retries; last from {source})",
+                        self.limit
+                    );
+                }
+                true
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+tokio::task_local! {
+    static RUN_RETRY_BUDGET: Arc<RetryBudget>;
+}
+
+/// Run `fut` with `budget` as the retry budget for everything it awaits.
+/// `None` leaves retries uncapped.
+pub async fn with_retry_budget<F: Future>(budget: Option<Arc<RetryBudget>>, fut: F) -> F::Output {
+    match budget {
+        Some(budget) => RUN_RETRY_BUDGET.scope(budget, fut).await,
+        None => fut.await,
+    }
+}
+
+/// Ask the current run's budget for one retry. Always true outside a run or
+/// when no budget is configured.
+pub fn try_consume_retry(source: &str) -> bool {
+    RUN_RETRY_BUDGET
+        .try_with(|budget| budget.try_consume(source))
+        .unwrap_or(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_budget_is_shared_across_retry_sources() {
+        let budget = Arc::new(RetryBudget::new(3));
+        let outcomes = with_retry_budget(Some(budget.clone()), async {
+            vec![
+                try_consume_retry("llm"),
+                try_consume_retry("tool"),
+                try_consume_retry("llm_stream"),
+                try_consume_retry("tool"),
+                try_consume_retry("llm"),
+            ]
+        })
+        .await;
+        assert_eq!(outcomes, vec![true, true, true, false, false]);
+        assert!(!budget.try_consume("llm"));
+    }
+
+    #[tokio::test]
+    async fn test_no_budget_leaves_retries_uncapped() {
+        assert!(try_consume_retry("llm"));
+        let all =
+            with_retry_budget(None, async { (0..50).all(|_| try_consume_retry("tool")) }).await;
+        assert!(all);
+
+        let zero = Arc::new(RetryBudget::new(0));
+        assert!(!with_retry_budget(Some(zero), async { try_consume_retry("llm") }).await);
+    }
+}
@@ -1,4 +1,5 @@
 use std::collections::{HashMap, HashSet};
+use std::future::Future;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, LazyLock};
 
@@ -54,6 +55,13 @@ pub async fn unregister_run(channel: &str, chat_id: i64, run_id: u64) {
     }
 }
 
+/// Whether an agent run is in progress for the chat.
+pub async fn has_active_run(channel: &str, chat_id: i64) -> bool {
+    let map = ACTIVE_RUNS.lock().await;
+    map.get(&(channel.to_string(), chat_id))
+        .is_some_and(|runs| !runs.is_empty())
+}
+
 pub async fn abort_runs(channel: &str, chat_id: i64) -> usize {
     let key = (channel.to_string(), chat_id);
     let runs = {
@@ -83,6 +91,44 @@ pub fn is_cancelled(flag: &AtomicBool) -> bool {
     flag.load(Ordering::SeqCst)
 }
 
+tokio::task_local! {
+    static CURRENT_RUN: (Arc<AtomicBool>, Arc<Notify>);
+}
+
+/// Run `fut` as the registered run whose cancellation flag is `cancelled`,
+/// so the agent loop can stop at a safe point via [`current_run_cancelled`]
+/// and [`cancelled`].
+pub async fn with_run_cancellation<F: Future>(
+    cancelled: Arc<AtomicBool>,
+    notify: Arc<Notify>,
+    fut: F,
+) -> F::Output {
+    CURRENT_RUN.scope((cancelled, notify), fut).await
+}
+
+/// Whether the current run has been aborted. Always false outside a run.
+pub fn current_run_cancelled() -> bool {
+    CURRENT_RUN
+        .try_with(|(cancelled, _)| is_cancelled(cancelled))
+        .unwrap_or(false)
+}
+
+/// Resolves once the current run is aborted; never resolves outside a run.
+pub async fn cancelled() {
+    let Ok((cancelled, notify)) = CURRENT_RUN.try_with(|run| run.clone()) else {
+        return std::future::pending().await;
+    };
+    let notified = notify.notified();
+    tokio::pin!(notified);
+    // Register for the wakeup before checking the flag so an abort that lands
+    // in between is not missed.
+    notified.as_mut().enable();
+    if is_cancelled(&cancelled) {
+        return;
+    }
+    notified.await;
+}
+
 pub async fn is_aborted_source_message(channel: &str, chat_id: i64, message_id: &str) -> bool {
     let key = (channel.to_string(), chat_id);
     let guard = ABORTED_SOURCE_MESSAGE_IDS.lock().await;
@@ -103,15 +149,35 @@ mod tests {
         let (run_id, cancelled, _notify) =
             register_run(channel, chat_id, Some("u1".to_string())).await;
         assert!(!is_cancelled(&cancelled));
+        assert!(has_active_run(channel, chat_id).await);
 
         let aborted = abort_runs(channel, chat_id).await;
         assert_eq!(aborted, 1);
         assert!(is_cancelled(&cancelled));
+        assert!(!has_active_run(channel, chat_id).await);
         assert!(is_aborted_source_message(channel, chat_id, "u1").await);
 
         unregister_run(channel, chat_id, run_id).await;
     }
 
+    #[tokio::test]
+    async fn test_run_cancellation_is_visible_inside_scope() {
+        let channel = "test.scope";
+        let chat_id = 202;
+        assert!(!current_run_cancelled());
+        let (run_id, cancelled_flag, notify) = register_run(channel, chat_id, None).await;
+        let stopped = with_run_cancellation(cancelled_flag, notify, async {
+            assert!(!current_run_cancelled());
+            let waiter = tokio::spawn(async move { abort_runs(channel, chat_id).await });
+            cancelled().await;
+            assert_eq!(waiter.await.unwrap(), 1);
+            current_run_cancelled()
+        })
+        .await;
+        assert!(stopped);
+        unregister_run(channel, chat_id, run_id).await;
+    }
+
     #[tokio::test]
     async fn test_abort_runs_without_active_returns_zero() {
         let aborted = abort_runs("test.none", 999).await;
@@ -8,6 +8,7 @@ use futures_util::FutureExt;
 use tokio::sync::RwLock;
 use tracing::{info, warn};
 
+use crate::channel_readiness::select_channels;
 use crate::channels::dingtalk::{build_dingtalk_runtime_contexts, DingTalkRuntimeContext};
 use crate::channels::discord::{build_discord_runtime_contexts, DiscordRuntimeContext};
 use crate::channels::email::{build_email_runtime_contexts, EmailRuntimeContext};
@@ -30,9 +31,11 @@ use crate::channels::{
 use crate::config::Config;
 use crate::embedding::EmbeddingProvider;
 use crate::hooks::HookManager;
+use crate::hot_reload::Live;
 use crate::llm::LlmProvider;
 use crate::memory::MemoryManager;
 use crate::memory_backend::MemoryBackend;
+use crate::provider_health::ProviderHealth;
 use crate::skills::SkillManager;
 use crate::tools::ToolRegistry;
 use crate::web::WebAdapter;
@@ -40,23 +43,29 @@ use microclaw_channels::channel_adapter::ChannelRegistry;
 use microclaw_storage::db::Database;
 
 pub struct AppState {
-    pub config: Config,
+    pub config: Arc<Live<Config>>,
     pub channel_registry: Arc<ChannelRegistry>,
     pub db: Arc<Database>,
     pub memory: MemoryManager,
     pub skills: SkillManager,
     pub hooks: Arc<HookManager>,
-    pub llm: Box<dyn LlmProvider>,
+    pub llm: Live<dyn LlmProvider>,
+    pub provider_health: Arc<ProviderHealth>,
     pub llm_provider_overrides: Arc<RwLock<HashMap<String, String>>>,
     pub llm_model_overrides: Arc<RwLock<HashMap<String, String>>>,
     pub embedding: Option<Arc<dyn EmbeddingProvider>>,
     pub memory_backend: Arc<MemoryBackend>,
     pub tools: ToolRegistry,
+    pub mcp_manager: Arc<crate::mcp::McpManager>,
+    /// Rebuilt when a reload changes the `event_webhook_*` settings.
+    pub event_webhook: Live<Option<Arc<crate::event_webhook::EventWebhook>>>,
+    pub chat_rate_limiter: Arc<crate::chat_rate_limit::ChatRateLimiter>,
+    pub chat_run_queue: Arc<crate::chat_run_queue::ChatRunQueue>,
 }
 
 fn prepare_channel_runtimes<T, Build, Register, ModelOverride>(
     config: &Config,
-    channel_key: &str,
+    active: bool,
     registry: &mut ChannelRegistry,
     llm_model_overrides: &mut HashMap<String, String>,
     build: Build,
@@ -68,7 +77,7 @@ where
     Register: Fn(&T, &mut ChannelRegistry),
     ModelOverride: Fn(&T) -> Option<(String, String)>,
 {
-    if !config.channel_enabled(channel_key) {
+    if !active {
         return Vec::new();
     }
 
@@ -142,13 +151,33 @@ pub async fn run(
         }
     }
 
+    let channel_selection = select_channels(&config);
+    if let Some(incomplete) = channel_selection.incomplete.iter().find(|c| c.explicit) {
+        return Err(anyhow!(
+            "Channel '{}' is enabled but not fully configured: missing {}. Set channels.{}.<key> (or on an enabled account), or disable the channel.",
+            incomplete.channel,
+            incomplete.missing.join(", "),
+            incomplete.channel
+        ));
+    }
+    for incomplete in &channel_selection.incomplete {
+        warn!("Skipping partially configured channel {incomplete}");
+    }
+    if !channel_selection.over_limit.is_empty() {
+        warn!(
+            "max_active_channels={} reached; not starting: {}",
+            config.max_active_channels,
+            channel_selection.over_limit.join(", ")
+        );
+    }
+
     // Build channel registry from config
     let mut registry = ChannelRegistry::new();
     let mut telegram_runtimes: Vec<(teloxide::Bot, TelegramRuntimeContext)> = Vec::new();
     let mut llm_model_overrides: HashMap<String, String> = HashMap::new();
     let discord_runtimes: Vec<(String, DiscordRuntimeContext)> = prepare_channel_runtimes(
         &config,
-        "discord",
+        channel_selection.is_active("discord"),
         &mut registry,
         &mut llm_model_overrides,
         build_discord_runtime_contexts,
@@ -168,7 +197,7 @@ pub async fn run(
     );
     let slack_runtimes: Vec<SlackRuntimeContext> = prepare_channel_runtimes(
         &config,
-        "slack",
+        channel_selection.is_active("slack"),
         &mut registry,
         &mut llm_model_overrides,
         build_slack_runtime_contexts,
@@ -187,7 +216,7 @@ pub async fn run(
     );
     let feishu_runtimes: Vec<FeishuRuntimeContext> = prepare_channel_runtimes(
         &config,
-        "feishu",
+        channel_selection.is_active("feishu"),
         &mut registry,
         &mut llm_model_overrides,
         build_feishu_runtime_contexts,
@@ -208,7 +237,7 @@ pub async fn run(
     );
     let matrix_runtimes: Vec<MatrixRuntimeContext> = prepare_channel_runtimes(
         &config,
-        "matrix",
+        channel_selection.is_active("matrix"),
         &mut registry,
         &mut llm_model_overrides,
         build_matrix_runtime_contexts,
@@ -223,7 +252,7 @@ pub async fn run(
     );
     let whatsapp_runtimes: Vec<WhatsAppRuntimeContext> = prepare_channel_runtimes(
         &config,
-        "whatsapp",
+        channel_selection.is_active("whatsapp"),
         &mut registry,
         &mut llm_model_overrides,
         build_whatsapp_runtime_contexts,
@@ -244,7 +273,7 @@ pub async fn run(
     );
     let imessage_runtimes: Vec<IMessageRuntimeContext> = prepare_channel_runtimes(
         &config,
-        "imessage",
+        channel_selection.is_active("imessage"),
         &mut registry,
         &mut llm_model_overrides,
         build_imessage_runtime_contexts,
@@ -263,7 +292,7 @@ pub async fn run(
     );
     let email_runtimes: Vec<EmailRuntimeContext> = prepare_channel_runtimes(
         &config,
-        "email",
+        channel_selection.is_active("email"),
         &mut registry,
         &mut llm_model_overrides,
         build_email_runtime_contexts,
@@ -283,7 +312,7 @@ pub async fn run(
     );
     let nostr_runtimes: Vec<NostrRuntimeContext> = prepare_channel_runtimes(
         &config,
-        "nostr",
+        channel_selection.is_active("nostr"),
         &mut registry,
         &mut llm_model_overrides,
         build_nostr_runtime_contexts,
@@ -302,7 +331,7 @@ pub async fn run(
     );
     let signal_runtimes: Vec<SignalRuntimeContext> = prepare_channel_runtimes(
         &config,
-        "signal",
+        channel_selection.is_active("signal"),
         &mut registry,
         &mut llm_model_overrides,
         build_signal_runtime_contexts,
@@ -321,7 +350,7 @@ pub async fn run(
     );
     let dingtalk_runtimes: Vec<DingTalkRuntimeContext> = prepare_channel_runtimes(
         &config,
-        "dingtalk",
+        channel_selection.is_active("dingtalk"),
         &mut registry,
         &mut llm_model_overrides,
         build_dingtalk_runtime_contexts,
@@ -340,7 +369,7 @@ pub async fn run(
     );
     let qq_runtimes: Vec<QQRuntimeContext> = prepare_channel_runtimes(
         &config,
-        "qq",
+        channel_selection.is_active("qq"),
         &mut registry,
         &mut llm_model_overrides,
         build_qq_runtime_contexts,
@@ -360,7 +389,7 @@ pub async fn run(
     let mut has_irc = false;
     let mut has_web = false;
 
-    if config.channel_enabled("telegram") {
+    if channel_selection.is_active("telegram") {
         if let Some(tg_cfg) = config.channel_config::<TelegramChannelConfig>("telegram") {
             for (token, runtime_ctx) in build_telegram_runtime_contexts(&config) {
                 if let Some(model) = runtime_ctx.model.clone() {
@@ -378,7 +407,7 @@ pub async fn run(
     }
 
     let mut irc_adapter: Option<Arc<IrcAdapter>> = None;
-    if config.channel_enabled("irc") {
+    if channel_selection.is_active("irc") {
         if let Some(irc_cfg) =
             config.channel_config::<crate::channels::irc::IrcChannelConfig>("irc")
         {
@@ -400,47 +429,65 @@ pub async fn run(
         }
     }
 
-    if config.channel_enabled("web") {
+    if channel_selection.is_active("web") {
         has_web = true;
         registry.register(Arc::new(WebAdapter));
     }
 
     let channel_registry = Arc::new(registry);
 
-    let memory_backend = Arc::new(MemoryBackend::new(
-        db.clone(),
-        crate::memory_backend::MemoryMcpClient::discover(&mcp_manager),
-    ));
-    let mut tools = ToolRegistry::new(
-        &config,
+    let memory_backend = Arc::new(
+        MemoryBackend::new(
+            db.clone(),
+            crate::memory_backend::MemoryMcpClient::discover(&mcp_manager),
+        )
+        .with_categories(&config),
+    );
+    let live_config = Arc::new(Live::from(config.clone()));
+    let tools = ToolRegistry::new(
+        live_config.clone(),
         channel_registry.clone(),
         db.clone(),
         memory_backend.clone(),
     );
-
-    for (server, tool_info) in mcp_manager.all_tools() {
-        tools.add_tool(Box::new(crate::tools::mcp::McpTool::new(server, tool_info)));
-    }
+    tools.set_mcp_tools(crate::tools::mcp::mcp_tools(&mcp_manager));
 
     let hooks = Arc::new(HookManager::from_config(&config).with_db(db.clone()));
+    let provider_health = Arc::new(ProviderHealth::from_config(&config));
+    let event_webhook = crate::event_webhook::EventWebhook::from_config(&config);
+    let chat_rate_limiter = Arc::new(crate::chat_rate_limit::ChatRateLimiter::from_config(
+        &config,
+    ));
 
     let state = Arc::new(AppState {
-        config,
+        config: live_config,
         channel_registry,
         db,
         memory,
         skills,
         hooks,
-        llm,
+        provider_health,
+        llm: Live::new(Arc::from(llm)),
         llm_provider_overrides: Arc::new(RwLock::new(HashMap::new())),
         llm_model_overrides: Arc::new(RwLock::new(llm_model_overrides)),
         embedding,
         memory_backend,
         tools,
+        mcp_manager: Arc::new(mcp_manager),
+        event_webhook: event_webhook.into(),
+        chat_rate_limiter,
+        chat_run_queue: Arc::new(crate::chat_run_queue::ChatRunQueue::default()),
     });
 
     crate::scheduler::spawn_scheduler(state.clone());
     crate::scheduler::spawn_reflector(state.clone());
+    crate::scheduler::spawn_db_maintenance(state.clone());
+    crate::scheduler::spawn_tool_call_log_pruner(state.clone());
+    crate::scheduler::spawn_memory_pruner(state.clone());
+    crate::provider_health::spawn_provider_health_probe(state.clone());
+    crate::heartbeat::spawn_heartbeat(state.clone());
+    #[cfg(unix)]
+    crate::hot_reload::spawn_sighup_reload(state.clone());
 
     let has_discord = !discord_runtimes.is_empty();
     if has_discord {
@@ -599,7 +646,8 @@ pub async fn run(
         let web_state = state.clone();
         info!(
             "Starting Web UI server on {}:{}",
-            state.config.web_host, state.config.web_port
+            state.config.load().web_host,
+            state.config.load().web_port
         );
         spawn_guarded("web".to_string(), async move {
             crate::web::start_web_server(web_state).await;
@@ -656,6 +704,16 @@ pub async fn run(
             .await
             .map_err(|e| anyhow!("Failed to listen for Ctrl-C: {e}"))?;
         Ok(())
+    } else if !channel_selection.incomplete.is_empty() {
+        let partial: Vec<String> = channel_selection
+            .incomplete
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        Err(anyhow!(
+            "No channel could start. Partially configured: {}.",
+            partial.join("; ")
+        ))
     } else {
         Err(anyhow!(
             "No channel is enabled. Configure channels.<name>.enabled (or legacy channel settings) for Telegram, Discord, Slack, Feishu, Matrix, WhatsApp, iMessage, Email, Nostr, Signal, DingTalk, QQ, IRC, or web."
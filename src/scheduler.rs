@@ -8,13 +8,15 @@ use tracing::{error, info, warn};
 use crate::agent_engine::process_with_agent;
 use crate::agent_engine::AgentRequestContext;
 use crate::runtime::AppState;
+use crate::tools::export_chat::export_chat_markdown;
 use crate::{db::Memory, memory_quality};
 use microclaw_channels::channel::{
     deliver_and_store_bot_message, get_chat_routing, ChatRouting, ConversationKind,
 };
+use microclaw_channels::channel_adapter::ChannelRegistry;
 use microclaw_core::llm_types::{Message, MessageContent, ResponseContentBlock};
 use microclaw_core::text::floor_char_boundary;
-use microclaw_storage::db::call_blocking;
+use microclaw_storage::db::{call_blocking, Database, ScheduledTask, StoredMessage};
 
 pub fn spawn_scheduler(state: Arc<AppState>) {
     tokio::spawn(async move {
@@ -71,7 +73,6 @@ async fn run_due_tasks(state: &Arc<AppState>) {
         );
 
         let started_at = Utc::now();
-        let started_at_str = started_at.to_rfc3339();
         let routing = get_chat_routing(&state.channel_registry, state.db.clone(), task.chat_id)
             .await
             .ok()
@@ -87,6 +88,24 @@ async fn run_due_tasks(state: &Arc<AppState>) {
                 }
             });
 
+        if task.task_type == crate::tools::schedule::TASK_TYPE_EXPORT {
+            let bot_username = state
+                .config
+                .load()
+                .bot_username_for_channel(&routing.channel_name);
+            let (success, result_summary) = run_export_task(
+                &state.channel_registry,
+                state.db.clone(),
+                &state.config.load().data_dir,
+                &bot_username,
+                &routing.channel_name,
+                &task,
+            )
+            .await;
+            finish_task_run(state, &task, started_at, success, result_summary).await;
+            continue;
+        }
+
         // Run agent loop with the task prompt
         let (success, result_summary) = match process_with_agent(
             state,
@@ -102,7 +121,10 @@ async fn run_due_tasks(state: &Arc<AppState>) {
         {
             Ok(response) => {
                 if !response.is_empty() {
-                    let bot_username = state.config.bot_username_for_channel(&routing.channel_name);
+                    let bot_username = state
+                        .config
+                        .load()
+                        .bot_username_for_channel(&routing.channel_name);
                     let _ = deliver_and_store_bot_message(
                         &state.channel_registry,
                         state.db.clone(),
@@ -122,7 +144,10 @@ async fn run_due_tasks(state: &Arc<AppState>) {
             Err(e) => {
                 error!("Scheduler: task #{} failed: {e}", task.id);
                 let err_text = format!("Scheduled task #{} failed: {e}", task.id);
-                let bot_username = state.config.bot_username_for_channel(&routing.channel_name);
+                let bot_username = state
+                    .config
+                    .load()
+                    .bot_username_for_channel(&routing.channel_name);
                 let _ = deliver_and_store_bot_message(
                     &state.channel_registry,
                     state.db.clone(),
@@ -135,82 +160,185 @@ async fn run_due_tasks(state: &Arc<AppState>) {
             }
         };
 
-        let finished_at = Utc::now();
-        let finished_at_str = finished_at.to_rfc3339();
-        let duration_ms = (finished_at - started_at).num_milliseconds();
+        finish_task_run(state, &task, started_at, success, result_summary).await;
+    }
+}
+
+/// Log a task run, dead-letter failures, and advance the task's next run.
+async fn finish_task_run(
+    state: &Arc<AppState>,
+    task: &ScheduledTask,
+    started_at: chrono::DateTime<Utc>,
+    success: bool,
+    result_summary: Option<String>,
+) {
+    let started_at_str = started_at.to_rfc3339();
+    let task_id = task.id;
+    let chat_id = task.chat_id;
+    let finished_at = Utc::now();
+    let finished_at_str = finished_at.to_rfc3339();
+    let duration_ms = (finished_at - started_at).num_milliseconds();
+
+    // Log the task run
+    let log_summary = result_summary.clone();
+    let started_for_log = started_at_str.clone();
+    let finished_for_log = finished_at_str.clone();
+    if let Err(e) = call_blocking(state.db.clone(), move |db| {
+        db.log_task_run(
+            task_id,
+            chat_id,
+            &started_for_log,
+            &finished_for_log,
+            duration_ms,
+            success,
+            log_summary.as_deref(),
+        )?;
+        Ok(())
+    })
+    .await
+    {
+        error!("Scheduler: failed to log task run for #{}: {e}", task_id);
+    }
 
-        // Log the task run
-        let log_summary = result_summary.clone();
-        let started_for_log = started_at_str.clone();
-        let finished_for_log = finished_at_str.clone();
+    if let Some(webhook) = state.event_webhook.load().as_ref() {
+        let channel = microclaw_channels::channel::get_chat_channel_raw(state.db.clone(), chat_id)
+            .await
+            .ok()
+            .flatten();
+        webhook.emit(
+            "task_run",
+            chat_id,
+            channel.as_deref(),
+            serde_json::json!({
+                "task_id": task_id,
+                "success": success,
+                "duration_ms": duration_ms,
+                "summary": result_summary,
+            }),
+        );
+    }
+
+    if !success {
+        let started_for_dlq = started_at_str.clone();
+        let finished_for_dlq = finished_at_str.clone();
+        let dlq_summary = result_summary.clone();
         if let Err(e) = call_blocking(state.db.clone(), move |db| {
-            db.log_task_run(
-                task.id,
-                task.chat_id,
-                &started_for_log,
-                &finished_for_log,
+            db.insert_scheduled_task_dlq(
+                task_id,
+                chat_id,
+                &started_for_dlq,
+                &finished_for_dlq,
                 duration_ms,
-                success,
-                log_summary.as_deref(),
+                dlq_summary.as_deref(),
             )?;
             Ok(())
         })
         .await
         {
-            error!("Scheduler: failed to log task run for #{}: {e}", task.id);
+            error!(
+                "Scheduler: failed to enqueue DLQ for task #{}: {e}",
+                task_id
+            );
         }
+    }
 
-        if !success {
-            let started_for_dlq = started_at_str.clone();
-            let finished_for_dlq = finished_at_str.clone();
-            let dlq_summary = result_summary.clone();
-            if let Err(e) = call_blocking(state.db.clone(), move |db| {
-                db.insert_scheduled_task_dlq(
-                    task.id,
-                    task.chat_id,
-                    &started_for_dlq,
-                    &finished_for_dlq,
-                    duration_ms,
-                    dlq_summary.as_deref(),
-                )?;
-                Ok(())
-            })
-            .await
-            {
-                error!(
-                    "Scheduler: failed to enqueue DLQ for task #{}: {e}",
-                    task.id
-                );
+    // Compute next run
+    let tz: chrono_tz::Tz = state
+        .config
+        .load()
+        .timezone
+        .parse()
+        .unwrap_or(chrono_tz::Tz::UTC);
+    let next_run = if task.schedule_type == "cron" {
+        match cron::Schedule::from_str(&task.schedule_value) {
+            Ok(schedule) => schedule
+                .upcoming(tz)
+                .next()
+                .map(|t| t.with_timezone(&chrono::Utc).to_rfc3339()),
+            Err(e) => {
+                error!("Scheduler: invalid cron for task #{}: {e}", task_id);
+                None
             }
         }
+    } else {
+        None // one-shot
+    };
 
-        // Compute next run
-        let tz: chrono_tz::Tz = state.config.timezone.parse().unwrap_or(chrono_tz::Tz::UTC);
-        let next_run = if task.schedule_type == "cron" {
-            match cron::Schedule::from_str(&task.schedule_value) {
-                Ok(schedule) => schedule
-                    .upcoming(tz)
-                    .next()
-                    .map(|t| t.with_timezone(&chrono::Utc).to_rfc3339()),
-                Err(e) => {
-                    error!("Scheduler: invalid cron for task #{}: {e}", task.id);
-                    None
-                }
-            }
-        } else {
-            None // one-shot
-        };
+    let started_for_update = started_at_str.clone();
+    if let Err(e) = call_blocking(state.db.clone(), move |db| {
+        db.update_task_after_run(task_id, &started_for_update, next_run.as_deref())?;
+        Ok(())
+    })
+    .await
+    {
+        error!("Scheduler: failed to update task #{}: {e}", task_id);
+    }
+}
 
-        let started_for_update = started_at_str.clone();
-        if let Err(e) = call_blocking(state.db.clone(), move |db| {
-            db.update_task_after_run(task.id, &started_for_update, next_run.as_deref())?;
-            Ok(())
-        })
-        .await
+/// Run an `export` task: write the chat history to a markdown file under
+/// `<data_dir>/exports`, then send it as an attachment when the channel supports
+/// it, falling back to a text notice with the saved path.
+async fn run_export_task(
+    registry: &ChannelRegistry,
+    db: Arc<Database>,
+    data_dir: &str,
+    bot_username: &str,
+    channel_name: &str,
+    task: &ScheduledTask,
+) -> (bool, Option<String>) {
+    let (count, path) = match export_chat_markdown(db.clone(), data_dir, task.chat_id, None).await {
+        Ok(v) => v,
+        Err(e) => {
+            error!("Scheduler: export task #{} failed: {e}", task.id);
+            let err_text = format!("Scheduled export #{} failed: {e}", task.id);
+            let _ =
+                deliver_and_store_bot_message(registry, db, bot_username, task.chat_id, &err_text)
+                    .await;
+            return (false, Some(format!("Error: {e}")));
+        }
+    };
+    let summary = format!("Exported {count} messages to {}", path.display());
+    info!("Scheduler: export task #{}: {summary}", task.id);
+
+    let caption = format!("Scheduled export #{}: {count} messages", task.id);
+    if let Some(adapter) = registry.get(channel_name).filter(|a| !a.is_local_only()) {
+        let chat_id = task.chat_id;
+        let external_chat_id = call_blocking(db.clone(), move |d| d.get_chat_external_id(chat_id))
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or_else(|| chat_id.to_string());
+        match adapter
+            .send_attachment(&external_chat_id, &path, Some(&caption))
+            .await
         {
-            error!("Scheduler: failed to update task #{}: {e}", task.id);
+            Ok(content) => {
+                let msg = StoredMessage {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    chat_id,
+                    sender_name: bot_username.to_string(),
+                    content,
+                    is_from_bot: true,
+                    timestamp: Utc::now().to_rfc3339(),
+                };
+                if let Err(e) = call_blocking(db, move |d| d.store_message(&msg)).await {
+                    warn!(
+                        "Scheduler: failed to store export message for task #{}: {e}",
+                        task.id
+                    );
+                }
+                return (true, Some(summary));
+            }
+            Err(e) => warn!(
+                "Scheduler: export attachment for task #{} not sent, falling back to text: {e}",
+                task.id
+            ),
         }
     }
+
+    let notice = format!("{caption} saved to {}", path.display());
+    let _ = deliver_and_store_bot_message(registry, db, bot_username, task.chat_id, &notice).await;
+    (true, Some(summary))
 }
 
 const REFLECTOR_SYSTEM_PROMPT: &str = r#"You are a memory extraction specialist. Extract durable, factual information from conversations.
@@ -219,9 +347,9 @@ Rules:
 - Extract ONLY concrete facts, preferences, expertise, or notable events
 - IGNORE: greetings, small talk, unanswered questions, transient requests
 - Each memory < 100 characters, specific and concrete
-- Category must be exactly one of: PROFILE (user attributes/preferences), KNOWLEDGE (facts/expertise), EVENT (significant things that happened)
+- Category must be exactly one of: {categories}
 - If a new memory updates or supersedes an existing one, add "supersedes_id": <id> to replace it
-- Output ONLY valid JSON array: [{"content":"...","category":"PROFILE","supersedes_id":null}]
+- Output ONLY valid JSON array: [{"content":"...","category":"{example_category}","supersedes_id":null}]
 - If nothing worth remembering: []
 
 CRITICAL — how to memorize bugs and problems:
@@ -236,6 +364,27 @@ CRITICAL — how to memorize bugs and problems:
   GOOD: "TODO: strictly follow TOOLS.md rules for every tool call"
 - The memory should tell the agent HOW TO BEHAVE CORRECTLY, never describe the broken behavior."#;
 
+fn reflector_system_prompt(categories: &[crate::config::MemoryCategory]) -> String {
+    let listed = categories
+        .iter()
+        .map(|c| {
+            if c.description.is_empty() {
+                c.name.clone()
+            } else {
+                format!("{} ({})", c.name, c.description)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    let example = categories
+        .first()
+        .map(|c| c.name.as_str())
+        .unwrap_or("KNOWLEDGE");
+    REFLECTOR_SYSTEM_PROMPT
+        .replace("{categories}", &listed)
+        .replace("{example_category}", example)
+}
+
 fn jaccard_similar(a: &str, b: &str, threshold: f64) -> bool {
     use std::collections::HashSet;
     let a_words: HashSet<&str> = a.split_whitespace().collect();
@@ -336,15 +485,15 @@ async fn backfill_embeddings(state: &Arc<AppState>) {
 }
 
 pub fn spawn_reflector(state: Arc<AppState>) {
-    if !state.config.reflector_enabled {
+    if !state.config.load().reflector_enabled {
         info!("Reflector disabled by config");
         return;
     }
-    let interval_secs = state.config.reflector_interval_mins * 60;
+    let interval_secs = state.config.load().reflector_interval_mins * 60;
     tokio::spawn(async move {
         info!(
             "Reflector started (interval: {}min)",
-            state.config.reflector_interval_mins
+            state.config.load().reflector_interval_mins
         );
         loop {
             tokio::time::sleep(std::time::Duration::from_secs(interval_secs)).await;
@@ -353,13 +502,330 @@ pub fn spawn_reflector(state: Arc<AppState>) {
     });
 }
 
+/// `hours` as a sleep interval, or `None` when it overflows.
+fn hours_interval(hours: u64) -> Option<std::time::Duration> {
+    hours.checked_mul(3600).map(std::time::Duration::from_secs)
+}
+
+pub fn spawn_db_maintenance(state: Arc<AppState>) {
+    let interval_hours = state.config.load().db_maintenance_interval_hours;
+    if interval_hours == 0 {
+        info!("Database maintenance disabled by config");
+        return;
+    }
+    let Some(interval) = hours_interval(interval_hours) else {
+        warn!("Database maintenance disabled: interval of {interval_hours}h is out of range");
+        return;
+    };
+    tokio::spawn(async move {
+        info!("Database maintenance started (interval: {interval_hours}h)");
+        loop {
+            tokio::time::sleep(interval).await;
+            let started = std::time::Instant::now();
+            match call_blocking(state.db.clone(), |db| db.run_maintenance()).await {
+                Ok(report) => info!(
+                    "Database maintenance done in {}ms: {} -> {} pages ({} free before)",
+                    started.elapsed().as_millis(),
+                    report.pages_before,
+                    report.pages_after,
+                    report.free_pages_before
+                ),
+                Err(e) => error!("Database maintenance failed: {e}"),
+            }
+        }
+    });
+}
+
+const TOOL_CALL_LOG_PRUNE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(6 * 3600);
+
+/// Cutoff before which tool call logs are dropped, or `None` when logs are
+/// kept forever.
+fn tool_call_log_cutoff(retention_days: u64, now: chrono::DateTime<chrono::Utc>) -> Option<String> {
+    if retention_days == 0 {
+        return None;
+    }
+    let cutoff = i64::try_from(retention_days)
+        .ok()
+        .and_then(chrono::Duration::try_days)
+        .and_then(|retention| now.checked_sub_signed(retention))?;
+    Some(cutoff.to_rfc3339())
+}
+
+pub fn spawn_tool_call_log_pruner(state: Arc<AppState>) {
+    tokio::spawn(async move {
+        loop {
+            // Read per pass so a reload can change or disable retention.
+            let retention_days = state.config.load().tool_call_log_retention_days;
+            if let Some(cutoff) = tool_call_log_cutoff(retention_days, chrono::Utc::now()) {
+                match call_blocking(state.db.clone(), move |db| {
+                    db.prune_tool_call_logs_before(&cutoff)
+                })
+                .await
+                {
+                    Ok(0) => {}
+                    Ok(n) => info!("Pruned {n} tool call logs older than {retention_days} days"),
+                    Err(e) => error!("Tool call log pruning failed: {e}"),
+                }
+            }
+            tokio::time::sleep(TOOL_CALL_LOG_PRUNE_INTERVAL).await;
+        }
+    });
+}
+
+pub fn spawn_memory_pruner(state: Arc<AppState>) {
+    let interval_hours = state.config.load().memory_prune_interval_hours;
+    if interval_hours == 0 {
+        info!("Memory pruning disabled by config");
+        return;
+    }
+    let Some(interval) = hours_interval(interval_hours) else {
+        warn!("Memory pruning disabled: interval of {interval_hours}h is out of range");
+        return;
+    };
+    tokio::spawn(async move {
+        info!("Memory pruning started (interval: {interval_hours}h)");
+        let mut cache = MemoryPruneCache::default();
+        loop {
+            tokio::time::sleep(interval).await;
+            let report = prune_memories(
+                state.db.clone(),
+                state.embedding.as_ref(),
+                &state.config.load(),
+                &mut cache,
+            )
+            .await;
+            info!(
+                "Memory pruning done: {} duplicates merged, {} decayed memories archived across {} chats",
+                report.merged, report.archived, report.chats
+            );
+        }
+    });
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct MemoryPruneReport {
+    chats: usize,
+    merged: usize,
+    archived: usize,
+}
+
+/// What earlier pruning passes saw of each active memory, so an unchanged
+/// memory is neither embedded nor compared with another unchanged one again.
+#[derive(Default)]
+struct MemoryPruneCache {
+    seen: std::collections::HashMap<i64, SeenMemory>,
+}
+
+struct SeenMemory {
+    content: String,
+    embedding: Option<Vec<f32>>,
+}
+
+/// Archive decayed memories, then archive near-duplicates within each chat in
+/// favour of the higher-confidence copy, which is kept as it is.
+async fn prune_memories(
+    db: Arc<Database>,
+    embedding: Option<&Arc<dyn crate::embedding::EmbeddingProvider>>,
+    config: &crate::config::Config,
+    cache: &mut MemoryPruneCache,
+) -> MemoryPruneReport {
+    let mut report = MemoryPruneReport::default();
+    let floor = config.memory_prune_confidence_floor;
+    let stale_days = config.memory_prune_stale_days.min(i64::MAX as u64) as i64;
+    match call_blocking(db.clone(), move |db| {
+        db.archive_decayed_memories(floor, stale_days)
+    })
+    .await
+    {
+        Ok(archived) => report.archived = archived,
+        Err(e) => error!("Memory pruning: failed to archive decayed memories: {e}"),
+    }
+
+    let chat_ids = match call_blocking(db.clone(), |db| db.get_memory_chat_ids()).await {
+        Ok(ids) => ids,
+        Err(e) => {
+            error!("Memory pruning: failed to list chats: {e}");
+            return report;
+        }
+    };
+    let mut active_ids = std::collections::HashSet::new();
+    for chat_id in chat_ids {
+        let memories: Vec<Memory> = match call_blocking(db.clone(), move |db| {
+            db.get_all_memories_for_chat(chat_id)
+        })
+        .await
+        {
+            Ok(all) => all.into_iter().filter(|m| !m.is_archived).collect(),
+            Err(e) => {
+                warn!("Memory pruning: failed to load memories for chat {chat_id:?}: {e}");
+                continue;
+            }
+        };
+        active_ids.extend(memories.iter().map(|m| m.id));
+        let fresh: Vec<bool> = memories
+            .iter()
+            .map(|m| cache.seen.get(&m.id).is_none_or(|s| s.content != m.content))
+            .collect();
+        if memories.len() < 2 || !fresh.contains(&true) {
+            remember_memories(cache, &memories, None);
+            continue;
+        }
+        report.chats += 1;
+
+        let embeddings = match embedding {
+            Some(provider) => embed_changed(provider.as_ref(), &memories, &fresh, cache).await,
+            None => None,
+        };
+        let pairs = duplicate_pairs(&memories, |a, b| {
+            if !fresh[a] && !fresh[b] {
+                return false;
+            }
+            match &embeddings {
+                Some(vectors) => {
+                    cosine_similarity(&vectors[a], &vectors[b]) >= config.memory_dedup_similarity
+                }
+                None => {
+                    normalized_memory_text(&memories[a].content)
+                        == normalized_memory_text(&memories[b].content)
+                }
+            }
+        });
+        remember_memories(cache, &memories, embeddings);
+
+        for (keeper_idx, dup_idx) in pairs {
+            let (keeper_id, dup_id) = (memories[keeper_idx].id, memories[dup_idx].id);
+            let reason = format!("dedup: duplicate of memory #{keeper_id}");
+            match call_blocking(db.clone(), move |db| {
+                db.merge_duplicate_memory(dup_id, keeper_id, &reason)
+            })
+            .await
+            {
+                Ok(true) => {
+                    cache.seen.remove(&dup_id);
+                    report.merged += 1;
+                }
+                Ok(false) => {}
+                Err(e) => {
+                    warn!("Memory pruning: failed to merge memory {dup_id} into {keeper_id}: {e}")
+                }
+            }
+        }
+    }
+    cache.seen.retain(|id, _| active_ids.contains(id));
+    report
+}
+
+fn remember_memories(
+    cache: &mut MemoryPruneCache,
+    memories: &[Memory],
+    embeddings: Option<Vec<Vec<f32>>>,
+) {
+    let mut embeddings = embeddings.map(Vec::into_iter);
+    for memory in memories {
+        let embedding = embeddings.as_mut().and_then(Iterator::next);
+        let entry = cache.seen.entry(memory.id).or_insert_with(|| SeenMemory {
+            content: String::new(),
+            embedding: None,
+        });
+        if entry.content != memory.content {
+            entry.content = memory.content.clone();
+            entry.embedding = None;
+        }
+        if embedding.is_some() {
+            entry.embedding = embedding;
+        }
+    }
+}
+
+/// Embeddings for every memory, reusing the cached vector of each unchanged
+/// one; `None` if an embedding fails so the caller can fall back to text
+/// comparison for the whole chat.
+async fn embed_changed(
+    provider: &dyn crate::embedding::EmbeddingProvider,
+    memories: &[Memory],
+    fresh: &[bool],
+    cache: &MemoryPruneCache,
+) -> Option<Vec<Vec<f32>>> {
+    let mut vectors = Vec::with_capacity(memories.len());
+    for (memory, &fresh) in memories.iter().zip(fresh) {
+        let cached = (!fresh)
+            .then(|| cache.seen.get(&memory.id))
+            .flatten()
+            .and_then(|s| s.embedding.clone());
+        if let Some(vector) = cached {
+            vectors.push(vector);
+            continue;
+        }
+        match provider.embed(&memory.content).await {
+            Ok(v) => vectors.push(v),
+            Err(e) => {
+                warn!("Memory pruning: embedding failed, falling back to text comparison: {e}");
+                return None;
+            }
+        }
+    }
+    Some(vectors)
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f64 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let (mut dot, mut norm_a, mut norm_b) = (0.0f64, 0.0f64, 0.0f64);
+    for (x, y) in a.iter().zip(b) {
+        let (x, y) = (f64::from(*x), f64::from(*y));
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+/// Lowercased, whitespace-collapsed text without trailing punctuation.
+fn normalized_memory_text(content: &str) -> String {
+    content
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end_matches(|c: char| c.is_ascii_punctuation())
+        .to_lowercase()
+}
+
+/// `(keeper, duplicate)` index pairs. Memories are visited from highest to
+/// lowest confidence (newest first on ties), so each duplicate is matched to
+/// the strongest earlier memory it resembles.
+fn duplicate_pairs(
+    memories: &[Memory],
+    similar: impl Fn(usize, usize) -> bool,
+) -> Vec<(usize, usize)> {
+    let mut order: Vec<usize> = (0..memories.len()).collect();
+    order.sort_by(|&a, &b| {
+        memories[b]
+            .confidence
+            .total_cmp(&memories[a].confidence)
+            .then_with(|| memories[b].updated_at.cmp(&memories[a].updated_at))
+    });
+    let mut keepers: Vec<usize> = Vec::new();
+    let mut pairs = Vec::new();
+    for idx in order {
+        match keepers.iter().find(|&&k| similar(k, idx)) {
+            Some(&keeper) => pairs.push((keeper, idx)),
+            None => keepers.push(idx),
+        }
+    }
+    pairs
+}
+
 async fn run_reflector(state: &Arc<AppState>) {
     #[cfg(feature = "sqlite-vec")]
     backfill_embeddings(state).await;
 
     let _ = call_blocking(state.db.clone(), move |db| db.archive_stale_memories(30)).await;
 
-    let lookback_secs = (state.config.reflector_interval_mins * 2 * 60) as i64;
+    let lookback_secs = (state.config.load().reflector_interval_mins * 2 * 60) as i64;
     let since = (Utc::now() - chrono::Duration::seconds(lookback_secs)).to_rfc3339();
 
     let chat_ids = match call_blocking(state.db.clone(), move |db| {
@@ -415,9 +881,24 @@ async fn reflect_for_chat(state: &Arc<AppState>, chat_id: i64) {
     let latest_message_ts = messages.last().map(|m| m.timestamp.clone());
 
     // 3. Format conversation for the LLM
+    let channel = call_blocking(state.db.clone(), move |db| db.get_chat_channel(chat_id))
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or_default();
     let conversation = messages
         .iter()
-        .map(|m| format!("[{}]: {}", m.sender_name, m.content))
+        .map(|m| {
+            let sender_name = if m.is_from_bot {
+                m.sender_name.clone()
+            } else {
+                state
+                    .config
+                    .load()
+                    .normalize_sender_name(&channel, &m.sender_name)
+            };
+            format!("[{sender_name}]: {}", m.content)
+        })
         .collect::<Vec<_>>()
         .join("\n");
 
@@ -451,7 +932,12 @@ async fn reflect_for_chat(state: &Arc<AppState>, chat_id: i64) {
     };
     let response = match state
         .llm
-        .send_message(REFLECTOR_SYSTEM_PROMPT, vec![user_msg], None)
+        .load()
+        .send_message(
+            &reflector_system_prompt(&state.config.load().memory_categories),
+            vec![user_msg],
+            None,
+        )
         .await
     {
         Ok(r) => r,
@@ -584,14 +1070,10 @@ async fn reflect_for_chat(state: &Arc<AppState>, chat_id: i64) {
             Some(s) => s,
             None => continue,
         };
-        let category = item
-            .get("category")
-            .and_then(|v| v.as_str())
-            .unwrap_or("KNOWLEDGE")
-            .to_ascii_uppercase();
-        if !matches!(category.as_str(), "PROFILE" | "KNOWLEDGE" | "EVENT") {
-            continue;
-        }
+        let category = state
+            .config
+            .load()
+            .normalize_memory_category(item.get("category").and_then(|v| v.as_str()));
         let content = match memory_quality::normalize_memory_content(content, 180) {
             Some(c) => c,
             None => continue,
@@ -812,6 +1294,22 @@ mod tests {
         assert!(!jaccard_similar("hello", "", 0.5));
     }
 
+    #[test]
+    fn test_reflector_prompt_lists_configured_categories() {
+        let prompt = reflector_system_prompt(&crate::config::MemoryCategory::defaults());
+        assert!(
+            prompt.contains("PROFILE (user attributes/preferences), KNOWLEDGE (facts/expertise)")
+        );
+        assert!(!prompt.contains("{categories}"));
+        assert!(prompt.contains(r#""category":"PROFILE""#));
+
+        let custom = vec![crate::config::MemoryCategory {
+            name: "TASK".into(),
+            description: String::new(),
+        }];
+        assert!(reflector_system_prompt(&custom).contains("exactly one of: TASK\n"));
+    }
+
     #[test]
     fn test_reflector_prompt_includes_memory_poisoning_guardrails() {
         assert!(REFLECTOR_SYSTEM_PROMPT.contains("CRITICAL"));
@@ -838,4 +1336,222 @@ mod tests {
             "Ensure TOOLS.md rules are followed for every tool call"
         ));
     }
+
+    #[test]
+    fn test_duplicate_pairs_keep_highest_confidence() {
+        let memory = |id: i64, content: &str, confidence: f64| Memory {
+            id,
+            chat_id: Some(1),
+            content: content.into(),
+            category: "KNOWLEDGE".into(),
+            created_at: "2024-01-01T00:00:00Z".into(),
+            updated_at: "2024-01-01T00:00:00Z".into(),
+            embedding_model: None,
+            confidence,
+            source: "tool".into(),
+            last_seen_at: "2024-01-01T00:00:00Z".into(),
+            is_archived: false,
+            archived_at: None,
+        };
+        let memories = vec![
+            memory(1, "likes  tea", 0.6),
+            memory(2, "Lives in Paris", 0.8),
+            memory(3, "Likes tea.", 0.9),
+            memory(4, "LIKES TEA", 0.5),
+        ];
+        let pairs = duplicate_pairs(&memories, |a, b| {
+            normalized_memory_text(&memories[a].content)
+                == normalized_memory_text(&memories[b].content)
+        });
+        assert_eq!(pairs, vec![(2, 0), (2, 3)]);
+        assert_eq!(normalized_memory_text("  Likes   Tea!! "), "likes tea");
+    }
+
+    #[test]
+    fn test_hours_interval_rejects_overflow() {
+        assert_eq!(
+            super::hours_interval(2),
+            Some(std::time::Duration::from_secs(7200))
+        );
+        assert_eq!(super::hours_interval(u64::MAX), None);
+    }
+
+    #[test]
+    fn test_tool_call_log_cutoff() {
+        let now = chrono::DateTime::parse_from_rfc3339("2026-03-31T00:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        assert_eq!(
+            super::tool_call_log_cutoff(30, now).as_deref(),
+            Some("2026-03-01T00:00:00+00:00")
+        );
+        assert_eq!(super::tool_call_log_cutoff(0, now), None);
+        assert_eq!(super::tool_call_log_cutoff(u64::MAX, now), None);
+    }
+
+    #[test]
+    fn test_cosine_similarity() {
+        assert!((cosine_similarity(&[1.0, 0.0], &[2.0, 0.0]) - 1.0).abs() < 1e-9);
+        assert!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]).abs() < 1e-9);
+        assert_eq!(cosine_similarity(&[1.0], &[1.0, 0.0]), 0.0);
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 0.0]), 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_prune_memories_merges_text_duplicates() {
+        let dir =
+            std::env::temp_dir().join(format!("microclaw_mem_prune_{}", uuid::Uuid::new_v4()));
+        let db = Arc::new(Database::new(dir.join("runtime").to_str().unwrap()).unwrap());
+        let strong = db
+            .insert_memory_with_metadata(None, "Prefers dark mode.", "PROFILE", "explicit", 0.9)
+            .unwrap();
+        let weak = db
+            .insert_memory_with_metadata(None, "prefers dark mode", "PROFILE", "reflector", 0.5)
+            .unwrap();
+        let other = db
+            .insert_memory_with_metadata(None, "Works at Acme", "PROFILE", "explicit", 0.7)
+            .unwrap();
+        let strong_before = db.get_memory_by_id(strong).unwrap().unwrap();
+
+        let config = crate::config::Config::test_defaults();
+        let mut cache = MemoryPruneCache::default();
+        let report = prune_memories(db.clone(), None, &config, &mut cache).await;
+        assert_eq!(
+            report,
+            MemoryPruneReport {
+                chats: 1,
+                merged: 1,
+                archived: 0
+            }
+        );
+        // The keeper is left as it was; only the weaker copy is archived.
+        assert_eq!(
+            db.get_memory_by_id(strong).unwrap().unwrap().updated_at,
+            strong_before.updated_at
+        );
+        assert!(db.get_memory_by_id(weak).unwrap().unwrap().is_archived);
+        let mut active: Vec<i64> = db
+            .get_all_memories_for_chat(None)
+            .unwrap()
+            .into_iter()
+            .filter(|m| !m.is_archived)
+            .map(|m| m.id)
+            .collect();
+        active.sort();
+        assert_eq!(active, vec![strong, other]);
+
+        // Nothing left to merge on the next pass.
+        let report = prune_memories(db.clone(), None, &config, &mut cache).await;
+        assert_eq!((report.chats, report.merged), (0, 0));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    struct CountingEmbedding {
+        calls: std::sync::Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl crate::embedding::EmbeddingProvider for CountingEmbedding {
+        async fn embed(&self, text: &str) -> anyhow::Result<Vec<f32>> {
+            self.calls.lock().unwrap().push(text.to_string());
+            Ok(if text.to_lowercase().contains("tea") {
+                vec![1.0, 0.0]
+            } else {
+                vec![0.0, 1.0]
+            })
+        }
+
+        fn model(&self) -> &str {
+            "counting"
+        }
+
+        fn dimension(&self) -> usize {
+            2
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prune_memories_embeds_only_new_or_changed_memories() {
+        let dir =
+            std::env::temp_dir().join(format!("microclaw_mem_embed_{}", uuid::Uuid::new_v4()));
+        let db = Arc::new(Database::new(dir.join("runtime").to_str().unwrap()).unwrap());
+        db.insert_memory_with_metadata(Some(1), "Likes tea", "PROFILE", "explicit", 0.9)
+            .unwrap();
+        db.insert_memory_with_metadata(Some(1), "Works at Acme", "PROFILE", "explicit", 0.8)
+            .unwrap();
+        let counting = Arc::new(CountingEmbedding {
+            calls: std::sync::Mutex::new(Vec::new()),
+        });
+        let provider: Arc<dyn crate::embedding::EmbeddingProvider> = counting.clone();
+        let config = crate::config::Config::test_defaults();
+        let mut cache = MemoryPruneCache::default();
+
+        let report = prune_memories(db.clone(), Some(&provider), &config, &mut cache).await;
+        assert_eq!(report.merged, 0);
+        assert_eq!(counting.calls.lock().unwrap().len(), 2);
+
+        // An unchanged chat is not embedded again.
+        prune_memories(db.clone(), Some(&provider), &config, &mut cache).await;
+        assert_eq!(counting.calls.lock().unwrap().len(), 2);
+
+        let dup = db
+            .insert_memory_with_metadata(Some(1), "Enjoys tea", "PROFILE", "reflector", 0.4)
+            .unwrap();
+        let report = prune_memories(db.clone(), Some(&provider), &config, &mut cache).await;
+        assert_eq!(report.merged, 1);
+        let calls = counting.calls.lock().unwrap().clone();
+        assert_eq!(calls.len(), 3);
+        assert_eq!(calls[2], "Enjoys tea");
+        assert!(db.get_memory_by_id(dup).unwrap().unwrap().is_archived);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_run_export_task_writes_archive_and_notifies_chat() {
+        let dir =
+            std::env::temp_dir().join(format!("microclaw_export_task_{}", uuid::Uuid::new_v4()));
+        let db = Arc::new(Database::new(dir.join("runtime").to_str().unwrap()).unwrap());
+        db.upsert_chat(42, Some("web"), "web").unwrap();
+        db.store_message(&StoredMessage {
+            id: "m1".into(),
+            chat_id: 42,
+            sender_name: "alice".into(),
+            content: "hello there".into(),
+            is_from_bot: false,
+            timestamp: Utc::now().to_rfc3339(),
+        })
+        .unwrap();
+        let task_id = db
+            .create_scheduled_task_with_type(
+                42,
+                crate::tools::schedule::TASK_TYPE_EXPORT,
+                "Export chat history to markdown",
+                "cron",
+                "0 0 9 * * *",
+                &Utc::now().to_rfc3339(),
+            )
+            .unwrap();
+        let task = db.get_task_by_id(task_id).unwrap().unwrap();
+
+        let mut registry = ChannelRegistry::new();
+        registry.register(Arc::new(crate::web::WebAdapter));
+        let data_dir = dir.to_string_lossy().to_string();
+        let (success, summary) =
+            run_export_task(&registry, db.clone(), &data_dir, "bot", "web", &task).await;
+
+        assert!(success);
+        assert!(summary.unwrap().starts_with("Exported 1 messages"));
+        let exports: Vec<_> = std::fs::read_dir(dir.join("exports"))
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .collect();
+        assert_eq!(exports.len(), 1);
+        let content = std::fs::read_to_string(&exports[0]).unwrap();
+        assert!(content.contains("hello there"));
+
+        let messages = db.get_all_messages(42).unwrap();
+        let notice = messages.iter().find(|m| m.is_from_bot).unwrap();
+        assert!(notice.content.contains("Scheduled export #"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
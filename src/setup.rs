@@ -530,6 +530,7 @@ fn default_souls_dir_for_setup() -> String {
 #[derive(Clone, Copy, PartialEq, Eq)]
 enum ProviderProtocol {
     Anthropic,
+    Gemini,
     OpenAiCompat,
 }
 
@@ -597,6 +598,17 @@ const PROVIDER_PRESETS: &[ProviderPreset] = &[
             "gemini-2.5-flash-lite",
         ],
     },
+    ProviderPreset {
+        id: "gemini",
+        label: "Google Gemini (native API)",
+        protocol: ProviderProtocol::Gemini,
+        default_base_url: "https://generativelanguage.googleapis.com/v1beta",
+        models: &[
+            "gemini-2.5-flash",
+            "gemini-2.5-pro",
+            "gemini-2.5-flash-lite",
+        ],
+    },
     ProviderPreset {
         id: "alibaba",
         label: "Alibaba Cloud (Qwen / DashScope)",
@@ -3693,6 +3705,34 @@ fn perform_online_validation(
             )));
         }
         checks.push(format!("LLM OK (anthropic, model={model})"));
+    } else if protocol == ProviderProtocol::Gemini {
+        let base = if base_url.is_empty() {
+            preset.map(|p| p.default_base_url).unwrap_or_default()
+        } else {
+            base_url
+        };
+        let body = serde_json::json!({
+            "contents": [{"role": "user", "parts": [{"text": "hi"}]}],
+            "generationConfig": {"maxOutputTokens": VALIDATION_MAX_OUTPUT_TOKENS}
+        });
+        let resp = client
+            .post(format!(
+                "{}/models/{model}:generateContent",
+                base.trim_end_matches('/')
+            ))
+            .header("x-goog-api-key", api_key)
+            .header("content-type", "application/json")
+            .body(body.to_string())
+            .send()?;
+        let status = resp.status();
+        if !status.is_success() {
+            let text = resp.text().unwrap_or_default();
+            return Err(MicroClawError::Config(format!(
+                "LLM validation failed: {}",
+                extract_openai_error_detail(status, &text)
+            )));
+        }
+        checks.push(format!("LLM OK (gemini, model={model})"));
     } else {
         let base = resolve_openai_compat_validation_base(provider, base_url, preset);
         let resp = if is_openai_codex_provider(provider) {
@@ -15,6 +15,22 @@ pub struct SkillMetadata {
     pub env_file: Option<String>,
 }
 
+/// A loaded skill ready to hand to the agent.
+#[derive(Debug, Clone)]
+pub struct SkillActivation {
+    pub meta: SkillMetadata,
+    pub instructions: String,
+    /// Files shipped alongside SKILL.md as `(path relative to the skill
+    /// directory, size in bytes)`, sorted by path.
+    pub files: Vec<(String, u64)>,
+    /// Whether the listing stopped at `MAX_SKILL_FILES`.
+    pub files_truncated: bool,
+}
+
+/// Upper bound on files listed per activation, to keep the result small.
+const MAX_SKILL_FILES: usize = 200;
+const MAX_SKILL_FILE_DEPTH: usize = 6;
+
 #[derive(Debug, Clone)]
 pub struct SkillAvailability {
     pub meta: SkillMetadata,
@@ -253,6 +269,18 @@ impl SkillManager {
         }
     }
 
+    /// Load a skill along with the manifest of files in its directory.
+    pub fn activate_skill(&self, name: &str) -> Result<SkillActivation, String> {
+        let (meta, instructions) = self.load_skill_checked(name)?;
+        let (files, files_truncated) = list_skill_files(&meta.dir_path);
+        Ok(SkillActivation {
+            meta,
+            instructions,
+            files,
+            files_truncated,
+        })
+    }
+
     fn skill_is_available(&self, skill: &SkillMetadata) -> Result<(), String> {
         if !platform_allowed(&skill.platforms) {
             return Err(format!(
@@ -316,6 +344,36 @@ impl SkillManager {
         catalog
     }
 
+    /// Rank available skills against an incoming message by keyword overlap.
+    /// Returns at most `max_results` skill names scoring at least `min_score`,
+    /// best first.
+    pub fn suggest_skills(
+        &self,
+        message: &str,
+        min_score: usize,
+        max_results: usize,
+    ) -> Vec<String> {
+        if max_results == 0 {
+            return Vec::new();
+        }
+        let message_tokens = skill_match_tokens(message);
+        if message_tokens.is_empty() {
+            return Vec::new();
+        }
+        let mut scored: Vec<(usize, String)> = self
+            .discover_skills()
+            .into_iter()
+            .map(|skill| (score_skill_match(&message_tokens, &skill), skill.name))
+            .filter(|(score, _)| *score > 0 && *score >= min_score)
+            .collect();
+        scored.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+        scored
+            .into_iter()
+            .take(max_results)
+            .map(|(_, name)| name)
+            .collect()
+    }
+
     /// Build a user-facing formatted list of available skills.
     pub fn list_skills_formatted(&self) -> String {
         let skills = self.discover_skills();
@@ -398,6 +456,61 @@ impl SkillManager {
     }
 }
 
+/// Regular files under `dir` other than the top-level SKILL.md, skipping
+/// hidden entries and symlinks.
+fn list_skill_files(dir: &Path) -> (Vec<(String, u64)>, bool) {
+    fn walk(
+        root: &Path,
+        dir: &Path,
+        depth: usize,
+        files: &mut Vec<(String, u64)>,
+        truncated: &mut bool,
+    ) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        let mut entries: Vec<_> = entries.flatten().collect();
+        entries.sort_by_key(|e| e.file_name());
+        for entry in entries {
+            if files.len() >= MAX_SKILL_FILES {
+                *truncated = true;
+                return;
+            }
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            let path = entry.path();
+            let Ok(meta) = std::fs::symlink_metadata(&path) else {
+                continue;
+            };
+            if meta.is_dir() {
+                if depth < MAX_SKILL_FILE_DEPTH {
+                    walk(root, &path, depth + 1, files, truncated);
+                }
+            } else if meta.is_file() {
+                let Ok(rel) = path.strip_prefix(root) else {
+                    continue;
+                };
+                if depth == 0 && rel == Path::new("SKILL.md") {
+                    continue;
+                }
+                let rel = rel
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                files.push((rel, meta.len()));
+            }
+        }
+    }
+
+    let mut files = Vec::new();
+    let mut truncated = false;
+    walk(dir, dir, 0, &mut files, &mut truncated);
+    files.sort();
+    (files, truncated)
+}
+
 fn current_platform() -> &'static str {
     if cfg!(target_os = "macos") {
         "darwin"
@@ -480,6 +593,39 @@ fn missing_deps(deps: &[String]) -> Vec<String> {
         .collect()
 }
 
+const SKILL_MATCH_STOPWORDS: &[&str] = &[
+    "the", "and", "for", "with", "this", "that", "from", "into", "your", "you", "use", "using",
+    "can", "are", "was", "will", "please", "help", "want", "need", "how", "what", "when", "skill",
+    "skills",
+];
+
+pub(crate) fn skill_match_tokens(text: &str) -> std::collections::HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .map(str::to_lowercase)
+        .filter(|t| t.chars().count() >= 3 && !SKILL_MATCH_STOPWORDS.contains(&t.as_str()))
+        .collect()
+}
+
+/// Keyword score of a skill for a tokenized message: each distinct word shared
+/// with the description counts 1, each shared word of the skill name counts 2.
+fn score_skill_match(
+    message_tokens: &std::collections::HashSet<String>,
+    skill: &SkillMetadata,
+) -> usize {
+    let name_tokens = skill_match_tokens(&skill.name);
+    let description_tokens = skill_match_tokens(&skill.description);
+    let name_score = name_tokens
+        .iter()
+        .filter(|t| message_tokens.contains(*t))
+        .count()
+        * 2;
+    let description_score = description_tokens
+        .iter()
+        .filter(|t| !name_tokens.contains(*t) && message_tokens.contains(*t))
+        .count();
+    name_score + description_score
+}
+
 fn truncate_chars(s: &str, max_chars: usize) -> String {
     if s.chars().count() <= max_chars {
         return s.to_string();
@@ -628,6 +774,61 @@ fn parse_skill_md(content: &str, dir_path: &std::path::Path) -> Option<(SkillMet
 mod tests {
     use super::*;
 
+    fn skill_meta(name: &str, description: &str) -> SkillMetadata {
+        SkillMetadata {
+            name: name.into(),
+            description: description.into(),
+            dir_path: PathBuf::from("/tmp/skills").join(name),
+            platforms: vec![],
+            deps: vec![],
+            source: "local".into(),
+            version: None,
+            updated_at: None,
+            env_file: None,
+        }
+    }
+
+    #[test]
+    fn test_score_skill_match_weights_name_over_description() {
+        let pdf = skill_meta("pdf", "Extract text and tables from PDF documents");
+        let tokens = skill_match_tokens("Can you extract the tables from this PDF?");
+        // "pdf" in the name (2) + "extract", "tables" in the description (1 each).
+        assert_eq!(score_skill_match(&tokens, &pdf), 4);
+
+        let weather = skill_meta("weather", "Current weather and forecasts");
+        assert_eq!(score_skill_match(&tokens, &weather), 0);
+        // Stopwords and short words never count.
+        let tokens = skill_match_tokens("please help me use the skill for it");
+        assert!(tokens.is_empty());
+    }
+
+    #[test]
+    fn test_suggest_skills_ranks_and_bounds_results() {
+        let dir = std::env::temp_dir().join(format!("mc_skill_suggest_{}", uuid::Uuid::new_v4()));
+        for (name, description) in [
+            ("pdf", "Extract text and tables from PDF documents"),
+            ("docx", "Create and edit Word documents"),
+            ("weather", "Current weather and forecasts"),
+        ] {
+            let skill_dir = dir.join(name);
+            std::fs::create_dir_all(&skill_dir).unwrap();
+            std::fs::write(
+                skill_dir.join("SKILL.md"),
+                format!("---\nname: {name}\ndescription: {description}\n---\nBody\n"),
+            )
+            .unwrap();
+        }
+        let manager = SkillManager::from_skills_dir(dir.to_str().unwrap());
+
+        let message = "Extract the tables from these PDF documents";
+        assert_eq!(manager.suggest_skills(message, 1, 3), vec!["pdf", "docx"]);
+        assert_eq!(manager.suggest_skills(message, 1, 1), vec!["pdf"]);
+        assert_eq!(manager.suggest_skills(message, 2, 3), vec!["pdf"]);
+        assert!(manager.suggest_skills(message, 1, 0).is_empty());
+        assert!(manager.suggest_skills("hello there", 1, 3).is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn test_parse_skill_md_valid() {
         let content = r#"---
@@ -34,7 +34,7 @@ impl Tool for ActivateSkillTool {
     fn definition(&self) -> ToolDefinition {
         ToolDefinition {
             name: "activate_skill".into(),
-            description: "Activate an agent skill to load its full instructions. IMPORTANT: You must CALL this tool (not write it as text) to activate a skill. Use this when you see a relevant skill in the available skills list and need its detailed instructions to complete a task. Skills are filtered by platform/dependencies before they are listed.".into(),
+            description: "Activate an agent skill to load its full instructions and the list of files (scripts, templates) shipped in its directory. IMPORTANT: You must CALL this tool (not write it as text) to activate a skill. Use this when you see a relevant skill in the available skills list and need its detailed instructions to complete a task. Skills are filtered by platform/dependencies before they are listed.".into(),
             input_schema: schema_object(
                 json!({
                     "skill_name": {
@@ -55,8 +55,9 @@ impl Tool for ActivateSkillTool {
 
         info!("Activating skill: {}", skill_name);
 
-        match self.skill_manager.load_skill_checked(skill_name) {
-            Ok((meta, body)) => {
+        match self.skill_manager.activate_skill(skill_name) {
+            Ok(activation) => {
+                let meta = &activation.meta;
                 let mut result = format!("# Skill: {}\n\n", meta.name);
                 result.push_str(&format!("Description: {}\n", meta.description));
                 result.push_str(&format!("Skill directory: {}\n", meta.dir_path.display()));
@@ -74,7 +75,18 @@ impl Tool for ActivateSkillTool {
                     result.push_str(&format!("Dependencies: {}\n", meta.deps.join(", ")));
                 }
                 result.push_str("\n## Instructions\n\n");
-                result.push_str(&body);
+                result.push_str(&activation.instructions);
+                if !activation.files.is_empty() {
+                    result.push_str(
+                        "\n\n## Files\n\nPaths are relative to the skill directory; use them with read_file or bash.\n",
+                    );
+                    for (path, size) in &activation.files {
+                        result.push_str(&format!("- {path} ({size} bytes)\n"));
+                    }
+                    if activation.files_truncated {
+                        result.push_str("- ... (more files not listed)\n");
+                    }
+                }
                 let mut tool_result = ToolResult::success(result);
                 if let Some(env_file_name) = &meta.env_file {
                     let env_path = meta.dir_path.join(env_file_name);
@@ -155,6 +167,38 @@ mod tests {
         cleanup(&dir);
     }
 
+    #[tokio::test]
+    async fn test_activate_skill_lists_skill_files() {
+        let dir = test_dir();
+        create_skill(&dir, "convert", "Convert files", "Run scripts/convert.py.");
+        let skill_dir = dir.join("convert");
+        std::fs::create_dir_all(skill_dir.join("scripts")).unwrap();
+        std::fs::write(skill_dir.join("scripts/convert.py"), "print('hi')\n").unwrap();
+        std::fs::write(skill_dir.join("template.txt"), "abc").unwrap();
+        std::fs::write(skill_dir.join(".env"), "SECRET=1").unwrap();
+
+        let manager = SkillManager::from_skills_dir(dir.to_str().unwrap());
+        let activation = manager.activate_skill("convert").unwrap();
+        assert_eq!(activation.instructions, "Run scripts/convert.py.");
+        assert_eq!(
+            activation.files,
+            vec![
+                ("scripts/convert.py".to_string(), 12),
+                ("template.txt".to_string(), 3),
+            ]
+        );
+        assert!(!activation.files_truncated);
+
+        let tool = ActivateSkillTool::new(dir.to_str().unwrap());
+        let result = tool.execute(json!({"skill_name": "convert"})).await;
+        assert!(!result.is_error);
+        assert!(result.content.contains("## Files"));
+        assert!(result.content.contains("- scripts/convert.py (12 bytes)"));
+        assert!(!result.content.contains(".env"));
+        assert!(!result.content.contains("- SKILL.md"));
+        cleanup(&dir);
+    }
+
     #[tokio::test]
     async fn test_activate_skill_not_found() {
         let dir = test_dir();
@@ -1,12 +1,11 @@
 use async_trait::async_trait;
 use serde_json::json;
-use std::path::PathBuf;
-use std::sync::Arc;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock};
 use tracing::info;
 
 use crate::config::WorkingDirIsolation;
 use microclaw_core::llm_types::ToolDefinition;
-use microclaw_core::text::floor_char_boundary;
 use microclaw_tools::sandbox::{SandboxExecOptions, SandboxRouter};
 
 use super::{schema_object, Tool, ToolResult};
@@ -16,6 +15,9 @@ pub struct BashTool {
     working_dir_isolation: WorkingDirIsolation,
     default_timeout_secs: u64,
     sandbox_router: Option<Arc<SandboxRouter>>,
+    restrict_to_working_dir: bool,
+    denied_commands: Vec<String>,
+    max_output_bytes: usize,
 }
 
 impl BashTool {
@@ -32,6 +34,9 @@ impl BashTool {
             working_dir_isolation,
             default_timeout_secs: 120,
             sandbox_router: None,
+            restrict_to_working_dir: false,
+            denied_commands: Vec::new(),
+            max_output_bytes: DEFAULT_MAX_OUTPUT_BYTES,
         }
     }
 
@@ -44,6 +49,22 @@ impl BashTool {
         self.sandbox_router = Some(router);
         self
     }
+
+    pub fn with_restrict_to_working_dir(mut self, restrict: bool) -> Self {
+        self.restrict_to_working_dir = restrict;
+        self
+    }
+
+    pub fn with_denied_commands(mut self, denied: Vec<String>) -> Self {
+        self.denied_commands = denied;
+        self
+    }
+
+    /// Cap on captured output; 0 captures everything.
+    pub fn with_max_output_bytes(mut self, max_output_bytes: usize) -> Self {
+        self.max_output_bytes = max_output_bytes;
+        self
+    }
 }
 
 fn extract_env_files(input: &serde_json::Value) -> Vec<PathBuf> {
@@ -53,6 +74,7 @@ fn extract_env_files(input: &serde_json::Value) -> Vec<PathBuf> {
 }
 
 const REDACT_MIN_VALUE_LEN: usize = 8;
+const DEFAULT_MAX_OUTPUT_BYTES: usize = 64 * 1024;
 
 fn redact_env_secrets(output: &str, env_files: &[PathBuf]) -> String {
     let mut secrets: Vec<(String, String)> = Vec::new();
@@ -98,6 +120,107 @@ fn contains_explicit_tmp_absolute_path(command: &str) -> bool {
     false
 }
 
+/// The simple commands of a shell line: split on `;`, `&&`, `||`, pipes,
+/// newlines and subshell/backtick boundaries.
+fn command_segments(command: &str) -> impl Iterator<Item = &str> {
+    command
+        .split([';', '&', '|', '\n', '(', ')', '`'])
+        .map(str::trim)
+        .filter(|segment| !segment.is_empty())
+}
+
+/// The first entry of `denied` that prefixes one of the command's segments.
+/// A program given by path (`/usr/bin/sudo`) matches by its file name.
+fn denied_command_prefix<'a>(command: &str, denied: &'a [String]) -> Option<&'a str> {
+    let segments: Vec<String> = command_segments(command)
+        .map(|segment| {
+            let mut words = segment.split_whitespace();
+            let program = words.next().unwrap_or_default();
+            let program = program.rsplit('/').next().unwrap_or(program);
+            std::iter::once(program)
+                .chain(words)
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect();
+    denied.iter().map(String::as_str).find(|prefix| {
+        let prefix = prefix.split_whitespace().collect::<Vec<_>>().join(" ");
+        !prefix.is_empty()
+            && segments.iter().any(|segment| {
+                segment == &prefix
+                    || segment
+                        .strip_prefix(prefix.as_str())
+                        .is_some_and(|rest| rest.starts_with(' '))
+            })
+    })
+}
+
+/// Absolute paths a restricted command may name outside its workspace.
+const ALLOWED_ABSOLUTE_PATHS: &[&str] = &["/dev/null", "/dev/stdin", "/dev/stdout", "/dev/stderr"];
+
+fn split_shell_words(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| {
+        c.is_whitespace()
+            || matches!(
+                c,
+                ';' | '&' | '|' | '(' | ')' | '\'' | '"' | '=' | ':' | '`'
+            )
+    })
+    .filter(|word| !word.is_empty())
+}
+
+/// What in `command` would leave `workspace`, if anything. The program of
+/// each segment may be an absolute path (`/usr/bin/python3`); its arguments
+/// may not point outside the workspace.
+fn working_dir_escape(command: &str, workspace: &Path) -> Option<String> {
+    for token in split_shell_words(command) {
+        if token == ".."
+            || token.starts_with("../")
+            || token.ends_with("/..")
+            || token.contains("/../")
+        {
+            return Some("`..`".into());
+        }
+        if token.starts_with('~') {
+            return Some("`~`".into());
+        }
+        if token.contains("$HOME") || token.contains("${HOME}") {
+            return Some("`$HOME`".into());
+        }
+    }
+    for segment in command_segments(command) {
+        let outside = split_shell_words(segment).skip(1).find(|arg| {
+            arg.starts_with('/')
+                && !ALLOWED_ABSOLUTE_PATHS.contains(arg)
+                && !Path::new(arg).starts_with(workspace)
+        });
+        if let Some(path) = outside {
+            return Some(format!(
+                "the absolute path `{path}` outside the working directory"
+            ));
+        }
+    }
+    None
+}
+
+static DESTRUCTIVE_PROGRAM: LazyLock<regex::Regex> = LazyLock::new(|| {
+    regex::Regex::new(
+        r"(?:^|[\s;&|(`/])(?:rm|rmdir|unlink|mv|dd|truncate|shred|mkfs(?:\.\w+)?|sed\s+-i\S*|git\s+(?:clean|reset\s+--hard))(?:\s|$)|\s-delete(?:\s|$)",
+    )
+    .expect("valid destructive command regex")
+});
+static FILE_REDIRECT: LazyLock<regex::Regex> =
+    LazyLock::new(|| regex::Regex::new(r">>?\s*([^\s&|;>]+)").expect("valid redirect regex"));
+
+/// Whether `command` deletes, moves or overwrites files: `rm`, `mv`, `dd`,
+/// `truncate` and friends, or an output redirect to anything but `/dev/null`.
+pub(crate) fn is_destructive_command(command: &str) -> bool {
+    DESTRUCTIVE_PROGRAM.is_match(command)
+        || FILE_REDIRECT
+            .captures_iter(command)
+            .any(|c| &c[1] != "/dev/null")
+}
+
 fn command_accesses_dotenv(command: &str) -> bool {
     let patterns = [".env", "dotenv", "env_file"];
     let lower = command.to_ascii_lowercase();
@@ -158,6 +281,28 @@ impl Tool for BashTool {
             .with_error_type("path_policy_blocked");
         }
 
+        if let Some(prefix) = denied_command_prefix(command, &self.denied_commands) {
+            return ToolResult::error(format!(
+                "Command is denied by bash_denied_commands (matched '{prefix}')."
+            ))
+            .with_error_type("command_denied");
+        }
+
+        if self.restrict_to_working_dir {
+            let workspace = super::resolve_tool_working_dir(
+                &self.working_dir,
+                self.working_dir_isolation,
+                &input,
+            );
+            if let Some(escape) = working_dir_escape(command, &workspace) {
+                return ToolResult::error(format!(
+                    "Command uses {escape}, which is disallowed while bash is restricted to the working directory: {}",
+                    working_dir.display()
+                ))
+                .with_error_type("path_policy_blocked");
+            }
+        }
+
         let env_files = extract_env_files(&input);
         if !env_files.is_empty() && command_accesses_dotenv(command) {
             return ToolResult::error(
@@ -172,11 +317,19 @@ impl Tool for BashTool {
             .map(|auth| format!("{}-{}", auth.caller_channel, auth.caller_chat_id))
             .unwrap_or_else(|| "shared".to_string());
         let env_files_for_redact = env_files.clone();
+        let mut envs = std::collections::HashMap::new();
+        if self.restrict_to_working_dir {
+            envs.insert("HOME".to_string(), working_dir.display().to_string());
+        }
         let exec_opts = SandboxExecOptions {
             timeout: std::time::Duration::from_secs(timeout_secs),
             working_dir: Some(working_dir.clone()),
-            envs: std::collections::HashMap::new(),
+            envs,
             env_files,
+            // The agent process holds provider keys and bot tokens in its
+            // environment; commands only get the safe subset plus skill env files.
+            clear_env: true,
+            max_output_bytes: (self.max_output_bytes > 0).then_some(self.max_output_bytes),
         };
         let result = if let Some(router) = &self.sandbox_router {
             router.exec(&session_key, command, &exec_opts).await
@@ -189,6 +342,7 @@ impl Tool for BashTool {
                 let stdout = output.stdout;
                 let stderr = output.stderr;
                 let exit_code = output.exit_code;
+                let truncated = output.truncated;
 
                 let mut result_text = String::new();
                 if !stdout.is_empty() {
@@ -206,20 +360,23 @@ impl Tool for BashTool {
                 }
 
                 result_text = redact_env_secrets(&result_text, &env_files_for_redact);
-
-                // Truncate very long output
-                if result_text.len() > 30000 {
-                    let cutoff = floor_char_boundary(&result_text, 30000);
-                    result_text.truncate(cutoff);
-                    result_text.push_str("\n... (output truncated)");
+                if truncated {
+                    result_text.push_str(&format!(
+                        "\n... (output truncated at {} bytes; the command was stopped)",
+                        self.max_output_bytes
+                    ));
                 }
 
-                if exit_code == 0 {
-                    ToolResult::success(result_text).with_status_code(exit_code)
+                let metadata = json!({ "exit_code": exit_code, "truncated": truncated });
+                if exit_code == 0 || truncated {
+                    ToolResult::success(result_text)
+                        .with_status_code(exit_code)
+                        .with_metadata(metadata)
                 } else {
                     ToolResult::error(format!("Exit code {exit_code}\n{result_text}"))
                         .with_status_code(exit_code)
                         .with_error_type("process_exit")
+                        .with_metadata(metadata)
                 }
             }
             Err(e) => {
@@ -282,6 +439,154 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_denied_command_prefix_checks_every_segment() {
+        let denied = vec!["sudo".to_string(), "rm  -rf".to_string()];
+        assert_eq!(denied_command_prefix("sudo ls", &denied), Some("sudo"));
+        assert_eq!(
+            denied_command_prefix("echo hi && /usr/bin/sudo ls", &denied),
+            Some("sudo")
+        );
+        assert_eq!(
+            denied_command_prefix("ls | xargs rm -rf", &denied),
+            None,
+            "only the start of a segment is a command"
+        );
+        assert_eq!(
+            denied_command_prefix("cd x; rm -rf build", &denied),
+            Some("rm  -rf")
+        );
+        assert_eq!(denied_command_prefix("sudoku --solve", &denied), None);
+        assert_eq!(denied_command_prefix("rm -r build", &denied), None);
+    }
+
+    #[test]
+    fn test_working_dir_escape_detection() {
+        let escape = |command| working_dir_escape(command, Path::new("/srv/work/chat"));
+        assert_eq!(escape("cat ../secret").as_deref(), Some("`..`"));
+        assert_eq!(escape("ls a/../../b").as_deref(), Some("`..`"));
+        assert_eq!(escape("cd ..").as_deref(), Some("`..`"));
+        assert_eq!(escape("cat ~/.bashrc").as_deref(), Some("`~`"));
+        assert_eq!(escape("ls \"$HOME\"").as_deref(), Some("`$HOME`"));
+        assert_eq!(
+            escape("echo hi && cd /etc").as_deref(),
+            Some("the absolute path `/etc` outside the working directory")
+        );
+        assert!(escape("cat /etc/passwd").is_some());
+        assert!(escape("python3 x.py --config=/etc/app.yaml").is_some());
+        assert!(escape("echo $(cat /root/.ssh/id_rsa)").is_some());
+        assert_eq!(escape("ls -la src/..hidden ./build"), None);
+        assert_eq!(escape("python3 script.py --out=out.txt"), None);
+        assert_eq!(escape("/usr/bin/python3 run.py > /dev/null"), None);
+        assert_eq!(escape("cat /srv/work/chat/notes.txt"), None);
+    }
+
+    #[test]
+    fn test_destructive_command_detection() {
+        assert!(is_destructive_command("rm -rf build"));
+        assert!(is_destructive_command("ls && /bin/mv a b"));
+        assert!(is_destructive_command("dd if=/dev/zero of=disk.img"));
+        assert!(is_destructive_command("find . -name '*.o' -delete"));
+        assert!(is_destructive_command("sed -i 's/a/b/' f.txt"));
+        assert!(is_destructive_command("echo hi > notes.txt"));
+        assert!(is_destructive_command("date >>log"));
+        assert!(!is_destructive_command("ls -la 2>/dev/null"));
+        assert!(!is_destructive_command("cargo build 2>&1 | tail"));
+        assert!(!is_destructive_command("grep -rn format src"));
+        assert!(!is_destructive_command("cat rm.txt"));
+    }
+
+    #[tokio::test]
+    async fn test_bash_refuses_denied_command() {
+        let tool = BashTool::new(".").with_denied_commands(vec!["sudo".into()]);
+        let result = tool
+            .execute(json!({"command": "echo hi; sudo whoami"}))
+            .await;
+        assert!(result.is_error);
+        assert_eq!(result.error_type.as_deref(), Some("command_denied"));
+        assert!(result.content.contains("'sudo'"));
+
+        let allowed = tool.execute(json!({"command": "echo hi"})).await;
+        assert!(!allowed.is_error);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_bash_does_not_inherit_process_secrets() {
+        {
+            let _guard = crate::test_support::env_lock();
+            std::env::set_var("MICROCLAW_TEST_BASH_SECRET", "do-not-leak");
+        }
+        let tool = BashTool::new(".");
+        let result = tool
+            .execute(json!({"command": "printenv MICROCLAW_TEST_BASH_SECRET; printenv PATH"}))
+            .await;
+        {
+            let _guard = crate::test_support::env_lock();
+            std::env::remove_var("MICROCLAW_TEST_BASH_SECRET");
+        }
+        assert!(
+            !result.content.contains("do-not-leak"),
+            "{}",
+            result.content
+        );
+        assert!(result.content.contains("/bin"), "{}", result.content);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_bash_restricted_applies_working_dir_and_env() {
+        let root = std::env::temp_dir().join(format!("microclaw_bash_{}", uuid::Uuid::new_v4()));
+        let work = root.join("workspace");
+        std::fs::create_dir_all(&work).unwrap();
+        let tool = BashTool::new(work.to_str().unwrap()).with_restrict_to_working_dir(true);
+
+        let result = tool
+            .execute(json!({"command": "pwd; printenv HOME; env | wc -l"}))
+            .await;
+        assert!(!result.is_error, "{}", result.content);
+        let expected = std::fs::canonicalize(work.join("shared").join("tmp")).unwrap();
+        let mut lines = result.content.lines();
+        assert_eq!(
+            std::fs::canonicalize(lines.next().unwrap().trim()).unwrap(),
+            expected
+        );
+        assert_eq!(
+            lines.next().unwrap(),
+            work.join("shared").join("tmp").display().to_string()
+        );
+        let env_count: usize = lines.next().unwrap().trim().parse().unwrap();
+        assert!(
+            env_count <= microclaw_tools::sandbox::SAFE_ENV_VARS.len() + 4,
+            "environment was not stripped: {env_count} variables"
+        );
+
+        let escaped = tool.execute(json!({"command": "cat ../../secret"})).await;
+        assert!(escaped.is_error);
+        assert_eq!(escaped.error_type.as_deref(), Some("path_policy_blocked"));
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_bash_truncates_output_past_cap() {
+        let tool = BashTool::new(".").with_max_output_bytes(2048);
+        let result = tool
+            .execute(json!({"command": "head -c 100000 /dev/zero | tr '\\0' x"}))
+            .await;
+        assert!(!result.is_error, "{}", result.content);
+        assert!(result
+            .content
+            .ends_with("(output truncated at 2048 bytes; the command was stopped)"));
+        assert!(result.content.starts_with(&"x".repeat(2048)));
+        assert!(result.content.len() < 2200);
+        let metadata = result.metadata.unwrap();
+        assert_eq!(metadata["truncated"], true);
+
+        let small = tool.execute(json!({"command": "echo ok"})).await;
+        assert_eq!(small.metadata.unwrap()["truncated"], false);
+    }
+
     #[tokio::test]
     async fn test_bash_echo() {
         let tool = BashTool::new(".");
@@ -0,0 +1,219 @@
+use async_trait::async_trait;
+use serde_json::json;
+use tracing::info;
+
+use microclaw_core::llm_types::ToolDefinition;
+
+use super::sync_skills::SyncSkillsTool;
+use super::{schema_object, Tool, ToolResult};
+
+/// A local skill that was installed with `sync_skills`.
+#[derive(Debug, Clone, PartialEq)]
+struct SyncedSkill {
+    name: String,
+    source_repo: String,
+    upstream_skill: String,
+    version: String,
+    upstream_sha256: Option<String>,
+    body: String,
+}
+
+pub struct CheckSkillUpdatesTool {
+    skills_dir: std::path::PathBuf,
+}
+
+impl CheckSkillUpdatesTool {
+    pub fn new(skills_dir: &str) -> Self {
+        Self {
+            skills_dir: std::path::PathBuf::from(skills_dir),
+        }
+    }
+
+    /// Skills whose frontmatter has `source: remote:<repo>`, sorted by name.
+    fn synced_skills(&self, only: Option<&str>) -> Vec<SyncedSkill> {
+        let Ok(entries) = std::fs::read_dir(&self.skills_dir) else {
+            return Vec::new();
+        };
+        let mut skills: Vec<SyncedSkill> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                if only.is_some_and(|only| only != name) {
+                    return None;
+                }
+                let content = std::fs::read_to_string(entry.path().join("SKILL.md")).ok()?;
+                Self::parse_synced_skill(&name, &content)
+            })
+            .collect();
+        skills.sort_by(|a, b| a.name.cmp(&b.name));
+        skills
+    }
+
+    fn parse_synced_skill(dir_name: &str, content: &str) -> Option<SyncedSkill> {
+        let (fm, body) = SyncSkillsTool::split_frontmatter(content);
+        let fm = fm?;
+        let get = |k: &str| {
+            fm.get(k)
+                .and_then(|v| v.as_str())
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+        };
+        let source_repo = get("source")?.strip_prefix("remote:")?.trim().to_string();
+        Some(SyncedSkill {
+            name: get("name").unwrap_or_else(|| dir_name.to_string()),
+            source_repo,
+            // Skills synced before `upstream_skill` was recorded used the
+            // upstream name as the directory name by default.
+            upstream_skill: get("upstream_skill").unwrap_or_else(|| dir_name.to_string()),
+            version: get("version").unwrap_or_else(|| "main".to_string()),
+            upstream_sha256: get("upstream_sha256"),
+            body,
+        })
+    }
+
+    /// Compare against the hash recorded at sync time, or the body for skills
+    /// synced before hashes were recorded.
+    fn upstream_changed(skill: &SyncedSkill, upstream_raw: &str) -> bool {
+        match &skill.upstream_sha256 {
+            Some(hash) => *hash != SyncSkillsTool::content_hash(upstream_raw),
+            None => {
+                let (_, upstream_body) = SyncSkillsTool::split_frontmatter(upstream_raw);
+                SyncSkillsTool::content_hash(&upstream_body)
+                    != SyncSkillsTool::content_hash(&skill.body)
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for CheckSkillUpdatesTool {
+    fn name(&self) -> &str {
+        "check_skill_updates"
+    }
+
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "check_skill_updates".into(),
+            description: "Check which skills installed with sync_skills have changed upstream since they were synced. Compares the upstream SKILL.md content with what was synced at the recorded source repo and version. Does not modify anything; re-run sync_skills to update an outdated skill.".into(),
+            input_schema: schema_object(
+                json!({
+                    "skill_name": {
+                        "type": "string",
+                        "description": "Only check this local skill (defaults to all synced skills)"
+                    }
+                }),
+                &[],
+            ),
+        }
+    }
+
+    async fn execute(&self, input: serde_json::Value) -> ToolResult {
+        let only = input
+            .get("skill_name")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|v| !v.is_empty());
+        let skills = self.synced_skills(only);
+        if skills.is_empty() {
+            return match only {
+                Some(name) => ToolResult::error(format!(
+                    "Skill '{name}' was not found or was not installed with sync_skills."
+                )),
+                None => ToolResult::success("No skills installed with sync_skills.".into()),
+            };
+        }
+
+        let mut results = Vec::new();
+        for skill in &skills {
+            let entry = match SyncSkillsTool::fetch_skill_content(
+                &skill.source_repo,
+                &skill.upstream_skill,
+                &skill.version,
+            )
+            .await
+            {
+                Ok(raw) => json!({
+                    "skill": skill.name,
+                    "local_version": skill.version,
+                    "upstream_changed": Self::upstream_changed(skill, &raw),
+                }),
+                Err(e) => json!({
+                    "skill": skill.name,
+                    "local_version": skill.version,
+                    "upstream_changed": null,
+                    "error": e,
+                }),
+            };
+            results.push(entry);
+        }
+        info!("check_skill_updates: checked {} skills", results.len());
+        ToolResult::success(
+            serde_json::to_string_pretty(&results).unwrap_or_else(|_| "[]".to_string()),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UPSTREAM: &str = "---\nname: demo\ndescription: Demo skill\n---\n# Demo\n\nDo the thing.";
+
+    #[test]
+    fn test_parse_synced_skill_and_detect_changes() {
+        let synced = SyncSkillsTool::normalize_skill_markdown(
+            UPSTREAM,
+            "acme/skills",
+            "v1",
+            "tools/demo",
+            "my-demo",
+        );
+        let skill = CheckSkillUpdatesTool::parse_synced_skill("my-demo", &synced).unwrap();
+        assert_eq!(skill.name, "my-demo");
+        assert_eq!(skill.source_repo, "acme/skills");
+        assert_eq!(skill.upstream_skill, "tools/demo");
+        assert_eq!(skill.version, "v1");
+        assert!(!CheckSkillUpdatesTool::upstream_changed(&skill, UPSTREAM));
+        assert!(CheckSkillUpdatesTool::upstream_changed(
+            &skill,
+            &format!("{UPSTREAM}\nNew step.")
+        ));
+
+        let local = "---\nname: local\ndescription: Hand-written\n---\nBody";
+        assert!(CheckSkillUpdatesTool::parse_synced_skill("local", local).is_none());
+    }
+
+    #[test]
+    fn test_upstream_changed_without_recorded_hash_compares_body() {
+        let legacy = "---\nname: demo\ndescription: Demo\nsource: remote:acme/skills\nversion: main\n---\n# Demo\n\nDo the thing.";
+        let skill = CheckSkillUpdatesTool::parse_synced_skill("demo", legacy).unwrap();
+        assert_eq!(skill.upstream_sha256, None);
+        assert_eq!(skill.upstream_skill, "demo");
+        assert!(!CheckSkillUpdatesTool::upstream_changed(&skill, UPSTREAM));
+        assert!(CheckSkillUpdatesTool::upstream_changed(
+            &skill,
+            "---\nname: demo\n---\n# Demo\n\nDo another thing."
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_check_skill_updates_without_synced_skills() {
+        let dir = std::env::temp_dir().join(format!("mc_skill_updates_{}", uuid::Uuid::new_v4()));
+        let local = dir.join("local");
+        std::fs::create_dir_all(&local).unwrap();
+        std::fs::write(
+            local.join("SKILL.md"),
+            "---\nname: local\ndescription: Hand-written\n---\nBody",
+        )
+        .unwrap();
+        let tool = CheckSkillUpdatesTool::new(dir.to_str().unwrap());
+
+        let all = tool.execute(json!({})).await;
+        assert!(!all.is_error);
+        assert!(all.content.contains("No skills installed with sync_skills"));
+        let one = tool.execute(json!({"skill_name": "local"})).await;
+        assert!(one.is_error);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,179 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde_json::json;
+
+use super::{auth_context_from_input, schema_object, Tool, ToolResult};
+use crate::config::Config;
+use crate::hot_reload::Live;
+use microclaw_core::llm_types::ToolDefinition;
+use microclaw_storage::db::{call_blocking, Database};
+
+const AVAILABLE_TOOLS_KEY: &str = "__microclaw_available_tools";
+const EFFECTIVE_MODEL_KEY: &str = "__microclaw_effective_model";
+
+/// Attach the names of the tools the registry offers. Any value the model put
+/// under the same key is dropped.
+pub(crate) fn inject_available_tools(
+    input: serde_json::Value,
+    definitions: &[ToolDefinition],
+) -> serde_json::Value {
+    let mut obj = match input {
+        serde_json::Value::Object(map) => map,
+        _ => serde_json::Map::new(),
+    };
+    let names: Vec<&str> = definitions.iter().map(|d| d.name.as_str()).collect();
+    obj.insert(AVAILABLE_TOOLS_KEY.to_string(), json!(names));
+    serde_json::Value::Object(obj)
+}
+
+/// Attach the provider and model the agent loop resolved for this run, so
+/// channel and `/model` overrides are reported as they apply.
+pub(crate) fn inject_effective_model(
+    input: serde_json::Value,
+    provider: &str,
+    model: &str,
+) -> serde_json::Value {
+    let mut obj = match input {
+        serde_json::Value::Object(map) => map,
+        _ => serde_json::Map::new(),
+    };
+    obj.insert(
+        EFFECTIVE_MODEL_KEY.to_string(),
+        json!({"provider": provider, "model": model}),
+    );
+    serde_json::Value::Object(obj)
+}
+
+pub struct ContextInfoTool {
+    config: Arc<Live<Config>>,
+    db: Arc<Database>,
+}
+
+impl ContextInfoTool {
+    pub fn new(config: Arc<Live<Config>>, db: Arc<Database>) -> Self {
+        ContextInfoTool { config, db }
+    }
+}
+
+#[async_trait]
+impl Tool for ContextInfoTool {
+    fn name(&self) -> &str {
+        "context_info"
+    }
+
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "context_info".into(),
+            description: "Describe the current runtime: channel, chat_id, bot username, timezone, provider and model, number of stored memories for this chat, data_dir and the names of the available tools. Use it to answer \"what can you do\" or to check your own setup.".into(),
+            input_schema: schema_object(json!({}), &[]),
+        }
+    }
+
+    async fn execute(&self, input: serde_json::Value) -> ToolResult {
+        let Some(auth) = auth_context_from_input(&input) else {
+            return ToolResult::error("context_info is only available inside a chat".into());
+        };
+        let config = self.config.load();
+        let chat_id = auth.caller_chat_id;
+        let memory_count = match call_blocking(self.db.clone(), move |db| {
+            db.count_active_memories_for_chat(chat_id)
+        })
+        .await
+        {
+            Ok(count) => count,
+            Err(e) => return ToolResult::error(format!("Failed to read chat state: {e}")),
+        };
+        let disabled =
+            crate::chat_commands::disabled_tools_for_chat(self.db.clone(), chat_id).await;
+        let tools: Vec<String> = input
+            .get(AVAILABLE_TOOLS_KEY)
+            .and_then(|v| v.as_array())
+            .map(|names| {
+                names
+                    .iter()
+                    .filter_map(|n| n.as_str())
+                    .filter(|n| !disabled.iter().any(|d| d == n))
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        // Sub-agents run on the configured default, and nothing injects theirs.
+        let effective = input.get(EFFECTIVE_MODEL_KEY);
+        let provider = effective
+            .and_then(|v| v.get("provider"))
+            .and_then(|v| v.as_str())
+            .unwrap_or(&config.llm_provider);
+        let model = effective
+            .and_then(|v| v.get("model"))
+            .and_then(|v| v.as_str())
+            .unwrap_or(&config.model);
+
+        let info = json!({
+            "channel": auth.caller_channel,
+            "chat_id": chat_id,
+            "bot_username": config.bot_username_for_channel(&auth.caller_channel),
+            "timezone": config.timezone,
+            "provider": provider,
+            "model": model,
+            "memory_count": memory_count,
+            "data_dir": config.data_dir,
+            "tool_count": tools.len(),
+            "tools": tools,
+        });
+        ToolResult::success(serde_json::to_string_pretty(&info).unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use microclaw_tools::runtime::{inject_auth_context, ToolAuthContext};
+
+    #[tokio::test]
+    async fn test_context_info_reports_chat_runtime() {
+        let dir = std::env::temp_dir().join(format!("microclaw_ci_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let db = Arc::new(Database::new(dir.to_str().unwrap()).unwrap());
+        db.insert_memory(Some(42), "likes tea", "PROFILE").unwrap();
+        let archived = db
+            .insert_memory(Some(42), "old address", "PROFILE")
+            .unwrap();
+        db.archive_memory(archived).unwrap();
+        db.set_chat_setting(42, crate::chat_commands::DISABLED_TOOLS_SETTING_KEY, "bash")
+            .unwrap();
+        let mut config = Config::test_defaults();
+        config.timezone = "Asia/Tokyo".into();
+        let tool = ContextInfoTool::new(Arc::new(Live::from(config)), db);
+
+        let auth = ToolAuthContext {
+            caller_channel: "web".into(),
+            caller_chat_id: 42,
+            control_chat_ids: vec![],
+            env_files: vec![],
+            inbound_attachments: Vec::new(),
+            sub_agent_depth: 0,
+        };
+        let bash = ToolDefinition {
+            name: "bash".into(),
+            description: String::new(),
+            input_schema: schema_object(json!({}), &[]),
+        };
+        let definitions = vec![tool.definition(), bash];
+        let input = inject_available_tools(json!({}), &definitions);
+        let input = inject_effective_model(input, "anthropic", "chat-model");
+        let result = tool.execute(inject_auth_context(input, &auth)).await;
+        assert!(!result.is_error, "{}", result.content);
+        let info: serde_json::Value = serde_json::from_str(&result.content).unwrap();
+        assert_eq!(info["channel"], "web");
+        assert_eq!(info["chat_id"], 42);
+        assert_eq!(info["timezone"], "Asia/Tokyo");
+        assert_eq!(info["model"], "chat-model");
+        assert_eq!(info["provider"], "anthropic");
+        assert_eq!(info["memory_count"], 1);
+        assert_eq!(info["tools"], json!(["context_info"]));
+
+        assert!(tool.execute(json!({})).await.is_error);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
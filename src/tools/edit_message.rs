@@ -0,0 +1,338 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde_json::json;
+use tracing::{info, warn};
+
+use super::send_message::sent_result;
+use super::{authorize_chat_access, schema_object, Tool, ToolResult};
+use microclaw_channels::channel::{
+    deliver_and_store_editable_bot_message, edit_and_store_bot_message, enforce_channel_policy,
+    get_required_chat_routing,
+};
+use microclaw_channels::channel_adapter::ChannelRegistry;
+use microclaw_core::llm_types::ToolDefinition;
+use microclaw_storage::db::Database;
+
+/// Whether an edit failed because the platform no longer lets the message be
+/// edited (too old, deleted), so a new message should be sent instead.
+fn is_uneditable_message_error(err: &str) -> bool {
+    let err = err.to_ascii_lowercase();
+    err.contains("message can't be edited") || err.contains("message to edit not found")
+}
+
+pub struct EditMessageTool {
+    registry: Arc<ChannelRegistry>,
+    db: Arc<Database>,
+    default_bot_username: String,
+    channel_bot_usernames: HashMap<String, String>,
+}
+
+impl EditMessageTool {
+    pub fn new(
+        registry: Arc<ChannelRegistry>,
+        db: Arc<Database>,
+        default_bot_username: String,
+        channel_bot_usernames: HashMap<String, String>,
+    ) -> Self {
+        EditMessageTool {
+            registry,
+            db,
+            default_bot_username,
+            channel_bot_usernames,
+        }
+    }
+
+    async fn sender_name(&self, chat_id: i64) -> String {
+        match get_required_chat_routing(&self.registry, self.db.clone(), chat_id).await {
+            Ok(routing) => self
+                .channel_bot_usernames
+                .get(&routing.channel_name)
+                .cloned()
+                .unwrap_or_else(|| self.default_bot_username.clone()),
+            Err(_) => self.default_bot_username.clone(),
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for EditMessageTool {
+    fn name(&self) -> &str {
+        "edit_message"
+    }
+
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "edit_message".into(),
+            description: "Replace the text of a message you sent earlier with send_message, using the message_id it returned. Useful to turn a \"working...\" message into the final result. If the message can no longer be edited, the text is sent as a new message and its message_id is returned.".into(),
+            input_schema: schema_object(
+                json!({
+                    "chat_id": {
+                        "type": "integer",
+                        "description": "The chat the message was sent to"
+                    },
+                    "message_id": {
+                        "type": "string",
+                        "description": "The message_id returned by send_message"
+                    },
+                    "text": {
+                        "type": "string",
+                        "description": "The new message text"
+                    }
+                }),
+                &["chat_id", "message_id", "text"],
+            ),
+        }
+    }
+
+    async fn execute(&self, input: serde_json::Value) -> ToolResult {
+        let chat_id = match input.get("chat_id").and_then(|v| v.as_i64()) {
+            Some(id) => id,
+            None => return ToolResult::error("Missing required parameter: chat_id".into()),
+        };
+        let message_id = match input.get("message_id") {
+            Some(serde_json::Value::String(s)) if !s.trim().is_empty() => s.trim().to_string(),
+            Some(serde_json::Value::Number(n)) => n.to_string(),
+            _ => return ToolResult::error("Missing required parameter: message_id".into()),
+        };
+        let text = input
+            .get("text")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .trim()
+            .to_string();
+        if text.is_empty() {
+            return ToolResult::error("Missing required parameter: text".into());
+        }
+
+        if let Err(e) = authorize_chat_access(&input, chat_id) {
+            return ToolResult::error(e);
+        }
+        if let Err(e) =
+            enforce_channel_policy(&self.registry, self.db.clone(), &input, chat_id).await
+        {
+            return ToolResult::error(e);
+        }
+
+        match edit_and_store_bot_message(
+            &self.registry,
+            self.db.clone(),
+            chat_id,
+            &message_id,
+            &text,
+        )
+        .await
+        {
+            Ok(()) => {
+                info!(
+                    "edit_message edited: chat_id={}, message_id={}",
+                    chat_id, message_id
+                );
+                ToolResult::success(
+                    json!({
+                        "status": "edited",
+                        "chat_id": chat_id,
+                        "message_id": message_id,
+                    })
+                    .to_string(),
+                )
+            }
+            Err(e) if is_uneditable_message_error(&e) => {
+                warn!(
+                    "edit_message falling back to a new message: chat_id={}, message_id={}, error={}",
+                    chat_id, message_id, e
+                );
+                let sender_name = self.sender_name(chat_id).await;
+                match deliver_and_store_editable_bot_message(
+                    &self.registry,
+                    self.db.clone(),
+                    &sender_name,
+                    chat_id,
+                    &text,
+                )
+                .await
+                {
+                    Ok(new_id) => ToolResult::success(sent_result(chat_id, new_id)),
+                    Err(e) => ToolResult::error(e),
+                }
+            }
+            Err(e) => {
+                warn!(
+                    "edit_message failed: chat_id={}, message_id={}, error={}",
+                    chat_id, message_id, e
+                );
+                ToolResult::error(e)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::send_message::SendMessageTool;
+    use microclaw_channels::channel::ConversationKind;
+    use microclaw_channels::channel_adapter::ChannelAdapter;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Mutex;
+
+    /// Records sends and edits; edits of ids listed in `stale` fail the way
+    /// Telegram reports messages that are too old to edit.
+    struct EditableAdapter {
+        next_id: AtomicU32,
+        stale: Vec<String>,
+        edits: Mutex<Vec<(String, String)>>,
+    }
+
+    #[async_trait]
+    impl ChannelAdapter for EditableAdapter {
+        fn name(&self) -> &str {
+            "telegram"
+        }
+
+        fn chat_type_routes(&self) -> Vec<(&str, ConversationKind)> {
+            vec![("telegram_private", ConversationKind::Private)]
+        }
+
+        async fn send_text(&self, _external_chat_id: &str, _text: &str) -> Result<(), String> {
+            Ok(())
+        }
+
+        async fn send_editable_text(
+            &self,
+            _external_chat_id: &str,
+            _text: &str,
+        ) -> Result<Option<String>, String> {
+            Ok(Some(
+                (self.next_id.fetch_add(1, Ordering::SeqCst) + 100).to_string(),
+            ))
+        }
+
+        async fn edit_text(
+            &self,
+            _external_chat_id: &str,
+            message_id: &str,
+            text: &str,
+        ) -> Result<(), String> {
+            if self.stale.iter().any(|id| id == message_id) {
+                return Err(
+                    "Failed to edit Telegram message: Bad Request: message can't be edited".into(),
+                );
+            }
+            self.edits
+                .lock()
+                .unwrap()
+                .push((message_id.to_string(), text.to_string()));
+            Ok(())
+        }
+    }
+
+    fn setup(stale: &[&str]) -> (Arc<Database>, Arc<EditableAdapter>, i64, std::path::PathBuf) {
+        let dir = std::env::temp_dir().join(format!("microclaw_editmsg_{}", uuid::Uuid::new_v4()));
+        let db = Arc::new(Database::new(dir.to_str().unwrap()).unwrap());
+        let chat_id = db
+            .resolve_or_create_chat_id("telegram", "5001", None, "telegram_private")
+            .unwrap();
+        let adapter = Arc::new(EditableAdapter {
+            next_id: AtomicU32::new(0),
+            stale: stale.iter().map(|s| s.to_string()).collect(),
+            edits: Mutex::new(Vec::new()),
+        });
+        (db, adapter, chat_id, dir)
+    }
+
+    fn tools(
+        db: &Arc<Database>,
+        adapter: &Arc<EditableAdapter>,
+    ) -> (SendMessageTool, EditMessageTool) {
+        let mut registry = ChannelRegistry::new();
+        registry.register(adapter.clone());
+        let registry = Arc::new(registry);
+        (
+            SendMessageTool::new(registry.clone(), db.clone(), "bot".into(), HashMap::new()),
+            EditMessageTool::new(registry, db.clone(), "bot".into(), HashMap::new()),
+        )
+    }
+
+    fn auth(chat_id: i64) -> serde_json::Value {
+        json!({"caller_chat_id": chat_id, "control_chat_ids": []})
+    }
+
+    #[tokio::test]
+    async fn test_send_then_edit_updates_message_and_history() {
+        let (db, adapter, chat_id, dir) = setup(&[]);
+        let (send, edit) = tools(&db, &adapter);
+
+        let sent = send
+            .execute(json!({"chat_id": chat_id, "text": "working...", "__microclaw_auth": auth(chat_id)}))
+            .await;
+        assert!(!sent.is_error, "{}", sent.content);
+        let sent: serde_json::Value = serde_json::from_str(&sent.content).unwrap();
+        assert_eq!(sent["status"], "sent");
+        let message_id = sent["message_id"].as_str().unwrap().to_string();
+        assert_eq!(message_id, "100");
+
+        let edited = edit
+            .execute(json!({
+                "chat_id": chat_id,
+                "message_id": message_id,
+                "text": "done: 42",
+                "__microclaw_auth": auth(chat_id),
+            }))
+            .await;
+        assert!(!edited.is_error, "{}", edited.content);
+        assert!(edited.content.contains("\"edited\""));
+        assert_eq!(
+            adapter.edits.lock().unwrap().clone(),
+            vec![("100".to_string(), "done: 42".to_string())]
+        );
+        let history = db.get_all_messages(chat_id).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].content, "done: 42");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_edit_falls_back_to_new_message_when_too_old() {
+        let (db, adapter, chat_id, dir) = setup(&["7"]);
+        let (_, edit) = tools(&db, &adapter);
+
+        let result = edit
+            .execute(json!({
+                "chat_id": chat_id,
+                "message_id": 7,
+                "text": "final answer",
+                "__microclaw_auth": auth(chat_id),
+            }))
+            .await;
+        assert!(!result.is_error, "{}", result.content);
+        let result: serde_json::Value = serde_json::from_str(&result.content).unwrap();
+        assert_eq!(result["status"], "sent");
+        assert_eq!(result["message_id"], "100");
+        let history = db.get_all_messages(chat_id).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].content, "final answer");
+        assert!(adapter.edits.lock().unwrap().is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_edit_message_cross_chat_denied() {
+        let (db, adapter, chat_id, dir) = setup(&[]);
+        let (_, edit) = tools(&db, &adapter);
+
+        let result = edit
+            .execute(json!({
+                "chat_id": chat_id,
+                "message_id": "100",
+                "text": "hijack",
+                "__microclaw_auth": auth(chat_id + 1),
+            }))
+            .await;
+        assert!(result.is_error);
+        assert!(result.content.contains("Permission denied"));
+        assert!(adapter.edits.lock().unwrap().is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,11 +1,71 @@
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 
 use super::{authorize_chat_access, schema_object, Tool, ToolResult};
 use microclaw_core::llm_types::ToolDefinition;
-use microclaw_storage::db::{call_blocking, Database};
+use microclaw_storage::db::{call_blocking, Database, StoredMessage};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Markdown,
+    Json,
+    Html,
+}
+
+impl ExportFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "markdown" | "md" => Some(Self::Markdown),
+            "json" => Some(Self::Json),
+            "html" => Some(Self::Html),
+            _ => None,
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Json => "json",
+            Self::Html => "html",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Markdown => "markdown",
+            Self::Json => "JSON",
+            Self::Html => "HTML",
+        }
+    }
+}
+
+/// One message in a JSON export; the fields of `StoredMessage`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportedMessage {
+    pub id: String,
+    pub chat_id: i64,
+    pub sender_name: String,
+    pub content: String,
+    pub is_from_bot: bool,
+    pub timestamp: String,
+}
+
+impl From<&StoredMessage> for ExportedMessage {
+    fn from(m: &StoredMessage) -> Self {
+        ExportedMessage {
+            id: m.id.clone(),
+            chat_id: m.chat_id,
+            sender_name: m.sender_name.clone(),
+            content: m.content.clone(),
+            is_from_bot: m.is_from_bot,
+            timestamp: m.timestamp.clone(),
+        }
+    }
+}
 
 pub struct ExportChatTool {
     db: Arc<Database>,
@@ -30,16 +90,23 @@ impl Tool for ExportChatTool {
     fn definition(&self) -> ToolDefinition {
         ToolDefinition {
             name: "export_chat".into(),
-            description: "Export chat history to a markdown file. Returns the file path.".into(),
+            description:
+                "Export chat history to a markdown, JSON or HTML file. Returns the file path."
+                    .into(),
             input_schema: schema_object(
                 json!({
                     "chat_id": {
                         "type": "integer",
                         "description": "The chat ID to export"
                     },
+                    "format": {
+                        "type": "string",
+                        "enum": ["markdown", "json", "html"],
+                        "description": "Output format (default: markdown)"
+                    },
                     "path": {
                         "type": "string",
-                        "description": "Optional output file path. Defaults to data/exports/{chat_id}_{timestamp}.md"
+                        "description": "Optional output file path. Defaults to data/groups/{channel}/{chat_id}/exports/chat-{timestamp}.{md|json|html}"
                     }
                 }),
                 &["chat_id"],
@@ -56,64 +123,171 @@ impl Tool for ExportChatTool {
             return ToolResult::error(e);
         }
 
-        let messages =
-            match call_blocking(self.db.clone(), move |db| db.get_all_messages(chat_id)).await {
-                Ok(msgs) => msgs,
-                Err(e) => return ToolResult::error(format!("Failed to load messages: {e}")),
-            };
+        let format = match input.get("format").and_then(|v| v.as_str()) {
+            None => ExportFormat::Markdown,
+            Some(raw) => match ExportFormat::parse(raw) {
+                Some(format) => format,
+                None => {
+                    return ToolResult::error(format!(
+                        "Unknown format '{raw}'. Use markdown, json or html."
+                    ))
+                }
+            },
+        };
+        let path = input.get("path").and_then(|v| v.as_str());
+        match export_chat(self.db.clone(), &self.data_dir, chat_id, path, format).await {
+            Ok((count, path)) => ToolResult::success(format!(
+                "Exported {} messages as {} to {}",
+                count,
+                format.label(),
+                path.display()
+            )),
+            Err(e) => ToolResult::error(e),
+        }
+    }
+}
 
-        if messages.is_empty() {
-            return ToolResult::error(format!("No messages found for chat {chat_id}."));
+/// Write the full history of a chat to a markdown file and return the message
+/// count and file path. Defaults to `<data_dir>/exports/{chat_id}_{timestamp}.md`,
+/// where scheduled exports have always been written.
+pub async fn export_chat_markdown(
+    db: Arc<Database>,
+    data_dir: &str,
+    chat_id: i64,
+    path: Option<&str>,
+) -> Result<(usize, PathBuf), String> {
+    let default_path;
+    let path = match path {
+        Some(p) => p,
+        None => {
+            let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
+            default_path = format!("{data_dir}/exports/{chat_id}_{timestamp}.md");
+            &default_path
         }
+    };
+    export_chat(db, data_dir, chat_id, Some(path), ExportFormat::Markdown).await
+}
 
-        let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
-        let default_path = format!("{}/exports/{}_{}.md", self.data_dir, chat_id, timestamp);
-        let path = input
-            .get("path")
-            .and_then(|v| v.as_str())
-            .unwrap_or(&default_path);
+/// Write the full history of a chat in `format` and return the message count
+/// and file path. Defaults to
+/// `<data_dir>/groups/<channel>/<chat_id>/exports/chat-{timestamp}.<ext>`.
+pub async fn export_chat(
+    db: Arc<Database>,
+    data_dir: &str,
+    chat_id: i64,
+    path: Option<&str>,
+    format: ExportFormat,
+) -> Result<(usize, PathBuf), String> {
+    let (messages, channel) = call_blocking(db, move |db| {
+        Ok((db.get_all_messages(chat_id)?, db.get_chat_channel(chat_id)?))
+    })
+    .await
+    .map_err(|e| format!("Failed to load messages: {e}"))?;
 
-        // Build markdown
-        let mut md = format!("# Chat Export: {chat_id}\n\n");
-        md.push_str(&format!(
-            "Exported at: {}\n\n---\n\n",
-            chrono::Utc::now().to_rfc3339()
-        ));
+    if messages.is_empty() {
+        return Err(format!("No messages found for chat {chat_id}."));
+    }
 
-        for msg in &messages {
-            let sender = if msg.is_from_bot {
-                "**Bot**"
-            } else {
-                &msg.sender_name
-            };
-            md.push_str(&format!(
-                "**{}** ({})\n\n{}\n\n---\n\n",
-                sender, msg.timestamp, msg.content
-            ));
+    let now = chrono::Utc::now();
+    let path = match path {
+        Some(p) => PathBuf::from(p),
+        None => {
+            let channel = channel
+                .as_deref()
+                .map(str::trim)
+                .filter(|c| !c.is_empty())
+                .unwrap_or("unknown");
+            PathBuf::from(data_dir)
+                .join("groups")
+                .join(channel)
+                .join(chat_id.to_string())
+                .join("exports")
+                .join(format!(
+                    "chat-{}.{}",
+                    now.format("%Y%m%d-%H%M%S"),
+                    format.extension()
+                ))
         }
+    };
 
-        // Write file
-        let path = std::path::Path::new(path);
-        if let Some(parent) = path.parent() {
-            if let Err(e) = std::fs::create_dir_all(parent) {
-                return ToolResult::error(format!("Failed to create directory: {e}"));
-            }
-        }
-        match std::fs::write(path, &md) {
-            Ok(_) => ToolResult::success(format!(
-                "Exported {} messages to {}",
-                messages.len(),
-                path.display()
-            )),
-            Err(e) => ToolResult::error(format!("Failed to write file: {e}")),
+    let exported_at = now.to_rfc3339();
+    let body = match format {
+        ExportFormat::Markdown => render_markdown(chat_id, &messages, &exported_at),
+        ExportFormat::Json => {
+            let items: Vec<ExportedMessage> = messages.iter().map(ExportedMessage::from).collect();
+            serde_json::to_string_pretty(&items)
+                .map_err(|e| format!("Failed to encode messages: {e}"))?
         }
+        ExportFormat::Html => render_html(chat_id, &messages, &exported_at),
+    };
+
+    // Write file
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {e}"))?;
     }
+    std::fs::write(&path, &body).map_err(|e| format!("Failed to write file: {e}"))?;
+    Ok((messages.len(), path))
+}
+
+fn render_markdown(chat_id: i64, messages: &[StoredMessage], exported_at: &str) -> String {
+    let mut md = format!("# Chat Export: {chat_id}\n\n");
+    md.push_str(&format!("Exported at: {exported_at}\n\n---\n\n"));
+
+    for msg in messages {
+        let sender = if msg.is_from_bot {
+            "**Bot**"
+        } else {
+            &msg.sender_name
+        };
+        md.push_str(&format!(
+            "**{}** ({})\n\n{}\n\n---\n\n",
+            sender, msg.timestamp, msg.content
+        ));
+    }
+    md
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+const HTML_STYLE: &str = "body{font-family:system-ui,sans-serif;max-width:760px;margin:2em auto;padding:0 1em;color:#222;background:#fafafa}\
+.msg{background:#fff;border:1px solid #e3e3e3;border-radius:8px;padding:.6em .9em;margin:.6em 0}\
+.msg.bot{background:#eef4ff;border-color:#cbdcff}\
+.meta{font-size:.8em;color:#666;margin-bottom:.3em}\
+.sender{font-weight:600;color:#222}\
+.content{white-space:pre-wrap;word-wrap:break-word}";
+
+fn render_html(chat_id: i64, messages: &[StoredMessage], exported_at: &str) -> String {
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Chat Export: {chat_id}</title>\n<style>{HTML_STYLE}</style>\n</head>\n<body>\n<h1>Chat Export: {chat_id}</h1>\n<p class=\"meta\">Exported at: {}</p>\n",
+        escape_html(exported_at)
+    );
+    for msg in messages {
+        let (class, sender) = if msg.is_from_bot {
+            ("msg bot", "Bot")
+        } else {
+            ("msg", msg.sender_name.as_str())
+        };
+        html.push_str(&format!(
+            "<div class=\"{class}\">\n<div class=\"meta\"><span class=\"sender\">{}</span> &middot; <time>{}</time></div>\n<div class=\"content\">{}</div>\n</div>\n",
+            escape_html(sender),
+            escape_html(&msg.timestamp),
+            escape_html(&msg.content)
+        ));
+    }
+    html.push_str("</body>\n</html>\n");
+    html
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use microclaw_storage::db::{Database, StoredMessage};
+    use microclaw_storage::db::Database;
 
     fn test_db() -> (Arc<Database>, std::path::PathBuf) {
         let dir = std::env::temp_dir().join(format!("microclaw_export_{}", uuid::Uuid::new_v4()));
@@ -230,4 +404,80 @@ mod tests {
         assert!(content.contains("hello"));
         cleanup(&dir);
     }
+
+    fn store_sample_chat(db: &Database) -> Vec<StoredMessage> {
+        let messages = vec![
+            StoredMessage {
+                id: "m1".into(),
+                chat_id: 300,
+                sender_name: "alice".into(),
+                content: "is 2 < 3 & \"true\"?".into(),
+                is_from_bot: false,
+                timestamp: "2024-01-01T00:00:01Z".into(),
+            },
+            StoredMessage {
+                id: "m2".into(),
+                chat_id: 300,
+                sender_name: "bot".into(),
+                content: "yes\nit is".into(),
+                is_from_bot: true,
+                timestamp: "2024-01-01T00:00:02Z".into(),
+            },
+        ];
+        for msg in &messages {
+            db.store_message(msg).unwrap();
+        }
+        messages
+    }
+
+    #[tokio::test]
+    async fn test_export_chat_json_round_trips() {
+        let (db, dir) = test_db();
+        let stored = store_sample_chat(&db);
+        let tool = ExportChatTool::new(db, dir.to_str().unwrap());
+        let result = tool
+            .execute(json!({"chat_id": 300, "format": "json"}))
+            .await;
+        assert!(!result.is_error, "{}", result.content);
+        assert!(result.content.contains("as JSON"));
+
+        let path = result.content.rsplit(" to ").next().unwrap();
+        assert!(path.ends_with(".json"));
+        assert!(std::path::Path::new(path).starts_with(
+            dir.join("groups")
+                .join("unknown")
+                .join("300")
+                .join("exports")
+        ));
+        let exported: Vec<ExportedMessage> =
+            serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        let expected: Vec<ExportedMessage> = stored.iter().map(ExportedMessage::from).collect();
+        assert_eq!(exported, expected);
+        cleanup(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_export_chat_html_contains_messages() {
+        let (db, dir) = test_db();
+        store_sample_chat(&db);
+        let out_path = dir.join("export.html");
+        let tool = ExportChatTool::new(db, dir.to_str().unwrap());
+        let result = tool
+            .execute(json!({"chat_id": 300, "format": "html", "path": out_path.to_str().unwrap()}))
+            .await;
+        assert!(!result.is_error, "{}", result.content);
+        assert!(result.content.contains("as HTML"));
+
+        let html = std::fs::read_to_string(&out_path).unwrap();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<span class=\"sender\">alice</span>"));
+        assert!(html.contains("is 2 &lt; 3 &amp; &quot;true&quot;?"));
+        assert!(html.contains("<div class=\"msg bot\">"));
+        assert!(html.contains("yes\nit is"));
+        assert!(html.contains("2024-01-01T00:00:02Z"));
+
+        let bad = tool.execute(json!({"chat_id": 300, "format": "pdf"})).await;
+        assert!(bad.is_error);
+        cleanup(&dir);
+    }
 }
@@ -0,0 +1,507 @@
+use async_trait::async_trait;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::info;
+
+use crate::memory_backend::MemoryBackend;
+use microclaw_core::llm_types::ToolDefinition;
+use microclaw_storage::db::Memory;
+
+use super::{auth_context_from_input, authorize_chat_access, schema_object, Tool, ToolResult};
+
+/// Share of the query's keywords a memory must contain to be forgotten.
+const FORGET_MIN_SCORE: f64 = 0.6;
+const FORGET_DEFAULT_LIMIT: usize = 5;
+const FORGET_MAX_LIMIT: usize = 20;
+/// Candidates fetched per keyword before scoring.
+const FORGET_SEARCH_LIMIT: usize = 50;
+
+const STOPWORDS: &[&str] = &[
+    "the",
+    "and",
+    "that",
+    "this",
+    "about",
+    "with",
+    "for",
+    "from",
+    "you",
+    "your",
+    "what",
+    "are",
+    "was",
+    "were",
+    "have",
+    "has",
+    "had",
+    "not",
+    "forget",
+    "remember",
+    "please",
+    "any",
+    "all",
+    "everything",
+    "thing",
+    "things",
+    "its",
+    "it's",
+    "i'm",
+    "me",
+    "my",
+    "mine",
+];
+
+/// Lowercased words of the query worth matching on.
+fn query_keywords(query: &str) -> Vec<String> {
+    let mut keywords: Vec<String> = Vec::new();
+    for word in query
+        .split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .map(|w| w.trim_matches('\'').to_lowercase())
+    {
+        if word.chars().count() >= 2
+            && !STOPWORDS.contains(&word.as_str())
+            && !keywords.contains(&word)
+        {
+            keywords.push(word);
+        }
+    }
+    keywords
+}
+
+/// Fraction of `keywords` found in `content`, in 0.0..=1.0.
+fn match_score(keywords: &[String], content: &str) -> f64 {
+    if keywords.is_empty() {
+        return 0.0;
+    }
+    let content = content.to_lowercase();
+    let hits = keywords
+        .iter()
+        .filter(|k| content.contains(k.as_str()))
+        .count();
+    hits as f64 / keywords.len() as f64
+}
+
+pub struct ForgetTool {
+    memory_backend: Arc<MemoryBackend>,
+}
+
+impl ForgetTool {
+    pub fn new(memory_backend: Arc<MemoryBackend>) -> Self {
+        Self { memory_backend }
+    }
+
+    /// Active memories in scope that score at least `FORGET_MIN_SCORE`,
+    /// best matches first.
+    async fn find_matches(
+        &self,
+        chat_id: i64,
+        global: bool,
+        keywords: &[String],
+        limit: usize,
+    ) -> Result<Vec<(Memory, f64)>, String> {
+        let mut candidates: HashMap<i64, Memory> = HashMap::new();
+        for keyword in keywords {
+            let found = self
+                .memory_backend
+                .search_memories_with_options(chat_id, keyword, FORGET_SEARCH_LIMIT, false, true)
+                .await
+                .map_err(|e| format!("Search failed: {e}"))?;
+            for memory in found {
+                candidates.entry(memory.id).or_insert(memory);
+            }
+        }
+        let mut matches: Vec<(Memory, f64)> = candidates
+            .into_values()
+            .filter(|m| {
+                !m.is_archived
+                    && if global {
+                        m.chat_id.is_none()
+                    } else {
+                        m.chat_id == Some(chat_id)
+                    }
+            })
+            .map(|m| {
+                let score = match_score(keywords, &m.content);
+                (m, score)
+            })
+            .filter(|(_, score)| *score >= FORGET_MIN_SCORE)
+            .collect();
+        matches.sort_by(|a, b| {
+            b.1.total_cmp(&a.1)
+                .then_with(|| b.0.confidence.total_cmp(&a.0.confidence))
+                .then_with(|| a.0.id.cmp(&b.0.id))
+        });
+        matches.truncate(limit);
+        Ok(matches)
+    }
+
+    /// Archive the memories the user confirmed from a preview, by id, so the
+    /// set cannot drift if memories changed since the preview. Ids that no
+    /// longer score `FORGET_MIN_SCORE` against the query are refused.
+    async fn archive_previewed(
+        &self,
+        input: &serde_json::Value,
+        chat_id: i64,
+        global: bool,
+        query: &str,
+        keywords: &[String],
+    ) -> ToolResult {
+        let ids: Vec<i64> =
+            match input.get("ids").and_then(|v| v.as_array()) {
+                Some(ids) if !ids.is_empty() => match ids.iter().map(|v| v.as_i64()).collect() {
+                    Some(ids) => ids,
+                    None => return ToolResult::error("'ids' must be a list of integers".into()),
+                },
+                _ => return ToolResult::error(
+                    "confirm=true needs 'ids': the ids from the preview the user agreed to forget"
+                        .into(),
+                ),
+            };
+        if ids.len() > FORGET_MAX_LIMIT {
+            return ToolResult::error(format!(
+                "Too many ids ({}); forget at most {FORGET_MAX_LIMIT} memories at a time",
+                ids.len()
+            ));
+        }
+
+        info!("forget: chat_id={chat_id} global={global} query={query:?} archiving ids {ids:?}");
+        let mut archived = Vec::new();
+        let mut failed = Vec::new();
+        for id in ids {
+            let memory = match self.memory_backend.get_memory_by_id(id).await {
+                Ok(Some(m)) => m,
+                Ok(None) => {
+                    failed.push(format!("[id={id}] not found"));
+                    continue;
+                }
+                Err(e) => {
+                    failed.push(format!("[id={id}] {e}"));
+                    continue;
+                }
+            };
+            let in_scope = if global {
+                memory.chat_id.is_none()
+            } else {
+                memory.chat_id == Some(chat_id)
+            };
+            if !in_scope {
+                failed.push(format!("[id={id}] not in this scope"));
+                continue;
+            }
+            if memory.is_archived {
+                failed.push(format!("[id={id}] already archived"));
+                continue;
+            }
+            let score = match_score(keywords, &memory.content);
+            if score < FORGET_MIN_SCORE {
+                failed.push(format!(
+                    "[id={id}] does not match the query (match {:.0}%)",
+                    score * 100.0
+                ));
+                continue;
+            }
+            match self.memory_backend.archive_memory(id).await {
+                Ok(true) => archived.push(format!(
+                    "[id={}] [{}] {}",
+                    memory.id, memory.category, memory.content
+                )),
+                Ok(false) => failed.push(format!("[id={id}] not found")),
+                Err(e) => failed.push(format!("[id={id}] {e}")),
+            }
+        }
+        let mut lines = vec![format!("Archived {} memories:", archived.len())];
+        lines.extend(archived);
+        if !failed.is_empty() {
+            lines.push(format!("Failed to archive {}:", failed.len()));
+            lines.extend(failed);
+        }
+        ToolResult::success(lines.join("\n"))
+    }
+}
+
+fn format_match(memory: &Memory, score: f64) -> String {
+    format!(
+        "[id={}] [{}] {} (match {:.0}%)",
+        memory.id,
+        memory.category,
+        memory.content,
+        score * 100.0
+    )
+}
+
+#[async_trait]
+impl Tool for ForgetTool {
+    fn name(&self) -> &str {
+        "forget"
+    }
+
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "forget".into(),
+            description: "Forget structured memories matching a natural-language description, e.g. when the user asks you to forget something. Works in two steps: call without confirm to see which memories match, show them to the user, then call again with confirm=true and the ids the user agreed to forget. Only the listed memories are archived.".into(),
+            input_schema: schema_object(
+                json!({
+                    "query": {
+                        "type": "string",
+                        "description": "What to forget, e.g. \"my old address\""
+                    },
+                    "scope": {
+                        "type": "string",
+                        "enum": ["chat", "global"],
+                        "description": "Forget memories of this chat (default) or global memories (control chats only)"
+                    },
+                    "confirm": {
+                        "type": "boolean",
+                        "description": "false (default) only lists the matches; true archives the memories in ids"
+                    },
+                    "ids": {
+                        "type": "array",
+                        "items": {"type": "integer"},
+                        "description": "Ids from the preview to archive (required with confirm=true)"
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "description": "Maximum number of memories to forget (default 5, max 20)"
+                    },
+                    "chat_id": {
+                        "type": "integer",
+                        "description": "Chat whose memories to forget (defaults to the current chat)"
+                    }
+                }),
+                &["query"],
+            ),
+        }
+    }
+
+    async fn execute(&self, input: serde_json::Value) -> ToolResult {
+        let query = match input.get("query").and_then(|v| v.as_str()) {
+            Some(q) if !q.trim().is_empty() => q.trim().to_string(),
+            _ => return ToolResult::error("Missing or empty 'query' parameter".into()),
+        };
+        let global = match input
+            .get("scope")
+            .and_then(|v| v.as_str())
+            .unwrap_or("chat")
+        {
+            "chat" => false,
+            "global" => true,
+            other => {
+                return ToolResult::error(format!(
+                    "Invalid scope '{other}'. Use \"chat\" or \"global\"."
+                ))
+            }
+        };
+        let confirm = input
+            .get("confirm")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let limit = input
+            .get("limit")
+            .and_then(|v| v.as_u64())
+            .map(|n| (n as usize).clamp(1, FORGET_MAX_LIMIT))
+            .unwrap_or(FORGET_DEFAULT_LIMIT);
+
+        let auth = auth_context_from_input(&input);
+        let chat_id = match input
+            .get("chat_id")
+            .and_then(|v| v.as_i64())
+            .or(auth.as_ref().map(|a| a.caller_chat_id))
+        {
+            Some(id) => id,
+            None => return ToolResult::error("Missing 'chat_id' parameter".into()),
+        };
+        if let Err(e) = authorize_chat_access(&input, chat_id) {
+            return ToolResult::error(e);
+        }
+        if global {
+            if let Some(auth) = &auth {
+                if !auth.is_control_chat() {
+                    return ToolResult::error(format!(
+                        "Permission denied: only control chats can forget global memories (caller: {})",
+                        auth.caller_chat_id
+                    ));
+                }
+            }
+        }
+
+        let keywords = query_keywords(&query);
+        if keywords.is_empty() {
+            return ToolResult::error(
+                "The query is too vague; describe what to forget more specifically.".into(),
+            );
+        }
+        if confirm {
+            return self
+                .archive_previewed(&input, chat_id, global, &query, &keywords)
+                .await;
+        }
+        let matches = match self.find_matches(chat_id, global, &keywords, limit).await {
+            Ok(m) => m,
+            Err(e) => return ToolResult::error(e),
+        };
+        if matches.is_empty() {
+            return ToolResult::success("No memories closely match that query.".into());
+        }
+
+        let mut lines = vec![format!(
+            "{} memories match. Show them to the user and call forget again with confirm=true and the ids to archive:",
+            matches.len()
+        )];
+        lines.extend(matches.iter().map(|(m, score)| format_match(m, *score)));
+        ToolResult::success(lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use microclaw_storage::db::Database;
+
+    fn setup() -> (Arc<Database>, ForgetTool, std::path::PathBuf) {
+        let dir = std::env::temp_dir().join(format!("mc_forget_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let db = Arc::new(Database::new(dir.to_str().unwrap()).unwrap());
+        let tool = ForgetTool::new(Arc::new(MemoryBackend::local_only(db.clone())));
+        (db, tool, dir)
+    }
+
+    fn auth(chat_id: i64) -> serde_json::Value {
+        json!({"caller_chat_id": chat_id, "control_chat_ids": []})
+    }
+
+    #[test]
+    fn test_query_keywords_and_score() {
+        assert_eq!(
+            query_keywords("Please forget my old address!"),
+            vec!["old".to_string(), "address".to_string()]
+        );
+        let keywords = query_keywords("old address");
+        assert_eq!(match_score(&keywords, "User's old address: 1 Main St"), 1.0);
+        assert_eq!(match_score(&keywords, "New address is 5 Elm St"), 0.5);
+        assert_eq!(match_score(&[], "anything"), 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_forget_is_two_step_and_scoped_to_chat() {
+        let (db, tool, dir) = setup();
+        let target = db
+            .insert_memory(Some(100), "User's old address is 1 Main St", "PROFILE")
+            .unwrap();
+        let weak = db
+            .insert_memory(Some(100), "User's new address is 5 Elm St", "PROFILE")
+            .unwrap();
+        let other_chat = db
+            .insert_memory(Some(200), "Old address on file: 9 Oak Rd", "PROFILE")
+            .unwrap();
+
+        let preview = tool
+            .execute(json!({"query": "old address", "__microclaw_auth": auth(100)}))
+            .await;
+        assert!(!preview.is_error, "{}", preview.content);
+        assert!(preview.content.contains(&format!("[id={target}]")));
+        assert!(!preview.content.contains(&format!("[id={weak}]")));
+        assert!(!preview.content.contains(&format!("[id={other_chat}]")));
+        assert!(!db.get_memory_by_id(target).unwrap().unwrap().is_archived);
+
+        let missing_ids = tool
+            .execute(json!({
+                "query": "old address",
+                "confirm": true,
+                "__microclaw_auth": auth(100)
+            }))
+            .await;
+        assert!(missing_ids.is_error);
+        assert!(!db.get_memory_by_id(target).unwrap().unwrap().is_archived);
+
+        // Added after the preview: matches the query but was never shown.
+        let late = db
+            .insert_memory(Some(100), "Old address was also 2 Main St", "PROFILE")
+            .unwrap();
+        let done = tool
+            .execute(json!({
+                "query": "old address",
+                "confirm": true,
+                "ids": [target, other_chat],
+                "__microclaw_auth": auth(100)
+            }))
+            .await;
+        assert!(!done.is_error, "{}", done.content);
+        assert!(done.content.starts_with("Archived 1 memories:"));
+        assert!(done
+            .content
+            .contains(&format!("[id={other_chat}] not in this scope")));
+        assert!(db.get_memory_by_id(target).unwrap().unwrap().is_archived);
+        assert!(!db.get_memory_by_id(late).unwrap().unwrap().is_archived);
+        assert!(!db.get_memory_by_id(weak).unwrap().unwrap().is_archived);
+        assert!(
+            !db.get_memory_by_id(other_chat)
+                .unwrap()
+                .unwrap()
+                .is_archived
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_forget_confirm_refuses_ids_not_matching_query() {
+        let (db, tool, dir) = setup();
+        let target = db
+            .insert_memory(Some(100), "User's old address is 1 Main St", "PROFILE")
+            .unwrap();
+        let unrelated = db
+            .insert_memory(Some(100), "User prefers tea over coffee", "PROFILE")
+            .unwrap();
+
+        let done = tool
+            .execute(json!({
+                "query": "old address",
+                "confirm": true,
+                "ids": [target, unrelated],
+                "__microclaw_auth": auth(100)
+            }))
+            .await;
+        assert!(!done.is_error, "{}", done.content);
+        assert!(done.content.starts_with("Archived 1 memories:"));
+        assert!(done
+            .content
+            .contains(&format!("[id={unrelated}] does not match the query")));
+        assert!(db.get_memory_by_id(target).unwrap().unwrap().is_archived);
+        assert!(!db.get_memory_by_id(unrelated).unwrap().unwrap().is_archived);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_forget_permissions() {
+        let (db, tool, dir) = setup();
+        db.insert_memory(None, "Team standup is at 9am", "KNOWLEDGE")
+            .unwrap();
+
+        let global = tool
+            .execute(json!({
+                "query": "standup",
+                "scope": "global",
+                "confirm": true,
+                "__microclaw_auth": auth(100)
+            }))
+            .await;
+        assert!(global.is_error);
+        assert!(global.content.contains("only control chats"));
+
+        let cross_chat = tool
+            .execute(json!({
+                "query": "standup",
+                "chat_id": 200,
+                "__microclaw_auth": auth(100)
+            }))
+            .await;
+        assert!(cross_chat.is_error);
+        assert!(cross_chat.content.contains("Permission denied"));
+
+        let vague = tool
+            .execute(json!({"query": "everything", "__microclaw_auth": auth(100)}))
+            .await;
+        assert!(vague.is_error);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
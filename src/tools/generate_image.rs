@@ -0,0 +1,530 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use base64::Engine;
+use serde_json::json;
+use tracing::{info, warn};
+
+use super::{authorize_chat_access, schema_object, Tool, ToolResult};
+use crate::config::Config;
+use microclaw_channels::channel::{enforce_channel_policy, get_required_chat_routing};
+use microclaw_channels::channel_adapter::ChannelRegistry;
+use microclaw_core::llm_types::ToolDefinition;
+use microclaw_storage::db::{call_blocking, Database, StoredMessage};
+
+const DEFAULT_IMAGE_BASE_URL: &str = "https://api.openai.com/v1";
+const CAPTION_MAX_CHARS: usize = 200;
+
+pub struct GenerateImageTool {
+    registry: Arc<ChannelRegistry>,
+    db: Arc<Database>,
+    http: reqwest::Client,
+    api_key: String,
+    endpoint: String,
+    model: String,
+    data_dir: String,
+    default_bot_username: String,
+    channel_bot_usernames: HashMap<String, String>,
+}
+
+impl GenerateImageTool {
+    /// `None` when no `openai_api_key` is configured.
+    pub fn from_config(
+        config: &Config,
+        registry: Arc<ChannelRegistry>,
+        db: Arc<Database>,
+    ) -> Option<Self> {
+        let api_key = config
+            .openai_api_key
+            .as_deref()
+            .map(str::trim)
+            .filter(|k| !k.is_empty())?;
+        let base_url = config
+            .image_generation_base_url
+            .as_deref()
+            .map(str::trim)
+            .filter(|u| !u.is_empty())
+            .unwrap_or(DEFAULT_IMAGE_BASE_URL)
+            .trim_end_matches('/');
+        Some(GenerateImageTool {
+            registry,
+            db,
+            http: reqwest::Client::builder()
+                .timeout(Duration::from_secs(180))
+                .build()
+                .unwrap_or_default(),
+            api_key: api_key.to_string(),
+            endpoint: format!("{base_url}/images/generations"),
+            model: config.image_generation_model.clone(),
+            data_dir: config.data_dir.clone(),
+            default_bot_username: if config.bot_username.trim().is_empty() {
+                "bot".to_string()
+            } else {
+                config.bot_username.clone()
+            },
+            channel_bot_usernames: config.bot_username_overrides(),
+        })
+    }
+
+    async fn request_image(&self, prompt: &str, size: Option<&str>) -> Result<Vec<u8>, ImageError> {
+        let mut body = json!({
+            "model": self.model,
+            "prompt": prompt,
+            "n": 1,
+        });
+        if let Some(size) = size {
+            body["size"] = json!(size);
+        }
+        // DALL-E models default to returning a URL; gpt-image models always
+        // return base64 and reject the parameter.
+        if self.model.starts_with("dall-e") {
+            body["response_format"] = json!("b64_json");
+        }
+
+        let response = self
+            .http
+            .post(&self.endpoint)
+            .bearer_auth(&self.api_key)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| ("network", format!("Image generation request failed: {e}")))?;
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        if !status.is_success() {
+            let (error_type, message) = classify_image_error(&text);
+            return Err((
+                error_type,
+                format!("Image generation failed (HTTP {status}): {message}"),
+            ));
+        }
+
+        match parse_image_payload(&text)? {
+            ImagePayload::Bytes(bytes) => Ok(bytes),
+            ImagePayload::Url(url) => {
+                let response = self.http.get(&url).send().await.map_err(|e| {
+                    (
+                        "network",
+                        format!("Failed to download generated image: {e}"),
+                    )
+                })?;
+                if !response.status().is_success() {
+                    return Err((
+                        "image_provider_error",
+                        format!(
+                            "Failed to download generated image: HTTP {}",
+                            response.status()
+                        ),
+                    ));
+                }
+                let bytes = response.bytes().await.map_err(|e| {
+                    (
+                        "network",
+                        format!("Failed to download generated image: {e}"),
+                    )
+                })?;
+                Ok(bytes.to_vec())
+            }
+        }
+    }
+
+    /// Send the saved image to the chat and record it in history. Returns
+    /// false on local-only channels (web), where there is nothing to send.
+    async fn deliver(
+        &self,
+        chat_id: i64,
+        path: &std::path::Path,
+        caption: &str,
+    ) -> Result<bool, String> {
+        let routing = get_required_chat_routing(&self.registry, self.db.clone(), chat_id).await?;
+        let adapter = self.registry.get(&routing.channel_name).ok_or_else(|| {
+            format!(
+                "No adapter registered for channel '{}'",
+                routing.channel_name
+            )
+        })?;
+        if adapter.is_local_only() {
+            return Ok(false);
+        }
+        let external_chat_id =
+            call_blocking(self.db.clone(), move |db| db.get_chat_external_id(chat_id))
+                .await
+                .map_err(|e| format!("Failed to resolve external chat id: {e}"))?
+                .unwrap_or_else(|| chat_id.to_string());
+        let content = adapter
+            .send_attachment(&external_chat_id, path, Some(caption))
+            .await?;
+        let msg = StoredMessage {
+            id: uuid::Uuid::new_v4().to_string(),
+            chat_id,
+            sender_name: self
+                .channel_bot_usernames
+                .get(&routing.channel_name)
+                .cloned()
+                .unwrap_or_else(|| self.default_bot_username.clone()),
+            content,
+            is_from_bot: true,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        };
+        call_blocking(self.db.clone(), move |db| db.store_message(&msg))
+            .await
+            .map_err(|e| format!("Failed to store sent image: {e}"))?;
+        Ok(true)
+    }
+}
+
+/// `error_type` and message for a failed generation.
+type ImageError = (&'static str, String);
+
+enum ImagePayload {
+    Bytes(Vec<u8>),
+    Url(String),
+}
+
+fn parse_image_payload(body: &str) -> Result<ImagePayload, ImageError> {
+    let invalid = |detail: &str| {
+        (
+            "image_provider_error",
+            format!("Unexpected image generation response: {detail}"),
+        )
+    };
+    let value: serde_json::Value =
+        serde_json::from_str(body).map_err(|e| invalid(&e.to_string()))?;
+    let first = value
+        .get("data")
+        .and_then(|d| d.as_array())
+        .and_then(|d| d.first())
+        .ok_or_else(|| invalid("no images returned"))?;
+    if let Some(b64) = first.get("b64_json").and_then(|v| v.as_str()) {
+        return base64::engine::general_purpose::STANDARD
+            .decode(b64.trim())
+            .map(ImagePayload::Bytes)
+            .map_err(|e| invalid(&format!("bad base64: {e}")));
+    }
+    first
+        .get("url")
+        .and_then(|v| v.as_str())
+        .map(|url| ImagePayload::Url(url.to_string()))
+        .ok_or_else(|| invalid("image has neither b64_json nor url"))
+}
+
+/// Map an error body to an `error_type` and a message for the model.
+/// Prompts refused by the provider's safety system become `content_policy`,
+/// so the model rephrases instead of retrying the same prompt.
+fn classify_image_error(body: &str) -> (&'static str, String) {
+    let error = serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|v| v.get("error").cloned());
+    let message = error
+        .as_ref()
+        .and_then(|e| e.get("message"))
+        .and_then(|m| m.as_str())
+        .map(str::to_string)
+        .unwrap_or_else(|| body.chars().take(300).collect());
+    let code = error
+        .as_ref()
+        .and_then(|e| e.get("code"))
+        .and_then(|c| c.as_str())
+        .unwrap_or("");
+    let lower = message.to_ascii_lowercase();
+    if code == "content_policy_violation"
+        || code == "moderation_blocked"
+        || lower.contains("safety system")
+    {
+        ("content_policy", message)
+    } else {
+        ("image_provider_error", message)
+    }
+}
+
+fn image_extension(bytes: &[u8]) -> &'static str {
+    if bytes.starts_with(&[0xFF, 0xD8]) {
+        "jpg"
+    } else if bytes.starts_with(b"RIFF") && bytes.len() >= 12 && &bytes[8..12] == b"WEBP" {
+        "webp"
+    } else {
+        "png"
+    }
+}
+
+#[async_trait]
+impl Tool for GenerateImageTool {
+    fn name(&self) -> &str {
+        "generate_image"
+    }
+
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "generate_image".into(),
+            description: "Generate an image from a text prompt and send it to the chat. The image is also saved under data/generated/ and its path is returned.".into(),
+            input_schema: schema_object(
+                json!({
+                    "chat_id": {
+                        "type": "integer",
+                        "description": "The chat to send the image to"
+                    },
+                    "prompt": {
+                        "type": "string",
+                        "description": "A detailed description of the image to generate"
+                    },
+                    "size": {
+                        "type": "string",
+                        "description": "Optional image size, e.g. 1024x1024, 1536x1024 (landscape), 1024x1536 (portrait)"
+                    }
+                }),
+                &["chat_id", "prompt"],
+            ),
+        }
+    }
+
+    async fn execute(&self, input: serde_json::Value) -> ToolResult {
+        let chat_id = match input.get("chat_id").and_then(|v| v.as_i64()) {
+            Some(id) => id,
+            None => return ToolResult::error("Missing required parameter: chat_id".into()),
+        };
+        let prompt = input
+            .get("prompt")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .unwrap_or("");
+        if prompt.is_empty() {
+            return ToolResult::error("Missing required parameter: prompt".into());
+        }
+        let size = input
+            .get("size")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|s| !s.is_empty());
+
+        if let Err(e) = authorize_chat_access(&input, chat_id) {
+            return ToolResult::error(e);
+        }
+        if let Err(e) =
+            enforce_channel_policy(&self.registry, self.db.clone(), &input, chat_id).await
+        {
+            return ToolResult::error(e);
+        }
+
+        info!(
+            "generate_image start: chat_id={}, model={}, size={:?}",
+            chat_id, self.model, size
+        );
+        let bytes = match self.request_image(prompt, size).await {
+            Ok(bytes) => bytes,
+            Err((error_type, message)) => {
+                warn!(
+                    "generate_image failed: chat_id={}, error={}",
+                    chat_id, message
+                );
+                return ToolResult::error(message).with_error_type(error_type);
+            }
+        };
+
+        let path = PathBuf::from(&self.data_dir)
+            .join("generated")
+            .join(format!(
+                "{chat_id}_{}_{}.{}",
+                chrono::Utc::now().format("%Y%m%d_%H%M%S"),
+                &uuid::Uuid::new_v4().simple().to_string()[..8],
+                image_extension(&bytes)
+            ));
+        if let Some(parent) = path.parent() {
+            if let Err(e) = std::fs::create_dir_all(parent) {
+                return ToolResult::error(format!("Failed to create directory: {e}"));
+            }
+        }
+        if let Err(e) = std::fs::write(&path, &bytes) {
+            return ToolResult::error(format!("Failed to save generated image: {e}"));
+        }
+
+        let caption: String = prompt.chars().take(CAPTION_MAX_CHARS).collect();
+        match self.deliver(chat_id, &path, &caption).await {
+            Ok(true) => ToolResult::success(format!(
+                "Generated image sent to the chat and saved to {}",
+                path.display()
+            )),
+            Ok(false) => ToolResult::success(format!(
+                "Generated image saved to {} (this channel cannot display images; share the path instead)",
+                path.display()
+            )),
+            Err(e) => {
+                warn!("generate_image delivery failed: chat_id={}, error={}", chat_id, e);
+                ToolResult::error(format!(
+                    "Generated image saved to {} but could not be sent: {e}",
+                    path.display()
+                ))
+                .with_error_type("delivery_failed")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use microclaw_channels::channel::ConversationKind;
+    use microclaw_channels::channel_adapter::ChannelAdapter;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::Mutex;
+
+    struct PhotoAdapter {
+        sent: Mutex<Vec<(PathBuf, Option<String>)>>,
+    }
+
+    #[async_trait]
+    impl ChannelAdapter for PhotoAdapter {
+        fn name(&self) -> &str {
+            "telegram"
+        }
+
+        fn chat_type_routes(&self) -> Vec<(&str, ConversationKind)> {
+            vec![("telegram_private", ConversationKind::Private)]
+        }
+
+        async fn send_text(&self, _external_chat_id: &str, _text: &str) -> Result<(), String> {
+            Ok(())
+        }
+
+        async fn send_attachment(
+            &self,
+            _external_chat_id: &str,
+            file_path: &std::path::Path,
+            caption: Option<&str>,
+        ) -> Result<String, String> {
+            self.sent
+                .lock()
+                .unwrap()
+                .push((file_path.to_path_buf(), caption.map(str::to_string)));
+            Ok(format!("[attachment:{}]", file_path.display()))
+        }
+    }
+
+    /// Answer one request with `status` and `body`.
+    fn spawn_image_server(status: u16, body: String) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/v1", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            stream
+                .set_read_timeout(Some(Duration::from_secs(2)))
+                .unwrap();
+            let mut buf = [0u8; 8192];
+            let _ = stream.read(&mut buf);
+            let response = format!(
+                "HTTP/1.1 {status} X\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            let _ = stream.write_all(response.as_bytes());
+        });
+        url
+    }
+
+    fn setup(
+        base_url: String,
+    ) -> (
+        GenerateImageTool,
+        Arc<Database>,
+        Arc<PhotoAdapter>,
+        i64,
+        PathBuf,
+    ) {
+        let dir = std::env::temp_dir().join(format!("microclaw_genimg_{}", uuid::Uuid::new_v4()));
+        let db = Arc::new(Database::new(dir.to_str().unwrap()).unwrap());
+        let chat_id = db
+            .resolve_or_create_chat_id("telegram", "5001", None, "telegram_private")
+            .unwrap();
+        let adapter = Arc::new(PhotoAdapter {
+            sent: Mutex::new(Vec::new()),
+        });
+        let mut registry = ChannelRegistry::new();
+        registry.register(adapter.clone());
+        let mut config = Config::test_defaults();
+        config.openai_api_key = Some("sk-test".into());
+        config.image_generation_base_url = Some(base_url);
+        config.data_dir = dir.to_string_lossy().to_string();
+        let tool = GenerateImageTool::from_config(&config, Arc::new(registry), db.clone()).unwrap();
+        (tool, db, adapter, chat_id, dir)
+    }
+
+    fn auth(chat_id: i64) -> serde_json::Value {
+        json!({"caller_chat_id": chat_id, "control_chat_ids": []})
+    }
+
+    #[test]
+    fn test_tool_requires_openai_api_key() {
+        let config = Config::test_defaults();
+        let dir = std::env::temp_dir().join(format!("microclaw_genimg_{}", uuid::Uuid::new_v4()));
+        let db = Arc::new(Database::new(dir.to_str().unwrap()).unwrap());
+        assert!(
+            GenerateImageTool::from_config(&config, Arc::new(ChannelRegistry::new()), db).is_none()
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_classify_image_error_detects_content_policy() {
+        let body = r#"{"error":{"code":"content_policy_violation","message":"Your request was rejected as a result of our safety system."}}"#;
+        assert_eq!(classify_image_error(body).0, "content_policy");
+        let body = r#"{"error":{"code":"invalid_size","message":"Invalid size"}}"#;
+        assert_eq!(
+            classify_image_error(body),
+            ("image_provider_error", "Invalid size".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_generate_image_saves_and_sends_photo() {
+        let png = [0x89, b'P', b'N', b'G', 1, 2, 3];
+        let b64 = base64::engine::general_purpose::STANDARD.encode(png);
+        let url = spawn_image_server(200, format!(r#"{{"data":[{{"b64_json":"{b64}"}}]}}"#));
+        let (tool, db, adapter, chat_id, dir) = setup(url);
+
+        let result = tool
+            .execute(json!({
+                "chat_id": chat_id,
+                "prompt": "a red fox in snow",
+                "__microclaw_auth": auth(chat_id),
+            }))
+            .await;
+        assert!(!result.is_error, "{}", result.content);
+        let sent = adapter.sent.lock().unwrap().clone();
+        assert_eq!(sent.len(), 1);
+        let (path, caption) = &sent[0];
+        assert!(path.starts_with(dir.join("generated")));
+        assert_eq!(path.extension().unwrap(), "png");
+        assert_eq!(std::fs::read(path).unwrap(), png);
+        assert_eq!(caption.as_deref(), Some("a red fox in snow"));
+        assert!(result.content.contains(&path.display().to_string()));
+        assert_eq!(db.get_all_messages(chat_id).unwrap().len(), 1);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_generate_image_reports_content_policy_rejection() {
+        let url = spawn_image_server(
+            400,
+            r#"{"error":{"code":"content_policy_violation","message":"Rejected by safety system"}}"#
+                .into(),
+        );
+        let (tool, _db, adapter, chat_id, dir) = setup(url);
+
+        let result = tool
+            .execute(json!({
+                "chat_id": chat_id,
+                "prompt": "something disallowed",
+                "__microclaw_auth": auth(chat_id),
+            }))
+            .await;
+        assert!(result.is_error);
+        assert_eq!(result.error_type.as_deref(), Some("content_policy"));
+        assert!(result.content.contains("Rejected by safety system"));
+        assert!(adapter.sent.lock().unwrap().is_empty());
+        assert!(!dir.join("generated").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,539 @@
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use reqwest::Url;
+use serde_json::json;
+
+use super::{schema_object, Tool, ToolResult};
+use microclaw_core::llm_types::ToolDefinition;
+
+const MAX_BODY_BYTES: usize = 20_000;
+const MAX_REDIRECTS: usize = 5;
+const MIN_TIMEOUT_SECS: u64 = 1;
+const MAX_TIMEOUT_SECS: u64 = 120;
+/// Credentials a caller sets for one origin; dropped when a redirect leaves it.
+const CREDENTIAL_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "x-api-key",
+    "cookie",
+];
+const METHODS: &[&str] = &["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD"];
+
+pub struct HttpRequestTool {
+    default_timeout_secs: u64,
+    allowed_hosts: Vec<String>,
+    allow_private_networks: bool,
+}
+
+impl HttpRequestTool {
+    pub fn new(
+        default_timeout_secs: u64,
+        allowed_hosts: Vec<String>,
+        allow_private_networks: bool,
+    ) -> Self {
+        Self {
+            default_timeout_secs,
+            allowed_hosts: allowed_hosts
+                .iter()
+                .map(|h| h.trim().trim_start_matches("*.").to_ascii_lowercase())
+                .filter(|h| !h.is_empty())
+                .collect(),
+            allow_private_networks,
+        }
+    }
+
+    fn check_host_allowed(&self, url: &Url) -> Result<String, String> {
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(format!("URL scheme '{}' is not allowed", url.scheme()));
+        }
+        let host = url
+            .host_str()
+            .ok_or_else(|| "URL must include a host".to_string())?
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_ascii_lowercase();
+        if self.allowed_hosts.is_empty() {
+            return Err(
+                "http_request is disabled: no hosts are allowed (set http_tool_allowed_hosts)"
+                    .into(),
+            );
+        }
+        if !self
+            .allowed_hosts
+            .iter()
+            .any(|rule| host == *rule || host.ends_with(&format!(".{rule}")))
+        {
+            return Err(format!(
+                "URL host '{host}' is not in http_tool_allowed_hosts"
+            ));
+        }
+        Ok(host)
+    }
+
+    /// Resolve the host and refuse private, loopback and link-local targets
+    /// unless they are allowed. The checked address is the one connected to,
+    /// so a second DNS answer cannot swap in an internal address.
+    async fn resolve_target(&self, url: &Url, host: &str) -> Result<SocketAddr, String> {
+        let port = url
+            .port_or_known_default()
+            .ok_or_else(|| "URL has no port".to_string())?;
+        let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port))
+            .await
+            .map_err(|e| format!("dns error: {e}"))?
+            .collect();
+        let first = *addrs
+            .first()
+            .ok_or_else(|| format!("dns error: no addresses for '{host}'"))?;
+        if !self.allow_private_networks {
+            if let Some(addr) = addrs.iter().find(|a| is_private_address(a.ip())) {
+                return Err(format!(
+                    "URL host '{host}' resolves to private or loopback address {}",
+                    addr.ip()
+                ));
+            }
+        }
+        Ok(first)
+    }
+}
+
+fn is_private_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            v4.is_private()
+                || v4.is_loopback()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                || v4.is_multicast()
+                // Carrier-grade NAT (100.64.0.0/10)
+                || (a == 100 && (64..128).contains(&b))
+                // Benchmarking (198.18.0.0/15)
+                || (a == 198 && (b & 0xfe) == 18)
+        }
+        IpAddr::V6(v6) => {
+            if let Some(v4) = v6.to_ipv4_mapped() {
+                return is_private_address(IpAddr::V4(v4));
+            }
+            let segments = v6.segments();
+            // NAT64 (64:ff9b::/96) and 6to4 (2002::/16) embed an IPv4
+            // address that the gateway connects to.
+            if segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0] {
+                return is_private_address(IpAddr::V4(embedded_ipv4(segments[6], segments[7])));
+            }
+            if segments[0] == 0x2002 {
+                return is_private_address(IpAddr::V4(embedded_ipv4(segments[1], segments[2])));
+            }
+            let first = segments[0];
+            v6.is_loopback()
+                || v6.is_unspecified()
+                || v6.is_multicast()
+                // Unique local (fc00::/7) and link-local (fe80::/10)
+                || (first & 0xfe00) == 0xfc00
+                || (first & 0xffc0) == 0xfe80
+        }
+    }
+}
+
+fn embedded_ipv4(high: u16, low: u16) -> std::net::Ipv4Addr {
+    std::net::Ipv4Addr::from((u32::from(high) << 16) | u32::from(low))
+}
+
+/// Read at most `limit` bytes of the body, returning them and whether more
+/// was left unread.
+async fn read_body_capped(
+    response: &mut reqwest::Response,
+    limit: usize,
+) -> Result<(Vec<u8>, bool), reqwest::Error> {
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        let room = limit - body.len();
+        if chunk.len() > room {
+            body.extend_from_slice(&chunk[..room]);
+            return Ok((body, true));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok((body, false))
+}
+
+fn parse_headers(input: &serde_json::Value) -> Result<reqwest::header::HeaderMap, String> {
+    let mut headers = reqwest::header::HeaderMap::new();
+    let Some(obj) = input.get("headers").and_then(|v| v.as_object()) else {
+        return Ok(headers);
+    };
+    for (name, value) in obj {
+        let value = match value {
+            serde_json::Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        let name = reqwest::header::HeaderName::from_bytes(name.as_bytes())
+            .map_err(|e| format!("invalid header name '{name}': {e}"))?;
+        let value = reqwest::header::HeaderValue::from_str(&value)
+            .map_err(|e| format!("invalid value for header '{name}': {e}"))?;
+        headers.insert(name, value);
+    }
+    Ok(headers)
+}
+
+#[async_trait]
+impl Tool for HttpRequestTool {
+    fn name(&self) -> &str {
+        "http_request"
+    }
+
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "http_request".into(),
+            description: "Send an HTTP request to an allowlisted host and return JSON with `status`, `headers` and the raw `body` (max 20KB). Use this for JSON APIs and non-GET requests; use web_fetch to read web pages.".into(),
+            input_schema: schema_object(
+                json!({
+                    "method": {
+                        "type": "string",
+                        "enum": METHODS,
+                        "description": "HTTP method (default: GET)"
+                    },
+                    "url": {
+                        "type": "string",
+                        "description": "The URL to request"
+                    },
+                    "headers": {
+                        "type": "object",
+                        "description": "Request headers as name/value pairs",
+                        "additionalProperties": {"type": "string"}
+                    },
+                    "body": {
+                        "description": "Request body. Objects and arrays are sent as JSON; strings are sent as-is"
+                    },
+                    "timeout_secs": {
+                        "type": "integer",
+                        "description": "Timeout in seconds (defaults to configured tool timeout budget, max 120)"
+                    }
+                }),
+                &["url"],
+            ),
+        }
+    }
+
+    async fn execute(&self, input: serde_json::Value) -> ToolResult {
+        let Some(raw_url) = input.get("url").and_then(|v| v.as_str()) else {
+            return ToolResult::error("Missing required parameter: url".into());
+        };
+        let method_name = input
+            .get("method")
+            .and_then(|v| v.as_str())
+            .unwrap_or("GET")
+            .trim()
+            .to_ascii_uppercase();
+        if !METHODS.contains(&method_name.as_str()) {
+            return ToolResult::error(format!(
+                "Unsupported method '{method_name}' (allowed: {})",
+                METHODS.join(", ")
+            ));
+        }
+        let mut method =
+            reqwest::Method::from_bytes(method_name.as_bytes()).unwrap_or(reqwest::Method::GET);
+        let mut headers = match parse_headers(&input) {
+            Ok(headers) => headers,
+            Err(e) => return ToolResult::error(e),
+        };
+        let mut body = match input.get("body") {
+            None | Some(serde_json::Value::Null) => None,
+            Some(serde_json::Value::String(s)) => Some(s.clone()),
+            Some(other) => {
+                headers.entry(reqwest::header::CONTENT_TYPE).or_insert(
+                    reqwest::header::HeaderValue::from_static("application/json"),
+                );
+                Some(other.to_string())
+            }
+        };
+        let timeout_secs = input
+            .get("timeout_secs")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(self.default_timeout_secs)
+            .clamp(MIN_TIMEOUT_SECS, MAX_TIMEOUT_SECS);
+        let mut url = match Url::parse(raw_url) {
+            Ok(url) => url,
+            Err(e) => return ToolResult::error(format!("invalid URL: {e}")),
+        };
+
+        let mut redirects = 0usize;
+        let response = loop {
+            let host = match self.check_host_allowed(&url) {
+                Ok(host) => host,
+                Err(e) => return ToolResult::error(e),
+            };
+            let target = match self.resolve_target(&url, &host).await {
+                Ok(target) => target,
+                Err(e) => return ToolResult::error(e),
+            };
+            // Redirects are followed by hand so every hop is re-checked.
+            let client = match reqwest::Client::builder()
+                .timeout(Duration::from_secs(timeout_secs))
+                .redirect(reqwest::redirect::Policy::none())
+                .user_agent("MicroClaw/1.0")
+                .resolve(&host, target)
+                .build()
+            {
+                Ok(client) => client,
+                Err(e) => return ToolResult::error(format!("Failed to build HTTP client: {e}")),
+            };
+            let mut request = client
+                .request(method.clone(), url.clone())
+                .headers(headers.clone());
+            if let Some(body) = &body {
+                request = request.body(body.clone());
+            }
+            let response = match request.send().await {
+                Ok(response) => response,
+                Err(e) => {
+                    let message = format!("HTTP request failed: {e}");
+                    let result = ToolResult::error(message.clone());
+                    return match super::transient_error_type(&message) {
+                        Some(kind) => result.with_error_type(kind),
+                        None => result,
+                    };
+                }
+            };
+            if !response.status().is_redirection() {
+                break response;
+            }
+            let Some(location) = response
+                .headers()
+                .get(reqwest::header::LOCATION)
+                .and_then(|v| v.to_str().ok())
+            else {
+                break response;
+            };
+            if redirects >= MAX_REDIRECTS {
+                return ToolResult::error(format!("too many redirects (max {MAX_REDIRECTS})"));
+            }
+            redirects += 1;
+            let next = match url.join(location) {
+                Ok(next) => next,
+                Err(e) => {
+                    return ToolResult::error(format!("invalid redirect target '{location}': {e}"))
+                }
+            };
+            if next.origin() != url.origin() {
+                for name in CREDENTIAL_HEADERS {
+                    headers.remove(*name);
+                }
+            }
+            url = next;
+            // 307/308 repeat the request as-is; other redirects become a GET.
+            if !matches!(response.status().as_u16(), 307 | 308) && method != reqwest::Method::HEAD {
+                method = reqwest::Method::GET;
+                body = None;
+                headers.remove(reqwest::header::CONTENT_TYPE);
+            }
+        };
+
+        let mut response = response;
+        let status = response.status().as_u16();
+        let response_headers: serde_json::Map<String, serde_json::Value> = response
+            .headers()
+            .iter()
+            .map(|(name, value)| {
+                (
+                    name.to_string(),
+                    json!(String::from_utf8_lossy(value.as_bytes())),
+                )
+            })
+            .collect();
+        let content_length = response.content_length();
+        let (bytes, more) = match read_body_capped(&mut response, MAX_BODY_BYTES).await {
+            Ok(read) => read,
+            Err(e) => return ToolResult::error(format!("Failed to read response body: {e}")),
+        };
+        let text = match std::str::from_utf8(&bytes) {
+            // The cut split a multi-byte character; drop the partial one.
+            Err(e) if more && e.error_len().is_none() => {
+                String::from_utf8_lossy(&bytes[..e.valid_up_to()])
+            }
+            _ => String::from_utf8_lossy(&bytes),
+        };
+        let mut output = json!({
+            "status": status,
+            "headers": response_headers,
+            "body": text,
+        });
+        if more {
+            output["truncated"] = json!(true);
+            if let Some(len) = content_length {
+                output["body_bytes"] = json!(len);
+            }
+        }
+        let content = serde_json::to_string_pretty(&output).unwrap_or_default();
+        if status >= 400 {
+            ToolResult::error(content)
+        } else {
+            ToolResult::success(content)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn test_is_private_address() {
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+            "198.18.0.1",
+            "198.19.255.254",
+            "64:ff9b::7f00:1",
+            "64:ff9b::a9fe:a9fe",
+            "2002:c0a8:101::1",
+        ] {
+            assert!(is_private_address(ip.parse().unwrap()), "{ip}");
+        }
+        for ip in [
+            "93.184.216.34",
+            "8.8.8.8",
+            "2606:4700::1111",
+            "198.20.0.1",
+            "64:ff9b::808:808",
+            "2002:808:808::1",
+        ] {
+            assert!(!is_private_address(ip.parse().unwrap()), "{ip}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_http_request_denies_all_hosts_by_default() {
+        let tool = HttpRequestTool::new(5, Vec::new(), false);
+        let result = tool
+            .execute(json!({"url": "https://api.example.com/v1"}))
+            .await;
+        assert!(result.is_error);
+        assert!(result.content.contains("http_tool_allowed_hosts"));
+
+        let tool = HttpRequestTool::new(5, vec!["example.com".into()], false);
+        let result = tool.execute(json!({"url": "https://other.org/"})).await;
+        assert!(result.is_error);
+        assert!(result.content.contains("not in http_tool_allowed_hosts"));
+    }
+
+    /// Accept one connection, answer it with `response` and return the
+    /// request text.
+    async fn serve_once(response: String) -> (u16, tokio::task::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0u8; 4096];
+            let n = stream.read(&mut buf).await.unwrap();
+            stream.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8_lossy(&buf[..n]).to_string()
+        });
+        (port, server)
+    }
+
+    #[tokio::test]
+    async fn test_http_request_blocks_loopback_unless_private_allowed() {
+        let body = r#"{"ok":true}"#;
+        let (port, server) = serve_once(format!(
+            "HTTP/1.1 201 Created\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        ))
+        .await;
+        let url = format!("http://127.0.0.1:{port}/items");
+
+        let blocked = HttpRequestTool::new(5, vec!["127.0.0.1".into()], false);
+        let result = blocked.execute(json!({"url": url})).await;
+        assert!(result.is_error);
+        assert!(result.content.contains("private or loopback"));
+
+        let allowed = HttpRequestTool::new(5, vec!["127.0.0.1".into()], true);
+        let result = allowed
+            .execute(json!({
+                "method": "post",
+                "url": url,
+                "headers": {"X-Api-Key": "secret"},
+                "body": {"name": "demo"}
+            }))
+            .await;
+        assert!(!result.is_error, "{}", result.content);
+        let output: serde_json::Value = serde_json::from_str(&result.content).unwrap();
+        assert_eq!(output["status"], 201);
+        assert_eq!(output["headers"]["content-type"], "application/json");
+        assert_eq!(output["body"], r#"{"ok":true}"#);
+
+        let request = server.await.unwrap();
+        assert!(request.starts_with("POST /items"));
+        assert!(request.to_ascii_lowercase().contains("x-api-key: secret"));
+        assert!(request.contains(r#"{"name":"demo"}"#));
+    }
+
+    #[tokio::test]
+    async fn test_http_request_drops_credentials_on_cross_origin_redirect() {
+        let (target_port, target) = serve_once(
+            "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok".into(),
+        )
+        .await;
+        let (origin_port, origin) = serve_once(format!(
+            "HTTP/1.1 302 Found\r\nLocation: http://127.0.0.1:{target_port}/next\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+        ))
+        .await;
+
+        let tool = HttpRequestTool::new(5, vec!["127.0.0.1".into()], true);
+        let result = tool
+            .execute(json!({
+                "url": format!("http://127.0.0.1:{origin_port}/start"),
+                "headers": {
+                    "Authorization": "Bearer secret",
+                    "X-Api-Key": "secret",
+                    "Cookie": "session=secret",
+                    "X-Trace": "kept"
+                }
+            }))
+            .await;
+        assert!(!result.is_error, "{}", result.content);
+
+        let first = origin.await.unwrap().to_ascii_lowercase();
+        assert!(first.contains("authorization: bearer secret"));
+        let second = target.await.unwrap().to_ascii_lowercase();
+        assert!(second.starts_with("get /next"));
+        assert!(second.contains("x-trace: kept"));
+        assert!(!second.contains("secret"), "{second}");
+    }
+
+    #[tokio::test]
+    async fn test_http_request_caps_response_body() {
+        let body = "é".repeat(MAX_BODY_BYTES);
+        let (port, server) = serve_once(format!(
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        ))
+        .await;
+
+        let tool = HttpRequestTool::new(5, vec!["127.0.0.1".into()], true);
+        let result = tool
+            .execute(json!({"url": format!("http://127.0.0.1:{port}/big")}))
+            .await;
+        assert!(!result.is_error, "{}", result.content);
+        let output: serde_json::Value = serde_json::from_str(&result.content).unwrap();
+        assert_eq!(output["truncated"], true);
+        assert_eq!(output["body_bytes"], body.len());
+        let text = output["body"].as_str().unwrap();
+        assert_eq!(text.len(), MAX_BODY_BYTES);
+        assert!(text.chars().all(|c| c == 'é'));
+        let _ = server.await;
+    }
+}
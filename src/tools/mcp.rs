@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 
-use crate::mcp::{McpServer, McpToolInfo};
+use crate::mcp::{McpManager, McpServer, McpToolInfo};
 use microclaw_core::llm_types::ToolDefinition;
 
 use super::{Tool, ToolResult};
@@ -51,6 +51,15 @@ impl McpTool {
     }
 }
 
+/// One tool per tool advertised by the manager's connected servers.
+pub fn mcp_tools(manager: &McpManager) -> Vec<Arc<dyn Tool>> {
+    manager
+        .all_tools()
+        .into_iter()
+        .map(|(server, tool_info)| Arc::new(McpTool::new(server, tool_info)) as Arc<dyn Tool>)
+        .collect()
+}
+
 #[async_trait]
 impl Tool for McpTool {
     fn name(&self) -> &str {
@@ -4,6 +4,8 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use tracing::info;
 
+use crate::config::Config;
+use crate::hot_reload::Live;
 use crate::memory_backend::MemoryBackend;
 use microclaw_core::llm_types::ToolDefinition;
 use microclaw_storage::db::Database;
@@ -56,13 +58,19 @@ fn memory_channel_for_chat(
     }
 }
 
-fn latest_sender_for_chat(db: &Database, chat_id: i64) -> Option<String> {
+/// The most recent human sender in the chat, with the channel's sender-name
+/// rules applied so bridged spellings share one person section.
+fn latest_sender_for_chat(
+    db: &Database,
+    chat_id: i64,
+    normalize: impl Fn(&str) -> String,
+) -> Option<String> {
     db.get_recent_messages(chat_id, 20)
         .ok()?
         .into_iter()
         .rev()
         .find(|m| !m.is_from_bot && !m.content.trim_start().starts_with('/'))
-        .map(|m| m.sender_name.trim().to_string())
+        .map(|m| normalize(&m.sender_name).trim().to_string())
         .filter(|s| !s.is_empty())
 }
 
@@ -195,6 +203,7 @@ pub struct WriteMemoryTool {
     groups_dir: PathBuf,
     db: Arc<Database>,
     memory_backend: Arc<MemoryBackend>,
+    config: Option<Arc<Live<Config>>>,
 }
 
 impl WriteMemoryTool {
@@ -203,8 +212,15 @@ impl WriteMemoryTool {
             groups_dir: PathBuf::from(data_dir).join("groups"),
             db,
             memory_backend,
+            config: None,
         }
     }
+
+    /// Apply the live config's sender-name rules when keying person sections.
+    pub fn with_config(mut self, config: Arc<Live<Config>>) -> Self {
+        self.config = Some(config);
+        self
+    }
 }
 
 #[async_trait]
@@ -248,7 +264,7 @@ impl Tool for WriteMemoryTool {
             None => return ToolResult::error("Missing 'content' parameter".into()),
         };
 
-        let (path, memory_chat_id) = match scope {
+        let (path, memory_chat_id, memory_channel) = match scope {
             "global" => {
                 if let Some(auth) = auth_context_from_input(&input) {
                     if !auth.is_control_chat() {
@@ -258,11 +274,11 @@ impl Tool for WriteMemoryTool {
                         ));
                     }
                 }
-                (self.groups_dir.join("AGENTS.md"), None)
+                (self.groups_dir.join("AGENTS.md"), None, None)
             }
             "bot" => {
                 let channel = memory_channel_from_auth(&input);
-                (self.groups_dir.join(channel).join("AGENTS.md"), None)
+                (self.groups_dir.join(channel).join("AGENTS.md"), None, None)
             }
             "chat" => {
                 let chat_id = match chat_id_from_input_or_auth(&input) {
@@ -278,10 +294,11 @@ impl Tool for WriteMemoryTool {
                 };
                 (
                     self.groups_dir
-                        .join(channel)
+                        .join(&channel)
                         .join(chat_id.to_string())
                         .join("AGENTS.md"),
                     Some(chat_id),
+                    Some(channel),
                 )
             }
             _ => return ToolResult::error("scope must be 'global', 'bot', or 'chat'".into()),
@@ -297,7 +314,12 @@ impl Tool for WriteMemoryTool {
 
         let write_content = if scope == "chat" {
             let chat_id = memory_chat_id.unwrap_or_default();
-            let sender = latest_sender_for_chat(&self.db, chat_id);
+            let channel = memory_channel.unwrap_or_default();
+            let config = self.config.as_ref().map(|c| c.load());
+            let sender = latest_sender_for_chat(&self.db, chat_id, |name| match &config {
+                Some(config) => config.normalize_sender_name(&channel, name),
+                None => name.to_string(),
+            });
             let existing = std::fs::read_to_string(&path).unwrap_or_default();
             if let Some(sender) = sender {
                 upsert_chat_person_memory(&existing, &sender, content)
@@ -511,6 +533,83 @@ mod tests {
         let _ = std::fs::remove_dir_all(&dir);
     }
 
+    #[tokio::test]
+    async fn test_write_memory_chat_normalizes_bridged_sender_names() {
+        let dir = test_dir();
+        let db = test_db(&dir);
+        db.resolve_or_create_chat_id("matrix", "42", Some("matrix-42"), "group")
+            .unwrap();
+        let mut config = Config::test_defaults();
+        config.channels.insert(
+            "matrix".into(),
+            serde_yaml::from_str(
+                r#"
+sender_name_rules:
+  - pattern: "^\\[irc\\] "
+  - pattern: "_+$"
+"#,
+            )
+            .unwrap(),
+        );
+        let tool =
+            WriteMemoryTool::new(dir.to_str().unwrap(), db.clone(), test_backend(db.clone()))
+                .with_config(Arc::new(Live::from(config)));
+
+        store_user_message(&db, 42, "[irc] bob", "remember profile");
+        let r1 = tool
+            .execute(json!({"scope": "chat", "chat_id": 42, "content": "likes tea"}))
+            .await;
+        assert!(!r1.is_error, "{}", r1.content);
+        store_user_message(&db, 42, "bob__", "update profile");
+        let r2 = tool
+            .execute(json!({"scope": "chat", "chat_id": 42, "content": "likes coffee"}))
+            .await;
+        assert!(!r2.is_error, "{}", r2.content);
+
+        let content = std::fs::read_to_string(
+            dir.join("groups")
+                .join("matrix")
+                .join("42")
+                .join("AGENTS.md"),
+        )
+        .unwrap();
+        assert_eq!(content.matches("## Person:").count(), 1);
+        assert!(content.contains("## Person: bob\nlikes coffee"));
+        assert!(!content.contains("likes tea"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_write_memory_stores_an_allowed_category() {
+        let dir = test_dir();
+        let db = test_db(&dir);
+        let mut config = Config::test_defaults();
+        config.memory_categories = vec![
+            crate::config::MemoryCategory {
+                name: "PEOPLE".into(),
+                description: String::new(),
+            },
+            crate::config::MemoryCategory {
+                name: "NOTES".into(),
+                description: String::new(),
+            },
+        ];
+        config.memory_default_category = "NOTES".into();
+        let backend = Arc::new(MemoryBackend::local_only(db.clone()).with_categories(&config));
+        let tool = WriteMemoryTool::new(dir.to_str().unwrap(), db.clone(), backend);
+
+        let result = tool
+            .execute(json!({"scope": "global", "content": "user prefers Rust"}))
+            .await;
+        assert!(!result.is_error, "{}", result.content);
+        let mems = db.get_all_memories_for_chat(None).unwrap();
+        assert_eq!(mems.len(), 1);
+        assert_eq!(mems[0].category, "NOTES");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[tokio::test]
     async fn test_write_memory_missing_scope() {
         let dir = test_dir();
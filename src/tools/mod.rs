@@ -1,13 +1,20 @@
 pub mod activate_skill;
 pub mod bash;
 pub mod browser;
+pub mod check_skill_updates;
+pub mod context_info;
 pub mod edit_file;
+pub mod edit_message;
 pub mod export_chat;
+pub mod forget;
+pub mod generate_image;
 pub mod glob;
 pub mod grep;
+pub mod http_request;
 pub mod mcp;
 pub mod memory;
 pub mod read_file;
+pub mod save_attachment;
 pub mod schedule;
 pub mod send_message;
 pub mod structured_memory;
@@ -19,28 +26,32 @@ pub mod web_fetch;
 pub mod web_search;
 pub mod write_file;
 
-use std::sync::{Arc, OnceLock};
+use std::sync::{Arc, OnceLock, RwLock};
 use std::{path::PathBuf, time::Instant};
 
 use crate::config::Config;
+use crate::hot_reload::Live;
 use crate::memory_backend::MemoryBackend;
 use microclaw_channels::channel_adapter::ChannelRegistry;
 use microclaw_core::llm_types::ToolDefinition;
 use microclaw_storage::db::Database;
 pub use microclaw_tools::runtime::{
     auth_context_from_input, authorize_chat_access, resolve_tool_path, resolve_tool_working_dir,
-    schema_object, tool_execution_policy, tool_risk, validate_execution_policy, Tool,
-    ToolAuthContext, ToolResult, ToolRisk,
+    schema_object, tool_execution_policy, tool_risk, validate_execution_policy, InboundAttachment,
+    Tool, ToolAuthContext, ToolResult, ToolRisk,
 };
 use microclaw_tools::runtime::{inject_auth_context, require_high_risk_approval};
 use microclaw_tools::sandbox::{ExtraMount, SandboxMode, SandboxRouter};
 
 pub struct ToolRegistry {
-    config: Config,
+    /// The live config, so hot-reloaded settings apply to every call.
+    config: Arc<Live<Config>>,
     tools: Vec<Box<dyn Tool>>,
     sandbox_mode: SandboxMode,
     sandbox_runtime_available: bool,
     cached_static_definitions: OnceLock<Vec<ToolDefinition>>,
+    /// Tools backed by MCP servers; replaced wholesale when MCP is reloaded.
+    mcp_tools: RwLock<Vec<Arc<dyn Tool>>>,
 }
 
 impl ToolRegistry {
@@ -74,11 +85,13 @@ impl ToolRegistry {
     }
 
     pub fn new(
-        config: &Config,
+        live_config: Arc<Live<Config>>,
         channel_registry: Arc<ChannelRegistry>,
         db: Arc<Database>,
         memory_backend: Arc<MemoryBackend>,
     ) -> Self {
+        let snapshot = live_config.load();
+        let config: &Config = &snapshot;
         let working_dir = PathBuf::from(&config.working_dir);
         if let Err(e) = std::fs::create_dir_all(&working_dir) {
             tracing::warn!(
@@ -105,7 +118,10 @@ impl ToolRegistry {
                     config.working_dir_isolation,
                 )
                 .with_default_timeout_secs(config.tool_timeout_secs("bash", 120))
-                .with_sandbox_router(sandbox_router.clone()),
+                .with_sandbox_router(sandbox_router.clone())
+                .with_restrict_to_working_dir(config.bash_restrict_to_working_dir)
+                .with_denied_commands(config.bash_denied_commands.clone())
+                .with_max_output_bytes(config.bash_max_output_bytes),
             ),
             Box::new(
                 browser::BrowserTool::new(&config.data_dir)
@@ -123,6 +139,7 @@ impl ToolRegistry {
                 &config.working_dir,
                 config.working_dir_isolation,
             )),
+            Box::new(save_attachment::SaveAttachmentTool::new(config)),
             Box::new(glob::GlobTool::new_with_isolation(
                 &config.working_dir,
                 config.working_dir_isolation,
@@ -132,22 +149,33 @@ impl ToolRegistry {
                 config.working_dir_isolation,
             )),
             Box::new(memory::ReadMemoryTool::new(&config.data_dir, db.clone())),
-            Box::new(memory::WriteMemoryTool::new(
-                &config.data_dir,
-                db.clone(),
-                memory_backend.clone(),
-            )),
-            Box::new(web_fetch::WebFetchTool::new(
-                config.tool_timeout_secs("web_fetch", 15),
-                config.web_fetch_validation,
-                config.web_fetch_url_validation.clone(),
-            )),
+            Box::new(
+                memory::WriteMemoryTool::new(&config.data_dir, db.clone(), memory_backend.clone())
+                    .with_config(live_config.clone()),
+            ),
+            Box::new(
+                web_fetch::WebFetchTool::new(
+                    config.tool_timeout_secs("web_fetch", 15),
+                    config.web_fetch_validation,
+                    config.web_fetch_url_validation.clone(),
+                )
+                .with_retry(config.web_fetch_retry),
+            ),
             Box::new(web_search::WebSearchTool::new(
                 config.tool_timeout_secs("web_search", 15),
             )),
+            Box::new(http_request::HttpRequestTool::new(
+                config.tool_timeout_secs("http_request", 15),
+                config.http_tool_allowed_hosts.clone(),
+                config.http_tool_allow_private_networks,
+            )),
             Box::new(time_math::GetCurrentTimeTool::new(config.timezone.clone())),
             Box::new(time_math::CompareTimeTool::new(config.timezone.clone())),
             Box::new(time_math::CalculateTool::new()),
+            Box::new(context_info::ContextInfoTool::new(
+                live_config.clone(),
+                db.clone(),
+            )),
             Box::new(send_message::SendMessageTool::new(
                 channel_registry.clone(),
                 db.clone(),
@@ -158,14 +186,36 @@ impl ToolRegistry {
                 },
                 config.bot_username_overrides(),
             )),
-            Box::new(schedule::ScheduleTaskTool::new(
+            Box::new(edit_message::EditMessageTool::new(
                 channel_registry.clone(),
                 db.clone(),
-                config.timezone.clone(),
+                if config.bot_username.trim().is_empty() {
+                    "bot".to_string()
+                } else {
+                    config.bot_username.clone()
+                },
+                config.bot_username_overrides(),
             )),
+            Box::new(
+                schedule::ScheduleTaskTool::new(
+                    channel_registry.clone(),
+                    db.clone(),
+                    config.timezone.clone(),
+                )
+                .with_limits(schedule::ScheduleLimits::from_config(config)),
+            ),
+            Box::new(
+                schedule::RemindMeTool::new(
+                    channel_registry.clone(),
+                    db.clone(),
+                    config.timezone.clone(),
+                )
+                .with_limits(schedule::ScheduleLimits::from_config(config)),
+            ),
             Box::new(schedule::ListTasksTool::new(
                 channel_registry.clone(),
                 db.clone(),
+                config.timezone.clone(),
             )),
             Box::new(schedule::PauseTaskTool::new(
                 channel_registry.clone(),
@@ -195,12 +245,18 @@ impl ToolRegistry {
                 db.clone(),
                 &config.data_dir,
             )),
-            Box::new(sub_agent::SubAgentTool::new(config, db.clone())),
+            Box::new(sub_agent::SubAgentTool::new(
+                live_config.clone(),
+                db.clone(),
+            )),
             Box::new(activate_skill::ActivateSkillTool::new_with_runtime(
                 &skills_data_dir,
                 &config.data_dir,
             )),
             Box::new(sync_skills::SyncSkillsTool::new(&skills_data_dir)),
+            Box::new(check_skill_updates::CheckSkillUpdatesTool::new(
+                &skills_data_dir,
+            )),
             Box::new(todo::TodoReadTool::new(&config.data_dir)),
             Box::new(todo::TodoWriteTool::new(&config.data_dir)),
             Box::new(structured_memory::StructuredMemorySearchTool::new(
@@ -211,12 +267,24 @@ impl ToolRegistry {
                 db.clone(),
                 memory_backend.clone(),
             )),
-            Box::new(structured_memory::StructuredMemoryUpdateTool::new(
-                db.clone(),
-                memory_backend.clone(),
-            )),
+            Box::new(forget::ForgetTool::new(memory_backend.clone())),
+            Box::new(
+                structured_memory::StructuredMemoryUpdateTool::new(
+                    db.clone(),
+                    memory_backend.clone(),
+                )
+                .with_categories(config),
+            ),
         ];
 
+        if let Some(tool) = generate_image::GenerateImageTool::from_config(
+            config,
+            channel_registry.clone(),
+            db.clone(),
+        ) {
+            tools.push(Box::new(tool));
+        }
+
         // Add ClawHub tools if enabled
         if config.clawhub.agent_tools_enabled {
             tools.push(Box::new(crate::clawhub::tools::ClawHubSearchTool::new(
@@ -228,16 +296,19 @@ impl ToolRegistry {
         }
 
         ToolRegistry {
-            config: config.clone(),
+            config: live_config,
             tools,
             sandbox_mode: sandbox_router.mode(),
             sandbox_runtime_available: sandbox_router.runtime_available(),
             cached_static_definitions: OnceLock::new(),
+            mcp_tools: RwLock::new(Vec::new()),
         }
     }
 
     /// Create a restricted tool registry for sub-agents (no side-effect or recursive tools).
-    pub fn new_sub_agent(config: &Config, db: Arc<Database>) -> Self {
+    pub fn new_sub_agent(live_config: Arc<Live<Config>>, db: Arc<Database>) -> Self {
+        let snapshot = live_config.load();
+        let config: &Config = &snapshot;
         let working_dir = PathBuf::from(&config.working_dir);
         if let Err(e) = std::fs::create_dir_all(&working_dir) {
             tracing::warn!(
@@ -260,7 +331,10 @@ impl ToolRegistry {
                     config.working_dir_isolation,
                 )
                 .with_default_timeout_secs(config.tool_timeout_secs("bash", 120))
-                .with_sandbox_router(sandbox_router.clone()),
+                .with_sandbox_router(sandbox_router.clone())
+                .with_restrict_to_working_dir(config.bash_restrict_to_working_dir)
+                .with_denied_commands(config.bash_denied_commands.clone())
+                .with_max_output_bytes(config.bash_max_output_bytes),
             ),
             Box::new(
                 browser::BrowserTool::new(&config.data_dir)
@@ -287,11 +361,14 @@ impl ToolRegistry {
                 config.working_dir_isolation,
             )),
             Box::new(memory::ReadMemoryTool::new(&config.data_dir, db.clone())),
-            Box::new(web_fetch::WebFetchTool::new(
-                config.tool_timeout_secs("web_fetch", 15),
-                config.web_fetch_validation,
-                config.web_fetch_url_validation.clone(),
-            )),
+            Box::new(
+                web_fetch::WebFetchTool::new(
+                    config.tool_timeout_secs("web_fetch", 15),
+                    config.web_fetch_validation,
+                    config.web_fetch_url_validation.clone(),
+                )
+                .with_retry(config.web_fetch_retry),
+            ),
             Box::new(web_search::WebSearchTool::new(
                 config.tool_timeout_secs("web_search", 15),
             )),
@@ -308,11 +385,12 @@ impl ToolRegistry {
             )),
         ];
         ToolRegistry {
-            config: config.clone(),
+            config: live_config,
             tools,
             sandbox_mode: sandbox_router.mode(),
             sandbox_runtime_available: sandbox_router.runtime_available(),
             cached_static_definitions: OnceLock::new(),
+            mcp_tools: RwLock::new(Vec::new()),
         }
     }
 
@@ -337,15 +415,46 @@ impl ToolRegistry {
         self.tools.push(tool);
     }
 
+    /// Replace the MCP-backed tools, e.g. after `/mcp reload`.
+    pub fn set_mcp_tools(&self, tools: Vec<Arc<dyn Tool>>) {
+        *self.mcp_tools.write().unwrap_or_else(|e| e.into_inner()) = tools;
+    }
+
+    fn mcp_tool(&self, name: &str) -> Option<Arc<dyn Tool>> {
+        self.mcp_tools
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .find(|t| t.name() == name)
+            .cloned()
+    }
+
     pub fn definitions(&self) -> Vec<ToolDefinition> {
         let static_defs = self
             .cached_static_definitions
             .get_or_init(|| self.tools.iter().map(|t| t.definition()).collect())
             .clone();
+        let config = self.config.load();
         let mut out = static_defs;
+        if config.confirm_destructive_tools {
+            out.iter_mut()
+                .filter(|d| CONFIRMABLE_TOOLS.contains(&d.name.as_str()))
+                .for_each(add_confirmed_property);
+        }
         let mut existing: std::collections::HashSet<String> =
             out.iter().map(|d| d.name.to_ascii_lowercase()).collect();
-        for plugin_def in crate::plugins::dynamic_plugin_tool_definitions(&self.config) {
+        for tool in self
+            .mcp_tools
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+        {
+            let def = tool.definition();
+            if existing.insert(def.name.to_ascii_lowercase()) {
+                out.push(def);
+            }
+        }
+        for plugin_def in crate::plugins::dynamic_plugin_tool_definitions(&config) {
             let normalized = plugin_def.name.to_ascii_lowercase();
             if existing.insert(normalized) {
                 out.push(plugin_def);
@@ -355,24 +464,69 @@ impl ToolRegistry {
     }
 
     pub async fn execute(&self, name: &str, input: serde_json::Value) -> ToolResult {
-        for tool in &self.tools {
-            if tool.name() == name {
-                let started = Instant::now();
-                let mut result = tool.execute(input).await;
-                result.duration_ms = Some(started.elapsed().as_millis());
-                result.bytes = result.content.len();
-                if result.is_error && result.error_type.is_none() {
-                    result.error_type = Some("tool_error".to_string());
-                }
-                if result.status_code.is_none() {
-                    result.status_code = Some(if result.is_error { 1 } else { 0 });
-                }
-                return result;
-            }
+        if let Some(tool) = self.tools.iter().find(|t| t.name() == name) {
+            return Self::run_tool(tool.as_ref(), input).await;
+        }
+        if let Some(tool) = self.mcp_tool(name) {
+            return Self::run_tool(tool.as_ref(), input).await;
         }
         ToolResult::error(format!("Unknown tool: {name}")).with_error_type("unknown_tool")
     }
 
+    async fn run_tool(tool: &dyn Tool, input: serde_json::Value) -> ToolResult {
+        let started = Instant::now();
+        let mut result = tool.execute(input).await;
+        result.duration_ms = Some(started.elapsed().as_millis());
+        result.bytes = result.content.len();
+        if result.is_error && result.error_type.is_none() {
+            result.error_type = Some("tool_error".to_string());
+        }
+        if result.status_code.is_none() {
+            result.status_code = Some(if result.is_error { 1 } else { 0 });
+        }
+        result
+    }
+
+    /// With `confirm_destructive_tools`, hold back a destructive call outside
+    /// control chats until the user replies with the token from the returned
+    /// result and the agent repeats the call with `confirmed: true`.
+    async fn require_destructive_confirmation(
+        &self,
+        name: &str,
+        auth: &ToolAuthContext,
+        input: &serde_json::Value,
+    ) -> Option<ToolResult> {
+        if !self.config.load().confirm_destructive_tools || auth.is_control_chat() {
+            return None;
+        }
+        let action = destructive_action(name, input)?;
+        let channel = auth.caller_channel.as_str();
+        let confirmed = input
+            .get(crate::pending_actions::CONFIRMED_KEY)
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        if confirmed
+            && crate::pending_actions::take_confirmed(channel, auth.caller_chat_id, name, input)
+                .await
+        {
+            tracing::info!(
+                tool = name,
+                channel,
+                chat_id = auth.caller_chat_id,
+                "Executing destructive tool after user confirmation"
+            );
+            return None;
+        }
+        let token =
+            crate::pending_actions::request(channel, auth.caller_chat_id, name, input).await;
+        Some(
+            ToolResult::error(format!(
+                "Confirmation required: this would {action}. Nothing was done. Tell the user what will happen and ask them to reply with `{token}` to confirm. After they do, call `{name}` again with the same arguments plus `\"confirmed\": true`."
+            ))
+            .with_error_type("confirmation_required"),
+        )
+    }
+
     pub async fn execute_with_auth(
         &self,
         name: &str,
@@ -387,6 +541,12 @@ impl ToolRegistry {
         if let Some(blocked) = require_high_risk_approval(name, auth, &input) {
             return blocked;
         }
+        if let Some(pending) = self
+            .require_destructive_confirmation(name, auth, &input)
+            .await
+        {
+            return pending;
+        }
 
         tracing::debug!(
             tool = name,
@@ -398,18 +558,119 @@ impl ToolRegistry {
         );
         let input = Self::inject_default_chat_id_if_missing(name, input, auth);
         let input = inject_auth_context(input, auth);
-        let result = self.execute(name, input.clone()).await;
-        if result.error_type.as_deref() == Some("unknown_tool") {
-            if let Some(dynamic) =
-                crate::plugins::execute_dynamic_plugin_tool(&self.config, name, input).await
-            {
-                return dynamic;
+        let input = match name {
+            "save_attachment" => save_attachment::inject_inbound_attachments(input, auth),
+            "context_info" => context_info::inject_available_tools(input, &self.definitions()),
+            _ => input,
+        };
+        let requested_timeout_secs = input.get("timeout_secs").and_then(|v| v.as_u64());
+        let config = self.config.load();
+        let run = async {
+            let result = self.execute(name, input.clone()).await;
+            if result.error_type.as_deref() == Some("unknown_tool") {
+                if let Some(dynamic) =
+                    crate::plugins::execute_dynamic_plugin_tool(&config, name, input).await
+                {
+                    return dynamic;
+                }
             }
-        }
+            result
+        };
+        let mut result = match config.tool_hard_timeout(name, requested_timeout_secs) {
+            None => run.await,
+            Some(limit) => match tokio::time::timeout(limit, run).await {
+                Ok(result) => result,
+                Err(_) => {
+                    tracing::warn!(
+                        tool = name,
+                        limit_secs = limit.as_secs(),
+                        "tool call timed out"
+                    );
+                    tool_timeout_result(name, limit)
+                }
+            },
+        };
+        // Results are stored in the session, archived and shown to users.
+        result.content = crate::redact::redact_secrets(&result.content, &config);
         result
     }
 }
 
+fn add_confirmed_property(def: &mut ToolDefinition) {
+    if let Some(props) = def
+        .input_schema
+        .get_mut("properties")
+        .and_then(|p| p.as_object_mut())
+    {
+        props.insert(
+            crate::pending_actions::CONFIRMED_KEY.to_string(),
+            serde_json::json!({
+                "type": "boolean",
+                "description": "Set to true only when repeating a call the user confirmed with its token"
+            }),
+        );
+    }
+}
+
+/// Tools that may ask for confirmation under `confirm_destructive_tools`.
+const CONFIRMABLE_TOOLS: &[&str] = &["bash", "write_file", "edit_file", "forget"];
+
+/// What a call would destroy or overwrite, if it needs confirmation under
+/// `confirm_destructive_tools`.
+fn destructive_action(name: &str, input: &serde_json::Value) -> Option<String> {
+    let field = |key: &str| input.get(key).and_then(|v| v.as_str()).unwrap_or_default();
+    match name {
+        "bash" if bash::is_destructive_command(field("command")) => {
+            Some(format!("run `{}`", field("command")))
+        }
+        "write_file" => Some(format!("write to `{}`", field("path"))),
+        "edit_file" => Some(format!("edit `{}`", field("path"))),
+        "forget" if input.get("confirm").and_then(|v| v.as_bool()) == Some(true) => {
+            let ids = input.get("ids").map(|v| v.to_string()).unwrap_or_default();
+            Some(format!(
+                "archive memories {ids} matching \"{}\"",
+                field("query")
+            ))
+        }
+        _ => None,
+    }
+}
+
+fn tool_timeout_result(name: &str, limit: std::time::Duration) -> ToolResult {
+    let mut result = ToolResult::error(format!(
+        "Tool '{name}' exceeded its time limit of {}s and was stopped. Do not retry it unchanged; try a smaller request or another approach.",
+        limit.as_secs()
+    ))
+    .with_error_type("timeout");
+    result.duration_ms = Some(limit.as_millis());
+    result.bytes = result.content.len();
+    result.status_code = Some(1);
+    result
+}
+
+/// Classify an HTTP request failure message as `timeout` or `network` so the
+/// agent loop can tell transient failures from permanent ones.
+pub fn transient_error_type(message: &str) -> Option<&'static str> {
+    let lower = message.to_ascii_lowercase();
+    if lower.contains("timed out") || lower.contains("timeout") {
+        return Some("timeout");
+    }
+    const NETWORK_MARKERS: &[&str] = &[
+        "error sending request",
+        "connection refused",
+        "connection reset",
+        "connection closed",
+        "dns error",
+        "http 502",
+        "http 503",
+        "http 504",
+    ];
+    NETWORK_MARKERS
+        .iter()
+        .any(|marker| lower.contains(marker))
+        .then_some("network")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -417,6 +678,21 @@ mod tests {
     use async_trait::async_trait;
     use serde_json::json;
 
+    #[test]
+    fn test_transient_error_type_classifies_request_failures() {
+        assert_eq!(
+            transient_error_type("error sending request for url (https://x.test/)"),
+            Some("network")
+        );
+        assert_eq!(
+            transient_error_type("HTTP 503 Service Unavailable"),
+            Some("network")
+        );
+        assert_eq!(transient_error_type("operation timed out"), Some("timeout"));
+        assert_eq!(transient_error_type("HTTP 404 Not Found"), None);
+        assert_eq!(transient_error_type("invalid URL: relative URL"), None);
+    }
+
     #[test]
     fn test_tool_result_success() {
         let r = ToolResult::success("ok".into());
@@ -565,6 +841,57 @@ mod tests {
         }
     }
 
+    struct HangingTool;
+
+    #[async_trait]
+    impl Tool for HangingTool {
+        fn name(&self) -> &str {
+            "hanging"
+        }
+
+        fn definition(&self) -> ToolDefinition {
+            ToolDefinition {
+                name: "hanging".into(),
+                description: "never returns".into(),
+                input_schema: schema_object(json!({}), &[]),
+            }
+        }
+
+        async fn execute(&self, _input: serde_json::Value) -> ToolResult {
+            std::future::pending().await
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_hung_tool_times_out_above_its_own_timeout() {
+        let mut config = crate::config::Config::test_defaults();
+        config.tool_timeout_overrides.insert("hanging".into(), 900);
+        let registry = ToolRegistry {
+            config: Arc::new(Live::from(config)),
+            sandbox_mode: SandboxMode::Off,
+            sandbox_runtime_available: false,
+            cached_static_definitions: OnceLock::new(),
+            mcp_tools: RwLock::new(Vec::new()),
+            tools: vec![Box::new(HangingTool)],
+        };
+        let auth = ToolAuthContext {
+            caller_channel: "telegram".into(),
+            caller_chat_id: 1,
+            control_chat_ids: vec![],
+            env_files: vec![],
+            inbound_attachments: Vec::new(),
+            sub_agent_depth: 0,
+        };
+
+        let result = registry
+            .execute_with_auth("hanging", json!({}), &auth)
+            .await;
+        assert!(result.is_error);
+        assert_eq!(result.error_type.as_deref(), Some("timeout"));
+        assert_eq!(result.duration_ms, Some(930_000));
+        assert!(result.content.contains("exceeded its time limit of 930s"));
+    }
+
     #[test]
     fn test_tool_risk_levels() {
         assert_eq!(tool_risk("bash"), ToolRisk::High);
@@ -577,10 +904,11 @@ mod tests {
     #[tokio::test]
     async fn test_high_risk_tool_requires_explicit_approval_on_web() {
         let registry = ToolRegistry {
-            config: crate::config::Config::test_defaults(),
+            config: Arc::new(Live::from(crate::config::Config::test_defaults())),
             sandbox_mode: SandboxMode::Off,
             sandbox_runtime_available: false,
             cached_static_definitions: OnceLock::new(),
+            mcp_tools: RwLock::new(Vec::new()),
             tools: vec![Box::new(DummyTool {
                 tool_name: "bash".into(),
             })],
@@ -590,6 +918,8 @@ mod tests {
             caller_chat_id: 1,
             control_chat_ids: vec![],
             env_files: vec![],
+            inbound_attachments: Vec::new(),
+            sub_agent_depth: 0,
         };
 
         let first = registry.execute_with_auth("bash", json!({}), &auth).await;
@@ -614,10 +944,11 @@ mod tests {
     #[tokio::test]
     async fn test_high_risk_tool_requires_explicit_approval_on_control_chat() {
         let registry = ToolRegistry {
-            config: crate::config::Config::test_defaults(),
+            config: Arc::new(Live::from(crate::config::Config::test_defaults())),
             sandbox_mode: SandboxMode::Off,
             sandbox_runtime_available: false,
             cached_static_definitions: OnceLock::new(),
+            mcp_tools: RwLock::new(Vec::new()),
             tools: vec![Box::new(DummyTool {
                 tool_name: "bash".into(),
             })],
@@ -627,6 +958,8 @@ mod tests {
             caller_chat_id: 123,
             control_chat_ids: vec![123],
             env_files: vec![],
+            inbound_attachments: Vec::new(),
+            sub_agent_depth: 0,
         };
 
         let first = registry.execute_with_auth("bash", json!({}), &auth).await;
@@ -644,13 +977,146 @@ mod tests {
         assert_eq!(approved.content, "ok");
     }
 
+    #[tokio::test]
+    async fn test_destructive_tool_confirm_round_trip() {
+        let mut config = crate::config::Config::test_defaults();
+        config.confirm_destructive_tools = true;
+        let registry = ToolRegistry {
+            config: Arc::new(Live::from(config)),
+            sandbox_mode: SandboxMode::Off,
+            sandbox_runtime_available: false,
+            cached_static_definitions: OnceLock::new(),
+            mcp_tools: RwLock::new(Vec::new()),
+            tools: vec![
+                Box::new(DummyTool {
+                    tool_name: "write_file".into(),
+                }),
+                Box::new(DummyTool {
+                    tool_name: "edit_file".into(),
+                }),
+            ],
+        };
+        let auth = ToolAuthContext {
+            caller_channel: "telegram".into(),
+            caller_chat_id: 4242,
+            control_chat_ids: vec![1],
+            env_files: vec![],
+            inbound_attachments: Vec::new(),
+            sub_agent_depth: 0,
+        };
+        let input = json!({"path": "notes.txt", "content": "hi"});
+
+        let held = registry
+            .execute_with_auth("write_file", input.clone(), &auth)
+            .await;
+        assert_eq!(held.error_type.as_deref(), Some("confirmation_required"));
+        assert!(held.content.contains("write to `notes.txt`"));
+        let token = held
+            .content
+            .split('`')
+            .find(|part| part.starts_with("CONFIRM-"))
+            .unwrap()
+            .to_string();
+
+        // The agent cannot skip the user by setting the flag itself.
+        let mut confirmed = input.clone();
+        confirmed["confirmed"] = json!(true);
+        let early = registry
+            .execute_with_auth("write_file", confirmed.clone(), &auth)
+            .await;
+        assert_eq!(early.error_type.as_deref(), Some("confirmation_required"));
+        assert!(early.content.contains(&token));
+
+        assert!(crate::pending_actions::confirm_from_user_text("telegram", 4242, &token).await);
+        let done = registry
+            .execute_with_auth("write_file", confirmed.clone(), &auth)
+            .await;
+        assert!(!done.is_error, "{}", done.content);
+        assert_eq!(done.content, "ok");
+
+        let again = registry
+            .execute_with_auth("write_file", confirmed, &auth)
+            .await;
+        assert_eq!(again.error_type.as_deref(), Some("confirmation_required"));
+
+        let control = ToolAuthContext {
+            caller_chat_id: 1,
+            ..auth.clone()
+        };
+        let direct = registry
+            .execute_with_auth("write_file", input, &control)
+            .await;
+        assert!(!direct.is_error);
+
+        let defs = registry.definitions();
+        assert!(defs[0].input_schema["properties"]
+            .get("confirmed")
+            .is_some());
+
+        // Editing a file needs the same approval as writing one.
+        let edit = registry
+            .execute_with_auth(
+                "edit_file",
+                json!({"path": "notes.txt", "old_string": "hi", "new_string": "bye"}),
+                &auth,
+            )
+            .await;
+        assert_eq!(edit.error_type.as_deref(), Some("confirmation_required"));
+        assert!(edit.content.contains("edit `notes.txt`"));
+        assert!(defs[1].input_schema["properties"]
+            .get("confirmed")
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn test_destructive_confirmation_follows_config_reload() {
+        let live = Arc::new(Live::from(crate::config::Config::test_defaults()));
+        let registry = ToolRegistry {
+            config: live.clone(),
+            sandbox_mode: SandboxMode::Off,
+            sandbox_runtime_available: false,
+            cached_static_definitions: OnceLock::new(),
+            mcp_tools: RwLock::new(Vec::new()),
+            tools: vec![Box::new(DummyTool {
+                tool_name: "write_file".into(),
+            })],
+        };
+        let auth = ToolAuthContext {
+            caller_channel: "telegram".into(),
+            caller_chat_id: 4343,
+            control_chat_ids: vec![],
+            env_files: vec![],
+            inbound_attachments: Vec::new(),
+            sub_agent_depth: 0,
+        };
+        let input = json!({"path": "notes.txt", "content": "hi"});
+        assert!(registry.definitions()[0].input_schema["properties"]
+            .get("confirmed")
+            .is_none());
+        let direct = registry
+            .execute_with_auth("write_file", input.clone(), &auth)
+            .await;
+        assert!(!direct.is_error);
+
+        let mut next = (*live.load()).clone();
+        next.confirm_destructive_tools = true;
+        live.store(Arc::new(next));
+
+        assert!(registry.definitions()[0].input_schema["properties"]
+            .get("confirmed")
+            .is_some());
+        let held = registry.execute_with_auth("write_file", input, &auth).await;
+        assert_eq!(held.error_type.as_deref(), Some("confirmation_required"));
+    }
+
     #[tokio::test]
     async fn test_medium_risk_tool_no_second_approval() {
         let registry = ToolRegistry {
-            config: crate::config::Config::test_defaults(),
+            config: Arc::new(Live::from(crate::config::Config::test_defaults())),
             sandbox_mode: SandboxMode::Off,
             sandbox_runtime_available: false,
             cached_static_definitions: OnceLock::new(),
+            mcp_tools: RwLock::new(Vec::new()),
             tools: vec![Box::new(DummyTool {
                 tool_name: "write_file".into(),
             })],
@@ -660,6 +1126,8 @@ mod tests {
             caller_chat_id: 1,
             control_chat_ids: vec![],
             env_files: vec![],
+            inbound_attachments: Vec::new(),
+            sub_agent_depth: 0,
         };
 
         let result = registry
@@ -669,6 +1137,38 @@ mod tests {
         assert_eq!(result.content, "ok");
     }
 
+    #[tokio::test]
+    async fn test_mcp_tools_can_be_swapped_at_runtime() {
+        let registry = ToolRegistry {
+            config: Arc::new(Live::from(crate::config::Config::test_defaults())),
+            tools: vec![Box::new(DummyTool {
+                tool_name: "read_file".into(),
+            })],
+            sandbox_mode: SandboxMode::Off,
+            sandbox_runtime_available: false,
+            cached_static_definitions: OnceLock::new(),
+            mcp_tools: RwLock::new(Vec::new()),
+        };
+        let mcp_tool = |name: &str| -> Arc<dyn Tool> {
+            Arc::new(DummyTool {
+                tool_name: name.into(),
+            })
+        };
+        let names = |registry: &ToolRegistry| -> Vec<String> {
+            registry.definitions().into_iter().map(|d| d.name).collect()
+        };
+
+        registry.set_mcp_tools(vec![mcp_tool("mcp_old_search")]);
+        assert_eq!(names(&registry), vec!["read_file", "mcp_old_search"]);
+        assert!(!registry.execute("mcp_old_search", json!({})).await.is_error);
+
+        registry.set_mcp_tools(vec![mcp_tool("mcp_new_search")]);
+        assert_eq!(names(&registry), vec!["read_file", "mcp_new_search"]);
+        let removed = registry.execute("mcp_old_search", json!({})).await;
+        assert_eq!(removed.error_type.as_deref(), Some("unknown_tool"));
+        assert!(!registry.execute("mcp_new_search", json!({})).await.is_error);
+    }
+
     #[tokio::test]
     async fn test_dynamic_plugin_tool_executes_without_restart() {
         let root = std::env::temp_dir().join(format!("microclaw_plugin_{}", uuid::Uuid::new_v4()));
@@ -700,17 +1200,20 @@ tools:
         config.plugins.dir = Some(plugins_dir.to_string_lossy().to_string());
 
         let registry = ToolRegistry {
-            config,
+            config: Arc::new(Live::from(config)),
             tools: vec![],
             sandbox_mode: SandboxMode::Off,
             sandbox_runtime_available: false,
             cached_static_definitions: OnceLock::new(),
+            mcp_tools: RwLock::new(Vec::new()),
         };
         let auth = ToolAuthContext {
             caller_channel: "web".into(),
             caller_chat_id: 7,
             control_chat_ids: vec![],
             env_files: vec![],
+            inbound_attachments: Vec::new(),
+            sub_agent_depth: 0,
         };
 
         let defs = registry.definitions();
@@ -728,10 +1231,11 @@ tools:
     #[tokio::test]
     async fn test_injects_default_chat_id_for_memory_tools() {
         let registry = ToolRegistry {
-            config: crate::config::Config::test_defaults(),
+            config: Arc::new(Live::from(crate::config::Config::test_defaults())),
             sandbox_mode: SandboxMode::Off,
             sandbox_runtime_available: false,
             cached_static_definitions: OnceLock::new(),
+            mcp_tools: RwLock::new(Vec::new()),
             tools: vec![Box::new(CaptureInputTool {
                 tool_name: "write_memory".into(),
             })],
@@ -741,6 +1245,8 @@ tools:
             caller_chat_id: 8009499081,
             control_chat_ids: vec![],
             env_files: vec![],
+            inbound_attachments: Vec::new(),
+            sub_agent_depth: 0,
         };
 
         let result = registry
@@ -758,10 +1264,11 @@ tools:
     #[tokio::test]
     async fn test_does_not_override_existing_chat_id() {
         let registry = ToolRegistry {
-            config: crate::config::Config::test_defaults(),
+            config: Arc::new(Live::from(crate::config::Config::test_defaults())),
             sandbox_mode: SandboxMode::Off,
             sandbox_runtime_available: false,
             cached_static_definitions: OnceLock::new(),
+            mcp_tools: RwLock::new(Vec::new()),
             tools: vec![Box::new(CaptureInputTool {
                 tool_name: "write_memory".into(),
             })],
@@ -771,6 +1278,8 @@ tools:
             caller_chat_id: 8009499081,
             control_chat_ids: vec![],
             env_files: vec![],
+            inbound_attachments: Vec::new(),
+            sub_agent_depth: 0,
         };
 
         let result = registry
@@ -1,6 +1,7 @@
 use async_trait::async_trait;
 use serde_json::json;
 use std::path::PathBuf;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, BufReader};
 use tracing::info;
 
 use crate::config::WorkingDirIsolation;
@@ -8,6 +9,14 @@ use microclaw_core::llm_types::ToolDefinition;
 
 use super::{schema_object, Tool, ToolResult};
 
+/// Lines returned when no range is given.
+const DEFAULT_LINE_LIMIT: usize = 2000;
+/// Bytes of file content returned per call, ranged or not; the rest of the
+/// window is cut off with a note asking for a narrower range.
+const MAX_OUTPUT_BYTES: usize = 100 * 1024;
+/// Bytes kept of a single line; longer lines are cut with a marker.
+const MAX_LINE_BYTES: usize = 2000;
+
 pub struct ReadFileTool {
     working_dir: PathBuf,
     working_dir_isolation: WorkingDirIsolation,
@@ -38,7 +47,7 @@ impl Tool for ReadFileTool {
     fn definition(&self) -> ToolDefinition {
         ToolDefinition {
             name: "read_file".into(),
-            description: "Read the contents of a file at the given path. Returns the file content with line numbers.".into(),
+            description: "Read the contents of a file at the given path. Returns the file content with line numbers. Large files are cut off with a note giving the total line count; read further windows with offset/limit or start_line/end_line. Very long lines are truncated.".into(),
             input_schema: schema_object(
                 json!({
                     "path": {
@@ -52,6 +61,14 @@ impl Tool for ReadFileTool {
                     "limit": {
                         "type": "integer",
                         "description": "Maximum number of lines to read"
+                    },
+                    "start_line": {
+                        "type": "integer",
+                        "description": "First line to read (1-based); same as offset"
+                    },
+                    "end_line": {
+                        "type": "integer",
+                        "description": "Last line to read (1-based, inclusive)"
                     }
                 }),
                 &["path"],
@@ -75,32 +92,144 @@ impl Tool for ReadFileTool {
 
         info!("Reading file: {}", resolved_path.display());
 
-        let content = match tokio::fs::read_to_string(&resolved_path).await {
-            Ok(c) => c,
+        let file = match tokio::fs::File::open(&resolved_path).await {
+            Ok(f) => f,
             Err(e) => return ToolResult::error(format!("Failed to read file: {e}")),
         };
+        let file_bytes = file.metadata().await.map(|m| m.len()).unwrap_or(0);
+
+        let line_arg = |key: &str| input.get(key).and_then(|v| v.as_u64()).map(|v| v as usize);
+        let start_line = line_arg("start_line").or_else(|| line_arg("offset"));
+        let end_line = line_arg("end_line");
+        let limit = line_arg("limit");
+
+        let offset = start_line.unwrap_or(1).saturating_sub(1);
+        let limit = match (end_line, limit) {
+            (Some(end_line), _) => end_line.saturating_sub(offset),
+            (None, Some(limit)) => limit,
+            (None, None) => DEFAULT_LINE_LIMIT,
+        };
+        let window_end = offset.saturating_add(limit);
+
+        let mut reader = BufReader::new(file);
+        let mut shown = Vec::new();
+        let mut used_bytes = 0;
+        let mut over_size_cap = false;
+        let mut total = 0;
+        loop {
+            let in_window = total >= offset && total < window_end && !over_size_cap;
+            let line = match read_line_capped(&mut reader, in_window).await {
+                Ok(Some(line)) => line,
+                Ok(None) => break,
+                Err(e) => return ToolResult::error(format!("Failed to read file: {e}")),
+            };
+            if in_window {
+                if !shown.is_empty() && used_bytes + line.text.len() + 1 > MAX_OUTPUT_BYTES {
+                    over_size_cap = true;
+                } else {
+                    used_bytes += line.text.len() + 1;
+                    shown.push(line.render(total + 1));
+                }
+            }
+            total += 1;
+        }
+
+        let first = offset.min(total);
+        let end = first + shown.len();
+        let mut output = shown.join("\n");
+        if over_size_cap {
+            output.push_str(&format!(
+                "\n\n[File is {file_bytes} bytes and {total} lines; showing lines {}-{end}. Read the rest with start_line/end_line or offset/limit.]",
+                first + 1
+            ));
+        } else if first > 0 || end < total {
+            let shown = if end > first {
+                format!("lines {}-{end}", first + 1)
+            } else {
+                "no lines".to_string()
+            };
+            output.push_str(&format!("\n\n[Showing {shown} of {total}.]"));
+        }
+
+        ToolResult::success(output)
+    }
+}
+
+/// One line of the file, possibly cut to `MAX_LINE_BYTES`.
+struct Line {
+    text: String,
+    full_len: usize,
+}
+
+impl Line {
+    fn render(&self, number: usize) -> String {
+        if self.full_len > self.text.len() {
+            format!(
+                "{number:>6}\t{} ... [line truncated, {} bytes]",
+                self.text, self.full_len
+            )
+        } else {
+            format!("{number:>6}\t{}", self.text)
+        }
+    }
+}
 
-        let lines: Vec<&str> = content.lines().collect();
-        let offset = input
-            .get("offset")
-            .and_then(|v| v.as_u64())
-            .map(|o| (o as usize).saturating_sub(1))
-            .unwrap_or(0);
-        let limit = input
-            .get("limit")
-            .and_then(|v| v.as_u64())
-            .map(|l| l as usize)
-            .unwrap_or(2000);
-
-        let end = (offset + limit).min(lines.len());
-        let selected: Vec<String> = lines[offset..end]
-            .iter()
-            .enumerate()
-            .map(|(i, line)| format!("{:>6}\t{}", offset + i + 1, line))
-            .collect();
-
-        ToolResult::success(selected.join("\n"))
+/// Read the next line without its line ending, keeping at most
+/// `MAX_LINE_BYTES` of it (nothing unless `keep`), so no whole file or line
+/// is ever buffered. Returns `None` at end of file.
+async fn read_line_capped<R: AsyncBufRead + Unpin>(
+    reader: &mut R,
+    keep: bool,
+) -> std::io::Result<Option<Line>> {
+    let mut kept = Vec::new();
+    let mut full_len = 0;
+    let mut last_byte = None;
+    let mut read_any = false;
+    loop {
+        let buf = reader.fill_buf().await?;
+        if buf.is_empty() {
+            break;
+        }
+        read_any = true;
+        let (chunk_len, consumed, done) = match buf.iter().position(|&b| b == b'\n') {
+            Some(i) => (i, i + 1, true),
+            None => (buf.len(), buf.len(), false),
+        };
+        if keep {
+            let room = MAX_LINE_BYTES.saturating_sub(kept.len());
+            kept.extend_from_slice(&buf[..chunk_len.min(room)]);
+        }
+        if chunk_len > 0 {
+            last_byte = Some(buf[chunk_len - 1]);
+        }
+        full_len += chunk_len;
+        reader.consume(consumed);
+        if done {
+            break;
+        }
+    }
+    if !read_any {
+        return Ok(None);
     }
+    if last_byte == Some(b'\r') {
+        full_len -= 1;
+        kept.truncate(full_len);
+    }
+    if kept.len() < full_len {
+        // The cut may have split a multi-byte character.
+        if let Err(e) = std::str::from_utf8(&kept) {
+            if e.error_len().is_none() {
+                kept.truncate(e.valid_up_to());
+            }
+        }
+    }
+    let text = String::from_utf8(kept).map_err(|_| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "stream did not contain valid UTF-8",
+        )
+    })?;
+    Ok(Some(Line { text, full_len }))
 }
 
 #[cfg(test)]
@@ -146,6 +275,101 @@ mod tests {
         let _ = std::fs::remove_dir_all(&dir);
     }
 
+    #[tokio::test]
+    async fn test_read_file_line_window_reports_total() {
+        let dir = std::env::temp_dir().join(format!("microclaw_rf4_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("test.txt");
+        let body = (1..=50)
+            .map(|i| format!("row {i}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        std::fs::write(&file, body).unwrap();
+
+        let tool = ReadFileTool::new(".");
+        let result = tool
+            .execute(json!({"path": file.to_str().unwrap(), "start_line": 10, "end_line": 12}))
+            .await;
+        assert!(!result.is_error);
+        assert_eq!(
+            result.content,
+            "    10\trow 10\n    11\trow 11\n    12\trow 12\n\n[Showing lines 10-12 of 50.]"
+        );
+
+        let result = tool
+            .execute(json!({"path": file.to_str().unwrap(), "offset": 80}))
+            .await;
+        assert!(!result.is_error);
+        assert!(result.content.contains("[Showing no lines of 50.]"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_read_file_oversized_without_range_returns_head() {
+        let dir = std::env::temp_dir().join(format!("microclaw_rf5_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("big.log");
+        let line = "x".repeat(99);
+        let body = vec![line.as_str(); 1500].join("\n");
+        std::fs::write(&file, &body).unwrap();
+
+        let tool = ReadFileTool::new(".");
+        let result = tool.execute(json!({"path": file.to_str().unwrap()})).await;
+        assert!(!result.is_error);
+        assert!(result.content.contains("     1\txxx"));
+        assert!(!result.content.contains("  1500\t"));
+        assert!(result
+            .content
+            .contains("[File is 149999 bytes and 1500 lines; showing lines 1-1024."));
+
+        // An explicit range within the cap is honored in full.
+        let result = tool
+            .execute(json!({"path": file.to_str().unwrap(), "start_line": 1400}))
+            .await;
+        assert!(result.content.contains("  1500\t"));
+        assert!(!result.content.contains("[File is"));
+
+        // A larger explicit range is still capped.
+        let result = tool
+            .execute(json!({"path": file.to_str().unwrap(), "start_line": 101, "end_line": 1500}))
+            .await;
+        assert!(result.content.contains("   101\txxx"));
+        assert!(!result.content.contains("  1500\t"));
+        assert!(result
+            .content
+            .contains("[File is 149999 bytes and 1500 lines; showing lines 101-1124."));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_read_file_truncates_long_lines() {
+        let dir = std::env::temp_dir().join(format!("microclaw_rf6_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("minified.js");
+        let long_line = "é".repeat(MAX_LINE_BYTES);
+        std::fs::write(&file, format!("short\r\n{long_line}\r\nlast\n")).unwrap();
+
+        let tool = ReadFileTool::new(".");
+        let result = tool.execute(json!({"path": file.to_str().unwrap()})).await;
+        assert!(!result.is_error);
+        let lines: Vec<&str> = result.content.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "     1\tshort");
+        assert_eq!(
+            lines[1],
+            format!(
+                "     2\t{} ... [line truncated, {} bytes]",
+                "é".repeat(MAX_LINE_BYTES / 2),
+                MAX_LINE_BYTES * 2
+            )
+        );
+        assert_eq!(lines[2], "     3\tlast");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[tokio::test]
     async fn test_read_file_not_found() {
         let tool = ReadFileTool::new(".");
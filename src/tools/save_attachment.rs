@@ -0,0 +1,376 @@
+use async_trait::async_trait;
+use base64::Engine;
+use serde_json::json;
+use std::path::PathBuf;
+use tracing::info;
+
+use crate::config::{Config, WorkingDirIsolation};
+use microclaw_core::llm_types::ToolDefinition;
+
+use super::{schema_object, Tool, ToolAuthContext, ToolResult};
+
+/// Input key carrying the current message's attachments. Only injected for
+/// this tool, so other tools never receive the payload.
+const INBOUND_ATTACHMENTS_KEY: &str = "__microclaw_inbound_attachments";
+
+/// Attach the turn's inbound media to the tool input. Any value the model put
+/// under the same key is dropped so it cannot smuggle in its own payload.
+pub(crate) fn inject_inbound_attachments(
+    input: serde_json::Value,
+    auth: &ToolAuthContext,
+) -> serde_json::Value {
+    let mut obj = match input {
+        serde_json::Value::Object(map) => map,
+        _ => serde_json::Map::new(),
+    };
+    obj.remove(INBOUND_ATTACHMENTS_KEY);
+    if !auth.inbound_attachments.is_empty() {
+        let attachments = auth
+            .inbound_attachments
+            .iter()
+            .map(|attachment| {
+                json!({
+                    "media_type": attachment.media_type,
+                    "data": attachment.data_base64,
+                })
+            })
+            .collect();
+        obj.insert(
+            INBOUND_ATTACHMENTS_KEY.to_string(),
+            serde_json::Value::Array(attachments),
+        );
+    }
+    serde_json::Value::Object(obj)
+}
+
+/// Whether `media_type` matches the allowlist. Entries ending in `/` match a
+/// whole family (`image/`), `*` matches anything, other entries match exactly.
+pub fn media_type_allowed(media_type: &str, allowed: &[String]) -> bool {
+    let media_type = media_type.trim().to_ascii_lowercase();
+    allowed.iter().any(|entry| {
+        let entry = entry.trim().to_ascii_lowercase();
+        entry == "*"
+            || (entry.ends_with('/') && media_type.starts_with(&entry))
+            || entry == media_type
+    })
+}
+
+fn extension_for_media_type(media_type: &str) -> &'static str {
+    match media_type.trim().to_ascii_lowercase().as_str() {
+        "image/jpeg" | "image/jpg" => "jpg",
+        "image/png" => "png",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "application/pdf" => "pdf",
+        "application/json" => "json",
+        "text/plain" => "txt",
+        "text/csv" => "csv",
+        "text/markdown" => "md",
+        "audio/ogg" => "ogg",
+        "audio/mpeg" => "mp3",
+        "audio/wav" | "audio/x-wav" => "wav",
+        _ => "bin",
+    }
+}
+
+/// Reduce a requested file name to a single safe path component.
+fn sanitize_file_name(raw: &str) -> Option<String> {
+    let name: String = raw
+        .trim()
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '-' | '_' => c,
+            _ => '_',
+        })
+        .collect();
+    if name.trim_matches('.').is_empty() {
+        return None;
+    }
+    Some(name)
+}
+
+pub struct SaveAttachmentTool {
+    working_dir: PathBuf,
+    working_dir_isolation: WorkingDirIsolation,
+    max_bytes: u64,
+    allowed_types: Vec<String>,
+}
+
+impl SaveAttachmentTool {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            working_dir: PathBuf::from(&config.working_dir),
+            working_dir_isolation: config.working_dir_isolation,
+            max_bytes: config
+                .save_attachment_max_size_mb
+                .saturating_mul(1024)
+                .saturating_mul(1024),
+            allowed_types: config.save_attachment_allowed_types.clone(),
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for SaveAttachmentTool {
+    fn name(&self) -> &str {
+        "save_attachment"
+    }
+
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "save_attachment".into(),
+            description: "Save a file or image attached to the user's current message into the chat's working directory (under attachments/) and return its path, so it can be processed with bash or file tools. When the message has several attachments, pick one with index.".into(),
+            input_schema: schema_object(
+                json!({
+                    "index": {
+                        "type": "integer",
+                        "description": "1-based position of the attachment to save when the message has several (default 1)"
+                    },
+                    "file_name": {
+                        "type": "string",
+                        "description": "Optional file name (no directories). Defaults to a timestamped name with an extension matching the media type."
+                    }
+                }),
+                &[],
+            ),
+        }
+    }
+
+    async fn execute(&self, input: serde_json::Value) -> ToolResult {
+        let attachments = input
+            .get(INBOUND_ATTACHMENTS_KEY)
+            .and_then(|v| v.as_array())
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        if attachments.is_empty() {
+            return ToolResult::error("The current message has no attachment to save.".into());
+        }
+        let index = input.get("index").and_then(|v| v.as_u64()).unwrap_or(1);
+        let Some(attachment) = usize::try_from(index)
+            .ok()
+            .and_then(|i| i.checked_sub(1))
+            .and_then(|i| attachments.get(i))
+        else {
+            return ToolResult::error(format!(
+                "Invalid index {index}: the current message has {} attachment(s).",
+                attachments.len()
+            ));
+        };
+        let media_type = attachment
+            .get("media_type")
+            .and_then(|v| v.as_str())
+            .unwrap_or("application/octet-stream");
+        if !media_type_allowed(media_type, &self.allowed_types) {
+            return ToolResult::error(format!(
+                "Attachment type '{media_type}' is not allowed (allowed: {})",
+                self.allowed_types.join(", ")
+            ))
+            .with_error_type("attachment_type_blocked");
+        }
+        let data = attachment
+            .get("data")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default();
+        let bytes = match base64::engine::general_purpose::STANDARD.decode(data) {
+            Ok(bytes) => bytes,
+            Err(e) => return ToolResult::error(format!("Failed to decode attachment: {e}")),
+        };
+        if bytes.len() as u64 > self.max_bytes {
+            return ToolResult::error(format!(
+                "Attachment is too large ({} bytes). Max allowed is {} bytes.",
+                bytes.len(),
+                self.max_bytes
+            ))
+            .with_error_type("attachment_too_large");
+        }
+
+        let extension = extension_for_media_type(media_type);
+        let file_name = match input.get("file_name").and_then(|v| v.as_str()) {
+            Some(raw) => match sanitize_file_name(raw) {
+                Some(name) if name.contains('.') => name,
+                Some(name) => format!("{name}.{extension}"),
+                None => return ToolResult::error(format!("Invalid file name '{raw}'")),
+            },
+            None => format!(
+                "attachment-{}.{extension}",
+                chrono::Utc::now().format("%Y%m%d-%H%M%S")
+            ),
+        };
+
+        let working_dir =
+            super::resolve_tool_working_dir(&self.working_dir, self.working_dir_isolation, &input);
+        let path = working_dir.join("attachments").join(&file_name);
+        let path_str = path.to_string_lossy().to_string();
+        if let Err(msg) = microclaw_tools::path_guard::check_path(&path_str) {
+            return ToolResult::error(msg);
+        }
+
+        info!("Saving attachment to {}", path_str);
+        if let Some(parent) = path.parent() {
+            if let Err(e) = tokio::fs::create_dir_all(parent).await {
+                return ToolResult::error(format!("Failed to create directories: {e}"));
+            }
+        }
+        match tokio::fs::write(&path, &bytes).await {
+            Ok(()) => ToolResult::success(format!(
+                "Saved attachment to {path_str} ({} bytes, {media_type})",
+                bytes.len()
+            )),
+            Err(e) => ToolResult::error(format!("Failed to write attachment: {e}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::InboundAttachment;
+    use std::sync::Arc;
+
+    fn test_tool(dir: &std::path::Path) -> SaveAttachmentTool {
+        let mut config = Config::test_defaults();
+        config.working_dir = dir.to_string_lossy().to_string();
+        config.working_dir_isolation = WorkingDirIsolation::Chat;
+        config.save_attachment_max_size_mb = 1;
+        SaveAttachmentTool::new(&config)
+    }
+
+    fn auth_with_all(attachments: &[(&str, &[u8])]) -> ToolAuthContext {
+        ToolAuthContext {
+            caller_channel: "telegram".into(),
+            caller_chat_id: 42,
+            control_chat_ids: vec![],
+            env_files: vec![],
+            inbound_attachments: attachments
+                .iter()
+                .map(|(media_type, bytes)| {
+                    Arc::new(InboundAttachment {
+                        media_type: media_type.to_string(),
+                        data_base64: base64::engine::general_purpose::STANDARD.encode(bytes),
+                    })
+                })
+                .collect(),
+            sub_agent_depth: 0,
+        }
+    }
+
+    fn auth_with(attachment: Option<(&str, &[u8])>) -> ToolAuthContext {
+        auth_with_all(attachment.as_slice())
+    }
+
+    fn tool_input(input: serde_json::Value, auth: &ToolAuthContext) -> serde_json::Value {
+        let input = microclaw_tools::runtime::inject_auth_context(input, auth);
+        inject_inbound_attachments(input, auth)
+    }
+
+    #[tokio::test]
+    async fn test_save_attachment_writes_into_chat_working_dir() {
+        let dir = std::env::temp_dir().join(format!("microclaw_sa_{}", uuid::Uuid::new_v4()));
+        let tool = test_tool(&dir);
+        let auth = auth_with(Some(("image/png", b"\x89PNGdata")));
+
+        let result = tool.execute(tool_input(json!({}), &auth)).await;
+        assert!(!result.is_error, "{}", result.content);
+        let saved_dir = dir
+            .join("chat")
+            .join("telegram")
+            .join("42")
+            .join("attachments");
+        let entries: Vec<_> = std::fs::read_dir(&saved_dir).unwrap().collect();
+        assert_eq!(entries.len(), 1);
+        let saved = entries[0].as_ref().unwrap().path();
+        assert_eq!(saved.extension().and_then(|e| e.to_str()), Some("png"));
+        assert_eq!(std::fs::read(&saved).unwrap(), b"\x89PNGdata");
+        assert!(result
+            .content
+            .contains(&saved.to_string_lossy().to_string()));
+
+        let result = tool
+            .execute(tool_input(json!({"file_name": "sub/receipt"}), &auth))
+            .await;
+        assert!(!result.is_error, "{}", result.content);
+        assert!(saved_dir.join("sub_receipt.png").exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_save_attachment_guardrails() {
+        let dir = std::env::temp_dir().join(format!("microclaw_sa_{}", uuid::Uuid::new_v4()));
+        let tool = test_tool(&dir);
+
+        let result = tool.execute(tool_input(json!({}), &auth_with(None))).await;
+        assert!(result.is_error);
+        assert!(result.content.contains("no attachment"));
+
+        // A payload supplied by the model itself is discarded.
+        let forged =
+            json!({INBOUND_ATTACHMENTS_KEY: [{"media_type": "text/plain", "data": "aGk="}]});
+        let result = tool.execute(tool_input(forged, &auth_with(None))).await;
+        assert!(result.is_error);
+
+        let auth = auth_with(Some(("application/x-msdownload", b"MZ")));
+        let result = tool.execute(tool_input(json!({}), &auth)).await;
+        assert_eq!(
+            result.error_type.as_deref(),
+            Some("attachment_type_blocked")
+        );
+
+        let big = vec![0u8; 1024 * 1024 + 1];
+        let auth = auth_with(Some(("image/jpeg", &big)));
+        let result = tool.execute(tool_input(json!({}), &auth)).await;
+        assert_eq!(result.error_type.as_deref(), Some("attachment_too_large"));
+
+        let auth = auth_with(Some(("text/plain", b"SECRET=1")));
+        let result = tool
+            .execute(tool_input(json!({"file_name": ".env"}), &auth))
+            .await;
+        assert!(result.is_error);
+
+        assert!(!dir
+            .join("chat")
+            .join("telegram")
+            .join("42")
+            .join("attachments")
+            .exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_save_attachment_picks_attachment_by_index() {
+        let dir = std::env::temp_dir().join(format!("microclaw_sa_{}", uuid::Uuid::new_v4()));
+        let tool = test_tool(&dir);
+        let auth = auth_with_all(&[("image/png", b"first"), ("image/jpeg", b"second")]);
+
+        let result = tool
+            .execute(tool_input(json!({"index": 2, "file_name": "b"}), &auth))
+            .await;
+        assert!(!result.is_error, "{}", result.content);
+        let saved_dir = dir
+            .join("chat")
+            .join("telegram")
+            .join("42")
+            .join("attachments");
+        assert_eq!(std::fs::read(saved_dir.join("b.jpg")).unwrap(), b"second");
+
+        for index in [0, 3] {
+            let result = tool
+                .execute(tool_input(json!({"index": index}), &auth))
+                .await;
+            assert!(result.is_error);
+            assert!(result.content.contains("has 2 attachment(s)"));
+        }
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_media_type_allowlist_matching() {
+        let allowed = vec!["image/".to_string(), "application/pdf".to_string()];
+        assert!(media_type_allowed("image/png", &allowed));
+        assert!(media_type_allowed("Application/PDF", &allowed));
+        assert!(!media_type_allowed("application/pdfx", &allowed));
+        assert!(!media_type_allowed("text/plain", &allowed));
+        assert!(media_type_allowed("text/plain", &["*".to_string()]));
+        assert!(!media_type_allowed("image/png", &[]));
+    }
+}
@@ -2,7 +2,7 @@ use std::str::FromStr;
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use chrono::{NaiveDateTime, TimeZone, Utc};
+use chrono::{Datelike, NaiveDateTime, TimeZone, Utc};
 use serde_json::json;
 
 use super::{authorize_chat_access, schema_object, Tool, ToolResult};
@@ -12,16 +12,44 @@ use microclaw_core::llm_types::ToolDefinition;
 use microclaw_storage::db::{call_blocking, Database};
 
 fn compute_next_run(cron_expr: &str, tz_name: &str) -> Result<String, String> {
+    let next = upcoming_runs(cron_expr, tz_name, 1)?.remove(0);
+    Ok(next.with_timezone(&chrono::Utc).to_rfc3339())
+}
+
+/// The next `count` fire times of a cron expression in `tz_name`, parsed the
+/// same way the scheduler parses it when the task is due.
+fn upcoming_runs(
+    cron_expr: &str,
+    tz_name: &str,
+    count: usize,
+) -> Result<Vec<chrono::DateTime<chrono_tz::Tz>>, String> {
     let tz: chrono_tz::Tz = tz_name
         .parse()
         .map_err(|_| format!("Invalid timezone: {tz_name}"))?;
-    let schedule =
-        cron::Schedule::from_str(cron_expr).map_err(|e| format!("Invalid cron expression: {e}"))?;
-    let next = schedule
-        .upcoming(tz)
-        .next()
-        .ok_or_else(|| "No upcoming run found for this cron expression".to_string())?;
-    Ok(next.with_timezone(&chrono::Utc).to_rfc3339())
+    let schedule = cron::Schedule::from_str(cron_expr).map_err(|e| {
+        let mut msg = format!(
+            "Invalid cron expression '{cron_expr}': {e}. Use 6 fields: sec min hour day-of-month month day-of-week, e.g. \"0 30 9 * * *\" for 09:30 every day."
+        );
+        if parse_cron_fields(cron_expr).len() == 5 {
+            msg.push_str(&format!(
+                " This looks like a 5-field cron; add a seconds field first: \"0 {}\".",
+                cron_expr.trim()
+            ));
+        }
+        msg
+    })?;
+    let runs: Vec<_> = schedule.upcoming(tz).take(count).collect();
+    if runs.is_empty() {
+        return Err("No upcoming run found for this cron expression".to_string());
+    }
+    Ok(runs)
+}
+
+fn format_run_time<Tz: TimeZone>(dt: &chrono::DateTime<Tz>) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    dt.format("%a %Y-%m-%d %H:%M:%S %Z").to_string()
 }
 
 fn parse_once_schedule_value(
@@ -126,12 +154,105 @@ fn cron_human_hint(cron_expr: &str) -> Option<String> {
     None
 }
 
+pub const TASK_TYPE_AGENT: &str = "agent";
+pub const TASK_TYPE_EXPORT: &str = "export";
+
 // --- schedule_task ---
 
+/// Guards against runaway schedules created by the model. A zero value disables
+/// the corresponding limit; the default applies no limits.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ScheduleLimits {
+    pub min_interval_secs: u64,
+    pub max_tasks_per_chat: usize,
+    pub max_once_lead_days: u64,
+}
+
+impl ScheduleLimits {
+    pub fn from_config(config: &crate::config::Config) -> Self {
+        ScheduleLimits {
+            min_interval_secs: config.schedule_min_interval_secs,
+            max_tasks_per_chat: config.schedule_max_tasks_per_chat,
+            max_once_lead_days: config.schedule_max_once_lead_days,
+        }
+    }
+
+    fn check_cron_interval(&self, cron_expr: &str, tz_name: &str) -> Result<(), String> {
+        if self.min_interval_secs == 0 {
+            return Ok(());
+        }
+        let Some(gap) = min_cron_gap_secs(cron_expr, tz_name) else {
+            return Ok(());
+        };
+        if gap < self.min_interval_secs as i64 {
+            return Err(format!(
+                "Schedule rejected: cron '{cron_expr}' runs every {gap}s, but the minimum interval is {}s. Choose a less frequent schedule.",
+                self.min_interval_secs
+            ));
+        }
+        Ok(())
+    }
+
+    fn check_once_lead(&self, run_at: chrono::DateTime<Utc>) -> Result<(), String> {
+        if self.max_once_lead_days == 0 {
+            return Ok(());
+        }
+        // A lead too large to represent is effectively no limit.
+        let Some(limit) = i64::try_from(self.max_once_lead_days)
+            .ok()
+            .and_then(chrono::TimeDelta::try_days)
+            .and_then(|lead| Utc::now().checked_add_signed(lead))
+        else {
+            return Ok(());
+        };
+        if run_at > limit {
+            return Err(format!(
+                "Schedule rejected: one-time tasks can be scheduled at most {} days ahead.",
+                self.max_once_lead_days
+            ));
+        }
+        Ok(())
+    }
+
+    fn check_task_count(&self, existing: usize) -> Result<(), String> {
+        if self.max_tasks_per_chat > 0 && existing >= self.max_tasks_per_chat {
+            return Err(format!(
+                "Schedule rejected: this chat already has {existing} scheduled tasks (limit {}). Cancel an existing task first.",
+                self.max_tasks_per_chat
+            ));
+        }
+        Ok(())
+    }
+}
+
+async fn check_chat_task_limit(
+    db: &Arc<Database>,
+    limits: &ScheduleLimits,
+    chat_id: i64,
+) -> Result<(), String> {
+    if limits.max_tasks_per_chat == 0 {
+        return Ok(());
+    }
+    let existing = call_blocking(db.clone(), move |db| db.get_tasks_for_chat(chat_id))
+        .await
+        .map_err(|e| format!("Failed to count tasks: {e}"))?
+        .len();
+    limits.check_task_count(existing)
+}
+
+/// Smallest gap in seconds between the next few runs of a cron expression.
+fn min_cron_gap_secs(cron_expr: &str, tz_name: &str) -> Option<i64> {
+    let tz: chrono_tz::Tz = tz_name.parse().ok()?;
+    let schedule = cron::Schedule::from_str(cron_expr).ok()?;
+    let runs: Vec<_> = schedule.upcoming(tz).take(32).collect();
+    runs.windows(2).map(|w| (w[1] - w[0]).num_seconds()).min()
+}
+
 pub struct ScheduleTaskTool {
     registry: Arc<ChannelRegistry>,
     db: Arc<Database>,
     default_timezone: String,
+    limits: ScheduleLimits,
 }
 
 impl ScheduleTaskTool {
@@ -144,8 +265,14 @@ impl ScheduleTaskTool {
             registry,
             db,
             default_timezone,
+            limits: ScheduleLimits::default(),
         }
     }
+
+    pub fn with_limits(mut self, limits: ScheduleLimits) -> Self {
+        self.limits = limits;
+        self
+    }
 }
 
 #[async_trait]
@@ -157,7 +284,7 @@ impl Tool for ScheduleTaskTool {
     fn definition(&self) -> ToolDefinition {
         ToolDefinition {
             name: "schedule_task".into(),
-            description: "Schedule a recurring or one-time task. For recurring tasks, provide a 6-field cron expression (sec min hour dom month dow). For one-time tasks, provide an ISO 8601 timestamp. The bot will execute the prompt at the scheduled time and send the result to this chat.".into(),
+            description: "Schedule a recurring or one-time task. For recurring tasks, provide a 6-field cron expression (sec min hour dom month dow). For one-time tasks, provide an ISO 8601 timestamp. The bot will execute the prompt at the scheduled time and send the result to this chat. Set task_type to 'export' to periodically export the chat history to a markdown file instead of running a prompt.".into(),
             input_schema: schema_object(
                 json!({
                     "chat_id": {
@@ -166,7 +293,12 @@ impl Tool for ScheduleTaskTool {
                     },
                     "prompt": {
                         "type": "string",
-                        "description": "The prompt/instruction to execute at the scheduled time"
+                        "description": "The prompt/instruction to execute at the scheduled time. Optional for export tasks."
+                    },
+                    "task_type": {
+                        "type": "string",
+                        "enum": ["agent", "export"],
+                        "description": "'agent' (default) runs the prompt; 'export' writes the chat history to a markdown file and sends it to the chat when the channel supports attachments"
                     },
                     "schedule_type": {
                         "type": "string",
@@ -182,7 +314,7 @@ impl Tool for ScheduleTaskTool {
                         "description": "Optional IANA timezone name (e.g. 'US/Eastern', 'Europe/London'). Defaults to server timezone setting."
                     }
                 }),
-                &["chat_id", "prompt", "schedule_type", "schedule_value"],
+                &["chat_id", "schedule_type", "schedule_value"],
             ),
         }
     }
@@ -200,8 +332,16 @@ impl Tool for ScheduleTaskTool {
         {
             return ToolResult::error(e);
         }
+        let task_type = input
+            .get("task_type")
+            .and_then(|v| v.as_str())
+            .unwrap_or(TASK_TYPE_AGENT);
+        if task_type != TASK_TYPE_AGENT && task_type != TASK_TYPE_EXPORT {
+            return ToolResult::error("task_type must be 'agent' or 'export'".into());
+        }
         let prompt = match input.get("prompt").and_then(|v| v.as_str()) {
             Some(p) => p,
+            None if task_type == TASK_TYPE_EXPORT => "Export chat history to markdown",
             None => return ToolResult::error("Missing required parameter: prompt".into()),
         };
         let schedule_type = match input.get("schedule_type").and_then(|v| v.as_str()) {
@@ -217,9 +357,18 @@ impl Tool for ScheduleTaskTool {
             .and_then(|v| v.as_str())
             .unwrap_or(&self.default_timezone);
 
+        let mut preview = Vec::new();
         let next_run = match schedule_type {
             "cron" => match compute_next_run(schedule_value, tz_name) {
-                Ok(nr) => nr,
+                Ok(nr) => {
+                    if let Err(e) = self.limits.check_cron_interval(schedule_value, tz_name) {
+                        return ToolResult::error(e);
+                    }
+                    if let Ok(runs) = upcoming_runs(schedule_value, tz_name, 3) {
+                        preview = runs.iter().map(format_run_time).collect();
+                    }
+                    nr
+                }
                 Err(e) => return ToolResult::error(e),
             },
             "once" => {
@@ -241,18 +390,30 @@ impl Tool for ScheduleTaskTool {
                         );
                     }
                 }
+                if let Err(e) = self.limits.check_once_lead(dt_utc) {
+                    return ToolResult::error(e);
+                }
+                if let Ok(tz) = tz_name.parse::<chrono_tz::Tz>() {
+                    preview.push(format_run_time(&dt_utc.with_timezone(&tz)));
+                }
                 dt_utc.to_rfc3339()
             }
             _ => return ToolResult::error("schedule_type must be 'cron' or 'once'".into()),
         };
 
+        if let Err(e) = check_chat_task_limit(&self.db, &self.limits, chat_id).await {
+            return ToolResult::error(e);
+        }
+
         let prompt_owned = prompt.to_string();
+        let task_type_owned = task_type.to_string();
         let schedule_type_owned = schedule_type.to_string();
         let schedule_value_owned = schedule_value.to_string();
         let next_run_owned = next_run.clone();
         match call_blocking(self.db.clone(), move |db| {
-            db.create_scheduled_task(
+            db.create_scheduled_task_with_type(
                 chat_id,
+                &task_type_owned,
                 &prompt_owned,
                 &schedule_type_owned,
                 &schedule_value_owned,
@@ -267,11 +428,28 @@ impl Tool for ScheduleTaskTool {
                 } else {
                     None
                 };
+                let kind = if task_type == TASK_TYPE_EXPORT {
+                    "Export task"
+                } else {
+                    "Task"
+                };
                 let mut message =
-                    format!("Task #{id} scheduled (tz: {tz_name}). Next run: {next_run}");
+                    format!("{kind} #{id} scheduled (tz: {tz_name}). Next run: {next_run}");
                 if let Some(c) = cadence {
                     message.push_str(&format!("\nCron interpretation: {c}."));
                 }
+                if !preview.is_empty() {
+                    let label = if schedule_type == "cron" {
+                        "Upcoming runs"
+                    } else {
+                        "Runs at"
+                    };
+                    message.push_str(&format!("\n{label} ({tz_name}):"));
+                    for run in &preview {
+                        message.push_str(&format!("\n- {run}"));
+                    }
+                    message.push_str("\nConfirm with the user that this is the intended schedule.");
+                }
                 ToolResult::success(message)
             }
             Err(e) => ToolResult::error(format!("Failed to create task: {e}")),
@@ -279,19 +457,423 @@ impl Tool for ScheduleTaskTool {
     }
 }
 
+// --- remind_me ---
+
+/// Resolve a reminder time such as "in 30 minutes", "tomorrow at 9am",
+/// "friday 14:00" or an ISO timestamp against `now`. Inputs that could mean
+/// more than one time are rejected with a request to clarify.
+fn parse_reminder_time(
+    when: &str,
+    now: chrono::DateTime<chrono_tz::Tz>,
+) -> Result<chrono::DateTime<Utc>, String> {
+    let when = when.trim();
+    if when.is_empty() {
+        return Err("Missing required parameter: when".into());
+    }
+    let tz = now.timezone();
+    if let Ok(dt) = parse_once_schedule_value(when, tz.name()) {
+        return Ok(dt);
+    }
+
+    let lower = when
+        .to_ascii_lowercase()
+        .replace("half an hour", "30 minutes")
+        .replace(',', " ");
+    let lower = lower.trim_end_matches('.');
+    let tokens: Vec<&str> = lower.split_whitespace().collect();
+    let unclear = || {
+        format!(
+            "Could not understand when '{when}' is. Please clarify, e.g. 'in 30 minutes', 'tomorrow at 9am', 'friday 14:00' or an ISO timestamp like 2026-03-01T09:00."
+        )
+    };
+
+    if tokens.first() == Some(&"in") {
+        return parse_relative_duration(&tokens[1..])
+            .and_then(|duration| now.with_timezone(&Utc).checked_add_signed(duration))
+            .ok_or_else(unclear);
+    }
+
+    let mut day: Option<(chrono::NaiveDate, bool)> = None;
+    let mut time_tokens = Vec::new();
+    for token in tokens {
+        let today = now.date_naive();
+        let resolved = match token {
+            "today" => Some((today, false)),
+            "tonight" => Some((today, true)),
+            "tomorrow" => Some((today + chrono::Duration::days(1), false)),
+            "at" | "on" | "next" | "this" => continue,
+            other => parse_weekday(other).map(|weekday| {
+                let ahead = (weekday.num_days_from_monday() + 7
+                    - today.weekday().num_days_from_monday())
+                    % 7;
+                // A bare weekday always means the coming one, never today.
+                let ahead = if ahead == 0 { 7 } else { ahead };
+                (today + chrono::Duration::days(ahead as i64), false)
+            }),
+        };
+        match resolved {
+            Some(_) if day.is_some() => return Err(unclear()),
+            Some(d) => day = Some(d),
+            None => time_tokens.push(token),
+        }
+    }
+    if time_tokens.is_empty() {
+        return Err(format!(
+            "'{when}' has no time of day. Please say when exactly, e.g. 'tomorrow at 9am'."
+        ));
+    }
+    let evening = day.is_some_and(|(_, evening)| evening);
+    let time = parse_time_of_day(&time_tokens.concat(), evening)?.ok_or_else(unclear)?;
+
+    let to_utc = |date: chrono::NaiveDate| {
+        tz.from_local_datetime(&date.and_time(time))
+            .single()
+            .map(|dt| dt.with_timezone(&Utc))
+            .ok_or_else(|| {
+                format!(
+                    "'{when}' falls on a daylight-saving change in {}. Please pick another time.",
+                    tz.name()
+                )
+            })
+    };
+    let now_utc = now.with_timezone(&Utc);
+    match day {
+        Some((date, _)) => {
+            let run_at = to_utc(date)?;
+            if run_at <= now_utc {
+                return Err(format!(
+                    "'{when}' has already passed. Please give a time in the future."
+                ));
+            }
+            Ok(run_at)
+        }
+        // A time alone means its next occurrence.
+        None => {
+            let today = to_utc(now.date_naive())?;
+            if today > now_utc {
+                Ok(today)
+            } else {
+                to_utc(now.date_naive() + chrono::Duration::days(1))
+            }
+        }
+    }
+}
+
+/// "2 hours and 15 minutes", "an hour", "90 secs". Amounts too large to
+/// represent are treated as not understood.
+fn parse_relative_duration(tokens: &[&str]) -> Option<chrono::Duration> {
+    let mut total = chrono::Duration::zero();
+    let mut amount: Option<i64> = None;
+    let mut matched = false;
+    for token in tokens {
+        if *token == "and" {
+            continue;
+        }
+        if amount.is_none() {
+            amount = match *token {
+                "a" | "an" | "one" => Some(1),
+                n => Some(n.parse::<i64>().ok().filter(|n| *n > 0)?),
+            };
+            continue;
+        }
+        let n = amount.take()?;
+        let unit = token.trim_end_matches('s');
+        let step = match unit {
+            "sec" | "second" => chrono::TimeDelta::try_seconds(n),
+            "min" | "minute" => chrono::TimeDelta::try_minutes(n),
+            "hr" | "hour" => chrono::TimeDelta::try_hours(n),
+            "day" => chrono::TimeDelta::try_days(n),
+            "week" => chrono::TimeDelta::try_weeks(n),
+            _ => return None,
+        }?;
+        total = total.checked_add(&step)?;
+        matched = true;
+    }
+    (matched && amount.is_none()).then_some(total)
+}
+
+fn parse_weekday(token: &str) -> Option<chrono::Weekday> {
+    let day = match token {
+        "mon" | "monday" => chrono::Weekday::Mon,
+        "tue" | "tues" | "tuesday" => chrono::Weekday::Tue,
+        "wed" | "wednesday" => chrono::Weekday::Wed,
+        "thu" | "thur" | "thurs" | "thursday" => chrono::Weekday::Thu,
+        "fri" | "friday" => chrono::Weekday::Fri,
+        "sat" | "saturday" => chrono::Weekday::Sat,
+        "sun" | "sunday" => chrono::Weekday::Sun,
+        _ => return None,
+    };
+    Some(day)
+}
+
+/// "9am", "9:30pm", "21:00", "noon". A bare hour from 1 to 12 without am/pm
+/// is ambiguous unless `evening` ("tonight at 8"). `Ok(None)` means the text
+/// is not a time at all.
+fn parse_time_of_day(text: &str, evening: bool) -> Result<Option<chrono::NaiveTime>, String> {
+    match text {
+        "noon" | "midday" => return Ok(chrono::NaiveTime::from_hms_opt(12, 0, 0)),
+        "midnight" => return Ok(chrono::NaiveTime::from_hms_opt(0, 0, 0)),
+        _ => {}
+    }
+    let text = text.replace('.', "");
+    let (clock, meridiem) = if let Some(c) = text.strip_suffix("am") {
+        (c, Some(false))
+    } else if let Some(c) = text.strip_suffix("pm") {
+        (c, Some(true))
+    } else {
+        (text.as_str(), None)
+    };
+    let (hour, minute, has_minutes) = match clock.split_once(':') {
+        Some((h, m)) if m.len() == 2 => match (h.parse::<u32>(), m.parse::<u32>()) {
+            (Ok(h), Ok(m)) => (h, m, true),
+            _ => return Ok(None),
+        },
+        Some(_) => return Ok(None),
+        None => match clock.parse::<u32>() {
+            Ok(h) => (h, 0, false),
+            Err(_) => return Ok(None),
+        },
+    };
+    let hour = match meridiem {
+        Some(pm) => {
+            if !(1..=12).contains(&hour) {
+                return Ok(None);
+            }
+            hour % 12 + if pm { 12 } else { 0 }
+        }
+        None if evening && (1..12).contains(&hour) => hour + 12,
+        None if !has_minutes && (1..=12).contains(&hour) => {
+            return Err(format!(
+                "'{hour}' could be morning or evening. Please add am/pm or use 24-hour time (e.g. {hour}:00 or {}:00).",
+                hour % 12 + 12
+            ))
+        }
+        None => hour,
+    };
+    Ok(chrono::NaiveTime::from_hms_opt(hour, minute, 0))
+}
+
+pub struct RemindMeTool {
+    registry: Arc<ChannelRegistry>,
+    db: Arc<Database>,
+    default_timezone: String,
+    limits: ScheduleLimits,
+}
+
+impl RemindMeTool {
+    pub fn new(
+        registry: Arc<ChannelRegistry>,
+        db: Arc<Database>,
+        default_timezone: String,
+    ) -> Self {
+        RemindMeTool {
+            registry,
+            db,
+            default_timezone,
+            limits: ScheduleLimits::default(),
+        }
+    }
+
+    pub fn with_limits(mut self, limits: ScheduleLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+}
+
+#[async_trait]
+impl Tool for RemindMeTool {
+    fn name(&self) -> &str {
+        "remind_me"
+    }
+
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "remind_me".into(),
+            description: "Set a one-time reminder. 'when' can be natural language ('in 30 minutes', 'in 2 hours', 'tomorrow at 9am', 'friday 14:00', 'tonight at 8') or an ISO timestamp, interpreted in the configured timezone. At that time the reminder message is sent to the chat. Returns the resolved time; tell it to the user so they can confirm. If 'when' is ambiguous an error asks for clarification — ask the user instead of guessing.".into(),
+            input_schema: schema_object(
+                json!({
+                    "chat_id": {
+                        "type": "integer",
+                        "description": "The chat to send the reminder to"
+                    },
+                    "when": {
+                        "type": "string",
+                        "description": "When to remind, e.g. 'in 30 minutes', 'tomorrow at 9am' or 2026-03-01T09:00"
+                    },
+                    "message": {
+                        "type": "string",
+                        "description": "What to remind the user about"
+                    }
+                }),
+                &["chat_id", "when", "message"],
+            ),
+        }
+    }
+
+    async fn execute(&self, input: serde_json::Value) -> ToolResult {
+        let chat_id = match input.get("chat_id").and_then(|v| v.as_i64()) {
+            Some(id) => id,
+            None => return ToolResult::error("Missing required parameter: chat_id".into()),
+        };
+        let when = input.get("when").and_then(|v| v.as_str()).unwrap_or("");
+        let message = input
+            .get("message")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .unwrap_or("");
+        if message.is_empty() {
+            return ToolResult::error("Missing required parameter: message".into());
+        }
+        if let Err(e) = authorize_chat_access(&input, chat_id) {
+            return ToolResult::error(e);
+        }
+        if let Err(e) =
+            enforce_channel_policy(&self.registry, self.db.clone(), &input, chat_id).await
+        {
+            return ToolResult::error(e);
+        }
+
+        let tz: chrono_tz::Tz = match self.default_timezone.parse() {
+            Ok(tz) => tz,
+            Err(_) => {
+                return ToolResult::error(format!("Invalid timezone: {}", self.default_timezone))
+            }
+        };
+        let run_at = match parse_reminder_time(when, Utc::now().with_timezone(&tz)) {
+            Ok(dt) => dt,
+            Err(e) => return ToolResult::error(e),
+        };
+        if run_at <= Utc::now() {
+            return ToolResult::error("Reminder time must be in the future".into());
+        }
+        if let Err(e) = self.limits.check_once_lead(run_at) {
+            return ToolResult::error(e);
+        }
+        if let Err(e) = check_chat_task_limit(&self.db, &self.limits, chat_id).await {
+            return ToolResult::error(e);
+        }
+
+        let prompt = format!(
+            "Deliver this reminder the user asked you to send them now. Reply with just the reminder, phrased naturally: {message}"
+        );
+        let run_at_str = run_at.to_rfc3339();
+        let next_run = run_at_str.clone();
+        match call_blocking(self.db.clone(), move |db| {
+            db.create_scheduled_task_with_type(
+                chat_id,
+                TASK_TYPE_AGENT,
+                &prompt,
+                "once",
+                &run_at_str,
+                &next_run,
+            )
+        })
+        .await
+        {
+            Ok(id) => {
+                let local = run_at.with_timezone(&tz);
+                ToolResult::success(format!(
+                    "Reminder #{id} set for {} ({}), i.e. {} UTC. Tell the user this time so they can confirm it.",
+                    local.format("%A %Y-%m-%d %H:%M"),
+                    tz.name(),
+                    run_at.format("%Y-%m-%d %H:%M")
+                ))
+            }
+            Err(e) => ToolResult::error(format!("Failed to create reminder: {e}")),
+        }
+    }
+}
+
 // --- list_tasks ---
 
+/// How long completed one-shot tasks keep showing up in the task list.
+pub(crate) const COMPLETED_TASK_LIST_DAYS: i64 = 7;
+
 pub struct ListTasksTool {
     registry: Arc<ChannelRegistry>,
     db: Arc<Database>,
+    default_timezone: String,
 }
 
 impl ListTasksTool {
-    pub fn new(registry: Arc<ChannelRegistry>, db: Arc<Database>) -> Self {
-        ListTasksTool { registry, db }
+    pub fn new(
+        registry: Arc<ChannelRegistry>,
+        db: Arc<Database>,
+        default_timezone: String,
+    ) -> Self {
+        ListTasksTool {
+            registry,
+            db,
+            default_timezone,
+        }
+    }
+}
+
+/// Render a stored RFC 3339 timestamp in `tz`, or return it unchanged.
+fn format_stored_time(ts: &str, tz: chrono_tz::Tz) -> String {
+    chrono::DateTime::parse_from_rfc3339(ts)
+        .map(|dt| format_run_time(&dt.with_timezone(&tz)))
+        .unwrap_or_else(|_| ts.to_string())
+}
+
+fn format_duration_ms(ms: i64) -> String {
+    if ms < 1000 {
+        format!("{ms}ms")
+    } else {
+        format!("{:.1}s", ms as f64 / 1000.0)
     }
 }
 
+fn format_task_line(
+    task: &microclaw_storage::db::ScheduledTask,
+    last_run: Option<&microclaw_storage::db::TaskRunLog>,
+    tz: chrono_tz::Tz,
+) -> String {
+    let status = if task.status == "paused" {
+        "PAUSED"
+    } else {
+        task.status.as_str()
+    };
+    let kind = if task.task_type == TASK_TYPE_EXPORT {
+        "[export] "
+    } else {
+        ""
+    };
+    let cadence = if task.schedule_type == "cron" {
+        cron_human_hint(&task.schedule_value)
+            .map(|s| format!(" | cadence: {s}"))
+            .unwrap_or_default()
+    } else {
+        String::new()
+    };
+    let next = match task.status.as_str() {
+        "active" => format_stored_time(&task.next_run, tz),
+        "paused" => "- (paused)".to_string(),
+        _ => "-".to_string(),
+    };
+    let last = match last_run {
+        Some(run) => format!(
+            "{} in {} at {}",
+            if run.success { "ok" } else { "failed" },
+            format_duration_ms(run.duration_ms),
+            format_stored_time(&run.finished_at, tz)
+        ),
+        None => "never".to_string(),
+    };
+    format!(
+        "#{} [{}] {}{}\n    {} '{}'{} | next: {} | last: {}\n",
+        task.id,
+        status,
+        kind,
+        task.prompt,
+        task.schedule_type,
+        task.schedule_value,
+        cadence,
+        next,
+        last
+    )
+}
+
 #[async_trait]
 impl Tool for ListTasksTool {
     fn name(&self) -> &str {
@@ -301,7 +883,7 @@ impl Tool for ListTasksTool {
     fn definition(&self) -> ToolDefinition {
         ToolDefinition {
             name: "list_scheduled_tasks".into(),
-            description: "List all active and paused scheduled tasks for a chat.".into(),
+            description: "List the active and paused scheduled tasks for a chat, plus recently completed one-time tasks, with each task's next run time in the configured timezone and the status and duration of its last run.".into(),
             input_schema: schema_object(
                 json!({
                     "chat_id": {
@@ -328,30 +910,21 @@ impl Tool for ListTasksTool {
             return ToolResult::error(e);
         }
 
-        match call_blocking(self.db.clone(), move |db| db.get_tasks_for_chat(chat_id)).await {
+        let tz: chrono_tz::Tz = self.default_timezone.parse().unwrap_or(chrono_tz::UTC);
+        let completed_since =
+            (Utc::now() - chrono::Duration::days(COMPLETED_TASK_LIST_DAYS)).to_rfc3339();
+        match call_blocking(self.db.clone(), move |db| {
+            db.get_tasks_with_last_run(Some(chat_id), &completed_since)
+        })
+        .await
+        {
             Ok(tasks) => {
                 if tasks.is_empty() {
                     return ToolResult::success("No scheduled tasks found for this chat.".into());
                 }
-                let mut output = String::new();
-                for t in &tasks {
-                    let cadence = if t.schedule_type == "cron" {
-                        cron_human_hint(&t.schedule_value)
-                            .map(|s| format!(" | cadence: {s}"))
-                            .unwrap_or_default()
-                    } else {
-                        String::new()
-                    };
-                    output.push_str(&format!(
-                        "#{} [{}] {} | {} '{}'{} | next: {}\n",
-                        t.id,
-                        t.status,
-                        t.prompt,
-                        t.schedule_type,
-                        t.schedule_value,
-                        cadence,
-                        t.next_run
-                    ));
+                let mut output = format!("{} tasks (times in {}):\n", tasks.len(), tz.name());
+                for (task, last_run) in &tasks {
+                    output.push_str(&format_task_line(task, last_run.as_ref(), tz));
                 }
                 ToolResult::success(output)
             }
@@ -928,6 +1501,21 @@ mod tests {
         assert!(result.unwrap_err().contains("Invalid cron"));
     }
 
+    #[test]
+    fn test_upcoming_runs_in_timezone_and_five_field_hint() {
+        let runs = upcoming_runs("0 30 9 * * *", "Asia/Shanghai", 3).unwrap();
+        assert_eq!(runs.len(), 3);
+        for pair in runs.windows(2) {
+            assert_eq!(pair[1] - pair[0], chrono::Duration::days(1));
+        }
+        let formatted = format_run_time(&runs[0]);
+        assert!(formatted.ends_with("09:30:00 CST"), "{formatted}");
+
+        let err = upcoming_runs("30 9 * * *", "UTC", 3).unwrap_err();
+        assert!(err.contains("Invalid cron expression '30 9 * * *'"));
+        assert!(err.contains("\"0 30 9 * * *\""));
+    }
+
     #[test]
     fn test_compute_next_run_invalid_timezone() {
         let result = compute_next_run("0 */5 * * * *", "Not/A/Zone");
@@ -963,6 +1551,132 @@ mod tests {
         assert!(result.content.contains("scheduled"));
         assert!(result.content.contains("Next run"));
         assert!(result.content.contains("Cron interpretation"));
+        assert!(result.content.contains("Upcoming runs (UTC):"));
+        assert_eq!(result.content.matches("\n- ").count(), 3);
+        cleanup(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_schedule_task_rejects_interval_below_minimum() {
+        let (db, dir) = test_db();
+        let tool = ScheduleTaskTool::new(test_registry(), db.clone(), "UTC".into()).with_limits(
+            ScheduleLimits {
+                min_interval_secs: 60,
+                ..ScheduleLimits::default()
+            },
+        );
+        let result = tool
+            .execute(json!({
+                "chat_id": 100,
+                "prompt": "ping",
+                "schedule_type": "cron",
+                "schedule_value": "* * * * * *"
+            }))
+            .await;
+        assert!(result.is_error);
+        assert!(result.content.contains("minimum interval is 60s"));
+        assert!(db.get_tasks_for_chat(100).unwrap().is_empty());
+
+        let relaxed = ScheduleTaskTool::new(test_registry(), db.clone(), "UTC".into());
+        let result = relaxed
+            .execute(json!({
+                "chat_id": 100,
+                "prompt": "ping",
+                "schedule_type": "cron",
+                "schedule_value": "* * * * * *"
+            }))
+            .await;
+        assert!(!result.is_error, "Error: {}", result.content);
+        cleanup(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_schedule_task_enforces_max_tasks_per_chat() {
+        let (db, dir) = test_db();
+        let tool = ScheduleTaskTool::new(test_registry(), db.clone(), "UTC".into()).with_limits(
+            ScheduleLimits {
+                max_tasks_per_chat: 2,
+                ..ScheduleLimits::default()
+            },
+        );
+        let input = json!({
+            "chat_id": 100,
+            "prompt": "report",
+            "schedule_type": "cron",
+            "schedule_value": "0 0 9 * * *"
+        });
+        assert!(!tool.execute(input.clone()).await.is_error);
+        assert!(!tool.execute(input.clone()).await.is_error);
+        let result = tool.execute(input).await;
+        assert!(result.is_error);
+        assert!(result.content.contains("limit 2"));
+        assert_eq!(db.get_tasks_for_chat(100).unwrap().len(), 2);
+        cleanup(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_schedule_task_rejects_once_beyond_max_lead() {
+        let (db, dir) = test_db();
+        let tool =
+            ScheduleTaskTool::new(test_registry(), db, "UTC".into()).with_limits(ScheduleLimits {
+                max_once_lead_days: 365,
+                ..ScheduleLimits::default()
+            });
+        let far = (Utc::now() + chrono::Duration::days(400)).to_rfc3339();
+        let result = tool
+            .execute(json!({
+                "chat_id": 100,
+                "prompt": "later",
+                "schedule_type": "once",
+                "schedule_value": far
+            }))
+            .await;
+        assert!(result.is_error);
+        assert!(result.content.contains("365 days"));
+        cleanup(&dir);
+    }
+
+    #[test]
+    fn test_once_lead_out_of_range_means_no_limit() {
+        let far = Utc::now() + chrono::Duration::days(400);
+        for max_once_lead_days in [100_000_000, i64::MAX as u64 / 86_400, u64::MAX] {
+            let limits = ScheduleLimits {
+                max_once_lead_days,
+                ..ScheduleLimits::default()
+            };
+            assert!(limits.check_once_lead(far).is_ok());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_schedule_export_task() {
+        let (db, dir) = test_db();
+        let tool = ScheduleTaskTool::new(test_registry(), db.clone(), "UTC".into());
+        let result = tool
+            .execute(json!({
+                "chat_id": 100,
+                "task_type": "export",
+                "schedule_type": "cron",
+                "schedule_value": "0 0 9 * * *"
+            }))
+            .await;
+        assert!(!result.is_error, "Error: {}", result.content);
+        assert!(result.content.starts_with("Export task #"));
+
+        let tasks = db.get_tasks_for_chat(100).unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].task_type, TASK_TYPE_EXPORT);
+        assert_eq!(tasks[0].prompt, "Export chat history to markdown");
+
+        let invalid = tool
+            .execute(json!({
+                "chat_id": 100,
+                "task_type": "backup",
+                "schedule_type": "cron",
+                "schedule_value": "0 0 9 * * *"
+            }))
+            .await;
+        assert!(invalid.is_error);
         cleanup(&dir);
     }
 
@@ -1092,7 +1806,7 @@ mod tests {
     #[tokio::test]
     async fn test_list_tasks_empty() {
         let (db, dir) = test_db();
-        let tool = ListTasksTool::new(test_registry(), db);
+        let tool = ListTasksTool::new(test_registry(), db, "UTC".into());
         let result = tool.execute(json!({"chat_id": 100})).await;
         assert!(!result.is_error);
         assert!(result.content.contains("No scheduled tasks"));
@@ -1113,7 +1827,7 @@ mod tests {
         )
         .unwrap();
 
-        let tool = ListTasksTool::new(test_registry(), db);
+        let tool = ListTasksTool::new(test_registry(), db, "UTC".into());
         let result = tool.execute(json!({"chat_id": 100})).await;
         assert!(!result.is_error);
         assert!(result.content.contains("task A"));
@@ -1123,6 +1837,63 @@ mod tests {
         cleanup(&dir);
     }
 
+    #[tokio::test]
+    async fn test_list_tasks_shows_local_times_last_run_and_states() {
+        let (db, dir) = test_db();
+        let active = db
+            .create_scheduled_task(100, "digest", "cron", "0 0 9 * * *", "2024-01-01T01:00:00Z")
+            .unwrap();
+        db.log_task_run(
+            active,
+            100,
+            "2023-12-31T01:00:00Z",
+            "2023-12-31T01:00:02Z",
+            2500,
+            false,
+            Some("boom"),
+        )
+        .unwrap();
+        let paused = db
+            .create_scheduled_task(
+                100,
+                "paused one",
+                "cron",
+                "0 0 * * * *",
+                "2024-01-01T00:00:00Z",
+            )
+            .unwrap();
+        db.update_task_status(paused, "paused").unwrap();
+        let fired = db
+            .create_scheduled_task(
+                100,
+                "one shot",
+                "once",
+                "2024-01-01T00:00:00Z",
+                "2024-01-01T00:00:00Z",
+            )
+            .unwrap();
+        db.update_task_after_run(fired, &Utc::now().to_rfc3339(), None)
+            .unwrap();
+
+        let tool = ListTasksTool::new(test_registry(), db, "Asia/Shanghai".into());
+        let result = tool.execute(json!({"chat_id": 100})).await;
+        assert!(!result.is_error, "{}", result.content);
+        assert!(result
+            .content
+            .starts_with("3 tasks (times in Asia/Shanghai):"));
+        assert!(result
+            .content
+            .contains("next: Mon 2024-01-01 09:00:00 CST | last: failed in 2.5s at Sun 2023-12-31 09:00:02 CST"));
+        assert!(result
+            .content
+            .contains(&format!("#{paused} [PAUSED] paused one")));
+        assert!(result.content.contains("next: - (paused) | last: never"));
+        assert!(result
+            .content
+            .contains(&format!("#{fired} [completed] one shot")));
+        cleanup(&dir);
+    }
+
     #[tokio::test]
     async fn test_pause_and_resume_task() {
         let (db, dir) = test_db();
@@ -1426,4 +2197,121 @@ mod tests {
         assert_eq!(task.status, "paused");
         cleanup(&dir);
     }
+
+    /// Wednesday 2026-03-04 10:00 in Shanghai (UTC+8, no DST).
+    fn reminder_now() -> chrono::DateTime<chrono_tz::Tz> {
+        chrono_tz::Asia::Shanghai
+            .with_ymd_and_hms(2026, 3, 4, 10, 0, 0)
+            .unwrap()
+    }
+
+    fn resolve(when: &str) -> Result<String, String> {
+        parse_reminder_time(when, reminder_now()).map(|dt| dt.to_rfc3339())
+    }
+
+    #[test]
+    fn test_parse_reminder_time_relative_and_natural() {
+        assert_eq!(
+            resolve("in 30 minutes").unwrap(),
+            "2026-03-04T02:30:00+00:00"
+        );
+        assert_eq!(
+            resolve("in 2 hours and 15 minutes").unwrap(),
+            "2026-03-04T04:15:00+00:00"
+        );
+        assert_eq!(resolve("in an hour").unwrap(), "2026-03-04T03:00:00+00:00");
+        assert_eq!(
+            resolve("in half an hour").unwrap(),
+            "2026-03-04T02:30:00+00:00"
+        );
+        assert_eq!(
+            resolve("tomorrow at 9am").unwrap(),
+            "2026-03-05T01:00:00+00:00"
+        );
+        assert_eq!(
+            resolve("Tomorrow 9:30 PM").unwrap(),
+            "2026-03-05T13:30:00+00:00"
+        );
+        assert_eq!(
+            resolve("friday 14:00").unwrap(),
+            "2026-03-06T06:00:00+00:00"
+        );
+        assert_eq!(
+            resolve("next wednesday at noon").unwrap(),
+            "2026-03-11T04:00:00+00:00"
+        );
+        assert_eq!(
+            resolve("tonight at 8").unwrap(),
+            "2026-03-04T12:00:00+00:00"
+        );
+        // A time alone is its next occurrence.
+        assert_eq!(resolve("at 5pm").unwrap(), "2026-03-04T09:00:00+00:00");
+        assert_eq!(resolve("9am").unwrap(), "2026-03-05T01:00:00+00:00");
+        assert_eq!(
+            resolve("2026-03-10T09:00").unwrap(),
+            "2026-03-10T01:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_parse_reminder_time_rejects_ambiguous_input() {
+        assert!(resolve("tomorrow at 9").unwrap_err().contains("am/pm"));
+        assert!(resolve("tomorrow").unwrap_err().contains("no time of day"));
+        assert!(resolve("today at 8am")
+            .unwrap_err()
+            .contains("already passed"));
+        for when in [
+            "sometime soon",
+            "in 5 parsecs",
+            "in 10",
+            "monday tuesday 9am",
+            "",
+        ] {
+            assert!(resolve(when).is_err(), "{when}");
+        }
+    }
+
+    #[test]
+    fn test_parse_reminder_time_rejects_out_of_range_amounts() {
+        for when in [
+            "in 99999999 days",
+            "in 9223372036854775807 weeks",
+            "in 9000000000000000 seconds and 9000000000000000 seconds",
+        ] {
+            assert!(
+                resolve(when).unwrap_err().contains("Could not understand"),
+                "{when}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_remind_me_creates_one_shot_task() {
+        let (db, dir) = test_db();
+        let tool = RemindMeTool::new(test_registry(), db.clone(), "Asia/Shanghai".into());
+        let result = tool
+            .execute(json!({
+                "chat_id": 100,
+                "when": "in 30 minutes",
+                "message": "take the laundry out"
+            }))
+            .await;
+        assert!(!result.is_error, "{}", result.content);
+        assert!(result.content.contains("(Asia/Shanghai)"));
+        let tasks = db.get_tasks_for_chat(100).unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].schedule_type, "once");
+        assert_eq!(tasks[0].task_type, TASK_TYPE_AGENT);
+        assert!(tasks[0].prompt.contains("take the laundry out"));
+        let run_at = chrono::DateTime::parse_from_rfc3339(&tasks[0].next_run).unwrap();
+        let lead = run_at.with_timezone(&Utc) - Utc::now();
+        assert!(lead > chrono::Duration::minutes(29) && lead <= chrono::Duration::minutes(30));
+
+        let unclear = tool
+            .execute(json!({"chat_id": 100, "when": "later", "message": "x"}))
+            .await;
+        assert!(unclear.is_error);
+        assert!(unclear.content.contains("Please clarify"));
+        cleanup(&dir);
+    }
 }
@@ -7,12 +7,23 @@ use tracing::{info, warn};
 
 use super::{authorize_chat_access, schema_object, Tool, ToolResult};
 use microclaw_channels::channel::{
-    deliver_and_store_bot_message, enforce_channel_policy, get_required_chat_routing,
+    deliver_and_store_editable_bot_message, enforce_channel_policy, get_required_chat_routing,
 };
 use microclaw_channels::channel_adapter::ChannelRegistry;
 use microclaw_core::llm_types::ToolDefinition;
 use microclaw_storage::db::{call_blocking, Database, StoredMessage};
 
+/// Tool result for a delivered message. `message_id` is set when the channel
+/// supports editing it later with `edit_message`.
+pub(crate) fn sent_result(chat_id: i64, message_id: Option<String>) -> String {
+    json!({
+        "status": "sent",
+        "chat_id": chat_id,
+        "message_id": message_id,
+    })
+    .to_string()
+}
+
 pub struct SendMessageTool {
     registry: Arc<ChannelRegistry>,
     db: Arc<Database>,
@@ -78,7 +89,7 @@ impl Tool for SendMessageTool {
     fn definition(&self) -> ToolDefinition {
         ToolDefinition {
             name: "send_message".into(),
-            description: "Send a message mid-conversation. Supports text for all channels, and attachments for Telegram/Discord/Slack via attachment_path.".into(),
+            description: "Send a message mid-conversation. Supports text for all channels, and attachments for Telegram/Discord/Slack via attachment_path. Returns JSON with the sent message_id when the channel supports edit_message (otherwise null).".into(),
             input_schema: schema_object(
                 json!({
                     "chat_id": {
@@ -213,7 +224,7 @@ impl Tool for SendMessageTool {
                         );
                         return ToolResult::error(e);
                     }
-                    ToolResult::success("Attachment sent successfully.".into())
+                    ToolResult::success(sent_result(chat_id, None))
                 }
                 Err(e) => {
                     warn!(
@@ -231,7 +242,7 @@ impl Tool for SendMessageTool {
                     Ok(routing) => self.bot_username_for_channel(&routing.channel_name),
                     Err(_) => self.default_bot_username.clone(),
                 };
-            match deliver_and_store_bot_message(
+            match deliver_and_store_editable_bot_message(
                 &self.registry,
                 self.db.clone(),
                 &sender_name,
@@ -240,9 +251,9 @@ impl Tool for SendMessageTool {
             )
             .await
             {
-                Ok(_) => {
+                Ok(message_id) => {
                     info!("send_message text sent: chat_id={}", chat_id);
-                    ToolResult::success("Message sent successfully.".into())
+                    ToolResult::success(sent_result(chat_id, message_id))
                 }
                 Err(e) => {
                     warn!(
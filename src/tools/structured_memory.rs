@@ -3,6 +3,7 @@ use serde_json::json;
 use std::sync::Arc;
 use tracing::info;
 
+use crate::config::{normalize_memory_category, Config, MemoryCategory};
 use crate::memory_backend::MemoryBackend;
 use microclaw_core::llm_types::ToolDefinition;
 use microclaw_storage::db::Database;
@@ -184,12 +185,24 @@ impl Tool for StructuredMemoryDeleteTool {
 
 pub struct StructuredMemoryUpdateTool {
     memory_backend: Arc<MemoryBackend>,
+    categories: Vec<MemoryCategory>,
+    default_category: String,
 }
 
 impl StructuredMemoryUpdateTool {
     pub fn new(db: Arc<Database>, memory_backend: Arc<MemoryBackend>) -> Self {
         let _ = db;
-        Self { memory_backend }
+        Self {
+            memory_backend,
+            categories: MemoryCategory::defaults(),
+            default_category: "KNOWLEDGE".into(),
+        }
+    }
+
+    pub fn with_categories(mut self, config: &Config) -> Self {
+        self.categories = config.memory_categories.clone();
+        self.default_category = config.memory_default_category.clone();
+        self
     }
 }
 
@@ -200,6 +213,7 @@ impl Tool for StructuredMemoryUpdateTool {
     }
 
     fn definition(&self) -> ToolDefinition {
+        let names: Vec<&str> = self.categories.iter().map(|c| c.name.as_str()).collect();
         ToolDefinition {
             name: "structured_memory_update".into(),
             description: "Update the content or category of an existing structured memory. Use this to correct outdated or wrong memories instead of creating a duplicate.".into(),
@@ -215,8 +229,8 @@ impl Tool for StructuredMemoryUpdateTool {
                     },
                     "category": {
                         "type": "string",
-                        "description": "Category: PROFILE, KNOWLEDGE, or EVENT",
-                        "enum": ["PROFILE", "KNOWLEDGE", "EVENT"]
+                        "description": format!("Category: one of {}", names.join(", ")),
+                        "enum": names
                     }
                 }),
                 &["id", "content"],
@@ -263,18 +277,12 @@ impl Tool for StructuredMemoryUpdateTool {
             }
         }
 
-        let category = input
-            .get("category")
-            .and_then(|v| v.as_str())
-            .unwrap_or(&mem.category)
-            .to_string();
-
-        let valid_categories = ["PROFILE", "KNOWLEDGE", "EVENT"];
-        if !valid_categories.contains(&category.as_str()) {
-            return ToolResult::error(format!(
-                "Invalid category '{category}'. Must be one of: PROFILE, KNOWLEDGE, EVENT"
-            ));
-        }
+        let category = match input.get("category").and_then(|v| v.as_str()) {
+            Some(raw) => {
+                normalize_memory_category(&self.categories, &self.default_category, Some(raw))
+            }
+            None => mem.category.clone(),
+        };
 
         info!("structured_memory_update: id={id}");
 
@@ -398,4 +406,41 @@ mod tests {
         assert!(result.is_error);
         assert!(result.content.contains("300 character"));
     }
+
+    #[tokio::test]
+    async fn test_update_normalizes_category() {
+        let db = test_db();
+        let id = db
+            .insert_memory(Some(100), "User lives in Tokyo", "PROFILE")
+            .unwrap();
+        let mut config = Config::test_defaults();
+        config.memory_categories.push(MemoryCategory {
+            name: "TASK".into(),
+            description: "open work items".into(),
+        });
+        let tool = StructuredMemoryUpdateTool::new(db.clone(), test_backend(db.clone()))
+            .with_categories(&config);
+        assert!(
+            tool.definition().input_schema["properties"]["category"]["enum"]
+                .as_array()
+                .unwrap()
+                .contains(&json!("TASK"))
+        );
+
+        let auth = json!({"caller_chat_id": 100, "control_chat_ids": []});
+        let result = tool
+            .execute(json!({"id": id, "content": "Finish the report", "category": " task ", "__microclaw_auth": auth}))
+            .await;
+        assert!(!result.is_error, "{}", result.content);
+        assert_eq!(db.get_memory_by_id(id).unwrap().unwrap().category, "TASK");
+
+        let result = tool
+            .execute(json!({"id": id, "content": "Finish the report", "category": "gossip", "__microclaw_auth": auth}))
+            .await;
+        assert!(!result.is_error, "{}", result.content);
+        assert_eq!(
+            db.get_memory_by_id(id).unwrap().unwrap().category,
+            "KNOWLEDGE"
+        );
+    }
 }
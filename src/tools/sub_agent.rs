@@ -1,30 +1,51 @@
 use async_trait::async_trait;
 use serde_json::json;
+use std::future::Future;
 use std::sync::Arc;
+use tokio::sync::mpsc::UnboundedSender;
 use tracing::info;
 
-use super::{auth_context_from_input, schema_object, Tool, ToolRegistry, ToolResult};
+use super::{
+    auth_context_from_input, schema_object, Tool, ToolAuthContext, ToolRegistry, ToolResult,
+};
+use crate::agent_engine::AgentEvent;
 use crate::config::Config;
 #[cfg(test)]
 use crate::config::WorkingDirIsolation;
+use crate::hot_reload::Live;
 use microclaw_core::llm_types::{
     ContentBlock, Message, MessageContent, ResponseContentBlock, ToolDefinition,
 };
 use microclaw_storage::db::{call_blocking, Database};
 
-const MAX_SUB_AGENT_ITERATIONS: usize = 10;
+tokio::task_local! {
+    static PARENT_EVENTS: UnboundedSender<AgentEvent>;
+}
+
+/// Run `fut` with `events` as the channel that sub-agent tool activity is
+/// forwarded to. `None` keeps sub-agent runs silent.
+pub(crate) async fn with_parent_events<F: Future>(
+    events: Option<UnboundedSender<AgentEvent>>,
+    fut: F,
+) -> F::Output {
+    match events {
+        Some(events) => PARENT_EVENTS.scope(events, fut).await,
+        None => fut.await,
+    }
+}
+
+fn forward_event(event: AgentEvent) {
+    let _ = PARENT_EVENTS.try_with(|events| events.send(event));
+}
 
 pub struct SubAgentTool {
-    config: Config,
+    config: Arc<Live<Config>>,
     db: Arc<Database>,
 }
 
 impl SubAgentTool {
-    pub fn new(config: &Config, db: Arc<Database>) -> Self {
-        SubAgentTool {
-            config: config.clone(),
-            db,
-        }
+    pub fn new(config: Arc<Live<Config>>, db: Arc<Database>) -> Self {
+        SubAgentTool { config, db }
     }
 }
 
@@ -63,11 +84,64 @@ impl Tool for SubAgentTool {
 
         let context = input.get("context").and_then(|v| v.as_str()).unwrap_or("");
 
-        info!("Sub-agent starting task: {}", task);
-
-        let llm = crate::llm::create_provider(&self.config);
-        let tools = ToolRegistry::new_sub_agent(&self.config, self.db.clone());
-        let tool_defs = tools.definitions().to_vec();
+        // One snapshot for the whole run, taken after any reload.
+        let config = self.config.load();
+        let depth = auth_context.as_ref().map_or(0, |a| a.sub_agent_depth);
+        let max_depth = config.sub_agent_max_depth;
+        if depth >= max_depth {
+            return ToolResult::error(format!(
+                "sub_agent depth limit reached ({max_depth}); complete this task directly instead of delegating it."
+            ))
+            .with_error_type("sub_agent_depth_limit");
+        }
+        // Tool calls inside the run carry the new depth. Without an auth
+        // context the depth cannot be tracked, so nesting is not offered.
+        let child_auth = auth_context.as_ref().map(|auth| ToolAuthContext {
+            sub_agent_depth: depth + 1,
+            ..auth.clone()
+        });
+        let event_prefix = "sub_agent/".repeat(depth + 1);
+
+        info!(depth = depth + 1, "Sub-agent starting task: {}", task);
+
+        // Bill the sub-agent's calls to the calling chat's tenant key too.
+        let mut llm_config = (*config).clone();
+        if let Some(auth) = auth_context.as_ref() {
+            let provider = config
+                .resolve_llm_provider_profile(&config.llm_provider)
+                .map_or_else(|| config.llm_provider.clone(), |p| p.provider);
+            if let Some(api_key) = crate::agent_engine::tenant_api_key_for_chat(
+                &config,
+                self.db.clone(),
+                &auth.caller_channel,
+                auth.caller_chat_id,
+                &provider,
+            )
+            .await
+            {
+                llm_config.api_key = api_key;
+            }
+        }
+        let llm = crate::llm::create_provider(&llm_config);
+        let mut tools = ToolRegistry::new_sub_agent(self.config.clone(), self.db.clone());
+        if child_auth.is_some() && depth + 1 < max_depth {
+            tools.add_tool(Box::new(SubAgentTool::new(
+                self.config.clone(),
+                self.db.clone(),
+            )));
+        }
+        // Tools switched off in the calling chat (e.g. `/web off`) stay off here.
+        let disabled_tools = match auth_context.as_ref() {
+            Some(auth) => {
+                crate::chat_commands::disabled_tools_for_chat(self.db.clone(), auth.caller_chat_id)
+                    .await
+            }
+            None => Vec::new(),
+        };
+        let tool_defs = crate::agent_engine::without_disabled_tools(
+            tools.definitions().to_vec(),
+            &disabled_tools,
+        );
 
         let system_prompt = "You are a sub-agent assistant. Complete the given task thoroughly and return a clear, concise result. You have access to tools for file operations, search, and web access. Focus on the task and provide actionable output.".to_string();
 
@@ -82,7 +156,7 @@ impl Tool for SubAgentTool {
             content: MessageContent::Text(user_content),
         }];
 
-        for iteration in 0..MAX_SUB_AGENT_ITERATIONS {
+        for iteration in 0..config.sub_agent_max_iterations {
             let response = match llm
                 .send_message(&system_prompt, messages.clone(), Some(tool_defs.clone()))
                 .await
@@ -99,18 +173,24 @@ impl Tool for SubAgentTool {
                     .as_ref()
                     .map(|a| a.caller_channel.clone())
                     .unwrap_or_else(|| "sub_agent".to_string());
-                let provider = self.config.llm_provider.clone();
-                let model = self.config.model.clone();
+                let (provider, model) = match &response.served_by {
+                    Some(served_by) => (served_by.provider.clone(), served_by.model.clone()),
+                    None => (config.llm_provider.clone(), config.model.clone()),
+                };
                 let input_tokens = i64::from(usage.input_tokens);
                 let output_tokens = i64::from(usage.output_tokens);
+                let cache_read_tokens = i64::from(usage.cache_read_input_tokens);
+                let cache_write_tokens = i64::from(usage.cache_creation_input_tokens);
                 let _ = call_blocking(self.db.clone(), move |db| {
-                    db.log_llm_usage(
+                    db.log_llm_usage_with_cache(
                         chat_id,
                         &caller_channel,
                         &provider,
                         &model,
                         input_tokens,
                         output_tokens,
+                        cache_read_tokens,
+                        cache_write_tokens,
                         "sub_agent",
                     )
                     .map(|_| ())
@@ -146,13 +226,17 @@ impl Tool for SubAgentTool {
                         ResponseContentBlock::Text { text } => {
                             Some(ContentBlock::Text { text: text.clone() })
                         }
-                        ResponseContentBlock::ToolUse { id, name, input } => {
-                            Some(ContentBlock::ToolUse {
-                                id: id.clone(),
-                                name: name.clone(),
-                                input: input.clone(),
-                            })
-                        }
+                        ResponseContentBlock::ToolUse {
+                            id,
+                            name,
+                            input,
+                            thought_signature,
+                        } => Some(ContentBlock::ToolUse {
+                            id: id.clone(),
+                            name: name.clone(),
+                            input: input.clone(),
+                            thought_signature: thought_signature.clone(),
+                        }),
                         ResponseContentBlock::Other => None,
                     })
                     .collect();
@@ -164,17 +248,45 @@ impl Tool for SubAgentTool {
 
                 let mut tool_results = Vec::new();
                 for block in &response.content {
-                    if let ResponseContentBlock::ToolUse { id, name, input } = block {
+                    if let ResponseContentBlock::ToolUse {
+                        id, name, input, ..
+                    } = block
+                    {
                         info!(
                             "Sub-agent executing tool: {} (iteration {})",
                             name,
                             iteration + 1
                         );
-                        let result = if let Some(ref auth) = auth_context {
+                        let event_name = format!("{event_prefix}{name}");
+                        forward_event(AgentEvent::ToolStart {
+                            name: event_name.clone(),
+                            input: input.clone(),
+                        });
+                        let started = std::time::Instant::now();
+                        let result = if disabled_tools.iter().any(|d| d == name) {
+                            ToolResult::error(format!("tool '{name}' is disabled in this chat"))
+                        } else if let Some(ref auth) = child_auth {
                             tools.execute_with_auth(name, input.clone(), auth).await
                         } else {
                             tools.execute(name, input.clone()).await
                         };
+                        let preview = if result.content.chars().count() > 160 {
+                            let clipped = result.content.chars().take(160).collect::<String>();
+                            format!("{clipped}...")
+                        } else {
+                            result.content.clone()
+                        };
+                        forward_event(AgentEvent::ToolResult {
+                            name: event_name,
+                            is_error: result.is_error,
+                            preview,
+                            duration_ms: result
+                                .duration_ms
+                                .unwrap_or_else(|| started.elapsed().as_millis()),
+                            status_code: result.status_code,
+                            bytes: result.bytes,
+                            error_type: result.error_type.clone(),
+                        });
                         tool_results.push(ContentBlock::ToolResult {
                             tool_use_id: id.clone(),
                             content: result.content,
@@ -238,9 +350,13 @@ mod tests {
         Arc::new(Database::new(dir.to_str().unwrap()).unwrap())
     }
 
+    fn live(config: Config) -> Arc<Live<Config>> {
+        Arc::new(Live::from(config))
+    }
+
     #[test]
     fn test_sub_agent_tool_name_and_definition() {
-        let tool = SubAgentTool::new(&test_config(), test_db());
+        let tool = SubAgentTool::new(live(test_config()), test_db());
         assert_eq!(tool.name(), "sub_agent");
         let def = tool.definition();
         assert_eq!(def.name, "sub_agent");
@@ -252,9 +368,55 @@ mod tests {
         assert_eq!(required[0], "task");
     }
 
+    fn auth_input(depth: usize) -> serde_json::Value {
+        json!({
+            "task": "look around",
+            "__microclaw_auth": {
+                "caller_channel": "web",
+                "caller_chat_id": 1,
+                "control_chat_ids": [],
+                "sub_agent_depth": depth
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_sub_agent_at_max_depth_refuses_to_spawn() {
+        let mut config = test_config();
+        config.sub_agent_max_depth = 2;
+        let tool = SubAgentTool::new(live(config.clone()), test_db());
+        let result = tool.execute(auth_input(2)).await;
+        assert!(result.is_error);
+        assert_eq!(result.error_type.as_deref(), Some("sub_agent_depth_limit"));
+        assert!(result.content.contains("depth limit reached (2)"));
+
+        config.sub_agent_max_depth = 0;
+        let tool = SubAgentTool::new(live(config.clone()), test_db());
+        let result = tool.execute(json!({"task": "look around"})).await;
+        assert_eq!(result.error_type.as_deref(), Some("sub_agent_depth_limit"));
+    }
+
+    #[tokio::test]
+    async fn test_forwarded_events_reach_parent_only_inside_scope() {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        with_parent_events(Some(tx), async {
+            forward_event(AgentEvent::ToolStart {
+                name: "sub_agent/bash".into(),
+                input: json!({}),
+            });
+        })
+        .await;
+        forward_event(AgentEvent::TextDelta { delta: "x".into() });
+        match rx.recv().await {
+            Some(AgentEvent::ToolStart { name, .. }) => assert_eq!(name, "sub_agent/bash"),
+            other => panic!("unexpected event: {other:?}"),
+        }
+        assert!(rx.recv().await.is_none());
+    }
+
     #[tokio::test]
     async fn test_sub_agent_missing_task() {
-        let tool = SubAgentTool::new(&test_config(), test_db());
+        let tool = SubAgentTool::new(live(test_config()), test_db());
         let result = tool.execute(json!({})).await;
         assert!(result.is_error);
         assert!(result.content.contains("Missing required parameter: task"));
@@ -263,7 +425,7 @@ mod tests {
     #[test]
     fn test_sub_agent_restricted_registry_tool_count() {
         let config = test_config();
-        let registry = ToolRegistry::new_sub_agent(&config, test_db());
+        let registry = ToolRegistry::new_sub_agent(live(config), test_db());
         let defs = registry.definitions();
         // Keep this test resilient to safe, read-only tool additions in restricted mode.
         assert!(defs.len() >= 12);
@@ -272,7 +434,7 @@ mod tests {
     #[test]
     fn test_sub_agent_restricted_registry_excluded_tools() {
         let config = test_config();
-        let registry = ToolRegistry::new_sub_agent(&config, test_db());
+        let registry = ToolRegistry::new_sub_agent(live(config), test_db());
         let defs = registry.definitions();
         let names: Vec<&str> = defs.iter().map(|d| d.name.as_str()).collect();
 
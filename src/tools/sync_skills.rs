@@ -17,7 +17,7 @@ impl SyncSkillsTool {
         }
     }
 
-    async fn fetch_skill_content(
+    pub(crate) async fn fetch_skill_content(
         source_repo: &str,
         skill_name: &str,
         git_ref: &str,
@@ -67,7 +67,7 @@ impl SyncSkillsTool {
         ))
     }
 
-    fn split_frontmatter(content: &str) -> (Option<serde_yaml::Value>, String) {
+    pub(crate) fn split_frontmatter(content: &str) -> (Option<serde_yaml::Value>, String) {
         let trimmed = content.trim_start_matches('\u{feff}');
         if !trimmed.starts_with("---\n") && !trimmed.starts_with("---\r\n") {
             return (None, trimmed.to_string());
@@ -110,6 +110,13 @@ impl SyncSkillsTool {
         }
     }
 
+    /// SHA-256 of an upstream SKILL.md, recorded at sync time so update checks
+    /// can tell whether upstream content changed under the same ref.
+    pub(crate) fn content_hash(raw: &str) -> String {
+        use sha2::{Digest, Sha256};
+        hex::encode(Sha256::digest(raw.trim().as_bytes()))
+    }
+
     fn str_seq(value: Option<&serde_yaml::Value>) -> Vec<String> {
         match value {
             Some(serde_yaml::Value::Sequence(items)) => items
@@ -121,7 +128,7 @@ impl SyncSkillsTool {
         }
     }
 
-    fn normalize_skill_markdown(
+    pub(crate) fn normalize_skill_markdown(
         raw: &str,
         source_repo: &str,
         git_ref: &str,
@@ -155,6 +162,8 @@ impl SyncSkillsTool {
             format!("description: {}", description),
             format!("source: remote:{}", source_repo),
             format!("version: {}", git_ref),
+            format!("upstream_skill: {}", skill_name),
+            format!("upstream_sha256: {}", Self::content_hash(raw)),
             format!("updated_at: {}", Utc::now().to_rfc3339()),
             "license: Proprietary. LICENSE.txt has complete terms".to_string(),
         ];
@@ -427,5 +436,10 @@ mod tests {
         assert!(out.contains("source: remote:vercel-labs/skills"));
         assert!(out.contains("version: main"));
         assert!(out.contains("updated_at:"));
+        assert!(out.contains("upstream_skill: demo"));
+        assert!(out.contains(&format!(
+            "upstream_sha256: {}",
+            SyncSkillsTool::content_hash(raw)
+        )));
     }
 }
@@ -1,6 +1,6 @@
 use async_trait::async_trait;
 use microclaw_tools::web_content_validation::WebContentValidationConfig;
-use microclaw_tools::web_fetch::WebFetchUrlValidationConfig;
+use microclaw_tools::web_fetch::{WebFetchRetryConfig, WebFetchUrlValidationConfig};
 use serde_json::json;
 
 use super::{schema_object, Tool, ToolResult};
@@ -10,6 +10,7 @@ pub struct WebFetchTool {
     default_timeout_secs: u64,
     validation: WebContentValidationConfig,
     url_validation: WebFetchUrlValidationConfig,
+    retry: WebFetchRetryConfig,
 }
 
 impl WebFetchTool {
@@ -22,8 +23,14 @@ impl WebFetchTool {
             default_timeout_secs,
             validation,
             url_validation,
+            retry: WebFetchRetryConfig::default(),
         }
     }
+
+    pub fn with_retry(mut self, retry: WebFetchRetryConfig) -> Self {
+        self.retry = retry;
+        self
+    }
 }
 
 #[async_trait]
@@ -36,7 +43,7 @@ impl Tool for WebFetchTool {
         ToolDefinition {
             name: "web_fetch".into(),
             description:
-                "Fetch a URL and return its text content (HTML parsed, scripts/styles removed). Max 20KB."
+                "Fetch a URL and return its text content. HTML is converted to text (scripts/styles removed), JSON is pretty-printed, and plain text, markdown and other text types are returned unchanged. Returns 20KB per call; for longer documents, call again with the offset from the truncation note to read the next part."
                     .into(),
             input_schema: schema_object(
                 json!({
@@ -47,6 +54,14 @@ impl Tool for WebFetchTool {
                     "timeout_secs": {
                         "type": "integer",
                         "description": "Timeout in seconds (defaults to configured tool timeout budget)"
+                    },
+                    "raw": {
+                        "type": "boolean",
+                        "description": "Return the response body as-is without any extraction (default: false)"
+                    },
+                    "offset": {
+                        "type": "integer",
+                        "description": "Byte offset to continue a truncated document from (use the offset given in the truncation note)"
                     }
                 }),
                 &["url"],
@@ -63,17 +78,33 @@ impl Tool for WebFetchTool {
             .get("timeout_secs")
             .and_then(|v| v.as_u64())
             .unwrap_or(self.default_timeout_secs);
+        let options = microclaw_tools::web_fetch::WebFetchOptions {
+            raw: input.get("raw").and_then(|v| v.as_bool()).unwrap_or(false),
+            offset: input
+                .get("offset")
+                .and_then(|v| v.as_u64())
+                .and_then(|v| usize::try_from(v).ok())
+                .unwrap_or(0),
+        };
 
-        match microclaw_tools::web_fetch::fetch_url_with_timeout_and_validation(
+        match microclaw_tools::web_fetch::fetch_url_with_options(
             url,
             timeout_secs,
             self.validation,
             self.url_validation.clone(),
+            self.retry,
+            options,
         )
         .await
         {
             Ok(text) => ToolResult::success(text),
-            Err(e) => ToolResult::error(format!("Failed to fetch URL: {e}")),
+            Err(e) => {
+                let result = ToolResult::error(format!("Failed to fetch URL: {e}"));
+                match super::transient_error_type(&e) {
+                    Some(kind) => result.with_error_type(kind),
+                    None => result,
+                }
+            }
         }
     }
 }
@@ -61,7 +61,13 @@ impl Tool for WebSearchTool {
                     ToolResult::success(results)
                 }
             }
-            Err(e) => ToolResult::error(format!("Search failed: {e}")),
+            Err(e) => {
+                let result = ToolResult::error(format!("Search failed: {e}"));
+                match super::transient_error_type(&e) {
+                    Some(kind) => result.with_error_type(kind),
+                    None => result,
+                }
+            }
         }
     }
 }
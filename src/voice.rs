@@ -0,0 +1,319 @@
+use std::path::Path;
+
+use tracing::{error, info};
+
+use crate::agent_engine::sanitize_xml;
+use crate::config::Config;
+pub use microclaw_app::transcribe::TranscriptionOptions;
+
+/// Whisper only considers the last 224 tokens of the prompt; keep well under.
+const PROMPT_MAX_CHARS: usize = 600;
+
+/// Number of segments needed to cover `duration_secs` of audio in chunks of
+/// `segment_secs`. Zero segment length means no splitting.
+pub fn segment_count(duration_secs: u64, segment_secs: u64) -> usize {
+    if segment_secs == 0 || duration_secs <= segment_secs {
+        return 1;
+    }
+    duration_secs.div_ceil(segment_secs) as usize
+}
+
+/// Whether audio of this length must be split before transcription.
+pub fn needs_segmentation(duration_secs: Option<u64>, segment_secs: u64) -> bool {
+    duration_secs.is_some_and(|d| segment_count(d, segment_secs) > 1)
+}
+
+/// Split audio into `segment_secs`-long chunks with ffmpeg. Chunks keep the
+/// input's container, named by `extension`. Returns the chunk bytes in
+/// playback order.
+pub async fn split_audio(
+    audio_bytes: &[u8],
+    segment_secs: u64,
+    extension: &str,
+) -> Result<Vec<Vec<u8>>, String> {
+    let work_dir = std::env::temp_dir().join(format!("voice_segments_{}", uuid::Uuid::new_v4()));
+    tokio::fs::create_dir_all(&work_dir)
+        .await
+        .map_err(|e| e.to_string())?;
+    let result = split_audio_in(&work_dir, audio_bytes, segment_secs, extension).await;
+    let _ = tokio::fs::remove_dir_all(&work_dir).await;
+    result
+}
+
+async fn split_audio_in(
+    work_dir: &Path,
+    audio_bytes: &[u8],
+    segment_secs: u64,
+    extension: &str,
+) -> Result<Vec<Vec<u8>>, String> {
+    let input = work_dir.join(format!("input.{extension}"));
+    tokio::fs::write(&input, audio_bytes)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let output = tokio::process::Command::new("ffmpeg")
+        .arg("-hide_banner")
+        .arg("-loglevel")
+        .arg("error")
+        .arg("-i")
+        .arg(&input)
+        .arg("-f")
+        .arg("segment")
+        .arg("-segment_time")
+        .arg(segment_secs.to_string())
+        .arg("-c")
+        .arg("copy")
+        .arg(work_dir.join(format!("segment_%03d.{extension}")))
+        .output()
+        .await
+        .map_err(|e| {
+            if e.kind() == std::io::ErrorKind::NotFound {
+                "Audio is too long to transcribe in one request and ffmpeg is not installed to split it".to_string()
+            } else {
+                format!("Failed to run ffmpeg: {e}")
+            }
+        })?;
+    if !output.status.success() {
+        return Err(format!(
+            "ffmpeg failed to split audio: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let mut paths = Vec::new();
+    let mut entries = tokio::fs::read_dir(work_dir)
+        .await
+        .map_err(|e| e.to_string())?;
+    while let Some(entry) = entries.next_entry().await.map_err(|e| e.to_string())? {
+        let path = entry.path();
+        if path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.starts_with("segment_"))
+        {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    if paths.is_empty() {
+        return Err("ffmpeg produced no audio segments".into());
+    }
+
+    let mut segments = Vec::with_capacity(paths.len());
+    for path in paths {
+        segments.push(tokio::fs::read(&path).await.map_err(|e| e.to_string())?);
+    }
+    Ok(segments)
+}
+
+/// Whether voice notes can be transcribed: `voice_provider: local` needs
+/// `voice_transcription_command`, anything else needs `openai_api_key`.
+pub fn transcription_available(config: &Config) -> bool {
+    if config.voice_provider == "local" {
+        config.voice_transcription_command.is_some()
+    } else {
+        config.openai_api_key.is_some()
+    }
+}
+
+/// Reply for a voice note that arrives while transcription is not configured.
+pub fn transcription_unavailable_message(config: &Config) -> &'static str {
+    if config.voice_provider == "local" {
+        "Voice messages not supported (local transcription configured but voice_transcription_command not set)"
+    } else {
+        "Voice messages not supported (no Whisper API key configured)"
+    }
+}
+
+/// Build a Whisper prompt from the bot's name and recent chat lines so names
+/// and domain terms transcribe correctly. The most recent text is kept when
+/// it has to be shortened.
+pub fn transcription_prompt(bot_name: &str, recent_messages: &[String]) -> String {
+    let context = recent_messages
+        .iter()
+        .map(|m| m.trim())
+        .filter(|m| !m.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let prompt = format!("Conversation with {}. {context}", bot_name.trim());
+    let chars: Vec<char> = prompt.trim().chars().collect();
+    let start = chars.len().saturating_sub(PROMPT_MAX_CHARS);
+    chars[start..].iter().collect()
+}
+
+/// Transcribe a downloaded voice note and format it as the message text the
+/// agent sees. Channels download the audio their own way and call this.
+pub async fn voice_message_text(
+    config: &Config,
+    sender_name: &str,
+    audio_bytes: &[u8],
+    duration_secs: Option<u64>,
+    options: &TranscriptionOptions,
+) -> String {
+    let transcription = transcribe_audio(config, audio_bytes, duration_secs, options).await;
+    if let Err(e) = &transcription {
+        error!("Voice transcription failed: {e}");
+    }
+    format_voice_message(sender_name, transcription)
+}
+
+fn format_voice_message(sender_name: &str, transcription: Result<String, String>) -> String {
+    let sender_name = sanitize_xml(sender_name);
+    match transcription {
+        Ok(text) => format!(
+            "[voice message from {sender_name}]: {}",
+            sanitize_xml(&text)
+        ),
+        Err(e) => format!("[voice message from {sender_name}]: [transcription failed: {e}]"),
+    }
+}
+
+/// Transcribe audio using configured provider (openai or local). Audio longer than
+/// `voice_segment_secs` is split with ffmpeg and the segment transcripts are joined.
+/// Only `options.mime_type` applies to the local command; the rest is for the Whisper API.
+pub async fn transcribe_audio(
+    config: &Config,
+    audio_bytes: &[u8],
+    duration_secs: Option<u64>,
+    options: &TranscriptionOptions,
+) -> Result<String, String> {
+    if !needs_segmentation(duration_secs, config.voice_segment_secs) {
+        return transcribe_audio_segment(config, audio_bytes, options).await;
+    }
+    let (extension, _) = microclaw_app::transcribe::audio_format(options.mime_type.as_deref());
+    let segments = split_audio(audio_bytes, config.voice_segment_secs, extension).await?;
+    info!(
+        "Transcribing {}s of audio in {} segments",
+        duration_secs.unwrap_or_default(),
+        segments.len()
+    );
+    let mut parts = Vec::with_capacity(segments.len());
+    for (idx, segment) in segments.iter().enumerate() {
+        let text = transcribe_audio_segment(config, segment, options)
+            .await
+            .map_err(|e| format!("segment {}/{}: {e}", idx + 1, segments.len()))?;
+        if !text.is_empty() {
+            parts.push(text);
+        }
+    }
+    Ok(parts.join(" "))
+}
+
+async fn transcribe_audio_segment(
+    config: &Config,
+    audio_bytes: &[u8],
+    options: &TranscriptionOptions,
+) -> Result<String, String> {
+    let provider = &config.voice_provider;
+
+    if provider == "local" {
+        // Use local transcription command
+        let Some(ref command) = config.voice_transcription_command else {
+            return Err(
+                "Local voice transcription configured but voice_transcription_command not set"
+                    .into(),
+            );
+        };
+
+        // Write audio to a temp file
+        let temp_dir = std::env::temp_dir();
+        let (extension, _) = microclaw_app::transcribe::audio_format(options.mime_type.as_deref());
+        let temp_file = temp_dir.join(format!("voice_{}.{extension}", uuid::Uuid::new_v4()));
+        tokio::fs::write(&temp_file, audio_bytes)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        // Replace {file} placeholder with actual path
+        let cmd = command.replace("{file}", temp_file.to_str().unwrap_or(""));
+
+        // Execute the command
+        let output_result = tokio::process::Command::new("sh")
+            .arg("-c")
+            .arg(&cmd)
+            .output()
+            .await;
+
+        // Clean up temp file
+        let _ = tokio::fs::remove_file(&temp_file).await;
+
+        let output =
+            output_result.map_err(|e| format!("Failed to run transcription command: {}", e))?;
+
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        } else {
+            Err(format!(
+                "Transcription command failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ))
+        }
+    } else {
+        // Default to OpenAI Whisper API
+        let Some(ref openai_key) = config.openai_api_key else {
+            return Err("Voice transcription requires openai_api_key".into());
+        };
+        microclaw_app::transcribe::transcribe_audio_with_options(openai_key, audio_bytes, options)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_segment_count_for_duration() {
+        assert_eq!(segment_count(30, 600), 1);
+        assert_eq!(segment_count(600, 600), 1);
+        assert_eq!(segment_count(601, 600), 2);
+        assert_eq!(segment_count(1800, 600), 3);
+        assert_eq!(segment_count(1801, 600), 4);
+        assert_eq!(segment_count(0, 600), 1);
+    }
+
+    #[test]
+    fn test_zero_segment_length_disables_splitting() {
+        assert_eq!(segment_count(7200, 0), 1);
+        assert!(!needs_segmentation(Some(7200), 0));
+    }
+
+    #[test]
+    fn test_needs_segmentation_requires_known_duration() {
+        assert!(needs_segmentation(Some(900), 600));
+        assert!(!needs_segmentation(Some(300), 600));
+        assert!(!needs_segmentation(None, 600));
+    }
+
+    #[test]
+    fn test_voice_message_formatting_and_availability() {
+        assert_eq!(
+            format_voice_message("<al>", Ok("hi & bye".into())),
+            "[voice message from &lt;al&gt;]: hi &amp; bye"
+        );
+        assert_eq!(
+            format_voice_message("bob", Err("timeout".into())),
+            "[voice message from bob]: [transcription failed: timeout]"
+        );
+
+        let mut config = Config::test_defaults();
+        config.voice_provider = "openai".into();
+        config.openai_api_key = None;
+        assert!(!transcription_available(&config));
+        config.openai_api_key = Some("sk-test".into());
+        assert!(transcription_available(&config));
+        config.voice_provider = "local".into();
+        assert!(!transcription_available(&config));
+        assert!(transcription_unavailable_message(&config).contains("voice_transcription_command"));
+    }
+
+    #[test]
+    fn test_transcription_prompt_keeps_latest_context() {
+        let prompt = transcription_prompt("claw", &["deploy to k8s".into(), " ".into()]);
+        assert_eq!(prompt, "Conversation with claw. deploy to k8s");
+
+        let long = vec!["a".repeat(1000), "the latest line".into()];
+        let prompt = transcription_prompt("claw", &long);
+        assert_eq!(prompt.chars().count(), PROMPT_MAX_CHARS);
+        assert!(prompt.ends_with("the latest line"));
+    }
+}
@@ -3,10 +3,11 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
-use axum::extract::{Path, Query, State};
+use axum::extract::multipart::MultipartError;
+use axum::extract::{DefaultBodyLimit, Multipart, Path, Query, State};
 use axum::http::{HeaderMap, StatusCode};
 use axum::response::sse::{Event, KeepAlive, Sse};
-use axum::response::{Html, IntoResponse};
+use axum::response::{Html, IntoResponse, Response};
 use axum::routing::{get, post};
 use axum::{Json, Router};
 use include_dir::{include_dir, Dir};
@@ -18,6 +19,7 @@ use tracing::{error, info, warn};
 use crate::agent_engine::{process_with_agent_with_events, AgentEvent, AgentRequestContext};
 use crate::chat_commands::handle_chat_command;
 use crate::config::{Config, WorkingDirIsolation};
+use crate::keyed_lock::KeyedLocks;
 use crate::otlp::{OtlpExporter, OtlpMetricSnapshot};
 use crate::runtime::AppState;
 use microclaw_channels::channel::ConversationKind;
@@ -35,6 +37,7 @@ mod middleware;
 mod sessions;
 mod skills;
 mod stream;
+mod tasks;
 use middleware::*;
 
 static WEB_ASSETS: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/web/dist");
@@ -117,7 +120,7 @@ struct RunHub {
 
 #[derive(Clone, Default)]
 struct SessionHub {
-    locks: Arc<Mutex<HashMap<String, SessionLockEntry>>>,
+    locks: Arc<KeyedLocks<String>>,
 }
 
 #[derive(Clone, Debug)]
@@ -180,11 +183,6 @@ impl Default for SessionQuota {
     }
 }
 
-struct SessionLockEntry {
-    lock: Arc<tokio::sync::Mutex<()>>,
-    last_touch: Instant,
-}
-
 #[derive(Clone)]
 struct RunChannel {
     sender: broadcast::Sender<RunEvent>,
@@ -309,25 +307,9 @@ impl RunHub {
 
 impl SessionHub {
     async fn lock_for(&self, session_key: &str, limits: &WebLimits) -> Arc<tokio::sync::Mutex<()>> {
-        let now = Instant::now();
-        let mut guard = self.locks.lock().await;
-        guard.retain(|key, entry| {
-            if key == session_key {
-                return true;
-            }
-            let stale = now.duration_since(entry.last_touch) > limits.session_idle_ttl;
-            // Remove only stale + uncontended locks.
-            !(stale && Arc::strong_count(&entry.lock) == 1 && entry.lock.try_lock().is_ok())
-        });
-        guard
-            .entry(session_key.to_string())
-            .and_modify(|entry| entry.last_touch = now)
-            .or_insert_with(|| SessionLockEntry {
-                lock: Arc::new(tokio::sync::Mutex::new(())),
-                last_touch: now,
-            })
-            .lock
-            .clone()
+        self.locks
+            .lock_for(&session_key.to_string(), limits.session_idle_ttl)
+            .await
     }
 }
 
@@ -625,7 +607,7 @@ async fn persist_metrics_snapshot(state: &WebState) -> Result<(), (StatusCode, S
     })
     .await
     .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-    let retention_days = metrics_history_retention_days(&state.app_state.config);
+    let retention_days = metrics_history_retention_days(&state.app_state.config.load());
     let cutoff = chrono::Utc::now() - chrono::Duration::days(retention_days);
     let cutoff_ms = cutoff.timestamp_millis();
     let _ = call_blocking(state.app_state.db.clone(), move |db| {
@@ -784,6 +766,11 @@ struct MemoryObservabilityQuery {
     offset: Option<usize>,
 }
 
+#[derive(Debug, Deserialize)]
+struct LogLevelRequest {
+    level: String,
+}
+
 #[derive(Debug, Deserialize)]
 struct UpdateConfigRequest {
     llm_provider: Option<String>,
@@ -933,7 +920,7 @@ fn config_path_for_save() -> Result<PathBuf, (StatusCode, String)> {
     }
 }
 
-fn is_sensitive_config_key(key: &str) -> bool {
+pub(crate) fn is_sensitive_config_key(key: &str) -> bool {
     let k = key.trim().to_ascii_lowercase();
     if k.is_empty() {
         return false;
@@ -961,7 +948,7 @@ fn is_sensitive_config_key(key: &str) -> bool {
         || k.ends_with("_api_key")
 }
 
-fn redact_json_secrets(value: &mut serde_json::Value, parent_key: Option<&str>) {
+pub(crate) fn redact_json_secrets(value: &mut serde_json::Value, parent_key: Option<&str>) {
     if parent_key.is_some_and(is_sensitive_config_key) {
         *value = serde_json::Value::String("***".to_string());
         return;
@@ -987,13 +974,81 @@ fn redact_config(config: &Config) -> serde_json::Value {
     value
 }
 
-async fn index() -> impl IntoResponse {
-    match WEB_ASSETS.get_file("index.html") {
-        Some(file) => Html(String::from_utf8_lossy(file.contents()).to_string()).into_response(),
-        None => (StatusCode::NOT_FOUND, "index.html missing").into_response(),
+/// Served at `/` when the binary was built without the frontend
+/// (`web/dist` missing). The API keeps working; this page says so and offers
+/// a bare form for `/api/send`.
+const FALLBACK_INDEX_HTML: &str = r#"<!doctype html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>MicroClaw</title>
+<style>
+body { font-family: system-ui, sans-serif; max-width: 42rem; margin: 2rem auto; padding: 0 1rem; }
+code { background: #f2f2f2; padding: 0 .25rem; }
+textarea, input { width: 100%; box-sizing: border-box; margin: .25rem 0; }
+pre { background: #f2f2f2; padding: .75rem; white-space: pre-wrap; }
+</style>
+</head>
+<body>
+<h1>MicroClaw</h1>
+<p>The web UI was not built into this binary, but the HTTP API is available.
+Build the frontend with <code>cd web &amp;&amp; npm install &amp;&amp; npm run build</code>
+and rebuild MicroClaw to get the full UI.</p>
+<p>Useful endpoints: <code>GET /api/health</code>, <code>GET /api/sessions</code>,
+<code>GET /api/history?session_key=...</code>, <code>POST /api/send</code>,
+<code>POST /api/send_stream</code>. Authenticate with
+<code>Authorization: Bearer &lt;api key&gt;</code> or a signed-in session.</p>
+<h2>Send a message</h2>
+<form id="send">
+<input id="key" type="password" placeholder="API key (optional if signed in)">
+<input id="session" value="main" placeholder="Session key">
+<textarea id="message" rows="4" placeholder="Message"></textarea>
+<button type="submit">Send</button>
+</form>
+<pre id="out"></pre>
+<script>
+document.getElementById("send").addEventListener("submit", async (e) => {
+  e.preventDefault();
+  const headers = { "content-type": "application/json" };
+  const key = document.getElementById("key").value.trim();
+  if (key) headers["authorization"] = "Bearer " + key;
+  const out = document.getElementById("out");
+  out.textContent = "...";
+  try {
+    const res = await fetch("/api/send", {
+      method: "POST",
+      headers,
+      credentials: "same-origin",
+      body: JSON.stringify({
+        session_key: document.getElementById("session").value.trim() || "main",
+        message: document.getElementById("message").value,
+      }),
+    });
+    out.textContent = res.status + " " + (await res.text());
+  } catch (err) {
+    out.textContent = String(err);
+  }
+});
+</script>
+</body>
+</html>
+"#;
+
+fn render_index(index_html: Option<&[u8]>) -> Response {
+    match index_html {
+        Some(contents) => Html(String::from_utf8_lossy(contents).to_string()).into_response(),
+        None => Html(FALLBACK_INDEX_HTML).into_response(),
     }
 }
 
+async fn index() -> impl IntoResponse {
+    render_index(
+        WEB_ASSETS
+            .get_file("index.html")
+            .map(|file| file.contents()),
+    )
+}
+
 async fn api_health(
     headers: HeaderMap,
     State(state): State<WebState>,
@@ -1030,24 +1085,32 @@ async fn api_health(
         .as_ref()
         .map(|s| s.reflector_skipped_24h)
         .unwrap_or(0);
+    let mcp_servers: Vec<crate::mcp::McpServerHealth> = state
+        .app_state
+        .mcp_manager
+        .servers()
+        .iter()
+        .map(|server| server.health_snapshot())
+        .collect();
 
     Ok(Json(json!({
         "ok": true,
         "version": env!("CARGO_PKG_VERSION"),
-        "web_enabled": state.app_state.config.web_enabled,
+        "web_enabled": state.app_state.config.load().web_enabled,
         "scheduler": {
             "task_runs_24h": task_runs_24h,
             "task_success_24h": task_success_24h,
             "task_failed_24h": task_failed_24h
         },
         "reflector": {
-            "enabled": state.app_state.config.reflector_enabled,
-            "interval_mins": state.app_state.config.reflector_interval_mins,
+            "enabled": state.app_state.config.load().reflector_enabled,
+            "interval_mins": state.app_state.config.load().reflector_interval_mins,
             "runs_24h": reflector_runs_24h,
             "inserted_24h": reflector_inserted_24h,
             "updated_24h": reflector_updated_24h,
             "skipped_24h": reflector_skipped_24h
-        }
+        },
+        "mcp": crate::mcp::mcp_health_json(&mcp_servers)
     })))
 }
 
@@ -1291,16 +1354,98 @@ async fn api_send(
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
     metrics_http_inc(&state).await;
     let identity = require_scope(&state, &headers, AuthScope::Write).await?;
+    send_with_limits(state, &identity.actor, "/api/send", body, None).await
+}
+
+/// Largest image accepted by `/api/send_image`.
+const MAX_WEB_IMAGE_BYTES: usize = 10 * 1024 * 1024;
+const WEB_IMAGE_CONTENT_TYPES: &[&str] = &["image/png", "image/jpeg", "image/gif", "image/webp"];
+
+/// Multipart upload of an `image` file with `session_key`, optional
+/// `message` caption and `sender_name`, answered like `/api/send`.
+async fn api_send_image(
+    headers: HeaderMap,
+    State(state): State<WebState>,
+    mut multipart: Multipart,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    metrics_http_inc(&state).await;
+    let identity = require_scope(&state, &headers, AuthScope::Write).await?;
+
+    let bad_request = |e: MultipartError| (StatusCode::BAD_REQUEST, e.body_text());
+    let mut body = SendRequest {
+        session_key: None,
+        sender_name: None,
+        message: String::new(),
+    };
+    let mut image: Option<Vec<u8>> = None;
+    while let Some(field) = multipart.next_field().await.map_err(bad_request)? {
+        match field.name().unwrap_or_default() {
+            "image" => {
+                let content_type = field
+                    .content_type()
+                    .unwrap_or_default()
+                    .to_ascii_lowercase();
+                if !WEB_IMAGE_CONTENT_TYPES.contains(&content_type.as_str()) {
+                    return Err((
+                        StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                        format!(
+                            "unsupported image type '{content_type}'; expected one of {}",
+                            WEB_IMAGE_CONTENT_TYPES.join(", ")
+                        ),
+                    ));
+                }
+                let bytes = field.bytes().await.map_err(bad_request)?;
+                if bytes.len() > MAX_WEB_IMAGE_BYTES {
+                    return Err((
+                        StatusCode::PAYLOAD_TOO_LARGE,
+                        format!("image exceeds {MAX_WEB_IMAGE_BYTES} bytes"),
+                    ));
+                }
+                image = Some(bytes.to_vec());
+            }
+            "session_key" => body.session_key = Some(field.text().await.map_err(bad_request)?),
+            "sender_name" => body.sender_name = Some(field.text().await.map_err(bad_request)?),
+            "message" => body.message = field.text().await.map_err(bad_request)?,
+            _ => {}
+        }
+    }
+    let Some(image) = image.filter(|b| !b.is_empty()) else {
+        return Err((StatusCode::BAD_REQUEST, "image is required".into()));
+    };
+    let media_type = crate::channels::telegram::guess_image_media_type(&image);
+    let encoded = {
+        use base64::Engine;
+        base64::engine::general_purpose::STANDARD.encode(&image)
+    };
+    send_with_limits(
+        state,
+        &identity.actor,
+        "/api/send_image",
+        body,
+        Some((encoded, media_type)),
+    )
+    .await
+}
+
+/// Run a blocking send under the per-session request limiter and record its
+/// metrics.
+async fn send_with_limits(
+    state: WebState,
+    actor: &str,
+    endpoint: &'static str,
+    body: SendRequest,
+    image_data: Option<(String, String)>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
     let start = Instant::now();
     let session_key = normalize_session_key(body.session_key.as_deref());
     if let Err((status, msg)) = state
         .request_hub
-        .begin(&session_key, &identity.actor, &state.limits)
+        .begin(&session_key, actor, &state.limits)
         .await
     {
         info!(
             target: "web",
-            endpoint = "/api/send",
+            endpoint,
             session_key = %session_key,
             status = status.as_u16(),
             reason = %msg,
@@ -1309,18 +1454,18 @@ async fn api_send(
         metrics_record_request_result(&state, false, start.elapsed().as_millis() as i64).await;
         return Err((status, msg));
     }
-    let result = send_and_store_response(state.clone(), body).await;
+    let result = send_and_store_response(state.clone(), body, image_data).await;
     if result.is_ok() {
         metrics_llm_completion_inc(&state).await;
     }
     metrics_record_request_result(&state, result.is_ok(), start.elapsed().as_millis() as i64).await;
     state
         .request_hub
-        .end_with_limits(&session_key, &identity.actor, &state.limits)
+        .end_with_limits(&session_key, actor, &state.limits)
         .await;
     info!(
         target: "web",
-        endpoint = "/api/send",
+        endpoint,
         session_key = %session_key,
         ok = result.is_ok(),
         latency_ms = start.elapsed().as_millis(),
@@ -1329,9 +1474,23 @@ async fn api_send(
     result
 }
 
+/// Chats that keep sending after their slow-down reply get 429s.
+fn agent_error_response(e: anyhow::Error) -> (StatusCode, String) {
+    let status = if e
+        .downcast_ref::<crate::chat_rate_limit::ChatRateLimitedError>()
+        .is_some()
+    {
+        StatusCode::TOO_MANY_REQUESTS
+    } else {
+        StatusCode::INTERNAL_SERVER_ERROR
+    };
+    (status, e.to_string())
+}
+
 async fn send_and_store_response(
     state: WebState,
     body: SendRequest,
+    image_data: Option<(String, String)>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
     let session_key = normalize_session_key(body.session_key.as_deref());
     let lock = state
@@ -1339,16 +1498,17 @@ async fn send_and_store_response(
         .lock_for(&session_key, &state.limits)
         .await;
     let _guard = lock.lock().await;
-    send_and_store_response_with_events(state, body, None).await
+    send_and_store_response_with_events(state, body, image_data, None).await
 }
 
 async fn send_and_store_response_with_events(
     state: WebState,
     body: SendRequest,
+    image_data: Option<(String, String)>,
     event_tx: Option<&tokio::sync::mpsc::UnboundedSender<AgentEvent>>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
     let text = body.message.trim().to_string();
-    if text.is_empty() {
+    if text.is_empty() && image_data.is_none() {
         return Err((StatusCode::BAD_REQUEST, "message is required".into()));
     }
 
@@ -1401,15 +1561,22 @@ async fn send_and_store_response_with_events(
         }
     }
 
-    if let Some(command_reply) =
+    let command_reply = if image_data.is_none() {
         handle_chat_command(&state.app_state, chat_id, "web", &text, None).await
-    {
+    } else {
+        None
+    };
+    if let Some(command_reply) = command_reply {
         if let Some(tx) = event_tx {
             let _ = tx.send(AgentEvent::FinalResponse {
                 text: command_reply.clone(),
             });
         }
-        let bot_username = state.app_state.config.bot_username_for_channel("web");
+        let bot_username = state
+            .app_state
+            .config
+            .load()
+            .bot_username_for_channel("web");
         deliver_and_store_bot_message(
             &state.app_state.channel_registry,
             state.app_state.db.clone(),
@@ -1431,15 +1598,38 @@ async fn send_and_store_response_with_events(
         id: uuid::Uuid::new_v4().to_string(),
         chat_id,
         sender_name: sender_name.clone(),
-        content: text,
+        content: if image_data.is_some() {
+            if text.is_empty() {
+                "[image]".to_string()
+            } else {
+                format!("[image] {text}")
+            }
+        } else {
+            text
+        },
         is_from_bot: false,
         timestamp: chrono::Utc::now().to_rfc3339(),
     };
+    let stored_msg = user_msg.clone();
     call_blocking(state.app_state.db.clone(), move |db| {
-        db.store_message(&user_msg)
+        db.store_message(&stored_msg)
     })
     .await
     .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    crate::channels::emit_message_received(&state.app_state, "web", &user_msg);
+
+    // Share the chat's run queue with the other entry points (edit re-runs,
+    // channel adapters). Web requests already wait on the session lock, so a
+    // turn is only skipped when a newer queued run will answer this message.
+    let Some(_run_turn) = state.app_state.chat_run_queue.acquire(chat_id).await else {
+        return Ok(Json(json!({
+            "ok": true,
+            "session_key": session_key,
+            "chat_id": chat_id,
+            "response": "",
+            "coalesced": true,
+        })));
+    };
 
     let request_ctx = AgentRequestContext {
         caller_channel: "web",
@@ -1447,15 +1637,20 @@ async fn send_and_store_response_with_events(
         chat_type: "web",
     };
     let response = if let Some(tx) = event_tx {
-        process_with_agent_with_events(&state.app_state, request_ctx, None, None, Some(tx))
+        process_with_agent_with_events(&state.app_state, request_ctx, None, image_data, Some(tx))
             .await
-            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+            .map_err(agent_error_response)?
     } else {
         let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<AgentEvent>();
-        let result =
-            process_with_agent_with_events(&state.app_state, request_ctx, None, None, Some(&tx))
-                .await
-                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()));
+        let result = process_with_agent_with_events(
+            &state.app_state,
+            request_ctx,
+            None,
+            image_data,
+            Some(&tx),
+        )
+        .await
+        .map_err(agent_error_response);
         drop(tx);
         while let Some(evt) = rx.recv().await {
             metrics_apply_agent_event(&state, &evt).await;
@@ -1474,7 +1669,11 @@ async fn send_and_store_response_with_events(
         m.llm_output_tokens += (after.output_tokens - before.output_tokens).max(0);
     }
 
-    let bot_username = state.app_state.config.bot_username_for_channel("web");
+    let bot_username = state
+        .app_state
+        .config
+        .load()
+        .bot_username_for_channel("web");
     deliver_and_store_bot_message(
         &state.app_state.channel_registry,
         state.app_state.db.clone(),
@@ -1526,8 +1725,13 @@ async fn api_audit_logs(
 }
 
 pub async fn start_web_server(state: Arc<AppState>) {
-    let limits = WebLimits::from_config(&state.config);
-    let flush_interval = metrics_flush_interval(&state.config);
+    if WEB_ASSETS.get_file("index.html").is_none() {
+        warn!(
+            "Web UI assets are missing (built without web/dist); serving a minimal fallback page. The /api endpoints still work. Run `npm run build` in web/ and rebuild for the full UI."
+        );
+    }
+    let limits = WebLimits::from_config(&state.config.load());
+    let flush_interval = metrics_flush_interval(&state.config.load());
     let mut has_password = call_blocking(state.db.clone(), |db| db.get_auth_password_hash())
         .await
         .ok()
@@ -1563,7 +1767,7 @@ pub async fn start_web_server(state: Arc<AppState>) {
         request_hub: RequestHub::default(),
         auth_hub: AuthHub::default(),
         metrics: Arc::new(Mutex::new(WebMetrics::default())),
-        otlp: OtlpExporter::from_config(&state.config),
+        otlp: OtlpExporter::from_config(&state.config.load()),
         limits,
     };
 
@@ -1591,7 +1795,11 @@ pub async fn start_web_server(state: Arc<AppState>) {
     router = crate::channels::dingtalk::register_dingtalk_webhook(router, state.clone());
     router = crate::channels::qq::register_qq_webhook(router, state.clone());
 
-    let addr = format!("{}:{}", state.config.web_host, state.config.web_port);
+    let addr = format!(
+        "{}:{}",
+        state.config.load().web_host,
+        state.config.load().web_port
+    );
     let listener = match tokio::net::TcpListener::bind(&addr).await {
         Ok(listener) => listener,
         Err(e) => {
@@ -1668,6 +1876,10 @@ fn build_router(web_state: WebState) -> Router {
             get(config::api_get_config).put(config::api_update_config),
         )
         .route("/api/config/self_check", get(config::api_config_self_check))
+        .route(
+            "/api/log_level",
+            get(config::api_get_log_level).put(config::api_set_log_level),
+        )
         .route("/api/sessions", get(sessions::api_sessions))
         .route("/api/sessions/tree", get(sessions::api_sessions_tree))
         .route("/api/sessions/fork", post(sessions::api_sessions_fork))
@@ -1679,6 +1891,11 @@ fn build_router(web_state: WebState) -> Router {
         .route("/api/metrics/summary", get(metrics::api_metrics_summary))
         .route("/api/metrics/history", get(metrics::api_metrics_history))
         .route("/api/send", post(api_send))
+        .route(
+            "/api/send_image",
+            // Leave room for the multipart framing and text fields.
+            post(api_send_image).layer(DefaultBodyLimit::max(MAX_WEB_IMAGE_BYTES + 64 * 1024)),
+        )
         .route("/api/send_stream", post(stream::api_send_stream))
         .route("/api/stream", get(stream::api_stream))
         .route("/api/run_status", get(stream::api_run_status))
@@ -1687,6 +1904,10 @@ fn build_router(web_state: WebState) -> Router {
         .route("/api/skills", get(skills::api_list_skills))
         .route("/api/skills/:name/enable", post(skills::api_enable_skill))
         .route("/api/skills/:name/disable", post(skills::api_disable_skill))
+        .route("/api/tasks", get(tasks::api_list_tasks))
+        .route("/api/tasks/:id/history", get(tasks::api_task_history))
+        .route("/api/tasks/:id/pause", post(tasks::api_pause_task))
+        .route("/api/tasks/:id/resume", post(tasks::api_resume_task))
         .with_state(web_state)
 }
 
@@ -1726,6 +1947,24 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_index_falls_back_when_assets_missing() {
+        let response = render_index(None);
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let html = String::from_utf8(body.to_vec()).unwrap();
+        assert!(html.contains("web UI was not built"));
+        assert!(html.contains("/api/send"));
+
+        let response = render_index(Some(b"<html>app</html>"));
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"<html>app</html>");
+    }
+
     struct DummyLlm;
 
     #[async_trait::async_trait]
@@ -1745,6 +1984,7 @@ mod tests {
                 }],
                 stop_reason: Some("end_turn".into()),
                 usage: None,
+                served_by: None,
             })
         }
 
@@ -1785,10 +2025,66 @@ mod tests {
                 }],
                 stop_reason: Some("end_turn".into()),
                 usage: None,
+                served_by: None,
+            })
+        }
+    }
+
+    /// Replies with whether the request carried a PNG image block.
+    struct ImageEchoLlm;
+
+    #[async_trait::async_trait]
+    impl LlmProvider for ImageEchoLlm {
+        async fn send_message(
+            &self,
+            _system: &str,
+            messages: Vec<microclaw_core::llm_types::Message>,
+            _tools: Option<Vec<microclaw_core::llm_types::ToolDefinition>>,
+        ) -> Result<microclaw_core::llm_types::MessagesResponse, MicroClawError> {
+            let raw = serde_json::to_string(&messages).unwrap();
+            let saw_image = raw.contains("\"type\":\"image\"") && raw.contains("image/png");
+            Ok(microclaw_core::llm_types::MessagesResponse {
+                content: vec![ResponseContentBlock::Text {
+                    text: if saw_image { "saw png" } else { "no image" }.into(),
+                }],
+                stop_reason: Some("end_turn".into()),
+                usage: None,
+                served_by: None,
             })
         }
     }
 
+    fn multipart_request(parts: &[(&str, Option<&str>, &[u8])]) -> Request<Body> {
+        let boundary = "mcboundary";
+        let mut body = Vec::new();
+        for (name, content_type, data) in parts {
+            body.extend_from_slice(format!("--{boundary}\r\n").as_bytes());
+            match content_type {
+                Some(ct) => body.extend_from_slice(
+                    format!(
+                        "Content-Disposition: form-data; name=\"{name}\"; filename=\"upload\"\r\nContent-Type: {ct}\r\n\r\n"
+                    )
+                    .as_bytes(),
+                ),
+                None => body.extend_from_slice(
+                    format!("Content-Disposition: form-data; name=\"{name}\"\r\n\r\n").as_bytes(),
+                ),
+            }
+            body.extend_from_slice(data);
+            body.extend_from_slice(b"\r\n");
+        }
+        body.extend_from_slice(format!("--{boundary}--\r\n").as_bytes());
+        Request::builder()
+            .method("POST")
+            .uri("/api/send_image")
+            .header(
+                "content-type",
+                format!("multipart/form-data; boundary={boundary}"),
+            )
+            .body(Body::from(body))
+            .unwrap()
+    }
+
     struct ToolFlowLlm {
         calls: AtomicUsize,
     }
@@ -1808,9 +2104,11 @@ mod tests {
                         id: "tool_1".into(),
                         name: "glob".into(),
                         input: json!({"pattern": "*.rs", "path": "."}),
+                        thought_signature: None,
                     }],
                     stop_reason: Some("tool_use".into()),
                     usage: None,
+                    served_by: None,
                 });
             }
             Ok(microclaw_core::llm_types::MessagesResponse {
@@ -1819,6 +2117,7 @@ mod tests {
                 }],
                 stop_reason: Some("end_turn".into()),
                 usage: None,
+                served_by: None,
             })
         }
     }
@@ -1842,14 +2141,16 @@ mod tests {
         let mut registry = ChannelRegistry::new();
         registry.register(Arc::new(WebAdapter));
         let channel_registry = Arc::new(registry);
+        let live_config = Arc::new(crate::hot_reload::Live::from(cfg.clone()));
         let state = AppState {
-            config: cfg.clone(),
+            config: live_config.clone(),
             channel_registry: channel_registry.clone(),
             db: db.clone(),
             memory: MemoryManager::new(&runtime_dir),
             skills: SkillManager::from_skills_dir(&cfg.skills_data_dir()),
             hooks: Arc::new(crate::hooks::HookManager::for_tests()),
-            llm,
+            llm: crate::hot_reload::Live::new(Arc::from(llm)),
+            provider_health: Arc::new(crate::provider_health::ProviderHealth::from_config(&cfg)),
             llm_provider_overrides: Arc::new(tokio::sync::RwLock::new(
                 std::collections::HashMap::new(),
             )),
@@ -1858,7 +2159,11 @@ mod tests {
             )),
             embedding: None,
             memory_backend: memory_backend.clone(),
-            tools: ToolRegistry::new(&cfg, channel_registry, db, memory_backend),
+            tools: ToolRegistry::new(live_config, channel_registry, db, memory_backend),
+            mcp_manager: Arc::new(crate::mcp::McpManager::default()),
+            event_webhook: None.into(),
+            chat_rate_limiter: Arc::new(crate::chat_rate_limit::ChatRateLimiter::from_config(&cfg)),
+            chat_run_queue: Arc::new(crate::chat_run_queue::ChatRunQueue::default()),
         };
         Arc::new(state)
     }
@@ -1921,6 +2226,147 @@ mod tests {
         assert!(text.contains("event: done"));
     }
 
+    #[tokio::test]
+    async fn test_tasks_list_history_pause_resume() {
+        let web_state = test_web_state(Box::new(DummyLlm), WebLimits::default());
+        let db = web_state.app_state.db.clone();
+        let task_id = db
+            .create_scheduled_task(42, "digest", "cron", "0 0 9 * * *", "2024-01-01T09:00:00Z")
+            .unwrap();
+        for i in 0..3 {
+            db.log_task_run(
+                task_id,
+                42,
+                &format!("2024-01-0{}T09:00:00Z", i + 1),
+                &format!("2024-01-0{}T09:00:01Z", i + 1),
+                1000 + i,
+                i != 2,
+                None,
+            )
+            .unwrap();
+        }
+        let app = build_router(web_state);
+        let call = |method: &str, uri: &str| {
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .body(Body::empty())
+                .unwrap()
+        };
+        let json_body = |resp: axum::response::Response| async move {
+            let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            serde_json::from_slice::<serde_json::Value>(&bytes).unwrap()
+        };
+
+        let resp = app
+            .clone()
+            .oneshot(call("GET", "/api/tasks"))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let v = json_body(resp).await;
+        assert_eq!(v["ok"], true);
+        assert_eq!(v["tasks"][0]["id"], task_id);
+        assert_eq!(v["tasks"][0]["next_run"], "2024-01-01T09:00:00Z");
+        assert_eq!(v["tasks"][0]["last_run"]["duration_ms"], 1002);
+        assert_eq!(v["tasks"][0]["last_run"]["success"], false);
+
+        let resp = app
+            .clone()
+            .oneshot(call(
+                "GET",
+                &format!("/api/tasks/{task_id}/history?limit=2&offset=1"),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let v = json_body(resp).await;
+        let runs = v["runs"].as_array().unwrap();
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0]["duration_ms"], 1001);
+
+        let resp = app
+            .clone()
+            .oneshot(call("POST", &format!("/api/tasks/{task_id}/pause")))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(json_body(resp).await["status"], "paused");
+        assert_eq!(
+            db.get_task_by_id(task_id).unwrap().unwrap().status,
+            "paused"
+        );
+        let resp = app
+            .clone()
+            .oneshot(call("POST", &format!("/api/tasks/{task_id}/pause")))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+        let resp = app
+            .clone()
+            .oneshot(call("POST", &format!("/api/tasks/{task_id}/resume")))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            db.get_task_by_id(task_id).unwrap().unwrap().status,
+            "active"
+        );
+
+        let resp = app
+            .oneshot(call("GET", "/api/tasks/999999/history"))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_send_image_routes_image_to_agent_and_stores_caption() {
+        let web_state = test_web_state(Box::new(ImageEchoLlm), WebLimits::default());
+        let db = web_state.app_state.db.clone();
+        let app = build_router(web_state);
+        let png: &[u8] = &[0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A, 0, 0];
+
+        let resp = app
+            .clone()
+            .oneshot(multipart_request(&[
+                ("session_key", None, b"main"),
+                ("message", None, b"what is this?"),
+                ("image", Some("image/png"), png),
+            ]))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let v: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(v["ok"], true);
+        assert_eq!(v["response"], "saw png");
+        let chat_id = v["chat_id"].as_i64().unwrap();
+        let history = db.get_all_messages(chat_id).unwrap();
+        assert_eq!(history[0].content, "[image] what is this?");
+
+        let resp = app
+            .clone()
+            .oneshot(multipart_request(&[
+                ("session_key", None, b"main"),
+                ("image", Some("application/pdf"), b"%PDF-1.4"),
+            ]))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+        let resp = app
+            .oneshot(multipart_request(&[("session_key", None, b"main")]))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
     #[tokio::test]
     async fn test_api_send_models_command_uses_live_models_for_non_preset_provider() {
         use std::io::{Read, Write};
@@ -2050,6 +2496,8 @@ mod tests {
             .and_then(|s| s.get("enabled"))
             .and_then(|v| v.as_bool())
             .is_some());
+        assert_eq!(json["mcp"]["total"], 0);
+        assert!(json["mcp"]["servers"].as_array().is_some());
     }
 
     #[tokio::test]
@@ -104,8 +104,8 @@ pub(super) async fn api_get_config(
     metrics_http_inc(&state).await;
     require_scope(&state, &headers, AuthScope::Read).await?;
 
-    let config_for_display =
-        crate::config::Config::load().unwrap_or_else(|_| state.app_state.config.clone());
+    let config_for_display = crate::config::Config::load()
+        .unwrap_or_else(|_| state.app_state.config.load().as_ref().clone());
     let path = config_path_for_save()?;
     Ok(Json(json!({
         "ok": true,
@@ -116,6 +116,53 @@ pub(super) async fn api_get_config(
     })))
 }
 
+fn log_filter_handle_or_unavailable(
+) -> Result<&'static crate::logging::LogFilterHandle, (StatusCode, String)> {
+    crate::logging::log_filter_handle().ok_or((
+        StatusCode::SERVICE_UNAVAILABLE,
+        "logging is not initialized".to_string(),
+    ))
+}
+
+pub(super) async fn api_get_log_level(
+    headers: HeaderMap,
+    State(state): State<WebState>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    metrics_http_inc(&state).await;
+    require_scope(&state, &headers, AuthScope::Admin).await?;
+    let handle = log_filter_handle_or_unavailable()?;
+    Ok(Json(json!({
+        "ok": true,
+        "level": handle.current(),
+        "levels": crate::logging::LOG_LEVELS,
+    })))
+}
+
+pub(super) async fn api_set_log_level(
+    headers: HeaderMap,
+    State(state): State<WebState>,
+    Json(body): Json<LogLevelRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    metrics_http_inc(&state).await;
+    let identity = require_scope(&state, &headers, AuthScope::Admin).await?;
+    let handle = log_filter_handle_or_unavailable()?;
+    let level = handle
+        .set(&body.level)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    info!("Log level changed to '{level}' by {}", identity.actor);
+    audit_log(
+        &state,
+        "operator",
+        &identity.actor,
+        "log_level.update",
+        Some(&level),
+        "ok",
+        None,
+    )
+    .await;
+    Ok(Json(json!({"ok": true, "level": level})))
+}
+
 pub(super) async fn api_config_self_check(
     headers: HeaderMap,
     State(state): State<WebState>,
@@ -123,6 +170,7 @@ pub(super) async fn api_config_self_check(
     metrics_http_inc(&state).await;
     require_scope(&state, &headers, AuthScope::Read).await?;
 
+    let config = state.app_state.config.load();
     let mut warnings = Vec::<ConfigWarning>::new();
     let has_password = call_blocking(state.app_state.db.clone(), |db| db.get_auth_password_hash())
         .await
@@ -140,10 +188,9 @@ pub(super) async fn api_config_self_check(
     })
     .await
     .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-    let sandbox_runtime_available =
-        runtime_available_for_backend(state.app_state.config.sandbox.backend);
-    let sandbox_runtime_cli = selected_runtime_cli(state.app_state.config.sandbox.backend);
-    let sandbox_mode = match state.app_state.config.sandbox.mode {
+    let sandbox_runtime_available = runtime_available_for_backend(config.sandbox.backend);
+    let sandbox_runtime_cli = selected_runtime_cli(config.sandbox.backend);
+    let sandbox_mode = match config.sandbox.mode {
         crate::config::SandboxMode::All => "all",
         crate::config::SandboxMode::Off => "off",
     };
@@ -164,9 +211,7 @@ pub(super) async fn api_config_self_check(
         })
     })
     .collect::<Vec<_>>();
-    let mount_allowlist_path = state
-        .app_state
-        .config
+    let mount_allowlist_path = config
         .sandbox
         .mount_allowlist_path
         .as_ref()
@@ -195,23 +240,17 @@ pub(super) async fn api_config_self_check(
             message: "Operator password is not configured.".to_string(),
         });
     }
-    if !matches!(
-        state.app_state.config.web_host.as_str(),
-        "127.0.0.1" | "localhost" | "::1"
-    ) {
+    if !matches!(config.web_host.as_str(), "127.0.0.1" | "localhost" | "::1") {
         warnings.push(ConfigWarning {
             code: "web_host_not_loopback",
             severity: "medium",
             message: format!(
                 "Web server host is '{}', verify network exposure and upstream protections.",
-                state.app_state.config.web_host
+                config.web_host
             ),
         });
     }
-    if matches!(
-        state.app_state.config.sandbox.mode,
-        crate::config::SandboxMode::Off
-    ) {
+    if matches!(config.sandbox.mode, crate::config::SandboxMode::Off) {
         warnings.push(ConfigWarning {
             code: "sandbox_disabled",
             severity: "medium",
@@ -220,7 +259,7 @@ pub(super) async fn api_config_self_check(
     } else if !sandbox_runtime_available {
         warnings.push(ConfigWarning {
             code: "sandbox_runtime_unavailable",
-            severity: if state.app_state.config.sandbox.require_runtime {
+            severity: if config.sandbox.require_runtime {
                 "high"
             } else {
                 "medium"
@@ -243,71 +282,68 @@ pub(super) async fn api_config_self_check(
                 .to_string(),
         });
     }
-    if state.app_state.config.web_max_requests_per_window > 200 {
+    if config.web_max_requests_per_window > 200 {
         warnings.push(ConfigWarning {
             code: "web_rate_limit_too_high",
             severity: "medium",
             message: format!(
                 "web_max_requests_per_window is {}, which is higher than typical safe defaults.",
-                state.app_state.config.web_max_requests_per_window
+                config.web_max_requests_per_window
             ),
         });
     }
-    if state.app_state.config.web_max_inflight_per_session > 10 {
+    if config.web_max_inflight_per_session > 10 {
         warnings.push(ConfigWarning {
             code: "web_inflight_limit_too_high",
             severity: "medium",
             message: format!(
                 "web_max_inflight_per_session is {}, which may amplify overload impact.",
-                state.app_state.config.web_max_inflight_per_session
+                config.web_max_inflight_per_session
             ),
         });
     }
-    if state.app_state.config.web_rate_window_seconds <= 2
-        && state.app_state.config.web_max_requests_per_window >= 20
-    {
+    if config.web_rate_window_seconds <= 2 && config.web_max_requests_per_window >= 20 {
         warnings.push(ConfigWarning {
             code: "web_rate_window_too_small_for_limit",
             severity: "medium",
             message: format!(
                 "web_rate_window_seconds={} with web_max_requests_per_window={} can allow burst spikes.",
-                state.app_state.config.web_rate_window_seconds,
-                state.app_state.config.web_max_requests_per_window
+                config.web_rate_window_seconds,
+                config.web_max_requests_per_window
             ),
         });
     }
-    if state.app_state.config.web_session_idle_ttl_seconds < 30 {
+    if config.web_session_idle_ttl_seconds < 30 {
         warnings.push(ConfigWarning {
             code: "web_session_idle_ttl_too_low",
             severity: "medium",
             message: format!(
                 "web_session_idle_ttl_seconds={} may cause frequent session lock churn.",
-                state.app_state.config.web_session_idle_ttl_seconds
+                config.web_session_idle_ttl_seconds
             ),
         });
     }
-    if state.app_state.config.max_session_messages <= state.app_state.config.compact_keep_recent {
+    if config.max_session_messages <= config.compact_keep_recent {
         warnings.push(ConfigWarning {
             code: "compaction_threshold_not_effective",
             severity: "medium",
             message: format!(
                 "max_session_messages={} and compact_keep_recent={} make compaction ineffective.",
-                state.app_state.config.max_session_messages,
-                state.app_state.config.compact_keep_recent
+                config.max_session_messages, config.compact_keep_recent
             ),
         });
     }
-    if state.app_state.config.memory_token_budget < 400 {
+    if config.memory_token_budget < 400 {
         warnings.push(ConfigWarning {
             code: "memory_token_budget_low",
             severity: "medium",
             message: format!(
                 "memory_token_budget={} may reduce memory recall quality for long tasks.",
-                state.app_state.config.memory_token_budget
+                config.memory_token_budget
             ),
         });
     }
-    if !state.app_state.config.reflector_enabled {
+    if !config.reflector_enabled {
         warnings.push(ConfigWarning {
             code: "reflector_disabled",
             severity: "medium",
@@ -315,22 +351,22 @@ pub(super) async fn api_config_self_check(
                 "Memory reflector is disabled; durable facts may not be extracted automatically."
                     .to_string(),
         });
-    } else if state.app_state.config.reflector_interval_mins < 5 {
+    } else if config.reflector_interval_mins < 5 {
         warnings.push(ConfigWarning {
             code: "reflector_interval_too_low",
             severity: "medium",
             message: format!(
                 "reflector_interval_mins={} may cause unnecessary LLM cost and churn.",
-                state.app_state.config.reflector_interval_mins
+                config.reflector_interval_mins
             ),
         });
-    } else if state.app_state.config.reflector_interval_mins > 240 {
+    } else if config.reflector_interval_mins > 240 {
         warnings.push(ConfigWarning {
             code: "reflector_interval_too_high",
             severity: "medium",
             message: format!(
                 "reflector_interval_mins={} may delay memory freshness significantly.",
-                state.app_state.config.reflector_interval_mins
+                config.reflector_interval_mins
             ),
         });
     }
@@ -344,7 +380,7 @@ pub(super) async fn api_config_self_check(
             ),
         });
     }
-    if state.app_state.config.reflector_enabled && reflector_observability.reflector_runs_24h == 0 {
+    if config.reflector_enabled && reflector_observability.reflector_runs_24h == 0 {
         warnings.push(ConfigWarning {
             code: "reflector_no_recent_runs",
             severity: "medium",
@@ -352,13 +388,7 @@ pub(super) async fn api_config_self_check(
         });
     }
 
-    if let Some(hooks) = state
-        .app_state
-        .config
-        .channels
-        .get("hooks")
-        .and_then(|v| v.as_mapping())
-    {
+    if let Some(hooks) = config.channels.get("hooks").and_then(|v| v.as_mapping()) {
         let enabled = hooks
             .get(serde_yaml::Value::String("enabled".to_string()))
             .and_then(|v| v.as_bool())
@@ -395,9 +425,7 @@ pub(super) async fn api_config_self_check(
         }
     }
 
-    if let Some(obs) = state
-        .app_state
-        .config
+    if let Some(obs) = config
         .channels
         .get("observability")
         .and_then(|v| v.as_mapping())
@@ -464,8 +492,8 @@ pub(super) async fn api_config_self_check(
             "sandbox_mode": sandbox_mode,
             "sandbox_runtime_available": sandbox_runtime_available,
             "sandbox_runtime_cli": sandbox_runtime_cli,
-            "sandbox_backend": format!("{:?}", state.app_state.config.sandbox.backend).to_lowercase(),
-            "sandbox_require_runtime": state.app_state.config.sandbox.require_runtime,
+            "sandbox_backend": format!("{:?}", config.sandbox.backend).to_lowercase(),
+            "sandbox_require_runtime": config.sandbox.require_runtime,
             "execution_policies": execution_policy_items,
             "mount_allowlist": mount_allowlist_status
         },
@@ -491,7 +519,8 @@ pub(super) async fn api_update_config(
     let identity = require_scope(&state, &headers, AuthScope::Admin).await?;
 
     let path = config_path_for_save()?;
-    let mut cfg = crate::config::Config::load().unwrap_or_else(|_| state.app_state.config.clone());
+    let mut cfg = crate::config::Config::load()
+        .unwrap_or_else(|_| state.app_state.config.load().as_ref().clone());
 
     if let Some(v) = body.llm_provider {
         cfg.llm_provider = v;
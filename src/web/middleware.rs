@@ -270,7 +270,7 @@ pub(super) async fn require_scope(
         .await
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
         {
-            let (max_requests, window) = api_key_limits(&state.app_state.config);
+            let (max_requests, window) = api_key_limits(&state.app_state.config.load());
             let allowed = state
                 .auth_hub
                 .allow_api_key_request(&format!("api-key:{key_id}"), max_requests, window)
@@ -111,7 +111,8 @@ pub(super) async fn api_reset(
     .ok()
     .flatten()
     .unwrap_or_else(|| "web".to_string());
-    let groups_dir = std::path::PathBuf::from(&state.app_state.config.data_dir).join("groups");
+    let groups_dir =
+        std::path::PathBuf::from(&state.app_state.config.load().data_dir).join("groups");
     if let Err(e) = clear_todos(&groups_dir, &todo_channel, chat_id) {
         warn!("Failed to clear TODO.json for chat {}: {}", chat_id, e);
     }
@@ -152,7 +153,8 @@ pub(super) async fn api_delete_session(
     })
     .await
     .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-    let groups_dir = std::path::PathBuf::from(&state.app_state.config.data_dir).join("groups");
+    let groups_dir =
+        std::path::PathBuf::from(&state.app_state.config.load().data_dir).join("groups");
     if let Err(e) = clear_todos(&groups_dir, &todo_channel, chat_id) {
         warn!("Failed to clear TODO.json for chat {}: {}", chat_id, e);
     }
@@ -159,8 +159,13 @@ pub(super) async fn api_send_stream(
                 }
             });
 
-            match send_and_store_response_with_events(state_for_task.clone(), body, Some(&evt_tx))
-                .await
+            match send_and_store_response_with_events(
+                state_for_task.clone(),
+                body,
+                None,
+                Some(&evt_tx),
+            )
+            .await
             {
                 Ok(resp) => {
                     metrics_llm_completion_inc(&state_for_task).await;
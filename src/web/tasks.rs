@@ -0,0 +1,144 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    Json,
+};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::tools::schedule::COMPLETED_TASK_LIST_DAYS;
+use crate::web::{middleware::AuthScope, require_scope, WebState};
+use microclaw_storage::db::{call_blocking, TaskRunLog};
+
+#[derive(Debug, Deserialize)]
+pub struct TasksQuery {
+    chat_id: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TaskHistoryQuery {
+    limit: Option<usize>,
+    offset: Option<usize>,
+}
+
+fn run_json(run: &TaskRunLog) -> serde_json::Value {
+    json!({
+        "id": run.id,
+        "started_at": run.started_at,
+        "finished_at": run.finished_at,
+        "duration_ms": run.duration_ms,
+        "success": run.success,
+        "result_summary": run.result_summary,
+    })
+}
+
+pub async fn api_list_tasks(
+    headers: HeaderMap,
+    State(state): State<WebState>,
+    Query(query): Query<TasksQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    require_scope(&state, &headers, AuthScope::Read).await?;
+
+    let completed_since =
+        (chrono::Utc::now() - chrono::Duration::days(COMPLETED_TASK_LIST_DAYS)).to_rfc3339();
+    let rows = call_blocking(state.app_state.db.clone(), move |db| {
+        db.get_tasks_with_last_run(query.chat_id, &completed_since)
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let tasks = rows
+        .iter()
+        .map(|(task, last_run)| {
+            json!({
+                "id": task.id,
+                "chat_id": task.chat_id,
+                "prompt": task.prompt,
+                "task_type": task.task_type,
+                "schedule_type": task.schedule_type,
+                "schedule_value": task.schedule_value,
+                "status": task.status,
+                "next_run": (task.status == "active").then_some(&task.next_run),
+                "last_run": last_run.as_ref().map(run_json),
+                "created_at": task.created_at,
+            })
+        })
+        .collect::<Vec<_>>();
+    Ok(Json(json!({
+        "ok": true,
+        "timezone": state.app_state.config.load().timezone,
+        "tasks": tasks
+    })))
+}
+
+pub async fn api_task_history(
+    headers: HeaderMap,
+    Path(task_id): Path<i64>,
+    State(state): State<WebState>,
+    Query(query): Query<TaskHistoryQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    require_scope(&state, &headers, AuthScope::Read).await?;
+
+    let limit = query.limit.unwrap_or(20).clamp(1, 200);
+    let offset = query.offset.unwrap_or(0);
+    let runs = call_blocking(state.app_state.db.clone(), move |db| {
+        if db.get_task_by_id(task_id)?.is_none() {
+            return Ok(None);
+        }
+        db.get_task_run_logs_page(task_id, limit, offset).map(Some)
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .ok_or((StatusCode::NOT_FOUND, "Task not found".to_string()))?;
+    Ok(Json(json!({
+        "ok": true,
+        "task_id": task_id,
+        "limit": limit,
+        "offset": offset,
+        "runs": runs.iter().map(run_json).collect::<Vec<_>>()
+    })))
+}
+
+/// Move a task from status `from` to `to`, refusing tasks in any other state.
+async fn set_task_status(
+    state: &WebState,
+    task_id: i64,
+    from: &'static str,
+    to: &'static str,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let task = call_blocking(state.app_state.db.clone(), move |db| {
+        db.get_task_by_id(task_id)
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .ok_or((StatusCode::NOT_FOUND, "Task not found".to_string()))?;
+    if task.status != from {
+        return Err((
+            StatusCode::CONFLICT,
+            format!("Task #{task_id} is {}, not {from}", task.status),
+        ));
+    }
+    call_blocking(state.app_state.db.clone(), move |db| {
+        db.update_task_status(task_id, to)
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(json!({"ok": true, "task_id": task_id, "status": to})))
+}
+
+pub async fn api_pause_task(
+    headers: HeaderMap,
+    Path(task_id): Path<i64>,
+    State(state): State<WebState>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    require_scope(&state, &headers, AuthScope::Write).await?;
+    set_task_status(&state, task_id, "active", "paused").await
+}
+
+pub async fn api_resume_task(
+    headers: HeaderMap,
+    Path(task_id): Path<i64>,
+    State(state): State<WebState>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    require_scope(&state, &headers, AuthScope::Write).await?;
+    set_task_status(&state, task_id, "paused", "active").await
+}
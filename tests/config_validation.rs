@@ -1,6 +1,6 @@
 //! Integration tests for configuration loading and validation.
 
-use microclaw::config::{Config, WorkingDirIsolation};
+use microclaw::config::{Config, MemoryCategory, WorkingDirIsolation};
 
 /// Helper to create a minimal valid config for testing.
 fn minimal_config() -> Config {
@@ -11,31 +11,95 @@ fn minimal_config() -> Config {
         api_key: "test-key".into(),
         model: String::new(),
         llm_providers: std::collections::HashMap::new(),
+        llm_fallbacks: Vec::new(),
+        tenant_api_keys: std::collections::HashMap::new(),
         llm_base_url: None,
         max_tokens: 8192,
         max_tool_iterations: 25,
+        sub_agent_max_depth: 1,
+        sub_agent_max_iterations: 10,
+        agent_stop_phrases: vec![],
+        tool_use_bias: "balanced".into(),
+        tool_output_summary_threshold_chars: 0,
+        tool_output_summary_model: None,
+        tool_transient_retry_enabled: false,
+        tool_transient_retry_tools: vec![
+            "web_fetch".into(),
+            "web_search".into(),
+            "read_file".into(),
+            "glob".into(),
+            "grep".into(),
+        ],
+        tool_transient_retry_backoff_ms: 1000,
+        tool_parallel_max: 4,
+        tool_parallel_tools: vec![],
+        max_tools_per_request: None,
+        core_tools: vec![],
+        run_retry_budget: 10,
+        chat_rate_limit_per_minute: 0,
+        chat_rate_limit_per_chat_type: std::collections::HashMap::new(),
+        chat_rate_limit_burst: 0,
+        event_webhook_url: None,
+        event_webhook_secret: None,
+        event_webhook_events: vec![],
+        event_webhook_max_retries: 3,
         max_history_messages: 50,
+        context_token_budget: 0,
+        group_backlog_summary_threshold: 0,
+        group_backlog_keep_recent: 10,
+        max_inbound_message_chars: 20_000,
         max_document_size_mb: 100,
+        max_inline_document_bytes: 65_536,
+        rerun_on_message_edit: false,
+        telegram_streaming: false,
+        save_attachment_max_size_mb: 20,
+        save_attachment_allowed_types: vec!["image/".into()],
         memory_token_budget: 1500,
+        memory_categories: MemoryCategory::defaults(),
+        memory_default_category: "KNOWLEDGE".into(),
         data_dir: "./microclaw.data".into(),
         skills_dir: None,
+        skill_suggestions_enabled: false,
+        skill_suggestion_min_score: 3,
+        skill_suggestion_max_hints: 2,
         working_dir: "./tmp".into(),
         working_dir_isolation: WorkingDirIsolation::Chat,
         high_risk_tool_user_confirmation_required: true,
+        confirm_destructive_tools: false,
+        bash_restrict_to_working_dir: false,
+        bash_denied_commands: Vec::new(),
+        bash_max_output_bytes: 64 * 1024,
         sandbox: microclaw::config::SandboxConfig::default(),
         openai_api_key: None,
+        image_generation_model: "gpt-image-1".into(),
+        image_generation_base_url: None,
         timezone: "UTC".into(),
+        schedule_min_interval_secs: 60,
+        schedule_max_tasks_per_chat: 50,
+        schedule_max_once_lead_days: 365,
         allowed_groups: vec![],
         control_chat_ids: vec![],
         max_session_messages: 40,
         compact_keep_recent: 20,
+        compact_token_threshold: 0,
+        session_recover_partial: true,
+        archive_structured_tool_calls: false,
+        agent_plan_messages_enabled: false,
         default_tool_timeout_secs: 30,
+        tool_hard_timeout_secs: 600,
         tool_timeout_overrides: std::collections::HashMap::new(),
         default_mcp_request_timeout_secs: 120,
         compaction_timeout_secs: 180,
+        llm_failure_threshold: 3,
+        llm_health_probe_interval_secs: 60,
+        llm_stream_fallback: true,
+        llm_max_retries: 3,
+        prompt_caching: true,
+        openai_strict_tools: false,
         discord_bot_token: None,
         discord_allowed_channels: vec![],
         discord_no_mention: false,
+        discord_use_threads: false,
         allow_group_slash_without_mention: false,
         show_thinking: false,
         openai_compat_body_overrides: std::collections::HashMap::new(),
@@ -53,6 +117,9 @@ fn minimal_config() -> Config {
             microclaw_tools::web_content_validation::WebContentValidationConfig::default(),
         web_fetch_url_validation: microclaw_tools::web_fetch::WebFetchUrlValidationConfig::default(
         ),
+        web_fetch_retry: microclaw_tools::web_fetch::WebFetchRetryConfig::default(),
+        http_tool_allowed_hosts: vec![],
+        http_tool_allow_private_networks: false,
         model_prices: vec![],
         embedding_provider: None,
         embedding_api_key: None,
@@ -61,13 +128,31 @@ fn minimal_config() -> Config {
         embedding_dim: None,
         reflector_enabled: true,
         reflector_interval_mins: 15,
+        db_maintenance_interval_hours: 0,
+        tool_call_log_retention_days: 30,
+        memory_prune_interval_hours: 0,
+        memory_dedup_similarity: 0.92,
+        memory_prune_confidence_floor: 0.35,
+        memory_prune_stale_days: 30,
         soul_path: None,
         souls_dir: None,
+        persona_name: None,
+        system_prompt_prepend: None,
+        system_prompt_append: None,
+        system_prompt_file: None,
         clawhub: microclaw::config::ClawHubConfig::default(),
         plugins: microclaw::plugins::PluginsConfig::default(),
         voice_provider: "openai".into(),
         voice_transcription_command: None,
+        voice_segment_secs: 600,
+        voice_languages: Default::default(),
+        image_ocr_provider: "none".into(),
+        image_ocr_command: None,
+        image_ocr_max_chars: 4000,
         channels: std::collections::HashMap::new(),
+        max_active_channels: 0,
+        message_claim_enabled: false,
+        message_claim_lease_secs: 300,
     }
 }
 